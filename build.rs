@@ -0,0 +1,313 @@
+//! Generates the Rust side of the shaders' bind groups.
+//!
+//! Every `src/*.wgsl` shader is parsed and validated with `naga`, and each of its
+//! `@group(n)` declarations becomes a struct with one field per binding, which creates the
+//! group's layout and bind groups. The code is written to `$OUT_DIR/shader_bindings.rs` and
+//! included by the `shader_bindings` module.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+/// One binding of a bind group, as found in a shader.
+struct Binding {
+    /// The `@binding(n)` index.
+    index: u32,
+
+    /// The name of the global variable, used as the field name.
+    name: String,
+
+    /// The WGSL declaration, for the field's documentation.
+    declaration: String,
+
+    /// The shader stages the variable is used in, as a `wgpu::ShaderStages` expression.
+    visibility: String,
+
+    /// The `wgpu::BindingType` expression of the layout entry.
+    ty: String,
+
+    /// The field's type, borrowing the bound resource for `'a`.
+    field_type: &'static str,
+
+    /// The `wgpu::BindingResource` expression of the bind group entry, given the field.
+    resource: fn(&str) -> String,
+}
+
+fn main() {
+    // Watching the directory also picks up new shaders.
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src");
+
+    let mut shaders = std::fs::read_dir("src")
+        .expect("failed to list the shaders")
+        .map(|entry| entry.expect("failed to list the shaders").path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "wgsl")
+        })
+        .collect::<Vec<_>>();
+    shaders.sort();
+
+    let mut code = String::new();
+    for path in &shaders {
+        generate_shader(&mut code, path);
+    }
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    std::fs::write(Path::new(&out_dir).join("shader_bindings.rs"), code)
+        .expect("failed to write the shader bindings");
+}
+
+/// Appends a module with the bind group structs of the shader at `path`.
+fn generate_shader(code: &mut String, path: &Path) {
+    let file_name = path.file_name().unwrap().to_string_lossy();
+    let module_name = path.file_stem().unwrap().to_string_lossy();
+    let source = std::fs::read_to_string(path).expect("failed to read a shader");
+    let module = naga::front::wgsl::parse_str(&source)
+        .unwrap_or_else(|error| panic!("{}", error.emit_to_string_with_path(&source, path)));
+    let path_name = path.display().to_string();
+    let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+        .validate(&module)
+        .unwrap_or_else(|error| panic!("{}", error.emit_to_string_with_path(&source, &path_name)));
+
+    let mut groups = BTreeMap::<u32, Vec<Binding>>::new();
+    for (handle, variable) in module.global_variables.iter() {
+        let Some(resource) = &variable.binding else {
+            continue;
+        };
+        let name = variable.name.clone().unwrap_or_default();
+        let mut stages = Vec::new();
+        for (index, entry_point) in module.entry_points.iter().enumerate() {
+            let stage = match entry_point.stage {
+                naga::ShaderStage::Vertex => "VERTEX",
+                naga::ShaderStage::Fragment => "FRAGMENT",
+                naga::ShaderStage::Compute => "COMPUTE",
+            };
+            if !info.get_entry_point(index)[handle].is_empty() && !stages.contains(&stage) {
+                stages.push(stage);
+            }
+        }
+        let visibility = match stages.split_first() {
+            None => "wgpu::ShaderStages::NONE".to_owned(),
+            Some((first, rest)) => rest.iter().fold(
+                format!("wgpu::ShaderStages::{first}"),
+                |visibility, stage| format!("{visibility}.union(wgpu::ShaderStages::{stage})"),
+            ),
+        };
+
+        let (ty, field_type, resource_of, declaration): (_, _, fn(&str) -> String, _) =
+            match (variable.space, &module.types[variable.ty].inner) {
+                (naga::AddressSpace::Uniform, inner) => (
+                    buffer_type("Uniform", inner.size(module.to_ctx())),
+                    "wgpu::BufferBinding<'a>",
+                    |field| format!("wgpu::BindingResource::Buffer(self.{field}.clone())"),
+                    format!("var<uniform> {name}"),
+                ),
+                (naga::AddressSpace::Storage { access }, inner) => {
+                    let read_only = !access.contains(naga::StorageAccess::STORE);
+                    (
+                        buffer_type(
+                            &format!("Storage {{ read_only: {read_only} }}"),
+                            inner.size(module.to_ctx()),
+                        ),
+                        "wgpu::BufferBinding<'a>",
+                        |field| format!("wgpu::BindingResource::Buffer(self.{field}.clone())"),
+                        format!(
+                            "var<storage, {}> {name}",
+                            if read_only { "read" } else { "read_write" }
+                        ),
+                    )
+                }
+                (
+                    naga::AddressSpace::Handle,
+                    naga::TypeInner::Image {
+                        dim,
+                        arrayed,
+                        class,
+                    },
+                ) => (
+                    image_type(file_name.as_ref(), *dim, *arrayed, *class),
+                    "&'a wgpu::TextureView",
+                    |field| format!("wgpu::BindingResource::TextureView(self.{field})"),
+                    format!("var {name}: texture"),
+                ),
+                (naga::AddressSpace::Handle, naga::TypeInner::Sampler { comparison }) => (
+                    format!(
+                        "wgpu::BindingType::Sampler(wgpu::SamplerBindingType::{})",
+                        if *comparison {
+                            "Comparison"
+                        } else {
+                            "Filtering"
+                        }
+                    ),
+                    "&'a wgpu::Sampler",
+                    |field| format!("wgpu::BindingResource::Sampler(self.{field})"),
+                    format!("var {name}: sampler"),
+                ),
+                (space, inner) => panic!(
+                    "{file_name}: unsupported binding `{name}` in {space:?} of type {inner:?}"
+                ),
+            };
+
+        groups.entry(resource.group).or_default().push(Binding {
+            index: resource.binding,
+            name,
+            declaration,
+            visibility,
+            ty,
+            field_type,
+            resource: resource_of,
+        });
+    }
+
+    writeln!(code, "/// The bind groups of `{file_name}`.").unwrap();
+    writeln!(code, "pub mod {module_name} {{").unwrap();
+    for (group, mut bindings) in groups {
+        bindings.sort_by_key(|binding| binding.index);
+        generate_group(code, &file_name, &module_name, group, &bindings);
+    }
+    writeln!(code, "}}").unwrap();
+}
+
+/// Appends the struct of bind group `group`, with `bindings` sorted by index.
+fn generate_group(
+    code: &mut String,
+    file_name: &str,
+    module_name: &str,
+    group: u32,
+    bindings: &[Binding],
+) {
+    let (mut fields, mut layout_entries, mut entries) =
+        (String::new(), String::new(), String::new());
+    for binding in bindings {
+        let Binding { index, name, .. } = binding;
+        writeln!(
+            fields,
+            "        /// `@binding({index}) {}`.",
+            binding.declaration
+        )
+        .unwrap();
+        writeln!(fields, "        pub {name}: {},", binding.field_type).unwrap();
+        writeln!(
+            layout_entries,
+            "            wgpu::BindGroupLayoutEntry {{
+                binding: {index},
+                visibility: {},
+                ty: {},
+                count: None,
+            }},",
+            binding.visibility, binding.ty,
+        )
+        .unwrap();
+        writeln!(
+            entries,
+            "                    wgpu::BindGroupEntry {{ binding: {index}, resource: {} }},",
+            (binding.resource)(name),
+        )
+        .unwrap();
+    }
+
+    let count = bindings.len();
+    write!(
+        code,
+        "    /// The resources of `@group({group})` in `{file_name}`.
+    #[derive(Debug, Clone)]
+    pub struct BindGroup{group}<'a> {{
+{fields}    }}
+
+    impl BindGroup{group}<'_> {{
+        /// The layout entries of the group, one per binding.
+        pub const LAYOUT_ENTRIES: [wgpu::BindGroupLayoutEntry; {count}] = [
+{layout_entries}        ];
+
+        /// Creates the layout of the group.
+        pub fn create_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {{
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {{
+                label: Some(\"{module_name}_bind_group_layout_{group}\"),
+                entries: &Self::LAYOUT_ENTRIES,
+            }})
+        }}
+
+        /// Creates a bind group of these resources with `layout`, created by `create_layout`.
+        pub fn create(
+            &self,
+            device: &wgpu::Device,
+            layout: &wgpu::BindGroupLayout,
+        ) -> wgpu::BindGroup {{
+            device.create_bind_group(&wgpu::BindGroupDescriptor {{
+                label: Some(\"{module_name}_bind_group_{group}\"),
+                layout,
+                entries: &[
+{entries}                ],
+            }})
+        }}
+    }}
+"
+    )
+    .unwrap();
+}
+
+/// Returns the `wgpu::BindingType` of a buffer of `buffer_type` whose contents take `size`
+/// bytes, or one element of a runtime-sized array.
+fn buffer_type(buffer_type: &str, size: u32) -> String {
+    format!(
+        "wgpu::BindingType::Buffer {{ ty: wgpu::BufferBindingType::{buffer_type}, \
+         has_dynamic_offset: false, min_binding_size: wgpu::BufferSize::new({size}) }}"
+    )
+}
+
+/// Returns the `wgpu::BindingType` of a texture.
+///
+/// WGSL does not tell whether a float texture is filtered, so float textures are bound as
+/// filterable. Bindings of unfilterable formats, such as `R32Float`, keep a hand-written layout.
+fn image_type(
+    file_name: &str,
+    dim: naga::ImageDimension,
+    arrayed: bool,
+    class: naga::ImageClass,
+) -> String {
+    let view_dimension = match (dim, arrayed) {
+        (naga::ImageDimension::D1, false) => "D1",
+        (naga::ImageDimension::D2, false) => "D2",
+        (naga::ImageDimension::D2, true) => "D2Array",
+        (naga::ImageDimension::D3, false) => "D3",
+        (naga::ImageDimension::Cube, false) => "Cube",
+        (naga::ImageDimension::Cube, true) => "CubeArray",
+        (dim, arrayed) => panic!("{file_name}: unsupported texture {dim:?} (arrayed: {arrayed})"),
+    };
+    let view_dimension = format!("wgpu::TextureViewDimension::{view_dimension}");
+    match class {
+        naga::ImageClass::Sampled { kind, multi } => {
+            let sample_type = match kind {
+                naga::ScalarKind::Float => "Float { filterable: true }",
+                naga::ScalarKind::Sint => "Sint",
+                naga::ScalarKind::Uint => "Uint",
+                kind => panic!("{file_name}: unsupported texture sample type {kind:?}"),
+            };
+            format!(
+                "wgpu::BindingType::Texture {{ sample_type: wgpu::TextureSampleType::{sample_type}, \
+                 view_dimension: {view_dimension}, multisampled: {multi} }}"
+            )
+        }
+        naga::ImageClass::Depth { multi } => format!(
+            "wgpu::BindingType::Texture {{ sample_type: wgpu::TextureSampleType::Depth, \
+             view_dimension: {view_dimension}, multisampled: {multi} }}"
+        ),
+        naga::ImageClass::Storage { format, access } => {
+            let access = match (
+                access.contains(naga::StorageAccess::LOAD),
+                access.contains(naga::StorageAccess::STORE),
+            ) {
+                (true, true) => "ReadWrite",
+                (true, false) => "ReadOnly",
+                _ => "WriteOnly",
+            };
+            format!(
+                "wgpu::BindingType::StorageTexture {{ access: wgpu::StorageTextureAccess::{access}, \
+                 format: wgpu::TextureFormat::{format:?}, view_dimension: {view_dimension} }}"
+            )
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! # Actions Module
+//!
+//! The `actions` module maps keys and mouse buttons to named actions, so the `App` reacts to
+//! "quit" or "camera_forward" instead of to particular key codes, and the bindings can be
+//! changed without touching the code handling them.
+//!
+//! ## Overview
+//!
+//! - [`Action`] names everything the keyboard and mouse can trigger outside of the GUI: the
+//!   shortcuts (quitting, toggling the panels, the frame statistics, and fullscreen, taking a
+//!   screenshot, and capturing a frame in an attached graphics debugger) and the FPS camera's
+//!   movement.
+//! - [`Binding`] is a physical key or a mouse button.
+//! - [`ActionMap`] binds each action to one binding and answers, from an `InputState`, which
+//!   actions were triggered since the last frame and which are held.
+//!
+//! The bindings are edited in the "Key Bindings" section of the settings window and saved
+//! with the other [`Settings`](crate::settings::Settings), as a JSON object from action names
+//! to bindings:
+//!
+//! ```json
+//! "bindings": {
+//!   "quit": { "key": "Escape" },
+//!   "camera_forward": { "key": "KeyW" },
+//!   "screenshot": { "mouse": "Middle" }
+//! }
+//! ```
+//!
+//! Actions missing from a settings file keep their default binding, so older files remain
+//! loadable. Several actions may share a binding, in which case it triggers all of them.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut action_map = ActionMap::default();
+//! action_map.bind(Action::Screenshot, Binding::Key(KeyCode::KeyP));
+//!
+//! // Once per frame:
+//! for action in action_map.triggered(&input_state) {
+//!     if action == Action::Quit {
+//!         event_loop.exit();
+//!     }
+//! }
+//! ```
+
+// Importing `BTreeMap` to keep the bindings in the order of the actions, also when saved.
+use std::collections::BTreeMap;
+
+use winit::event::MouseButton;
+use winit::keyboard::KeyCode;
+
+use crate::input::InputState;
+
+/// Something the keyboard and mouse can trigger.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Exits the application.
+    Quit,
+
+    /// Shows or hides the top, left, right, and bottom panels.
+    TogglePanels,
+
+    /// Saves the next frame as a PNG.
+    Screenshot,
+
+    /// Shows or hides the frame statistics overlay.
+    ToggleFrameStats,
+
+    /// Captures the next frame in the attached graphics debugger, such as RenderDoc.
+    CaptureGpuFrame,
+
+    /// Enters or leaves fullscreen, as does `Alt+Enter`.
+    ToggleFullscreen,
+
+    /// Moves the FPS camera forwards while held.
+    CameraForward,
+
+    /// Moves the FPS camera backwards while held.
+    CameraBack,
+
+    /// Moves the FPS camera to the left while held.
+    CameraLeft,
+
+    /// Moves the FPS camera to the right while held.
+    CameraRight,
+
+    /// Moves the FPS camera down while held.
+    CameraDown,
+
+    /// Moves the FPS camera up while held.
+    CameraUp,
+}
+
+impl Action {
+    /// All actions, in the order they are listed in the GUI.
+    pub const ALL: [Action; 12] = [
+        Action::Quit,
+        Action::TogglePanels,
+        Action::Screenshot,
+        Action::ToggleFrameStats,
+        Action::CaptureGpuFrame,
+        Action::ToggleFullscreen,
+        Action::CameraForward,
+        Action::CameraBack,
+        Action::CameraLeft,
+        Action::CameraRight,
+        Action::CameraDown,
+        Action::CameraUp,
+    ];
+
+    /// Returns the name shown in the GUI.
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::TogglePanels => "Toggle Panels",
+            Action::Screenshot => "Screenshot",
+            Action::ToggleFrameStats => "Toggle Frame Statistics",
+            Action::CaptureGpuFrame => "Capture GPU Frame",
+            Action::ToggleFullscreen => "Toggle Fullscreen",
+            Action::CameraForward => "Camera Forward",
+            Action::CameraBack => "Camera Back",
+            Action::CameraLeft => "Camera Left",
+            Action::CameraRight => "Camera Right",
+            Action::CameraDown => "Camera Down",
+            Action::CameraUp => "Camera Up",
+        }
+    }
+
+    /// Returns the binding the action has unless it is changed.
+    pub fn default_binding(self) -> Binding {
+        Binding::Key(match self {
+            Action::Quit => KeyCode::Escape,
+            Action::TogglePanels => KeyCode::F2,
+            Action::Screenshot => KeyCode::F12,
+            Action::ToggleFrameStats => KeyCode::F1,
+            Action::CaptureGpuFrame => KeyCode::F9,
+            Action::ToggleFullscreen => KeyCode::F11,
+            Action::CameraForward => KeyCode::KeyW,
+            Action::CameraBack => KeyCode::KeyS,
+            Action::CameraLeft => KeyCode::KeyA,
+            Action::CameraRight => KeyCode::KeyD,
+            Action::CameraDown => KeyCode::KeyQ,
+            Action::CameraUp => KeyCode::KeyE,
+        })
+    }
+}
+
+/// A physical key or a mouse button an action is bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Binding {
+    /// A key, by its position on the keyboard rather than the character it types.
+    Key(KeyCode),
+
+    /// A mouse button.
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    /// Returns the first key or mouse button pressed since the last frame, if any.
+    pub fn first_pressed(input: &InputState) -> Option<Binding> {
+        input
+            .keys_pressed()
+            .map(Binding::Key)
+            .chain(input.buttons_pressed().map(Binding::Mouse))
+            .next()
+    }
+
+    /// Returns `true` if the key or button is down.
+    pub fn is_held(self, input: &InputState) -> bool {
+        match self {
+            Binding::Key(key) => input.is_key_held(key),
+            Binding::Mouse(button) => input.is_button_held(button),
+        }
+    }
+
+    /// Returns `true` if the key or button was pressed since the last frame.
+    pub fn was_pressed(self, input: &InputState) -> bool {
+        match self {
+            Binding::Key(key) => input.was_key_pressed(key),
+            Binding::Mouse(button) => input.was_button_pressed(button),
+        }
+    }
+
+    /// Returns the name shown in the GUI, such as "W", "Escape", or "Right Mouse".
+    pub fn label(self) -> String {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{key:?}");
+                match name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                {
+                    Some(character) if character.len() == 1 => character.to_string(),
+                    _ => name,
+                }
+            }
+            Binding::Mouse(MouseButton::Other(index)) => format!("Mouse Button {index}"),
+            Binding::Mouse(button) => format!("{button:?} Mouse"),
+        }
+    }
+}
+
+/// The binding of every action.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "BTreeMap<Action, Binding>", into = "BTreeMap<Action, Binding>")]
+pub struct ActionMap {
+    /// The binding of each action. Every action has one.
+    bindings: BTreeMap<Action, Binding>,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        Self::from(BTreeMap::new())
+    }
+}
+
+impl From<BTreeMap<Action, Binding>> for ActionMap {
+    /// Binds the actions to the given bindings, and the others to their default ones.
+    fn from(mut bindings: BTreeMap<Action, Binding>) -> Self {
+        for action in Action::ALL {
+            bindings
+                .entry(action)
+                .or_insert_with(|| action.default_binding());
+        }
+        Self { bindings }
+    }
+}
+
+impl From<ActionMap> for BTreeMap<Action, Binding> {
+    fn from(action_map: ActionMap) -> Self {
+        action_map.bindings
+    }
+}
+
+impl ActionMap {
+    /// Returns the binding of `action`.
+    pub fn binding(&self, action: Action) -> Binding {
+        self.bindings[&action]
+    }
+
+    /// Binds `action` to `binding`, replacing its previous binding.
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        self.bindings.insert(action, binding);
+    }
+
+    /// Returns `true` if the binding of `action` is held.
+    pub fn is_held(&self, action: Action, input: &InputState) -> bool {
+        self.binding(action).is_held(input)
+    }
+
+    /// Returns the actions whose binding was pressed since the last frame, in the order of
+    /// [`Action::ALL`].
+    pub fn triggered<'a>(&'a self, input: &'a InputState) -> impl Iterator<Item = Action> + 'a {
+        self.bindings
+            .iter()
+            .filter(|(_, binding)| binding.was_pressed(input))
+            .map(|(action, _)| *action)
+    }
+}
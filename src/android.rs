@@ -0,0 +1,54 @@
+//! # Android Module
+//!
+//! The `android` module is the entry point of the library on Android, enabled with the
+//! `android` feature.
+//!
+//! ## Overview
+//!
+//! An Android `NativeActivity` loads the library, which is built as a `cdylib`, and calls its
+//! `android_main` function on a thread of its own instead of a `main` function. `android_main`
+//! routes `log` to logcat, builds the event loop on the activity, and runs the `App` in it
+//! until the activity is destroyed.
+//!
+//! The activity's native window only exists while the app is in the foreground. The `App`
+//! drops the window's surface when it is suspended and creates it again when it is resumed,
+//! keeping the device and all GPU resources in between, so switching apps neither reloads
+//! the scene nor loses its state.
+//!
+//! ## Example Usage
+//!
+//! With `cargo-apk`, and an `[package.metadata.android]` section describing the APK:
+//!
+//! ```sh
+//! $ cargo apk run --lib --features android
+//! ```
+
+use winit::event_loop::{ControlFlow, EventLoop};
+use winit::platform::android::activity::AndroidApp;
+use winit::platform::android::EventLoopBuilderExtAndroid;
+
+use crate::app::App;
+
+/// The entry point called by the `NativeActivity` with the running `app`.
+///
+/// # Panics
+///
+/// Panics if the event loop cannot be created or fails while running, which the activity
+/// reports as a crash.
+#[no_mangle]
+fn android_main(app: AndroidApp) {
+    android_logger::init_once(
+        android_logger::Config::default()
+            .with_max_level(log::LevelFilter::Info)
+            .with_tag("app"),
+    );
+
+    let event_loop = EventLoop::builder()
+        .with_android_app(app)
+        .build()
+        .expect("failed to create the event loop");
+    event_loop.set_control_flow(ControlFlow::Poll);
+    event_loop
+        .run_app(&mut App::default())
+        .expect("failed to run the event loop");
+}
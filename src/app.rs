@@ -54,46 +54,139 @@
 //!
 //! For each field, platform-specific initialization and usage details are included in the struct-level documentation.
 
-// The `wasm_bindgen::prelude::*` is needed to enable seamless interaction between the Rust application 
-// and JavaScript for WebAssembly platforms. It provides attributes and macros like `#[wasm_bindgen]`, 
-// which make it possible to call Rust functions from JavaScript or vice versa, manage DOM elements, 
+// The `wasm_bindgen::prelude::*` is needed to enable seamless interaction between the Rust application
+// and JavaScript for WebAssembly platforms. It provides attributes and macros like `#[wasm_bindgen]`,
+// which make it possible to call Rust functions from JavaScript or vice versa, manage DOM elements,
 // and access browser-specific APIs effectively.
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
-// Importing `Arc` (Atomic Reference Counted) from the standard library, 
+// Importing `Arc` (Atomic Reference Counted) from the standard library,
 // which is used to create thread-safe, reference-counted pointers for shared ownership of data.
 // In this context, it ensures safe and efficient sharing of the `Window` instance across threads.
 use std::sync::Arc;
 
 // Importing `Instant` from the `web_time` crate, which provides a cross-platform abstraction
 // for measuring time. On WebAssembly, it uses high-resolution time from the browser,
-// while on other platforms, it falls back to a standard time measurement. This is 
+// while on other platforms, it falls back to a standard time measurement. This is
 // particularly useful for tracking frame render timings and performance metrics
 // in the application.
 use web_time::Instant;
 
-// Importing necessary types and traits from the `winit` crate, which is used for window 
+// Importing necessary types and traits from the `winit` crate, which is used for window
 // creation and event handling. These include:
 // - `ApplicationHandler`: Provides the trait for implementing application-specific event handling logic.
 // - `PhysicalSize`: Represents physical dimensions of a window or surface in pixels, used for resizing.
 // - `WindowEvent`: Enumerates various events related to the window, such as resizing, focus changes, etc.
+// - `KeyCode`: Names the physical keys, such as the Alt and Enter keys toggling fullscreen.
 // - `Theme`: Allows querying or setting the theme of the application (e.g., Light or Dark mode).
 // - `Window`: Represents the main application window used for rendering, GUI, and handling user interactions.
 use winit::{
-    application::ApplicationHandler,
-    dpi::PhysicalSize,
-    event::WindowEvent,
-    window::{Theme, Window},
+    application::ApplicationHandler, dpi::PhysicalSize, event::WindowEvent, keyboard::KeyCode,
+    window::Window,
 };
 
-// Importing the `Renderer` type from the local `renderer` module.
-// The `Renderer` is the core of the rendering system, responsible for interacting 
-// with the WGPU backend to render graphical content. It handles tasks such as
-// creating GPU buffers, compiling shaders, and managing frame updates. 
-// This is a crucial component for rendering the application's visuals.
+// Importing the backend abstraction from the local `backend` module. The `App` drives any
+// `RendererBackend`, created through a `BackendFactory`, so alternate renderers can replace
+// the wgpu `Renderer` when the application is constructed.
+use crate::backend::{BackendFactory, RendererBackend};
+
+// Importing the wgpu `Renderer`, whose own features, such as picking, captures, and the render
+// settings of the Inspector, the `App` uses when it is the active backend.
 use crate::renderer::Renderer;
 
+// Importing the `Toasts` notification queue from the local `toast` module. It is used to
+// present recoverable problems, such as configuration validation warnings, to the user.
+use crate::toast::Toasts;
+
+// Importing the error types and policy hooks from the local `error` module. Renderer
+// creation and frame rendering return a `RenderError`, and the `ErrorPolicy` decides
+// whether the application retries, falls back to a degraded configuration, or exits.
+use crate::error::{ErrorAction, ErrorPhase, ErrorPolicy, RenderError};
+
+// Importing the curve editor, used to edit and visualize the camera's flight path.
+use crate::curves::CurveEditor;
+use crate::gizmo::TransformGizmo;
+
+// Importing the cameras and the input driving them.
+use crate::camera::{Camera, CameraInput, CameraMode};
+
+// Importing the safe-area insets, which keep the GUI clear of notches and system UI.
+use crate::safe_area::SafeAreaInsets;
+
+// Importing the persisted settings, loaded at startup and saved whenever they change.
+use crate::settings::Settings;
+
+// Importing the options the window and the renderer are created with.
+use crate::config::AppConfig;
+
+// Importing the gesture recognizer, which turns touch input into camera and picking gestures.
+use crate::gestures::{Gesture, GestureRecognizer};
+
+// Importing the input coalescer, which merges the cursor moves of high-rate mice per frame.
+use crate::input::{set_mouse_look, InputCoalescer, InputState};
+
+// Importing the actions the keys and mouse buttons are bound to.
+use crate::actions::{Action, Binding};
+
+// Importing the controller entering and leaving fullscreen as chosen in the settings window.
+use crate::fullscreen::FullscreenController;
+
+// Importing the secondary windows, each with its own surface, renderer, and GUI.
+#[cfg(not(target_arch = "wasm32"))]
+use crate::windows::{WindowKind, WindowState};
+
+// Importing the native windows of the `egui` viewports detached from the main window.
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+use crate::viewports::ViewportWindows;
+
+// Importing the redraw scheduler, which lets the event loop sleep while nothing changes.
+use crate::redraw::{RedrawPolicy, RedrawScheduler};
+
+// Importing the camera interchange format, used to share camera setups with other tools.
+use crate::camera_exchange::CameraExchange;
+
+// Importing the dolly zoom animation, which drives the camera while the demo is enabled.
+use crate::dolly_zoom::DollyZoomAnimation;
+
+// Importing the flythrough, which records the camera into a path and flies it along it.
+use crate::camera_path::{CameraPath, Flythrough};
+
+// Importing the quality scaler, which steps quality settings to hold the target frame rate.
+use crate::quality::QualityScaler;
+
+// Importing the CPU profiler, which records the scopes of each frame while enabled.
+use crate::cpu_profiler::CpuProfiler;
+
+// Importing the benchmark, which drives a scripted workload and reports its frame times.
+#[cfg(not(target_arch = "wasm32"))]
+use crate::benchmark::{Benchmark, BenchmarkRun};
+
+// Importing the CPU rasterizer, which replaces the renderer when no GPU adapter is available.
+#[cfg(all(feature = "software", not(target_arch = "wasm32")))]
+use crate::software::SoftwareBackend;
+
+// Importing the asset loader, which reads the model set with `App::with_model` and the texture
+// set with `App::with_texture`, and the model loader parsing the model.
+use crate::asset::{AssetError, AssetLoader, PendingLoad};
+#[cfg(feature = "import-gltf")]
+use crate::model::{Model, ModelError, ModelLoader};
+
+// Importing the material library, replaced with the materials of a loaded model.
+#[cfg(all(feature = "gui", feature = "import-gltf"))]
+use crate::material::MaterialLibrary;
+
+/// The load of the model set with `App::with_model`, finishing with its source and the model.
+#[cfg(feature = "import-gltf")]
+type ModelLoad = PendingLoad<(String, Result<Model, ModelError>)>;
+
+/// The load of the texture set with `App::with_texture`, finishing with its source and bytes.
+type TextureLoad = PendingLoad<(String, Result<Vec<u8>, AssetError>)>;
+
+/// The load of the configuration's asset pack, finishing with its source and a loader it is
+/// mounted into, unless mounting failed.
+type PackLoad = PendingLoad<(String, AssetLoader, Result<(), AssetError>)>;
+
 /// Main application structure for managing the GUI application state.
 ///
 /// The `App` struct implements the `ApplicationHandler` trait to manage
@@ -126,8 +219,17 @@ use crate::renderer::Renderer;
 ///   Stores the dimensions of the window (width and height) in physical pixels,
 ///   which are used to resize the rendering surface when necessary.
 ///
-/// - `panels_visible`:
-///   A flag to track whether GUI panels are currently visible in the application.
+/// - `ui`:
+///   The state edited through the GUI, such as whether the panels are visible.
+///
+/// - `toasts`:
+///   The queue of transient notifications drawn on top of the GUI.
+///
+/// - `error_policy`:
+///   The hook deciding how renderer errors are handled (retry, fallback, ignore, or exit).
+///
+/// - `init_failures` / `frame_failures`:
+///   Counters of consecutive failures, passed to the error policy so it can escalate.
 ///
 /// # Platform-Specific Implementation
 ///
@@ -187,7 +289,15 @@ pub struct App {
     /// - Managing frame updates and presenting the final frame.
     ///
     /// When this field is `None`, the application is not yet ready for rendering.
-    renderer: Option<Renderer>,
+    ///
+    /// The backend is stored as a trait object, so any `RendererBackend` (the wgpu `Renderer`
+    /// by default) can be used. It is created through the `backend` factory.
+    renderer: Option<Box<dyn RendererBackend>>,
+
+    /// Creates the rendering backend stored in `renderer`.
+    ///
+    /// Defaults to a factory for the wgpu `Renderer`; replace it with `App::with_backend`.
+    backend: BackendFactory,
 
     /// Manages the state and integration of the `egui` GUI framework with the `winit` window.
     ///
@@ -204,6 +314,7 @@ pub struct App {
     ///
     /// This field is initialized when the application starts and remains `None` if the GUI
     /// state has not yet been set up.
+    #[cfg(feature = "gui")]
     gui_state: Option<egui_winit::State>,
 
     /// Tracks the timestamp of the last render frame.
@@ -217,6 +328,10 @@ pub struct App {
     /// since the application started or resumed.
     last_render_time: Option<Instant>,
 
+    /// Splits the time between frames into the fixed ticks the scene's animation advances by,
+    /// so it moves at the same speed whatever the frame rate and its hiccups.
+    timestep: crate::determinism::FixedTimestep,
+
     /// A receiver for asynchronously initializing the WGPU renderer on WebAssembly platforms.
     ///
     /// This field is only available when targeting the `wasm32` architecture. It holds a
@@ -237,7 +352,8 @@ pub struct App {
     /// When this field is `None`, either the platform is not using asynchronous initialization, or
     /// the renderer has already been fully initialized.
     #[cfg(target_arch = "wasm32")]
-    renderer_receiver: Option<futures::channel::oneshot::Receiver<Renderer>>,
+    renderer_receiver:
+        Option<futures::channel::oneshot::Receiver<Result<Box<dyn RendererBackend>, RenderError>>>,
 
     /// Tracks the size of the application window during its last update.
     ///
@@ -251,17 +367,767 @@ pub struct App {
     /// and ensuring that the application properly handles window resizing events.
     last_size: (u32, u32),
 
-    /// Indicates whether application panels are visible.
+    /// The state edited through the GUI built by `ui::show`.
+    ///
+    /// It tracks, among other things, whether the top, left, right, and bottom panels are
+    /// currently displayed. Panels start hidden and are toggled with the "Show Panels" checkbox.
+    ui: crate::ui::UiState,
+
+    /// The cursor position inside the window in physical pixels, or `None` when the cursor
+    /// is outside of it. Probed by the depth probe while it is enabled.
+    cursor_position: Option<(u32, u32)>,
+
+    /// The camera's flight path, drawn and edited with the mouse while the "Camera Path"
+    /// checkbox is checked.
+    camera_path: CurveEditor,
+
+    /// The transform gizmo of the selected node, drawn with the other editor helpers, in the
+    /// mode and with the snapping chosen in the Inspector.
+    gizmo: TransformGizmo,
+
+    /// The orbit, arcball, and FPS cameras, switched with the camera mode in the input settings.
+    camera: Camera,
+
+    /// The mouse and keyboard input gathered for the camera since the last frame.
+    camera_input: CameraInput,
+
+    /// The running dolly zoom, while the demo is enabled in the settings window.
+    dolly_zoom: Option<DollyZoomAnimation>,
+
+    /// The recorded camera path, and its playback, controlled in the settings window.
+    flythrough: Flythrough,
+
+    /// Steps the renderer's quality settings to hold the frame rate set in the settings window.
+    quality_scaler: QualityScaler,
+
+    /// Recognizes gestures from touch input, applied to the camera and picking every frame.
+    gestures: GestureRecognizer,
+
+    /// Makes the window fullscreen, or windowed again, whenever the fullscreen settings change.
+    fullscreen: FullscreenController,
+
+    /// Merges the cursor moves of each frame and limits redraw requests to one per frame.
+    input: InputCoalescer,
+
+    /// The keys and mouse buttons held and pressed, the cursor, and the scrolling, queried for
+    /// the keyboard shortcuts and the camera's movement keys.
+    input_state: InputState,
+
+    /// Decides when frames are rendered, following the redraw policy edited in the Inspector.
+    redraw: RedrawScheduler,
+
+    /// The platform's safe-area insets, queried whenever the window changes size or scale.
+    safe_area: SafeAreaInsets,
+
+    /// Transient notifications shown in the bottom-right corner of the window.
+    ///
+    /// Recoverable problems, such as configuration validation warnings reported by the
+    /// renderer, are pushed into this queue and drawn on top of the GUI every frame until
+    /// they expire or are dismissed.
+    toasts: Toasts,
+
+    /// Decides how the application reacts to errors returned by the renderer.
+    ///
+    /// Both renderer initialization and frame rendering report failures as a `RenderError`
+    /// instead of panicking. This policy chooses an `ErrorAction` for each of them; it
+    /// defaults to `error::default_error_policy` and can be replaced with
+    /// `App::with_error_policy`.
+    error_policy: ErrorPolicy,
+
+    /// The number of consecutive failed renderer initialization attempts.
+    init_failures: u32,
+
+    /// The number of consecutive frames that failed to render.
+    ///
+    /// Reset to zero whenever a frame is rendered successfully.
+    frame_failures: u32,
+
+    /// The options the window and the renderer are created with. Set with `App::with_config`
+    /// or the builder methods for single window options, such as
+    /// `App::with_transparent_window`.
+    config: AppConfig,
+
+    /// Whether the window is fully hidden: minimized, covered by other windows, or, on the
+    /// web, in a background tab or scrolled out of view. No frames are rendered meanwhile.
+    occluded: bool,
+
+    /// The time the oldest input not yet shown by a frame arrived, if any. Once the frame
+    /// handling it is presented, the time since is recorded as the input latency.
+    input_arrived: Option<Instant>,
+
+    /// Whether the app is suspended, between `ApplicationHandler::suspended` and the next
+    /// `resumed`. No frames are rendered meanwhile, and on Android the window's surface is
+    /// dropped, since its native window is destroyed.
+    surface_suspended: bool,
+
+    /// The window's scale factor, the physical pixels per logical pixel, as of the latest
+    /// `ScaleFactorChanged` event.
+    scale_factor: f64,
+
+    /// Whether the window was resized since the surface was last configured.
+    ///
+    /// Reconfiguring the surface is expensive, so it happens once, right before the next frame
+    /// acquires its texture, however many `Resized` events arrived in between.
+    resize_pending: bool,
+
+    /// Whether rendering is suspended because the window has no area, as when it is minimized
+    /// on Windows. A surface of that size cannot be configured, so no frames are rendered and
+    /// the renderer keeps its last size until the window is restored.
+    rendering_suspended: bool,
+
+    /// The address to serve the rendered frames on, until streaming has been started. Set
+    /// with `App::with_stream_address`.
+    #[cfg(not(target_arch = "wasm32"))]
+    stream_address: Option<std::net::SocketAddr>,
+
+    /// The loader the model and the texture are read with, shared with their pending loads.
+    assets: std::rc::Rc<AssetLoader>,
+
+    /// Whether the load of the configuration's asset pack has been started.
+    pack_requested: bool,
+
+    /// The asset pack being mounted. The model and the texture wait for it.
+    pack_load: Option<PackLoad>,
+
+    /// The path or URL of the glTF model to draw in place of the triangle, until its load has
+    /// started. Set with `App::with_model`.
+    #[cfg(feature = "import-gltf")]
+    model_source: Option<String>,
+
+    /// The model being loaded.
+    #[cfg(feature = "import-gltf")]
+    model_load: Option<ModelLoad>,
+
+    /// The path or URL of the PNG or JPEG image to texture the scene with, until its load has
+    /// started. Set with `App::with_texture`.
+    texture_source: Option<String>,
+
+    /// The texture being loaded.
+    texture_load: Option<TextureLoad>,
+
+    /// The seed of determinism mode, or `None` when it is off. Set with
+    /// `App::with_determinism`.
+    determinism_seed: Option<u64>,
+
+    /// The benchmark being run, until its report is written. Set with `App::with_benchmark`.
+    #[cfg(not(target_arch = "wasm32"))]
+    benchmark: Option<BenchmarkRun>,
+
+    /// The persisted settings, loaded before the first window is created, and saved whenever
+    /// the GUI changes them and when the application exits.
+    settings: Settings,
+
+    /// The open secondary windows, by the id their events arrive with.
+    #[cfg(not(target_arch = "wasm32"))]
+    windows: std::collections::HashMap<winit::window::WindowId, WindowState>,
+
+    /// The secondary windows to open once the event loop is idle.
+    #[cfg(not(target_arch = "wasm32"))]
+    pending_windows: Vec<WindowKind>,
+
+    /// The native windows of the `egui` viewports, such as the GUI windows detached with
+    /// "Detach Windows". They share the main window's `egui` context and renderer.
+    #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+    viewports: ViewportWindows,
+
+    /// Records the CPU time of event handling, the GUI pass, and rendering while the CPU
+    /// Profiler's "Record" is checked. Shared with the renderer, which records its own scopes.
+    cpu_profiler: CpuProfiler,
+}
+
+impl App {
+    /// The distance the camera's position or direction must change within a frame to count as
+    /// still moving, when redrawing on demand.
+    const CAMERA_SETTLED_DISTANCE: f32 = 1e-5;
+
+    /// The shortest time between frames rendered straight from `Resized` events while the
+    /// window is resized interactively on Windows.
+    #[cfg(target_os = "windows")]
+    const LIVE_RESIZE_FRAME_INTERVAL: crate::Duration = crate::Duration::from_micros(16_667);
+
+    /// Returns the safe-area insets the GUI is laid out with, in points.
+    ///
+    /// These are the platform's insets (for example around the notch on iOS), or the
+    /// simulated notch's while the "Simulate Notch" setting is checked.
+    pub fn safe_area_insets(&self) -> SafeAreaInsets {
+        self.ui.safe_area
+    }
+
+    /// Returns the state of the keyboard and mouse, as of the last frame.
+    ///
+    /// Keys and buttons pressed while the GUI used them are not recorded as pressed.
+    pub fn input_state(&self) -> &InputState {
+        &self.input_state
+    }
+
+    /// Replaces the policy deciding how renderer errors are handled.
+    ///
+    /// The policy receives the error, the phase it occurred in, and the number of consecutive
+    /// failures before it in that phase, and returns the `ErrorAction` to take.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default().with_error_policy(|_error, phase, attempt| match phase {
+    ///     ErrorPhase::Initialization if attempt < 3 => ErrorAction::Retry,
+    ///     _ => ErrorAction::Exit,
+    /// });
+    /// ```
+    pub fn with_error_policy(
+        mut self,
+        policy: impl FnMut(&RenderError, ErrorPhase, u32) -> ErrorAction + 'static,
+    ) -> Self {
+        self.error_policy = ErrorPolicy::new(policy);
+        self
+    }
+
+    /// Replaces the rendering backend with `B`.
+    ///
+    /// By default the application renders with the wgpu `Renderer`. Any other
+    /// `RendererBackend`, such as the `NullBackend` for running without a GPU, can be
+    /// selected here before the event loop starts.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default().with_backend::<NullBackend>();
+    /// ```
+    pub fn with_backend<B: RendererBackend + 'static>(mut self) -> Self {
+        self.backend = BackendFactory::of::<B>();
+        self
+    }
+
+    /// Creates the window and the renderer with the options of `config`, replacing those set
+    /// before.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let config = AppConfig::default()
+    ///     .with_title("Viewer")
+    ///     .with_inner_size(1280, 720)
+    ///     .with_vsync(false);
+    /// let app = App::default().with_config(config);
+    /// ```
+    pub fn with_config(mut self, config: AppConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Makes the window transparent with per-pixel alpha, compositing the scene over the
+    /// desktop (or the page, on the web) wherever neither the sky nor the GUI covers it.
+    ///
+    /// The sky is not drawn in a transparent window. If the surface cannot be composited with
+    /// transparency, it stays opaque and a warning toast explains why.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default()
+    ///     .with_transparent_window(true)
+    ///     .with_always_on_top(true);
+    /// ```
+    pub fn with_transparent_window(mut self, transparent: bool) -> Self {
+        self.config.transparent_window = transparent;
+        self
+    }
+
+    /// Keeps the window above all other windows, for overlay and widget-style applications.
+    ///
+    /// This has no effect on the web.
+    pub fn with_always_on_top(mut self, always_on_top: bool) -> Self {
+        self.config.always_on_top = always_on_top;
+        self
+    }
+
+    /// Renders frames as `redraw_policy` says: continuously, only when something changed, or
+    /// at a fixed rate. It can still be changed in the Inspector panel.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default().with_redraw_policy(RedrawPolicy::OnDemand);
+    /// ```
+    pub fn with_redraw_policy(mut self, redraw_policy: RedrawPolicy) -> Self {
+        self.config.redraw_policy = redraw_policy;
+        self
+    }
+
+    /// Creates the window without the platform's decorations and draws the title bar with
+    /// `egui` instead, with the window title and minimize, maximize, and close buttons.
+    ///
+    /// Dragging the title bar moves the window and double-clicking it toggles maximization.
+    /// This has no effect on the web, where the canvas has no decorations, or without the `gui`
+    /// feature, where the platform's decorations are kept.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default().with_custom_titlebar(true);
+    /// ```
+    pub fn with_custom_titlebar(mut self, custom_titlebar: bool) -> Self {
+        self.config.custom_titlebar = custom_titlebar;
+        self
+    }
+
+    /// Creates the window without the platform's decorations and without a title bar drawn
+    /// by the GUI, so only the scene and the GUI's panels are shown.
+    ///
+    /// Combined with a transparent window and a background color with an alpha below one,
+    /// the scene can be used as a desktop overlay widget. The window cannot be moved or
+    /// resized by the user. This has no effect on the web.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default()
+    ///     .with_transparent_window(true)
+    ///     .with_borderless_window(true)
+    ///     .with_always_on_top(true);
+    /// ```
+    pub fn with_borderless_window(mut self, borderless: bool) -> Self {
+        self.config.borderless_window = borderless;
+        self
+    }
+
+    /// Serves the rendered frames over HTTP on `address` once the renderer is running, so a
+    /// remote browser can watch them at `http://<address>/`.
+    ///
+    /// Whether the server started, and the address it listens on, is shown in a toast.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default().with_stream_address("0.0.0.0:8080".parse()?);
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_stream_address(mut self, address: std::net::SocketAddr) -> Self {
+        self.stream_address = Some(address);
+        self
+    }
+
+    /// Draws the glTF or GLB model at `source`, a path or an HTTP(S) URL, in place of the
+    /// triangle once the renderer is running.
+    ///
+    /// The model is centered on the origin and framed by the camera. Whether it loaded is shown
+    /// in a toast.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default().with_model("assets/models/Duck.glb");
+    /// ```
+    #[cfg(feature = "import-gltf")]
+    pub fn with_model(mut self, source: impl Into<String>) -> Self {
+        self.model_source = Some(source.into());
+        self
+    }
+
+    /// Textures the scene with the PNG or JPEG image at `source`, a path or an HTTP(S) URL,
+    /// once the renderer is running.
+    ///
+    /// The image is multiplied with the vertex colors, at the texture coordinates of the
+    /// vertices. Whether it loaded is shown in a toast.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default().with_texture("assets/textures/checker.png");
+    /// ```
+    pub fn with_texture(mut self, source: impl Into<String>) -> Self {
+        self.texture_source = Some(source.into());
+        self
+    }
+
+    /// Runs `benchmark` once the renderer is running: the scene's meshes are drawn on a growing
+    /// grid of instances for the benchmark's duration, then the report is written, its summary
+    /// printed, and the application exits.
+    ///
+    /// Configure the app without vsync and with the continuous redraw policy, or the frame
+    /// rate is capped and the report measures the display instead.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default()
+    ///     .with_config(AppConfig::default().with_vsync(false))
+    ///     .with_benchmark(Benchmark::new("bench.csv"));
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_benchmark(mut self, benchmark: Benchmark) -> Self {
+        self.benchmark = Some(BenchmarkRun::new(benchmark));
+        self
+    }
+
+    /// Runs the scene in determinism mode: all randomness is drawn from streams of `seed`, and
+    /// the simulation restarts from its initial state and advances only in fixed ticks.
+    ///
+    /// Runs with the same seed record identical state hashes for every tick, which replay
+    /// tests and networked synchronization compare with `TickLog::first_divergence`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let app = App::default().with_determinism(42);
+    /// ```
+    pub fn with_determinism(mut self, seed: u64) -> Self {
+        self.determinism_seed = Some(seed);
+        self
+    }
+
+    /// Returns the background color used until one is saved in the settings: the renderer's
+    /// default, or fully transparent in a transparent window.
+    fn default_clear_color(transparent_window: bool) -> [f32; 4] {
+        if transparent_window {
+            [0.0; 4]
+        } else {
+            let color = crate::renderer::Renderer::CLEAR_COLOR;
+            [color.r, color.g, color.b, color.a].map(|channel| channel as f32)
+        }
+    }
+
+    /// Updates `settings` with the GUI scale, the background color unless it is the default,
+    /// the key bindings, and whether the panels are shown, as edited in `ui`, and saves them.
+    fn save_settings(settings: &mut Settings, ui: &crate::ui::UiState, transparent_window: bool) {
+        let default_clear_color = Self::default_clear_color(transparent_window);
+        settings.gui_scale = ui.gui_scale;
+        settings.clear_color = (ui.clear_color != default_clear_color).then_some(ui.clear_color);
+        settings.bindings = ui.action_map.clone();
+        settings.panels_visible = ui.panels_visible;
+        if let Err(error) = settings.save() {
+            log::warn!("Failed to save the settings: {error}");
+        }
+    }
+
+    /// Saves the settings together with the window's geometry and the camera's pose, which
+    /// change too often to be saved whenever they do.
     ///
-    /// This boolean field tracks the visibility state of various panels in the application.
-    /// Panels may include UI components like menus, sidebars, or overlays that are toggled
-    /// on or off during runtime.
+    /// The geometry of a minimized or fullscreen window is not saved, and a maximized window
+    /// keeps the size and position it had before, so it is restored to them once it is
+    /// unmaximized.
+    fn save_session(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(window) = self.window.as_ref() {
+            let size = window.inner_size();
+            let windowed = window.is_minimized() != Some(true) && window.fullscreen().is_none();
+            if windowed && size.width > 0 && size.height > 0 {
+                let mut geometry = crate::settings::WindowGeometry {
+                    size: [size.width, size.height],
+                    position: window
+                        .outer_position()
+                        .ok()
+                        .map(|position| [position.x, position.y]),
+                    maximized: window.is_maximized(),
+                };
+                if let (true, Some(previous)) = (geometry.maximized, self.settings.window) {
+                    geometry.size = previous.size;
+                    geometry.position = previous.position;
+                }
+                self.settings.window = Some(geometry);
+            }
+        }
+        self.settings.camera = Some(crate::settings::CameraPose {
+            eye: self.camera.eye().into(),
+            forward: self.camera.forward().into(),
+            focus_distance: self.camera.focus_distance(),
+            field_of_view: self.ui.projection.field_of_view,
+        });
+        Self::save_settings(&mut self.settings, &self.ui, self.config.transparent_window);
+    }
+
+    /// Applies an `action` chosen in the custom title bar to `window`.
+    #[cfg(feature = "gui")]
+    fn apply_titlebar_action(
+        window: &Window,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        action: crate::titlebar::TitlebarAction,
+    ) {
+        use crate::titlebar::TitlebarAction;
+
+        match action {
+            TitlebarAction::Drag => {
+                if let Err(error) = window.drag_window() {
+                    log::warn!("Failed to drag the window: {error}");
+                }
+            }
+            TitlebarAction::Minimize => window.set_minimized(true),
+            TitlebarAction::ToggleMaximize => window.set_maximized(!window.is_maximized()),
+            TitlebarAction::Close => {
+                log::info!("Close requested. Exiting...");
+                event_loop.exit();
+            }
+        }
+    }
+
+    /// Consults the error policy about a failed renderer initialization.
     ///
-    /// - A value of `true` means that the panels are currently displayed to the user.
-    /// - A value of `false` means that the panels are hidden.
+    /// Logs the error, increments the consecutive failure counter, and returns the chosen
+    /// action. On `ErrorAction::Exit` the event loop is stopped.
+    fn handle_init_error(
+        &mut self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        error: &RenderError,
+    ) -> ErrorAction {
+        log::error!("Failed to create renderer: {error}");
+        let action =
+            self.error_policy
+                .decide(error, ErrorPhase::Initialization, self.init_failures);
+        self.init_failures += 1;
+        if action == ErrorAction::Exit {
+            event_loop.exit();
+        }
+        action
+    }
+
+    /// Sets up a newly created backend, given as the wgpu `renderer` if it is one, and resets
+    /// what the Inspector shows of the renderer otherwise.
+    fn adopt_renderer(&mut self, renderer: Option<&mut Renderer>) {
+        match renderer {
+            Some(renderer) => {
+                // The Inspector starts from the configured present mode.
+                self.ui.present_mode = renderer.present_mode();
+                self.ui.frame_latency = renderer.frame_latency();
+                self.ui.gpu_info = Some(renderer.gpu_info().clone());
+                renderer.set_cpu_profiler(self.cpu_profiler.clone());
+            }
+            // Other backends present with vsync, draw filled triangles, and measure nothing.
+            None => {
+                self.ui.present_mode = wgpu::PresentMode::Fifo;
+                self.ui.present_modes = vec![wgpu::PresentMode::Fifo];
+                self.ui.frame_latency = crate::gpu::Gpu::DEFAULT_FRAME_LATENCY;
+                self.ui.render_modes = vec![crate::scene::RenderMode::Filled];
+                self.ui.gpu_info = None;
+                self.ui.draw_stats = Default::default();
+                self.ui.frame_stats = Default::default();
+                self.ui.gpu_errors.clear();
+            }
+        }
+    }
+
+    /// Starts creating the renderer asynchronously on WebAssembly.
     ///
-    /// This field is typically used to manage and render UI elements conditionally.
-    panels_visible: bool,
+    /// The browser does not allow blocking on the GPU setup futures, so the renderer is
+    /// created in a spawned task and delivered through `renderer_receiver` once ready.
+    #[cfg(target_arch = "wasm32")]
+    fn spawn_renderer(
+        &mut self,
+        window: Arc<Window>,
+        width: u32,
+        height: u32,
+        use_fallback_adapter: bool,
+    ) {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        self.renderer_receiver = Some(receiver);
+        let renderer = self.backend.create(
+            window,
+            width,
+            height,
+            use_fallback_adapter,
+            self.config.renderer.clone(),
+        );
+        wasm_bindgen_futures::spawn_local(async move {
+            let renderer = renderer.await;
+            if sender.send(renderer).is_err() {
+                log::error!("Failed to send renderer!");
+            }
+        });
+    }
+
+    /// Returns the refresh rate of the monitor `window` is on, in millihertz, if known.
+    fn refresh_rate(window: &Window) -> Option<u32> {
+        window
+            .current_monitor()
+            .and_then(|monitor| monitor.refresh_rate_millihertz())
+    }
+
+    /// Requests a redraw of the window if the redraw policy wants one now, at most once per
+    /// frame, and unless the window is hidden. The secondary windows are redrawn along with it.
+    fn schedule_redraw(&mut self) {
+        let hidden = self.occluded || self.surface_suspended;
+        if let (false, Some(window)) = (hidden, self.window.as_ref()) {
+            if self.redraw.wants_redraw(Instant::now()) && self.input.request_redraw() {
+                window.request_redraw();
+                #[cfg(not(target_arch = "wasm32"))]
+                for state in self.windows.values() {
+                    state.request_redraw();
+                }
+                #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+                self.viewports.request_redraws();
+            }
+        }
+    }
+
+    /// Opens a secondary window showing `kind` next to the main window, such as a detached
+    /// Inspector. It is opened once the event loop is idle, and closed like any other window.
+    ///
+    /// Secondary windows are only available on native targets.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// app.open_window(WindowKind::Inspector);
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open_window(&mut self, kind: WindowKind) {
+        self.pending_windows.push(kind);
+    }
+
+    /// Returns the number of open secondary windows.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn secondary_window_count(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Opens the windows requested with `open_window` or from the GUI.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_pending_windows(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        if std::mem::take(&mut self.ui.inspector_window_requested) {
+            self.pending_windows.push(WindowKind::Inspector);
+        }
+        for kind in std::mem::take(&mut self.pending_windows) {
+            match WindowState::open(
+                event_loop,
+                kind,
+                &self.backend,
+                self.config.renderer.clone(),
+            ) {
+                Ok(state) => {
+                    state.request_redraw();
+                    self.windows.insert(state.id(), state);
+                }
+                Err(error) => self.toasts.error(error.to_string()),
+            }
+        }
+    }
+
+    /// Applies the gestures recognized since the last frame.
+    ///
+    /// Drags look around like the right mouse button, two-finger gestures pan, zoom, and turn
+    /// the camera, and a double tap resets it. A tap selects the node under the finger like a
+    /// click and moves the probed pixel there, and a long press also turns on the depth probe.
+    fn apply_gestures(&mut self, now: Instant) {
+        self.gestures.set_settings(self.ui.gesture_settings);
+        self.gestures.update(now);
+        for gesture in self.gestures.drain() {
+            let probe_at = |position: crate::math::Vec2| {
+                Some((position.x.max(0.0) as u32, position.y.max(0.0) as u32))
+            };
+            match gesture {
+                Gesture::Tap { position } => {
+                    self.cursor_position = probe_at(position);
+                    let renderer = self.renderer.as_mut();
+                    if let (Some(renderer), Some(cursor)) = (
+                        renderer.and_then(|renderer| renderer.as_renderer_mut()),
+                        self.cursor_position,
+                    ) {
+                        renderer.pick(cursor);
+                    }
+                }
+                Gesture::DoubleTap { .. } => self.camera.reset(),
+                Gesture::LongPress { position } => {
+                    self.cursor_position = probe_at(position);
+                    self.ui.depth_probe_enabled = true;
+                }
+                Gesture::Drag { delta, .. } => self.camera_input.look_delta += delta,
+                Gesture::Pan { delta, .. } => self.camera_input.pan += delta,
+                Gesture::Pinch { scale, .. } => self.camera_input.pinch += scale.ln(),
+                Gesture::Rotate { angle, .. } => self.camera_input.twist += angle,
+            }
+            self.ui.last_gesture = Some(gesture.name());
+        }
+    }
+
+    /// Handles a cursor move, the latest one reported since the previous event.
+    ///
+    /// The cursor is tracked for the camera and the depth probe before `egui` sees the move,
+    /// so the probe keeps following the cursor over GUI widgets. Unless a widget is being
+    /// dragged, the move also drags the picked control point of the camera path, if any, or
+    /// the grabbed handle of the transform gizmo.
+    fn cursor_moved(&mut self, event: WindowEvent) {
+        let WindowEvent::CursorMoved { position, .. } = event else {
+            return;
+        };
+        #[cfg(feature = "gui")]
+        let (Some(gui_state), Some(window)) = (self.gui_state.as_mut(), self.window.as_ref()) else {
+            return;
+        };
+        let Some(renderer) = self.renderer.as_mut() else {
+            return;
+        };
+
+        let cursor_position = (position.x >= 0.0 && position.y >= 0.0)
+            .then_some((position.x as u32, position.y as u32));
+        if let (Some(old), Some(new)) = (self.cursor_position, cursor_position) {
+            self.camera_input.cursor_moved(crate::math::vec2(
+                new.0 as f32 - old.0 as f32,
+                new.1 as f32 - old.1 as f32,
+            ));
+        }
+        self.cursor_position = cursor_position;
+        self.input_state.handle_window_event(&event, false);
+
+        // The GUI consumes moves over all of its panels, but the one showing the scene passes
+        // them on to the camera path.
+        #[cfg(feature = "gui")]
+        if gui_state.on_window_event(window, &event).consumed && !self.ui.scene_panel_hovered {
+            return;
+        }
+
+        // Over the letterbox bars, the control point follows the nearest edge of the viewport.
+        if let (true, Some(cursor), Some(view_projection), Some(viewport)) = (
+            self.ui.camera_path_visible && self.ui.layers.shows(self.ui.layers.helpers),
+            self.cursor_position,
+            renderer.view_projection(),
+            renderer.viewport(),
+        ) {
+            self.camera_path.pointer_moved(
+                viewport.to_local_clamped(cursor),
+                &view_projection,
+                viewport.size(),
+            );
+        }
+        if let (true, Some(cursor), Some(view_projection), Some(viewport)) = (
+            self.ui.layers.shows(self.ui.layers.helpers),
+            self.cursor_position,
+            renderer.view_projection(),
+            renderer.viewport(),
+        ) {
+            let renderer = renderer.as_renderer_mut();
+            let target = renderer.as_deref().and_then(Renderer::gizmo_target);
+            if let (Some(local), Some(renderer)) = (
+                self.gizmo.pointer_moved(
+                    viewport.to_local_clamped(cursor),
+                    target.as_ref(),
+                    &view_projection,
+                    viewport.size(),
+                ),
+                renderer,
+            ) {
+                renderer.set_selected_node_local(local);
+            }
+        }
+    }
+
+    /// Shows the flythrough's keyframes on the camera path once it has two of them. Control
+    /// points dragged on the path move their keyframes, and new keyframes replace the path.
+    fn sync_camera_path(path: &mut CameraPath, editor: &mut CurveEditor) {
+        if path.keyframes.len() < 2 {
+            return;
+        }
+        match &editor.curve {
+            crate::curves::Curve::CatmullRom { points, .. }
+                if points.len() == path.keyframes.len() =>
+            {
+                path.set_positions(points);
+            }
+            _ => {
+                *editor = CurveEditor::new(crate::curves::Curve::CatmullRom {
+                    points: path.positions(),
+                    closed: false,
+                });
+            }
+        }
+    }
 }
 
 /// Implements the `ApplicationHandler` trait for `App`, defining how the application
@@ -304,6 +1170,8 @@ impl ApplicationHandler for App {
     /// ### Behavior:
     /// - If this is the first window being created for the application, additional tasks, such as
     ///   setting up the `egui` context and GUI state, are performed.
+    /// - Once the window exists, resuming after `suspended` keeps it and the renderer, and on
+    ///   Android only recreates the surface dropped on suspension.
     /// - On WebAssembly platforms, platform-specific hooks and loggers are configured to integrate seamlessly
     ///   with the browser environment.
     ///
@@ -313,6 +1181,25 @@ impl ApplicationHandler for App {
     /// This method ensures that the application is ready to render frames and interact with the user
     /// across different platforms (desktop and WebAssembly).
     fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        // Resuming after a suspension keeps the window, the renderer, and all GPU resources,
+        // and only creates the surface again where it was dropped.
+        if let Some(window) = self.window.clone() {
+            if std::mem::take(&mut self.surface_suspended) {
+                log::info!("Resumed");
+                if let Some(renderer) = self.renderer.as_mut() {
+                    if cfg!(target_os = "android") {
+                        if let Err(error) = renderer.resume_surface(window.clone()) {
+                            log::error!("Failed to recreate the surface: {error}");
+                            self.toasts
+                                .error(format!("Failed to recreate the surface: {error}"));
+                        }
+                    }
+                }
+                self.redraw.mark_dirty();
+            }
+            return;
+        }
+
         // Represents the attributes used to create or configure an application window.
         //
         // This field is initialized with default attributes and can be further customized
@@ -326,11 +1213,54 @@ impl ApplicationHandler for App {
         //
         // This variable plays a key role in the cross-platform support capabilities of this application,
         // allowing platform-specific customization via feature flags or attributes extensions.
-        let mut attributes = Window::default_attributes();
+        // The settings are loaded before the first window is created, which reopens with the
+        // size and position it was closed with.
+        if self.window.is_none() {
+            self.settings = Settings::load();
+        }
+
+        let mut attributes = Window::default_attributes()
+            .with_decorations(
+                !(self.config.borderless_window
+                    || (self.config.custom_titlebar && cfg!(feature = "gui"))),
+            )
+            .with_transparent(self.config.transparent_window)
+            .with_window_level(if self.config.always_on_top {
+                winit::window::WindowLevel::AlwaysOnTop
+            } else {
+                winit::window::WindowLevel::Normal
+            });
 
         #[cfg(not(target_arch = "wasm32"))]
         {
-            attributes = attributes.with_title("Standalone Winit/Wgpu Example");
+            attributes = attributes.with_title(&self.config.title);
+            // The icon is decoded whenever the window is created. A broken icon only
+            // leaves the platform's default in place, instead of failing to start.
+            match self.config.window_icon() {
+                Ok(icon) => attributes = attributes.with_window_icon(icon),
+                Err(error) => log::warn!("{error}"),
+            }
+            // The Wayland app id and the X11 `WM_CLASS` are the same attribute in `winit`.
+            #[cfg(target_os = "linux")]
+            if let Some((general, instance)) = &self.config.app_name {
+                use winit::platform::x11::WindowAttributesExtX11;
+                attributes = attributes.with_name(general, instance);
+            }
+            if let Some((width, height)) = self.config.inner_size {
+                attributes =
+                    attributes.with_inner_size(winit::dpi::LogicalSize::new(width, height));
+            }
+            // A size set in the configuration takes precedence over the saved one.
+            if let Some(geometry) = self.settings.window {
+                if self.config.inner_size.is_none() {
+                    let [width, height] = geometry.size;
+                    attributes = attributes.with_inner_size(PhysicalSize::new(width, height));
+                }
+                if let Some([x, y]) = geometry.position {
+                    attributes = attributes.with_position(winit::dpi::PhysicalPosition::new(x, y));
+                }
+                attributes = attributes.with_maximized(geometry.maximized);
+            }
         }
 
         // Represents the width of the HTML canvas element when running on WebAssembly.
@@ -373,9 +1303,10 @@ impl ApplicationHandler for App {
         let mut canvas_height = 0;
 
         // Platform-specific configuration for WebAssembly:
-        // This block retrieves the HTML canvas element using its ID "canvas" and sets the application's
-        // window attributes with this canvas. It also initializes the `last_size` field to match the
-        // canvas dimensions (width and height). This setup ensures that the application correctly adapts
+        // This block retrieves the HTML canvas element using its configured ID (`AppConfig::canvas_id`,
+        // "canvas" by default) and sets the application's window attributes with this canvas. It
+        // also initializes the `last_size` field to match the canvas dimensions (width and height).
+        // This setup ensures that the application correctly adapts
         // to the browser environment when running on WebAssembly. The use of `WindowAttributesExtWebSys`
         // allows the configuration of the canvas element to be integrated into the `winit` window system.
         #[cfg(target_arch = "wasm32")]
@@ -384,7 +1315,8 @@ impl ApplicationHandler for App {
 
             // Represents the HTML canvas element used as the rendering surface when running on WebAssembly.
             //
-            // This variable references the HTML canvas element retrieved from the DOM via its unique ID ("canvas").
+            // This variable references the HTML canvas element retrieved from the DOM via its unique ID
+            // (`AppConfig::canvas_id`, "canvas" by default).
             // The canvas serves as the main rendering surface for the application and is directly tied
             // to the browser environment. This connection allows the application to seamlessly integrate
             // its rendering logic with the web platform.
@@ -401,7 +1333,7 @@ impl ApplicationHandler for App {
             //   the `winit` window system.
             //
             // ### Notes:
-            // - The `canvas` element must exist in the HTML document with the ID "canvas" for the application
+            // - The `canvas` element must exist in the HTML document with the configured ID for the application
             //   to initialize correctly.
             // - The dimensions of the canvas are dynamically retrieved to synchronize it with the application's
             //   rendering state.
@@ -411,17 +1343,22 @@ impl ApplicationHandler for App {
             //   surface in the browser.
             // - The retrieved canvas is passed to the `WindowAttributes` configuration using the
             //   `attributes.with_canvas(Some(canvas))` method.
+            //
+            // A page without the canvas cannot show the application, which is reported to the
+            // error policy like any other failure to create the renderer.
             let canvas = wgpu::web_sys::window()
-                .unwrap()
-                .document()
-                .unwrap()
-                .get_element_by_id("canvas")
-                .unwrap()
-                .dyn_into::<wgpu::web_sys::HtmlCanvasElement>()
-                .unwrap();
+                .and_then(|window| window.document())
+                .and_then(|document| document.get_element_by_id(&self.config.canvas_id))
+                .and_then(|element| element.dyn_into::<wgpu::web_sys::HtmlCanvasElement>().ok());
+            let Some(canvas) = canvas else {
+                let error = RenderError::MissingCanvas(self.config.canvas_id.clone());
+                self.handle_init_error(event_loop, &error);
+                return;
+            };
             canvas_width = canvas.width();
             canvas_height = canvas.height();
             self.last_size = (canvas_width, canvas_height);
+            self.rendering_suspended = canvas_width == 0 || canvas_height == 0;
             attributes = attributes.with_canvas(Some(canvas));
         }
 
@@ -484,7 +1421,33 @@ impl ApplicationHandler for App {
             let window_handle = Arc::new(window);
 
             self.window = Some(window_handle.clone());
+            self.scale_factor = window_handle.scale_factor();
+            self.safe_area = SafeAreaInsets::query(&window_handle);
+            self.redraw
+                .set_refresh_rate(Self::refresh_rate(&window_handle));
+            self.ui.fullscreen.exclusive_supported =
+                crate::fullscreen::exclusive_supported(event_loop);
+            self.ui.fullscreen.refresh_video_modes(&window_handle);
+            self.ui.gui_scale = self.settings.gui_scale;
+            self.ui.action_map = self.settings.bindings.clone();
+            self.ui.clear_color = self
+                .settings
+                .clear_color
+                .unwrap_or_else(|| Self::default_clear_color(self.config.transparent_window));
+            self.ui.redraw_policy = self.config.redraw_policy;
+            self.redraw.mark_dirty();
             if first_window_handle {
+                // The panels and the camera are restored as they were left in the last session.
+                self.ui.panels_visible = self.settings.panels_visible;
+                if let Some(pose) = self.settings.camera {
+                    self.camera.look_from(
+                        pose.eye.into(),
+                        pose.forward.into(),
+                        pose.focus_distance,
+                    );
+                    self.ui.projection.field_of_view = pose.field_of_view;
+                }
+
                 // Checks if this is the first time a window is being created for the application.
                 // If it is, performs several initialization steps for the application's state:
                 // - Creates and sets up an `egui` context and GUI state for rendering.
@@ -522,6 +1485,7 @@ impl ApplicationHandler for App {
                 //
                 // The `gui_context` is a central component for initializing and managing all subsequent
                 // GUI-related stages in the application.
+                #[cfg(feature = "gui")]
                 let gui_context = egui::Context::default();
 
                 #[cfg(not(target_arch = "wasm32"))]
@@ -545,6 +1509,7 @@ impl ApplicationHandler for App {
                     // ```
                     let inner_size = window_handle.inner_size();
                     self.last_size = (inner_size.width, inner_size.height);
+                    self.rendering_suspended = inner_size.width == 0 || inner_size.height == 0;
                 }
 
                 // This code block handles platform-specific initialization for desktop and WebAssembly targets.
@@ -559,7 +1524,7 @@ impl ApplicationHandler for App {
                 //
                 // These steps ensure the application adapts to the platform's specific requirements
                 // for accurate window size, DPI scaling, and rendering behaviors.
-                #[cfg(target_arch = "wasm32")]
+                #[cfg(all(target_arch = "wasm32", feature = "gui"))]
                 {
                     gui_context.set_pixels_per_point(window_handle.scale_factor() as f32);
                 }
@@ -581,6 +1546,7 @@ impl ApplicationHandler for App {
                 // ### Platform-Specific Behavior:
                 // - On desktop platforms, this ID is stable and directly corresponds to the `winit` or windowing system's identifiers.
                 // - On WebAssembly, it adapts to the platform specifics, ensuring proper scaling and viewport positioning.
+                #[cfg(feature = "gui")]
                 let viewport_id = gui_context.viewport_id();
 
                 // Represents the state of the `egui` integration with the `winit` windowing system.
@@ -616,12 +1582,13 @@ impl ApplicationHandler for App {
                 //     None,
                 // );
                 // ```
+                #[cfg(feature = "gui")]
                 let gui_state = egui_winit::State::new(
                     gui_context,
                     viewport_id,
                     &window_handle,
                     Some(window_handle.scale_factor() as _),
-                    Some(Theme::Dark),
+                    Some(winit::window::Theme::Dark),
                     None,
                 );
 
@@ -681,10 +1648,41 @@ impl ApplicationHandler for App {
                     //
                     // The renderer ensures proper graphics output and integrates seamlessly with the
                     // GUI state and the platform's rendering pipeline.
-                    let renderer = pollster::block_on(async move {
-                        Renderer::new(window_handle.clone(), width, height).await
-                    });
-                    self.renderer = Some(renderer);
+                    //
+                    // Creation is retried according to the error policy: a `Retry` attempts the
+                    // same configuration again, a `Fallback` switches to a software adapter,
+                    // `Software` replaces the backend with the CPU rasterizer, and `Ignore` or
+                    // `Exit` give up (the latter also stops the event loop).
+                    let mut use_fallback_adapter = false;
+                    loop {
+                        let result = pollster::block_on(self.backend.create(
+                            window_handle.clone(),
+                            width,
+                            height,
+                            use_fallback_adapter,
+                            self.config.renderer.clone(),
+                        ));
+                        match result {
+                            Ok(mut renderer) => {
+                                renderer.set_scale_factor(self.scale_factor as f32);
+                                self.adopt_renderer(renderer.as_renderer_mut());
+                                self.renderer = Some(renderer);
+                                self.init_failures = 0;
+                                break;
+                            }
+                            Err(error) => match self.handle_init_error(event_loop, &error) {
+                                ErrorAction::Retry => {}
+                                ErrorAction::Fallback => use_fallback_adapter = true,
+                                #[cfg(feature = "software")]
+                                ErrorAction::Software => {
+                                    self.backend = BackendFactory::of::<SoftwareBackend>();
+                                    self.toasts
+                                        .warning("No GPU available, rendering on the CPU");
+                                }
+                                ErrorAction::Ignore | ErrorAction::Exit => break,
+                            },
+                        }
+                    }
                 }
 
                 #[cfg(target_arch = "wasm32")]
@@ -719,45 +1717,20 @@ impl ApplicationHandler for App {
                     // - On successful reception, the renderer instance is set up for the application.
                     // - If the `sender` fails or drops before sending the renderer, the `receiver` will
                     //   return an error, which is handled to log the failure gracefully.
-                    let (sender, receiver) = futures::channel::oneshot::channel();
-                    self.renderer_receiver = Some(receiver);
                     std::panic::set_hook(Box::new(console_error_panic_hook::hook));
                     console_log::init().expect("Failed to initialize logger!");
                     log::info!("Canvas dimensions: ({canvas_width} x {canvas_height})");
-                    wasm_bindgen_futures::spawn_local(async move {
-                        // Represents the primary renderer responsible for managing graphical output and ensuring the rendering pipeline aligns with the application's state.
-                        //
-                        // This instance is initialized as part of the application setup. It is responsible for core tasks such as creating shaders, setting up the swap
-                        // chain, and synchronizing rendering operations with the windowing system.
-                        //
-                        // ### Responsibilities:
-                        // - Manage the rendering pipeline and swap chains.
-                        // - Adapt to window size changes by reinitializing rendering resources.
-                        // - Render frames in coordination with GUI interactions and the application's main event loop.
-                        //
-                        // ### Notes on Asynchronous Initialization:
-                        // - **Desktop Platforms:** The renderer is initialized using a blocking async call (`pollster::block_on`) to ensure readiness before the main event loop starts.
-                        // - **WebAssembly:** Due to asynchronous restrictions on WebAssembly, the renderer is set up non-blockingly through an async task, leveraging `wasm_bindgen_futures::spawn_local`.
-                        //
-                        // ### Error Handling:
-                        // - On WebAssembly, failure to create or send the renderer instance is logged as an error using the `log` crate.
-                        //
-                        // ### Example Use:
-                        // After initialization, the renderer is used to render frames:
-                        // ```rust
-                        // if let Some(renderer) = &self.renderer {
-                        //     renderer.render_frame(&gui_data);
-                        // }
-                        // ```
-                        let renderer =
-                            Renderer::new(window_handle.clone(), canvas_width, canvas_height).await;
-                        if sender.send(renderer).is_err() {
-                            log::error!("Failed to create and send renderer!");
-                        }
-                    });
+                    //
+                    // The renderer is created in a spawned task and delivered through the
+                    // `renderer_receiver`. If creation fails, the error policy is consulted once
+                    // the result arrives in `window_event`.
+                    self.spawn_renderer(window_handle.clone(), canvas_width, canvas_height, false);
                 }
 
-                self.gui_state = Some(gui_state);
+                #[cfg(feature = "gui")]
+                {
+                    self.gui_state = Some(gui_state);
+                }
                 self.last_render_time = Some(Instant::now());
             }
         }
@@ -773,7 +1746,8 @@ impl ApplicationHandler for App {
     ///
     /// # Parameters
     /// - `event_loop`: Reference to the active event loop, used to control application state (e.g., exiting).
-    /// - `_window_id`: The ID of the window that triggered the event. Currently unused.
+    /// - `window_id`: The ID of the window that triggered the event. Events of secondary windows
+    ///   are handed to their `WindowState`.
     /// - `event`: The `WindowEvent` instance containing details about the event that occurred.
     ///
     /// # Behavior
@@ -781,33 +1755,37 @@ impl ApplicationHandler for App {
     ///   and assigns the renderer to the appropriate field if ready.
     /// - Makes an early return if any of the required application state components (`gui_state`,
     ///   `renderer`, `window`, `last_render_time`) are missing.
-    /// - Routes events to the GUI state. If the event is consumed by the GUI,
-    ///   it does not handle it further.
+    /// - Routes events to the GUI state, then records them in the input state. If the event
+    ///   is consumed by the GUI, it does not handle it further.
     /// - Intercepts certain events for additional processing:
-    ///   - `KeyboardInput`: Closes the application if the Escape key is pressed.
-    ///   - `Resized`: Adjusts the renderer's surface size to match the new dimensions.
+    ///   - `MouseInput` and `MouseWheel`: Look around (right button) and zoom the camera.
+    ///   - `Resized`: Records the new size, applied to the renderer's surface right before the
+    ///     next frame. On Windows, that frame is rendered straight away during a live resize.
     ///   - `CloseRequested`: Exits the application when a close request is received.
-    ///   - `RedrawRequested`: Triggers GUI rendering and updates the renderer with
-    ///     the current frame data.
+    ///   - `RedrawRequested`: Dispatches the actions whose keys or buttons were pressed since
+    ///     the last frame (by default, Escape exits, F12 takes a screenshot, F9 captures the
+    ///     frame in an attached graphics debugger, and F1 and F2 toggle the frame statistics
+    ///     and the panels), moves the camera with the held movement actions, triggers GUI
+    ///     rendering, and updates the renderer with the current frame data.
     ///
     /// # Example
-    /// In the case of a window resize event, the function logs the new size and ensures
-    /// the renderer adjusts accordingly:
+    /// In the case of a window resize event, the function records the new size, which the
+    /// next frame applies to the renderer before acquiring its surface texture:
     ///
     /// ```ignore
     /// WindowEvent::Resized(PhysicalSize { width, height }) => {
-    ///     log::info!("Resizing renderer surface to: ({width}, {height})");
-    ///     renderer.resize(width, height);
     ///     self.last_size = (width, height);
+    ///     self.resize_pending = true;
     /// }
     /// ```
     ///
     /// The function ensures that the application responds gracefully to user interactions
     /// and system events and highlights the modular handling of events based on type.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
     fn window_event(
         &mut self,
         event_loop: &winit::event_loop::ActiveEventLoop,
-        _window_id: winit::window::WindowId,
+        window_id: winit::window::WindowId,
         event: winit::event::WindowEvent,
     ) {
         // Handles platform-specific logic for WebAssembly (`wasm32`) environments.
@@ -817,18 +1795,117 @@ impl ApplicationHandler for App {
         // and the receiver is set to `None` to indicate that no further waiting is required.
         // This ensures the application correctly initializes the renderer in an asynchronous
         // manner, which is required for WebAssembly environments.
+        //
+        // If initialization failed, the error policy decides whether to spawn another attempt
+        // (optionally on a fallback adapter), continue without a renderer, or exit.
         #[cfg(target_arch = "wasm32")]
         {
-            let mut renderer_received = false;
+            let mut received = None;
             if let Some(receiver) = self.renderer_receiver.as_mut() {
-                if let Ok(Some(renderer)) = receiver.try_recv() {
-                    self.renderer = Some(renderer);
-                    renderer_received = true;
+                if let Ok(Some(result)) = receiver.try_recv() {
+                    received = Some(result);
                 }
             }
-            if renderer_received {
+            if let Some(result) = received {
                 self.renderer_receiver = None;
+                match result {
+                    Ok(mut renderer) => {
+                        renderer.set_scale_factor(self.scale_factor as f32);
+                        self.adopt_renderer(renderer.as_renderer_mut());
+                        self.renderer = Some(renderer);
+                        self.init_failures = 0;
+                    }
+                    Err(error) => {
+                        let action = self.handle_init_error(event_loop, &error);
+                        if let (ErrorAction::Retry | ErrorAction::Fallback, Some(window)) =
+                            (action, self.window.clone())
+                        {
+                            let (width, height) = self.last_size;
+                            self.spawn_renderer(
+                                window,
+                                width,
+                                height,
+                                action == ErrorAction::Fallback,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // A frame of the CPU profiler begins with each redraw, and holds the events handled
+        // until the next one.
+        if matches!(event, WindowEvent::RedrawRequested) {
+            self.cpu_profiler.finish_frame();
+        }
+        let _event_scope = self.cpu_profiler.scope(match event {
+            WindowEvent::RedrawRequested => "Redraw",
+            _ => "Event Handling",
+        });
+
+        // Any event but the redraw itself may change what the next frame shows.
+        if !matches!(event, WindowEvent::RedrawRequested) {
+            self.redraw.mark_dirty();
+        }
+
+        // Secondary windows handle their own events, and are closed once they are asked to.
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(state) = self.windows.get_mut(&window_id) {
+            if !state.handle_event(&event, &mut self.ui, &self.camera) {
+                if let Some(mut state) = self.windows.remove(&window_id) {
+                    state.shutdown();
+                }
+            }
+            return;
+        }
+
+        // The windows of `egui` viewports run their passes on the main window's context, and
+        // are drawn by its renderer.
+        #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+        if self.viewports.contains(window_id) {
+            let renderer = self.renderer.as_mut();
+            if let Some(renderer) = renderer.and_then(|renderer| renderer.as_renderer_mut()) {
+                self.viewports
+                    .handle_event(event_loop, window_id, &event, renderer);
             }
+            return;
+        }
+
+        // The input latency is measured from the first input after the previous frame.
+        if matches!(
+            event,
+            WindowEvent::KeyboardInput { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::CursorMoved { .. }
+                | WindowEvent::Touch(_)
+        ) {
+            self.input_arrived.get_or_insert_with(Instant::now);
+        }
+
+        // Cursor moves are coalesced: only the latest one is kept until another event arrives
+        // or the next frame starts, so a mouse polled at 1000 Hz or more is handled once per
+        // frame. Clicks, releases, and frames therefore always see the latest cursor position.
+        if let WindowEvent::CursorMoved { .. } = event {
+            self.input.defer_cursor(event);
+            self.schedule_redraw();
+            return;
+        }
+        if let Some(moved) = self.input.take_cursor() {
+            self.cursor_moved(moved);
+        }
+        if matches!(event, WindowEvent::RedrawRequested) {
+            self.input.begin_frame();
+            self.ui.pointer_events = self.input.last_frame();
+            self.redraw.set_policy(self.ui.redraw_policy);
+            self.redraw.set_frame_pacing(self.ui.frame_pacing);
+            self.redraw.begin_frame(Instant::now());
+        }
+
+        // Gestures are applied once per frame, before the GUI is built, so it shows the last
+        // one and the camera moves with them in the same frame.
+        if matches!(event, WindowEvent::RedrawRequested) {
+            self.apply_gestures(Instant::now());
         }
 
         // Destructures and checks if all necessary components of the application state
@@ -836,8 +1913,11 @@ impl ApplicationHandler for App {
         // If any of them is missing, the function exits early. This ensures that
         // subsequent operations only proceed when the application is in a valid and
         // fully initialized state.
-        let (Some(gui_state), Some(renderer), Some(window), Some(last_render_time)) = (
-            self.gui_state.as_mut(),
+        #[cfg(feature = "gui")]
+        let Some(gui_state) = self.gui_state.as_mut() else {
+            return;
+        };
+        let (Some(renderer), Some(window), Some(last_render_time)) = (
             self.renderer.as_mut(),
             self.window.as_ref(),
             self.last_render_time.as_mut(),
@@ -846,34 +1926,142 @@ impl ApplicationHandler for App {
         };
 
         // Receive gui window event
-        if gui_state.on_window_event(window, &event).consumed {
+        // Track the cursor before egui sees the event, so the depth probe stops probing once
+        // the cursor leaves the window, even over a GUI widget.
+        // Releases are handled here as well, so a button released over a widget does not stay
+        // held for the camera. The input state records the releases of keys itself.
+        match event {
+            WindowEvent::CursorLeft { .. } => self.cursor_position = None,
+            WindowEvent::MouseInput {
+                state: winit::event::ElementState::Released,
+                button: winit::event::MouseButton::Right,
+                ..
+            } => {
+                self.camera_input.looking = false;
+                set_mouse_look(window, false);
+            }
+            // A release outside the window may never arrive, so losing focus ends looking too.
+            WindowEvent::Focused(false) if self.camera_input.looking => {
+                self.camera_input.looking = false;
+                set_mouse_look(window, false);
+            }
+            WindowEvent::Touch(touch) if touch.phase != winit::event::TouchPhase::Started => {
+                self.gestures.touch(
+                    touch.id,
+                    touch.phase,
+                    crate::math::vec2(touch.location.x as f32, touch.location.y as f32),
+                    Instant::now(),
+                );
+            }
+            _ => {}
+        }
+
+        // The GUI consumes pointer input over all of its panels, but the one showing the scene
+        // passes it on to the camera.
+        #[cfg(feature = "gui")]
+        let captured = gui_state.on_window_event(window, &event).consumed
+            && !(self.ui.scene_panel_hovered
+                && matches!(
+                    event,
+                    WindowEvent::MouseInput { .. }
+                        | WindowEvent::MouseWheel { .. }
+                        | WindowEvent::Touch(_)
+                ));
+        #[cfg(not(feature = "gui"))]
+        let captured = false;
+        self.input_state.handle_window_event(&event, captured);
+        if captured {
+            self.schedule_redraw();
             return;
         }
 
+        #[cfg(target_os = "windows")]
+        let resized = matches!(event, WindowEvent::Resized(_));
+
         // If the gui didn't consume the event, handle it
         match event {
-            WindowEvent::KeyboardInput {
-                event:
-                winit::event::KeyEvent {
-                    physical_key: winit::keyboard::PhysicalKey::Code(key_code),
-                    ..
-                },
-                ..
-            } => {
-                // Exit by pressing the escape key
+            WindowEvent::Resized(PhysicalSize { width, height }) => {
+                // Handles the `Resized` event, which is triggered when the window size changes.
+                // It stores the new dimensions in `self.last_size`, and marks the renderer's
+                // surface for reconfiguration before the next frame.
+                self.last_size = (width, height);
+                self.resize_pending = true;
 
-                if matches!(key_code, winit::keyboard::KeyCode::Escape) {
-                    event_loop.exit();
+                // A minimized window may report a size of zero instead of being occluded.
+                // Rendering is suspended until it has an area again, and resumes with the
+                // surface resized to the restored size.
+                let suspended = width == 0 || height == 0;
+                if suspended != self.rendering_suspended {
+                    log::info!(
+                        "Rendering {}",
+                        if suspended { "suspended" } else { "resumed" }
+                    );
+                    self.rendering_suspended = suspended;
+                    if !suspended {
+                        // The time spent minimized must not be simulated in a single frame.
+                        *last_render_time = Instant::now();
+                        self.input.cancel_redraw();
+                    }
                 }
+                self.safe_area = SafeAreaInsets::query(window);
             }
-            WindowEvent::Resized(PhysicalSize { width, height }) => {
-                // Handles the `Resized` event, which is triggered when the window size changes.
-                // It logs the new width and height dimensions, updates the renderer's surface
-                // to match the new size, and stores the new dimensions in `self.last_size`.
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                mut inner_size_writer,
+            } => {
+                // Moving the window to a monitor of another density keeps its logical size, so
+                // its physical size changes with the scale factor. The suggested size is
+                // requested explicitly, so the surface is resized to it before the next frame
+                // instead of presenting one frame stretched.
+                if self.scale_factor > 0.0 {
+                    let ratio = scale_factor / self.scale_factor;
+                    let (width, height) = self.last_size;
+                    let size = PhysicalSize::new(
+                        (f64::from(width) * ratio).round() as u32,
+                        (f64::from(height) * ratio).round() as u32,
+                    );
+                    if inner_size_writer.request_inner_size(size).is_ok() {
+                        self.last_size = (size.width, size.height);
+                        self.resize_pending = true;
+                    }
+                }
+                self.scale_factor = scale_factor;
+                log::info!("Scale factor changed to {scale_factor}");
 
-                log::info!("Resizing renderer surface to: ({width}, {height})");
-                renderer.resize(width, height);
-                self.last_size = (width, height);
+                // `egui` took the new native pixels per point from this event already, and
+                // lays out the next frame with it times the GUI scale. Lines and labels are
+                // sized in logical pixels as well, and the glyphs are rasterized again for
+                // the new density if needed.
+                renderer.set_scale_factor(scale_factor as f32);
+
+                // The insets are in points, so they change with the scale factor.
+                self.safe_area = SafeAreaInsets::query(window);
+            }
+            WindowEvent::Moved(_) => {
+                // Frames are paced to the refresh rate of the monitor the window is now on, and
+                // exclusive fullscreen offers that monitor's video modes.
+                self.redraw.set_refresh_rate(Self::refresh_rate(window));
+                self.ui.fullscreen.refresh_video_modes(window);
+            }
+            WindowEvent::Occluded(occluded) => {
+                // Stop rendering while nothing of the window can be seen, and resume with the
+                // next redraw once it is visible again. On the web, winit reports the page's
+                // visibility and whether the canvas is scrolled into view as occlusion.
+                log::info!("Window {}", if occluded { "occluded" } else { "visible" });
+                self.occluded = occluded;
+                if !occluded {
+                    // The time spent hidden must not be simulated in a single frame.
+                    *last_render_time = Instant::now();
+                    // A redraw requested just before the window was hidden may have been
+                    // dropped, so it is requested again.
+                    self.input.cancel_redraw();
+                }
+            }
+            WindowEvent::RedrawRequested
+                if self.occluded || self.rendering_suspended || self.surface_suspended =>
+            {
+                // Other events are still handled, but no frame is submitted while the window is
+                // hidden, has no area, or the app is suspended.
             }
             WindowEvent::CloseRequested => {
                 // Handles the `CloseRequested` event, which is emitted when the user attempts to close the window.
@@ -891,7 +2079,7 @@ impl ApplicationHandler for App {
                 // 1. Calculates the time elapsed since the last frame render.
                 // 2. Prepares the GUI input and processes it within the Egui context.
                 // 3. Conditionally shows various interactive GUI panels (like top, side, and bottom panels)
-                //    and a main window based on the `self.panels_visible` state.
+                //    and a main window based on the `self.ui.panels_visible` state.
                 // 4. Finalizes the Egui GUI pass, gets the drawing commands (paint jobs),
                 //    and updates any necessary platform GUI behavior.
                 // 5. Updates the renderer by passing in frame data, including the screen
@@ -903,6 +2091,69 @@ impl ApplicationHandler for App {
                 // and to calculate the delta time for smooth animations and updates within the application.
                 let now = Instant::now();
 
+                // While a binding is being edited, the next key or button pressed outside of
+                // the GUI becomes the binding instead of triggering anything.
+                if let Some(action) = self.ui.rebinding {
+                    if let Some(binding) = Binding::first_pressed(&self.input_state) {
+                        self.ui.action_map.bind(action, binding);
+                        self.ui.rebinding = None;
+                        Self::save_settings(
+                            &mut self.settings,
+                            &self.ui,
+                            self.config.transparent_window,
+                        );
+                    }
+                } else {
+                    // The actions triggered since the last frame, by keys and buttons the GUI
+                    // had no use for.
+                    for action in self.ui.action_map.triggered(&self.input_state) {
+                        match action {
+                            Action::Quit => event_loop.exit(),
+                            Action::TogglePanels => {
+                                self.ui.panels_visible = !self.ui.panels_visible;
+                            }
+                            Action::Screenshot => self.ui.screenshot_requested = true,
+                            Action::ToggleFrameStats => {
+                                self.ui.frame_stats_visible = !self.ui.frame_stats_visible;
+                            }
+                            Action::CaptureGpuFrame => self.ui.gpu_capture_requested = true,
+                            Action::ToggleFullscreen => {
+                                self.ui.fullscreen.enabled = !self.ui.fullscreen.enabled;
+                            }
+                            // The camera reads the movement actions while they are held.
+                            Action::CameraForward
+                            | Action::CameraBack
+                            | Action::CameraLeft
+                            | Action::CameraRight
+                            | Action::CameraDown
+                            | Action::CameraUp => {}
+                        }
+                    }
+
+                    // Alt+Enter toggles fullscreen as well, as in most games, whatever F11 is
+                    // bound to.
+                    let alt_held = self.input_state.is_key_held(KeyCode::AltLeft)
+                        || self.input_state.is_key_held(KeyCode::AltRight);
+                    if alt_held && self.input_state.was_key_pressed(KeyCode::Enter) {
+                        self.ui.fullscreen.enabled = !self.ui.fullscreen.enabled;
+                    }
+                }
+
+                // Entering or leaving fullscreen resizes the window once the platform has
+                // switched, and the `Resized` event reconfigures the surface as usual.
+                if self.fullscreen.update(&mut self.ui.fullscreen, window) {
+                    self.redraw.mark_dirty();
+                }
+
+                // The surface is reconfigured before its next texture is acquired, so the
+                // frame never renders to (or blocks on) a swapchain of the old size.
+                if self.resize_pending {
+                    let (width, height) = self.last_size;
+                    log::info!("Resizing renderer surface to: ({width}, {height})");
+                    renderer.resize(width, height);
+                    self.resize_pending = false;
+                }
+
                 // `delta_time` represents the time duration that has elapsed since the last frame was rendered.
                 //
                 // This value is calculated as the difference between the current time (`now`) and the timestamp
@@ -917,156 +2168,917 @@ impl ApplicationHandler for App {
                 let delta_time = now - *last_render_time;
                 *last_render_time = now;
 
-                // `gui_input` contains the input data received from the window,
-                // such as pointer events, keyboard events, and other UI-related inputs.
-                // This data is taken from the window and passed to the Egui context in order to
-                // handle and process user interactions for rendering and updating GUI components.
-                let gui_input = gui_state.take_egui_input(window);
-                gui_state.egui_ctx().begin_pass(gui_input);
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(address) = self.stream_address.take() {
+                    let streaming = match renderer.as_renderer_mut() {
+                        Some(renderer) => renderer.start_streaming(address),
+                        None => Err(crate::streaming::StreamError::Unsupported),
+                    };
+                    match streaming {
+                        Ok(address) => self.toasts.info(format!("Streaming at http://{address}/")),
+                        Err(error) => self.toasts.error(error.to_string()),
+                    }
+                }
 
-                // The `title` variable contains the title of the application window.
-                //
-                // This title is determined by compile-time configurations, which allow conditional compilation
-                // for different platforms and features. For example:
-                //
-                // - When not targeting `wasm32`, the title defaults to "Rust/Wgpu".
-                // - When the `webgpu` feature is enabled, the title is set to "Rust/Wgpu/Webgpu".
-                // - When the `webgl` feature is enabled, the title is set to "Rust/Wgpu/Webgl".
+                // The model and the texture are read through the asset loader, so either may be
+                // a file, an entry of a mounted pack, or a URL. On the web they finish loading
+                // in a later frame. Elsewhere the loader is synchronous, so they load right here,
+                // stalling this one frame.
                 //
-                // This ensures the application title accurately reflects the platform or feature in use.
+                // The configuration's asset pack is mounted first, so both are read from it if
+                // it holds them.
+                if !std::mem::replace(&mut self.pack_requested, true) {
+                    if let Some(source) = self.config.asset_pack.clone() {
+                        self.pack_load = Some(PendingLoad::spawn(async move {
+                            let mut loader = AssetLoader::new();
+                            let mounted = loader.mount_pack(&source).await;
+                            (source, loader, mounted)
+                        }));
+                    }
+                }
+                if let Some((source, loader, mounted)) =
+                    self.pack_load.as_mut().and_then(PendingLoad::take)
+                {
+                    self.pack_load = None;
+                    match mounted {
+                        Ok(()) => self.assets = std::rc::Rc::new(loader),
+                        Err(error) => self.toasts.error(format!(
+                            "Failed to mount the asset pack '{source}': {error}"
+                        )),
+                    }
+                }
+                let assets_ready = self.pack_load.is_none();
+
+                #[cfg(feature = "import-gltf")]
+                if let Some(source) = self.model_source.take_if(|_| assets_ready) {
+                    let assets = self.assets.clone();
+                    self.model_load = Some(PendingLoad::spawn(async move {
+                        let loaded = ModelLoader::new(&assets).load(&source).await;
+                        (source, loaded)
+                    }));
+                }
+                #[cfg(feature = "import-gltf")]
+                if let Some((source, loaded)) = self.model_load.as_mut().and_then(PendingLoad::take)
+                {
+                    self.model_load = None;
+                    match loaded {
+                        Ok(model) => {
+                            let model = model.centered();
+                            if let Some(renderer) = renderer.as_renderer_mut() {
+                                renderer.set_scene_meshes(&model.meshes);
+                                renderer.set_scene_materials(&model.materials);
+                                #[cfg(feature = "gui")]
+                                {
+                                    self.ui.materials =
+                                        MaterialLibrary::new(model.materials.clone());
+                                }
+                                self.ui.frame_scene_requested = true;
+                                self.toasts.info(format!(
+                                    "Loaded '{source}' with {} triangles",
+                                    model.triangle_count()
+                                ));
+                            } else {
+                                self.toasts.warning(format!(
+                                    "Cannot show '{source}': model import is unsupported on this \
+                                     backend"
+                                ));
+                            }
+                        }
+                        Err(error) => self
+                            .toasts
+                            .error(format!("Failed to load '{source}': {error}")),
+                    }
+                }
+
+                if let Some(source) = self.texture_source.take_if(|_| assets_ready) {
+                    let assets = self.assets.clone();
+                    self.texture_load = Some(PendingLoad::spawn(async move {
+                        let loaded = assets.load(&source).await;
+                        (source, loaded)
+                    }));
+                }
+                if let Some((source, loaded)) =
+                    self.texture_load.as_mut().and_then(PendingLoad::take)
+                {
+                    self.texture_load = None;
+                    let loaded =
+                        loaded
+                            .map_err(|error| error.to_string())
+                            .and_then(|bytes| match renderer.as_renderer_mut() {
+                                Some(renderer) => renderer
+                                    .load_scene_texture(&bytes)
+                                    .map(|()| true)
+                                    .map_err(|error| error.to_string()),
+                                None => Ok(false),
+                            });
+                    match loaded {
+                        Ok(true) => self.toasts.info(format!("Loaded the texture '{source}'")),
+                        Ok(false) => self.toasts.warning(format!(
+                            "Cannot show the texture '{source}': textures are unsupported on \
+                             this backend"
+                        )),
+                        Err(error) => self
+                            .toasts
+                            .error(format!("Failed to load the texture '{source}': {error}")),
+                    }
+                }
+
+                // A screenshot requested with F12 or the "Screenshot" button captures this frame.
+                // A GPU capture requested with F9 records this frame in the graphics debugger.
+                let screenshot_requested = std::mem::take(&mut self.ui.screenshot_requested);
+                let gpu_capture_requested = std::mem::take(&mut self.ui.gpu_capture_requested);
+                if let Some(renderer) = renderer.as_renderer_mut() {
+                    if screenshot_requested {
+                        renderer.capture_frame();
+                    }
+                    if gpu_capture_requested {
+                        renderer.trigger_capture();
+                    }
+                    match renderer.take_frame_capture() {
+                        Some(Ok(location)) => {
+                            self.toasts.info(format!("Saved screenshot to {location}"));
+                        }
+                        Some(Err(error)) => self.toasts.error(error.to_string()),
+                        None => {}
+                    }
+                }
+
+                // The GUI pass is begun here and ended after the overlays below, once the
+                // renderer has been updated.
+                #[cfg(feature = "gui")]
+                let gui_scope = self.cpu_profiler.scope("GUI Pass");
+                #[cfg(feature = "gui")]
+                {
+                    // The thumbnails of the Inspector and Assets panels are only rendered
+                    // while the panels or the Widget Gallery are shown, and then only when
+                    // their content changed.
+                    let previews_visible = self.ui.panels_visible || self.ui.gallery.visible;
+                    if let (true, Some(renderer)) = (previews_visible, renderer.as_renderer_mut()) {
+                        renderer.update_previews(
+                            self.ui.materials.selected(),
+                            self.ui.turntable_mesh,
+                            delta_time.as_secs_f32(),
+                        );
+                        self.ui.material_preview = Some(renderer.material_preview());
+                        self.ui.mesh_previews = renderer.mesh_previews();
+                    }
+
+                    // `gui_input` contains the input data received from the window,
+                    // such as pointer events, keyboard events, and other UI-related inputs.
+                    // This data is taken from the window and passed to the Egui context in order to
+                    // handle and process user interactions for rendering and updating GUI components.
+                    let gui_input = gui_state.take_egui_input(window);
+                    gui_state.egui_ctx().begin_pass(gui_input);
+
+                    // The `title` variable contains the title of the application window.
+                    //
+                    // This title is determined by compile-time configurations, which allow conditional compilation
+                    // for different platforms and features. For example:
+                    //
+                    // - When not targeting `wasm32`, the title defaults to "Rust/Wgpu".
+                    // - When the `webgpu` feature is enabled, the title is set to "Rust/Wgpu/Webgpu".
+                    // - When the `webgl` feature is enabled, the title is set to "Rust/Wgpu/Webgl".
+                    //
+                    // This ensures the application title accurately reflects the platform or feature in use.
+                    #[cfg(not(target_arch = "wasm32"))]
+                    let title = "Rust/Wgpu";
+
+                    #[cfg(feature = "webgpu")]
+                    let title = "Rust/Wgpu/Webgpu";
+
+                    #[cfg(feature = "webgl")]
+                    let title = "Rust/Wgpu/Webgl";
+
+                    // Builds the settings window and, if enabled, the top, left, right, and bottom
+                    // panels. The panel-building code lives in the `ui` module so it can be tested
+                    // headlessly, independent of the window and renderer.
+                    // The insets are in native points, while the GUI lays out in zoomed points.
+                    let safe_area = if self.ui.simulate_notch {
+                        SafeAreaInsets::SIMULATED_NOTCH
+                    } else {
+                        self.safe_area
+                    };
+                    self.ui.safe_area = safe_area.scaled(1.0 / self.ui.gui_scale.get());
+                    // A GUI capture requested in the previous frame captures this one, so the menu
+                    // item requesting it is not part of the capture.
+                    if std::mem::take(&mut self.ui.ui_capture_requested) {
+                        if let Some(renderer) = renderer.as_renderer_mut() {
+                            renderer.capture_ui();
+                        }
+                    }
+                    // The custom title bar is added first, so it spans the window above the panels.
+                    // A borderless window has none.
+                    let titlebar = self.config.custom_titlebar && !self.config.borderless_window;
+                    if titlebar && cfg!(not(target_arch = "wasm32")) {
+                        let window_title = window.title();
+                        let maximized = window.is_maximized();
+                        let action = crate::ui::show_titlebar(
+                            gui_state.egui_ctx(),
+                            &window_title,
+                            maximized,
+                        );
+                        if let Some(action) = action {
+                            Self::apply_titlebar_action(window, event_loop, action);
+                        }
+                    }
+                    let wgpu_renderer = renderer.as_renderer();
+                    self.ui.tasks = wgpu_renderer
+                        .map(Renderer::task_progress)
+                        .unwrap_or_default();
+                    self.ui.selected_node = wgpu_renderer.and_then(Renderer::selected_node_name);
+                    self.ui.scene_texture = wgpu_renderer.and_then(Renderer::scene_panel_texture);
+                    let gui_scale = self.ui.gui_scale;
+                    let clear_color = self.ui.clear_color;
+                    let action_map = self.ui.action_map.clone();
+                    crate::ui::show(gui_state.egui_ctx(), title, &mut self.ui);
+                    // The scene panel is laid out in points, while the scene's texture is sized
+                    // in physical pixels.
+                    let pixels_per_point = gui_state.egui_ctx().pixels_per_point();
+                    if let Some(renderer) = renderer.as_renderer_mut() {
+                        renderer.set_scene_panel(self.ui.scene_panel_rect.map(|rect| {
+                            let rect = rect * pixels_per_point;
+                            crate::viewport::Viewport {
+                                x: rect.min.x.round().max(0.0) as u32,
+                                y: rect.min.y.round().max(0.0) as u32,
+                                width: rect.width().round() as u32,
+                                height: rect.height().round() as u32,
+                            }
+                        }));
+                    }
+                    if self.ui.gui_scale != gui_scale
+                        || self.ui.clear_color != clear_color
+                        || self.ui.action_map != action_map
+                    {
+                        Self::save_settings(
+                            &mut self.settings,
+                            &self.ui,
+                            self.config.transparent_window,
+                        );
+                    }
+                }
+
+                // Probe the pixel under the cursor while the depth probe is enabled, and show
+                // the latest readback next to the cursor. The cursor is tracked in physical
+                // pixels, while egui lays out in points.
+                let probe_cursor = self.cursor_position.filter(|_| self.ui.depth_probe_enabled);
+                let [r, g, b, a] = self.ui.clear_color.map(f64::from);
+                renderer.set_clear_color(wgpu::Color { r, g, b, a });
+                renderer.set_layer_masks(&self.ui.layers);
+                // Only the wgpu renderer draws egui viewports into windows of their own.
+                #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+                {
+                    self.ui.viewports_supported = renderer.as_renderer().is_some();
+                }
+                let shader_source = self.ui.shader_editor.take_apply_request();
+                if let Some(renderer) = renderer.as_renderer_mut() {
+                    renderer.set_depth_probe_cursor(probe_cursor);
+                    renderer.set_grid_visible(self.ui.grid_visible);
+                    renderer.set_world_grid_visible(self.ui.world_grid_visible);
+                    renderer.set_node_labels_visible(self.ui.node_labels_visible);
+                    renderer.set_debug_view(self.ui.debug_view);
+                    renderer.set_rasterization(self.ui.rasterization);
+                    renderer.set_present_mode(self.ui.present_mode);
+                    self.ui.present_mode = renderer.present_mode();
+                    self.ui.present_modes = renderer.present_modes();
+                    renderer.set_frame_latency(self.ui.frame_latency);
+                    self.ui.frame_latency = renderer.frame_latency();
+                    #[cfg(feature = "scene3d")]
+                    {
+                        renderer.set_crowd_demo_enabled(self.ui.crowd_demo_enabled);
+                        renderer.set_particle_demo_enabled(self.ui.particle_demo_enabled);
+                    }
+                    renderer.set_grid_demo(self.ui.grid_demo);
+                }
+                if let Some(source) = shader_source {
+                    match renderer.as_renderer_mut() {
+                        Some(renderer) => renderer.set_scene_shader(&source),
+                        None => self
+                            .ui
+                            .shader_editor
+                            .set_result(Err(crate::shader_editor::ShaderError::Unsupported)),
+                    }
+                }
+                // The browser validates the shader in a later frame, so its result is polled
+                // every frame until it arrives.
+                if let Some(result) = renderer
+                    .as_renderer_mut()
+                    .and_then(Renderer::take_scene_shader_result)
+                {
+                    if result.is_ok() {
+                        self.toasts.info("Applied the scene shader");
+                    }
+                    self.ui.shader_editor.set_result(result);
+                }
+
+                // Scale the quality to hold the target frame rate, judged by the GPU time of
+                // the frames where the adapter measures it, and by their interval otherwise.
+                let wgpu_renderer = renderer.as_renderer();
+                let frame_time = wgpu_renderer
+                    .and_then(Renderer::gpu_frame_time)
+                    .unwrap_or(delta_time);
+                self.quality_scaler
+                    .update(&self.ui.adaptive_quality, frame_time);
+                self.ui.quality_levels = *self.quality_scaler.levels();
+                if let Some(renderer) = wgpu_renderer {
+                    self.ui.draw_stats = renderer.draw_stats();
+                    self.ui.frame_stats = renderer.frame_stats();
+                }
+
+                // The benchmark measures the frame that just finished, then grows the workload
+                // when the next stage begins. Once finished, the application exits.
                 #[cfg(not(target_arch = "wasm32"))]
-                let title = "Rust/Wgpu";
-
-                #[cfg(feature = "webgpu")]
-                let title = "Rust/Wgpu/Webgpu";
-
-                #[cfg(feature = "webgl")]
-                let title = "Rust/Wgpu/Webgl";
-
-                if self.panels_visible {
-                    // Displays the top, left, right, and bottom panels if `self.panels_visible` is true.
-                    // Each panel contains interactive GUI elements such as headings and buttons,
-                    // which can trigger specific actions when clicked (e.g., logging button clicks).
-                    // This block defines the layout and functionality for these GUI panels.
-
-                    // Creates a top panel using `egui::TopBottomPanel` with the identifier "top" and renders its content.
-                    // The `show` method is used to build and display the GUI elements defined within the closure (`|ui|`).
-                    // Inside the closure, the panel is populated with horizontal navigation options labeled "File" and "Edit".
-                    egui::TopBottomPanel::top("top").show(gui_state.egui_ctx(), |ui| {
-                        ui.horizontal(|ui| {
-                            ui.label("File");
-                            ui.label("Edit");
-                        });
-                    });
+                if let Some(run) = &mut self.benchmark {
+                    let mut renderer = renderer.as_renderer_mut();
+                    if let Some(instances) = run.frame(delta_time, &self.ui.frame_stats) {
+                        if let Some(renderer) = renderer.as_deref_mut() {
+                            renderer.set_scene_instances(&instances);
+                        }
+                    }
+                    if run.is_finished() {
+                        let report = run.report(renderer.as_deref().map(Renderer::gpu_info));
+                        let output = &run.benchmark().output;
+                        match report.write(output) {
+                            Ok(()) => {
+                                println!("{}", report.to_markdown());
+                                println!("Wrote the benchmark report to '{}'", output.display());
+                            }
+                            Err(error) => log::error!("Failed to write the benchmark: {error}"),
+                        }
+                        self.benchmark = None;
+                        event_loop.exit();
+                    }
+                }
+                self.cpu_profiler.set_enabled(self.ui.cpu_profiler_enabled);
+                self.ui.cpu_frame = self
+                    .cpu_profiler
+                    .latest_frame()
+                    .filter(|_| self.ui.cpu_profiler_visible);
+                #[cfg(not(target_arch = "wasm32"))]
+                if std::mem::take(&mut self.ui.cpu_trace_export_requested) {
+                    match self.cpu_profiler.save_chrome_trace() {
+                        Ok(path) => self.toasts.info(format!("Saved CPU trace to {path}")),
+                        Err(error) => self
+                            .toasts
+                            .error(format!("Failed to save the CPU trace: {error}")),
+                    }
+                }
+                let gpu_errors_clear_requested =
+                    std::mem::take(&mut self.ui.gpu_errors_clear_requested);
+                if let Some(renderer) = renderer.as_renderer_mut() {
+                    if gpu_errors_clear_requested {
+                        renderer.clear_gpu_errors();
+                    }
+                    self.ui.gpu_errors = renderer.gpu_errors();
+                    self.ui.render_modes = renderer.render_modes();
+                    renderer.set_quality_levels(self.quality_scaler.levels());
+                }
+                self.ui.gallery.record_frame_time(delta_time);
 
-                    // Creates a left-side panel using `egui::SidePanel` with the identifier "left" and renders its content.
-                    // The `show` method defines the layout and interactive elements inside the panel through a closure (`|ui|`).
-                    // Within this closure, a heading labeled "Scene Explorer" is displayed.
-                    // Additionally, a button labeled "Click me!" is rendered, and when clicked, it logs a message using the `log` crate.
-                    egui::SidePanel::left("left").show(gui_state.egui_ctx(), |ui| {
-                        ui.heading("Scene Explorer");
-                        if ui.button("Click me!").clicked() {
-                            log::info!("Button clicked!");
+                // Frame the content whenever it changes, such as when the crowd demo is loaded.
+                // The first bounds only clamp the camera, keeping the default view at startup.
+                let field_of_view = self.ui.projection.sanitized().field_of_view;
+                if let Some(bounds) = renderer.scene_bounds() {
+                    match self.camera.bounds() {
+                        None => self.camera.set_bounds(Some(bounds)),
+                        Some(previous) if previous != bounds => {
+                            self.camera.frame(&bounds, field_of_view);
                         }
-                    });
+                        Some(_) => {}
+                    }
+                }
+                if std::mem::take(&mut self.ui.frame_scene_requested) {
+                    if let Some(bounds) = self.camera.bounds() {
+                        self.camera.frame(&bounds, field_of_view);
+                    }
+                }
 
-                    // Creates a right-side panel using `egui::SidePanel` with the identifier "right" and renders its content.
-                    // The `show` method is used to define the panel's layout and interactive elements within a closure (`|ui|`).
-                    // Inside this closure, a heading labeled "Inspector" is displayed.
-                    // Additionally, a button labeled "Click me!" is rendered, and when clicked, a message is logged using the `log` crate.
-                    egui::SidePanel::right("right").show(gui_state.egui_ctx(), |ui| {
-                        ui.heading("Inspector");
-                        if ui.button("Click me!").clicked() {
-                            log::info!("Button clicked!");
+                // Share the camera through `camera.json`, in the interchange format of DCC
+                // tools. The exported clip planes are the ones in use, fitted or not.
+                if std::mem::take(&mut self.ui.camera_export_requested) {
+                    let bounds = self.camera.bounds();
+                    let projection = self
+                        .ui
+                        .projection
+                        .fitted(&self.camera.eye(), bounds.as_ref());
+                    let aspect_ratio = renderer.viewport().map_or(1.0, |viewport| {
+                        viewport.width as f32 / viewport.height as f32
+                    });
+                    let exchange = CameraExchange::new(
+                        self.camera.eye(),
+                        self.camera.forward(),
+                        self.camera.focus_distance(),
+                        &projection,
+                        aspect_ratio,
+                    );
+                    match exchange.export() {
+                        Ok(location) => self.toasts.info(format!("Exported camera to {location}")),
+                        Err(error) => self.toasts.error(error.to_string()),
+                    }
+                }
+                if std::mem::take(&mut self.ui.camera_import_requested) {
+                    match CameraExchange::import() {
+                        Ok(exchange) => {
+                            let (eye, forward) = exchange.pose();
+                            self.camera.look_from(eye, forward, exchange.focus_distance);
+                            self.ui.projection = exchange.projection();
+                            self.ui.aspect_lock = exchange.aspect_lock();
+                            self.toasts.info("Imported camera");
                         }
+                        Err(error) => self.toasts.error(error.to_string()),
+                    }
+                }
+
+                // Move the camera with the input gathered since the last frame.
+                let (camera_eye, camera_forward) = (self.camera.eye(), self.camera.forward());
+                self.camera_input
+                    .read_actions(&self.ui.action_map, &self.input_state);
+                self.camera.set_mode(self.ui.camera_mode);
+                self.camera.update(
+                    &self.camera_input,
+                    &self.ui.camera_settings,
+                    delta_time.as_secs_f32(),
+                );
+                self.camera_input.end_frame();
+                self.input_state.end_frame();
+
+                // The dolly zoom moves the camera along its view direction after the input,
+                // so the camera can still be orbited, and animates the field of view.
+                let pose = if self.ui.dolly_zoom.enabled {
+                    let field_of_view = self.ui.projection.field_of_view;
+                    let animation = self.dolly_zoom.get_or_insert_with(|| {
+                        DollyZoomAnimation::start(&self.camera, field_of_view)
                     });
+                    Some(animation.advance(
+                        &self.ui.dolly_zoom,
+                        self.camera.forward(),
+                        delta_time.as_secs_f32(),
+                    ))
+                } else {
+                    self.dolly_zoom
+                        .take()
+                        .map(|animation| animation.stop(self.camera.forward()))
+                };
+                if let Some(pose) = pose {
+                    self.camera
+                        .look_from(pose.eye, self.camera.forward(), pose.distance);
+                    self.ui.projection.field_of_view = pose.field_of_view;
+                }
 
-                    // Creates a bottom panel using `egui::TopBottomPanel` with the identifier "bottom" and renders its content.
-                    // The `show` method is used to define the layout and interactive elements within the panel through a closure (`|ui|`).
-                    // Inside this closure, a heading labeled "Assets" is displayed.
-                    // Additionally, a button labeled "Click me!" is rendered, and when clicked, a message is logged using the `log` crate.
-                    egui::TopBottomPanel::bottom("bottom").show(gui_state.egui_ctx(), |ui| {
-                        ui.heading("Assets");
-                        if ui.button("Click me!").clicked() {
-                            log::info!("Button clicked!");
+                // The flythrough records the camera as moved, or places it on the path while
+                // playing or scrubbing, overriding the input.
+                let cleared = self.ui.flythrough.clear_requested;
+                if let Some(pose) = self.flythrough.update(
+                    &mut self.ui.flythrough,
+                    &self.camera,
+                    delta_time.as_secs_f32(),
+                ) {
+                    self.camera
+                        .look_from(pose.eye, pose.forward, pose.focus_distance);
+                }
+                if cleared {
+                    self.camera_path = CurveEditor::default();
+                }
+                Self::sync_camera_path(&mut self.flythrough.path, &mut self.camera_path);
+                if std::mem::take(&mut self.ui.flythrough.export_requested) {
+                    match self.flythrough.path.export() {
+                        Ok(location) => self
+                            .toasts
+                            .info(format!("Exported camera path to {location}")),
+                        Err(error) => self.toasts.error(error.to_string()),
+                    }
+                }
+                if std::mem::take(&mut self.ui.flythrough.import_requested) {
+                    match CameraPath::import() {
+                        Ok(path) => {
+                            self.camera_path = CurveEditor::new(crate::curves::Curve::CatmullRom {
+                                points: path.positions(),
+                                closed: false,
+                            });
+                            self.flythrough.path = path;
+                            self.ui.flythrough.time = 0.0;
+                            self.toasts.info("Imported camera path");
                         }
-                    });
+                        Err(error) => self.toasts.error(error.to_string()),
+                    }
                 }
 
-                // Renders a dynamic, interactive window using `egui::Window`.
-                // This window displays a checkbox that toggles the visibility of GUI panels
-                // (controlled by `self.panels_visible`). The title of the window is determined
-                // by compile-time settings, adjusting based on the platform or features.
-                egui::Window::new(title).show(gui_state.egui_ctx(), |ui| {
-                    ui.checkbox(&mut self.panels_visible, "Show Panels");
-                });
+                // The next frame is needed while the camera glides towards its goal or is
+                // moved with held keys, while the dolly zoom or the flythrough runs, and while
+                // tasks load.
+                let camera_moved = crate::math::distance(&self.camera.eye(), &camera_eye)
+                    > Self::CAMERA_SETTLED_DISTANCE
+                    || crate::math::distance(&self.camera.forward(), &camera_forward)
+                        > Self::CAMERA_SETTLED_DISTANCE;
+                if camera_moved
+                    || self.camera_input.movement() != crate::math::zeros()
+                    || self.ui.dolly_zoom.enabled
+                    || self.ui.flythrough.playing
+                    || self.ui.flythrough.recording
+                    || renderer
+                        .as_renderer()
+                        .is_some_and(|renderer| !renderer.task_progress().is_empty())
+                {
+                    self.redraw.mark_dirty();
+                }
+                renderer.set_camera(self.camera.view(), self.camera.eye());
+                renderer.set_projection(self.ui.projection);
+                renderer.set_aspect_lock(self.ui.aspect_lock);
+                self.gizmo.mode = self.ui.gizmo_mode;
+                self.gizmo.snapping = self.ui.gizmo_snapping;
+                if let Some(renderer) = renderer.as_renderer_mut() {
+                    renderer.set_transparent(self.config.transparent_window);
+                    renderer.set_determinism(self.determinism_seed);
+                    renderer.set_environment(&self.ui.environment);
+                    renderer.set_scene_environment(self.ui.scene_environment);
+                    renderer.set_light(&self.ui.light);
+                    renderer.set_post_effects(&self.ui.post_effects);
+                    #[cfg(feature = "gui")]
+                    renderer.set_scene_materials(self.ui.materials.materials());
 
-                // This let statement creates an interactive GUI window using `egui::Window`.
-                //
-                // - The `new` method initializes the window with a title, which is derived
-                //   from the `title` variable determined earlier based on compile-time configurations.
-                // - The `show` method renders the window within the Egui context (`gui_state.egui_ctx()`),
-                //   providing a closure (`|ui|`) that specifies the GUI elements and interactions within the window.
-                // - In this particular case, a checkbox is added to toggle the visibility of GUI panels
-                //   by modifying the `self.panels_visible` property.
-                //
-                // This mechanism allows for dynamic and interactive user interfaces within the 3D application.
-                let egui_winit::egui::FullOutput {
-                    textures_delta,
-                    shapes,
-                    pixels_per_point,
-                    platform_output,
-                    ..
-                } = gui_state.egui_ctx().end_pass();
-
-                gui_state.handle_platform_output(window, platform_output);
-
-                // A collection of painting jobs generated by the Egui framework
-                // after tessellating the shapes defined in the GUI context.
-                //
-                // The `paint_jobs` variable contains instructions for rendering
-                // GUI shapes and elements. These jobs are produced by the tessellation
-                // process, which converts the GUI's high-level visual elements (e.g.,
-                // labels, buttons, panels) into a set of low-level graphical primitives
-                // (triangles and vertices) to be rendered by the GPU.
-                //
-                // The jobs refer to specific textures, vertex coordinates, and
-                // other properties required for displaying the GUI accurately
-                // on the screen. It also accounts for proper scaling through the
-                // `pixels_per_point` parameter.
-                //
-                // These painting jobs are later passed to the renderer for processing
-                // and drawing in the final frame.
-                let paint_jobs = gui_state.egui_ctx().tessellate(shapes, pixels_per_point);
+                    let view_projection = renderer.view_projection();
+                    let viewport = renderer.viewport();
+                    // The camera path is an editor helper, hidden along with the others.
+                    let helpers_visible = self.ui.layers.shows(self.ui.layers.helpers);
+                    if self.ui.camera_path_visible && helpers_visible {
+                        self.camera_path.draw(
+                            renderer.debug_lines(),
+                            &view_projection,
+                            viewport.size(),
+                        );
+                    }
+                    if let (true, Some(target)) = (helpers_visible, renderer.gizmo_target()) {
+                        self.gizmo.draw(
+                            renderer.debug_lines(),
+                            &target,
+                            &view_projection,
+                            viewport.size(),
+                        );
+                    }
 
-                // Represents the display parameters needed for rendering a graphical frame on the screen.
-                //
-                // The `screen_descriptor` variable contains information about the size of the rendering surface
-                // and the scaling factor to account for high-DPI displays. This information is essential for
-                // ensuring that graphical elements (both 3D content and GUI) are rendered at the correct size
-                // and position on the screen.
-                //
-                // # Fields
-                //
-                // - `size_in_pixels`: A 2-element array representing the width and height of the rendering surface
-                //   in physical pixels. This is derived from the `self.last_size` property, which holds the latest
-                //   dimensions of the window.
-                // - `pixels_per_point`: A floating-point value representing the scaling factor to account for
-                //   high-DPI displays (e.g., retina displays). This is fetched using the `scale_factor` method
-                //   of the `window` object.
-                let screen_descriptor = {
-                    let (width, height) = self.last_size;
-                    egui_wgpu::ScreenDescriptor {
-                        size_in_pixels: [width, height],
-                        pixels_per_point: window.scale_factor() as f32,
+                    // Surface any recoverable configuration problems the renderer detected
+                    // (for example unsupported texture usages that required a fallback) as
+                    // toasts. They are drawn above the rest of the GUI below.
+                    for warning in renderer.take_validation_warnings() {
+                        self.toasts.warning(warning.to_string());
+                    }
+                }
+
+                // Draws the overlays over the scene's viewport and ends the GUI pass begun
+                // above, producing what the renderer draws of the GUI.
+                #[cfg(feature = "gui")]
+                let (screen_descriptor, paint_jobs, textures_delta) = {
+                    if let Some(viewport) = renderer.viewport() {
+                        crate::ui::show_letterbox(gui_state.egui_ctx(), &viewport, self.last_size);
+                        let world_units_per_pixel = crate::rulers::world_units_per_pixel(
+                            field_of_view.to_radians(),
+                            self.camera.focus_distance(),
+                            viewport.height,
+                        );
+                        crate::ui::show_rulers(
+                            gui_state.egui_ctx(),
+                            &mut self.ui,
+                            &viewport,
+                            world_units_per_pixel,
+                        );
+                        if self.ui.draw_stats_visible {
+                            crate::ui::show_draw_stats(
+                                gui_state.egui_ctx(),
+                                &viewport,
+                                &self.ui.draw_stats,
+                            );
+                        }
+                    }
+                    let sample = renderer
+                        .as_renderer()
+                        .and_then(Renderer::depth_probe_sample);
+                    if let (Some((x, y)), Some(sample)) = (probe_cursor, sample) {
+                        let pixels_per_point = gui_state.egui_ctx().pixels_per_point();
+                        crate::ui::show_depth_probe(
+                            gui_state.egui_ctx(),
+                            egui_winit::egui::pos2(x as f32, y as f32) / pixels_per_point,
+                            &sample,
+                        );
+                    }
+
+                    match renderer
+                        .as_renderer_mut()
+                        .and_then(Renderer::take_ui_capture)
+                    {
+                        Some(Ok(location)) => {
+                            self.toasts.info(format!("Saved GUI capture to {location}"));
+                        }
+                        Some(Err(error)) => self.toasts.error(error.to_string()),
+                        None => {}
+                    }
+                    self.toasts.show(gui_state.egui_ctx());
+
+                    // Ends the Egui pass started above, collecting the texture updates, shapes, and
+                    // platform output (such as cursor changes) produced by the GUI built this frame.
+                    let egui_winit::egui::FullOutput {
+                        textures_delta,
+                        shapes,
+                        pixels_per_point,
+                        platform_output,
+                        viewport_output,
+                        ..
+                    } = gui_state.egui_ctx().end_pass();
+
+                    // Animations and fading toasts ask for a repaint, right away or after a
+                    // delay, which the redraw policy may otherwise not render.
+                    if let Some(viewport) = viewport_output.get(&egui_winit::egui::ViewportId::ROOT)
+                    {
+                        self.redraw.repaint_after(now, viewport.repaint_delay);
+                    }
+
+                    gui_state.handle_platform_output(window, platform_output);
+
+                    // Opens the windows of the viewports shown for the first time this pass, and
+                    // closes those of the viewports no longer shown.
+                    #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+                    if let Some(renderer) = renderer.as_renderer_mut() {
+                        self.viewports.update(
+                            event_loop,
+                            gui_state.egui_ctx(),
+                            renderer,
+                            &viewport_output,
+                        );
                     }
+
+                    // A collection of painting jobs generated by the Egui framework
+                    // after tessellating the shapes defined in the GUI context.
+                    //
+                    // The `paint_jobs` variable contains instructions for rendering
+                    // GUI shapes and elements. These jobs are produced by the tessellation
+                    // process, which converts the GUI's high-level visual elements (e.g.,
+                    // labels, buttons, panels) into a set of low-level graphical primitives
+                    // (triangles and vertices) to be rendered by the GPU.
+                    //
+                    // The jobs refer to specific textures, vertex coordinates, and
+                    // other properties required for displaying the GUI accurately
+                    // on the screen. It also accounts for proper scaling through the
+                    // `pixels_per_point` parameter.
+                    //
+                    // These painting jobs are later passed to the renderer for processing
+                    // and drawing in the final frame.
+                    let paint_jobs = gui_state.egui_ctx().tessellate(shapes, pixels_per_point);
+                    drop(gui_scope);
+
+                    // Represents the display parameters needed for rendering a graphical frame on the screen.
+                    //
+                    // The `screen_descriptor` variable contains information about the size of the rendering surface
+                    // and the scaling factor to account for high-DPI displays. This information is essential for
+                    // ensuring that graphical elements (both 3D content and GUI) are rendered at the correct size
+                    // and position on the screen.
+                    //
+                    // # Fields
+                    //
+                    // - `size_in_pixels`: A 2-element array representing the width and height of the rendering surface
+                    //   in physical pixels. This is derived from the `self.last_size` property, which holds the latest
+                    //   dimensions of the window.
+                    // - `pixels_per_point`: A floating-point value representing the scaling factor to account for
+                    //   high-DPI displays (e.g., retina displays) and the GUI scale. This is the value egui laid
+                    //   out and tessellated the frame with, the window's `scale_factor` times the GUI scale.
+                    let screen_descriptor = {
+                        let (width, height) = self.last_size;
+                        egui_wgpu::ScreenDescriptor {
+                            size_in_pixels: [width, height],
+                            pixels_per_point,
+                        }
+                    };
+
+                    (screen_descriptor, paint_jobs, textures_delta)
                 };
 
-                renderer.render_frame(screen_descriptor, paint_jobs, textures_delta, delta_time);
+                // Advance the scene's animation by the whole ticks that passed, and draw it between
+                // the last two by the time left over.
+                let ticks = self.timestep.advance(delta_time);
+                renderer.tick_scene(ticks, self.timestep.alpha());
+
+                // Render the frame, handing any failure to the error policy. Depending on the
+                // chosen action the frame is skipped (`Retry`/`Ignore`), the surface is
+                // reconfigured before the next frame (`Fallback`), or the application exits.
+                let render_scope = self.cpu_profiler.scope("Render Frame");
+                #[cfg(feature = "gui")]
+                let result =
+                    renderer.render(screen_descriptor, paint_jobs, textures_delta, delta_time);
+                #[cfg(not(feature = "gui"))]
+                let result = renderer.render(delta_time);
+                drop(render_scope);
+                self.redraw.end_frame(Instant::now());
+                match result {
+                    Ok(()) => {
+                        self.frame_failures = 0;
+                        let input_arrived = self.input_arrived.take();
+                        if let (Some(arrived), Some(renderer)) =
+                            (input_arrived, renderer.as_renderer_mut())
+                        {
+                            renderer.record_input_latency(arrived.elapsed());
+                        }
+                    }
+                    Err(error) => {
+                        log::warn!("Failed to render frame: {error}");
+                        let action = self.error_policy.decide(
+                            &error,
+                            ErrorPhase::Frame,
+                            self.frame_failures,
+                        );
+                        self.frame_failures += 1;
+                        match action {
+                            ErrorAction::Retry | ErrorAction::Ignore => {}
+                            #[cfg(all(feature = "software", not(target_arch = "wasm32")))]
+                            ErrorAction::Software => {}
+                            // The surface is outdated or no longer supports its configuration.
+                            // Resizing it to the unchanged size does nothing, so it is configured
+                            // again with freshly queried capabilities, selecting the nearest
+                            // supported present mode if needed.
+                            ErrorAction::Fallback => {
+                                if let Err(error) = renderer.reconfigure_surface() {
+                                    log::error!("Failed to reconfigure the surface: {error}");
+                                }
+                            }
+                            ErrorAction::Exit => {
+                                log::error!("Exiting after unrecoverable render error: {error}");
+                                event_loop.exit();
+                            }
+                        }
+                    }
+                }
+            }
+            WindowEvent::MouseInput {
+                state,
+                button: winit::event::MouseButton::Left,
+                ..
+            } => {
+                // Grabs (or releases) a handle of the transform gizmo, or else picks a control
+                // point of the camera path while it is shown. Presses on the letterbox bars are
+                // ignored, while releases anywhere end a drag.
+                let helpers_visible = self.ui.layers.shows(self.ui.layers.helpers);
+                let mut picked_point = false;
+                if let (true, Some(cursor), Some(view_projection), Some(viewport)) = (
+                    helpers_visible,
+                    self.cursor_position,
+                    renderer.view_projection(),
+                    renderer.viewport(),
+                ) {
+                    if !state.is_pressed() || viewport.to_local(cursor).is_some() {
+                        picked_point = self.gizmo.pointer_button(
+                            viewport.to_local_clamped(cursor),
+                            state.is_pressed(),
+                            renderer
+                                .as_renderer()
+                                .and_then(Renderer::gizmo_target)
+                                .as_ref(),
+                            &view_projection,
+                            viewport.size(),
+                        );
+                    }
+                }
+                let path_visible = self.ui.camera_path_visible && helpers_visible;
+                if let (true, false, Some(cursor), Some(view_projection), Some(viewport)) = (
+                    path_visible,
+                    picked_point,
+                    self.cursor_position,
+                    renderer.view_projection(),
+                    renderer.viewport(),
+                ) {
+                    if !state.is_pressed() || viewport.to_local(cursor).is_some() {
+                        picked_point = self.camera_path.pointer_button(
+                            viewport.to_local_clamped(cursor),
+                            state.is_pressed(),
+                            &view_projection,
+                            viewport.size(),
+                        );
+                    }
+                }
+                // Other presses select the scene's node under the cursor, or clear the
+                // selection over the background.
+                if let (true, false, Some(cursor), Some(renderer)) = (
+                    state.is_pressed(),
+                    picked_point,
+                    self.cursor_position,
+                    renderer.as_renderer_mut(),
+                ) {
+                    renderer.pick(cursor);
+                }
+            }
+            WindowEvent::MouseInput {
+                state: winit::event::ElementState::Pressed,
+                button: winit::event::MouseButton::Right,
+                ..
+            } => {
+                // Holding the right button looks around with the camera. The FPS camera turns
+                // with the hidden, locked cursor, like in games.
+                self.camera_input.looking = true;
+                if self.camera.mode() == CameraMode::Fps {
+                    set_mouse_look(window, true);
+                }
+            }
+            WindowEvent::Touch(touch) => {
+                // Only touches starting outside the GUI are tracked; they are followed from
+                // here on even over GUI widgets.
+                self.gestures.touch(
+                    touch.id,
+                    touch.phase,
+                    crate::math::vec2(touch.location.x as f32, touch.location.y as f32),
+                    Instant::now(),
+                );
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                // Scrolling zooms the orbit camera. Pixel deltas (from touchpads) are converted
+                // to roughly one line per 50 pixels.
+                self.camera_input.zoom += match delta {
+                    winit::event::MouseScrollDelta::LineDelta(_, y) => y,
+                    winit::event::MouseScrollDelta::PixelDelta(position) => {
+                        position.y as f32 / 50.0
+                    }
+                };
             }
             _ => (),
         }
 
-        window.request_redraw();
+        self.schedule_redraw();
+
+        // While the window's border is dragged, Windows runs a modal loop that delivers
+        // `Resized` events but holds redraws back, so the content lags behind the border and
+        // acquiring a texture of the outdated swapchain can block. Rendering straight from
+        // the `Resized` event, at most once per interval, keeps live resizing smooth.
+        #[cfg(target_os = "windows")]
+        if resized
+            && self
+                .last_render_time
+                .is_some_and(|time| time.elapsed() >= Self::LIVE_RESIZE_FRAME_INTERVAL)
+        {
+            self.window_event(event_loop, window_id, WindowEvent::RedrawRequested);
+        }
+    }
+
+    /// Feeds raw mouse motion to the cameras, which use it instead of the cursor movement
+    /// once the platform reports it. It is not coalesced, as it is only accumulated.
+    fn device_event(
+        &mut self,
+        _event_loop: &winit::event_loop::ActiveEventLoop,
+        _device_id: winit::event::DeviceId,
+        event: winit::event::DeviceEvent,
+    ) {
+        self.input_state.handle_device_event(&event);
+        if let winit::event::DeviceEvent::MouseMotion { delta } = event {
+            self.camera_input
+                .mouse_motion(crate::math::vec2(delta.0 as f32, delta.1 as f32));
+        }
+    }
+
+    /// Requests the next frame once the redraw policy wants it, and lets the event loop sleep
+    /// until then, or until the next event arrives.
+    fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        // Until the renderer is created (asynchronously, on the web), frames keep being
+        // requested so it is picked up as soon as it is ready.
+        if self.renderer.is_none() {
+            self.redraw.mark_dirty();
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        self.open_pending_windows(event_loop);
+        self.schedule_redraw();
+        event_loop.set_control_flow(self.redraw.control_flow());
+    }
+
+    /// Saves the settings with the window's geometry and the camera's pose, and shuts down the
+    /// rendering backend when the event loop is about to exit.
+    ///
+    /// This gives the backend a chance to finish outstanding work (for the wgpu `Renderer`,
+    /// waiting for submitted GPU commands) before it is dropped.
+    /// Stops rendering while the app is suspended. On Android, the native window is destroyed
+    /// once this returns, so the surface is dropped as well; the device and all GPU resources
+    /// are kept, and `resumed` creates a new surface for the same window.
+    ///
+    /// The session is saved, as a suspended app may be killed without exiting.
+    fn suspended(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        if self.surface_suspended || self.window.is_none() {
+            return;
+        }
+        log::info!("Suspended");
+        self.surface_suspended = true;
+        self.input.cancel_redraw();
+        if cfg!(target_os = "android") {
+            if let Some(renderer) = self.renderer.as_mut() {
+                renderer.suspend_surface();
+            }
+        }
+        self.save_session();
+    }
+
+    fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        // Every way of exiting ends here, whether the window was closed or an action quit.
+        self.save_session();
+        #[cfg(not(target_arch = "wasm32"))]
+        for state in self.windows.values_mut() {
+            state.shutdown();
+        }
+        if let Some(renderer) = self.renderer.as_mut() {
+            #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+            if let Some(renderer) = renderer.as_renderer_mut() {
+                self.viewports.close_all(renderer);
+            }
+            renderer.shutdown();
+        }
     }
 }
@@ -0,0 +1,741 @@
+//! # Asset Loading Module
+//!
+//! The `asset` module provides the `AssetLoader`, a small cross-platform abstraction for
+//! retrieving raw asset bytes (models, textures, shaders) either from the local project
+//! directory or from a remote HTTP(S) server.
+//!
+//! ## Overview
+//!
+//! Demo scenes frequently reference large binary files such as glTF models and textures.
+//! Bundling all of them into the binary (or the wasm package) makes builds slow and
+//! downloads heavy, so the loader also accepts `http://` and `https://` URLs and keeps
+//! a cache of everything it downloaded:
+//!
+//! - **Desktop**: Requests are issued with `reqwest` and stored in an on-disk cache
+//!   (one data file and one ETag file per URL). On subsequent loads the stored ETag is
+//!   sent in an `If-None-Match` header; a `304 Not Modified` response is answered from
+//!   the cache without transferring the body again. If the network is unavailable, a
+//!   previously cached copy is returned instead of failing. Downloading needs the
+//!   `import-gltf` feature; without it, only local files and packs are loaded.
+//! - **WebAssembly**: Requests are issued with the browser's `fetch` API, and remote assets
+//!   are stored in an IndexedDB cache (one record per URL, holding the body and its ETag),
+//!   revalidated and used offline just like the on-disk cache. Servers on other origins
+//!   must list `If-None-Match` in `Access-Control-Allow-Headers` and `ETag` in
+//!   `Access-Control-Expose-Headers` for the revalidation to work. Relative paths are
+//!   resolved against the page URL and revalidated through the browser's HTTP cache.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let loader = AssetLoader::new();
+//!
+//! // Loads from the local file system (or relative to the page on the web).
+//! let shader = loader.load("assets/shader.wgsl").await?;
+//!
+//! // Downloads once, then revalidates through the cache on later runs.
+//! let model = loader
+//!     .load("https://example.com/models/DamagedHelmet.glb")
+//!     .await?;
+//! ```
+//!
+//! ## Asset Packs
+//!
+//! An asset pack built by the `pack` subcommand (see the `pack` module) can be mounted with
+//! `AssetLoader::mount_pack`. Relative paths are then answered from the mounted packs, most
+//! recently mounted first, before falling back to the file system or the page URL. On the
+//! web, this loads all of a project's assets with a single fetch.
+//!
+//! The `App` mounts the pack of `AppConfig::asset_pack`, set with `--pack` on the command line
+//! or `?pack=` in the page URL, before it loads its model and texture.
+//!
+//! The `App` loads its model and texture through the loader on both platforms. On the web,
+//! where nothing may block, a load is a [`PendingLoad`] finishing in a later frame.
+//!
+//! ## Errors
+//!
+//! All failures are reported through the `AssetError` enum, which distinguishes between
+//! local I/O errors, transport errors, and unsuccessful HTTP status codes.
+
+// Importing `PathBuf` for building locations of local assets and of the on-disk cache
+// entries. The on-disk cache is only used on desktop platforms; the web uses IndexedDB.
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+// Importing `Future` for the loads a `PendingLoad` runs.
+use std::future::Future;
+
+use crate::pack::{AssetPack, PackError};
+
+/// Errors that can occur while loading an asset.
+///
+/// # Variants
+///
+/// - `Io`: A local file could not be read, or a cache entry could not be written.
+/// - `Request`: The HTTP request could not be performed (DNS failure, refused connection,
+///   TLS error, or a failed `fetch` promise on the web).
+/// - `Status`: The server answered with an unsuccessful HTTP status code.
+/// - `Pack`: An asset pack could not be mounted, or one of its entries is corrupt.
+#[derive(Debug, thiserror::Error)]
+pub enum AssetError {
+    /// A local file could not be read or a cache entry could not be written.
+    #[error("failed to read asset '{path}': {source}")]
+    Io {
+        /// The path of the file that failed to load.
+        path: String,
+
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+
+    /// The HTTP request failed before a response was received.
+    #[error("failed to request asset '{url}': {message}")]
+    Request {
+        /// The URL that was requested.
+        url: String,
+
+        /// A human readable description of the transport error.
+        message: String,
+    },
+
+    /// The server answered with an unsuccessful status code.
+    #[error("server returned status {status} for asset '{url}'")]
+    Status {
+        /// The URL that was requested.
+        url: String,
+
+        /// The HTTP status code of the response.
+        status: u16,
+    },
+
+    /// An asset pack could not be mounted, or one of its entries could not be read.
+    #[error("failed to read asset pack '{path}': {source}")]
+    Pack {
+        /// The source of the pack, or the path of the entry that could not be read.
+        path: String,
+
+        /// The underlying pack error.
+        source: PackError,
+    },
+}
+
+/// Loads raw asset bytes from local files or HTTP(S) URLs, with caching.
+///
+/// The `AssetLoader` is the single entry point used by scene and resource code to obtain
+/// asset data. Callers pass either a relative path (resolved against the loader's root
+/// directory on desktop, or the page URL on the web) or an absolute `http(s)://` URL.
+///
+/// # Fields
+///
+/// - `root`: _(Desktop only)_ The directory that relative asset paths are resolved against.
+/// - `cache`: _(Desktop only)_ The on-disk cache used for remote assets.
+/// - `client`: _(Desktop only)_ The blocking `reqwest` client used for HTTP requests.
+///
+/// The desktop `cache` and `client` need the `import-gltf` feature.
+/// - `packs`: The mounted asset packs, answering relative paths before the file system.
+///
+/// # Platform-Specific Implementation
+///
+/// - **Desktop:** Remote assets are cached on disk and revalidated with ETags.
+/// - **WebAssembly:** Remote assets are fetched with `fetch`, cached in IndexedDB, and
+///   revalidated with ETags.
+///
+/// # Example
+///
+/// ```ignore
+/// let loader = AssetLoader::new().with_root("assets");
+/// let bytes = loader.load("textures/checker.png").await?;
+/// ```
+pub struct AssetLoader {
+    /// The directory that relative asset paths are resolved against.
+    ///
+    /// Defaults to the current working directory. Absolute paths and URLs are not affected.
+    #[cfg(not(target_arch = "wasm32"))]
+    root: PathBuf,
+
+    /// The on-disk cache for assets downloaded over HTTP(S).
+    ///
+    /// Each cached URL is stored as a pair of files named after a stable hash of the URL:
+    /// one holding the response body and one holding the ETag the server sent with it.
+    #[cfg(all(feature = "import-gltf", not(target_arch = "wasm32")))]
+    cache: DiskCache,
+
+    /// The HTTP client used for remote assets.
+    ///
+    /// The blocking client is used because the desktop application drives its futures with
+    /// `pollster`, which does not provide the async I/O reactor `reqwest` would otherwise need.
+    #[cfg(all(feature = "import-gltf", not(target_arch = "wasm32")))]
+    client: reqwest::blocking::Client,
+
+    /// The mounted asset packs, in the order they were mounted.
+    packs: Vec<AssetPack>,
+}
+
+impl Default for AssetLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AssetLoader {
+    /// Creates a new `AssetLoader` with the default root and cache locations.
+    ///
+    /// On desktop, relative paths are resolved against the current working directory and
+    /// downloads are cached in the platform cache directory (for example `~/.cache` on
+    /// Linux), falling back to the system temporary directory when none is available.
+    pub fn new() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            root: PathBuf::from("."),
+            #[cfg(all(feature = "import-gltf", not(target_arch = "wasm32")))]
+            cache: DiskCache::new(
+                dirs::cache_dir()
+                    .unwrap_or_else(std::env::temp_dir)
+                    .join("wgpu-example")
+                    .join("assets"),
+            ),
+            #[cfg(all(feature = "import-gltf", not(target_arch = "wasm32")))]
+            client: reqwest::blocking::Client::new(),
+            packs: Vec::new(),
+        }
+    }
+
+    /// Sets the directory that relative asset paths are resolved against.
+    ///
+    /// This has no effect on WebAssembly, where relative paths are resolved by the browser.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables, unused_mut))]
+    pub fn with_root(mut self, root: impl Into<std::path::PathBuf>) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.root = root.into();
+        }
+        self
+    }
+
+    /// Sets the directory used to cache downloaded assets.
+    ///
+    /// This has no effect on WebAssembly, where downloads are cached in IndexedDB instead, nor
+    /// without the `import-gltf` feature, which downloads are made with.
+    #[cfg_attr(
+        any(target_arch = "wasm32", not(feature = "import-gltf")),
+        allow(unused_variables, unused_mut)
+    )]
+    pub fn with_cache_dir(mut self, directory: impl Into<std::path::PathBuf>) -> Self {
+        #[cfg(all(feature = "import-gltf", not(target_arch = "wasm32")))]
+        {
+            self.cache = DiskCache::new(directory.into());
+        }
+        self
+    }
+
+    /// Mounts `pack`, so relative paths found in it are loaded from it. Packs mounted later
+    /// take precedence over earlier ones.
+    pub fn mount(&mut self, pack: AssetPack) {
+        self.packs.push(pack);
+    }
+
+    /// Loads the asset pack at `source` (a path or an HTTP(S) URL) and mounts it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AssetError` if the pack cannot be loaded, or `AssetError::Pack` if it is
+    /// not a valid asset pack.
+    pub async fn mount_pack(&mut self, source: &str) -> Result<(), AssetError> {
+        let bytes = self.load(source).await?;
+        let pack = AssetPack::from_bytes(bytes).map_err(|error| AssetError::Pack {
+            path: source.to_string(),
+            source: error,
+        })?;
+        log::info!(
+            "Mounted asset pack '{source}' with {} entries",
+            pack.paths().count()
+        );
+        self.mount(pack);
+        Ok(())
+    }
+
+    /// Returns `true` if `source` refers to a remote HTTP(S) asset.
+    pub fn is_remote(source: &str) -> bool {
+        source.starts_with("http://") || source.starts_with("https://")
+    }
+
+    /// Loads the bytes of an asset from a local path or an HTTP(S) URL.
+    ///
+    /// # Parameters
+    ///
+    /// - `source`: A relative or absolute file path, or an `http://`/`https://` URL.
+    ///
+    /// # Returns
+    ///
+    /// The complete contents of the asset.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AssetError` if the file cannot be read, the request fails and no cached
+    /// copy is available, the server responds with an unsuccessful status code, or the
+    /// asset's entry in a mounted pack is corrupt.
+    pub async fn load(&self, source: &str) -> Result<Vec<u8>, AssetError> {
+        if let Some(bytes) = self.load_packed(source)? {
+            return Ok(bytes);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if Self::is_remote(source) {
+                self.load_remote(source)
+            } else {
+                let path = self.root.join(source);
+                std::fs::read(&path).map_err(|source| AssetError::Io {
+                    path: path.display().to_string(),
+                    source,
+                })
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if Self::is_remote(source) {
+                load_remote(source).await
+            } else {
+                fetch(source, None).await.map(|(bytes, _)| bytes)
+            }
+        }
+    }
+
+    /// Reads a relative asset from the most recently mounted pack holding it, if any.
+    fn load_packed(&self, source: &str) -> Result<Option<Vec<u8>>, AssetError> {
+        if Self::is_remote(source) {
+            return Ok(None);
+        }
+        let path = source.trim_start_matches("./");
+        for pack in self.packs.iter().rev() {
+            let bytes = pack.read(path).map_err(|error| AssetError::Pack {
+                path: path.to_string(),
+                source: error,
+            })?;
+            if bytes.is_some() {
+                return Ok(bytes);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Fails to download a remote asset in builds without the `import-gltf` feature.
+    #[cfg(all(not(feature = "import-gltf"), not(target_arch = "wasm32")))]
+    fn load_remote(&self, url: &str) -> Result<Vec<u8>, AssetError> {
+        Err(AssetError::Request {
+            url: url.to_string(),
+            message: "downloading assets requires the `import-gltf` feature".to_string(),
+        })
+    }
+
+    /// Downloads a remote asset, revalidating any cached copy with its ETag.
+    #[cfg(all(feature = "import-gltf", not(target_arch = "wasm32")))]
+    fn load_remote(&self, url: &str) -> Result<Vec<u8>, AssetError> {
+        // The previously downloaded copy of this URL, if any. Its ETag is sent along with
+        // the request so the server can answer with `304 Not Modified` when nothing changed.
+        let cached = self.cache.get(url);
+
+        let mut request = self.client.get(url);
+        if let Some(etag) = cached.as_ref().and_then(|entry| entry.etag.as_deref()) {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+
+        let response = match request.send() {
+            Ok(response) => response,
+            Err(error) => {
+                // Prefer a possibly stale asset over failing outright while offline.
+                if let Some(entry) = cached {
+                    log::warn!("Using cached copy of '{url}' after request failure: {error}");
+                    return Ok(entry.bytes);
+                }
+                return Err(AssetError::Request {
+                    url: url.to_string(),
+                    message: error.to_string(),
+                });
+            }
+        };
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached {
+                log::info!("Asset '{url}' not modified, using cached copy");
+                return Ok(entry.bytes);
+            }
+        }
+
+        if !response.status().is_success() {
+            return Err(AssetError::Status {
+                url: url.to_string(),
+                status: response.status().as_u16(),
+            });
+        }
+
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let bytes = response
+            .bytes()
+            .map_err(|error| AssetError::Request {
+                url: url.to_string(),
+                message: error.to_string(),
+            })?
+            .to_vec();
+
+        if let Err(error) = self.cache.put(url, etag.as_deref(), &bytes) {
+            log::warn!("Failed to cache asset '{url}': {error}");
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// A single entry read back from the on-disk or IndexedDB asset cache.
+#[cfg(any(feature = "import-gltf", target_arch = "wasm32"))]
+struct CacheEntry {
+    /// The ETag the server sent with the cached response, if it sent one.
+    etag: Option<String>,
+
+    /// The cached response body.
+    bytes: Vec<u8>,
+}
+
+/// An on-disk cache of downloaded assets keyed by URL.
+///
+/// Entries are named after a 64-bit FNV-1a hash of the URL, which is stable across runs
+/// and compiler versions (unlike `std::collections::hash_map::DefaultHasher`).
+#[cfg(all(feature = "import-gltf", not(target_arch = "wasm32")))]
+struct DiskCache {
+    /// The directory holding the cached files. It is created lazily on the first write.
+    directory: PathBuf,
+}
+
+#[cfg(all(feature = "import-gltf", not(target_arch = "wasm32")))]
+impl DiskCache {
+    /// Creates a cache rooted at `directory`.
+    fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    /// Returns the paths of the data file and the ETag file for `url`.
+    fn paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key = format!("{:016x}", fnv1a(url.as_bytes()));
+        (
+            self.directory.join(format!("{key}.bin")),
+            self.directory.join(format!("{key}.etag")),
+        )
+    }
+
+    /// Reads the cached entry for `url`, if present.
+    fn get(&self, url: &str) -> Option<CacheEntry> {
+        let (data_path, etag_path) = self.paths(url);
+        let bytes = std::fs::read(data_path).ok()?;
+        let etag = std::fs::read_to_string(etag_path).ok();
+        Some(CacheEntry { etag, bytes })
+    }
+
+    /// Stores `bytes` and the optional `etag` for `url`, replacing any previous entry.
+    ///
+    /// The old ETag is removed before the data is replaced, and each file is written to a
+    /// temporary path and renamed over the old one. If the process dies or a write fails
+    /// midway, the entry is at worst left without an ETag, which the next load refetches in
+    /// full, and never with an ETag of other data than it holds.
+    fn put(&self, url: &str, etag: Option<&str>, bytes: &[u8]) -> Result<(), AssetError> {
+        let io_error = |path: &std::path::Path| {
+            let path = path.display().to_string();
+            move |source| AssetError::Io { path, source }
+        };
+
+        std::fs::create_dir_all(&self.directory).map_err(io_error(&self.directory))?;
+
+        let (data_path, etag_path) = self.paths(url);
+        match std::fs::remove_file(&etag_path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                return Err(io_error(&etag_path)(error));
+            }
+            _ => {}
+        }
+        Self::replace(&data_path, bytes).map_err(io_error(&data_path))?;
+        if let Some(etag) = etag {
+            Self::replace(&etag_path, etag.as_bytes()).map_err(io_error(&etag_path))?;
+        }
+        Ok(())
+    }
+
+    /// Writes `bytes` to a temporary file next to `path`, and renames it to `path`.
+    fn replace(path: &std::path::Path, bytes: &[u8]) -> std::io::Result<()> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, bytes)?;
+        std::fs::rename(&temporary, path)
+    }
+}
+
+/// Computes the 64-bit FNV-1a hash of `bytes`.
+#[cfg(all(feature = "import-gltf", not(target_arch = "wasm32")))]
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Downloads a remote asset on the web, revalidating any copy cached in IndexedDB with its
+/// ETag, and caching the new copy.
+#[cfg(target_arch = "wasm32")]
+async fn load_remote(url: &str) -> Result<Vec<u8>, AssetError> {
+    // Without IndexedDB (as in some private browsing modes), assets are downloaded uncached.
+    let cache = match IdbCache::open().await {
+        Ok(cache) => Some(cache),
+        Err(error) => {
+            log::warn!("The asset cache is unavailable: {error:?}");
+            None
+        }
+    };
+    let cached = match &cache {
+        Some(cache) => cache.get(url).await,
+        None => None,
+    };
+
+    let etag = cached.as_ref().and_then(|entry| entry.etag.as_deref());
+    let (bytes, etag) = match fetch(url, etag).await {
+        Ok(response) => response,
+        Err(error @ AssetError::Status { status: 304, .. }) => match cached {
+            Some(entry) => {
+                log::info!("Asset '{url}' not modified, using cached copy");
+                return Ok(entry.bytes);
+            }
+            None => return Err(error),
+        },
+        Err(error @ AssetError::Request { .. }) => match cached {
+            // Prefer a possibly stale asset over failing outright while offline.
+            Some(entry) => {
+                log::warn!("Using cached copy of '{url}' after request failure: {error}");
+                return Ok(entry.bytes);
+            }
+            None => return Err(error),
+        },
+        Err(error) => return Err(error),
+    };
+
+    if let Some(cache) = cache {
+        if let Err(error) = cache.put(url, etag.as_deref(), &bytes).await {
+            log::warn!("Failed to cache asset '{url}': {error:?}");
+        }
+    }
+    Ok(bytes)
+}
+
+/// Fetches `url` with the browser's `fetch` API and returns the response body and its ETag.
+///
+/// With an `etag`, the request bypasses the browser's HTTP cache and asks the server with
+/// `If-None-Match` whether the copy of that ETag is current, failing with a `304` status if
+/// it is. Without one, the request uses `RequestCache::NoCache`, so the browser revalidates
+/// any response it stored itself before reusing it.
+#[cfg(target_arch = "wasm32")]
+async fn fetch(url: &str, etag: Option<&str>) -> Result<(Vec<u8>, Option<String>), AssetError> {
+    use wasm_bindgen::JsCast;
+
+    let request_error = |value: wasm_bindgen::JsValue| AssetError::Request {
+        url: url.to_string(),
+        message: format!("{value:?}"),
+    };
+
+    let init = web_sys::RequestInit::new();
+    init.set_method("GET");
+    match etag {
+        Some(_) => init.set_cache(web_sys::RequestCache::NoStore),
+        None => init.set_cache(web_sys::RequestCache::NoCache),
+    }
+
+    let request = web_sys::Request::new_with_str_and_init(url, &init).map_err(request_error)?;
+    if let Some(etag) = etag {
+        request
+            .headers()
+            .set("If-None-Match", etag)
+            .map_err(request_error)?;
+    }
+    let window = web_sys::window().ok_or_else(|| AssetError::Request {
+        url: url.to_string(),
+        message: "no browser window available".to_string(),
+    })?;
+
+    let response: web_sys::Response =
+        wasm_bindgen_futures::JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(request_error)?
+            .dyn_into()
+            .map_err(request_error)?;
+
+    if !response.ok() {
+        return Err(AssetError::Status {
+            url: url.to_string(),
+            status: response.status(),
+        });
+    }
+
+    let etag = response.headers().get("ETag").ok().flatten();
+    let buffer =
+        wasm_bindgen_futures::JsFuture::from(response.array_buffer().map_err(request_error)?)
+            .await
+            .map_err(request_error)?;
+    Ok((js_sys::Uint8Array::new(&buffer).to_vec(), etag))
+}
+
+/// The IndexedDB cache of downloaded assets on the web, keyed by URL.
+///
+/// Each record is an object with the response body in `bytes` (a `Uint8Array`) and the ETag
+/// the server sent with it in `etag`, if it sent one. Both are replaced in one transaction,
+/// so a record never pairs an ETag with other data than it was sent with.
+#[cfg(target_arch = "wasm32")]
+struct IdbCache {
+    /// The opened database.
+    database: web_sys::IdbDatabase,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl IdbCache {
+    /// The name of the database.
+    const DATABASE: &'static str = "wgpu-example";
+
+    /// The name of the object store holding the assets.
+    const STORE: &'static str = "assets";
+
+    /// Opens the cache, creating its database on first use.
+    async fn open() -> Result<Self, wasm_bindgen::JsValue> {
+        use wasm_bindgen::closure::Closure;
+        use wasm_bindgen::JsCast;
+
+        let factory = web_sys::window()
+            .ok_or("no browser window available")?
+            .indexed_db()?
+            .ok_or("IndexedDB is not supported")?;
+        let request = factory.open_with_u32(Self::DATABASE, 1)?;
+        let upgrade = Closure::<dyn FnMut(web_sys::IdbVersionChangeEvent)>::new(
+            |event: web_sys::IdbVersionChangeEvent| {
+                let database = event
+                    .target()
+                    .and_then(|target| target.dyn_into::<web_sys::IdbOpenDbRequest>().ok())
+                    .and_then(|request| request.result().ok())
+                    .and_then(|result| result.dyn_into::<web_sys::IdbDatabase>().ok());
+                if let Some(database) = database {
+                    if let Err(error) = database.create_object_store(Self::STORE) {
+                        log::error!("Failed to create the asset cache: {error:?}");
+                    }
+                }
+            },
+        );
+        request.set_onupgradeneeded(Some(upgrade.as_ref().unchecked_ref()));
+        let database = request_result(&request).await?;
+        request.set_onupgradeneeded(None);
+        drop(upgrade);
+        Ok(Self {
+            database: database.dyn_into()?,
+        })
+    }
+
+    /// Reads the cached entry for `url`, if present.
+    async fn get(&self, url: &str) -> Option<CacheEntry> {
+        let store = self
+            .database
+            .transaction_with_str(Self::STORE)
+            .and_then(|transaction| transaction.object_store(Self::STORE))
+            .ok()?;
+        let record = request_result(&store.get(&url.into()).ok()?).await.ok()?;
+        if record.is_undefined() {
+            return None;
+        }
+        let field = |name: &str| js_sys::Reflect::get(&record, &name.into()).ok();
+        let bytes = js_sys::Uint8Array::new(&field("bytes")?).to_vec();
+        let etag = field("etag").and_then(|etag| etag.as_string());
+        Some(CacheEntry { etag, bytes })
+    }
+
+    /// Stores `bytes` and the optional `etag` for `url`, replacing any previous entry.
+    async fn put(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        bytes: &[u8],
+    ) -> Result<(), wasm_bindgen::JsValue> {
+        let record = js_sys::Object::new();
+        js_sys::Reflect::set(&record, &"bytes".into(), &js_sys::Uint8Array::from(bytes))?;
+        if let Some(etag) = etag {
+            js_sys::Reflect::set(&record, &"etag".into(), &etag.into())?;
+        }
+        let store = self
+            .database
+            .transaction_with_str_and_mode(Self::STORE, web_sys::IdbTransactionMode::Readwrite)?
+            .object_store(Self::STORE)?;
+        request_result(&store.put_with_key(&record, &url.into())?).await?;
+        Ok(())
+    }
+}
+
+/// Waits for an IndexedDB `request` to finish, and returns its result.
+#[cfg(target_arch = "wasm32")]
+async fn request_result(
+    request: &web_sys::IdbRequest,
+) -> Result<wasm_bindgen::JsValue, wasm_bindgen::JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    wasm_bindgen_futures::JsFuture::from(promise).await?;
+    request.set_onsuccess(None);
+    request.set_onerror(None);
+    request.result()
+}
+
+/// An asset load that finishes in a later frame on the web, where nothing may block, and at
+/// once on desktop platforms, where the loader is synchronous.
+pub(crate) struct PendingLoad<T> {
+    /// Receives the result of the load once it finished.
+    receiver: futures::channel::oneshot::Receiver<T>,
+}
+
+impl<T: 'static> PendingLoad<T> {
+    /// Starts running `load`: on the web in a spawned task, elsewhere right away.
+    pub(crate) fn spawn(load: impl Future<Output = T> + 'static) -> Self {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = sender.send(load.await);
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = sender.send(pollster::block_on(load));
+
+        Self { receiver }
+    }
+
+    /// Returns the result of the load if it finished; later calls return `None`.
+    pub(crate) fn take(&mut self) -> Option<T> {
+        self.receiver.try_recv().ok().flatten()
+    }
+}
+
+#[cfg(all(test, feature = "import-gltf", not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disk_cache_replaces_the_etag_with_the_data() {
+        let directory = std::env::temp_dir().join(format!("asset-cache-{}", std::process::id()));
+        let cache = DiskCache::new(directory.clone());
+        let url = "https://example.com/model.glb";
+
+        cache.put(url, Some("\"v1\""), b"first").unwrap();
+        let entry = cache.get(url).unwrap();
+        assert_eq!(entry.bytes, b"first");
+        assert_eq!(entry.etag.as_deref(), Some("\"v1\""));
+
+        cache.put(url, None, b"second").unwrap();
+        let entry = cache.get(url).unwrap();
+        assert_eq!(entry.bytes, b"second");
+        assert_eq!(entry.etag, None);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,438 @@
+//! # Renderer Backend Module
+//!
+//! The `backend` module defines the [`RendererBackend`] trait, the interface the `App` uses to
+//! drive rendering. Decoupling the application from a concrete renderer makes it possible to
+//! swap in alternate backends when the `App` is constructed, for example:
+//!
+//! - The default wgpu [`Renderer`], which draws the scene and GUI on the GPU.
+//! - The [`NullBackend`], which accepts every frame without drawing anything. It is useful
+//!   for tests and for CI machines without GPUs.
+//! - The `SoftwareBackend` of the `software` feature, which rasterizes a simplified scene and
+//!   the GUI on the CPU. The default error policy switches to it when no adapter is found.
+//! - Any other backend implemented outside this crate.
+//!
+//! ## Overview
+//!
+//! A backend goes through four stages:
+//!
+//! 1. **`init`**: Asynchronously creates the backend for a window. This is an `async` trait
+//!    method (via `async-trait`) because GPU setup is asynchronous, especially on WebAssembly.
+//! 2. **`resize`**: Adapts the backend to a new surface size.
+//! 3. **`render`**: Renders one frame, including the `egui` output produced by the `App` when
+//!    the `gui` feature is enabled.
+//! 4. **`shutdown`**: Releases or flushes resources before the application exits.
+//!
+//! The `App` stores the active backend as a `Box<dyn RendererBackend>` and creates it through a
+//! [`BackendFactory`], which is chosen with `App::with_backend`.
+//!
+//! The trait only covers what any backend can do: the stages above, the camera and viewport
+//! the scene is drawn with, and the fixed ticks its animation advances by. The many features
+//! only the wgpu renderer has are used through [`RendererBackend::as_renderer_mut`], which
+//! returns the concrete [`Renderer`], so they do not each add a method to every backend.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! // Run the application without touching the GPU.
+//! let mut app = App::default().with_backend::<NullBackend>();
+//! event_loop.run_app(&mut app)?;
+//! ```
+
+// Importing `Arc` to share ownership of the window between the `App` and the backend, which
+// may keep it alive for as long as it renders to the window's surface.
+use std::sync::Arc;
+
+// Importing `LocalBoxFuture`, a boxed future without a `Send` bound. Backends are created on
+// the main thread, and on WebAssembly the GPU types are not `Send`.
+use futures::future::LocalBoxFuture;
+
+// Importing the window type that backends are initialized for.
+use winit::window::Window;
+
+use crate::camera::{Bounds, Projection};
+use crate::config::RendererConfig;
+use crate::error::RenderError;
+use crate::layers::LayerMasks;
+use crate::renderer::Renderer;
+use crate::viewport::{AspectLock, Viewport};
+
+/// The interface between the `App` and a rendering backend.
+///
+/// Implementations are created asynchronously with [`RendererBackend::init`] and are then
+/// driven by the application's event loop through `resize` and `render`, until `shutdown`
+/// is called when the application exits.
+///
+/// The trait uses `async_trait(?Send)` so that backends can be stored as
+/// `Box<dyn RendererBackend>` and created on WebAssembly, where GPU handles are not `Send`.
+#[async_trait::async_trait(?Send)]
+pub trait RendererBackend {
+    /// Creates the backend for `window` with an initial surface size of `width` x `height`.
+    ///
+    /// When `force_fallback_adapter` is `true`, the backend should use a degraded
+    /// configuration, such as a software adapter. Backends without such a mode can ignore it.
+    /// `config` holds the graphics backends and present mode chosen by the application, which
+    /// backends without a `wgpu` surface can ignore as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if the backend cannot be initialized. The `App` hands it to its
+    /// `ErrorPolicy`, which decides whether to retry, fall back, or exit.
+    async fn init(
+        window: Arc<Window>,
+        width: u32,
+        height: u32,
+        force_fallback_adapter: bool,
+        config: RendererConfig,
+    ) -> Result<Self, RenderError>
+    where
+        Self: Sized;
+
+    /// Adapts the backend to a new surface size. Resizing to the current size should be cheap,
+    /// since the `App` may do so whenever a `Resized` event arrives.
+    fn resize(&mut self, width: u32, height: u32);
+
+    /// Renders a single frame.
+    ///
+    /// `screen_descriptor`, `paint_jobs`, and `textures_delta` describe the GUI produced by
+    /// `egui` for this frame, and `delta_time` is the time elapsed since the previous frame.
+    /// Without the `gui` feature, only `delta_time` is passed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if the frame could not be rendered.
+    fn render(
+        &mut self,
+        #[cfg(feature = "gui")] screen_descriptor: egui_wgpu::ScreenDescriptor,
+        #[cfg(feature = "gui")] paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
+        #[cfg(feature = "gui")] textures_delta: egui::TexturesDelta,
+        delta_time: crate::Duration,
+    ) -> Result<(), RenderError>;
+
+    /// Reconfigures the surface with freshly queried capabilities.
+    ///
+    /// The `App` calls this when frames fail with a surface that is outdated or may no longer
+    /// support its configuration, which resizing to the unchanged size would not fix. The
+    /// default implementation does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if the surface can no longer be configured.
+    fn reconfigure_surface(&mut self) -> Result<(), RenderError> {
+        Ok(())
+    }
+
+    /// Draws DPI-dependent content, such as line widths and label sizes given in logical
+    /// pixels, for a window with `scale_factor` physical pixels per logical pixel.
+    ///
+    /// The default implementation ignores it.
+    fn set_scale_factor(&mut self, _scale_factor: f32) {}
+
+    /// Drops the window's surface while it cannot be presented to, as when an Android app is
+    /// suspended, keeping the device and all GPU resources.
+    ///
+    /// The default implementation does nothing.
+    fn suspend_surface(&mut self) {}
+
+    /// Creates the window's surface again after `suspend_surface`, when the app is resumed.
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if the new surface cannot be created or configured.
+    fn resume_surface(&mut self, _window: Arc<Window>) -> Result<(), RenderError> {
+        Ok(())
+    }
+
+    /// Returns the backend as the wgpu [`Renderer`], if it is one.
+    ///
+    /// The features only the wgpu renderer has, such as picking, captures, previews, and the
+    /// render settings of the Inspector panel, are used through the `Renderer` itself rather
+    /// than through this trait. The default implementation returns `None`.
+    fn as_renderer(&self) -> Option<&Renderer> {
+        None
+    }
+
+    /// Returns the backend as the wgpu [`Renderer`] to modify, if it is one.
+    ///
+    /// The default implementation returns `None`.
+    fn as_renderer_mut(&mut self) -> Option<&mut Renderer> {
+        None
+    }
+
+    /// Sets the layers the main camera draws and the layers of each object.
+    ///
+    /// The default implementation ignores them, for backends drawing everything or nothing.
+    fn set_layer_masks(&mut self, _masks: &LayerMasks) {}
+
+    /// Locks the scene's viewport to an aspect ratio, or unlocks it.
+    ///
+    /// The default implementation always fills the surface.
+    fn set_aspect_lock(&mut self, _aspect_lock: AspectLock) {}
+
+    /// Sets the color the surface is cleared to behind the scene, as linear RGBA with
+    /// unmultiplied alpha. The alpha only matters for a transparent surface.
+    ///
+    /// The default implementation ignores it.
+    fn set_clear_color(&mut self, _color: wgpu::Color) {}
+
+    /// Returns the rectangle of the surface the scene is drawn into, used to map the cursor
+    /// into the scene.
+    ///
+    /// The default implementation draws no scene.
+    fn viewport(&self) -> Option<Viewport> {
+        None
+    }
+
+    /// Sets the camera's view matrix and its position in world space.
+    ///
+    /// The default implementation has no camera and ignores it.
+    fn set_camera(&mut self, _view: crate::math::Mat4, _eye: crate::math::Vec3) {}
+
+    /// Sets the camera's field of view and clip planes.
+    ///
+    /// The default implementation has no camera and ignores it.
+    fn set_projection(&mut self, _projection: Projection) {}
+
+    /// Advances the scene's animation by `ticks` fixed ticks of the simulation's `TICK`, and
+    /// draws the following frames `alpha` of a tick past the last one.
+    ///
+    /// The `App` calls this every frame with the ticks its `FixedTimestep` split the frame's
+    /// delta time into. The default implementation has no animation and ignores them.
+    fn tick_scene(&mut self, _ticks: u32, _alpha: f32) {}
+
+    /// Returns the bounds of the content drawn in the scene, used to frame the camera.
+    ///
+    /// The default implementation draws no scene.
+    fn scene_bounds(&self) -> Option<Bounds> {
+        None
+    }
+
+    /// Returns the view-projection matrix of the most recent frame, used to pick and edit
+    /// objects with the cursor.
+    ///
+    /// The default implementation has no camera.
+    fn view_projection(&self) -> Option<crate::math::Mat4> {
+        None
+    }
+
+    /// Releases or flushes resources before the application exits.
+    ///
+    /// The default implementation does nothing.
+    fn shutdown(&mut self) {}
+}
+
+/// The wgpu renderer is the default backend.
+#[async_trait::async_trait(?Send)]
+impl RendererBackend for Renderer {
+    async fn init(
+        window: Arc<Window>,
+        width: u32,
+        height: u32,
+        force_fallback_adapter: bool,
+        config: RendererConfig,
+    ) -> Result<Self, RenderError> {
+        Renderer::new_with_config(window, width, height, force_fallback_adapter, config).await
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        Renderer::resize(self, width, height);
+    }
+
+    fn render(
+        &mut self,
+        #[cfg(feature = "gui")] screen_descriptor: egui_wgpu::ScreenDescriptor,
+        #[cfg(feature = "gui")] paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
+        #[cfg(feature = "gui")] textures_delta: egui::TexturesDelta,
+        delta_time: crate::Duration,
+    ) -> Result<(), RenderError> {
+        self.render_frame(
+            #[cfg(feature = "gui")]
+            screen_descriptor,
+            #[cfg(feature = "gui")]
+            paint_jobs,
+            #[cfg(feature = "gui")]
+            textures_delta,
+            delta_time,
+        )
+    }
+
+    fn reconfigure_surface(&mut self) -> Result<(), RenderError> {
+        Renderer::reconfigure_surface(self)
+    }
+
+    fn set_scale_factor(&mut self, scale_factor: f32) {
+        Renderer::set_scale_factor(self, scale_factor);
+    }
+
+    fn suspend_surface(&mut self) {
+        Renderer::suspend_surface(self);
+    }
+
+    fn resume_surface(&mut self, window: Arc<Window>) -> Result<(), RenderError> {
+        Renderer::resume_surface(self, window)
+    }
+
+    fn as_renderer(&self) -> Option<&Renderer> {
+        Some(self)
+    }
+
+    fn as_renderer_mut(&mut self) -> Option<&mut Renderer> {
+        Some(self)
+    }
+
+    fn set_layer_masks(&mut self, masks: &LayerMasks) {
+        Renderer::set_layer_masks(self, masks);
+    }
+
+    fn set_aspect_lock(&mut self, aspect_lock: AspectLock) {
+        Renderer::set_aspect_lock(self, aspect_lock);
+    }
+
+    fn set_clear_color(&mut self, color: wgpu::Color) {
+        Renderer::set_clear_color(self, color);
+    }
+
+    fn viewport(&self) -> Option<Viewport> {
+        Some(Renderer::viewport(self))
+    }
+
+    fn set_camera(&mut self, view: crate::math::Mat4, eye: crate::math::Vec3) {
+        Renderer::set_camera(self, view, eye);
+    }
+
+    fn set_projection(&mut self, projection: Projection) {
+        Renderer::set_projection(self, projection);
+    }
+
+    fn tick_scene(&mut self, ticks: u32, alpha: f32) {
+        Renderer::tick_scene(self, ticks, alpha);
+    }
+
+    fn scene_bounds(&self) -> Option<Bounds> {
+        Some(Renderer::scene_bounds(self))
+    }
+
+    fn view_projection(&self) -> Option<crate::math::Mat4> {
+        Some(Renderer::view_projection(self))
+    }
+
+    fn shutdown(&mut self) {
+        Renderer::shutdown(self);
+    }
+}
+
+/// A backend that accepts every frame without drawing anything.
+///
+/// The `NullBackend` never touches the GPU, so it can run where no adapter is available, such
+/// as in tests or on CI machines. It only keeps track of the surface size and the number of
+/// frames it was asked to render.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NullBackend {
+    /// The current surface size as `(width, height)`.
+    size: (u32, u32),
+
+    /// The number of frames rendered so far.
+    frames_rendered: u64,
+}
+
+impl NullBackend {
+    /// Creates a `NullBackend` for a surface of `width` x `height` without a window.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            size: (width, height),
+            frames_rendered: 0,
+        }
+    }
+
+    /// Returns the current surface size as `(width, height)`.
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Returns the number of frames rendered so far.
+    pub fn frames_rendered(&self) -> u64 {
+        self.frames_rendered
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl RendererBackend for NullBackend {
+    async fn init(
+        _window: Arc<Window>,
+        width: u32,
+        height: u32,
+        _force_fallback_adapter: bool,
+        _config: RendererConfig,
+    ) -> Result<Self, RenderError> {
+        log::info!("Using the null renderer backend; nothing will be drawn");
+        Ok(Self::new(width, height))
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        self.size = (width, height);
+    }
+
+    fn render(
+        &mut self,
+        #[cfg(feature = "gui")] _screen_descriptor: egui_wgpu::ScreenDescriptor,
+        #[cfg(feature = "gui")] _paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
+        #[cfg(feature = "gui")] _textures_delta: egui::TexturesDelta,
+        _delta_time: crate::Duration,
+    ) -> Result<(), RenderError> {
+        self.frames_rendered += 1;
+        Ok(())
+    }
+}
+
+/// The signature of the closure stored in a `BackendFactory`.
+type BackendInit = dyn Fn(
+    Arc<Window>,
+    u32,
+    u32,
+    bool,
+    RendererConfig,
+)
+    -> LocalBoxFuture<'static, Result<Box<dyn RendererBackend>, RenderError>>;
+
+/// Creates the `RendererBackend` used by the `App`.
+///
+/// A factory erases the concrete backend type, so the `App` can create (and, when its
+/// `ErrorPolicy` asks for it, re-create) a backend without being generic over it. The default
+/// factory creates the wgpu [`Renderer`].
+pub struct BackendFactory(Box<BackendInit>);
+
+impl BackendFactory {
+    /// Returns a factory creating backends of type `B` through [`RendererBackend::init`].
+    pub fn of<B: RendererBackend + 'static>() -> Self {
+        Self(Box::new(
+            |window, width, height, force_fallback_adapter, config| {
+                Box::pin(async move {
+                    let backend =
+                        B::init(window, width, height, force_fallback_adapter, config).await?;
+                    Ok(Box::new(backend) as Box<dyn RendererBackend>)
+                })
+            },
+        ))
+    }
+
+    /// Starts creating a backend for `window`.
+    ///
+    /// The returned future does not borrow the factory, so it can be spawned as a task.
+    pub fn create(
+        &self,
+        window: Arc<Window>,
+        width: u32,
+        height: u32,
+        force_fallback_adapter: bool,
+        config: RendererConfig,
+    ) -> LocalBoxFuture<'static, Result<Box<dyn RendererBackend>, RenderError>> {
+        (self.0)(window, width, height, force_fallback_adapter, config)
+    }
+}
+
+impl Default for BackendFactory {
+    fn default() -> Self {
+        Self::of::<Renderer>()
+    }
+}
@@ -0,0 +1,315 @@
+//! # Background Module
+//!
+//! The `background` module runs long GPU jobs, such as probe baking, mipmap generation, or
+//! particle simulation, alongside rendering without causing frame hitches.
+//!
+//! ## Overview
+//!
+//! - A [`BackgroundJob`] records its work in slices of at most a given number of compute
+//!   workgroups, and reports its progress as a [`TaskProgress`].
+//! - [`SlicedDispatch`] is a job running one compute shader over a large 1D range of
+//!   workgroups, a slice per frame. The shader reads the first workgroup of the current slice
+//!   from a uniform in bind group 1.
+//! - [`BackgroundScheduler`] queues the jobs and runs a slice of the oldest one after every
+//!   frame. Its [`BackgroundBudget`] adapts the slice size to the frame time: slices shrink as
+//!   soon as frames take longer than the target, and grow again while there is headroom.
+//!
+//! wgpu exposes a single queue per device, with no separate lower-priority compute queue, so
+//! the work is always time-sliced. Each slice is submitted in its own command buffer after the
+//! frame's, so the GPU starts on it only once the frame has been queued.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let job = SlicedDispatch::new(
+//!     renderer.device(),
+//!     "Bake Probes",
+//!     &shader_module,
+//!     "bake",
+//!     &probe_bind_group_layout,
+//!     probe_bind_group,
+//!     probe_count.div_ceil(64),
+//! );
+//! renderer.spawn_background_job(Box::new(job));
+//!
+//! for task in renderer.task_progress() {
+//!     println!("{}: {:.0}%", task.label, task.fraction() * 100.0);
+//! }
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::Duration;
+
+/// The progress of a long-running task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskProgress {
+    /// The name of the task, shown in the GUI.
+    pub label: String,
+
+    /// The units of work done so far.
+    pub completed: u64,
+
+    /// The units of work in the whole task.
+    pub total: u64,
+}
+
+impl TaskProgress {
+    /// Returns the fraction of the task that is done, from `0.0` to `1.0`.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.completed as f32 / self.total as f32
+        }
+    }
+}
+
+/// Whether a [`BackgroundJob`] has more work to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    /// More slices remain.
+    Running,
+
+    /// All the work was recorded; the job is dropped once it is submitted.
+    Finished,
+}
+
+/// A long-running GPU job, recorded one slice at a time.
+pub trait BackgroundJob {
+    /// Returns the job's progress.
+    fn progress(&self) -> TaskProgress;
+
+    /// Records the next slice of at most `workgroups` compute workgroups into `encoder`.
+    ///
+    /// Buffer writes through `queue` take effect before the slice runs, since the slice is
+    /// submitted right after this returns.
+    fn record(
+        &mut self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        workgroups: u32,
+    ) -> JobStatus;
+}
+
+/// How much background work is recorded per frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BackgroundBudget {
+    /// The frame time to stay within. Slices shrink when frames take longer.
+    pub target_frame_time: Duration,
+
+    /// The number of workgroups in the next slice.
+    pub workgroups: u32,
+
+    /// The smallest slice, so jobs always make progress.
+    pub min_workgroups: u32,
+
+    /// The largest slice.
+    pub max_workgroups: u32,
+}
+
+impl Default for BackgroundBudget {
+    fn default() -> Self {
+        Self {
+            target_frame_time: Duration::from_micros(16_667),
+            workgroups: 256,
+            min_workgroups: 16,
+            max_workgroups: 65_535,
+        }
+    }
+}
+
+impl BackgroundBudget {
+    /// Adapts the slice size to the last `frame_time`: halves it when the frame took longer
+    /// than the target, and grows it by a quarter when the frame took less than 80% of it.
+    pub fn adapt(&mut self, frame_time: Duration) {
+        if frame_time > self.target_frame_time {
+            self.workgroups /= 2;
+        } else if frame_time.as_secs_f32() < self.target_frame_time.as_secs_f32() * 0.8 {
+            self.workgroups += self.workgroups.div_ceil(4);
+        }
+        self.workgroups = self
+            .workgroups
+            .clamp(self.min_workgroups, self.max_workgroups);
+    }
+}
+
+/// A compute shader dispatched over a large 1D range of workgroups, a slice per frame.
+///
+/// The shader's bind group 0 is supplied by the caller. Bind group 1 holds a single
+/// `var<uniform> first_workgroup: u32` at binding 0, the index of the slice's first workgroup,
+/// which the shader adds to `workgroup_id.x`.
+pub struct SlicedDispatch {
+    /// The name of the job, shown in the GUI.
+    label: String,
+
+    /// The compute pipeline running the shader.
+    pipeline: wgpu::ComputePipeline,
+
+    /// The caller's bind group, bound to group 0.
+    bind_group: wgpu::BindGroup,
+
+    /// The uniform holding the slice's first workgroup.
+    slice_buffer: wgpu::Buffer,
+
+    /// The bind group for `slice_buffer`, bound to group 1.
+    slice_bind_group: wgpu::BindGroup,
+
+    /// The number of workgroups in the whole job.
+    total_workgroups: u32,
+
+    /// The first workgroup of the next slice.
+    next_workgroup: u32,
+
+    /// The device's limit on workgroups per dispatch dimension.
+    max_workgroups: u32,
+}
+
+impl SlicedDispatch {
+    /// Creates a job running `entry_point` of `module` over `total_workgroups` workgroups,
+    /// with `bind_group` (created from `layout`) bound to group 0.
+    pub fn new(
+        device: &wgpu::Device,
+        label: &str,
+        module: &wgpu::ShaderModule,
+        entry_point: &str,
+        layout: &wgpu::BindGroupLayout,
+        bind_group: wgpu::BindGroup,
+        total_workgroups: u32,
+    ) -> Self {
+        let slice_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Background Slice Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let slice_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Background Slice Buffer"),
+            size: std::mem::size_of::<u32>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let slice_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Background Slice Bind Group"),
+            layout: &slice_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: slice_buffer.as_entire_binding(),
+            }],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(label),
+            bind_group_layouts: &[layout, &slice_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            module,
+            entry_point: Some(entry_point),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Self {
+            label: label.to_owned(),
+            pipeline,
+            bind_group,
+            slice_buffer,
+            slice_bind_group,
+            total_workgroups,
+            next_workgroup: 0,
+            max_workgroups: device.limits().max_compute_workgroups_per_dimension,
+        }
+    }
+}
+
+impl BackgroundJob for SlicedDispatch {
+    fn progress(&self) -> TaskProgress {
+        TaskProgress {
+            label: self.label.clone(),
+            completed: self.next_workgroup.into(),
+            total: self.total_workgroups.into(),
+        }
+    }
+
+    fn record(
+        &mut self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        workgroups: u32,
+    ) -> JobStatus {
+        let remaining = self.total_workgroups - self.next_workgroup;
+        let count = workgroups.min(self.max_workgroups).min(remaining);
+        if count > 0 {
+            queue.write_buffer(&self.slice_buffer, 0, &self.next_workgroup.to_le_bytes());
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some(&self.label),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_group, &[]);
+            pass.set_bind_group(1, &self.slice_bind_group, &[]);
+            pass.dispatch_workgroups(count, 1, 1);
+            self.next_workgroup += count;
+        }
+
+        if self.next_workgroup >= self.total_workgroups {
+            JobStatus::Finished
+        } else {
+            JobStatus::Running
+        }
+    }
+}
+
+/// Queues background jobs and runs a slice of the oldest one after every frame.
+#[derive(Default)]
+pub struct BackgroundScheduler {
+    /// The queued jobs, oldest first.
+    jobs: VecDeque<Box<dyn BackgroundJob>>,
+
+    /// How much work is recorded per frame.
+    budget: BackgroundBudget,
+}
+
+impl BackgroundScheduler {
+    /// Queues `job` behind the jobs already queued.
+    pub fn spawn(&mut self, job: Box<dyn BackgroundJob>) {
+        self.jobs.push_back(job);
+    }
+
+    /// Returns the progress of every queued job, oldest first.
+    pub fn progress(&self) -> Vec<TaskProgress> {
+        self.jobs.iter().map(|job| job.progress()).collect()
+    }
+
+    /// Returns the budget slices are sized by, to tune its target frame time and bounds.
+    pub fn budget_mut(&mut self) -> &mut BackgroundBudget {
+        &mut self.budget
+    }
+
+    /// Adapts the budget to the last `frame_time`, then records and submits a slice of the
+    /// oldest job. Call this after the frame has been submitted.
+    pub fn run(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, frame_time: Duration) {
+        let Some(job) = self.jobs.front_mut() else {
+            return;
+        };
+        self.budget.adapt(frame_time);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Background Encoder"),
+        });
+        let status = job.record(queue, &mut encoder, self.budget.workgroups);
+        queue.submit(std::iter::once(encoder.finish()));
+        if status == JobStatus::Finished {
+            self.jobs.pop_front();
+        }
+    }
+}
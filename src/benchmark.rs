@@ -0,0 +1,411 @@
+//! # Benchmark Module
+//!
+//! The `benchmark` module runs a scripted workload for a fixed time and reports how fast each
+//! step of it rendered, for comparing backends, adapters, and driver versions on the same
+//! machine.
+//!
+//! ## Overview
+//!
+//! - [`Benchmark`] describes a run: its duration, its number of stages, and the report's
+//!   path. The workload grows stage by stage: stage `k` draws the scene's meshes on a grid of
+//!   `2^k` × `2^k` instances, so the last of the [`Benchmark::DEFAULT_STAGES`] stages draws
+//!   16384 of them. The first [`Benchmark::WARMUP`] of every stage is not measured, leaving
+//!   out the upload of the new instances and the GPU timings still in flight.
+//! - [`BenchmarkRun`] is a run in progress. The `App` hands it the time and statistics of
+//!   every frame and sets the instances it returns when a stage begins.
+//! - [`BenchmarkReport`] holds every measured frame, with its frame, CPU, and GPU times, and a
+//!   [`BenchmarkStage`] summary per stage. It is written as CSV, one row per frame, or, for a
+//!   path ending in `.json`, as JSON with the adapter and the summaries too.
+//!
+//! The `--bench` option of the binary runs a benchmark without vsync and exits once the
+//! report is written, printing the summaries as a Markdown table:
+//!
+//! ```sh
+//! $ cargo run --release -- --bench bench.csv --backend vulkan
+//! $ cargo run --release -- --bench bench.json --bench-duration 32
+//! ```
+//!
+//! The GPU times are those of the latest frame measured by the `GpuTimer`, a few frames behind
+//! the frame times, and missing on adapters without timestamp queries.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut run = BenchmarkRun::new(Benchmark::new("bench.csv"));
+//!
+//! // Every frame:
+//! if let Some(instances) = run.frame(delta_time, &renderer.frame_stats()) {
+//!     renderer.set_scene_instances(&instances);
+//! }
+//! if run.is_finished() {
+//!     let report = run.report(renderer.gpu_info().as_ref());
+//!     report.write(&run.benchmark().output)?;
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::capabilities::GpuInfo;
+use crate::frame_stats::FrameStatsSummary;
+use crate::instancing::{grid_instances, GridDemo, Instance};
+use crate::quality::FrameTimeHistory;
+
+/// Errors produced while writing a benchmark report.
+#[derive(Debug, thiserror::Error)]
+pub enum BenchmarkError {
+    /// The report could not be serialized as JSON.
+    #[error("failed to serialize the benchmark report: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The report could not be written.
+    #[error("failed to write '{path}': {source}")]
+    Io {
+        /// The path of the report.
+        path: String,
+
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+}
+
+/// The workload and duration of a benchmark, and where its report goes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Benchmark {
+    /// The path of the report: JSON if it ends in `.json`, CSV otherwise.
+    pub output: PathBuf,
+
+    /// How long the whole benchmark runs.
+    pub duration: crate::Duration,
+
+    /// The number of stages, each running for an equal part of the duration.
+    pub stages: u32,
+}
+
+impl Benchmark {
+    /// The duration unless set with [`Benchmark::with_duration`].
+    pub const DEFAULT_DURATION: crate::Duration = crate::Duration::from_secs(16);
+
+    /// The number of stages unless set with [`Benchmark::with_stages`].
+    pub const DEFAULT_STAGES: u32 = 8;
+
+    /// The most stages. The last one's grid of 512 × 512 instances already fills 20 MiB.
+    pub const MAX_STAGES: u32 = 10;
+
+    /// The time at the beginning of every stage that is not measured.
+    pub const WARMUP: crate::Duration = crate::Duration::from_millis(250);
+
+    /// Creates a benchmark of the default duration and stages, reported to `output`.
+    pub fn new(output: impl Into<PathBuf>) -> Self {
+        Self {
+            output: output.into(),
+            duration: Self::DEFAULT_DURATION,
+            stages: Self::DEFAULT_STAGES,
+        }
+    }
+
+    /// Sets how long the whole benchmark runs.
+    pub fn with_duration(mut self, duration: crate::Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Sets the number of stages, from one to [`Benchmark::MAX_STAGES`].
+    pub fn with_stages(mut self, stages: u32) -> Self {
+        self.stages = stages.clamp(1, Self::MAX_STAGES);
+        self
+    }
+
+    /// Returns the number of instances along each side of the grid drawn in `stage`.
+    pub fn grid_side(stage: u32) -> u32 {
+        1 << stage.min(Self::MAX_STAGES - 1)
+    }
+
+    /// Returns how long each stage runs.
+    pub fn stage_duration(&self) -> crate::Duration {
+        self.duration / self.stages.max(1)
+    }
+
+    /// Returns the stage running `elapsed` after the start, or `None` once the benchmark has
+    /// ended.
+    pub fn stage_at(&self, elapsed: crate::Duration) -> Option<u32> {
+        let stage_duration = self.stage_duration().as_secs_f64();
+        if elapsed >= self.duration || stage_duration <= 0.0 {
+            return None;
+        }
+        Some(((elapsed.as_secs_f64() / stage_duration) as u32).min(self.stages - 1))
+    }
+}
+
+/// One measured frame of a benchmark.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct BenchmarkSample {
+    /// The time since the start of the benchmark, in seconds.
+    pub time_s: f64,
+
+    /// The stage the frame was rendered in.
+    pub stage: u32,
+
+    /// The number of instances drawn.
+    pub instances: u32,
+
+    /// The time since the previous frame, in milliseconds.
+    pub frame_ms: f64,
+
+    /// The CPU time of recording and submitting the frame, in milliseconds.
+    pub cpu_ms: f64,
+
+    /// The GPU time of the latest measured frame, in milliseconds, or `None` without
+    /// timestamp queries.
+    pub gpu_ms: Option<f64>,
+}
+
+/// The frame time statistics of one stage of a benchmark.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct BenchmarkStage {
+    /// The number of instances drawn.
+    pub instances: u32,
+
+    /// The number of measured frames.
+    pub frames: usize,
+
+    /// The mean frame rate, in frames per second.
+    pub fps: f64,
+
+    /// The mean time between frames, in milliseconds.
+    pub mean_ms: f64,
+
+    /// The median time between frames, in milliseconds.
+    pub p50_ms: f64,
+
+    /// The time between frames that 95% of the frames took at most, in milliseconds.
+    pub p95_ms: f64,
+
+    /// The time between frames that 99% of the frames took at most, in milliseconds.
+    pub p99_ms: f64,
+
+    /// The mean CPU time of a frame, in milliseconds.
+    pub cpu_ms: f64,
+
+    /// The mean GPU time of a frame, in milliseconds, or `None` without timestamp queries.
+    pub gpu_ms: Option<f64>,
+}
+
+/// The measured frames of a finished benchmark, and the adapter they were rendered on.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct BenchmarkReport {
+    /// The adapter's name, or `"unknown"` if the backend did not report it.
+    pub adapter: String,
+
+    /// The adapter's backend, such as `"Vulkan"`.
+    pub backend: String,
+
+    /// The adapter's driver and its version.
+    pub driver: String,
+
+    /// The summary of every stage, in order.
+    pub stages: Vec<BenchmarkStage>,
+
+    /// Every measured frame, in order.
+    pub samples: Vec<BenchmarkSample>,
+}
+
+impl BenchmarkReport {
+    /// Returns the report as CSV: a header, then one row per measured frame. Frames without a
+    /// GPU time leave its column empty.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time_s,stage,instances,frame_ms,cpu_ms,gpu_ms\n");
+        for sample in &self.samples {
+            let gpu_ms = sample
+                .gpu_ms
+                .map(|gpu| format!("{gpu:.4}"))
+                .unwrap_or_default();
+            csv.push_str(&format!(
+                "{:.4},{},{},{:.4},{:.4},{gpu_ms}\n",
+                sample.time_s, sample.stage, sample.instances, sample.frame_ms, sample.cpu_ms
+            ));
+        }
+        csv
+    }
+
+    /// Returns the report as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BenchmarkError::Json` if the report cannot be serialized.
+    pub fn to_json(&self) -> Result<String, BenchmarkError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Returns the stage summaries as a Markdown table, headed by the adapter.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!(
+            "# Benchmark\n\n{} ({}, {})\n\n\
+             | Instances | Frames | FPS | Mean | P50 | P95 | P99 | CPU | GPU |\n\
+             | ---: | ---: | ---: | ---: | ---: | ---: | ---: | ---: | ---: |\n",
+            self.adapter, self.backend, self.driver
+        );
+        let ms = |milliseconds: f64| format!("{milliseconds:.2} ms");
+        for stage in &self.stages {
+            let gpu = stage.gpu_ms.map_or_else(|| "n/a".to_string(), ms);
+            markdown.push_str(&format!(
+                "| {} | {} | {:.1} | {} | {} | {} | {} | {} | {gpu} |\n",
+                stage.instances,
+                stage.frames,
+                stage.fps,
+                ms(stage.mean_ms),
+                ms(stage.p50_ms),
+                ms(stage.p95_ms),
+                ms(stage.p99_ms),
+                ms(stage.cpu_ms),
+            ));
+        }
+        markdown
+    }
+
+    /// Writes the report to `path`: as JSON if it ends in `.json`, as CSV otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `BenchmarkError` if the report cannot be serialized or written.
+    pub fn write(&self, path: &Path) -> Result<(), BenchmarkError> {
+        let json = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let contents = if json { self.to_json()? } else { self.to_csv() };
+        std::fs::write(path, contents).map_err(|source| BenchmarkError::Io {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+}
+
+/// A benchmark in progress.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkRun {
+    /// The benchmark being run.
+    benchmark: Benchmark,
+
+    /// The time since the benchmark started.
+    elapsed: crate::Duration,
+
+    /// The stage whose instances were set last, or `None` before the first frame.
+    stage: Option<u32>,
+
+    /// The measured frames.
+    samples: Vec<BenchmarkSample>,
+}
+
+impl BenchmarkRun {
+    /// Starts running `benchmark`.
+    pub fn new(benchmark: Benchmark) -> Self {
+        Self {
+            benchmark,
+            elapsed: crate::Duration::ZERO,
+            stage: None,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Returns the benchmark being run.
+    pub fn benchmark(&self) -> &Benchmark {
+        &self.benchmark
+    }
+
+    /// Returns whether the benchmark has run for its whole duration.
+    pub fn is_finished(&self) -> bool {
+        self.benchmark.stage_at(self.elapsed).is_none()
+    }
+
+    /// Records the previous frame, which came `frame_time` after the one before it, with the
+    /// renderer's latest `stats`, and advances the benchmark by it.
+    ///
+    /// Returns the instances to draw from now on when a stage begins, and `None` otherwise.
+    pub fn frame(
+        &mut self,
+        frame_time: crate::Duration,
+        stats: &FrameStatsSummary,
+    ) -> Option<Vec<Instance>> {
+        if let Some(stage) = self.stage {
+            let stage_start = self.benchmark.stage_duration() * stage;
+            if self.elapsed >= stage_start + Benchmark::WARMUP {
+                let side = Benchmark::grid_side(stage);
+                self.samples.push(BenchmarkSample {
+                    time_s: self.elapsed.as_secs_f64(),
+                    stage,
+                    instances: side * side,
+                    frame_ms: frame_time.as_secs_f64() * 1e3,
+                    cpu_ms: stats.latest_cpu.as_secs_f64() * 1e3,
+                    gpu_ms: stats.gpu.map(|gpu| gpu.as_secs_f64() * 1e3),
+                });
+            }
+            self.elapsed += frame_time;
+        }
+
+        let stage = self.benchmark.stage_at(self.elapsed)?;
+        if self.stage == Some(stage) {
+            return None;
+        }
+        self.stage = Some(stage);
+        let side = Benchmark::grid_side(stage);
+        log::info!("Benchmark stage {}: {} instances", stage + 1, side * side);
+        Some(grid_instances(side, GridDemo::SPACING))
+    }
+
+    /// Returns the report of the frames measured so far, rendered on the adapter of `gpu`.
+    pub fn report(&self, gpu: Option<&GpuInfo>) -> BenchmarkReport {
+        let stages = (0..self.benchmark.stages)
+            .filter_map(|stage| self.stage_summary(stage))
+            .collect();
+        let (adapter, backend, driver) = gpu.map_or_else(
+            || ("unknown".to_string(), "unknown".to_string(), String::new()),
+            |gpu| {
+                let info = &gpu.adapter;
+                (
+                    info.name.clone(),
+                    format!("{:?}", info.backend),
+                    format!("{} {}", info.driver, info.driver_info)
+                        .trim()
+                        .to_string(),
+                )
+            },
+        );
+        BenchmarkReport {
+            adapter,
+            backend,
+            driver,
+            stages,
+            samples: self.samples.clone(),
+        }
+    }
+
+    /// Returns the summary of the frames measured in `stage`, or `None` if there are none.
+    fn stage_summary(&self, stage: u32) -> Option<BenchmarkStage> {
+        let samples: Vec<_> = self
+            .samples
+            .iter()
+            .filter(|sample| sample.stage == stage)
+            .collect();
+        let first = samples.first()?;
+        let mut frame_times = FrameTimeHistory::new(samples.len());
+        for sample in &samples {
+            frame_times.push(crate::Duration::from_secs_f64(sample.frame_ms / 1e3));
+        }
+        let milliseconds =
+            |duration: Option<crate::Duration>| duration.unwrap_or_default().as_secs_f64() * 1e3;
+        let mean_ms = milliseconds(frame_times.mean());
+        let count = samples.len() as f64;
+        let gpu_times: Vec<_> = samples.iter().filter_map(|sample| sample.gpu_ms).collect();
+        Some(BenchmarkStage {
+            instances: first.instances,
+            frames: samples.len(),
+            fps: if mean_ms > 0.0 { 1e3 / mean_ms } else { 0.0 },
+            mean_ms,
+            p50_ms: milliseconds(frame_times.percentile(0.5)),
+            p95_ms: milliseconds(frame_times.percentile(0.95)),
+            p99_ms: milliseconds(frame_times.percentile(0.99)),
+            cpu_ms: samples.iter().map(|sample| sample.cpu_ms).sum::<f64>() / count,
+            gpu_ms: (!gpu_times.is_empty())
+                .then(|| gpu_times.iter().sum::<f64>() / gpu_times.len() as f64),
+        })
+    }
+}
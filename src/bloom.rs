@@ -0,0 +1,407 @@
+//! # Bloom Module
+//!
+//! The `bloom` module makes the brightest parts of the HDR scene glow, blurring their light
+//! over their surroundings as a camera lens would.
+//!
+//! ## Overview
+//!
+//! [`Bloom::render`] runs a chain of fullscreen passes over the mips of a half-resolution
+//! [`PostProcess::HDR_FORMAT`] texture:
+//!
+//! 1. **Prefilter**: Keeps the pixels of the HDR scene brighter than
+//!    [`PostEffects::bloom_threshold`], easing in those just below it, in the first mip.
+//! 2. **Downsample**: Blurs each mip into the next, half as large, with a box filter, down to
+//!    [`Bloom::MAX_MIPS`] mips.
+//! 3. **Upsample**: Blurs each mip with a tent filter and adds it onto the next larger one,
+//!    from the smallest mip back to the first.
+//!
+//! The first mip then holds glows of every size, which the post-processing pass adds to the
+//! scene, scaled by [`PostEffects::bloom_intensity`], before tonemapping.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let bloom = Bloom::new(&device, post_process.hdr_texture_view(), width, height, None);
+//! // ... once per frame, after the scene pass:
+//! bloom.update(&queue, &effects);
+//! bloom.render(&mut encoder, None);
+//! // ... then sample `bloom.view()` with `bloom.sampler()`.
+//! ```
+
+use crate::deletion_queue::DeletionQueue;
+use crate::post_process::{PostEffects, PostProcess};
+use crate::shader_bindings::bloom as bloom_bindings;
+
+/// The WGSL source of the bloom passes.
+const BLOOM_SHADER_SOURCE: &str = include_str!("bloom.wgsl");
+
+/// The bloom settings as read by the shader.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct BloomUniform {
+    /// The brightness above which pixels glow.
+    pub threshold: f32,
+
+    /// The width of the soft transition around the threshold, half the threshold.
+    pub knee: f32,
+
+    /// Pads the struct to 16 bytes.
+    pub padding: [f32; 2],
+}
+
+impl BloomUniform {
+    /// Reduces the bloom settings of `effects` to the values the shader uses.
+    pub fn new(effects: &PostEffects) -> Self {
+        let threshold = effects.bloom_threshold.max(0.0);
+        Self {
+            threshold,
+            knee: threshold * 0.5,
+            padding: [0.0; 2],
+        }
+    }
+}
+
+/// The pipelines, mip chain, and bind groups of the bloom effect.
+#[derive(Debug)]
+pub struct Bloom {
+    /// Extracts the bright pixels of the HDR scene into the first mip.
+    prefilter_pipeline: wgpu::RenderPipeline,
+
+    /// Blurs a mip into the next smaller one.
+    downsample_pipeline: wgpu::RenderPipeline,
+
+    /// Blurs a mip and adds it onto the next larger one.
+    upsample_pipeline: wgpu::RenderPipeline,
+
+    /// The layout of the bind groups, needed to recreate them when resizing.
+    bind_group_layout: wgpu::BindGroupLayout,
+
+    /// The buffer holding the `BloomUniform`.
+    buffer: wgpu::Buffer,
+
+    /// Samples the mips bilinearly, clamped to their edges.
+    sampler: wgpu::Sampler,
+
+    /// The mip chain and the bind groups reading it, recreated when resizing.
+    target: BloomTarget,
+}
+
+/// The bloom texture of a surface size, and the bind groups reading the passes' sources.
+#[derive(Debug)]
+struct BloomTarget {
+    /// The half-resolution texture holding the mip chain.
+    texture: wgpu::Texture,
+
+    /// A view of each mip, rendered into by the passes and read by the next ones.
+    mip_views: Vec<wgpu::TextureView>,
+
+    /// Exposes the HDR scene to the prefilter pass.
+    hdr_bind_group: wgpu::BindGroup,
+
+    /// Exposes each mip to the pass reading it.
+    mip_bind_groups: Vec<wgpu::BindGroup>,
+}
+
+impl Bloom {
+    /// The most mips the glow is blurred over. More mips spread the glow further.
+    pub const MAX_MIPS: u32 = 6;
+
+    /// Creates the bloom's pipelines, compiled through `cache` if one is given, and a mip
+    /// chain for a surface of `width` x `height` pixels, whose HDR scene is `hdr_view`.
+    pub fn new(
+        device: &wgpu::Device,
+        hdr_view: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Bloom Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(BLOOM_SHADER_SOURCE)),
+        });
+
+        let bind_group_layout = bloom_bindings::BindGroup0::create_layout(device);
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Bloom Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // The upsample passes add their result onto the downsampled contents of the target.
+        let additive = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        };
+        let create_pipeline = |label, entry_point, blend| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader_module,
+                    entry_point: Some("bloom_vertex"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader_module,
+                    entry_point: Some(entry_point),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: PostProcess::HDR_FORMAT,
+                        blend,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                multiview: None,
+                cache,
+            })
+        };
+        let prefilter_pipeline =
+            create_pipeline("Bloom Prefilter Pipeline", "prefilter_fragment", None);
+        let downsample_pipeline =
+            create_pipeline("Bloom Downsample Pipeline", "downsample_fragment", None);
+        let upsample_pipeline = create_pipeline(
+            "Bloom Upsample Pipeline",
+            "upsample_fragment",
+            Some(wgpu::BlendState {
+                color: additive,
+                alpha: additive,
+            }),
+        );
+
+        let buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Bloom Uniform Buffer"),
+                contents: bytemuck::bytes_of(&BloomUniform::new(&PostEffects::default())),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Bloom Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let target = BloomTarget::new(
+            device,
+            &bind_group_layout,
+            &buffer,
+            &sampler,
+            hdr_view,
+            width,
+            height,
+        );
+
+        Self {
+            prefilter_pipeline,
+            downsample_pipeline,
+            upsample_pipeline,
+            bind_group_layout,
+            buffer,
+            sampler,
+            target,
+        }
+    }
+
+    /// Returns the view of the first mip, holding the glow once the passes ran.
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.target.mip_views[0]
+    }
+
+    /// Returns the sampler to read [`Bloom::view`] with.
+    pub fn sampler(&self) -> &wgpu::Sampler {
+        &self.sampler
+    }
+
+    /// Recreates the mip chain for a surface of `width` x `height` pixels, whose HDR scene is
+    /// now `hdr_view`. The replaced resources are retired to `deletion_queue`.
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        hdr_view: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+        deletion_queue: &mut DeletionQueue,
+    ) {
+        let target = BloomTarget::new(
+            device,
+            &self.bind_group_layout,
+            &self.buffer,
+            &self.sampler,
+            hdr_view,
+            width,
+            height,
+        );
+        let replaced = std::mem::replace(&mut self.target, target);
+        deletion_queue.retire(replaced.hdr_bind_group);
+        for bind_group in replaced.mip_bind_groups {
+            deletion_queue.retire(bind_group);
+        }
+        for view in replaced.mip_views {
+            deletion_queue.retire(view);
+        }
+        deletion_queue.retire(replaced.texture);
+    }
+
+    /// Uploads the threshold of `effects` for the next passes.
+    pub fn update(&self, queue: &wgpu::Queue, effects: &PostEffects) {
+        queue.write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::bytes_of(&BloomUniform::new(effects)),
+        );
+    }
+
+    /// Encodes the prefilter, downsample, and upsample passes, leaving the glow in the first
+    /// mip. The beginning of `timestamp_writes` is written by the first pass, and its end by
+    /// the last, so they time the whole sequence.
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        timestamp_writes: Option<wgpu::RenderPassTimestampWrites>,
+    ) {
+        let target = &self.target;
+        let mips = target.mip_views.len();
+        let (first_writes, last_writes) = match timestamp_writes {
+            Some(writes) if mips > 1 => (
+                Some(wgpu::RenderPassTimestampWrites {
+                    end_of_pass_write_index: None,
+                    ..writes
+                }),
+                Some(wgpu::RenderPassTimestampWrites {
+                    beginning_of_pass_write_index: None,
+                    ..writes
+                }),
+            ),
+            writes => (writes, None),
+        };
+        self.encode_pass(
+            encoder,
+            &self.prefilter_pipeline,
+            &target.hdr_bind_group,
+            &target.mip_views[0],
+            true,
+            first_writes,
+        );
+        for mip in 1..mips {
+            self.encode_pass(
+                encoder,
+                &self.downsample_pipeline,
+                &target.mip_bind_groups[mip - 1],
+                &target.mip_views[mip],
+                true,
+                None,
+            );
+        }
+        for mip in (0..mips - 1).rev() {
+            self.encode_pass(
+                encoder,
+                &self.upsample_pipeline,
+                &target.mip_bind_groups[mip + 1],
+                &target.mip_views[mip],
+                false,
+                if mip == 0 { last_writes.clone() } else { None },
+            );
+        }
+    }
+
+    /// Encodes one fullscreen pass of `pipeline` reading `source` into `target`, which is
+    /// cleared first if `clear` is set, and loaded otherwise.
+    fn encode_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        source: &wgpu::BindGroup,
+        target: &wgpu::TextureView,
+        clear: bool,
+        timestamp_writes: Option<wgpu::RenderPassTimestampWrites>,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Bloom Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: if clear {
+                        wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)
+                    } else {
+                        wgpu::LoadOp::Load
+                    },
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, source, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+impl BloomTarget {
+    /// Creates a half-resolution mip chain for a surface of `width` x `height` pixels, and the
+    /// bind groups reading `hdr_view` and each mip.
+    fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        buffer: &wgpu::Buffer,
+        sampler: &wgpu::Sampler,
+        hdr_view: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let (width, height) = ((width / 2).max(1), (height / 2).max(1));
+        let mip_level_count = width.min(height).ilog2().clamp(1, Bloom::MAX_MIPS);
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Bloom Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: PostProcess::HDR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let mip_views: Vec<_> = (0..mip_level_count)
+            .map(|mip| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some("Bloom Mip View"),
+                    base_mip_level: mip,
+                    mip_level_count: Some(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        let bind_group = |view| {
+            bloom_bindings::BindGroup0 {
+                settings: buffer.as_entire_buffer_binding(),
+                source: view,
+                source_sampler: sampler,
+            }
+            .create(device, layout)
+        };
+        let hdr_bind_group = bind_group(hdr_view);
+        let mip_bind_groups = mip_views.iter().map(bind_group).collect();
+        Self {
+            texture,
+            mip_views,
+            hdr_bind_group,
+            mip_bind_groups,
+        }
+    }
+}
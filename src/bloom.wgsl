@@ -0,0 +1,82 @@
+// Bloom.
+//
+// The prefilter pass keeps the parts of the HDR scene brighter than the threshold, at half
+// resolution, in the first mip of the bloom texture. The downsample passes then blur it into
+// ever smaller mips, and the upsample passes add each mip, blurred once more, back onto the
+// next larger one, so the first mip ends up holding the glow of every size.
+
+struct BloomSettings {
+    // The brightness above which pixels glow.
+    threshold: f32,
+    // The width of the soft transition around the threshold.
+    knee: f32,
+    padding: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> settings: BloomSettings;
+
+// The texture read by the pass: the HDR scene, or another mip of the bloom texture.
+@group(0) @binding(1)
+var source: texture_2d<f32>;
+
+@group(0) @binding(2)
+var source_sampler: sampler;
+
+struct BloomOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// Draws a single triangle covering the whole target.
+@vertex
+fn bloom_vertex(@builtin(vertex_index) vertex_index: u32) -> BloomOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: BloomOutput;
+    out.position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+// Averages four bilinear samples around `uv`, a box filter over 4x4 source texels.
+fn box_filter(uv: vec2<f32>) -> vec3<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source));
+    let a = textureSampleLevel(source, source_sampler, uv + texel * vec2<f32>(-1.0, -1.0), 0.0);
+    let b = textureSampleLevel(source, source_sampler, uv + texel * vec2<f32>(1.0, -1.0), 0.0);
+    let c = textureSampleLevel(source, source_sampler, uv + texel * vec2<f32>(-1.0, 1.0), 0.0);
+    let d = textureSampleLevel(source, source_sampler, uv + texel * vec2<f32>(1.0, 1.0), 0.0);
+    return (a.rgb + b.rgb + c.rgb + d.rgb) * 0.25;
+}
+
+@fragment
+fn prefilter_fragment(in: BloomOutput) -> @location(0) vec4<f32> {
+    let color = max(box_filter(in.uv), vec3<f32>(0.0));
+
+    // A quadratic curve eases pixels within the knee of the threshold into the glow.
+    let brightness = max(color.r, max(color.g, color.b));
+    var soft = clamp(brightness - settings.threshold + settings.knee, 0.0, 2.0 * settings.knee);
+    soft = soft * soft / (4.0 * settings.knee + 0.00001);
+    let contribution = max(soft, brightness - settings.threshold) / max(brightness, 0.00001);
+    return vec4<f32>(color * contribution, 1.0);
+}
+
+@fragment
+fn downsample_fragment(in: BloomOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(box_filter(in.uv), 1.0);
+}
+
+// Blurs the smaller mip with a 3x3 tent filter. The pipeline adds the result onto the target.
+@fragment
+fn upsample_fragment(in: BloomOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source));
+    var sum = vec3<f32>(0.0);
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let weight = f32((2 - abs(x)) * (2 - abs(y)));
+            let offset = texel * vec2<f32>(f32(x), f32(y));
+            sum += textureSampleLevel(source, source_sampler, in.uv + offset, 0.0).rgb * weight;
+        }
+    }
+    return vec4<f32>(sum / 16.0, 1.0);
+}
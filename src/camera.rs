@@ -0,0 +1,870 @@
+//! # Camera Module
+//!
+//! The `camera` module provides the orbit, arcball, and first-person (FPS) cameras that
+//! produce the scene's view matrix, and the input settings controlling them.
+//!
+//! ## Overview
+//!
+//! - [`OrbitCamera`] circles a target point: dragging rotates around it and scrolling zooms.
+//! - [`ArcballCamera`] also circles a target point, but turns freely: dragging rotates around
+//!   the screen axis perpendicular to the drag, so it passes over the poles and can roll,
+//!   where the orbit camera stops short of straight up or down.
+//! - [`FpsCamera`] flies freely: dragging looks around and `W`/`A`/`S`/`D` (plus `Q`/`E` for
+//!   down/up) move it.
+//! - [`Camera`] owns all three and switches between them with [`CameraMode`]. Input moves a *goal*
+//!   pose, and the visible pose follows the goal with exponential smoothing, which gives the
+//!   cameras their inertial, damped feel.
+//! - [`CameraSettings`] holds the per-axis sensitivity, invert options, and smoothing edited in
+//!   the input settings.
+//! - [`Projection`] holds the field of view and clip planes. They are sanitized before use, so
+//!   extreme values entered in the GUI cannot break the depth buffer's precision, and the clip
+//!   planes can be derived from the scene's [`Bounds`] instead.
+//!
+//! ## Scene Bounds
+//!
+//! The cameras know the bounds of the loaded content. [`Camera::frame`] moves the orbit camera
+//! to look at the whole content, which the `App` does whenever content is loaded (the bounds
+//! change). The zoom range, the orbit target, and the FPS camera's position are clamped
+//! relative to the bounds, so the content cannot be lost off-screen or zoomed into oblivion.
+//!
+//! ## Frame-Rate Independence
+//!
+//! Each frame, the visible pose moves the fraction [`smoothing_factor`] of the way towards the
+//! goal. The fraction is `1 - exp(-dt / smoothing)`, so two frames of `dt` move exactly as far
+//! as one frame of `2 * dt`, and the motion looks the same at 30 and 144 frames per second.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! camera_input.read_actions(&action_map, &input_state);
+//! camera.update(&camera_input, &settings, delta_time);
+//! camera_input.end_frame();
+//!
+//! renderer.set_camera(camera.view(), camera.eye());
+//!
+//! if let Some(bounds) = renderer.scene_bounds() {
+//!     camera.frame(&bounds, projection.field_of_view);
+//! }
+//! ```
+
+/// The world's up direction.
+fn up() -> crate::math::Vec3 {
+    crate::math::up()
+}
+
+/// Returns the unit direction for a `yaw` (around Y, from +Z towards +X) and `pitch` (upwards).
+fn direction(yaw: f32, pitch: f32) -> crate::math::Vec3 {
+    crate::math::vec3(
+        pitch.cos() * yaw.sin(),
+        pitch.sin(),
+        pitch.cos() * yaw.cos(),
+    )
+}
+
+/// Returns the fraction of the remaining distance to cover in a frame of `delta_time` seconds.
+///
+/// `smoothing` is the time constant in seconds: after `smoothing` seconds about 63% of the
+/// distance is covered. A `smoothing` of zero (or less) disables smoothing.
+pub fn smoothing_factor(smoothing: f32, delta_time: f32) -> f32 {
+    if smoothing <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-delta_time / smoothing).exp()
+    }
+}
+
+/// Which camera drives the view.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CameraMode {
+    /// The [`OrbitCamera`].
+    #[default]
+    Orbit,
+
+    /// The [`ArcballCamera`].
+    Arcball,
+
+    /// The [`FpsCamera`].
+    Fps,
+}
+
+impl CameraMode {
+    /// Every camera mode, in the order they are offered in the user interface.
+    pub const ALL: [CameraMode; 3] = [CameraMode::Orbit, CameraMode::Arcball, CameraMode::Fps];
+
+    /// Returns the name shown in the user interface.
+    pub fn label(self) -> &'static str {
+        match self {
+            CameraMode::Orbit => "Orbit",
+            CameraMode::Arcball => "Arcball",
+            CameraMode::Fps => "FPS",
+        }
+    }
+}
+
+/// How input moves the cameras.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraSettings {
+    /// The horizontal rotation per dragged pixel, in degrees.
+    pub sensitivity_x: f32,
+
+    /// The vertical rotation per dragged pixel, in degrees.
+    pub sensitivity_y: f32,
+
+    /// Reverses the horizontal rotation.
+    pub invert_x: bool,
+
+    /// Reverses the vertical rotation.
+    pub invert_y: bool,
+
+    /// The time constant of the camera damping, in seconds. Zero follows input immediately.
+    pub smoothing: f32,
+
+    /// The relative zoom per scrolled line of the orbit and arcball cameras.
+    pub zoom_sensitivity: f32,
+
+    /// The speed of the FPS camera, in world units per second.
+    pub move_speed: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            sensitivity_x: 0.3,
+            sensitivity_y: 0.3,
+            invert_x: false,
+            invert_y: false,
+            smoothing: 0.08,
+            zoom_sensitivity: 0.1,
+            move_speed: 3.0,
+        }
+    }
+}
+
+/// An axis-aligned bounding box in world space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    /// The corner with the smallest coordinates.
+    pub min: crate::math::Vec3,
+
+    /// The corner with the largest coordinates.
+    pub max: crate::math::Vec3,
+}
+
+impl Bounds {
+    /// The smallest radius returned by [`Bounds::radius`].
+    const MIN_RADIUS: f32 = 0.01;
+
+    /// Returns the bounds of `points`, or `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = crate::math::Vec3>) -> Option<Self> {
+        points
+            .into_iter()
+            .fold(None, |bounds: Option<Self>, point| {
+                Some(match bounds {
+                    Some(bounds) => Self {
+                        min: crate::math::min(&bounds.min, &point),
+                        max: crate::math::max(&bounds.max, &point),
+                    },
+                    None => Self {
+                        min: point,
+                        max: point,
+                    },
+                })
+            })
+    }
+
+    /// Returns the bounds enclosing both `self` and `other`.
+    pub fn union(&self, other: &Bounds) -> Self {
+        Self {
+            min: crate::math::min(&self.min, &other.min),
+            max: crate::math::max(&self.max, &other.max),
+        }
+    }
+
+    /// Returns the center of the box.
+    pub fn center(&self) -> crate::math::Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns the radius of the sphere around the box, at least a small positive value so
+    /// that the bounds of a single point can still be framed.
+    pub fn radius(&self) -> f32 {
+        (crate::math::distance(&self.min, &self.max) * 0.5).max(Self::MIN_RADIUS)
+    }
+
+    /// Returns the bounds enclosing the box transformed by `matrix`.
+    pub fn transformed(&self, matrix: &crate::math::Mat4) -> Self {
+        let corners = [self.min.x, self.max.x].into_iter().flat_map(|x| {
+            [self.min.y, self.max.y].into_iter().flat_map(move |y| {
+                [self.min.z, self.max.z]
+                    .map(|z| crate::math::xyz(&(*matrix * crate::math::vec4(x, y, z, 1.0))))
+            })
+        });
+        Self::from_points(corners).expect("a box has corners")
+    }
+}
+
+/// The camera's field of view and clip planes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projection {
+    /// The vertical field of view, in degrees.
+    pub field_of_view: f32,
+
+    /// The distance of the near clip plane. Ignored while `auto_clip` is set.
+    pub near: f32,
+
+    /// The distance of the far clip plane. Ignored while `auto_clip` is set.
+    pub far: f32,
+
+    /// Whether the clip planes are derived from the scene bounds, enclosing the content
+    /// tightly, instead of using `near` and `far`.
+    pub auto_clip: bool,
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self {
+            field_of_view: crate::scene::Scene::FIELD_OF_VIEW_DEGREES,
+            near: 0.1,
+            far: 1000.0,
+            auto_clip: true,
+        }
+    }
+}
+
+impl Projection {
+    /// The narrowest and widest field of view, in degrees. Beyond these, the view is either a
+    /// telescope or so distorted the content disappears at the edges.
+    pub const FIELD_OF_VIEW_RANGE: (f32, f32) = (10.0, 150.0);
+
+    /// The closest near plane.
+    pub const MIN_NEAR: f32 = 0.001;
+
+    /// The largest ratio of the far to the near plane. Larger ratios run out of depth buffer
+    /// precision, and distant surfaces start to flicker through each other.
+    pub const MAX_DEPTH_RATIO: f32 = 100_000.0;
+
+    /// The ratio of the far to the near plane used for derived clip planes, when the camera
+    /// is inside the bounds.
+    const AUTO_DEPTH_RATIO: f32 = 1_000.0;
+
+    /// Returns the projection with a field of view and clip planes the depth buffer can
+    /// handle: the field of view within [`Projection::FIELD_OF_VIEW_RANGE`], the near plane
+    /// at least [`Projection::MIN_NEAR`], and the far plane beyond the near plane but at most
+    /// [`Projection::MAX_DEPTH_RATIO`] times as far. Non-finite values are replaced by the
+    /// defaults.
+    pub fn sanitized(&self) -> Self {
+        let defaults = Self::default();
+        let finite_or = |value: f32, default: f32| if value.is_finite() { value } else { default };
+        let (min_fov, max_fov) = Self::FIELD_OF_VIEW_RANGE;
+        let field_of_view = finite_or(self.field_of_view, defaults.field_of_view);
+        let near = finite_or(self.near, defaults.near).max(Self::MIN_NEAR);
+        let far = finite_or(self.far, defaults.far);
+        Self {
+            field_of_view: field_of_view.clamp(min_fov, max_fov),
+            near,
+            far: far.clamp(near * 2.0, near * Self::MAX_DEPTH_RATIO),
+            auto_clip: self.auto_clip,
+        }
+    }
+
+    /// Returns the sanitized projection, with the clip planes enclosing `bounds` as seen from
+    /// `eye` while `auto_clip` is set.
+    pub fn fitted(&self, eye: &crate::math::Vec3, bounds: Option<&Bounds>) -> Self {
+        let mut projection = *self;
+        if let Some(bounds) = bounds.filter(|_| self.auto_clip) {
+            let distance = crate::math::distance(eye, &bounds.center());
+            let radius = bounds.radius();
+            // A little margin keeps surfaces on the bounding sphere from being clipped.
+            projection.far = (distance + radius) * 1.05;
+            projection.near =
+                ((distance - radius) * 0.95).max(projection.far / Self::AUTO_DEPTH_RATIO);
+        }
+        projection.sanitized()
+    }
+
+    /// Returns the left-handed perspective matrix for `aspect_ratio`, mapping depth to `0..1`.
+    pub fn matrix(&self, aspect_ratio: f32) -> crate::math::Mat4 {
+        let projection = self.sanitized();
+        crate::math::perspective_lh_zo(
+            aspect_ratio,
+            projection.field_of_view.to_radians(),
+            projection.near,
+            projection.far,
+        )
+    }
+}
+
+/// The input gathered for the cameras since the last frame.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CameraInput {
+    /// Whether the look button (the right mouse button) is held.
+    pub looking: bool,
+
+    /// The mouse movement while looking, in pixels (or raw mouse counts, see
+    /// [`CameraInput::mouse_motion`]).
+    pub look_delta: crate::math::Vec2,
+
+    /// The scrolled distance, in lines. Positive values zoom in.
+    pub zoom: f32,
+
+    /// The two-finger pan, in pixels.
+    pub pan: crate::math::Vec2,
+
+    /// The logarithm of the pinch scale. Positive values (spreading fingers) zoom in.
+    pub pinch: f32,
+
+    /// The two-finger rotation, in radians clockwise on screen.
+    pub twist: f32,
+
+    /// The movement actions held, as `[forward, back, left, right, down, up]`.
+    keys: [bool; 6],
+
+    /// Whether the platform reports raw mouse motion, which then replaces cursor movement.
+    raw_motion: bool,
+}
+
+impl CameraInput {
+    /// The movement actions, in the order of [`CameraInput::keys`].
+    const MOVEMENT_ACTIONS: [crate::actions::Action; 6] = [
+        crate::actions::Action::CameraForward,
+        crate::actions::Action::CameraBack,
+        crate::actions::Action::CameraLeft,
+        crate::actions::Action::CameraRight,
+        crate::actions::Action::CameraDown,
+        crate::actions::Action::CameraUp,
+    ];
+
+    /// Reads which movement actions are held from `input`, through their bindings in
+    /// `action_map`.
+    pub fn read_actions(
+        &mut self,
+        action_map: &crate::actions::ActionMap,
+        input: &crate::input::InputState,
+    ) {
+        for (held, action) in self.keys.iter_mut().zip(Self::MOVEMENT_ACTIONS) {
+            *held = action_map.is_held(action, input);
+        }
+    }
+
+    /// Records the cursor moving by `delta` pixels. It only counts while looking, and only
+    /// until the platform reports raw mouse motion.
+    pub fn cursor_moved(&mut self, delta: crate::math::Vec2) {
+        if self.looking && !self.raw_motion {
+            self.look_delta += delta;
+        }
+    }
+
+    /// Records raw mouse motion of `delta` counts, which is neither rounded to whole pixels nor
+    /// stopped by the window edge. Once reported, it is used instead of the cursor movement.
+    /// It only counts while looking.
+    pub fn mouse_motion(&mut self, delta: crate::math::Vec2) {
+        self.raw_motion = true;
+        if self.looking {
+            self.look_delta += delta;
+        }
+    }
+
+    /// Returns the held movement as `(right, up, forward)`, each from `-1.0` to `1.0`.
+    pub fn movement(&self) -> crate::math::Vec3 {
+        let axis = |negative: bool, positive: bool| positive as i32 as f32 - negative as i32 as f32;
+        crate::math::vec3(
+            axis(self.keys[2], self.keys[3]),
+            axis(self.keys[4], self.keys[5]),
+            axis(self.keys[1], self.keys[0]),
+        )
+    }
+
+    /// Clears the per-frame input (look, zoom, and gestures), keeping the held buttons and
+    /// keys.
+    pub fn end_frame(&mut self) {
+        self.look_delta = crate::math::vec2(0.0, 0.0);
+        self.zoom = 0.0;
+        self.pan = crate::math::vec2(0.0, 0.0);
+        self.pinch = 0.0;
+        self.twist = 0.0;
+    }
+}
+
+/// A camera circling a target point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitCamera {
+    /// The point the camera looks at.
+    pub target: crate::math::Vec3,
+
+    /// The angle around the Y axis, in radians. Zero looks down the -Z axis.
+    pub yaw: f32,
+
+    /// The angle above the target's horizon, in radians.
+    pub pitch: f32,
+
+    /// The distance to the target.
+    pub distance: f32,
+}
+
+impl Default for OrbitCamera {
+    /// Looks at the origin from `(0, 0, 3)`, the scene's original fixed camera.
+    fn default() -> Self {
+        Self {
+            target: crate::math::zeros(),
+            yaw: 0.0,
+            pitch: 0.0,
+            distance: 3.0,
+        }
+    }
+}
+
+impl OrbitCamera {
+    /// The closest and farthest zoom.
+    const DISTANCE_RANGE: (f32, f32) = (0.5, 50.0);
+
+    /// Returns the camera's position.
+    pub fn eye(&self) -> crate::math::Vec3 {
+        self.target + direction(self.yaw, self.pitch) * self.distance
+    }
+
+    /// Returns the view matrix.
+    pub fn view(&self) -> crate::math::Mat4 {
+        crate::math::look_at_lh(&self.eye(), &self.target, &up())
+    }
+
+    /// Moves this pose the fraction `factor` of the way towards `goal`.
+    fn damp(&mut self, goal: &Self, factor: f32) {
+        self.target = crate::math::lerp(&self.target, &goal.target, factor);
+        self.yaw += (goal.yaw - self.yaw) * factor;
+        self.pitch += (goal.pitch - self.pitch) * factor;
+        self.distance += (goal.distance - self.distance) * factor;
+    }
+}
+
+/// A camera circling a target point with a free orientation.
+///
+/// Unlike the [`OrbitCamera`], it keeps no yaw and pitch but a rotation, which dragging turns
+/// around the view's own up and right axes. It can therefore pass over the poles and end up
+/// rolled, which suits inspecting a model from every side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArcballCamera {
+    /// The point the camera looks at.
+    pub target: crate::math::Vec3,
+
+    /// The rotation from the default pose, which looks at the target from +Z with +Y up.
+    pub rotation: crate::math::Quat,
+
+    /// The distance to the target.
+    pub distance: f32,
+}
+
+impl Default for ArcballCamera {
+    /// Looks at the origin from `(0, 0, 3)`, like the default [`OrbitCamera`].
+    fn default() -> Self {
+        Self::looking_like(&OrbitCamera::default())
+    }
+}
+
+impl ArcballCamera {
+    /// Takes the pose of `orbit`.
+    pub fn looking_like(orbit: &OrbitCamera) -> Self {
+        // The orbit camera's direction is +Z pitched up around X, then turned around Y.
+        let rotation = axis_angle(&up(), orbit.yaw)
+            * axis_angle(&crate::math::vec3(1.0, 0.0, 0.0), -orbit.pitch);
+        Self {
+            target: orbit.target,
+            rotation,
+            distance: orbit.distance,
+        }
+    }
+
+    /// Returns the direction from the target to the camera.
+    pub fn backward(&self) -> crate::math::Vec3 {
+        crate::math::quat_rotate_vec3(&self.rotation, &crate::math::vec3(0.0, 0.0, 1.0))
+    }
+
+    /// Returns the view's up direction, which is tilted away from +Y when rolled.
+    pub fn view_up(&self) -> crate::math::Vec3 {
+        crate::math::quat_rotate_vec3(&self.rotation, &up())
+    }
+
+    /// Returns the camera's position.
+    pub fn eye(&self) -> crate::math::Vec3 {
+        self.target + self.backward() * self.distance
+    }
+
+    /// Returns the view matrix.
+    pub fn view(&self) -> crate::math::Mat4 {
+        crate::math::look_at_lh(&self.eye(), &self.target, &self.view_up())
+    }
+
+    /// Turns the camera around the target by `yaw` radians around the view's up axis and
+    /// `pitch` radians around its right axis, moving it the way the orbit camera moves for
+    /// the same angles.
+    fn rotate(&mut self, yaw: f32, pitch: f32) {
+        let view_up = self.view_up();
+        let right = crate::math::cross(&view_up, &-self.backward());
+        let turn = view_up * -yaw + right * pitch;
+        let angle = crate::math::length(&turn);
+        if angle > f32::EPSILON {
+            let axis = turn / angle;
+            self.rotation =
+                crate::math::quat_normalize(&(axis_angle(&axis, angle) * self.rotation));
+        }
+    }
+
+    /// Moves this pose the fraction `factor` of the way towards `goal`.
+    fn damp(&mut self, goal: &Self, factor: f32) {
+        self.target = crate::math::lerp(&self.target, &goal.target, factor);
+        self.rotation = nlerp(&self.rotation, &goal.rotation, factor);
+        self.distance += (goal.distance - self.distance) * factor;
+    }
+}
+
+/// Returns the rotation by `angle` radians around the unit vector `axis`.
+fn axis_angle(axis: &crate::math::Vec3, angle: f32) -> crate::math::Quat {
+    let (sin, cos) = (angle * 0.5).sin_cos();
+    crate::math::quat(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+}
+
+/// Interpolates between two rotations along the shorter way, normalizing the result.
+///
+/// Unlike a spherical interpolation, the speed is not constant, which the damping hides.
+fn nlerp(from: &crate::math::Quat, to: &crate::math::Quat, factor: f32) -> crate::math::Quat {
+    let from = crate::math::quat_to_array(from);
+    let mut to = crate::math::quat_to_array(to);
+    let dot: f32 = from.iter().zip(&to).map(|(a, b)| a * b).sum();
+    if dot < 0.0 {
+        to = to.map(|component| -component);
+    }
+    let [x, y, z, w]: [f32; 4] =
+        std::array::from_fn(|index| from[index] + (to[index] - from[index]) * factor);
+    crate::math::quat_normalize(&crate::math::quat(x, y, z, w))
+}
+
+/// A freely flying first-person camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FpsCamera {
+    /// The camera's position.
+    pub position: crate::math::Vec3,
+
+    /// The angle around the Y axis, in radians. Zero looks down the +Z axis.
+    pub yaw: f32,
+
+    /// The angle above the horizon, in radians.
+    pub pitch: f32,
+}
+
+impl Default for FpsCamera {
+    /// Stands at `(0, 0, 3)` looking at the origin, like the default [`OrbitCamera`].
+    fn default() -> Self {
+        Self::looking_like(&OrbitCamera::default())
+    }
+}
+
+impl FpsCamera {
+    /// Returns an FPS camera with the same position and view direction as `orbit`.
+    pub fn looking_like(orbit: &OrbitCamera) -> Self {
+        Self {
+            position: orbit.eye(),
+            yaw: orbit.yaw + std::f32::consts::PI,
+            pitch: -orbit.pitch,
+        }
+    }
+
+    /// Returns the direction the camera looks in.
+    pub fn forward(&self) -> crate::math::Vec3 {
+        direction(self.yaw, self.pitch)
+    }
+
+    /// Returns the view matrix.
+    pub fn view(&self) -> crate::math::Mat4 {
+        crate::math::look_at_lh(&self.position, &(self.position + self.forward()), &up())
+    }
+
+    /// Moves this pose the fraction `factor` of the way towards `goal`.
+    fn damp(&mut self, goal: &Self, factor: f32) {
+        self.position = crate::math::lerp(&self.position, &goal.position, factor);
+        self.yaw += (goal.yaw - self.yaw) * factor;
+        self.pitch += (goal.pitch - self.pitch) * factor;
+    }
+}
+
+/// The orbit, arcball, and FPS cameras, with damped motion.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Camera {
+    /// The camera driving the view.
+    mode: CameraMode,
+
+    /// The poses input moves directly.
+    goal_orbit: OrbitCamera,
+    goal_arcball: ArcballCamera,
+    goal_fps: FpsCamera,
+
+    /// The visible poses, following the goals with exponential smoothing.
+    orbit: OrbitCamera,
+    arcball: ArcballCamera,
+    fps: FpsCamera,
+
+    /// The bounds of the loaded content, which the poses are clamped relative to.
+    bounds: Option<Bounds>,
+}
+
+impl Camera {
+    /// The largest pitch, just short of straight up or down, in radians.
+    const MAX_PITCH: f32 = 89.0 * std::f32::consts::PI / 180.0;
+
+    /// The pan per pixel, relative to the orbit distance.
+    const PAN_SCALE: f32 = 0.002;
+
+    /// The FPS camera's movement per unit of pinch logarithm, in world units.
+    const PINCH_DISTANCE: f32 = 2.0;
+
+    /// The closest and farthest zoom relative to the radius of the bounds.
+    const DISTANCE_RANGE_RADII: (f32, f32) = (0.25, 40.0);
+
+    /// The distance [`Camera::frame`] leaves around the bounds, relative to a tight fit.
+    const FRAME_MARGIN: f32 = 1.1;
+
+    /// Returns the camera driving the view.
+    pub fn mode(&self) -> CameraMode {
+        self.mode
+    }
+
+    /// Switches to another camera.
+    ///
+    /// Switching to the FPS or arcball camera starts it from the orbit camera's current pose,
+    /// so the view does not jump. Leaving the arcball camera hands its pose to the orbit camera,
+    /// without the roll and with the pitch limited.
+    pub fn set_mode(&mut self, mode: CameraMode) {
+        if mode == self.mode {
+            return;
+        }
+        if self.mode == CameraMode::Arcball {
+            self.orbit = Self::orbit_looking(
+                self.arcball.eye(),
+                -self.arcball.backward(),
+                self.arcball.distance,
+            );
+            self.goal_orbit = self.orbit;
+        }
+        match mode {
+            CameraMode::Orbit => {}
+            CameraMode::Arcball => {
+                self.arcball = ArcballCamera::looking_like(&self.orbit);
+                self.goal_arcball = self.arcball;
+            }
+            CameraMode::Fps => {
+                self.fps = FpsCamera::looking_like(&self.orbit);
+                self.goal_fps = self.fps;
+            }
+        }
+        self.mode = mode;
+    }
+
+    /// Returns both cameras to their default poses, keeping the mode and the bounds.
+    pub fn reset(&mut self) {
+        *self = Self {
+            mode: self.mode,
+            bounds: self.bounds,
+            ..Self::default()
+        };
+    }
+
+    /// Returns the bounds of the loaded content, if known.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// Sets the bounds of the loaded content, which the zoom range, the orbit targets, and the
+    /// FPS camera's position are clamped relative to from the next update on.
+    pub fn set_bounds(&mut self, bounds: Option<Bounds>) {
+        self.bounds = bounds;
+    }
+
+    /// Moves the orbit camera to look at the center of `bounds` from far enough away that all
+    /// of them fit into a vertical field of view of `field_of_view` degrees, keeping its
+    /// direction. The arcball camera moves the same way, keeping its rotation, and the FPS
+    /// camera is placed at the orbit camera's pose. All move there smoothly.
+    pub fn frame(&mut self, bounds: &Bounds, field_of_view: f32) {
+        self.bounds = Some(*bounds);
+        let (min_distance, max_distance) = self.distance_range();
+        let half_angle = (field_of_view.to_radians() * 0.5).clamp(0.05, 1.5);
+        self.goal_orbit.target = bounds.center();
+        self.goal_orbit.distance = (bounds.radius() / half_angle.sin() * Self::FRAME_MARGIN)
+            .clamp(min_distance, max_distance);
+        self.goal_arcball.target = self.goal_orbit.target;
+        self.goal_arcball.distance = self.goal_orbit.distance;
+        self.goal_fps = FpsCamera::looking_like(&self.goal_orbit);
+    }
+
+    /// Returns the closest and farthest zoom of the orbit and arcball cameras: relative to the size of the
+    /// bounds when they are known.
+    fn distance_range(&self) -> (f32, f32) {
+        match self.bounds {
+            Some(bounds) => (
+                bounds.radius() * Self::DISTANCE_RANGE_RADII.0,
+                bounds.radius() * Self::DISTANCE_RANGE_RADII.1,
+            ),
+            None => OrbitCamera::DISTANCE_RANGE,
+        }
+    }
+
+    /// Returns `point` moved into the region the cameras may move in: the bounds, expanded by
+    /// the farthest zoom. Without bounds, points are not moved.
+    fn clamp_to_bounds(&self, point: crate::math::Vec3, margin: f32) -> crate::math::Vec3 {
+        match self.bounds {
+            Some(bounds) => {
+                let margin = crate::math::vec3(margin, margin, margin);
+                crate::math::clamp_vec(&point, &(bounds.min - margin), &(bounds.max + margin))
+            }
+            None => point,
+        }
+    }
+
+    /// Applies `input` to the active camera's goal, then moves the visible pose towards it.
+    pub fn update(&mut self, input: &CameraInput, settings: &CameraSettings, delta_time: f32) {
+        let sign = |invert: bool| if invert { -1.0 } else { 1.0 };
+        let yaw =
+            input.look_delta.x * settings.sensitivity_x.to_radians() * sign(settings.invert_x);
+        let pitch =
+            input.look_delta.y * settings.sensitivity_y.to_radians() * sign(settings.invert_y);
+        let factor = smoothing_factor(settings.smoothing, delta_time);
+        let (near, far) = self.distance_range();
+
+        match self.mode {
+            CameraMode::Orbit => {
+                let goal = &mut self.goal_orbit;
+                // Dragging moves the camera around the target, so the scene follows the cursor.
+                goal.yaw -= yaw - input.twist;
+                goal.pitch = (goal.pitch + pitch).clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
+                let zoom = input.zoom * settings.zoom_sensitivity + input.pinch;
+                goal.distance = (goal.distance * (-zoom).exp()).clamp(near, far);
+
+                // Panning drags the target along the view plane, so the scene follows the
+                // fingers.
+                let forward = direction(goal.yaw, goal.pitch);
+                let right = crate::math::normalize(&crate::math::cross(&forward, &up()));
+                let view_up = crate::math::cross(&right, &forward);
+                goal.target +=
+                    (view_up * input.pan.y - right * input.pan.x) * goal.distance * Self::PAN_SCALE;
+                // The target may not leave the bounds, so the content stays in view.
+                self.goal_orbit.target = self.clamp_to_bounds(self.goal_orbit.target, 0.0);
+                self.orbit.damp(&self.goal_orbit, factor);
+            }
+            CameraMode::Arcball => {
+                let goal = &mut self.goal_arcball;
+                // Dragging turns the camera the way it moves the orbit camera, but without
+                // stopping at the poles.
+                goal.rotate(yaw - input.twist, pitch);
+                let zoom = input.zoom * settings.zoom_sensitivity + input.pinch;
+                goal.distance = (goal.distance * (-zoom).exp()).clamp(near, far);
+
+                let backward = goal.backward();
+                let right = crate::math::normalize(&crate::math::cross(&backward, &goal.view_up()));
+                let view_up = crate::math::cross(&right, &backward);
+                goal.target +=
+                    (view_up * input.pan.y - right * input.pan.x) * goal.distance * Self::PAN_SCALE;
+                self.goal_arcball.target = self.clamp_to_bounds(self.goal_arcball.target, 0.0);
+                self.arcball.damp(&self.goal_arcball, factor);
+            }
+            CameraMode::Fps => {
+                let goal = &mut self.goal_fps;
+                // Dragging turns the view with the cursor.
+                goal.yaw += yaw - input.twist;
+                goal.pitch = (goal.pitch - pitch).clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
+
+                let movement = input.movement();
+                let forward = goal.forward();
+                let right = crate::math::normalize(&crate::math::cross(&up(), &forward));
+                goal.position += (right * movement.x + up() * movement.y + forward * movement.z)
+                    * settings.move_speed
+                    * delta_time;
+
+                // Pinching walks forwards and backwards; panning strafes against the fingers,
+                // like dragging the world.
+                goal.position += forward * input.pinch * Self::PINCH_DISTANCE;
+                goal.position += (up() * input.pan.y - right * input.pan.x)
+                    * settings.move_speed
+                    * Self::PAN_SCALE;
+                self.goal_fps.position = self.clamp_to_bounds(self.goal_fps.position, far);
+                self.fps.damp(&self.goal_fps, factor);
+            }
+        }
+    }
+
+    /// Returns the active camera's position.
+    pub fn eye(&self) -> crate::math::Vec3 {
+        match self.mode {
+            CameraMode::Orbit => self.orbit.eye(),
+            CameraMode::Arcball => self.arcball.eye(),
+            CameraMode::Fps => self.fps.position,
+        }
+    }
+
+    /// Returns the distance from the active camera to the plane it focuses on: the orbit or
+    /// arcball target, or for the FPS camera the orbit target's depth in front of it.
+    pub fn focus_distance(&self) -> f32 {
+        match self.mode {
+            CameraMode::Orbit => self.orbit.distance,
+            CameraMode::Arcball => self.arcball.distance,
+            CameraMode::Fps => crate::math::dot(
+                &(self.orbit.target - self.fps.position),
+                &self.fps.forward(),
+            )
+            .max(self.distance_range().0),
+        }
+    }
+
+    /// Returns the direction the active camera looks in.
+    pub fn forward(&self) -> crate::math::Vec3 {
+        match self.mode {
+            CameraMode::Orbit => -direction(self.orbit.yaw, self.orbit.pitch),
+            CameraMode::Arcball => -self.arcball.backward(),
+            CameraMode::Fps => self.fps.forward(),
+        }
+    }
+
+    /// Places all cameras at `eye`, looking in the `forward` direction, without smoothing.
+    /// The orbit and arcball cameras circle the point `focus_distance` in front of `eye`.
+    pub fn look_from(
+        &mut self,
+        eye: crate::math::Vec3,
+        forward: crate::math::Vec3,
+        focus_distance: f32,
+    ) {
+        self.goal_orbit = Self::orbit_looking(eye, forward, focus_distance);
+        self.goal_arcball = ArcballCamera::looking_like(&self.goal_orbit);
+        self.goal_fps = FpsCamera::looking_like(&self.goal_orbit);
+        self.orbit = self.goal_orbit;
+        self.arcball = self.goal_arcball;
+        self.fps = self.goal_fps;
+    }
+
+    /// Returns the orbit camera at `eye`, looking in the `forward` direction at the point
+    /// `focus_distance` in front of it.
+    fn orbit_looking(
+        eye: crate::math::Vec3,
+        forward: crate::math::Vec3,
+        focus_distance: f32,
+    ) -> OrbitCamera {
+        let forward = crate::math::normalize(&forward);
+        let distance = focus_distance.max(OrbitCamera::DISTANCE_RANGE.0);
+        // The orbit camera's direction points from its target back to the eye.
+        let backward = -forward;
+        OrbitCamera {
+            target: eye + forward * distance,
+            yaw: backward.x.atan2(backward.z),
+            pitch: backward
+                .y
+                .clamp(-1.0, 1.0)
+                .asin()
+                .clamp(-Self::MAX_PITCH, Self::MAX_PITCH),
+            distance,
+        }
+    }
+
+    /// Returns the active camera's view matrix.
+    pub fn view(&self) -> crate::math::Mat4 {
+        match self.mode {
+            CameraMode::Orbit => self.orbit.view(),
+            CameraMode::Arcball => self.arcball.view(),
+            CameraMode::Fps => self.fps.view(),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! # Camera Exchange Module
+//!
+//! The `camera_exchange` module exports and imports the viewport camera in a small JSON
+//! interchange format, so camera setups can be shared with DCC tools and other viewers.
+//!
+//! ## Overview
+//!
+//! - [`CameraExchange`] holds the camera's position, orientation, vertical field of view, clip
+//!   planes, aspect ratio, and focus distance.
+//! - The format follows the USD and glTF camera conventions rather than the renderer's: the
+//!   world is right-handed with +Y up and distances in scene units, and the camera looks down
+//!   its local -Z axis with +Y up. The orientation is a unit quaternion `[x, y, z, w]`
+//!   rotating the camera's local axes into the world. The renderer's left-handed world is
+//!   converted by mirroring the Z axis.
+//! - The cameras cannot roll, so an imported orientation keeps only its view direction.
+//!   Importing places the camera immediately, turns off the clip planes fitted to the scene
+//!   so the imported ones are used, and locks the viewport to a preset aspect ratio if one
+//!   matches.
+//! - The File menu exports the active camera to and imports it from `camera.json`:
+//!   - **Desktop**: In the working directory.
+//!   - **Web**: In the browser's local storage.
+//!
+//! An exported camera looks like this:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "position": [0.0, 0.0, -3.0],
+//!   "orientation": [0.0, 1.0, 0.0, 0.0],
+//!   "vertical_fov": 80.0,
+//!   "near": 0.1,
+//!   "far": 1000.0,
+//!   "aspect_ratio": 1.7777778,
+//!   "focus_distance": 3.0
+//! }
+//! ```
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let exchange = CameraExchange::new(camera.eye(), camera.forward(), distance, &projection, 1.5);
+//! let location = exchange.export()?;
+//!
+//! let imported = CameraExchange::import()?;
+//! let (eye, forward) = imported.pose();
+//! camera.look_from(eye, forward, imported.focus_distance);
+//! ```
+
+use crate::camera::Projection;
+use crate::viewport::AspectLock;
+
+/// Errors produced while exporting or importing a camera.
+#[derive(Debug, thiserror::Error)]
+pub enum CameraExchangeError {
+    /// The camera could not be serialized, or the imported JSON is not a camera.
+    #[error("invalid camera JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The camera was exported by a newer version of the format.
+    #[error("unsupported camera format version {0}")]
+    Version(u32),
+
+    /// The camera file could not be read or written.
+    #[error("failed to access the camera file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// There is no exported camera to import, or no place to store one.
+    #[error("no camera storage is available")]
+    Unavailable,
+}
+
+/// A camera in the JSON interchange format.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CameraExchange {
+    /// The version of the format.
+    pub version: u32,
+
+    /// The camera's position in the right-handed, Y-up world.
+    pub position: [f32; 3],
+
+    /// The unit quaternion `[x, y, z, w]` rotating the camera's axes into the world. The
+    /// camera looks down its -Z axis.
+    pub orientation: [f32; 4],
+
+    /// The vertical field of view, in degrees.
+    pub vertical_fov: f32,
+
+    /// The distance of the near clip plane.
+    pub near: f32,
+
+    /// The distance of the far clip plane.
+    pub far: f32,
+
+    /// The ratio of the viewport's width to its height.
+    pub aspect_ratio: f32,
+
+    /// The distance to the point the camera looks at, which the orbit camera circles.
+    pub focus_distance: f32,
+}
+
+impl CameraExchange {
+    /// The version of the format written by this build.
+    pub const VERSION: u32 = 1;
+
+    /// The name of the camera file, or the local storage key on the web.
+    const NAME: &'static str = "camera.json";
+
+    /// Describes a camera at `eye` looking in the `forward` direction, both in the renderer's
+    /// left-handed world, with the field of view and clip planes of `projection`.
+    pub fn new(
+        eye: crate::math::Vec3,
+        forward: crate::math::Vec3,
+        focus_distance: f32,
+        projection: &Projection,
+        aspect_ratio: f32,
+    ) -> Self {
+        let projection = projection.sanitized();
+        let position = mirror(&eye);
+
+        // The camera's axes in the right-handed world: it looks down -Z, so +Z points back.
+        let back = -crate::math::normalize(&mirror(&forward));
+        let mut right = crate::math::cross(&crate::math::up(), &back);
+        if crate::math::length(&right) < 1e-6 {
+            // Looking straight up or down; any horizontal axis will do.
+            right = crate::math::vec3(1.0, 0.0, 0.0);
+        }
+        let right = crate::math::normalize(&right);
+        let up = crate::math::cross(&back, &right);
+        let rotation = crate::math::mat3_from_columns(&right, &up, &back);
+        let orientation = crate::math::quat_normalize(&crate::math::mat3_to_quat(&rotation));
+
+        Self {
+            version: Self::VERSION,
+            position: position.into(),
+            orientation: crate::math::quat_to_array(&orientation),
+            vertical_fov: projection.field_of_view,
+            near: projection.near,
+            far: projection.far,
+            aspect_ratio,
+            focus_distance,
+        }
+    }
+
+    /// Parses a camera from `json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CameraExchangeError::Json` if `json` is not a camera, and
+    /// `CameraExchangeError::Version` if it was written by a newer version of the format.
+    pub fn from_json(json: &str) -> Result<Self, CameraExchangeError> {
+        let exchange: Self = serde_json::from_str(json)?;
+        if exchange.version > Self::VERSION {
+            return Err(CameraExchangeError::Version(exchange.version));
+        }
+        Ok(exchange)
+    }
+
+    /// Returns the camera as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CameraExchangeError::Json` if the camera cannot be serialized.
+    pub fn to_json(&self) -> Result<String, CameraExchangeError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Returns the camera's position and view direction in the renderer's left-handed world.
+    pub fn pose(&self) -> (crate::math::Vec3, crate::math::Vec3) {
+        let [x, y, z, w] = self.orientation;
+        let orientation = crate::math::quat_normalize(&crate::math::quat(x, y, z, w));
+        let forward =
+            crate::math::quat_rotate_vec3(&orientation, &crate::math::vec3(0.0, 0.0, -1.0));
+        (mirror(&self.position.into()), mirror(&forward))
+    }
+
+    /// Returns the projection with the camera's field of view and its clip planes, which are
+    /// used as they are rather than fitted to the scene.
+    pub fn projection(&self) -> Projection {
+        Projection {
+            field_of_view: self.vertical_fov,
+            near: self.near,
+            far: self.far,
+            auto_clip: false,
+        }
+        .sanitized()
+    }
+
+    /// Returns the aspect lock whose ratio matches the camera's within 1%, or
+    /// [`AspectLock::Free`] if none does.
+    pub fn aspect_lock(&self) -> AspectLock {
+        AspectLock::ALL
+            .into_iter()
+            .find(|lock| {
+                lock.ratio()
+                    .is_some_and(|ratio| (ratio / self.aspect_ratio - 1.0).abs() < 0.01)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Writes the camera to `camera.json`, returning where it was stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CameraExchangeError` if the camera could not be written.
+    pub fn export(&self) -> Result<String, CameraExchangeError> {
+        Self::write(&self.to_json()?)
+    }
+
+    /// Reads the camera exported last from `camera.json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CameraExchangeError` if there is no exported camera or it is invalid.
+    pub fn import() -> Result<Self, CameraExchangeError> {
+        Self::from_json(&Self::read()?)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read() -> Result<String, CameraExchangeError> {
+        Ok(std::fs::read_to_string(Self::NAME)?)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write(json: &str) -> Result<String, CameraExchangeError> {
+        let path = std::env::current_dir()?.join(Self::NAME);
+        std::fs::write(&path, json)?;
+        Ok(path.display().to_string())
+    }
+
+    /// Returns the browser's local storage.
+    #[cfg(target_arch = "wasm32")]
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok().flatten()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn read() -> Result<String, CameraExchangeError> {
+        Self::storage()
+            .and_then(|storage| storage.get_item(Self::NAME).ok().flatten())
+            .ok_or(CameraExchangeError::Unavailable)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn write(json: &str) -> Result<String, CameraExchangeError> {
+        Self::storage()
+            .ok_or(CameraExchangeError::Unavailable)?
+            .set_item(Self::NAME, json)
+            .map_err(|_| CameraExchangeError::Unavailable)?;
+        Ok(format!("local storage '{}'", Self::NAME))
+    }
+}
+
+/// Converts a point or direction between the renderer's left-handed world and the format's
+/// right-handed one, which are mirror images along Z.
+fn mirror(vector: &crate::math::Vec3) -> crate::math::Vec3 {
+    crate::math::vec3(vector.x, vector.y, -vector.z)
+}
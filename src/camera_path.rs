@@ -0,0 +1,574 @@
+//! # Camera Path Module
+//!
+//! The `camera_path` module records the viewport camera into timed keyframes and flies it
+//! along them again, for demos and for benchmarks that need every run to show the same
+//! frames.
+//!
+//! ## Overview
+//!
+//! - [`CameraPath`] is a list of [`CameraKeyframe`]s, each holding the camera's position, view
+//!   direction, and focus distance at a time in seconds. [`CameraPath::sample`] interpolates
+//!   them with the same uniform Catmull-Rom spline the `curves` module draws, with each
+//!   segment spanning the time between its two keyframes. The view direction is interpolated
+//!   the same way and normalized; the cameras cannot roll, so no up vector is kept.
+//! - [`Flythrough`] records and plays back a path, driven by the [`FlythroughControls`] of the
+//!   settings window:
+//!   - **Recording** appends a keyframe every [`Flythrough::RECORD_INTERVAL`] seconds, and
+//!     "Add Keyframe" appends one [`Flythrough::KEYFRAME_SPACING`] seconds after the last.
+//!   - **Playback** advances the time by the frame's delta time, or by exactly
+//!     [`Flythrough::FIXED_STEP`] per frame with "Fixed Step" checked, so a benchmark renders
+//!     the same sequence of frames on every run, however fast they are rendered.
+//!   - **Scrubbing** the time slider while paused places the camera at that time.
+//! - Paths are exported to and imported from `camera_path.json`, in the working directory on
+//!   desktop and in the browser's local storage on the web, like the exported camera.
+//!
+//! The positions of the keyframes are edited as the camera's flight path, drawn while the
+//! "Camera Path" checkbox is checked.
+//!
+//! A path is stored like this:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "keyframes": [
+//!     {
+//!       "time": 0.0,
+//!       "position": [0.0, 0.0, -3.0],
+//!       "forward": [0.0, 0.0, 1.0],
+//!       "focus_distance": 3.0
+//!     },
+//!     {
+//!       "time": 2.0,
+//!       "position": [2.0, 1.0, -2.0],
+//!       "forward": [-0.6, -0.3, 0.7],
+//!       "focus_distance": 3.0
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut flythrough = Flythrough::default();
+//!
+//! // Once per frame, after the camera's update:
+//! if let Some(pose) = flythrough.update(&mut controls, &camera, delta_time) {
+//!     camera.look_from(pose.eye, pose.forward, pose.focus_distance);
+//! }
+//! ```
+
+use crate::camera::Camera;
+use crate::curves::{evaluate_bezier, Curve};
+
+/// Errors produced while exporting or importing a camera path.
+#[derive(Debug, thiserror::Error)]
+pub enum CameraPathError {
+    /// The path could not be serialized, or the imported JSON is not a camera path.
+    #[error("invalid camera path JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The path was exported by a newer version of the format.
+    #[error("unsupported camera path format version {0}")]
+    Version(u32),
+
+    /// The keyframes' times do not increase.
+    #[error("the camera path's keyframe times must increase")]
+    Unordered,
+
+    /// The camera path file could not be read or written.
+    #[error("failed to access the camera path file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// There is no exported camera path to import, or no place to store one.
+    #[error("no camera path storage is available")]
+    Unavailable,
+}
+
+/// Where the camera is at a time of a [`CameraPath`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathPose {
+    /// The camera's position.
+    pub eye: crate::math::Vec3,
+
+    /// The direction the camera looks in, of unit length.
+    pub forward: crate::math::Vec3,
+
+    /// The distance to the point the orbit camera circles.
+    pub focus_distance: f32,
+}
+
+/// The camera at a time of a [`CameraPath`].
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CameraKeyframe {
+    /// The time of the keyframe, in seconds from the start of the path.
+    pub time: f32,
+
+    /// The camera's position.
+    pub position: [f32; 3],
+
+    /// The direction the camera looks in.
+    pub forward: [f32; 3],
+
+    /// The distance to the point the orbit camera circles.
+    pub focus_distance: f32,
+}
+
+/// Timed keyframes of the camera, flown through along a Catmull-Rom spline.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CameraPath {
+    /// The version of the format.
+    pub version: u32,
+
+    /// The keyframes, by increasing time.
+    pub keyframes: Vec<CameraKeyframe>,
+}
+
+impl Default for CameraPath {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            keyframes: Vec::new(),
+        }
+    }
+}
+
+impl CameraPath {
+    /// The version of the format written by this build.
+    pub const VERSION: u32 = 1;
+
+    /// The name of the camera path file, or the local storage key on the web.
+    const NAME: &'static str = "camera_path.json";
+
+    /// Returns the time of the last keyframe, or `0.0` for an empty path.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+
+    /// Appends a keyframe of `pose` at `time`, which is moved after the last keyframe if it is
+    /// not already.
+    pub fn push(&mut self, time: f32, pose: &PathPose) {
+        let time = match self.keyframes.last() {
+            // Scaled with the time, as `f32::EPSILON` alone is lost once past 2 seconds.
+            Some(last) => time.max(last.time + f32::EPSILON * last.time.max(1.0)),
+            None => time.max(0.0),
+        };
+        self.keyframes.push(CameraKeyframe {
+            time,
+            position: pose.eye.into(),
+            forward: pose.forward.into(),
+            focus_distance: pose.focus_distance,
+        });
+    }
+
+    /// Returns the keyframes' positions, the control points of the flight path.
+    pub fn positions(&self) -> Vec<crate::math::Vec3> {
+        self.keyframes
+            .iter()
+            .map(|keyframe| keyframe.position.into())
+            .collect()
+    }
+
+    /// Moves the keyframes to `positions`, as edited on the flight path. Extra positions, or
+    /// keyframes without one, are left alone.
+    pub fn set_positions(&mut self, positions: &[crate::math::Vec3]) {
+        for (keyframe, position) in self.keyframes.iter_mut().zip(positions) {
+            keyframe.position = (*position).into();
+        }
+    }
+
+    /// Returns the pose at `time`, clamped to the path, or `None` for an empty path.
+    pub fn sample(&self, time: f32) -> Option<PathPose> {
+        let first = self.keyframes.first()?;
+        if self.keyframes.len() == 1 {
+            return Some(Self::pose(first));
+        }
+        let time = time.clamp(first.time, self.duration());
+        // The segment ending at the first keyframe after `time`, or the last one.
+        let end = self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.time > time)
+            .unwrap_or(self.keyframes.len() - 1)
+            .max(1);
+        let (start_key, end_key) = (&self.keyframes[end - 1], &self.keyframes[end]);
+        let span = end_key.time - start_key.time;
+        let t = if span > 0.0 {
+            ((time - start_key.time) / span).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        let spline = |points: Vec<crate::math::Vec3>| {
+            let segments = Curve::CatmullRom {
+                points,
+                closed: false,
+            }
+            .bezier_segments();
+            evaluate_bezier(&segments[end - 1], t)
+        };
+        let eye = spline(self.positions());
+        let forward = spline(
+            self.keyframes
+                .iter()
+                .map(|keyframe| crate::math::normalize(&keyframe.forward.into()))
+                .collect(),
+        );
+        let forward = if crate::math::length(&forward) > 1e-6 {
+            crate::math::normalize(&forward)
+        } else {
+            crate::math::normalize(&end_key.forward.into())
+        };
+        let focus_distance =
+            start_key.focus_distance + (end_key.focus_distance - start_key.focus_distance) * t;
+        Some(PathPose {
+            eye,
+            forward,
+            focus_distance,
+        })
+    }
+
+    /// Parses a camera path from `json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CameraPathError::Json` if `json` is not a camera path,
+    /// `CameraPathError::Version` if it was written by a newer version of the format, and
+    /// `CameraPathError::Unordered` if its keyframes' times do not increase.
+    pub fn from_json(json: &str) -> Result<Self, CameraPathError> {
+        let path: Self = serde_json::from_str(json)?;
+        if path.version > Self::VERSION {
+            return Err(CameraPathError::Version(path.version));
+        }
+        if path
+            .keyframes
+            .windows(2)
+            .any(|pair| pair[1].time <= pair[0].time)
+        {
+            return Err(CameraPathError::Unordered);
+        }
+        Ok(path)
+    }
+
+    /// Returns the path as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CameraPathError::Json` if the path cannot be serialized.
+    pub fn to_json(&self) -> Result<String, CameraPathError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Writes the path to `camera_path.json`, returning where it was stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CameraPathError` if the path could not be written.
+    pub fn export(&self) -> Result<String, CameraPathError> {
+        Self::write(&self.to_json()?)
+    }
+
+    /// Reads the path exported last from `camera_path.json`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CameraPathError` if there is no exported path or it is invalid.
+    pub fn import() -> Result<Self, CameraPathError> {
+        Self::from_json(&Self::read()?)
+    }
+
+    /// Returns the pose of `keyframe`.
+    fn pose(keyframe: &CameraKeyframe) -> PathPose {
+        PathPose {
+            eye: keyframe.position.into(),
+            forward: crate::math::normalize(&keyframe.forward.into()),
+            focus_distance: keyframe.focus_distance,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read() -> Result<String, CameraPathError> {
+        Ok(std::fs::read_to_string(Self::NAME)?)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write(json: &str) -> Result<String, CameraPathError> {
+        let path = std::env::current_dir()?.join(Self::NAME);
+        std::fs::write(&path, json)?;
+        Ok(path.display().to_string())
+    }
+
+    /// Returns the browser's local storage.
+    #[cfg(target_arch = "wasm32")]
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok().flatten()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn read() -> Result<String, CameraPathError> {
+        Self::storage()
+            .and_then(|storage| storage.get_item(Self::NAME).ok().flatten())
+            .ok_or(CameraPathError::Unavailable)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn write(json: &str) -> Result<String, CameraPathError> {
+        Self::storage()
+            .ok_or(CameraPathError::Unavailable)?
+            .set_item(Self::NAME, json)
+            .map_err(|_| CameraPathError::Unavailable)?;
+        Ok(format!("local storage '{}'", Self::NAME))
+    }
+}
+
+/// The flythrough controls of the settings window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlythroughControls {
+    /// Whether the camera is recorded into the path.
+    pub recording: bool,
+
+    /// Whether the camera flies along the path.
+    pub playing: bool,
+
+    /// Whether playback starts over at the end of the path instead of stopping.
+    pub looping: bool,
+
+    /// Whether playback advances by `Flythrough::FIXED_STEP` per frame instead of the frame's
+    /// delta time.
+    pub fixed_step: bool,
+
+    /// The playback time, in seconds, scrubbed with a slider while paused.
+    pub time: f32,
+
+    /// The duration of the path, set by the `App` every frame.
+    pub duration: f32,
+
+    /// The number of keyframes, set by the `App` every frame.
+    pub keyframes: usize,
+
+    /// Whether "Add Keyframe" was clicked. The `Flythrough` clears it and appends the camera.
+    pub add_keyframe_requested: bool,
+
+    /// Whether "Clear" was clicked. The `Flythrough` clears it and removes every keyframe.
+    pub clear_requested: bool,
+
+    /// Whether "Export Path" was clicked. The `App` clears it and writes the path to
+    /// `camera_path.json`.
+    pub export_requested: bool,
+
+    /// Whether "Import Path" was clicked. The `App` clears it and reads the path from
+    /// `camera_path.json`.
+    pub import_requested: bool,
+}
+
+impl Default for FlythroughControls {
+    fn default() -> Self {
+        Self {
+            recording: false,
+            playing: false,
+            looping: true,
+            fixed_step: false,
+            time: 0.0,
+            duration: 0.0,
+            keyframes: 0,
+            add_keyframe_requested: false,
+            clear_requested: false,
+            export_requested: false,
+            import_requested: false,
+        }
+    }
+}
+
+/// Records the camera into a [`CameraPath`] and plays it back.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Flythrough {
+    /// The recorded or imported path.
+    pub path: CameraPath,
+
+    /// The time since the last keyframe was recorded, in seconds, while recording.
+    since_keyframe: f32,
+
+    /// The playback time the camera was last placed at, to tell scrubbing from idling.
+    placed_at: Option<f32>,
+}
+
+impl Flythrough {
+    /// The time between keyframes while recording, in seconds.
+    pub const RECORD_INTERVAL: f32 = 0.25;
+
+    /// The time between the last keyframe and one added with "Add Keyframe", in seconds.
+    pub const KEYFRAME_SPACING: f32 = 2.0;
+
+    /// The time playback advances by per frame with "Fixed Step" checked: 60 frames per
+    /// second.
+    pub const FIXED_STEP: f32 = 1.0 / 60.0;
+
+    /// Records `camera` or advances the playback by `delta_time` seconds, as `controls` say,
+    /// and updates their duration, keyframe count, and time.
+    ///
+    /// Returns the pose to place the camera at while playing or scrubbing.
+    pub fn update(
+        &mut self,
+        controls: &mut FlythroughControls,
+        camera: &Camera,
+        delta_time: f32,
+    ) -> Option<PathPose> {
+        let pose = PathPose {
+            eye: camera.eye(),
+            forward: camera.forward(),
+            focus_distance: camera.focus_distance(),
+        };
+        if std::mem::take(&mut controls.clear_requested) {
+            self.path.keyframes.clear();
+            controls.recording = false;
+            controls.playing = false;
+            controls.time = 0.0;
+        }
+        if std::mem::take(&mut controls.add_keyframe_requested) {
+            let time = if self.path.keyframes.is_empty() {
+                0.0
+            } else {
+                self.path.duration() + Self::KEYFRAME_SPACING
+            };
+            self.path.push(time, &pose);
+        }
+
+        // Recording continues the path, one keyframe per interval.
+        if controls.recording {
+            controls.playing = false;
+            self.since_keyframe += delta_time;
+            if self.path.keyframes.is_empty() {
+                self.path.push(0.0, &pose);
+                self.since_keyframe = 0.0;
+            } else if self.since_keyframe >= Self::RECORD_INTERVAL {
+                let time = self.path.duration() + self.since_keyframe;
+                self.path.push(time, &pose);
+                self.since_keyframe = 0.0;
+            }
+            controls.time = self.path.duration();
+        } else {
+            self.since_keyframe = 0.0;
+        }
+        controls.duration = self.path.duration();
+        controls.keyframes = self.path.keyframes.len();
+
+        if controls.playing && self.path.keyframes.len() >= 2 {
+            let step = if controls.fixed_step {
+                Self::FIXED_STEP
+            } else {
+                delta_time
+            };
+            controls.time += step;
+            if controls.time > controls.duration {
+                if controls.looping {
+                    controls.time = controls.time.rem_euclid(controls.duration);
+                } else {
+                    controls.time = controls.duration;
+                    controls.playing = false;
+                }
+            }
+        } else {
+            controls.playing = false;
+            controls.time = controls.time.clamp(0.0, controls.duration);
+            // While paused, the camera is only placed when the time is scrubbed.
+            if self.placed_at == Some(controls.time) || controls.recording {
+                self.placed_at = Some(controls.time);
+                return None;
+            }
+        }
+        self.placed_at = Some(controls.time);
+        self.path.sample(controls.time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::vec3;
+
+    /// Returns a pose at `eye` looking down the Z axis, `focus_distance` from its focus.
+    fn pose(eye: crate::math::Vec3, focus_distance: f32) -> PathPose {
+        PathPose {
+            eye,
+            forward: vec3(0.0, 0.0, 1.0),
+            focus_distance,
+        }
+    }
+
+    /// Returns a path through three keyframes, one second apart.
+    fn path() -> CameraPath {
+        let mut path = CameraPath::default();
+        path.push(0.0, &pose(vec3(0.0, 0.0, 0.0), 1.0));
+        path.push(1.0, &pose(vec3(1.0, 1.0, 0.0), 3.0));
+        path.push(2.0, &pose(vec3(2.0, 0.0, 0.0), 5.0));
+        path
+    }
+
+    /// Asserts that `a` and `b` are the same point, up to rounding.
+    fn assert_close(a: crate::math::Vec3, b: crate::math::Vec3) {
+        assert!(crate::math::distance(&a, &b) < 1e-5, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn sampling_passes_through_the_keyframes() {
+        let path = path();
+
+        for (time, keyframe) in [(0.0, 0), (1.0, 1), (2.0, 2)] {
+            let sampled = path.sample(time).unwrap();
+            assert_close(sampled.eye, path.keyframes[keyframe].position.into());
+            assert_eq!(
+                sampled.focus_distance,
+                path.keyframes[keyframe].focus_distance
+            );
+        }
+        assert_eq!(path.sample(0.5).unwrap().focus_distance, 2.0);
+        assert_close(path.sample(0.5).unwrap().forward, vec3(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn sampling_clamps_to_the_path() {
+        let path = path();
+
+        assert_eq!(path.sample(-1.0), path.sample(0.0));
+        assert_eq!(path.sample(10.0), path.sample(2.0));
+        assert_eq!(CameraPath::default().sample(0.0), None);
+    }
+
+    #[test]
+    fn pushed_keyframes_keep_increasing_times() {
+        let mut path = path();
+        path.push(1.5, &pose(vec3(3.0, 0.0, 0.0), 1.0));
+
+        assert!(path.keyframes[3].time > 2.0);
+        assert_eq!(path.duration(), path.keyframes[3].time);
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let path = path();
+
+        let json = path.to_json().unwrap();
+        assert_eq!(CameraPath::from_json(&json).unwrap(), path);
+    }
+
+    #[test]
+    fn json_from_newer_versions_or_with_unordered_keyframes_is_rejected() {
+        let mut newer = path();
+        newer.version = CameraPath::VERSION + 1;
+        let mut unordered = path();
+        unordered.keyframes.swap(0, 1);
+
+        assert!(matches!(
+            CameraPath::from_json(&newer.to_json().unwrap()),
+            Err(CameraPathError::Version(version)) if version == CameraPath::VERSION + 1
+        ));
+        assert!(matches!(
+            CameraPath::from_json(&unordered.to_json().unwrap()),
+            Err(CameraPathError::Unordered)
+        ));
+        assert!(matches!(
+            CameraPath::from_json("{}"),
+            Err(CameraPathError::Json(_))
+        ));
+    }
+}
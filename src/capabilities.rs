@@ -0,0 +1,211 @@
+//! # GPU Capabilities Module
+//!
+//! The `capabilities` module negotiates the optional device features requested from the
+//! adapter and records what was actually granted in [`GpuCapabilities`]. [`GpuInfo`] describes
+//! the adapter in use together with those features and limits.
+//!
+//! ## Overview
+//!
+//! Adapters differ widely in the features they expose: a desktop Vulkan driver typically
+//! offers timestamp queries and push constants, while WebGL offers almost none of them.
+//! Instead of requiring a fixed set of features (and failing device creation when one is
+//! missing), the `Gpu` asks for a set of *optional* features, receives the subset the adapter
+//! supports, and stores it in a `GpuCapabilities`. Subsystems then query the capabilities
+//! (for example [`GpuCapabilities::supports_timestamp_queries`]) instead of assuming a feature
+//! is present.
+//!
+//! Applications built on top of the renderer get a [`GpuInfo`] from `Renderer::gpu_info`, to
+//! make the same kind of decisions, and the GUI shows it in the GPU Info window.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let granted = negotiate_features(adapter.features(), GpuCapabilities::OPTIONAL_FEATURES);
+//! // ... request the device with `granted` ...
+//! let capabilities = GpuCapabilities::new(device.features(), device.limits());
+//!
+//! let polygon_mode = if capabilities.supports_line_polygon_mode() {
+//!     wgpu::PolygonMode::Line
+//! } else {
+//!     wgpu::PolygonMode::Fill
+//! };
+//! ```
+
+/// The features and limits granted to the device.
+///
+/// Created once the device has been requested, so it reflects what the device actually
+/// supports rather than what was asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuCapabilities {
+    /// The features enabled on the device.
+    pub features: wgpu::Features,
+
+    /// The limits the device was created with.
+    pub limits: wgpu::Limits,
+}
+
+impl GpuCapabilities {
+    /// The optional features requested by default when they are available.
+    ///
+    /// - `TIMESTAMP_QUERY`: GPU timing of passes.
+    /// - `POLYGON_MODE_LINE`: Wireframe rendering.
+    /// - `POLYGON_MODE_POINT`: Point rendering of triangle corners.
+    /// - `PUSH_CONSTANTS`: Small per-draw constants without a uniform buffer.
+    /// - `MULTI_DRAW_INDIRECT`: Issuing many indirect draws with one call.
+    /// - `PIPELINE_CACHE`: Persisting compiled pipelines between runs.
+    pub const OPTIONAL_FEATURES: wgpu::Features = wgpu::Features::TIMESTAMP_QUERY
+        .union(wgpu::Features::POLYGON_MODE_LINE)
+        .union(wgpu::Features::POLYGON_MODE_POINT)
+        .union(wgpu::Features::PUSH_CONSTANTS)
+        .union(wgpu::Features::MULTI_DRAW_INDIRECT)
+        .union(wgpu::Features::PIPELINE_CACHE);
+
+    /// The push constant size requested when `PUSH_CONSTANTS` is granted, in bytes.
+    ///
+    /// This is the minimum guaranteed by every backend that supports push constants, and
+    /// enough for a 4x4 matrix plus a few additional values.
+    pub const PUSH_CONSTANT_SIZE: u32 = 128;
+
+    /// Creates the capabilities from the device's `features` and `limits`.
+    pub fn new(features: wgpu::Features, limits: wgpu::Limits) -> Self {
+        Self { features, limits }
+    }
+
+    /// Returns `true` if every feature in `features` was granted.
+    pub fn supports(&self, features: wgpu::Features) -> bool {
+        self.features.contains(features)
+    }
+
+    /// Returns `true` if timestamp queries can be written at pass boundaries.
+    pub fn supports_timestamp_queries(&self) -> bool {
+        self.supports(wgpu::Features::TIMESTAMP_QUERY)
+    }
+
+    /// Returns `true` if pipelines can use `wgpu::PolygonMode::Line`.
+    pub fn supports_line_polygon_mode(&self) -> bool {
+        self.supports(wgpu::Features::POLYGON_MODE_LINE)
+    }
+
+    /// Returns `true` if pipelines can use `wgpu::PolygonMode::Point`.
+    pub fn supports_point_polygon_mode(&self) -> bool {
+        self.supports(wgpu::Features::POLYGON_MODE_POINT)
+    }
+
+    /// Returns `true` if pipelines can declare push constant ranges.
+    pub fn supports_push_constants(&self) -> bool {
+        self.supports(wgpu::Features::PUSH_CONSTANTS) && self.limits.max_push_constant_size > 0
+    }
+
+    /// Returns `true` if compute shaders can be dispatched, which WebGL cannot do.
+    pub fn supports_compute(&self) -> bool {
+        self.limits.max_compute_workgroups_per_dimension > 0
+    }
+
+    /// Returns `true` if `multi_draw_indirect` can be used in render passes.
+    pub fn supports_multi_draw_indirect(&self) -> bool {
+        self.supports(wgpu::Features::MULTI_DRAW_INDIRECT)
+    }
+
+    /// Returns `true` if compiled pipelines can be persisted with a `wgpu::PipelineCache`.
+    pub fn supports_pipeline_cache(&self) -> bool {
+        self.supports(wgpu::Features::PIPELINE_CACHE)
+    }
+}
+
+/// The adapter the device was requested from, and the features and limits it was granted.
+///
+/// Captured once when the `Gpu` is created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuInfo {
+    /// The adapter's name, vendor, device type, backend, and driver.
+    pub adapter: wgpu::AdapterInfo,
+
+    /// The features enabled on the device.
+    pub features: wgpu::Features,
+
+    /// The limits the device was created with.
+    pub limits: wgpu::Limits,
+}
+
+impl GpuInfo {
+    /// Creates the description of the adapter `adapter` with the granted `capabilities`.
+    pub fn new(adapter: wgpu::AdapterInfo, capabilities: &GpuCapabilities) -> Self {
+        Self {
+            adapter,
+            features: capabilities.features,
+            limits: capabilities.limits.clone(),
+        }
+    }
+
+    /// Returns the adapter's name.
+    pub fn name(&self) -> &str {
+        &self.adapter.name
+    }
+
+    /// Returns the graphics backend the adapter is used through.
+    pub fn backend(&self) -> wgpu::Backend {
+        self.adapter.backend
+    }
+
+    /// Returns `true` if every feature in `features` is enabled on the device.
+    pub fn supports(&self, features: wgpu::Features) -> bool {
+        self.features.contains(features)
+    }
+
+    /// Returns the names and values of the limits shown in the GUI: those that most often
+    /// decide whether a technique is usable.
+    pub fn key_limits(&self) -> [(&'static str, u64); 12] {
+        let limits = &self.limits;
+        [
+            (
+                "Max Texture Size 2D",
+                limits.max_texture_dimension_2d.into(),
+            ),
+            (
+                "Max Texture Size 3D",
+                limits.max_texture_dimension_3d.into(),
+            ),
+            (
+                "Max Texture Array Layers",
+                limits.max_texture_array_layers.into(),
+            ),
+            ("Max Bind Groups", limits.max_bind_groups.into()),
+            (
+                "Max Samplers Per Stage",
+                limits.max_samplers_per_shader_stage.into(),
+            ),
+            (
+                "Max Uniform Binding Size",
+                limits.max_uniform_buffer_binding_size.into(),
+            ),
+            (
+                "Max Storage Binding Size",
+                limits.max_storage_buffer_binding_size.into(),
+            ),
+            ("Max Buffer Size", limits.max_buffer_size),
+            ("Max Vertex Attributes", limits.max_vertex_attributes.into()),
+            ("Max Color Attachments", limits.max_color_attachments.into()),
+            (
+                "Max Push Constant Size",
+                limits.max_push_constant_size.into(),
+            ),
+            (
+                "Max Workgroups Per Dimension",
+                limits.max_compute_workgroups_per_dimension.into(),
+            ),
+        ]
+    }
+}
+
+/// Returns the subset of `requested` that `available` supports.
+///
+/// Optional features that are missing are logged, so it is visible which code paths will
+/// fall back to a slower or simpler implementation.
+pub fn negotiate_features(available: wgpu::Features, requested: wgpu::Features) -> wgpu::Features {
+    let granted = available & requested;
+    let missing = requested - granted;
+    if !missing.is_empty() {
+        log::info!("Optional GPU features not available on this adapter: {missing:?}");
+    }
+    granted
+}
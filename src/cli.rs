@@ -0,0 +1,524 @@
+//! # Command Line Module
+//!
+//! The `cli` module parses the arguments of the desktop binary into the [`Command`] it runs,
+//! and rejects any argument it does not know, so a misspelled option is reported instead of
+//! silently ignored.
+//!
+//! ## Overview
+//!
+//! - [`Command::parse`] reads the `pack` and `test-matrix` subcommands, or else the
+//!   [`Options`] of a run: the backend, adapter, asset pack, model, and texture, and the
+//!   [`ExportOptions`] of `--export` or the [`BenchOptions`] of `--bench`. Every value is
+//!   checked while parsing.
+//! - `--backend` and `--adapter` default to the `WGPU_BACKEND` and `WGPU_ADAPTER` environment
+//!   variables, read through the lookup handed to `Command::parse`, so tests do not depend on
+//!   the process's environment.
+//! - [`CliError`] names the argument that could not be parsed. `main` prints it followed by
+//!   [`USAGE`], and exits with status 2.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let args = std::env::args().skip(1);
+//! match Command::parse(args, |name| std::env::var(name).ok()) {
+//!     Ok(Command::Run(options)) => run(options),
+//!     Ok(Command::Help) => println!("{USAGE}"),
+//!     Err(error) => {
+//!         eprintln!("{error}\n\n{USAGE}");
+//!         std::process::exit(2);
+//!     }
+//!     // ...
+//! }
+//! ```
+
+use crate::config::{AdapterSelection, BackendSelection};
+use crate::Duration;
+
+/// The usage message printed by `--help` and after an invalid argument.
+pub const USAGE: &str = "\
+Usage:
+  app [OPTIONS]
+  app pack [SOURCE_DIR] [OUTPUT]      Bundle a directory (`assets`) into a pack (`assets.pak`)
+  app test-matrix [OUTPUT_DIR]        Compare the demo gallery on every adapter
+
+Options:
+  --backend <auto|vulkan|dx12|metal|gl>
+                                      The graphics backend (default: $WGPU_BACKEND)
+  --adapter <INDEX|NAME>              The adapter, by index or a part of its name
+                                      (default: $WGPU_ADAPTER)
+  --pack <PATH|URL>                   Mount an asset pack to read the model and texture from
+  --model <PATH|URL>                  Draw a glTF or GLB model (with `import-gltf`)
+  --texture <PATH|URL>                Texture the scene with a PNG or JPEG image
+  --export <OUTPUT>                   Render a camera path into a video or a PNG directory
+    --camera-path <PATH>              The camera path to render (default: camera_path.json)
+    --size <WIDTHxHEIGHT>             The resolution of the frames
+    --fps <FPS>                       The frame rate
+    --frames <COUNT>                  The number of frames, instead of the whole path
+    --seed <SEED>                     The seed of the random streams
+  --bench [OUTPUT]                    Run the benchmark and write its report (default: bench.csv)
+    --bench-duration <SECONDS>        The length of the benchmark
+    --bench-stages <COUNT>            The number of times the workload grows
+  -h, --help                          Print this message";
+
+/// Errors produced while parsing the command line.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CliError {
+    /// The argument is not an option or subcommand of the binary.
+    #[error("unknown argument '{0}'")]
+    UnknownArgument(String),
+
+    /// The option was the last argument, without its value.
+    #[error("'{0}' expects a value")]
+    MissingValue(&'static str),
+
+    /// The option's value, or the environment variable standing in for it, is invalid.
+    #[error("invalid value '{value}' for '{option}': {message}")]
+    InvalidValue {
+        /// The option or environment variable.
+        option: &'static str,
+
+        /// The value given.
+        value: String,
+
+        /// Why the value is invalid.
+        message: String,
+    },
+
+    /// The option only applies together with another one, which is missing.
+    #[error("'{option}' requires '{requires}'")]
+    Requires {
+        /// The option given.
+        option: &'static str,
+
+        /// The option it applies to.
+        requires: &'static str,
+    },
+}
+
+/// What the binary was asked to do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Open the window, or export frames or run the benchmark, with these options.
+    Run(Options),
+
+    /// Bundle the directory `source` into the asset pack `destination`, and exit.
+    Pack {
+        /// The directory of assets, `assets` by default.
+        source: String,
+
+        /// The pack written, `assets.pak` by default.
+        destination: String,
+    },
+
+    /// Render the demo gallery on every adapter into `output`, and exit.
+    TestMatrix {
+        /// The directory of the screenshots and the report, `test-matrix` by default.
+        output: String,
+    },
+
+    /// Print [`USAGE`], and exit.
+    Help,
+}
+
+/// The options of a run.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Options {
+    /// The graphics backend, from `--backend` or `WGPU_BACKEND`.
+    pub backend: Option<BackendSelection>,
+
+    /// The adapter, from `--adapter` or `WGPU_ADAPTER`.
+    pub adapter: Option<AdapterSelection>,
+
+    /// The asset pack mounted before the model and texture are loaded.
+    pub pack: Option<String>,
+
+    /// The glTF or GLB model drawn in place of the triangle.
+    pub model: Option<String>,
+
+    /// The image the scene is textured with.
+    pub texture: Option<String>,
+
+    /// Renders a camera path into frames instead of opening the window.
+    pub export: Option<ExportOptions>,
+
+    /// Runs the benchmark without vsync, then exits.
+    pub bench: Option<BenchOptions>,
+}
+
+/// The options of `--export`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOptions {
+    /// The video or directory of PNGs written.
+    pub output: String,
+
+    /// The JSON camera path rendered.
+    pub camera_path: String,
+
+    /// The width and height of the frames, or `None` for the default.
+    pub size: Option<(u32, u32)>,
+
+    /// The frame rate, or `None` for the default.
+    pub frame_rate: Option<u32>,
+
+    /// The number of frames, or `None` for the whole path.
+    pub frames: Option<u32>,
+
+    /// The seed of the random streams, or `None` for the default.
+    pub seed: Option<u64>,
+}
+
+/// The options of `--bench`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchOptions {
+    /// The CSV or JSON report written.
+    pub output: String,
+
+    /// The length of the benchmark, or `None` for the default.
+    pub duration: Option<Duration>,
+
+    /// The number of stages, or `None` for the default.
+    pub stages: Option<u32>,
+}
+
+impl Command {
+    /// Parses the arguments after the binary's name, looking up the environment variables
+    /// `--backend` and `--adapter` default to with `env`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CliError` for an unknown argument, a missing or invalid value, or an option
+    /// of `--export` or `--bench` given without it.
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, CliError> {
+        let mut args = args.into_iter().peekable();
+        match args.peek().map(String::as_str) {
+            Some("pack") => {
+                args.next();
+                let source = args.next().unwrap_or_else(|| "assets".to_string());
+                let destination = args.next().unwrap_or_else(|| "assets.pak".to_string());
+                return reject_rest(
+                    args,
+                    Command::Pack {
+                        source,
+                        destination,
+                    },
+                );
+            }
+            Some("test-matrix") => {
+                args.next();
+                let output = args.next().unwrap_or_else(|| "test-matrix".to_string());
+                return reject_rest(args, Command::TestMatrix { output });
+            }
+            _ => {}
+        }
+
+        let mut options = Options::default();
+        let (mut export, mut camera_path, mut size, mut frame_rate, mut frames, mut seed) =
+            (None, None, None, None, None, None);
+        let (mut bench, mut bench_duration, mut bench_stages) = (None, None, None);
+        while let Some(arg) = args.next() {
+            let option = match arg.as_str() {
+                "-h" | "--help" => return Ok(Command::Help),
+                "--backend" => "--backend",
+                "--adapter" => "--adapter",
+                "--pack" => "--pack",
+                #[cfg(feature = "import-gltf")]
+                "--model" => "--model",
+                "--texture" => "--texture",
+                "--export" => "--export",
+                "--camera-path" => "--camera-path",
+                "--size" => "--size",
+                "--fps" => "--fps",
+                "--frames" => "--frames",
+                "--seed" => "--seed",
+                "--bench" => {
+                    let output = args.next_if(|next| !next.starts_with('-'));
+                    bench = Some(output.unwrap_or_else(|| "bench.csv".to_string()));
+                    continue;
+                }
+                "--bench-duration" => "--bench-duration",
+                "--bench-stages" => "--bench-stages",
+                _ => return Err(CliError::UnknownArgument(arg)),
+            };
+            let value = args.next().ok_or(CliError::MissingValue(option))?;
+            match option {
+                "--backend" => options.backend = Some(parse_value(option, value)?),
+                "--adapter" => options.adapter = Some(parse_value(option, value)?),
+                "--pack" => options.pack = Some(value),
+                "--model" => options.model = Some(value),
+                "--texture" => options.texture = Some(value),
+                "--export" => export = Some(value),
+                "--camera-path" => camera_path = Some(value),
+                "--size" => size = Some(parse_size(value)?),
+                "--fps" => frame_rate = Some(parse_value(option, value)?),
+                "--frames" => frames = Some(parse_value(option, value)?),
+                "--seed" => seed = Some(parse_value(option, value)?),
+                "--bench-duration" => bench_duration = Some(parse_duration(value)?),
+                _ => bench_stages = Some(parse_value(option, value)?),
+            }
+        }
+
+        if options.backend.is_none() {
+            if let Some(value) = env("WGPU_BACKEND") {
+                options.backend = Some(parse_value("WGPU_BACKEND", value)?);
+            }
+        }
+        if options.adapter.is_none() {
+            if let Some(value) = env("WGPU_ADAPTER") {
+                options.adapter = Some(parse_value("WGPU_ADAPTER", value)?);
+            }
+        }
+
+        options.export = match export {
+            Some(output) => Some(ExportOptions {
+                output,
+                camera_path: camera_path.unwrap_or_else(|| "camera_path.json".to_string()),
+                size,
+                frame_rate,
+                frames,
+                seed,
+            }),
+            None => {
+                let given = [
+                    ("--camera-path", camera_path.is_some()),
+                    ("--size", size.is_some()),
+                    ("--fps", frame_rate.is_some()),
+                    ("--frames", frames.is_some()),
+                    ("--seed", seed.is_some()),
+                ];
+                require(&given, "--export")?;
+                None
+            }
+        };
+        options.bench = match bench {
+            Some(output) => Some(BenchOptions {
+                output,
+                duration: bench_duration,
+                stages: bench_stages,
+            }),
+            None => {
+                let given = [
+                    ("--bench-duration", bench_duration.is_some()),
+                    ("--bench-stages", bench_stages.is_some()),
+                ];
+                require(&given, "--bench")?;
+                None
+            }
+        };
+        Ok(Command::Run(options))
+    }
+}
+
+/// Returns `command` if `args` are exhausted, or the first remaining argument as unknown.
+fn reject_rest(
+    mut args: impl Iterator<Item = String>,
+    command: Command,
+) -> Result<Command, CliError> {
+    match args.next() {
+        Some(arg) => Err(CliError::UnknownArgument(arg)),
+        None => Ok(command),
+    }
+}
+
+/// Returns an error for the first of the `given` options, which apply only with `requires`.
+fn require(given: &[(&'static str, bool)], requires: &'static str) -> Result<(), CliError> {
+    match given.iter().find(|(_, is_given)| *is_given) {
+        Some(&(option, _)) => Err(CliError::Requires { option, requires }),
+        None => Ok(()),
+    }
+}
+
+/// Parses the `value` of `option` with its `FromStr` implementation.
+fn parse_value<T>(option: &'static str, value: String) -> Result<T, CliError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|error: T::Err| CliError::InvalidValue {
+            option,
+            message: error.to_string(),
+            value,
+        })
+}
+
+/// Parses the `WIDTHxHEIGHT` value of `--size`.
+fn parse_size(value: String) -> Result<(u32, u32), CliError> {
+    let size = value
+        .split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)));
+    size.ok_or_else(|| CliError::InvalidValue {
+        option: "--size",
+        value,
+        message: "expected WIDTHxHEIGHT".to_string(),
+    })
+}
+
+/// Parses the positive number of seconds of `--bench-duration`.
+fn parse_duration(value: String) -> Result<Duration, CliError> {
+    match value.parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(Duration::from_secs_f64(seconds)),
+        _ => Err(CliError::InvalidValue {
+            option: "--bench-duration",
+            value,
+            message: "expected a positive number of seconds".to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses `args` without any environment variables set.
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        Command::parse(args.iter().map(|arg| arg.to_string()), |_| None)
+    }
+
+    #[test]
+    fn no_arguments_run_with_the_defaults() {
+        assert_eq!(parse(&[]), Ok(Command::Run(Options::default())));
+    }
+
+    #[test]
+    fn subcommands_default_their_paths() {
+        assert_eq!(
+            parse(&["pack"]),
+            Ok(Command::Pack {
+                source: "assets".to_string(),
+                destination: "assets.pak".to_string(),
+            })
+        );
+        assert_eq!(
+            parse(&["test-matrix", "out"]),
+            Ok(Command::TestMatrix {
+                output: "out".to_string()
+            })
+        );
+        assert_eq!(
+            parse(&["pack", "a", "b", "c"]),
+            Err(CliError::UnknownArgument("c".to_string()))
+        );
+    }
+
+    #[test]
+    fn unknown_arguments_are_rejected() {
+        assert_eq!(
+            parse(&["--texure", "checker.png"]),
+            Err(CliError::UnknownArgument("--texure".to_string()))
+        );
+        assert_eq!(
+            parse(&["--texture", "checker.png", "extra"]),
+            Err(CliError::UnknownArgument("extra".to_string()))
+        );
+    }
+
+    #[test]
+    fn options_are_parsed() {
+        let Ok(Command::Run(options)) = parse(&[
+            "--backend",
+            "vulkan",
+            "--adapter",
+            "1",
+            "--pack",
+            "assets.pak",
+            "--texture",
+            "checker.png",
+        ]) else {
+            panic!("the options parse");
+        };
+        assert_eq!(options.backend, Some(BackendSelection::Vulkan));
+        assert_eq!(options.adapter, Some(AdapterSelection::Index(1)));
+        assert_eq!(options.pack.as_deref(), Some("assets.pak"));
+        assert_eq!(options.texture.as_deref(), Some("checker.png"));
+    }
+
+    #[test]
+    fn invalid_and_missing_values_are_rejected() {
+        assert!(matches!(
+            parse(&["--backend", "glide"]),
+            Err(CliError::InvalidValue {
+                option: "--backend",
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse(&["--export", "out", "--size", "1920"]),
+            Err(CliError::InvalidValue {
+                option: "--size",
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse(&["--bench", "--bench-duration", "-1"]),
+            Err(CliError::InvalidValue {
+                option: "--bench-duration",
+                ..
+            })
+        ));
+        assert_eq!(parse(&["--pack"]), Err(CliError::MissingValue("--pack")));
+    }
+
+    #[test]
+    fn environment_variables_back_the_selection() {
+        let env = |name: &str| match name {
+            "WGPU_BACKEND" => Some("dx12".to_string()),
+            "WGPU_ADAPTER" => Some("nvidia".to_string()),
+            _ => None,
+        };
+        let args = ["--backend", "gl"].map(String::from);
+        let Ok(Command::Run(options)) = Command::parse(args, env) else {
+            panic!("the options parse");
+        };
+        assert_eq!(options.backend, Some(BackendSelection::Gl));
+        assert_eq!(
+            options.adapter,
+            Some(AdapterSelection::Name("nvidia".to_string()))
+        );
+    }
+
+    #[test]
+    fn export_and_bench_collect_their_options() {
+        let Ok(Command::Run(options)) = parse(&[
+            "--export",
+            "frames.mp4",
+            "--size",
+            "1920x1080",
+            "--fps",
+            "60",
+            "--bench",
+            "--bench-stages",
+            "4",
+        ]) else {
+            panic!("the options parse");
+        };
+        let export = options.export.expect("--export is given");
+        assert_eq!(export.camera_path, "camera_path.json");
+        assert_eq!(export.size, Some((1920, 1080)));
+        assert_eq!(export.frame_rate, Some(60));
+        let bench = options.bench.expect("--bench is given");
+        assert_eq!(bench.output, "bench.csv");
+        assert_eq!(bench.stages, Some(4));
+    }
+
+    #[test]
+    fn options_of_export_and_bench_require_them() {
+        assert_eq!(
+            parse(&["--fps", "60"]),
+            Err(CliError::Requires {
+                option: "--fps",
+                requires: "--export"
+            })
+        );
+        assert_eq!(
+            parse(&["--bench-stages", "4"]),
+            Err(CliError::Requires {
+                option: "--bench-stages",
+                requires: "--bench"
+            })
+        );
+    }
+}
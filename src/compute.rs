@@ -0,0 +1,224 @@
+//! # Compute Module
+//!
+//! The `compute` module is the template's building block for general-purpose GPU work that
+//! does not rasterize anything, such as simulations and image processing.
+//!
+//! ## Overview
+//!
+//! - [`StorageBuffer`] holds an array of `bytemuck::Pod` elements that shaders read and
+//!   write as `var<storage>`, and remembers how many elements it holds.
+//! - [`ComputePass`] is a compute pipeline running one entry point of a shader, which
+//!   dispatches enough workgroups to cover a given number of invocations.
+//!
+//! Both are created from the `Gpu`, with [`Gpu::create_storage_buffer`] and
+//! [`Gpu::create_compute_pass`]. Compute shaders are not available on every backend (notably
+//! WebGL), so `GpuCapabilities::supports_compute` must be checked before creating a pass.
+//!
+//! A dispatch never exceeds the device's limit of workgroups per dimension: larger counts are
+//! spread over the `y` dimension, so shaders compute the index of their invocation as
+//! `id.x + id.y * num_workgroups.x * WORKGROUP_SIZE` from the `global_invocation_id` and
+//! `num_workgroups` builtins, and skip indices past the end of their data.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let particles = gpu.create_storage_buffer("Particles", &initial, wgpu::BufferUsages::empty());
+//! let simulate = gpu.create_compute_pass(
+//!     "Simulate Particles",
+//!     SHADER_SOURCE,
+//!     "simulate",
+//!     &[&bind_group_layout],
+//!     64,
+//!     None,
+//! );
+//!
+//! // Once per frame:
+//! simulate.dispatch(&mut encoder, &[&bind_group], particles.len());
+//! ```
+
+use crate::gpu::Gpu;
+
+/// A buffer of elements read and written by shaders as `var<storage>`.
+#[derive(Debug)]
+pub struct StorageBuffer {
+    /// The buffer, with at least the `STORAGE` and `COPY_DST` usages.
+    buffer: wgpu::Buffer,
+
+    /// The number of elements in the buffer.
+    len: u32,
+}
+
+impl StorageBuffer {
+    /// Creates a buffer holding `contents`, with the `usage` flags added to `STORAGE` and
+    /// `COPY_DST`, such as `VERTEX` to also read the elements as vertices.
+    pub fn new<T: bytemuck::Pod>(
+        device: &wgpu::Device,
+        label: &str,
+        contents: &[T],
+        usage: wgpu::BufferUsages,
+    ) -> Self {
+        let buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: bytemuck::cast_slice(contents),
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST | usage,
+            },
+        );
+        Self {
+            buffer,
+            len: contents.len() as u32,
+        }
+    }
+
+    /// Returns the underlying buffer.
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// Returns the binding of the whole buffer, for the generated bind group structs.
+    pub fn binding(&self) -> wgpu::BufferBinding<'_> {
+        self.buffer.as_entire_buffer_binding()
+    }
+
+    /// Returns the number of elements in the buffer.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Returns `true` if the buffer holds no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// A compute pipeline running one entry point of a shader.
+#[derive(Debug)]
+pub struct ComputePass {
+    /// The name of the pass, shown by graphics debuggers.
+    label: String,
+
+    /// The pipeline running the entry point.
+    pipeline: wgpu::ComputePipeline,
+
+    /// The number of invocations per workgroup, matching the entry point's
+    /// `@workgroup_size`.
+    workgroup_size: u32,
+
+    /// The device's limit on workgroups per dispatch dimension.
+    max_workgroups: u32,
+}
+
+impl ComputePass {
+    /// Creates a pass running `entry_point` of `module`, with one bind group of each of
+    /// `bind_group_layouts`, compiled through `cache` if one is given. `workgroup_size` must
+    /// match the entry point's one-dimensional `@workgroup_size`.
+    pub fn new(
+        device: &wgpu::Device,
+        label: &str,
+        module: &wgpu::ShaderModule,
+        entry_point: &str,
+        bind_group_layouts: &[&wgpu::BindGroupLayout],
+        workgroup_size: u32,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(label),
+            bind_group_layouts,
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some(label),
+            layout: Some(&layout),
+            module,
+            entry_point: Some(entry_point),
+            compilation_options: Default::default(),
+            cache,
+        });
+
+        Self {
+            label: label.to_owned(),
+            pipeline,
+            workgroup_size: workgroup_size.max(1),
+            max_workgroups: device.limits().max_compute_workgroups_per_dimension.max(1),
+        }
+    }
+
+    /// Returns the number of workgroups along `x` and `y` covering `invocations`.
+    ///
+    /// Up to the device's limit the workgroups form a single row; beyond it, further rows are
+    /// added, and the last row may run past `invocations`.
+    pub fn workgroups(&self, invocations: u32) -> (u32, u32) {
+        let workgroups = invocations.div_ceil(self.workgroup_size);
+        let x = workgroups.min(self.max_workgroups);
+        (x, workgroups.div_ceil(x.max(1)))
+    }
+
+    /// Records a compute pass into `encoder` running the entry point `invocations` times, with
+    /// `bind_groups` bound to the groups of the same index. Nothing is recorded for zero
+    /// invocations.
+    pub fn dispatch(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        bind_groups: &[&wgpu::BindGroup],
+        invocations: u32,
+    ) {
+        let (x, y) = self.workgroups(invocations);
+        if x == 0 || y == 0 {
+            return;
+        }
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some(&self.label),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        for (index, bind_group) in bind_groups.iter().enumerate() {
+            pass.set_bind_group(index as u32, *bind_group, &[]);
+        }
+        pass.dispatch_workgroups(x, y, 1);
+    }
+}
+
+impl Gpu {
+    /// Creates a [`StorageBuffer`] holding `contents`, with the `usage` flags added to
+    /// `STORAGE` and `COPY_DST`.
+    pub fn create_storage_buffer<T: bytemuck::Pod>(
+        &self,
+        label: &str,
+        contents: &[T],
+        usage: wgpu::BufferUsages,
+    ) -> StorageBuffer {
+        StorageBuffer::new(&self.device, label, contents, usage)
+    }
+
+    /// Compiles the WGSL `source` and creates a [`ComputePass`] running its `entry_point`,
+    /// compiled through `cache` if one is given.
+    ///
+    /// Compute shaders require `GpuCapabilities::supports_compute`.
+    pub fn create_compute_pass(
+        &self,
+        label: &str,
+        source: &str,
+        entry_point: &str,
+        bind_group_layouts: &[&wgpu::BindGroupLayout],
+        workgroup_size: u32,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> ComputePass {
+        let module = self
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(source)),
+            });
+        ComputePass::new(
+            &self.device,
+            label,
+            &module,
+            entry_point,
+            bind_group_layouts,
+            workgroup_size,
+            cache,
+        )
+    }
+}
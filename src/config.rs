@@ -0,0 +1,388 @@
+//! # Config Module
+//!
+//! The `config` module holds the options an application sets once, before the `App` creates
+//! its window and renderer: the window's title, size, style, and icon, the canvas it draws
+//! into on the web, when frames are redrawn, the asset pack assets are read from, and the
+//! graphics backends and present mode the renderer starts with.
+//!
+//! ## Overview
+//!
+//! - [`AppConfig`] holds the window options and a [`RendererConfig`], and is handed to the
+//!   `App` with `App::with_config`. Its builder methods start from the defaults, so only the
+//!   options that differ need to be set.
+//! - [`RendererConfig`] is passed on to `RendererBackend::init`, and from the wgpu `Renderer`
+//!   to `Gpu::new_async`, which creates the `wgpu::Instance` with its backends, picks the
+//!   adapter, and configures the surface with its present mode.
+//! - [`BackendSelection`] and [`AdapterSelection`] choose the graphics backend and the adapter.
+//!   Both parse from strings, so `main` reads them from the `--backend` and `--adapter`
+//!   arguments or the `WGPU_BACKEND` and `WGPU_ADAPTER` environment variables.
+//!
+//! Unlike the `settings` module's preferences, the configuration is chosen by the application
+//! and is not persisted. The present mode and the redraw policy can still be changed at runtime
+//! in the Inspector.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let config = AppConfig::default()
+//!     .with_title("Viewer")
+//!     .with_icon(include_bytes!("icon.png"))
+//!     .with_app_name("org.example.Viewer", "viewer")
+//!     .with_inner_size(1280, 720)
+//!     .with_backends(wgpu::Backends::VULKAN | wgpu::Backends::METAL)
+//!     .with_vsync(false);
+//! let mut app = App::default().with_config(config);
+//! event_loop.run_app(&mut app)?;
+//!
+//! // Or, from the command line: `--backend vulkan --adapter nvidia`.
+//! let config = AppConfig::default()
+//!     .with_backend("vulkan".parse()?)
+//!     .with_adapter("nvidia".parse()?);
+//! ```
+
+// Importing the redraw policy, which the `App` starts with.
+use crate::gpu::Gpu;
+use crate::redraw::RedrawPolicy;
+
+/// The graphics backend the renderer uses, or `Auto` to let `wgpu` choose.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackendSelection {
+    /// Any backend available on the platform.
+    #[default]
+    Auto,
+
+    /// Vulkan, on Windows, Linux, and Android.
+    Vulkan,
+
+    /// Direct3D 12, on Windows.
+    Dx12,
+
+    /// Metal, on macOS and iOS.
+    Metal,
+
+    /// OpenGL, OpenGL ES, or WebGL.
+    Gl,
+}
+
+impl BackendSelection {
+    /// Every selection, in the order they are listed in the command-line help.
+    pub const ALL: [BackendSelection; 5] = [
+        BackendSelection::Auto,
+        BackendSelection::Vulkan,
+        BackendSelection::Dx12,
+        BackendSelection::Metal,
+        BackendSelection::Gl,
+    ];
+
+    /// Returns the name the selection is parsed from.
+    pub fn label(self) -> &'static str {
+        match self {
+            BackendSelection::Auto => "auto",
+            BackendSelection::Vulkan => "vulkan",
+            BackendSelection::Dx12 => "dx12",
+            BackendSelection::Metal => "metal",
+            BackendSelection::Gl => "gl",
+        }
+    }
+
+    /// Returns the backends an adapter may be picked from.
+    pub fn backends(self) -> wgpu::Backends {
+        match self {
+            BackendSelection::Auto => wgpu::InstanceDescriptor::default().backends,
+            BackendSelection::Vulkan => wgpu::Backends::VULKAN,
+            BackendSelection::Dx12 => wgpu::Backends::DX12,
+            BackendSelection::Metal => wgpu::Backends::METAL,
+            BackendSelection::Gl => wgpu::Backends::GL,
+        }
+    }
+}
+
+impl std::str::FromStr for BackendSelection {
+    type Err = ConfigError;
+
+    /// Parses a backend's name, ignoring case. `d3d12` and `opengl` are accepted as well.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let name = name.trim().to_ascii_lowercase();
+        let alias = match name.as_str() {
+            "d3d12" => "dx12",
+            "opengl" | "gles" | "webgl" => "gl",
+            name => name,
+        };
+        Self::ALL
+            .into_iter()
+            .find(|selection| selection.label() == alias)
+            .ok_or_else(|| ConfigError::UnknownBackend(name.clone()))
+    }
+}
+
+/// The adapter the renderer uses, among those of the selected backends.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub enum AdapterSelection {
+    /// The adapter `wgpu` prefers for the surface.
+    #[default]
+    Default,
+
+    /// The adapter at this position among those of the selected backends that can present to
+    /// the window, in the order `wgpu` enumerates them. The order is logged when no adapter
+    /// matches.
+    Index(usize),
+
+    /// The first adapter whose name contains this text, ignoring case.
+    Name(String),
+}
+
+impl AdapterSelection {
+    /// Returns whether `info`, the adapter at `index` in the list of adapters, is selected.
+    pub fn matches(&self, index: usize, info: &wgpu::AdapterInfo) -> bool {
+        match self {
+            AdapterSelection::Default => true,
+            AdapterSelection::Index(selected) => *selected == index,
+            AdapterSelection::Name(name) => info.name.to_lowercase().contains(&name.to_lowercase()),
+        }
+    }
+}
+
+impl std::str::FromStr for AdapterSelection {
+    type Err = ConfigError;
+
+    /// Parses an index, a part of an adapter's name, or `default`.
+    fn from_str(selection: &str) -> Result<Self, Self::Err> {
+        let selection = selection.trim();
+        if selection.is_empty() {
+            return Err(ConfigError::EmptyAdapter);
+        }
+        if selection.eq_ignore_ascii_case("default") {
+            return Ok(AdapterSelection::Default);
+        }
+        Ok(match selection.parse() {
+            Ok(index) => AdapterSelection::Index(index),
+            Err(_) => AdapterSelection::Name(selection.to_string()),
+        })
+    }
+}
+
+/// Errors produced while parsing or applying configuration options.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConfigError {
+    /// The backend name is not one of `auto`, `vulkan`, `dx12`, `metal`, or `gl`.
+    #[error("unknown backend '{0}', expected auto, vulkan, dx12, metal, or gl")]
+    UnknownBackend(String),
+
+    /// The adapter selection is empty.
+    #[error("no adapter given, expected an index or a part of its name")]
+    EmptyAdapter,
+
+    /// The window icon is not a PNG, or too large for the platform.
+    #[error("invalid window icon: {0}")]
+    InvalidIcon(String),
+}
+
+/// The options the renderer is created with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RendererConfig {
+    /// The graphics backends an adapter may be picked from.
+    pub backends: wgpu::Backends,
+
+    /// The adapter picked among those of `backends`. If no adapter matches, the default one
+    /// is used and a warning is logged. The selection is ignored on the web, and when a
+    /// fallback adapter is requested.
+    pub adapter: AdapterSelection,
+
+    /// The present mode the surface is configured with, or the nearest one it supports.
+    pub present_mode: wgpu::PresentMode,
+
+    /// The number of frames that may be queued for presentation, at least one.
+    pub frame_latency: u32,
+}
+
+/// The renderer may use any backend and the default adapter, and presents with vsync.
+impl Default for RendererConfig {
+    fn default() -> Self {
+        Self {
+            backends: BackendSelection::Auto.backends(),
+            adapter: AdapterSelection::Default,
+            present_mode: wgpu::PresentMode::Fifo,
+            frame_latency: Gpu::DEFAULT_FRAME_LATENCY,
+        }
+    }
+}
+
+/// The options the `App` creates its window and renderer with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppConfig {
+    /// The window's title. It has no effect on the web.
+    pub title: String,
+
+    /// The window's initial inner size in logical pixels, or `None` for the platform's default.
+    /// It has no effect on the web, where the canvas keeps its size.
+    pub inner_size: Option<(u32, u32)>,
+
+    /// The id of the HTML canvas element drawn into on the web.
+    pub canvas_id: String,
+
+    /// Whether the window is transparent, compositing the scene over the desktop. See
+    /// `App::with_transparent_window`.
+    pub transparent_window: bool,
+
+    /// Whether the window stays on top of other windows. See `App::with_always_on_top`.
+    pub always_on_top: bool,
+
+    /// Whether the window is undecorated, with a title bar drawn by the GUI instead. See
+    /// `App::with_custom_titlebar`.
+    pub custom_titlebar: bool,
+
+    /// Whether the window is undecorated and has no title bar at all. See
+    /// `App::with_borderless_window`.
+    pub borderless_window: bool,
+
+    /// The PNG the window's icon is decoded from when the window is created, usually embedded
+    /// with `include_bytes!`, or `None` for the platform's default icon. It has no effect on
+    /// the web and on macOS, where the icon comes from the page and the bundle.
+    pub icon: Option<&'static [u8]>,
+
+    /// The general and instance names of the application on Linux: the app id on Wayland, and
+    /// the class and instance of `WM_CLASS` on X11, which the taskbar groups the windows and
+    /// finds their `.desktop` file by. `None` leaves them to `winit`'s defaults.
+    pub app_name: Option<(String, String)>,
+
+    /// When frames are rendered: continuously, on demand, or at a fixed rate.
+    pub redraw_policy: RedrawPolicy,
+
+    /// The path or HTTP(S) URL of an asset pack built by the `pack` subcommand, or `None`. It
+    /// is mounted into the asset loader before the model and the texture are loaded, so they
+    /// are read from the pack when it holds them. On the web, this loads them with one fetch.
+    pub asset_pack: Option<String>,
+
+    /// The options the renderer is created with.
+    pub renderer: RendererConfig,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            title: "Standalone Winit/Wgpu Example".to_string(),
+            inner_size: None,
+            canvas_id: "canvas".to_string(),
+            transparent_window: false,
+            always_on_top: false,
+            custom_titlebar: false,
+            borderless_window: false,
+            icon: None,
+            app_name: None,
+            redraw_policy: RedrawPolicy::Continuous,
+            asset_pack: None,
+            renderer: RendererConfig::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Returns the configuration with `title` as the window's title.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Returns the configuration with an initial inner size of `width` x `height` logical
+    /// pixels.
+    pub fn with_inner_size(mut self, width: u32, height: u32) -> Self {
+        self.inner_size = Some((width, height));
+        self
+    }
+
+    /// Returns the configuration drawing into the HTML canvas with the id `canvas_id` on the
+    /// web.
+    pub fn with_canvas_id(mut self, canvas_id: impl Into<String>) -> Self {
+        self.canvas_id = canvas_id.into();
+        self
+    }
+
+    /// Returns the configuration with the window icon decoded from the PNG `icon`.
+    pub fn with_icon(mut self, icon: &'static [u8]) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Returns the configuration naming the application `general` and `instance` on Linux.
+    /// `general` should match the application's `.desktop` file, such as `org.example.Viewer`.
+    pub fn with_app_name(
+        mut self,
+        general: impl Into<String>,
+        instance: impl Into<String>,
+    ) -> Self {
+        self.app_name = Some((general.into(), instance.into()));
+        self
+    }
+
+    /// Decodes the window icon, if one is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidIcon` if the icon is not a PNG or cannot be used as one.
+    pub fn window_icon(&self) -> Result<Option<winit::window::Icon>, ConfigError> {
+        let Some(png) = self.icon else {
+            return Ok(None);
+        };
+        let image = image::load_from_memory_with_format(png, image::ImageFormat::Png)
+            .map_err(|error| ConfigError::InvalidIcon(error.to_string()))?
+            .to_rgba8();
+        let (width, height) = image.dimensions();
+        winit::window::Icon::from_rgba(image.into_raw(), width, height)
+            .map(Some)
+            .map_err(|error| ConfigError::InvalidIcon(error.to_string()))
+    }
+
+    /// Returns the configuration rendering frames as `redraw_policy` says.
+    pub fn with_redraw_policy(mut self, redraw_policy: RedrawPolicy) -> Self {
+        self.redraw_policy = redraw_policy;
+        self
+    }
+
+    /// Returns the configuration mounting the asset pack at `source`, a path or an HTTP(S) URL.
+    pub fn with_asset_pack(mut self, source: impl Into<String>) -> Self {
+        self.asset_pack = Some(source.into());
+        self
+    }
+
+    /// Returns the configuration picking an adapter from `backends` only.
+    pub fn with_backends(mut self, backends: wgpu::Backends) -> Self {
+        self.renderer.backends = backends;
+        self
+    }
+
+    /// Returns the configuration picking an adapter of the selected `backend`.
+    pub fn with_backend(self, backend: BackendSelection) -> Self {
+        self.with_backends(backend.backends())
+    }
+
+    /// Returns the configuration using the selected `adapter`.
+    pub fn with_adapter(mut self, adapter: AdapterSelection) -> Self {
+        self.renderer.adapter = adapter;
+        self
+    }
+
+    /// Returns the configuration presenting with vsync (`Fifo`), or without it
+    /// (`Immediate`, or the nearest mode the surface supports).
+    pub fn with_vsync(mut self, vsync: bool) -> Self {
+        self.renderer.present_mode = if vsync {
+            wgpu::PresentMode::Fifo
+        } else {
+            wgpu::PresentMode::Immediate
+        };
+        self
+    }
+
+    /// Returns the configuration presenting with `present_mode`, or the nearest mode the
+    /// surface supports.
+    pub fn with_present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.renderer.present_mode = present_mode;
+        self
+    }
+
+    /// Returns the configuration letting at most `frame_latency` frames be queued for
+    /// presentation, at least one. Fewer frames show input sooner but leave the GPU idle more.
+    pub fn with_frame_latency(mut self, frame_latency: u32) -> Self {
+        self.renderer.frame_latency = frame_latency.max(1);
+        self
+    }
+}
@@ -0,0 +1,348 @@
+//! # CPU Profiler Module
+//!
+//! The `cpu_profiler` module measures where the CPU time of a frame goes, in named scopes
+//! around event handling, the GUI pass, the uniform updates, and the command submission, so
+//! users can see why the application is slow on their machines.
+//!
+//! ## Overview
+//!
+//! [`CpuProfiler`] is a cheap handle to the recorded scopes, cloned into every part of the
+//! application that records them, such as the `App` and the `Renderer`. It is off by default
+//! and toggled at runtime with [`CpuProfiler::set_enabled`]; while off, opening a scope costs
+//! a single atomic load.
+//!
+//! - [`CpuProfiler::scope`] opens a scope that closes when the returned guard is dropped.
+//!   Scopes opened while another is open are nested inside it.
+//! - [`CpuProfiler::finish_frame`] closes the frame, which keeps the scopes recorded since the
+//!   previous call. The latest [`CpuProfiler::HISTORY`] frames are kept.
+//! - [`CpuProfiler::write_chrome_trace`] writes the kept frames in the Chrome trace event
+//!   format, which `chrome://tracing`, Perfetto, and Speedscope open.
+//!
+//! Scopes are recorded on the thread of the event loop only: the simulation thread and the
+//! background tasks are not profiled.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let profiler = CpuProfiler::default();
+//! profiler.set_enabled(true);
+//!
+//! // Every frame:
+//! {
+//!     let _scope = profiler.scope("Uniform Updates");
+//!     scene.update(&mut uploads, aspect_ratio);
+//! }
+//! profiler.finish_frame();
+//!
+//! if let Some(frame) = profiler.latest_frame() {
+//!     for scope in &frame.scopes {
+//!         println!("{}{}: {:?}", "  ".repeat(scope.depth), scope.name, scope.duration);
+//!     }
+//! }
+//! ```
+
+// Importing `Instant` from the `web_time` crate, which also measures time on the web.
+use web_time::Instant;
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A closed scope of a profiled frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuScope {
+    /// The name the scope was opened with.
+    pub name: &'static str,
+
+    /// When the scope was opened, relative to when the profiler was created.
+    pub start: crate::Duration,
+
+    /// How long the scope was open.
+    pub duration: crate::Duration,
+
+    /// The number of scopes the scope is nested in.
+    pub depth: usize,
+}
+
+/// The scopes recorded in one frame, in the order they were opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuFrame {
+    /// The number of frames finished before this one.
+    pub index: u64,
+
+    /// When the frame began, relative to when the profiler was created.
+    pub start: crate::Duration,
+
+    /// The time from the beginning of the frame to its end.
+    pub duration: crate::Duration,
+
+    /// The scopes closed during the frame.
+    pub scopes: Vec<CpuScope>,
+}
+
+/// The recording shared by the clones of a [`CpuProfiler`].
+#[derive(Debug)]
+struct Recording {
+    /// The scopes of the current frame, and whether each was closed.
+    scopes: Vec<(CpuScope, bool)>,
+
+    /// The number of open scopes.
+    depth: usize,
+
+    /// When the current frame began.
+    frame_start: crate::Duration,
+
+    /// The number of finished frames.
+    frame_index: u64,
+
+    /// Counts the times the current scopes were discarded, by finishing a frame or toggling
+    /// the recording, so guards of discarded scopes leave the current ones alone.
+    generation: u64,
+
+    /// The latest finished frames, oldest first.
+    frames: VecDeque<CpuFrame>,
+}
+
+/// The state shared by the clones of a [`CpuProfiler`].
+#[derive(Debug)]
+struct Shared {
+    /// Whether scopes are recorded.
+    enabled: AtomicBool,
+
+    /// The instant the times of the scopes are relative to.
+    epoch: Instant,
+
+    /// The recorded scopes and frames.
+    recording: Mutex<Recording>,
+}
+
+/// A handle to the scopes recorded by the CPU profiler, shared by its clones.
+#[derive(Debug, Clone)]
+pub struct CpuProfiler {
+    /// The state shared with the other clones.
+    shared: Arc<Shared>,
+}
+
+impl Default for CpuProfiler {
+    fn default() -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                enabled: AtomicBool::new(false),
+                epoch: Instant::now(),
+                recording: Mutex::new(Recording {
+                    scopes: Vec::new(),
+                    depth: 0,
+                    frame_start: crate::Duration::ZERO,
+                    frame_index: 0,
+                    generation: 0,
+                    frames: VecDeque::new(),
+                }),
+            }),
+        }
+    }
+}
+
+impl CpuProfiler {
+    /// The number of finished frames kept for the viewer and the trace export.
+    pub const HISTORY: usize = 300;
+
+    /// Returns whether scopes are recorded.
+    pub fn is_enabled(&self) -> bool {
+        self.shared.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Starts or stops recording scopes. Stopping keeps the finished frames, so they can still
+    /// be viewed and exported.
+    pub fn set_enabled(&self, enabled: bool) {
+        if self.shared.enabled.swap(enabled, Ordering::Relaxed) != enabled {
+            let mut recording = self.lock();
+            recording.scopes.clear();
+            recording.depth = 0;
+            recording.generation += 1;
+            recording.frame_start = self.now();
+        }
+    }
+
+    /// Opens a scope named `name`, which closes when the returned guard is dropped.
+    pub fn scope(&self, name: &'static str) -> CpuScopeGuard {
+        if !self.is_enabled() {
+            return CpuScopeGuard { open: None };
+        }
+        let start = self.now();
+        let mut recording = self.lock();
+        let depth = recording.depth;
+        recording.depth += 1;
+        let scope = CpuScope {
+            name,
+            start,
+            duration: crate::Duration::ZERO,
+            depth,
+        };
+        recording.scopes.push((scope, false));
+        let open = (
+            self.clone(),
+            recording.generation,
+            recording.scopes.len() - 1,
+        );
+        CpuScopeGuard { open: Some(open) }
+    }
+
+    /// Ends the current frame and begins the next one. Scopes still open are left out of the
+    /// finished frame.
+    pub fn finish_frame(&self) {
+        if !self.is_enabled() {
+            return;
+        }
+        let now = self.now();
+        let mut recording = self.lock();
+        let scopes = recording
+            .scopes
+            .drain(..)
+            .filter_map(|(scope, closed)| closed.then_some(scope))
+            .collect();
+        let frame = CpuFrame {
+            index: recording.frame_index,
+            start: recording.frame_start,
+            duration: now.saturating_sub(recording.frame_start),
+            scopes,
+        };
+        if recording.frames.len() == Self::HISTORY {
+            recording.frames.pop_front();
+        }
+        recording.frames.push_back(frame);
+        recording.frame_index += 1;
+        recording.generation += 1;
+        recording.frame_start = now;
+        recording.depth = 0;
+    }
+
+    /// Returns the most recently finished frame, or `None` before the first one.
+    pub fn latest_frame(&self) -> Option<CpuFrame> {
+        self.lock().frames.back().cloned()
+    }
+
+    /// Writes the kept frames to `writer` as a Chrome trace: one complete event for each
+    /// scope and each frame, in microseconds.
+    pub fn write_chrome_trace(&self, writer: impl std::io::Write) -> serde_json::Result<()> {
+        let recording = self.lock();
+        let mut events = Vec::new();
+        for frame in &recording.frames {
+            events.push(TraceEvent::new("Frame", frame.start, frame.duration));
+            events.extend(
+                frame
+                    .scopes
+                    .iter()
+                    .map(|scope| TraceEvent::new(scope.name, scope.start, scope.duration)),
+            );
+        }
+        serde_json::to_writer(
+            writer,
+            &ChromeTrace {
+                trace_events: events,
+                display_time_unit: "ms",
+            },
+        )
+    }
+
+    /// Writes the kept frames as a Chrome trace into the working directory, named after the
+    /// time, returning its path.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_chrome_trace(&self) -> std::io::Result<String> {
+        let seconds = web_time::SystemTime::now()
+            .duration_since(web_time::SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = std::env::current_dir()?.join(format!("cpu-trace-{seconds}.json"));
+        let file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+        self.write_chrome_trace(file)?;
+        Ok(path.display().to_string())
+    }
+
+    /// Returns the time since the profiler was created.
+    fn now(&self) -> crate::Duration {
+        self.shared.epoch.elapsed()
+    }
+
+    /// Locks the recording, even if a thread panicked while holding it.
+    fn lock(&self) -> std::sync::MutexGuard<'_, Recording> {
+        self.shared
+            .recording
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+/// Closes the scope it was returned for when dropped.
+#[derive(Debug)]
+#[must_use = "the scope closes as soon as the guard is dropped"]
+pub struct CpuScopeGuard {
+    /// The profiler, the generation of the recording, and the index of the scope, or `None`
+    /// if the profiler was disabled when the scope was opened.
+    open: Option<(CpuProfiler, u64, usize)>,
+}
+
+impl Drop for CpuScopeGuard {
+    fn drop(&mut self) {
+        let Some((profiler, generation, index)) = self.open.take() else {
+            return;
+        };
+        let now = profiler.now();
+        let mut recording = profiler.lock();
+        // A frame finished while the scope was open no longer holds it.
+        if recording.generation != generation {
+            return;
+        }
+        recording.depth = recording.depth.saturating_sub(1);
+        if let Some((scope, closed)) = recording.scopes.get_mut(index) {
+            scope.duration = now.saturating_sub(scope.start);
+            *closed = true;
+        }
+    }
+}
+
+/// A trace in the Chrome trace event format.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChromeTrace {
+    /// The complete events of the frames and their scopes.
+    trace_events: Vec<TraceEvent>,
+
+    /// The unit the viewers show times in.
+    display_time_unit: &'static str,
+}
+
+/// A complete event of the Chrome trace event format, with a beginning and a duration.
+#[derive(serde::Serialize)]
+struct TraceEvent {
+    /// The name of the scope.
+    name: &'static str,
+
+    /// The phase, `"X"` for complete events.
+    ph: &'static str,
+
+    /// The time the scope began, in microseconds.
+    ts: f64,
+
+    /// How long the scope was open, in microseconds.
+    dur: f64,
+
+    /// The process, always the application's.
+    pid: u32,
+
+    /// The thread, always the event loop's.
+    tid: u32,
+}
+
+impl TraceEvent {
+    /// Returns the event of a scope named `name`.
+    fn new(name: &'static str, start: crate::Duration, duration: crate::Duration) -> Self {
+        Self {
+            name,
+            ph: "X",
+            ts: start.as_secs_f64() * 1e6,
+            dur: duration.as_secs_f64() * 1e6,
+            pid: 1,
+            tid: 1,
+        }
+    }
+}
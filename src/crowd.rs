@@ -0,0 +1,602 @@
+//! # Crowd Module
+//!
+//! The `crowd` module is a gallery demo and stress scenario combining instancing, skinning,
+//! and level of detail: hundreds of walking characters drawn with two instanced draw calls.
+//!
+//! ## Overview
+//!
+//! - **Instancing**: Each character is a [`CrowdInstance`] (position, heading, and animation
+//!   phase) in a storage buffer, so the number of characters only changes buffer sizes.
+//! - **Skinning**: The character mesh is bound to a five-bone skeleton ([`Bone`]). Vertices
+//!   near a joint are weighted between two bones, and the walk cycle is evaluated per vertex
+//!   in `crowd.wgsl` from the instance's phase.
+//! - **Level of detail**: Every frame, the instances are split by their distance to the camera
+//!   into a detailed mesh and a single-box impostor. Each level draws only the instances listed
+//!   in its own index buffer.
+//!
+//! Storage buffers in the vertex stage are not available on every backend (notably WebGL), so
+//! [`CrowdDemo::is_supported`] must be checked before creating the demo.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut crowd =
+//!     CrowdDemo::new(&device, surface_format, CrowdDemo::DEFAULT_COUNT, seed, None);
+//!
+//! // Once per frame:
+//! crowd.update(&queue, &view_projection, &camera_position, delta_time);
+//! crowd.render(&mut render_pass);
+//! ```
+
+use crate::camera::Bounds;
+use crate::determinism::DeterministicRng;
+#[cfg(feature = "gui")]
+use crate::preview::PreviewGeometry;
+use crate::renderer::Renderer;
+use crate::scene::Scene;
+use crate::shader_bindings::crowd as crowd_bindings;
+
+/// The WGSL source of the crowd shader.
+const CROWD_SHADER_SOURCE: &str = include_str!("crowd.wgsl");
+
+/// The bones of a crowd character's skeleton, matching the constants in `crowd.wgsl`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bone {
+    Hips = 0,
+    LeftLeg = 1,
+    RightLeg = 2,
+    LeftArm = 3,
+    RightArm = 4,
+}
+
+/// A vertex of a crowd character, bound to up to two bones.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CrowdVertex {
+    position: [f32; 3],
+    color: [f32; 3],
+    bones: [u32; 2],
+    weights: [f32; 2],
+}
+
+impl CrowdVertex {
+    /// Returns the vertex attributes, matching `VertexInput` in the shader.
+    pub fn vertex_attributes() -> Vec<wgpu::VertexAttribute> {
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Uint32x2, 3 => Float32x2]
+            .to_vec()
+    }
+
+    /// Returns the vertex buffer layout for the given `attributes`.
+    pub fn description(attributes: &[wgpu::VertexAttribute]) -> wgpu::VertexBufferLayout {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<CrowdVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes,
+        }
+    }
+}
+
+/// One character of the crowd, stored in the instance storage buffer.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CrowdInstance {
+    /// The position of the character's feet in world space.
+    pub position: [f32; 3],
+
+    /// The rotation around the Y axis, in radians.
+    pub heading: f32,
+
+    /// The offset into the walk cycle, in radians.
+    pub phase: f32,
+
+    /// The number of walk cycles per second.
+    pub speed: f32,
+
+    /// Pads the struct to the 16 byte alignment of the WGSL `Instance`.
+    _padding: [f32; 2],
+}
+
+/// The uniform data of the crowd shader.
+#[repr(C)]
+#[derive(Default, Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct CrowdUniform {
+    view_projection: crate::math::Mat4,
+    time: f32,
+    _padding: [f32; 3],
+}
+
+/// Builds the triangles of character meshes out of boxes.
+#[derive(Debug, Default)]
+struct MeshBuilder {
+    vertices: Vec<CrowdVertex>,
+    indices: Vec<u32>,
+}
+
+impl MeshBuilder {
+    /// Adds a box from `min` to `max`, bound to `bone`.
+    ///
+    /// With a `joint`, the top vertices are weighted half to `bone` and half to the joint's
+    /// bone, so the box bends smoothly where it meets its parent. Faces are shaded by their
+    /// orientation, as if lit from above.
+    fn cuboid(
+        &mut self,
+        min: [f32; 3],
+        max: [f32; 3],
+        color: [f32; 3],
+        bone: Bone,
+        joint: Option<Bone>,
+    ) {
+        // The four corners of each face and its shading. Bit `n` of a corner index selects
+        // `max` over `min` along axis `n`.
+        const FACES: [([usize; 4], f32); 6] = [
+            ([2, 3, 7, 6], 1.0),
+            ([0, 4, 5, 1], 0.45),
+            ([0, 1, 3, 2], 0.8),
+            ([4, 6, 7, 5], 0.7),
+            ([0, 2, 6, 4], 0.6),
+            ([1, 5, 7, 3], 0.65),
+        ];
+
+        for (corners, shade) in FACES {
+            let base = self.vertices.len() as u32;
+            for corner in corners {
+                let is_max = |axis: usize| (corner >> axis) & 1 == 1;
+                let position =
+                    [0, 1, 2].map(|axis| if is_max(axis) { max[axis] } else { min[axis] });
+                let (bones, weights) = match joint {
+                    Some(parent) if is_max(1) => ([bone as u32, parent as u32], [0.5, 0.5]),
+                    _ => ([bone as u32, bone as u32], [1.0, 0.0]),
+                };
+                self.vertices.push(CrowdVertex {
+                    position,
+                    color: color.map(|channel| channel * shade),
+                    bones,
+                    weights,
+                });
+            }
+            self.indices
+                .extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+    }
+}
+
+/// A character mesh uploaded to the GPU.
+#[derive(Debug)]
+struct CrowdMesh {
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    index_count: u32,
+}
+
+impl CrowdMesh {
+    fn new(device: &wgpu::Device, label: &str, builder: MeshBuilder) -> Self {
+        let vertex_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("{label} Vertex Buffer")),
+                contents: bytemuck::cast_slice(&builder.vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            },
+        );
+        let index_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("{label} Index Buffer")),
+                contents: bytemuck::cast_slice(&builder.indices),
+                usage: wgpu::BufferUsages::INDEX,
+            },
+        );
+        Self {
+            vertex_buffer,
+            index_buffer,
+            index_count: builder.indices.len() as u32,
+        }
+    }
+}
+
+/// The colors of a character's clothes and skin.
+const SHIRT: [f32; 3] = [0.2, 0.45, 0.8];
+const TROUSERS: [f32; 3] = [0.25, 0.25, 0.3];
+const SKIN: [f32; 3] = [0.9, 0.7, 0.55];
+
+/// Builds the detailed character: a torso and head on the hips, and two legs and two arms
+/// that swing around the hips and shoulders.
+fn detailed_character() -> MeshBuilder {
+    let mut mesh = MeshBuilder::default();
+    mesh.cuboid(
+        [-0.13, 0.25, -0.07],
+        [0.13, 0.5, 0.07],
+        SHIRT,
+        Bone::Hips,
+        None,
+    );
+    mesh.cuboid(
+        [-0.07, 0.52, -0.07],
+        [0.07, 0.66, 0.07],
+        SKIN,
+        Bone::Hips,
+        None,
+    );
+    mesh.cuboid(
+        [-0.12, 0.0, -0.05],
+        [-0.02, 0.28, 0.05],
+        TROUSERS,
+        Bone::LeftLeg,
+        Some(Bone::Hips),
+    );
+    mesh.cuboid(
+        [0.02, 0.0, -0.05],
+        [0.12, 0.28, 0.05],
+        TROUSERS,
+        Bone::RightLeg,
+        Some(Bone::Hips),
+    );
+    mesh.cuboid(
+        [-0.19, 0.26, -0.04],
+        [-0.14, 0.48, 0.04],
+        SHIRT,
+        Bone::LeftArm,
+        Some(Bone::Hips),
+    );
+    mesh.cuboid(
+        [0.14, 0.26, -0.04],
+        [0.19, 0.48, 0.04],
+        SHIRT,
+        Bone::RightArm,
+        Some(Bone::Hips),
+    );
+    mesh
+}
+
+/// Returns the detailed character in its rest pose, for its thumbnail in the Assets panel.
+#[cfg(feature = "gui")]
+pub(crate) fn character_preview() -> PreviewGeometry {
+    let mesh = detailed_character();
+    let positions: Vec<_> = mesh
+        .vertices
+        .iter()
+        .map(|vertex| crate::math::Vec3::from(vertex.position))
+        .collect();
+    let colors: Vec<_> = mesh.vertices.iter().map(|vertex| vertex.color).collect();
+    PreviewGeometry::from_triangles(&positions, &colors, &mesh.indices)
+}
+
+/// Builds the distant character: a single box covering the whole body.
+fn impostor_character() -> MeshBuilder {
+    let mut mesh = MeshBuilder::default();
+    mesh.cuboid(
+        [-0.13, 0.0, -0.06],
+        [0.13, 0.66, 0.06],
+        SHIRT,
+        Bone::Hips,
+        None,
+    );
+    mesh
+}
+
+/// One level of detail: a mesh and the instances drawn with it this frame.
+#[derive(Debug)]
+struct CrowdLod {
+    mesh: CrowdMesh,
+
+    /// The indices of the instances drawn with this level, rewritten every frame.
+    visible_buffer: wgpu::Buffer,
+
+    /// Binds the uniforms, the instances, and `visible_buffer`.
+    bind_group: wgpu::BindGroup,
+
+    /// The number of indices in `visible_buffer` written this frame.
+    visible_count: u32,
+}
+
+/// The crowd demo's GPU resources and animation state.
+#[derive(Debug)]
+pub struct CrowdDemo {
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+
+    /// The characters, kept on the CPU to choose their level of detail.
+    instances: Vec<CrowdInstance>,
+
+    /// The detailed level first, then the impostor.
+    lods: [CrowdLod; 2],
+
+    /// The time since the demo started, in seconds.
+    time: f32,
+
+    /// Characters closer to the camera than this use the detailed mesh.
+    lod_distance: f32,
+
+    /// The fraction of the characters drawn, from `0.0` to `1.0`.
+    density: f32,
+}
+
+impl CrowdDemo {
+    /// The number of characters the demo is shown with.
+    pub const DEFAULT_COUNT: usize = 400;
+
+    /// Characters closer to the camera than this use the detailed mesh, unless changed with
+    /// [`CrowdDemo::set_detail`].
+    pub const LOD_DISTANCE: f32 = 6.0;
+
+    /// The object id written for the crowd, reported by the depth probe.
+    pub const OBJECT_ID: u32 = 2;
+
+    /// The name shown for [`CrowdDemo::OBJECT_ID`] by the depth probe.
+    pub const OBJECT_NAME: &'static str = "Crowd";
+
+    /// The height of the ground the crowd walks on, matching the ground grid.
+    const GROUND_HEIGHT: f32 = -1.0;
+
+    /// The distance between neighboring characters.
+    const SPACING: f32 = 0.6;
+
+    /// The height of a character, from its feet to the top of its head.
+    const CHARACTER_HEIGHT: f32 = 0.66;
+
+    /// The farthest a character's limbs reach from its center, horizontally.
+    const CHARACTER_REACH: f32 = 0.19;
+
+    /// Returns `true` if the adapter can read storage buffers in vertex shaders.
+    pub fn is_supported(downlevel: &wgpu::DownlevelCapabilities, limits: &wgpu::Limits) -> bool {
+        downlevel
+            .flags
+            .contains(wgpu::DownlevelFlags::VERTEX_STORAGE)
+            && limits.max_storage_buffers_per_shader_stage >= 2
+    }
+
+    /// Creates a crowd of `count` characters, arranged in a square behind the scene, whose
+    /// variations are drawn from the "crowd" stream of `seed`. The pipeline is compiled
+    /// through `cache` if one is given.
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        count: usize,
+        seed: u64,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let instances = Self::arrange(count, &mut DeterministicRng::stream(seed, "crowd"));
+
+        let uniform_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Crowd Uniform Buffer"),
+                contents: bytemuck::cast_slice(&[CrowdUniform::default()]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+        let instance_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Crowd Instance Buffer"),
+                contents: bytemuck::cast_slice(&instances),
+                usage: wgpu::BufferUsages::STORAGE,
+            },
+        );
+
+        let bind_group_layout = crowd_bindings::BindGroup0::create_layout(device);
+
+        let lod = |label: &str, builder: MeshBuilder| {
+            let visible_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(&format!("{label} Visible Buffer")),
+                size: (count.max(1) * std::mem::size_of::<u32>()) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let bind_group = crowd_bindings::BindGroup0 {
+                ubo: uniform_buffer.as_entire_buffer_binding(),
+                instances: instance_buffer.as_entire_buffer_binding(),
+                visible: visible_buffer.as_entire_buffer_binding(),
+            }
+            .create(device, &bind_group_layout);
+            CrowdLod {
+                mesh: CrowdMesh::new(device, label, builder),
+                visible_buffer,
+                bind_group,
+                visible_count: 0,
+            }
+        };
+        let lods = [
+            lod("Crowd Detailed", detailed_character()),
+            lod("Crowd Impostor", impostor_character()),
+        ];
+
+        let pipeline = Self::create_pipeline(device, surface_format, &bind_group_layout, cache);
+
+        Self {
+            pipeline,
+            uniform_buffer,
+            instances,
+            lods,
+            time: 0.0,
+            lod_distance: Self::LOD_DISTANCE,
+            density: 1.0,
+        }
+    }
+
+    /// Returns the bounds of the crowd in world space, covering the characters in every
+    /// heading and pose, or `None` for an empty crowd.
+    pub fn bounds(&self) -> Option<Bounds> {
+        let (reach, height) = (Self::CHARACTER_REACH, Self::CHARACTER_HEIGHT);
+        let corners = self.instances.iter().flat_map(|instance| {
+            let position = crate::math::Vec3::from(instance.position);
+            [
+                position - crate::math::vec3(reach, 0.0, reach),
+                position + crate::math::vec3(reach, height, reach),
+            ]
+        });
+        Bounds::from_points(corners)
+    }
+
+    /// Returns the number of characters drawn with the detailed and the impostor mesh in the
+    /// most recent frame.
+    pub fn lod_counts(&self) -> (u32, u32) {
+        (self.lods[0].visible_count, self.lods[1].visible_count)
+    }
+
+    /// Sets the distance up to which characters use the detailed mesh, and the fraction of the
+    /// characters drawn, from `0.0` to `1.0`. The drawn characters are spread evenly over the
+    /// crowd, so a thinner crowd still fills the same area.
+    pub fn set_detail(&mut self, lod_distance: f32, density: f32) {
+        self.lod_distance = lod_distance.max(0.0);
+        self.density = density.clamp(0.0, 1.0);
+    }
+
+    /// Advances the animation by `delta_time` seconds and assigns every drawn character a level
+    /// of detail based on its distance to `camera_position`.
+    pub fn update(
+        &mut self,
+        queue: &wgpu::Queue,
+        view_projection: &crate::math::Mat4,
+        camera_position: &crate::math::Vec3,
+        delta_time: f32,
+    ) {
+        self.time += delta_time;
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[CrowdUniform {
+                view_projection: *view_projection,
+                time: self.time,
+                _padding: [0.0; 3],
+            }]),
+        );
+
+        // A character is drawn when the running count of drawn characters steps up at its
+        // index, which picks `density` of them at even intervals.
+        let density = self.density;
+        let drawn =
+            |index: u32| ((index + 1) as f32 * density).floor() > (index as f32 * density).floor();
+        let (near, far): (Vec<u32>, Vec<u32>) = (0..self.instances.len() as u32)
+            .filter(|index| drawn(*index))
+            .partition(|index| {
+                let position = crate::math::Vec3::from(self.instances[*index as usize].position);
+                crate::math::distance(&position, camera_position) < self.lod_distance
+            });
+        for (lod, visible) in self.lods.iter_mut().zip([near, far]) {
+            if !visible.is_empty() {
+                queue.write_buffer(&lod.visible_buffer, 0, bytemuck::cast_slice(&visible));
+            }
+            lod.visible_count = visible.len() as u32;
+        }
+    }
+
+    /// Draws the crowd with one instanced draw call per level of detail.
+    pub fn render<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        self.bind_pipeline(render_pass);
+        for lod in self.visible_lods() {
+            self.bind_lod(render_pass, lod);
+            self.draw_lod(render_pass, lod);
+        }
+    }
+
+    /// Returns the indices of the levels of detail with characters to draw, the detailed mesh
+    /// being `0`.
+    pub fn visible_lods(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.lods.len()).filter(|lod| self.lods[*lod].visible_count > 0)
+    }
+
+    /// Sets the crowd's pipeline.
+    pub fn bind_pipeline<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_pipeline(&self.pipeline);
+    }
+
+    /// Binds the instances and animation of level of detail `lod`, its material, at set 0.
+    pub fn bind_lod<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>, lod: usize) {
+        render_pass.set_bind_group(0, &self.lods[lod].bind_group, &[]);
+    }
+
+    /// Draws the visible characters of level of detail `lod` with one instanced draw call,
+    /// after the pipeline and `lod` are bound.
+    pub fn draw_lod<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>, lod: usize) {
+        let lod = &self.lods[lod];
+        render_pass.set_vertex_buffer(0, lod.mesh.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(lod.mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..lod.mesh.index_count, 0, 0..lod.visible_count);
+    }
+
+    /// Places `count` characters on a square grid behind the scene, with headings, phases, and
+    /// walking speeds drawn from `rng`.
+    fn arrange(count: usize, rng: &mut DeterministicRng) -> Vec<CrowdInstance> {
+        let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+        (0..count)
+            .map(|index| {
+                let (row, column) = (index / columns, index % columns);
+                let variation = rng.next_f32();
+                CrowdInstance {
+                    position: [
+                        (column as f32 - (columns - 1) as f32 / 2.0) * Self::SPACING,
+                        Self::GROUND_HEIGHT,
+                        -(row as f32) * Self::SPACING,
+                    ],
+                    heading: variation * std::f32::consts::TAU,
+                    phase: variation * 17.0,
+                    speed: 0.8 + 0.6 * variation,
+                    _padding: [0.0; 2],
+                }
+            })
+            .collect()
+    }
+
+    /// Creates the pipeline drawing the skinned characters into the scene pass's targets.
+    fn create_pipeline(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> wgpu::RenderPipeline {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Crowd Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(CROWD_SHADER_SOURCE)),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Crowd Pipeline Layout"),
+            bind_group_layouts: &[bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Crowd Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vertex_main"),
+                buffers: &[CrowdVertex::description(&CrowdVertex::vertex_attributes())],
+                compilation_options: Default::default(),
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: Renderer::DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fragment_main"),
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: surface_format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(wgpu::ColorTargetState {
+                        format: Scene::OBJECT_ID_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                ],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        })
+    }
+}
@@ -0,0 +1,119 @@
+// Skinned crowd.
+//
+// Every character is one instance. Its position, heading, and animation phase are read from a
+// storage buffer, through a list of the instances drawn with the current level of detail.
+// The walk cycle is computed in the vertex shader: each vertex is bound to up to two bones,
+// which swing around the hips and shoulders, and the posed positions are blended by weight.
+
+struct CrowdUniform {
+    view_projection: mat4x4<f32>,
+    // The time since the demo started, in seconds.
+    time: f32,
+};
+
+struct Instance {
+    position: vec3<f32>,
+    heading: f32,
+    // The offset into the walk cycle, in radians.
+    phase: f32,
+    // The number of walk cycles per second.
+    speed: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> ubo: CrowdUniform;
+
+@group(0) @binding(1)
+var<storage, read> instances: array<Instance>;
+
+// The indices of the instances drawn with this level of detail.
+@group(0) @binding(2)
+var<storage, read> visible: array<u32>;
+
+struct VertexInput {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) color: vec3<f32>,
+    @location(2) bones: vec2<u32>,
+    @location(3) weights: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec3<f32>,
+};
+
+// The bones of the skeleton, matching `Bone` in `crowd.rs`. Bone 0, the hips, only bobs.
+const LEFT_LEG: u32 = 1u;
+const RIGHT_LEG: u32 = 2u;
+const LEFT_ARM: u32 = 3u;
+const RIGHT_ARM: u32 = 4u;
+
+// The heights the legs and arms swing around.
+const HIP_HEIGHT: f32 = 0.25;
+const SHOULDER_HEIGHT: f32 = 0.45;
+
+// The object id written for the crowd, matching `CrowdDemo::OBJECT_ID`.
+const OBJECT_ID: u32 = 2u;
+
+fn rotate_x(position: vec3<f32>, angle: f32) -> vec3<f32> {
+    let c = cos(angle);
+    let s = sin(angle);
+    return vec3<f32>(position.x, c * position.y - s * position.z, s * position.y + c * position.z);
+}
+
+// Poses `position` with `bone` at the given point of the walk cycle.
+fn pose(position: vec3<f32>, bone: u32, phase: f32) -> vec3<f32> {
+    let swing = sin(phase);
+    var angle = 0.0;
+    var pivot = 0.0;
+    switch bone {
+        case LEFT_LEG: { angle = 0.6 * swing; pivot = HIP_HEIGHT; }
+        case RIGHT_LEG: { angle = -0.6 * swing; pivot = HIP_HEIGHT; }
+        case LEFT_ARM: { angle = -0.5 * swing; pivot = SHOULDER_HEIGHT; }
+        case RIGHT_ARM: { angle = 0.5 * swing; pivot = SHOULDER_HEIGHT; }
+        default: {}
+    }
+    let offset = vec3<f32>(0.0, pivot, 0.0);
+    let posed = rotate_x(position - offset, angle) + offset;
+    // The whole body bobs twice per cycle.
+    return posed + vec3<f32>(0.0, 0.03 * abs(swing), 0.0);
+}
+
+// A pseudo-random value in [0, 1) per instance, used to vary the characters' colors.
+fn hash(value: u32) -> f32 {
+    return fract(sin(f32(value) * 12.9898) * 43758.5453);
+}
+
+@vertex
+fn vertex_main(in: VertexInput) -> VertexOutput {
+    let index = visible[in.instance_index];
+    let instance = instances[index];
+    let phase = instance.phase + ubo.time * instance.speed * 6.2831853;
+
+    let skinned = pose(in.position, in.bones.x, phase) * in.weights.x
+        + pose(in.position, in.bones.y, phase) * in.weights.y;
+
+    let c = cos(instance.heading);
+    let s = sin(instance.heading);
+    let world = vec3<f32>(c * skinned.x + s * skinned.z, skinned.y, -s * skinned.x + c * skinned.z)
+        + instance.position;
+
+    var out: VertexOutput;
+    out.position = ubo.view_projection * vec4<f32>(world, 1.0);
+    out.color = in.color * (0.8 + 0.4 * hash(index));
+    return out;
+}
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) object_id: u32,
+};
+
+@fragment
+fn fragment_main(in: VertexOutput) -> FragmentOutput {
+    var out: FragmentOutput;
+    out.color = vec4<f32>(in.color, 1.0);
+    out.object_id = OBJECT_ID;
+    return out;
+}
@@ -0,0 +1,437 @@
+//! # Curves Module
+//!
+//! The `curves` module provides curve primitives for the debug/overlay line renderer, and an
+//! editor for their control points.
+//!
+//! ## Overview
+//!
+//! - [`Curve`] is a cubic Bezier curve or a Catmull-Rom spline through a list of points.
+//!   Both are evaluated as a chain of cubic Bezier segments.
+//! - [`Curve::tessellate`] turns a curve into a polyline, subdividing each segment until the
+//!   on-screen distance between the curve and the polyline is below a tolerance in pixels.
+//!   Curves far away from the camera therefore use few segments, and close ones many.
+//! - [`CurveEditor`] picks control points under the cursor and drags them parallel to the
+//!   screen. The `App` uses it to edit and visualize the camera's flight path.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let path = Curve::CatmullRom {
+//!     points: vec![a, b, c, d],
+//!     closed: true,
+//! };
+//! lines.curve(&path, &view_projection, (width, height), 2.0, [1.0, 0.8, 0.2, 1.0]);
+//! ```
+
+use crate::lines::{LineRenderer, LineSegment};
+use crate::math::{distance, xy};
+use crate::probe::{project, unproject};
+
+/// A curve drawn by the line renderer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Curve {
+    /// A cubic Bezier curve: it starts at the first point, ends at the last, and is pulled
+    /// towards the two points in between.
+    CubicBezier([crate::math::Vec3; 4]),
+
+    /// A (uniform) Catmull-Rom spline passing through every point.
+    ///
+    /// A `closed` spline also connects the last point back to the first.
+    CatmullRom {
+        points: Vec<crate::math::Vec3>,
+        closed: bool,
+    },
+}
+
+impl Curve {
+    /// The maximum number of times a segment is halved during tessellation.
+    const MAX_SUBDIVISIONS: u32 = 8;
+
+    /// The number of times a segment is always halved, so S-shaped segments whose midpoint
+    /// happens to lie on the chord are not mistaken for straight lines.
+    const MIN_SUBDIVISIONS: u32 = 2;
+
+    /// Returns the control points, which the `CurveEditor` edits.
+    pub fn control_points(&self) -> &[crate::math::Vec3] {
+        match self {
+            Curve::CubicBezier(points) => points,
+            Curve::CatmullRom { points, .. } => points,
+        }
+    }
+
+    /// Returns the control points for editing.
+    pub fn control_points_mut(&mut self) -> &mut [crate::math::Vec3] {
+        match self {
+            Curve::CubicBezier(points) => points,
+            Curve::CatmullRom { points, .. } => points,
+        }
+    }
+
+    /// Returns the curve as a chain of cubic Bezier segments.
+    pub fn bezier_segments(&self) -> Vec<[crate::math::Vec3; 4]> {
+        match self {
+            Curve::CubicBezier(points) => vec![*points],
+            Curve::CatmullRom { points, closed } => {
+                let count = points.len();
+                if count < 2 {
+                    return Vec::new();
+                }
+                // Open splines repeat their end points, so the curve reaches them.
+                let point = |index: isize| {
+                    if *closed {
+                        points[index.rem_euclid(count as isize) as usize]
+                    } else {
+                        points[index.clamp(0, count as isize - 1) as usize]
+                    }
+                };
+                let segments = if *closed { count } else { count - 1 };
+                (0..segments as isize)
+                    .map(|index| {
+                        let (p0, p1, p2, p3) = (
+                            point(index - 1),
+                            point(index),
+                            point(index + 1),
+                            point(index + 2),
+                        );
+                        [p1, p1 + (p2 - p0) / 6.0, p2 - (p3 - p1) / 6.0, p2]
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns the point at `t` (from `0.0` to `1.0`) on the curve.
+    pub fn evaluate(&self, t: f32) -> Option<crate::math::Vec3> {
+        let segments = self.bezier_segments();
+        let scaled = t.clamp(0.0, 1.0) * segments.len() as f32;
+        let index = (scaled as usize).min(segments.len().checked_sub(1)?);
+        Some(evaluate_bezier(&segments[index], scaled - index as f32))
+    }
+
+    /// Turns the curve into a polyline whose distance to the curve is at most `tolerance`
+    /// pixels on screen.
+    ///
+    /// `view_projection` and `viewport` describe the camera and the size of the render target
+    /// in pixels. Parts of the curve behind the camera are not refined.
+    pub fn tessellate(
+        &self,
+        view_projection: &crate::math::Mat4,
+        viewport: (u32, u32),
+        tolerance: f32,
+    ) -> Vec<crate::math::Vec3> {
+        let to_screen = |point: &crate::math::Vec3| project(view_projection, point, viewport);
+        let mut polyline = Vec::new();
+        for segment in self.bezier_segments() {
+            if polyline.is_empty() {
+                polyline.push(segment[0]);
+            }
+            subdivide(
+                &segment,
+                (0.0, segment[0]),
+                (1.0, segment[3]),
+                0,
+                &mut polyline,
+                &to_screen,
+                tolerance,
+            );
+        }
+        polyline
+    }
+}
+
+/// Evaluates a cubic Bezier segment at `t`.
+pub(crate) fn evaluate_bezier(points: &[crate::math::Vec3; 4], t: f32) -> crate::math::Vec3 {
+    let s = 1.0 - t;
+    points[0] * (s * s * s)
+        + points[1] * (3.0 * s * s * t)
+        + points[2] * (3.0 * s * t * t)
+        + points[3] * (t * t * t)
+}
+
+/// Appends the polyline for the part of `segment` between `start` and `end` (each a
+/// parameter and its point), excluding the start point.
+fn subdivide(
+    segment: &[crate::math::Vec3; 4],
+    start: (f32, crate::math::Vec3),
+    end: (f32, crate::math::Vec3),
+    depth: u32,
+    polyline: &mut Vec<crate::math::Vec3>,
+    project: &dyn Fn(&crate::math::Vec3) -> Option<crate::math::Vec3>,
+    tolerance: f32,
+) {
+    let t = (start.0 + end.0) * 0.5;
+    let middle = evaluate_bezier(segment, t);
+
+    let refine = depth < Curve::MIN_SUBDIVISIONS
+        || (depth < Curve::MAX_SUBDIVISIONS
+            && match (project(&start.1), project(&middle), project(&end.1)) {
+                (Some(a), Some(m), Some(b)) => {
+                    distance(&xy(&m), &((xy(&a) + xy(&b)) * 0.5)) > tolerance
+                }
+                _ => false,
+            });
+
+    if refine {
+        subdivide(
+            segment,
+            start,
+            (t, middle),
+            depth + 1,
+            polyline,
+            project,
+            tolerance,
+        );
+        subdivide(
+            segment,
+            (t, middle),
+            end,
+            depth + 1,
+            polyline,
+            project,
+            tolerance,
+        );
+    } else {
+        polyline.push(end.1);
+    }
+}
+
+/// Returns the default camera flight path: a closed loop circling the scene.
+pub fn default_flight_path() -> Curve {
+    Curve::CatmullRom {
+        points: vec![
+            crate::math::vec3(2.0, 0.5, 0.0),
+            crate::math::vec3(0.0, 1.0, 2.0),
+            crate::math::vec3(-2.0, 0.5, 0.0),
+            crate::math::vec3(0.0, 0.0, -2.0),
+        ],
+        closed: true,
+    }
+}
+
+/// Picks and drags the control points of a [`Curve`] with the cursor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurveEditor {
+    /// The edited curve.
+    pub curve: Curve,
+
+    /// The index of the control point under the cursor or being dragged.
+    pub selected: Option<usize>,
+
+    /// Whether the selected control point follows the cursor.
+    dragging: bool,
+}
+
+/// The default editor edits the [`default_flight_path`].
+impl Default for CurveEditor {
+    fn default() -> Self {
+        Self::new(default_flight_path())
+    }
+}
+
+impl CurveEditor {
+    /// How close the cursor must be to a control point to pick it, in pixels.
+    pub const PICK_RADIUS: f32 = 10.0;
+
+    /// The color of the curve.
+    const CURVE_COLOR: [f32; 4] = [1.0, 0.8, 0.2, 1.0];
+
+    /// The color of the control points and the polygon connecting them.
+    const HANDLE_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 0.8];
+
+    /// The color of the selected control point.
+    const SELECTED_COLOR: [f32; 4] = [0.2, 1.0, 0.4, 1.0];
+
+    /// Creates an editor for `curve`.
+    pub fn new(curve: Curve) -> Self {
+        Self {
+            curve,
+            selected: None,
+            dragging: false,
+        }
+    }
+
+    /// Returns the control point closest to `cursor` within [`CurveEditor::PICK_RADIUS`].
+    pub fn pick(
+        &self,
+        cursor: (u32, u32),
+        view_projection: &crate::math::Mat4,
+        viewport: (u32, u32),
+    ) -> Option<usize> {
+        let cursor = crate::math::vec2(cursor.0 as f32, cursor.1 as f32);
+        self.curve
+            .control_points()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, point)| {
+                let screen = project(view_projection, point, viewport)?;
+                let distance = crate::math::distance(&crate::math::xy(&screen), &cursor);
+                (distance <= Self::PICK_RADIUS).then_some((index, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+    }
+
+    /// Handles a press (`pressed == true`) or release of the primary button at `cursor`.
+    ///
+    /// Pressing on a control point selects it and starts dragging it. Returns `true` if the
+    /// press picked a control point, so the caller can ignore it otherwise.
+    pub fn pointer_button(
+        &mut self,
+        cursor: (u32, u32),
+        pressed: bool,
+        view_projection: &crate::math::Mat4,
+        viewport: (u32, u32),
+    ) -> bool {
+        if !pressed {
+            self.dragging = false;
+            return false;
+        }
+        self.selected = self.pick(cursor, view_projection, viewport);
+        self.dragging = self.selected.is_some();
+        self.dragging
+    }
+
+    /// Moves the dragged control point under `cursor`, keeping its distance to the camera.
+    pub fn pointer_moved(
+        &mut self,
+        cursor: (u32, u32),
+        view_projection: &crate::math::Mat4,
+        viewport: (u32, u32),
+    ) {
+        let Some(index) = self.selected.filter(|_| self.dragging) else {
+            return;
+        };
+        let point = &mut self.curve.control_points_mut()[index];
+        if let Some(screen) = project(view_projection, point, viewport) {
+            let inverse = crate::math::inverse(view_projection);
+            *point = unproject(&inverse, cursor, viewport, screen.z);
+        }
+    }
+
+    /// Queues the curve, its control points, and their control polygon on `lines`.
+    pub fn draw(
+        &self,
+        lines: &mut LineRenderer,
+        view_projection: &crate::math::Mat4,
+        viewport: (u32, u32),
+    ) {
+        lines.curve(
+            &self.curve,
+            view_projection,
+            viewport,
+            2.0,
+            Self::CURVE_COLOR,
+        );
+
+        let points = self.curve.control_points();
+        lines.extend(
+            points
+                .windows(2)
+                .map(|pair| LineSegment::new(pair[0], pair[1], 1.0, Self::HANDLE_COLOR)),
+        );
+
+        // Each control point is marked with a small cross along the world axes.
+        const HALF_SIZE: f32 = 0.05;
+        for (index, point) in points.iter().enumerate() {
+            let color = if self.selected == Some(index) {
+                Self::SELECTED_COLOR
+            } else {
+                Self::HANDLE_COLOR
+            };
+            for axis in [
+                crate::math::vec3(1.0, 0.0, 0.0),
+                crate::math::vec3(0.0, 1.0, 0.0),
+                crate::math::vec3(0.0, 0.0, 1.0),
+            ] {
+                lines.line(
+                    point - axis * HALF_SIZE,
+                    point + axis * HALF_SIZE,
+                    3.0,
+                    color,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::vec3;
+
+    /// Asserts that `a` and `b` are the same point, up to rounding.
+    fn assert_close(a: crate::math::Vec3, b: crate::math::Vec3) {
+        assert!(distance(&a, &b) < 1e-5, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn bezier_starts_and_ends_at_its_end_points() {
+        let points = [
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 2.0, 0.0),
+            vec3(3.0, 2.0, 0.0),
+            vec3(4.0, 0.0, 0.0),
+        ];
+        let curve = Curve::CubicBezier(points);
+
+        assert_close(curve.evaluate(0.0).unwrap(), points[0]);
+        assert_close(curve.evaluate(1.0).unwrap(), points[3]);
+        assert_close(curve.evaluate(0.5).unwrap(), vec3(2.0, 1.5, 0.0));
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_every_point() {
+        let Curve::CatmullRom { points, .. } = default_flight_path() else {
+            unreachable!();
+        };
+        for closed in [false, true] {
+            let curve = Curve::CatmullRom {
+                points: points.clone(),
+                closed,
+            };
+            let segments = curve.bezier_segments();
+            assert_eq!(segments.len(), if closed { 4 } else { 3 });
+            for (index, segment) in segments.iter().enumerate() {
+                assert_close(segment[0], points[index]);
+                assert_close(segment[3], points[(index + 1) % points.len()]);
+            }
+        }
+    }
+
+    #[test]
+    fn catmull_rom_needs_two_points() {
+        let curve = Curve::CatmullRom {
+            points: vec![vec3(1.0, 0.0, 0.0)],
+            closed: true,
+        };
+
+        assert!(curve.bezier_segments().is_empty());
+        assert_eq!(curve.evaluate(0.5), None);
+    }
+
+    #[test]
+    fn tessellation_refines_curved_segments_only() {
+        let line = Curve::CubicBezier([
+            vec3(-0.5, 0.0, 0.0),
+            vec3(-0.5 / 3.0, 0.0, 0.0),
+            vec3(0.5 / 3.0, 0.0, 0.0),
+            vec3(0.5, 0.0, 0.0),
+        ]);
+        let arc = Curve::CubicBezier([
+            vec3(-0.5, 0.0, 0.0),
+            vec3(-0.5, 0.8, 0.0),
+            vec3(0.5, 0.8, 0.0),
+            vec3(0.5, 0.0, 0.0),
+        ]);
+        let tessellate =
+            |curve: &Curve| curve.tessellate(&crate::math::identity(), (800, 800), 0.5);
+
+        let straight = tessellate(&line);
+        assert_eq!(straight.len(), (1 << Curve::MIN_SUBDIVISIONS) + 1);
+
+        let curved = tessellate(&arc);
+        assert!(curved.len() > straight.len());
+        assert_close(curved[0], vec3(-0.5, 0.0, 0.0));
+        assert_close(*curved.last().unwrap(), vec3(0.5, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,301 @@
+//! # Debug Draw Module
+//!
+//! The `debug_draw` module draws an infinite ground grid and the world axes below the scene,
+//! for orientation in scenes larger than a single object.
+//!
+//! ## Overview
+//!
+//! - [`DebugDraw`] has two unlit pipelines of its own, drawn in the scene pass. The grid is a
+//!   fullscreen triangle whose fragment shader intersects each pixel's view ray with the
+//!   ground plane (see `debug_draw.wgsl`), so it covers the plane up to the horizon with a
+//!   single draw and no geometry. It fades out with the distance to the camera, and its lines
+//!   through the origin are drawn in the X and Z axes' colors.
+//! - The axes are three short lines from the grid's origin along +X, +Y, and +Z, in red, green,
+//!   and blue.
+//! - [`DebugDraw::bounds`] returns what the grid covers around the camera, so the clip planes
+//!   fitted to the scene keep it until it has faded out.
+//!
+//! Unlike the ground grid built from wide lines by `lines::grid_lines`, the debug grid has no
+//! extent, and its lines stay one pixel wide at any distance.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let debug_draw = DebugDraw::new(&device, PostProcess::HDR_FORMAT, -1.0, 0.5, None);
+//! // ... once per frame, before the scene pass:
+//! debug_draw.update(&queue, &view_projection, &camera_position);
+//! // ... in the scene pass:
+//! debug_draw.bind_material(&mut render_pass);
+//! debug_draw.bind_grid_pipeline(&mut render_pass);
+//! DebugDraw::draw_grid(&mut render_pass);
+//! debug_draw.bind_axes_pipeline(&mut render_pass);
+//! DebugDraw::draw_axes(&mut render_pass);
+//! ```
+
+use crate::camera::Bounds;
+use crate::renderer::Renderer;
+use crate::scene::Scene;
+use crate::shader_bindings::debug_draw as debug_draw_bindings;
+
+/// The WGSL source of the grid and axes shaders.
+const DEBUG_DRAW_SHADER_SOURCE: &str = include_str!("debug_draw.wgsl");
+
+/// The uniform data of the debug draw shaders.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct DebugDrawUniform {
+    /// The matrix transforming world space into clip space.
+    view_projection: crate::math::Mat4,
+
+    /// The inverse of `view_projection`, unprojecting the pixels' view rays.
+    inverse_view_projection: crate::math::Mat4,
+
+    /// The camera's position, which the grid fades out around.
+    camera_position: [f32; 3],
+
+    /// The height of the ground plane.
+    height: f32,
+
+    /// The distance between grid lines, in world units.
+    spacing: f32,
+
+    /// The distance from the camera at which the grid has faded out.
+    fade_distance: f32,
+
+    /// The length of the axis lines, in world units.
+    axis_length: f32,
+
+    /// Pads the struct to the 16 byte alignment of WGSL uniforms.
+    _padding: f32,
+}
+
+/// Draws the infinite ground grid and the world axes.
+#[derive(Debug)]
+pub struct DebugDraw {
+    /// The pipeline drawing the grid as a fullscreen triangle.
+    grid_pipeline: wgpu::RenderPipeline,
+
+    /// The pipeline drawing the axes as a line list.
+    axes_pipeline: wgpu::RenderPipeline,
+
+    /// The buffer holding the `DebugDrawUniform`.
+    uniform_buffer: wgpu::Buffer,
+
+    /// The bind group exposing `uniform_buffer` to the shaders.
+    bind_group: wgpu::BindGroup,
+
+    /// The height of the ground plane.
+    height: f32,
+
+    /// The distance between grid lines, in world units.
+    spacing: f32,
+}
+
+impl DebugDraw {
+    /// The distance from the camera at which the grid has faded out, in world units.
+    pub const FADE_DISTANCE: f32 = 25.0;
+
+    /// The length of the axis lines, in world units.
+    pub const AXIS_LENGTH: f32 = 1.0;
+
+    /// Creates the grid and axes pipelines, drawing into the scene pass's color targets of
+    /// `color_format` and compiled through `cache` if one is given. The grid lies at `height`,
+    /// with lines `spacing` world units apart.
+    pub fn new(
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+        height: f32,
+        spacing: f32,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Debug Draw Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(DEBUG_DRAW_SHADER_SOURCE)),
+        });
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Debug Draw Uniform Buffer"),
+            size: std::mem::size_of::<DebugDrawUniform>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout = debug_draw_bindings::BindGroup0::create_layout(device);
+        let bind_group = debug_draw_bindings::BindGroup0 {
+            debug: uniform_buffer.as_entire_buffer_binding(),
+        }
+        .create(device, &bind_group_layout);
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Debug Draw Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let grid_pipeline = Self::create_pipeline(
+            device,
+            &layout,
+            &shader_module,
+            color_format,
+            DebugPrimitive::Grid,
+            cache,
+        );
+        let axes_pipeline = Self::create_pipeline(
+            device,
+            &layout,
+            &shader_module,
+            color_format,
+            DebugPrimitive::Axes,
+            cache,
+        );
+
+        Self {
+            grid_pipeline,
+            axes_pipeline,
+            uniform_buffer,
+            bind_group,
+            height,
+            spacing,
+        }
+    }
+
+    /// Returns the part of the ground plane the grid covers before it has faded out, seen
+    /// from `camera_position`, along with the axes.
+    pub fn bounds(&self, camera_position: &crate::math::Vec3) -> Bounds {
+        let fade = Self::FADE_DISTANCE;
+        let (x, z) = (camera_position.x, camera_position.z);
+        Bounds {
+            min: crate::math::vec3((x - fade).min(0.0), self.height, (z - fade).min(0.0)),
+            max: crate::math::vec3(
+                (x + fade).max(Self::AXIS_LENGTH),
+                self.height + Self::AXIS_LENGTH,
+                (z + fade).max(Self::AXIS_LENGTH),
+            ),
+        }
+    }
+
+    /// Uploads the camera for the next scene pass.
+    pub fn update(
+        &self,
+        queue: &wgpu::Queue,
+        view_projection: &crate::math::Mat4,
+        camera_position: &crate::math::Vec3,
+    ) {
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&DebugDrawUniform {
+                view_projection: *view_projection,
+                inverse_view_projection: crate::math::inverse(view_projection),
+                camera_position: (*camera_position).into(),
+                height: self.height,
+                spacing: self.spacing,
+                fade_distance: Self::FADE_DISTANCE,
+                axis_length: Self::AXIS_LENGTH,
+                _padding: 0.0,
+            }),
+        );
+    }
+
+    /// Sets the grid pipeline.
+    pub fn bind_grid_pipeline<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_pipeline(&self.grid_pipeline);
+    }
+
+    /// Sets the axes pipeline.
+    pub fn bind_axes_pipeline<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_pipeline(&self.axes_pipeline);
+    }
+
+    /// Binds the camera uniforms, shared by the grid and the axes, at set 0.
+    pub fn bind_material<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+    }
+
+    /// Draws the grid, after its pipeline and the material are bound.
+    pub fn draw_grid(render_pass: &mut wgpu::RenderPass) {
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// Draws the axes, after their pipeline and the material are bound.
+    pub fn draw_axes(render_pass: &mut wgpu::RenderPass) {
+        render_pass.draw(0..6, 0..1);
+    }
+
+    /// Creates the pipeline drawing `primitive`.
+    ///
+    /// Both are depth tested against the scene but do not write depth, are blended over it,
+    /// and leave the object id target untouched, like the debug lines.
+    fn create_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader_module: &wgpu::ShaderModule,
+        color_format: wgpu::TextureFormat,
+        primitive: DebugPrimitive,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> wgpu::RenderPipeline {
+        let (label, vertex_entry, fragment_entry, topology) = match primitive {
+            DebugPrimitive::Grid => (
+                "Debug Grid Pipeline",
+                "grid_vertex",
+                "grid_fragment",
+                wgpu::PrimitiveTopology::TriangleList,
+            ),
+            DebugPrimitive::Axes => (
+                "Debug Axes Pipeline",
+                "axes_vertex",
+                "axes_fragment",
+                wgpu::PrimitiveTopology::LineList,
+            ),
+        };
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader_module,
+                entry_point: Some(vertex_entry),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            primitive: wgpu::PrimitiveState {
+                topology,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: Renderer::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: shader_module,
+                entry_point: Some(fragment_entry),
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: color_format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(wgpu::ColorTargetState {
+                        format: Scene::OBJECT_ID_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::empty(),
+                    }),
+                ],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        })
+    }
+}
+
+/// What a pipeline of the [`DebugDraw`] draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DebugPrimitive {
+    /// The ground grid.
+    Grid,
+
+    /// The world axes.
+    Axes,
+}
@@ -0,0 +1,136 @@
+// Infinite ground grid and world axes.
+//
+// The grid is a fullscreen triangle: every pixel casts a ray from the near to the far plane,
+// intersects it with the ground plane, and draws the grid lines at the hit point, writing its
+// depth so the scene hides the grid where it is in front of it. The lines are anti-aliased
+// from the screen-space derivatives of the grid coordinates, the lines through the origin are
+// drawn in the colors of the X and Z axes, and the grid fades out with the distance to the
+// camera, so it has no visible edge.
+//
+// The axes are three unlit line segments from the grid's origin along +X, +Y, and +Z.
+
+struct DebugDrawUniform {
+    view_projection: mat4x4<f32>,
+    inverse_view_projection: mat4x4<f32>,
+    camera_position: vec3<f32>,
+    // The height of the ground plane.
+    height: f32,
+    // The distance between grid lines, in world units.
+    spacing: f32,
+    // The distance from the camera at which the grid has faded out.
+    fade_distance: f32,
+    // The length of the axis lines, in world units.
+    axis_length: f32,
+    padding: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> debug: DebugDrawUniform;
+
+// The color of the grid lines.
+const GRID_COLOR: vec4<f32> = vec4<f32>(0.6, 0.6, 0.6, 0.5);
+
+// The colors of the X, Y, and Z axes.
+const X_AXIS_COLOR: vec4<f32> = vec4<f32>(0.9, 0.2, 0.2, 1.0);
+const Y_AXIS_COLOR: vec4<f32> = vec4<f32>(0.3, 0.85, 0.3, 1.0);
+const Z_AXIS_COLOR: vec4<f32> = vec4<f32>(0.2, 0.4, 0.9, 1.0);
+
+struct GridOutput {
+    @builtin(position) position: vec4<f32>,
+    // The pixel's position in normalized device coordinates.
+    @location(0) ndc: vec2<f32>,
+};
+
+// Draws a single triangle covering the whole viewport.
+@vertex
+fn grid_vertex(@builtin(vertex_index) vertex_index: u32) -> GridOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    var out: GridOutput;
+    out.ndc = uv * 2.0 - 1.0;
+    out.position = vec4<f32>(out.ndc, 0.0, 1.0);
+    return out;
+}
+
+// Returns the world position at `ndc` and the depth value `depth`.
+fn unproject(ndc: vec2<f32>, depth: f32) -> vec3<f32> {
+    let world = debug.inverse_view_projection * vec4<f32>(ndc, depth, 1.0);
+    return world.xyz / world.w;
+}
+
+struct GridFragment {
+    @location(0) color: vec4<f32>,
+    // The grid does not belong to an object; the pipeline masks out writes to the id target.
+    @location(1) object_id: u32,
+    @builtin(frag_depth) depth: f32,
+};
+
+@fragment
+fn grid_fragment(in: GridOutput) -> GridFragment {
+    let near = unproject(in.ndc, 0.0);
+    let far = unproject(in.ndc, 1.0);
+    let t = (debug.height - near.y) / (far.y - near.y);
+    let point = mix(near, far, t);
+    let clip = debug.view_projection * vec4<f32>(point, 1.0);
+
+    // The distance to the nearest line, in pixels, from the derivatives of the coordinates.
+    // They are taken before any fragment is discarded, while control flow is uniform.
+    let coords = point.xz / debug.spacing;
+    let cell = abs(fract(coords - 0.5) - 0.5) / fwidth(coords);
+    let axis = abs(point.xz) / fwidth(point.xz);
+    let line = 1.0 - min(min(cell.x, cell.y), 1.0);
+
+    // The axes are twice as wide, with a solid core.
+    let on_axis = 1.0 - clamp(axis - 1.0, vec2<f32>(0.0), vec2<f32>(1.0));
+
+    var color = vec4<f32>(GRID_COLOR.rgb, GRID_COLOR.a * line);
+    color = mix(color, X_AXIS_COLOR, on_axis.y);
+    color = mix(color, Z_AXIS_COLOR, on_axis.x);
+    let fade = 1.0 - smoothstep(0.0, debug.fade_distance, distance(point, debug.camera_position));
+    color.a *= fade;
+
+    // Rays parallel to the plane, or hitting it behind the camera or beyond the far plane, miss.
+    if !(t > 0.0 && t <= 1.0) || color.a <= 0.0 {
+        discard;
+    }
+
+    var out: GridFragment;
+    out.color = color;
+    out.object_id = 0u;
+    out.depth = clamp(clip.z / clip.w, 0.0, 1.0);
+    return out;
+}
+
+struct AxisOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+// Draws the axes as a line list: vertices 0 and 1 are the X axis, 2 and 3 the Y axis, and
+// 4 and 5 the Z axis.
+@vertex
+fn axes_vertex(@builtin(vertex_index) vertex_index: u32) -> AxisOutput {
+    var colors = array<vec4<f32>, 3>(X_AXIS_COLOR, Y_AXIS_COLOR, Z_AXIS_COLOR);
+    let axis = vertex_index / 2u;
+    var offset = vec3<f32>(0.0);
+    offset[axis] = f32(vertex_index & 1u) * debug.axis_length;
+
+    var out: AxisOutput;
+    let origin = vec3<f32>(0.0, debug.height, 0.0);
+    out.position = debug.view_projection * vec4<f32>(origin + offset, 1.0);
+    out.color = colors[axis];
+    return out;
+}
+
+struct AxisFragment {
+    @location(0) color: vec4<f32>,
+    // The axes do not belong to an object; the pipeline masks out writes to the id target.
+    @location(1) object_id: u32,
+};
+
+@fragment
+fn axes_fragment(in: AxisOutput) -> AxisFragment {
+    var out: AxisFragment;
+    out.color = in.color;
+    out.object_id = 0u;
+    return out;
+}
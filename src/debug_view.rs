@@ -0,0 +1,51 @@
+//! # Debug View Module
+//!
+//! The `debug_view` module lists the ways the renderer can visualize the scene for debugging
+//! and teaching, selected in the settings window.
+//!
+//! ## Overview
+//!
+//! - [`DebugView::Shaded`] is the regular rendering of the scene.
+//! - [`DebugView::Overdraw`] replaces the scene with a heatmap of how many fragments were
+//!   written to each pixel (see the `overdraw` module). It makes the cost of overlapping and
+//!   transparent geometry visible: every layer is shaded, even if it is hidden later.
+//!
+//! New debug views are added as variants here and handled in `Renderer::render_frame`. The
+//! overdraw heatmap is a profiling view, compiled in with the `profiling` feature.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! renderer.set_debug_view(DebugView::Overdraw);
+//! ```
+
+/// How the renderer draws the scene.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DebugView {
+    /// The scene as it normally looks.
+    #[default]
+    Shaded,
+
+    /// A heatmap of the number of fragments written to each pixel.
+    #[cfg(feature = "profiling")]
+    Overdraw,
+}
+
+impl DebugView {
+    /// Every debug view, in the order they are offered in the user interface.
+    #[cfg(feature = "profiling")]
+    pub const ALL: [DebugView; 2] = [DebugView::Shaded, DebugView::Overdraw];
+
+    /// Every debug view, in the order they are offered in the user interface.
+    #[cfg(not(feature = "profiling"))]
+    pub const ALL: [DebugView; 1] = [DebugView::Shaded];
+
+    /// Returns the name shown in the user interface.
+    pub fn label(self) -> &'static str {
+        match self {
+            DebugView::Shaded => "Shaded",
+            #[cfg(feature = "profiling")]
+            DebugView::Overdraw => "Overdraw",
+        }
+    }
+}
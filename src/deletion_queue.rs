@@ -0,0 +1,257 @@
+//! # Deletion Queue Module
+//!
+//! The `deletion_queue` module releases GPU resources only after every frame that may still
+//! reference them has finished on the GPU.
+//!
+//! ## Overview
+//!
+//! Replacing a resource while frames using it are in flight, such as the depth target when
+//! the window is resized or the crowd demo when it is hidden, must not free its memory right
+//! away. [`DeletionQueue`] keeps such resources alive instead:
+//!
+//! 1. **`retire`**: Takes ownership of a [`Retired`] resource, tagged with the number of
+//!    submissions made so far. Those are the submissions that may still use it.
+//! 2. **`after_submit`**: Counts a submission, and registers a callback with
+//!    `Queue::on_submitted_work_done` that records the submission as completed.
+//! 3. **`collect`**: Polls the device without blocking, then destroys every retired resource
+//!    whose submissions have all completed. Buffers and textures are destroyed explicitly, so
+//!    their memory is released at that point rather than whenever `wgpu` gets to it.
+//!
+//! Submissions complete in order, so the queue is sorted by its tags and `collect` only looks
+//! at its front. `flush` releases everything, after the device was waited on.
+//!
+//! `wgpu::SubmissionIndex` cannot be compared or queried without blocking, so the queue counts
+//! submissions itself. Every submission of the renderer that may use retired resources must be
+//! followed by `after_submit`.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let old_texture = std::mem::replace(&mut self.texture, new_texture);
+//! deletion_queue.retire(old_texture);
+//!
+//! // Every frame:
+//! deletion_queue.collect(&device);
+//! // ... encode the frame ...
+//! queue.submit(std::iter::once(encoder.finish()));
+//! deletion_queue.after_submit(&queue);
+//! ```
+
+// Importing `VecDeque` to release the oldest retired resources first.
+use std::collections::VecDeque;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A resource waiting in the [`DeletionQueue`].
+pub enum Retired {
+    /// A buffer, destroyed once released.
+    Buffer(wgpu::Buffer),
+
+    /// A texture, destroyed once released.
+    Texture(wgpu::Texture),
+
+    /// A view of a texture.
+    TextureView(wgpu::TextureView),
+
+    /// A bind group.
+    BindGroup(wgpu::BindGroup),
+
+    /// Any other value owning GPU resources, such as a whole demo, dropped once released.
+    Other(Box<dyn std::any::Any>),
+}
+
+impl std::fmt::Debug for Retired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Retired::Buffer(buffer) => f.debug_tuple("Buffer").field(buffer).finish(),
+            Retired::Texture(texture) => f.debug_tuple("Texture").field(texture).finish(),
+            Retired::TextureView(view) => f.debug_tuple("TextureView").field(view).finish(),
+            Retired::BindGroup(bind_group) => f.debug_tuple("BindGroup").field(bind_group).finish(),
+            Retired::Other(_) => f.write_str("Other"),
+        }
+    }
+}
+
+impl Retired {
+    /// Wraps a value owning GPU resources, which are released when it is dropped.
+    pub fn other(value: impl std::any::Any) -> Self {
+        Retired::Other(Box::new(value))
+    }
+
+    /// Releases the resource.
+    fn release(self) {
+        match self {
+            Retired::Buffer(buffer) => buffer.destroy(),
+            Retired::Texture(texture) => texture.destroy(),
+            Retired::TextureView(_) | Retired::BindGroup(_) | Retired::Other(_) => {}
+        }
+    }
+}
+
+impl From<wgpu::Buffer> for Retired {
+    fn from(buffer: wgpu::Buffer) -> Self {
+        Retired::Buffer(buffer)
+    }
+}
+
+impl From<wgpu::Texture> for Retired {
+    fn from(texture: wgpu::Texture) -> Self {
+        Retired::Texture(texture)
+    }
+}
+
+impl From<wgpu::TextureView> for Retired {
+    fn from(view: wgpu::TextureView) -> Self {
+        Retired::TextureView(view)
+    }
+}
+
+impl From<wgpu::BindGroup> for Retired {
+    fn from(bind_group: wgpu::BindGroup) -> Self {
+        Retired::BindGroup(bind_group)
+    }
+}
+
+/// Holds retired GPU resources until the submissions that may reference them have completed.
+#[derive(Debug, Default)]
+pub struct DeletionQueue {
+    /// The retired resources, each with the number of submissions made when it was retired,
+    /// oldest first.
+    pending: VecDeque<(u64, Retired)>,
+
+    /// The number of submissions made.
+    submitted: u64,
+
+    /// The number of submissions the GPU has completed, raised by the callbacks registered in
+    /// `after_submit`.
+    completed: Arc<AtomicU64>,
+}
+
+impl DeletionQueue {
+    /// Keeps `resource` alive until every submission made so far has completed.
+    pub fn retire(&mut self, resource: impl Into<Retired>) {
+        self.pending.push_back((self.submitted, resource.into()));
+    }
+
+    /// Returns the number of resources waiting to be released.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns `true` if no resources are waiting to be released.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Counts a submission made to `queue`, which completes after the previous ones.
+    pub fn after_submit(&mut self, queue: &wgpu::Queue) {
+        self.submitted += 1;
+        let submission = self.submitted;
+        let completed = Arc::clone(&self.completed);
+        queue.on_submitted_work_done(move || {
+            completed.fetch_max(submission, Ordering::Release);
+        });
+    }
+
+    /// Releases the resources whose submissions have all completed. This never blocks.
+    ///
+    /// Returns the number of resources released.
+    pub fn collect(&mut self, device: &wgpu::Device) -> usize {
+        if self.pending.is_empty() {
+            return 0;
+        }
+
+        let _ = device.poll(wgpu::Maintain::Poll);
+        self.release_completed(self.completed.load(Ordering::Acquire))
+    }
+
+    /// Releases the resources retired before the first `completed` submissions were made, in
+    /// the order they were retired, and returns how many there were.
+    fn release_completed(&mut self, completed: u64) -> usize {
+        let mut released = 0;
+        while self
+            .pending
+            .front()
+            .is_some_and(|(submission, _)| *submission <= completed)
+        {
+            if let Some((_, resource)) = self.pending.pop_front() {
+                resource.release();
+                released += 1;
+            }
+        }
+        released
+    }
+
+    /// Releases every retired resource, whether its submissions completed or not.
+    ///
+    /// Only call this after waiting for the device, such as when the renderer shuts down.
+    pub fn flush(&mut self) {
+        for (_, resource) in self.pending.drain(..) {
+            resource.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A resource recording its id in a shared log when it is dropped.
+    struct Tracked(u32, Rc<RefCell<Vec<u32>>>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.1.borrow_mut().push(self.0);
+        }
+    }
+
+    /// Counts a submission the way `after_submit` does, without a GPU queue.
+    fn submit(queue: &mut DeletionQueue) {
+        queue.submitted += 1;
+    }
+
+    #[test]
+    fn releases_resources_once_their_submissions_completed() {
+        let dropped = Rc::new(RefCell::new(Vec::new()));
+        let mut queue = DeletionQueue::default();
+
+        queue.retire(Retired::other(Tracked(0, dropped.clone())));
+        submit(&mut queue);
+        queue.retire(Retired::other(Tracked(1, dropped.clone())));
+        queue.retire(Retired::other(Tracked(2, dropped.clone())));
+        submit(&mut queue);
+        queue.retire(Retired::other(Tracked(3, dropped.clone())));
+        assert_eq!(queue.len(), 4);
+
+        // The first resource was retired before any submission, so it is released right away.
+        assert_eq!(queue.release_completed(0), 1);
+        assert_eq!(*dropped.borrow(), [0]);
+
+        assert_eq!(queue.release_completed(1), 2);
+        assert_eq!(*dropped.borrow(), [0, 1, 2]);
+
+        // The last resource may still be used by the submission made after it was retired.
+        assert_eq!(queue.release_completed(1), 0);
+        assert_eq!(queue.release_completed(2), 1);
+        assert_eq!(*dropped.borrow(), [0, 1, 2, 3]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn flush_releases_everything_in_retired_order() {
+        let dropped = Rc::new(RefCell::new(Vec::new()));
+        let mut queue = DeletionQueue::default();
+        for id in 0..3 {
+            queue.retire(Retired::other(Tracked(id, dropped.clone())));
+            submit(&mut queue);
+        }
+
+        queue.flush();
+        assert_eq!(*dropped.borrow(), [0, 1, 2]);
+        assert!(queue.is_empty());
+    }
+}
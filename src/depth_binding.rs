@@ -0,0 +1,224 @@
+//! # Depth Binding Module
+//!
+//! The `depth_binding` module exposes the scene's depth buffer to passes that run after the
+//! scene pass, such as soft particles, screen-space ambient occlusion, or depth fog.
+//!
+//! ## Overview
+//!
+//! - [`DepthBinding`] owns a bind group with a depth-only view of the depth texture at
+//!   binding 0 and a sampler without a compare function at binding 1. The depth is bound as
+//!   an unfilterable `texture_2d<f32>` rather than a `texture_depth_2d`, since the GL backend
+//!   can only sample the latter with a comparison. Shaders read the raw depth from the red
+//!   channel with `textureLoad` or `textureSample`, with nearest filtering.
+//! - [`DepthPass`] is a pass added with `Renderer::add_depth_pass`. The renderer encodes it
+//!   after the scene pass has ended, when the depth texture is no longer attached and may be
+//!   sampled, and before the GUI is drawn on top.
+//! - [`linearize_depth`] turns a depth value back into a distance from the camera, which is
+//!   what soft particles and fog compare.
+//!
+//! The depth texture is re-created when the surface is resized, and with it the bind group.
+//! The bind group layout and the sampler stay the same, so pipelines created once against
+//! [`DepthBinding::bind_group_layout`] remain valid. Adapters that cannot sample the depth
+//! format have no depth binding (see `Gpu::depth_texture_usages`).
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! // WGSL:
+//! // @group(0) @binding(0) var scene_depth: texture_2d<f32>;
+//! // @group(0) @binding(1) var scene_depth_sampler: sampler;
+//! // ...
+//! // let depth = textureLoad(scene_depth, vec2<i32>(position.xy), 0).r;
+//!
+//! struct Fog { pipeline: wgpu::RenderPipeline }
+//!
+//! impl DepthPass for Fog {
+//!     fn encode(
+//!         &mut self,
+//!         _queue: &wgpu::Queue,
+//!         encoder: &mut wgpu::CommandEncoder,
+//!         frame: &DepthPassFrame,
+//!     ) {
+//!         let mut pass = encoder.begin_render_pass(/* load `frame.target` */);
+//!         frame.viewport.apply(&mut pass);
+//!         pass.set_pipeline(&self.pipeline);
+//!         pass.set_bind_group(0, &frame.depth.bind_group, &[]);
+//!         pass.draw(0..3, 0..1);
+//!     }
+//! }
+//!
+//! if let Some(depth) = renderer.scene_depth() {
+//!     let fog = Fog::new(renderer.device(), &depth.bind_group_layout, PostProcess::HDR_FORMAT);
+//!     renderer.add_depth_pass(Box::new(fog));
+//! }
+//! ```
+
+use crate::camera::Projection;
+use crate::deletion_queue::DeletionQueue;
+use crate::renderer::Renderer;
+use crate::viewport::Viewport;
+
+/// The scene's depth texture, bound for sampling in later passes.
+#[derive(Debug)]
+pub struct DepthBinding {
+    /// The layout of `bind_group`: the depth texture at binding 0 and the sampler at
+    /// binding 1, visible to fragment and compute shaders.
+    pub bind_group_layout: wgpu::BindGroupLayout,
+
+    /// Samples the depth texture without comparing, with nearest filtering.
+    pub sampler: wgpu::Sampler,
+
+    /// The depth-only view of the current depth texture.
+    pub view: wgpu::TextureView,
+
+    /// Exposes `view` and `sampler` to the shaders.
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl DepthBinding {
+    /// Creates the binding for `depth_texture`, which must have the `TEXTURE_BINDING` usage.
+    pub fn new(device: &wgpu::Device, depth_texture: &wgpu::Texture) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("scene_depth_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT | wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        // Depth formats may be bound as unfilterable floats, which unlike
+                        // `Depth` can be sampled without a comparison on every backend.
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT | wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::NonFiltering),
+                    count: None,
+                },
+            ],
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Scene Depth Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            compare: None,
+            ..Default::default()
+        });
+
+        let (view, bind_group) =
+            Self::create_bind_group(device, &bind_group_layout, &sampler, depth_texture);
+        Self {
+            bind_group_layout,
+            sampler,
+            view,
+            bind_group,
+        }
+    }
+
+    /// Binds a new `depth_texture`, after the old one was re-created for a new surface size.
+    ///
+    /// The old view and bind group may still be used by frames in flight, so they are handed
+    /// to `deletion_queue`.
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        depth_texture: &wgpu::Texture,
+        deletion_queue: &mut DeletionQueue,
+    ) {
+        let (view, bind_group) = Self::create_bind_group(
+            device,
+            &self.bind_group_layout,
+            &self.sampler,
+            depth_texture,
+        );
+        deletion_queue.retire(std::mem::replace(&mut self.bind_group, bind_group));
+        deletion_queue.retire(std::mem::replace(&mut self.view, view));
+    }
+
+    /// Creates the depth-only view of `depth_texture` and the bind group exposing it.
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        depth_texture: &wgpu::Texture,
+    ) -> (wgpu::TextureView, wgpu::BindGroup) {
+        // Depth-stencil formats must be viewed through a single aspect to be sampled.
+        let view = depth_texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Scene Depth View"),
+            format: Some(Renderer::DEPTH_FORMAT),
+            dimension: Some(wgpu::TextureViewDimension::D2),
+            aspect: wgpu::TextureAspect::DepthOnly,
+            ..Default::default()
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("scene_depth_bind_group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+
+        (view, bind_group)
+    }
+}
+
+/// What a [`DepthPass`] draws into and reads from in the current frame.
+#[derive(Debug, Clone, Copy)]
+pub struct DepthPassFrame<'a> {
+    /// The frame's color target, the HDR texture in `PostProcess::HDR_FORMAT`. The scene was
+    /// drawn into it and must be loaded, not cleared. It is post-processed afterwards.
+    pub target: &'a wgpu::TextureView,
+
+    /// The scene's depth.
+    pub depth: &'a DepthBinding,
+
+    /// The part of the target the scene was drawn into.
+    pub viewport: Viewport,
+
+    /// The projection the scene was drawn with, its clip planes fitted to the content.
+    pub projection: Projection,
+
+    /// The view-projection matrix the scene was drawn with.
+    pub view_projection: crate::math::Mat4,
+}
+
+/// A pass sampling the scene's depth, encoded every frame after the scene pass.
+pub trait DepthPass {
+    /// Records the pass into `encoder`.
+    ///
+    /// Buffer writes through `queue` take effect before the pass runs, since the frame is
+    /// submitted after all passes are recorded. The depth texture must not be attached as a
+    /// writable depth target while it is sampled.
+    fn encode(
+        &mut self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        frame: &DepthPassFrame,
+    );
+}
+
+/// Converts a depth value of the scene, from `0.0` at `near` to `1.0` at `far`, back into the
+/// distance along the view direction.
+///
+/// This inverts the left-handed, zero-to-one perspective projection used by the scene. Shaders
+/// compute the same with `near * far / (far - depth * (far - near))`.
+pub fn linearize_depth(depth: f32, near: f32, far: f32) -> f32 {
+    near * far / (far - depth * (far - near))
+}
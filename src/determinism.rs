@@ -0,0 +1,340 @@
+//! # Determinism Module
+//!
+//! The `determinism` module provides the building blocks for reproducible simulations, the
+//! foundation for replay tests and networked synchronization.
+//!
+//! ## Overview
+//!
+//! - [`DeterministicRng`] is a small seeded random number generator using only integer
+//!   arithmetic, so it yields the same sequence on every platform. Each subsystem takes its own
+//!   named stream with [`DeterministicRng::stream`], so adding random draws to one subsystem
+//!   never shifts the numbers another one sees.
+//! - [`FixedTimestep`] turns variable frame times into a whole number of fixed simulation
+//!   ticks, carrying the remainder over to the next frame. The `App` advances the scene's
+//!   animation with the ticks of one, and draws it between the last two by the remainder.
+//! - [`StateHasher`] hashes simulation state bit-exactly, and [`TickLog`] records the hash of
+//!   every tick. Comparing the logs of two runs with [`TickLog::first_divergence`] finds the
+//!   first tick at which they behaved differently.
+//!
+//! In determinism mode (see `App::with_determinism`), all randomness in the scene is drawn
+//! from streams of one seed, the scene restarts from its initial state, and the simulation
+//! only advances in fixed ticks. Runs with the same seed then produce identical tick logs on
+//! the same platform. Across platforms the logs also match as long as the platforms' math
+//! libraries agree on the results of `sin` and `cos`, which the animation relies on.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut rng = DeterministicRng::stream(seed, "crowd");
+//! let heading = rng.range(0.0..std::f32::consts::TAU);
+//!
+//! let first = simulation::simulate(model, 1_000);
+//! let second = simulation::simulate(model, 1_000);
+//! assert_eq!(first.first_divergence(&second), None);
+//! ```
+
+use std::collections::VecDeque;
+
+use crate::Duration;
+
+/// A seeded random number generator (SplitMix64) that is identical on every platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeterministicRng {
+    /// The generator's state, advanced by every draw.
+    state: u64,
+}
+
+impl DeterministicRng {
+    /// The seed used outside of determinism mode.
+    pub const DEFAULT_SEED: u64 = 0x5EED_C0FF_EE00;
+
+    /// Creates a generator from `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Creates the generator of the stream `name` of `seed`, independent of all other streams.
+    pub fn stream(seed: u64, name: &str) -> Self {
+        let mut hasher = StateHasher::new();
+        hasher.write(name.as_bytes());
+        let mut rng = Self::new(seed ^ hasher.finish());
+        // Discard the first draw, so streams of neighboring seeds do not start out correlated.
+        rng.next_u64();
+        rng
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns the next 32 random bits.
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// Returns a random number in `[0, 1)`, with 24 bits of precision.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1 << 24) as f32
+    }
+
+    /// Returns a random number in `range`.
+    pub fn range(&mut self, range: std::ops::Range<f32>) -> f32 {
+        range.start + (range.end - range.start) * self.next_f32()
+    }
+}
+
+/// Hashes simulation state bit-exactly (FNV-1a, 64 bits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateHasher {
+    /// The hash of the bytes written so far.
+    hash: u64,
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateHasher {
+    /// Creates a hasher that has not seen any state.
+    pub fn new() -> Self {
+        Self {
+            hash: 0xCBF2_9CE4_8422_2325,
+        }
+    }
+
+    /// Adds `bytes` to the hash.
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash = (self.hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01B3);
+        }
+    }
+
+    /// Adds `value` to the hash.
+    pub fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Adds the bits of `value` to the hash, so values differing in the last bit differ.
+    pub fn write_f32(&mut self, value: f32) {
+        self.write(&value.to_bits().to_le_bytes());
+    }
+
+    /// Returns the hash.
+    pub fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Splits variable frame times into fixed simulation ticks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTimestep {
+    /// The duration of one tick.
+    tick: Duration,
+
+    /// The time not yet simulated.
+    accumulator: Duration,
+}
+
+impl Default for FixedTimestep {
+    /// Creates a timestep of the simulation's [`TICK`](crate::simulation::TICK).
+    fn default() -> Self {
+        Self::new(crate::simulation::TICK)
+    }
+}
+
+impl FixedTimestep {
+    /// The most ticks simulated per frame. After a long stall the simulation falls behind
+    /// instead of freezing the application while it catches up.
+    pub const MAX_TICKS_PER_FRAME: u32 = 8;
+
+    /// Creates a timestep of `tick` per tick.
+    pub fn new(tick: Duration) -> Self {
+        Self {
+            tick,
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// Returns the duration of one tick.
+    pub fn tick(&self) -> Duration {
+        self.tick
+    }
+
+    /// Returns the fraction of a tick accumulated but not simulated yet, from `0.0` to `1.0`,
+    /// to interpolate the rendered state between the last two ticks.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f32() / self.tick.as_secs_f32()).min(1.0)
+    }
+
+    /// Adds `delta` to the time to simulate and returns the number of whole ticks to simulate
+    /// now.
+    pub fn advance(&mut self, delta: Duration) -> u32 {
+        self.accumulator += delta;
+        let mut ticks = 0;
+        while self.accumulator >= self.tick && ticks < Self::MAX_TICKS_PER_FRAME {
+            self.accumulator -= self.tick;
+            ticks += 1;
+        }
+        if ticks == Self::MAX_TICKS_PER_FRAME {
+            self.accumulator = self.accumulator.min(self.tick);
+        }
+        ticks
+    }
+}
+
+/// The state hashes of the most recent simulation ticks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TickLog {
+    /// The tick of the first hash in `hashes`.
+    first_tick: u64,
+
+    /// The hash after each tick, oldest first.
+    hashes: VecDeque<u64>,
+}
+
+impl TickLog {
+    /// The most ticks kept; older ones are dropped. At 120 ticks per second, this is about
+    /// two hours.
+    pub const CAPACITY: usize = 1 << 20;
+
+    /// Records `hash` as the state after `tick`. Ticks must be recorded in order; recording a
+    /// tick out of order starts a new log.
+    pub fn push(&mut self, tick: u64, hash: u64) {
+        if self.first_tick + self.hashes.len() as u64 != tick {
+            self.first_tick = tick;
+            self.hashes.clear();
+        }
+        self.hashes.push_back(hash);
+        if self.hashes.len() > Self::CAPACITY {
+            self.hashes.pop_front();
+            self.first_tick += 1;
+        }
+    }
+
+    /// Returns the hash recorded after `tick`, if it is still kept.
+    pub fn hash(&self, tick: u64) -> Option<u64> {
+        let index = tick.checked_sub(self.first_tick)?;
+        self.hashes.get(usize::try_from(index).ok()?).copied()
+    }
+
+    /// Returns the most recent tick and its hash.
+    pub fn last(&self) -> Option<(u64, u64)> {
+        let hash = *self.hashes.back()?;
+        Some((self.first_tick + self.hashes.len() as u64 - 1, hash))
+    }
+
+    /// Returns the first tick recorded in both logs whose hashes differ, or `None` if the runs
+    /// behaved identically for every tick both logs kept.
+    pub fn first_divergence(&self, other: &TickLog) -> Option<u64> {
+        let start = self.first_tick.max(other.first_tick);
+        (start..)
+            .map_while(|tick| Some((tick, self.hash(tick)?, other.hash(tick)?)))
+            .find(|(_, hash, other_hash)| hash != other_hash)
+            .map(|(tick, ..)| tick)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestep_simulates_whole_ticks_and_carries_the_remainder() {
+        let mut timestep = FixedTimestep::new(Duration::from_millis(10));
+
+        assert_eq!(timestep.advance(Duration::from_millis(25)), 2);
+        assert_eq!(timestep.advance(Duration::from_millis(4)), 0);
+        assert_eq!(timestep.advance(Duration::from_millis(1)), 1);
+        assert_eq!(timestep.advance(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn timestep_caps_the_ticks_after_a_stall() {
+        let mut timestep = FixedTimestep::new(Duration::from_millis(10));
+
+        assert_eq!(
+            timestep.advance(Duration::from_secs(1)),
+            FixedTimestep::MAX_TICKS_PER_FRAME
+        );
+        // The backlog is dropped, keeping at most one tick for the next frame.
+        assert_eq!(timestep.advance(Duration::ZERO), 1);
+        assert_eq!(timestep.advance(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn alpha_is_the_fraction_of_a_tick_left_over() {
+        let mut timestep = FixedTimestep::new(Duration::from_millis(10));
+        assert_eq!(timestep.alpha(), 0.0);
+
+        timestep.advance(Duration::from_millis(25));
+        assert!((timestep.alpha() - 0.5).abs() < 1e-6);
+
+        timestep.advance(Duration::from_millis(5));
+        assert_eq!(timestep.alpha(), 0.0);
+    }
+
+    #[test]
+    fn alpha_stays_at_most_one_after_a_stall() {
+        let mut timestep = FixedTimestep::new(Duration::from_millis(10));
+        timestep.advance(Duration::from_secs(1));
+
+        assert_eq!(timestep.alpha(), 1.0);
+    }
+
+    #[test]
+    fn rng_streams_are_reproducible_and_independent() {
+        let draw = |rng: &mut DeterministicRng| (0..4).map(|_| rng.next_u64()).collect::<Vec<_>>();
+
+        let particles = draw(&mut DeterministicRng::stream(7, "particles"));
+        assert_eq!(
+            particles,
+            draw(&mut DeterministicRng::stream(7, "particles"))
+        );
+        assert_ne!(particles, draw(&mut DeterministicRng::stream(7, "camera")));
+        assert_ne!(
+            particles,
+            draw(&mut DeterministicRng::stream(8, "particles"))
+        );
+    }
+
+    #[test]
+    fn rng_range_stays_in_bounds() {
+        let mut rng = DeterministicRng::new(1);
+        for _ in 0..1000 {
+            let value = rng.range(-2.0..3.0);
+            assert!((-2.0..3.0).contains(&value), "{value} is out of range");
+        }
+    }
+
+    #[test]
+    fn tick_logs_report_the_first_differing_tick() {
+        let mut log = TickLog::default();
+        let mut other = TickLog::default();
+        for tick in 1..=5 {
+            log.push(tick, tick);
+            other.push(tick, if tick >= 3 { 0 } else { tick });
+        }
+
+        assert_eq!(log.first_divergence(&log.clone()), None);
+        assert_eq!(log.first_divergence(&other), Some(3));
+        assert_eq!(log.last(), Some((5, 5)));
+    }
+
+    #[test]
+    fn tick_log_restarts_when_a_tick_is_skipped() {
+        let mut log = TickLog::default();
+        log.push(1, 10);
+        log.push(2, 20);
+        log.push(4, 40);
+
+        assert_eq!(log.hash(2), None);
+        assert_eq!(log.hash(4), Some(40));
+    }
+}
@@ -0,0 +1,163 @@
+//! # Dolly Zoom Module
+//!
+//! The `dolly_zoom` module animates the camera's field of view while moving the camera towards
+//! or away from its focus point, so the focus plane keeps its size on screen while the
+//! perspective around it stretches or flattens: the "vertigo" effect.
+//!
+//! ## Overview
+//!
+//! - [`DollyZoom`] holds the demo's settings, edited with sliders in the settings window: the
+//!   field of view the animation starts and ends at, and how long one way takes. The field of
+//!   view swings back and forth between the two, eased at both ends.
+//! - [`DollyZoomAnimation`] is a running dolly zoom. It is anchored at the point the camera
+//!   focuses on when it starts, and the width of the view at that point. Every frame it
+//!   returns the field of view and the camera position that keep the width, looking at the
+//!   anchor from the camera's current direction, so the camera can still be orbited.
+//! - [`focus_width`] and [`dolly_distance`] convert between the distance of the focus plane
+//!   and its width for a field of view.
+//!
+//! The `App` drives the animation between the camera update and the renderer, through
+//! `Camera::look_from` and the projection's field of view.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut animation = DollyZoomAnimation::start(&camera, projection.field_of_view);
+//!
+//! // Once per frame, after the camera's update:
+//! let pose = animation.advance(&settings, camera.forward(), delta_time);
+//! camera.look_from(pose.eye, camera.forward(), pose.distance);
+//! projection.field_of_view = pose.field_of_view;
+//! ```
+
+use crate::camera::{Camera, Projection};
+
+/// The settings of the dolly zoom demo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DollyZoom {
+    /// Whether the animation runs.
+    pub enabled: bool,
+
+    /// The vertical field of view the animation starts at, in degrees.
+    pub start_field_of_view: f32,
+
+    /// The vertical field of view the animation turns around at, in degrees.
+    pub end_field_of_view: f32,
+
+    /// The time from the start to the end field of view, in seconds.
+    pub duration: f32,
+}
+
+impl Default for DollyZoom {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_field_of_view: crate::scene::Scene::FIELD_OF_VIEW_DEGREES,
+            end_field_of_view: 25.0,
+            duration: 4.0,
+        }
+    }
+}
+
+impl DollyZoom {
+    /// The shortest and longest duration offered in the settings window, in seconds.
+    pub const DURATION_RANGE: (f32, f32) = (0.5, 10.0);
+
+    /// Returns the field of view `time` seconds into the animation, in degrees.
+    ///
+    /// The field of view moves from the start to the end in `duration` seconds and back in as
+    /// many, easing in and out at both ends.
+    pub fn field_of_view(&self, time: f32) -> f32 {
+        let duration = self.duration.max(Self::DURATION_RANGE.0);
+        let phase = (time / duration).rem_euclid(2.0);
+        let progress = if phase < 1.0 { phase } else { 2.0 - phase };
+        let eased = progress * progress * (3.0 - 2.0 * progress);
+        let field_of_view =
+            self.start_field_of_view + (self.end_field_of_view - self.start_field_of_view) * eased;
+        let (min_fov, max_fov) = Projection::FIELD_OF_VIEW_RANGE;
+        field_of_view.clamp(min_fov, max_fov)
+    }
+}
+
+/// Returns the width of the view at `distance` in front of a camera with a field of view of
+/// `field_of_view` degrees.
+pub fn focus_width(distance: f32, field_of_view: f32) -> f32 {
+    2.0 * distance * (field_of_view.to_radians() * 0.5).tan()
+}
+
+/// Returns the distance at which the view of a camera with a field of view of
+/// `field_of_view` degrees is `width` wide. This is the inverse of [`focus_width`].
+pub fn dolly_distance(width: f32, field_of_view: f32) -> f32 {
+    width / (2.0 * (field_of_view.to_radians() * 0.5).tan())
+}
+
+/// Where the camera is placed in one frame of a dolly zoom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DollyZoomPose {
+    /// The camera's position.
+    pub eye: crate::math::Vec3,
+
+    /// The distance from `eye` to the focus point.
+    pub distance: f32,
+
+    /// The vertical field of view, in degrees.
+    pub field_of_view: f32,
+}
+
+/// A running dolly zoom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DollyZoomAnimation {
+    /// The point the camera focused on when the animation started, which it keeps looking at.
+    focus: crate::math::Vec3,
+
+    /// The width of the view at `focus`, kept by the animation.
+    width: f32,
+
+    /// The field of view when the animation started, in degrees, restored by `stop`.
+    initial_field_of_view: f32,
+
+    /// The time since the animation started, in seconds.
+    time: f32,
+}
+
+impl DollyZoomAnimation {
+    /// Starts a dolly zoom from the pose of `camera`, which has a field of view of
+    /// `field_of_view` degrees.
+    pub fn start(camera: &Camera, field_of_view: f32) -> Self {
+        let distance = camera.focus_distance();
+        Self {
+            focus: camera.eye() + camera.forward() * distance,
+            width: focus_width(distance, field_of_view),
+            initial_field_of_view: field_of_view,
+            time: 0.0,
+        }
+    }
+
+    /// Advances the animation by `delta_time` seconds and returns the pose of a camera looking
+    /// in the `forward` direction.
+    pub fn advance(
+        &mut self,
+        settings: &DollyZoom,
+        forward: crate::math::Vec3,
+        delta_time: f32,
+    ) -> DollyZoomPose {
+        self.time += delta_time;
+        self.pose(settings.field_of_view(self.time), forward)
+    }
+
+    /// Returns the pose of a camera looking in the `forward` direction with the field of view
+    /// the animation started with, which ends it where it began.
+    pub fn stop(&self, forward: crate::math::Vec3) -> DollyZoomPose {
+        self.pose(self.initial_field_of_view, forward)
+    }
+
+    /// Returns the pose keeping the width at the focus point for `field_of_view` degrees.
+    fn pose(&self, field_of_view: f32, forward: crate::math::Vec3) -> DollyZoomPose {
+        let distance = dolly_distance(self.width, field_of_view);
+        DollyZoomPose {
+            eye: self.focus - crate::math::normalize(&forward) * distance,
+            distance,
+            field_of_view,
+        }
+    }
+}
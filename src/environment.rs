@@ -0,0 +1,429 @@
+//! # Environment Module
+//!
+//! The `environment` module gathers the settings that set the overall look of the scene:
+//! ambient light, sky, fog, exposure, and image-based lighting (IBL), and applies them on the
+//! GPU.
+//!
+//! ## Overview
+//!
+//! - [`Environment`] holds every setting in one serializable struct, edited in the
+//!   Environment panel. [`EnvironmentPreset`] provides complete looks (Studio, Outdoor, Night)
+//!   to start from.
+//! - [`EnvironmentBinding`] uploads the settings, reduced to an [`EnvironmentUniform`], to a
+//!   uniform buffer the scene's shader reads its lighting, fog, and exposure from.
+//! - `SkyRenderer` draws the selected [`Sky`] behind the scene, with the `scene3d` feature.
+//!   Without it, the scene is drawn over a plain clear color, and the sky only tints the
+//!   image-based lighting.
+//!
+//! The renderer holds a global environment, and a scene may override it with its own (see
+//! `Scene::environment_override`).
+//!
+//! The image-based lighting approximates the light arriving from the sky by the average of
+//! its horizon and zenith colors, so a brighter sky also lights the scene more.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let environment = EnvironmentPreset::Outdoor.environment();
+//! let json = serde_json::to_string_pretty(&environment)?;
+//!
+//! renderer.set_environment(&environment);
+//! ```
+
+#[cfg(feature = "scene3d")]
+use crate::renderer::Renderer;
+#[cfg(feature = "scene3d")]
+use crate::scene::Scene;
+use crate::shader_bindings::environment as environment_bindings;
+
+/// The WGSL source of the sky pass.
+#[cfg(feature = "scene3d")]
+const SKY_SHADER_SOURCE: &str = include_str!("environment.wgsl");
+
+/// The sky drawn behind the scene.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum Sky {
+    /// A flat backdrop in the renderer's clear color, the application's original background
+    /// color unless changed. Its image-based lighting keeps the original color.
+    #[default]
+    Backdrop,
+
+    /// A neutral gray gradient, like a photo studio's cyclorama.
+    Studio,
+
+    /// A clear blue daylight sky.
+    Daylight,
+
+    /// A dark night sky.
+    Night,
+}
+
+impl Sky {
+    /// Every sky, in the order they are offered in the user interface.
+    pub const ALL: [Sky; 4] = [Sky::Backdrop, Sky::Studio, Sky::Daylight, Sky::Night];
+
+    /// Returns the name shown in the user interface.
+    pub fn label(self) -> &'static str {
+        match self {
+            Sky::Backdrop => "Backdrop",
+            Sky::Studio => "Studio",
+            Sky::Daylight => "Daylight",
+            Sky::Night => "Night",
+        }
+    }
+
+    /// Returns the linear colors at the horizon and at the zenith.
+    pub fn colors(self) -> ([f32; 3], [f32; 3]) {
+        match self {
+            Sky::Backdrop => ([0.19, 0.24, 0.42], [0.19, 0.24, 0.42]),
+            Sky::Studio => ([0.45, 0.45, 0.47], [0.22, 0.22, 0.25]),
+            Sky::Daylight => ([0.70, 0.80, 0.95], [0.20, 0.40, 0.85]),
+            Sky::Night => ([0.05, 0.06, 0.12], [0.01, 0.01, 0.03]),
+        }
+    }
+}
+
+/// Exponential distance fog.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Fog {
+    /// Whether the fog is applied.
+    pub enabled: bool,
+
+    /// The linear color distant objects fade into.
+    pub color: [f32; 3],
+
+    /// How quickly objects fade with distance, per world unit.
+    pub density: f32,
+}
+
+impl Default for Fog {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            color: [0.5, 0.55, 0.6],
+            density: 0.1,
+        }
+    }
+}
+
+/// The ambient light, sky, fog, exposure, and image-based lighting of a scene.
+///
+/// Missing fields take their default values when deserializing, so saved environments keep
+/// loading when settings are added.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Environment {
+    /// The linear color of the ambient light.
+    pub ambient_color: [f32; 3],
+
+    /// The strength of the ambient light.
+    pub ambient_intensity: f32,
+
+    /// The sky drawn behind the scene.
+    pub sky: Sky,
+
+    /// The distance fog.
+    pub fog: Fog,
+
+    /// The exposure, in stops. Each stop doubles the brightness.
+    pub exposure: f32,
+
+    /// The strength of the light from the sky (image-based lighting).
+    pub ibl_strength: f32,
+}
+
+/// The default environment lights the scene's vertex colors unchanged, in front of the
+/// original backdrop.
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            ambient_color: [1.0, 1.0, 1.0],
+            ambient_intensity: 1.0,
+            sky: Sky::Backdrop,
+            fog: Fog::default(),
+            exposure: 0.0,
+            ibl_strength: 0.0,
+        }
+    }
+}
+
+impl Environment {
+    /// Reduces the settings to the values the shaders use, for a camera with the given
+    /// view-projection matrix.
+    pub fn uniform(&self, view_projection: &crate::math::Mat4) -> EnvironmentUniform {
+        let (horizon, zenith) = self.sky.colors();
+        let ambient: [f32; 3] = std::array::from_fn(|channel| {
+            let sky_light = (horizon[channel] + zenith[channel]) * 0.5;
+            self.ambient_color[channel] * self.ambient_intensity + sky_light * self.ibl_strength
+        });
+        let fog_density = if self.fog.enabled {
+            self.fog.density
+        } else {
+            0.0
+        };
+
+        EnvironmentUniform {
+            ambient: [ambient[0], ambient[1], ambient[2], self.exposure.exp2()],
+            fog: [
+                self.fog.color[0],
+                self.fog.color[1],
+                self.fog.color[2],
+                fog_density,
+            ],
+            sky_horizon: [horizon[0], horizon[1], horizon[2], 1.0],
+            sky_zenith: [zenith[0], zenith[1], zenith[2], 1.0],
+            inverse_view_projection: crate::math::inverse(view_projection),
+        }
+    }
+}
+
+/// A complete environment to start editing from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EnvironmentPreset {
+    /// Even, neutral light in front of a gray gradient.
+    Studio,
+
+    /// Warm daylight under a blue sky, with light haze.
+    Outdoor,
+
+    /// Dim, blue light under a dark sky, with dense fog and raised exposure.
+    Night,
+}
+
+impl EnvironmentPreset {
+    /// Every preset, in the order they are offered in the user interface.
+    pub const ALL: [EnvironmentPreset; 3] = [
+        EnvironmentPreset::Studio,
+        EnvironmentPreset::Outdoor,
+        EnvironmentPreset::Night,
+    ];
+
+    /// Returns the name shown in the user interface.
+    pub fn label(self) -> &'static str {
+        match self {
+            EnvironmentPreset::Studio => "Studio",
+            EnvironmentPreset::Outdoor => "Outdoor",
+            EnvironmentPreset::Night => "Night",
+        }
+    }
+
+    /// Returns the preset's environment.
+    pub fn environment(self) -> Environment {
+        match self {
+            EnvironmentPreset::Studio => Environment {
+                ambient_color: [1.0, 1.0, 1.0],
+                ambient_intensity: 0.8,
+                sky: Sky::Studio,
+                fog: Fog::default(),
+                exposure: 0.0,
+                ibl_strength: 0.5,
+            },
+            EnvironmentPreset::Outdoor => Environment {
+                ambient_color: [1.0, 0.97, 0.9],
+                ambient_intensity: 0.7,
+                sky: Sky::Daylight,
+                fog: Fog {
+                    enabled: true,
+                    color: Sky::Daylight.colors().0,
+                    density: 0.05,
+                },
+                exposure: 0.0,
+                ibl_strength: 0.6,
+            },
+            EnvironmentPreset::Night => Environment {
+                ambient_color: [0.4, 0.5, 0.8],
+                ambient_intensity: 0.3,
+                sky: Sky::Night,
+                fog: Fog {
+                    enabled: true,
+                    color: Sky::Night.colors().0,
+                    density: 0.15,
+                },
+                exposure: 1.0,
+                ibl_strength: 0.3,
+            },
+        }
+    }
+}
+
+/// The environment as read by the shaders.
+///
+/// Colors are stored in `vec4`s to match WGSL's uniform layout rules.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct EnvironmentUniform {
+    /// The combined ambient and image-based light in `rgb`, and the exposure scale in `a`.
+    pub ambient: [f32; 4],
+
+    /// The fog color in `rgb`, and the fog density in `a` (zero without fog).
+    pub fog: [f32; 4],
+
+    /// The sky's color at the horizon.
+    pub sky_horizon: [f32; 4],
+
+    /// The sky's color at the zenith.
+    pub sky_zenith: [f32; 4],
+
+    /// Reconstructs view directions for the sky.
+    pub inverse_view_projection: crate::math::Mat4,
+}
+
+/// The uniform buffer holding the [`EnvironmentUniform`], and its bind group.
+#[derive(Debug)]
+pub struct EnvironmentBinding {
+    /// The buffer holding the `EnvironmentUniform`.
+    pub buffer: wgpu::Buffer,
+
+    /// The layout of `bind_group`, shared by the scene's and the sky's pipelines.
+    pub bind_group_layout: wgpu::BindGroupLayout,
+
+    /// Exposes `buffer` to the shaders.
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl EnvironmentBinding {
+    /// Creates the binding, holding the default environment.
+    pub fn new(device: &wgpu::Device) -> Self {
+        let buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Environment Uniform Buffer"),
+                contents: bytemuck::bytes_of(
+                    &Environment::default().uniform(&crate::math::identity()),
+                ),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+
+        // The sky's group is also the scene's `@group(1)`, declared alike in
+        // `shader_source.wgsl`.
+        let bind_group_layout = environment_bindings::BindGroup0::create_layout(device);
+        let bind_group = environment_bindings::BindGroup0 {
+            environment: buffer.as_entire_buffer_binding(),
+        }
+        .create(device, &bind_group_layout);
+
+        Self {
+            buffer,
+            bind_group_layout,
+            bind_group,
+        }
+    }
+
+    /// Uploads `environment` for a camera with the given view-projection matrix.
+    pub fn update(
+        &self,
+        queue: &wgpu::Queue,
+        environment: &Environment,
+        view_projection: &crate::math::Mat4,
+    ) {
+        queue.write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::bytes_of(&environment.uniform(view_projection)),
+        );
+    }
+}
+
+/// Draws the sky behind the scene.
+#[cfg(feature = "scene3d")]
+#[derive(Debug)]
+pub struct SkyRenderer {
+    /// Draws a fullscreen triangle with the sky gradient.
+    pipeline: wgpu::RenderPipeline,
+}
+
+#[cfg(feature = "scene3d")]
+impl SkyRenderer {
+    /// Creates the sky's pipeline for the scene pass, reading the `scene`'s environment, and
+    /// compiles it through `cache` if one is given.
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        scene: &Scene,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Sky Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(SKY_SHADER_SOURCE)),
+        });
+
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Sky Pipeline Layout"),
+            bind_group_layouts: &[&scene.environment.bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Sky Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("sky_vertex"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            // The sky is drawn first and leaves the depth buffer untouched, so everything else
+            // is drawn in front of it.
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: Renderer::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("sky_fragment"),
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: surface_format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(wgpu::ColorTargetState {
+                        format: Scene::OBJECT_ID_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::empty(),
+                    }),
+                ],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        });
+
+        Self { pipeline }
+    }
+
+    /// Draws the sky of the `scene`'s environment. Call this first in the scene pass.
+    pub fn render<'rpass>(
+        &'rpass self,
+        render_pass: &mut wgpu::RenderPass<'rpass>,
+        scene: &'rpass Scene,
+    ) {
+        self.bind_pipeline(render_pass);
+        Self::bind_material(render_pass, scene);
+        Self::draw(render_pass);
+    }
+
+    /// Sets the sky's pipeline.
+    pub fn bind_pipeline<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_pipeline(&self.pipeline);
+    }
+
+    /// Binds the `scene`'s environment, the sky's material, at set 0.
+    pub fn bind_material<'rpass>(render_pass: &mut wgpu::RenderPass<'rpass>, scene: &'rpass Scene) {
+        render_pass.set_bind_group(0, &scene.environment.bind_group, &[]);
+    }
+
+    /// Draws the full-screen triangle of the sky, after its pipeline and material are bound.
+    pub fn draw(render_pass: &mut wgpu::RenderPass<'_>) {
+        render_pass.draw(0..3, 0..1);
+    }
+}
@@ -0,0 +1,61 @@
+// Draws the sky behind the scene: a gradient from the horizon to the zenith color along the
+// view direction, reconstructed per pixel from the inverse view-projection matrix.
+
+struct Environment {
+    // rgb: ambient and image-based light, a: exposure scale.
+    ambient: vec4<f32>,
+    // rgb: fog color, a: fog density (0 disables fog).
+    fog: vec4<f32>,
+    sky_horizon: vec4<f32>,
+    sky_zenith: vec4<f32>,
+    inverse_view_projection: mat4x4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> environment: Environment;
+
+struct SkyOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+};
+
+@vertex
+fn sky_vertex(@builtin(vertex_index) index: u32) -> SkyOutput {
+    // A single triangle covering the viewport.
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    let ndc = uv * 2.0 - 1.0;
+
+    var out: SkyOutput;
+    out.position = vec4<f32>(ndc, 0.0, 1.0);
+    out.ndc = ndc;
+    return out;
+}
+
+fn unproject(ndc: vec2<f32>, depth: f32) -> vec3<f32> {
+    let world = environment.inverse_view_projection * vec4<f32>(ndc, depth, 1.0);
+    return world.xyz / world.w;
+}
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    // The sky is background; the pipeline masks out writes to the id target.
+    @location(1) object_id: u32,
+};
+
+@fragment
+fn sky_fragment(in: SkyOutput) -> FragmentOutput {
+    let direction = normalize(unproject(in.ndc, 1.0) - unproject(in.ndc, 0.0));
+
+    // Above the horizon the gradient rises quickly and then levels off; below it, the horizon
+    // color fades into a darker ground.
+    let height = sqrt(max(direction.y, 0.0));
+    var color = mix(environment.sky_horizon.rgb, environment.sky_zenith.rgb, height);
+    if direction.y < 0.0 {
+        color = environment.sky_horizon.rgb * mix(1.0, 0.5, min(-direction.y * 4.0, 1.0));
+    }
+
+    var out: FragmentOutput;
+    out.color = vec4<f32>(color * environment.ambient.a, 1.0);
+    out.object_id = 0u;
+    return out;
+}
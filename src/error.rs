@@ -0,0 +1,208 @@
+//! # Error Handling Module
+//!
+//! The `error` module defines the crate-wide [`RenderError`] type returned by GPU
+//! initialization and frame rendering, together with the policy hooks the application
+//! uses to decide how to react to those errors.
+//!
+//! ## Overview
+//!
+//! Rendering code (`Gpu::new_async`, `Renderer::new`, `Renderer::render_frame`) never panics
+//! on recoverable conditions such as a missing adapter or a lost surface. Instead, it
+//! returns a `RenderError` describing what went wrong. The `App` then consults an
+//! [`ErrorPolicy`] to choose an [`ErrorAction`]:
+//!
+//! - **Retry**: Try the failed operation again (re-run initialization, or render the next frame).
+//! - **Fallback**: Retry with a degraded configuration (a software adapter during
+//!   initialization, or a reconfigured surface while rendering frames).
+//! - **Software**: Switch to the CPU rasterizer of the `software` feature during initialization.
+//! - **Ignore**: Continue without taking any action.
+//! - **Exit**: Stop the event loop.
+//!
+//! This makes the crate usable as a library in environments where panicking is not acceptable:
+//! embedding applications can install their own policy with `App::with_error_policy`.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let app = App::default().with_error_policy(|error, phase, attempt| match phase {
+//!     ErrorPhase::Initialization if attempt < 5 => ErrorAction::Retry,
+//!     ErrorPhase::Initialization => ErrorAction::Exit,
+//!     ErrorPhase::Frame => ErrorAction::Ignore,
+//! });
+//! ```
+
+// Importing the `ValidationError` type so configuration validation failures that cannot be
+// recovered from during initialization can be propagated as a `RenderError`.
+use crate::validation::ValidationError;
+
+/// Errors produced while initializing the GPU or rendering a frame.
+///
+/// # Variants
+///
+/// - `CreateSurface`: The window or canvas could not be turned into a `wgpu::Surface`.
+/// - `NoAdapter`: No GPU adapter compatible with the surface was found.
+/// - `RequestDevice`: The adapter refused to create a device with the requested features/limits.
+/// - `IncompatibleSurface`: The surface reports no usable formats, present modes, or alpha modes.
+/// - `Validation`: The requested configuration is not supported and no fallback exists.
+/// - `Surface`: The next surface texture could not be acquired while rendering a frame.
+/// - `NotHeadless`: A frame was rendered into a buffer by a renderer drawing into a window.
+/// - `Readback`: The pixels of a frame rendered into a buffer could not be read back.
+/// - `Software`: The software rasterizer could not present to the window.
+/// - `MissingCanvas`: The page has no canvas element with the configured id to draw into.
+#[derive(Debug, thiserror::Error)]
+pub enum RenderError {
+    /// The window or canvas could not be turned into a `wgpu::Surface`.
+    #[error("failed to create surface: {0}")]
+    CreateSurface(#[from] wgpu::CreateSurfaceError),
+
+    /// No GPU adapter compatible with the surface was found.
+    #[error("no compatible GPU adapter was found")]
+    NoAdapter,
+
+    /// The adapter refused to create a device.
+    #[error("failed to request a device: {0}")]
+    RequestDevice(#[from] wgpu::RequestDeviceError),
+
+    /// The surface is not compatible with the selected adapter.
+    #[error("the surface is not compatible with the selected adapter")]
+    IncompatibleSurface,
+
+    /// The requested configuration is not supported and no fallback exists.
+    #[error("invalid configuration: {0}")]
+    Validation(#[from] ValidationError),
+
+    /// The next surface texture could not be acquired.
+    #[error("failed to acquire the next surface texture: {0}")]
+    Surface(#[from] wgpu::SurfaceError),
+
+    /// Only headless renderers can render frames into buffers.
+    #[error("the renderer draws into a window; only headless renderers render into buffers")]
+    NotHeadless,
+
+    /// The pixels of a frame could not be read back from the GPU.
+    #[error("failed to read back the rendered frame: {0}")]
+    Readback(#[from] wgpu::BufferAsyncError),
+
+    /// The software rasterizer could not present to the window.
+    #[cfg(all(feature = "software", not(target_arch = "wasm32")))]
+    #[error("software rendering failed: {0}")]
+    Software(#[from] softbuffer::SoftBufferError),
+
+    /// The page has no canvas element with the given id.
+    #[cfg(target_arch = "wasm32")]
+    #[error("the page has no canvas element with the id '{0}'")]
+    MissingCanvas(String),
+}
+
+/// The stage of the application lifecycle in which a `RenderError` occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorPhase {
+    /// While creating the GPU context and renderer.
+    Initialization,
+
+    /// While rendering a frame.
+    Frame,
+}
+
+/// The reaction chosen by an `ErrorPolicy` for a `RenderError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Try the failed operation again.
+    ///
+    /// During initialization the renderer is created again; while rendering, the current
+    /// frame is skipped and the next one is attempted normally.
+    Retry,
+
+    /// Try again with a degraded configuration.
+    ///
+    /// During initialization a fallback (software) adapter is requested; while rendering,
+    /// the surface is reconfigured before the next frame.
+    Fallback,
+
+    /// Replace the renderer with the CPU rasterizer of the `software` feature.
+    ///
+    /// Only used during initialization, when no GPU adapter can be created at all. While
+    /// rendering, it is treated like `Ignore`.
+    #[cfg(all(feature = "software", not(target_arch = "wasm32")))]
+    Software,
+
+    /// Continue without any further action.
+    Ignore,
+
+    /// Stop the application's event loop.
+    Exit,
+}
+
+/// The signature of a closure used as an `ErrorPolicy`.
+type ErrorPolicyFn = dyn FnMut(&RenderError, ErrorPhase, u32) -> ErrorAction;
+
+/// A hook deciding how the application reacts to a `RenderError`.
+///
+/// The hook receives the error, the phase it occurred in, and the number of consecutive
+/// failures that preceded it in that phase (`0` for the first failure), so policies can
+/// escalate from retrying to exiting.
+///
+/// The default policy is [`default_error_policy`].
+pub struct ErrorPolicy(Box<ErrorPolicyFn>);
+
+impl ErrorPolicy {
+    /// Creates a policy from a closure.
+    pub fn new(policy: impl FnMut(&RenderError, ErrorPhase, u32) -> ErrorAction + 'static) -> Self {
+        Self(Box::new(policy))
+    }
+
+    /// Decides how to react to `error` that occurred in `phase` after `attempt` previous
+    /// consecutive failures.
+    pub fn decide(&mut self, error: &RenderError, phase: ErrorPhase, attempt: u32) -> ErrorAction {
+        (self.0)(error, phase, attempt)
+    }
+}
+
+impl Default for ErrorPolicy {
+    fn default() -> Self {
+        Self::new(default_error_policy)
+    }
+}
+
+/// The default reaction to rendering errors.
+///
+/// # Initialization
+///
+/// - A missing adapter is retried once with a fallback (software) adapter.
+/// - A failed device request is retried up to two times.
+/// - With the `software` feature, the renderer is replaced by the CPU rasterizer once the
+///   above are exhausted.
+/// - Anything else exits, because retrying cannot change the outcome.
+///
+/// # Frames
+///
+/// - Timeouts skip the frame and retry on the next one. If they keep happening, the surface
+///   is reconfigured (fallback), since its present mode may no longer be supported.
+/// - Outdated or lost surfaces reconfigure the surface (fallback), exiting if that keeps
+///   failing for more than a few frames in a row.
+/// - Generic acquisition failures (`SurfaceError::Other`), which some drivers report when a
+///   monitor is unplugged or the display mode changes, also reconfigure the surface, but exit
+///   sooner, since they may not be transient.
+/// - Running out of memory, or any other failure, exits.
+pub fn default_error_policy(error: &RenderError, phase: ErrorPhase, attempt: u32) -> ErrorAction {
+    match phase {
+        ErrorPhase::Initialization => match error {
+            RenderError::NoAdapter if attempt == 0 => ErrorAction::Fallback,
+            RenderError::RequestDevice(_) if attempt < 2 => ErrorAction::Retry,
+            #[cfg(all(feature = "software", not(target_arch = "wasm32")))]
+            RenderError::NoAdapter | RenderError::RequestDevice(_) => ErrorAction::Software,
+            _ => ErrorAction::Exit,
+        },
+        ErrorPhase::Frame => match error {
+            RenderError::Surface(wgpu::SurfaceError::Timeout) if attempt < 3 => ErrorAction::Retry,
+            RenderError::Surface(wgpu::SurfaceError::Timeout) => ErrorAction::Fallback,
+            RenderError::Surface(wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost)
+                if attempt < 8 =>
+            {
+                ErrorAction::Fallback
+            }
+            RenderError::Surface(wgpu::SurfaceError::Other) if attempt < 3 => ErrorAction::Fallback,
+            _ => ErrorAction::Exit,
+        },
+    }
+}
@@ -0,0 +1,276 @@
+//! # Frame Capture Module
+//!
+//! The `frame_capture` module saves screenshots: it copies a rendered frame out of the surface
+//! texture and writes it as a PNG, on desktop into the working directory, and on the web as a
+//! download by the browser.
+//!
+//! ## Overview
+//!
+//! [`FrameCapture`] reads the frame back without blocking the render thread, in the same stages
+//! as the depth probe and the GUI capture:
+//!
+//! 1. **`request`**: Asks for the next rendered frame to be captured.
+//! 2. **`encode_copy`**: Records a copy of the surface texture into a readback buffer, once the
+//!    frame has been rendered into it.
+//! 3. **`after_submit`**: Once the frame is submitted, starts mapping the readback buffer.
+//! 4. **`poll`**: In a later frame, converts the pixels to RGBA and saves the PNG.
+//!
+//! Copying requires the surface textures to have `COPY_SRC` usage, which the `Gpu` requests
+//! wherever the surface supports it. The pixels are converted from BGRA to RGBA order where
+//! needed, and from premultiplied to straight alpha for transparent windows.
+//!
+//! The readback layout and the PNG helpers are shared with the `ui_capture` module.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! capture.request(true);
+//!
+//! // ... in the next frame, after rendering into `surface_texture` ...
+//! capture.encode_copy(&device, &mut encoder, &surface_texture.texture);
+//! queue.submit(std::iter::once(encoder.finish()));
+//! capture.after_submit();
+//!
+//! capture.poll(&device);
+//! if let Some(Ok(location)) = capture.take_finished() {
+//!     log::info!("Saved the screenshot to {location}");
+//! }
+//! ```
+
+// Importing the readback the captured frame is mapped through.
+use crate::readback::Readback;
+
+/// Errors produced while capturing a frame.
+#[derive(Debug, thiserror::Error)]
+pub enum FrameCaptureError {
+    /// The surface textures lack `COPY_SRC` usage, so frames cannot be read back.
+    #[error("the surface does not support copying its frames")]
+    SurfaceNotCopyable,
+
+    /// The surface format cannot be converted to an 8-bit RGBA PNG.
+    #[error("screenshots are not supported with the {0:?} surface format")]
+    UnsupportedFormat(wgpu::TextureFormat),
+
+    /// The captured pixels could not be read back from the GPU.
+    #[error("failed to read back the screenshot: {0}")]
+    Readback(#[from] wgpu::BufferAsyncError),
+
+    /// The captured pixels could not be encoded as a PNG.
+    #[error("failed to encode the screenshot: {0}")]
+    Encode(#[from] png::EncodingError),
+
+    /// The PNG could not be written, or the browser refused to download it.
+    #[error("failed to save the screenshot: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The size and layout of a captured image in its readback buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CaptureLayout {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+
+    /// The bytes per row in the buffer, padded to `wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`.
+    pub(crate) padded_bytes_per_row: u32,
+
+    /// Whether the pixels are stored in BGRA order.
+    pub(crate) bgra: bool,
+}
+
+impl CaptureLayout {
+    /// Returns the layout of a `width` x `height` capture in `format`, or `None` if the format
+    /// is not an 8-bit RGBA or BGRA format.
+    pub(crate) fn new(width: u32, height: u32, format: wgpu::TextureFormat) -> Option<Self> {
+        let bgra = match format {
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+            _ => return None,
+        };
+        Some(Self {
+            width,
+            height,
+            padded_bytes_per_row: (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+            bgra,
+        })
+    }
+}
+
+/// Captures rendered frames into PNGs.
+#[derive(Debug, Default)]
+pub struct FrameCapture {
+    /// Whether the next rendered frame is captured.
+    requested: bool,
+
+    /// The readback of the captured frame, in the layout it was copied with.
+    readback: Readback<CaptureLayout>,
+
+    /// Where the last capture was saved, or why it failed, until taken.
+    finished: Option<Result<String, FrameCaptureError>>,
+}
+
+impl FrameCapture {
+    /// Asks for the next rendered frame to be captured. Ignored while a capture is in
+    /// progress.
+    ///
+    /// `copyable` tells whether the surface textures have `COPY_SRC` usage; if not, the
+    /// capture fails right away.
+    pub fn request(&mut self, copyable: bool) {
+        if self.requested || !self.readback.is_idle() {
+            return;
+        }
+        if copyable {
+            self.requested = true;
+        } else {
+            self.finished = Some(Err(FrameCaptureError::SurfaceNotCopyable));
+        }
+    }
+
+    /// Returns where the last capture was saved, or why it failed, once it finished.
+    pub fn take_finished(&mut self) -> Option<Result<String, FrameCaptureError>> {
+        self.finished.take()
+    }
+
+    /// Records a copy of `texture`, the rendered surface texture, into a readback buffer if a
+    /// capture was requested.
+    pub fn encode_copy(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) {
+        if !std::mem::take(&mut self.requested) {
+            return;
+        }
+
+        let Some(layout) = CaptureLayout::new(texture.width(), texture.height(), texture.format())
+        else {
+            self.finished = Some(Err(FrameCaptureError::UnsupportedFormat(texture.format())));
+            return;
+        };
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Capture Readback Buffer"),
+            size: (layout.padded_bytes_per_row * layout.height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(layout.padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        self.readback.copied(buffer, layout);
+    }
+
+    /// Starts mapping the readback buffer once the frame containing the copy was submitted.
+    pub fn after_submit(&mut self) {
+        self.readback.after_submit();
+    }
+
+    /// Finishes a capture in flight, if its buffer has been mapped, and saves the PNG. This
+    /// never blocks.
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        let Some(result) = self
+            .readback
+            .poll(device, |data, layout| (straight_rgba(data, layout), layout))
+        else {
+            return;
+        };
+
+        self.finished = Some(result.map_err(FrameCaptureError::from).and_then(
+            |(pixels, layout)| {
+                let png = encode_png(&pixels, layout.width, layout.height)?;
+                Ok(save(png, "screenshot")?)
+            },
+        ));
+    }
+}
+
+/// Converts the premultiplied pixels in a readback buffer into tightly packed, straight alpha
+/// RGBA rows.
+pub(crate) fn straight_rgba(data: &[u8], layout: CaptureLayout) -> Vec<u8> {
+    let row_bytes = (layout.width * 4) as usize;
+    let mut pixels = Vec::with_capacity(row_bytes * layout.height as usize);
+    for row in data.chunks(layout.padded_bytes_per_row as usize) {
+        for pixel in row[..row_bytes].chunks_exact(4) {
+            let (mut red, green, mut blue, alpha) = (pixel[0], pixel[1], pixel[2], pixel[3]);
+            if layout.bgra {
+                std::mem::swap(&mut red, &mut blue);
+            }
+            let straight = |channel: u8| match alpha {
+                0 => 0,
+                255 => channel,
+                _ => ((channel as u32 * 255 + alpha as u32 / 2) / alpha as u32).min(255) as u8,
+            };
+            pixels.extend_from_slice(&[straight(red), straight(green), straight(blue), alpha]);
+        }
+    }
+    pixels
+}
+
+/// Encodes straight alpha RGBA `pixels` as an sRGB PNG.
+pub(crate) fn encode_png(
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, png::EncodingError> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels)?;
+    writer.finish()?;
+    Ok(bytes)
+}
+
+/// Returns a file name starting with `prefix` for a capture taken now.
+fn file_name(prefix: &str) -> String {
+    let seconds = web_time::SystemTime::now()
+        .duration_since(web_time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    format!("{prefix}-{seconds}.png")
+}
+
+/// Writes the PNG into the working directory, named after `prefix` and the time, returning
+/// its path.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn save(png: Vec<u8>, prefix: &str) -> std::io::Result<String> {
+    let path = std::env::current_dir()?.join(file_name(prefix));
+    std::fs::write(&path, png)?;
+    Ok(path.display().to_string())
+}
+
+/// Lets the browser download the PNG, named after `prefix` and the time, returning its file
+/// name.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn save(png: Vec<u8>, prefix: &str) -> std::io::Result<String> {
+    use wasm_bindgen::JsCast;
+
+    let download = || -> Option<String> {
+        let bytes = js_sys::Array::of1(&js_sys::Uint8Array::from(png.as_slice()));
+        let blob = web_sys::Blob::new_with_u8_array_sequence(&bytes).ok()?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob).ok()?;
+        let anchor = web_sys::window()?
+            .document()?
+            .create_element("a")
+            .ok()?
+            .dyn_into::<web_sys::HtmlAnchorElement>()
+            .ok()?;
+        let name = file_name(prefix);
+        anchor.set_href(&url);
+        anchor.set_download(&name);
+        anchor.click();
+        // The URL is not revoked, since the download may still be reading it.
+        Some(name)
+    };
+    download().ok_or_else(|| std::io::Error::other("the browser refused to download the PNG"))
+}
@@ -0,0 +1,351 @@
+//! # Frame Export Module
+//!
+//! The `frame_export` module renders a camera path offline into an image sequence or a video,
+//! at a resolution independent of the window, for demo reels and for comparing benchmark runs
+//! frame by frame.
+//!
+//! ## Overview
+//!
+//! [`export_frames`] flies a headless renderer along a [`CameraPath`], as configured by a
+//! [`FrameExport`]:
+//!
+//! - Frame `i` is rendered at `i / frame_rate` seconds into the path, after advancing the
+//!   scene's animations by exactly one frame: the scene by the simulation ticks up to that
+//!   time, drawn between the last two, and the other animations by the frame's duration. The
+//!   renderer runs in determinism mode with the export's seed, so every export of a path
+//!   renders the same frames however long each takes.
+//! - The output's extension picks the [`FrameSink`]: a video extension (`.mp4`, `.mkv`,
+//!   `.mov`, `.webm`, or `.gif`) pipes the raw frames into an `ffmpeg` child process, which
+//!   must be on the `PATH`; anything else is a directory the frames are written into as
+//!   `frame_00000.png`, `frame_00001.png`, and so on.
+//! - The frames are rendered without the GUI, at [`FrameExport::DEFAULT_SIZE`] unless another
+//!   size is set.
+//!
+//! The `--export` option of the binary exports the path `camera_path.json`, as exported from
+//! the "Flythrough" settings, and exits without opening a window:
+//!
+//! ```sh
+//! $ cargo run --release -- --export frames.mp4 --size 1280x720 --fps 30
+//! $ cargo run --release -- --export frames --camera-path demo.json --frames 120
+//! ```
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let path = CameraPath::from_json(&std::fs::read_to_string("camera_path.json")?)?;
+//! let export = FrameExport::new("frames.mp4", path)
+//!     .with_size(1920, 1080)
+//!     .with_frame_rate(60);
+//! let frames = export_frames(&export)?;
+//! println!("Exported {frames} frames");
+//! ```
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use crate::camera::{Camera, Projection};
+use crate::camera_path::CameraPath;
+use crate::error::RenderError;
+use crate::frame_capture::encode_png;
+use crate::renderer::Renderer;
+use crate::simulation::TICK;
+
+/// Errors produced while exporting frames.
+#[derive(Debug, thiserror::Error)]
+pub enum FrameExportError {
+    /// The camera path has no keyframes to fly along.
+    #[error("the camera path has no keyframes")]
+    EmptyPath,
+
+    /// The frames would have no pixels.
+    #[error("the frames must be at least 1x1 pixels")]
+    EmptySize,
+
+    /// The headless renderer could not be created or could not render a frame.
+    #[error("failed to render a frame: {0}")]
+    Render(#[from] RenderError),
+
+    /// A frame could not be encoded as a PNG.
+    #[error("failed to encode a frame: {0}")]
+    Encode(#[from] png::EncodingError),
+
+    /// A frame or the output directory could not be written.
+    #[error("failed to write '{path}': {source}")]
+    Io {
+        /// The path of the file or directory.
+        path: String,
+
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+
+    /// `ffmpeg` could not be started, or stopped reading the frames.
+    #[error("failed to run ffmpeg: {0}")]
+    Ffmpeg(std::io::Error),
+
+    /// `ffmpeg` failed to encode the video.
+    #[error("ffmpeg failed to encode the video ({0})")]
+    FfmpegStatus(std::process::ExitStatus),
+}
+
+/// What to render, and where to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameExport {
+    /// The video file, or the directory the PNGs are written into.
+    pub output: PathBuf,
+
+    /// The path the camera flies along.
+    pub path: CameraPath,
+
+    /// The width and height of the frames, in pixels.
+    pub size: (u32, u32),
+
+    /// The frames per second of the path's time.
+    pub frame_rate: u32,
+
+    /// The number of frames, or `None` for as many as the path lasts.
+    pub frames: Option<u32>,
+
+    /// The seed of the determinism mode the frames are rendered in.
+    pub seed: u64,
+}
+
+impl FrameExport {
+    /// The size of the frames unless set with [`FrameExport::with_size`].
+    pub const DEFAULT_SIZE: (u32, u32) = (1920, 1080);
+
+    /// The frame rate unless set with [`FrameExport::with_frame_rate`].
+    pub const DEFAULT_FRAME_RATE: u32 = 60;
+
+    /// The extensions of the outputs encoded into a video by `ffmpeg`.
+    pub const VIDEO_EXTENSIONS: [&'static str; 5] = ["mp4", "mkv", "mov", "webm", "gif"];
+
+    /// Creates an export of `path` into `output`, at the default size and frame rate, for as
+    /// long as the path lasts.
+    pub fn new(output: impl Into<PathBuf>, path: CameraPath) -> Self {
+        Self {
+            output: output.into(),
+            path,
+            size: Self::DEFAULT_SIZE,
+            frame_rate: Self::DEFAULT_FRAME_RATE,
+            frames: None,
+            seed: 0,
+        }
+    }
+
+    /// Sets the width and height of the frames, in pixels.
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    /// Sets the frames per second, at least one.
+    pub fn with_frame_rate(mut self, frame_rate: u32) -> Self {
+        self.frame_rate = frame_rate.max(1);
+        self
+    }
+
+    /// Sets the number of frames, instead of as many as the path lasts.
+    pub fn with_frames(mut self, frames: u32) -> Self {
+        self.frames = Some(frames);
+        self
+    }
+
+    /// Sets the seed of the determinism mode the frames are rendered in.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the number of frames rendered: the one set, or enough to reach the end of the
+    /// path.
+    pub fn frame_count(&self) -> u32 {
+        self.frames
+            .unwrap_or_else(|| (self.path.duration() * self.frame_rate as f32).ceil() as u32 + 1)
+    }
+
+    /// Returns whether the output is a video encoded by `ffmpeg`, by its extension.
+    pub fn is_video(&self) -> bool {
+        self.output
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                Self::VIDEO_EXTENSIONS
+                    .iter()
+                    .any(|video| video.eq_ignore_ascii_case(extension))
+            })
+    }
+}
+
+/// Where the exported frames go.
+#[derive(Debug)]
+pub enum FrameSink {
+    /// A directory of numbered PNGs.
+    Images(PathBuf),
+
+    /// An `ffmpeg` child process reading raw RGBA frames from its standard input.
+    Ffmpeg {
+        /// The process.
+        child: Child,
+
+        /// Its standard input, closed to end the video.
+        stdin: Option<ChildStdin>,
+    },
+}
+
+impl FrameSink {
+    /// Opens the sink of `export`: creates the output directory, or starts `ffmpeg`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FrameExportError::Io` if the directory cannot be created, or
+    /// `FrameExportError::Ffmpeg` if `ffmpeg` cannot be started.
+    pub fn open(export: &FrameExport) -> Result<Self, FrameExportError> {
+        if !export.is_video() {
+            std::fs::create_dir_all(&export.output).map_err(|source| FrameExportError::Io {
+                path: export.output.display().to_string(),
+                source,
+            })?;
+            return Ok(Self::Images(export.output.clone()));
+        }
+
+        let (width, height) = export.size;
+        let mut command = Command::new("ffmpeg");
+        command
+            .args([
+                "-y",
+                "-loglevel",
+                "error",
+                "-f",
+                "rawvideo",
+                "-pix_fmt",
+                "rgba",
+            ])
+            .args(["-s", &format!("{width}x{height}")])
+            .args(["-r", &export.frame_rate.to_string()])
+            .args(["-i", "-"]);
+        // Most players only play 4:2:0 video, which needs an even width and height.
+        let gif = export
+            .output
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gif"));
+        if !gif {
+            command.args([
+                "-vf",
+                "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+                "-pix_fmt",
+                "yuv420p",
+            ]);
+        }
+        let mut child = command
+            .arg(&export.output)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(FrameExportError::Ffmpeg)?;
+        let stdin = child.stdin.take();
+        Ok(Self::Ffmpeg { child, stdin })
+    }
+
+    /// Writes the frame numbered `index`: straight RGBA pixels of `width` x `height`, row by
+    /// row from the top.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FrameExportError` if the frame cannot be encoded or written.
+    pub fn write(
+        &mut self,
+        index: u32,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+    ) -> Result<(), FrameExportError> {
+        match self {
+            Self::Images(directory) => {
+                let path = directory.join(format!("frame_{index:05}.png"));
+                let png = encode_png(pixels, width, height)?;
+                std::fs::write(&path, png).map_err(|source| FrameExportError::Io {
+                    path: path.display().to_string(),
+                    source,
+                })
+            }
+            Self::Ffmpeg { stdin, .. } => {
+                let Some(stdin) = stdin else {
+                    return Ok(());
+                };
+                stdin.write_all(pixels).map_err(FrameExportError::Ffmpeg)
+            }
+        }
+    }
+
+    /// Ends the output, waiting for `ffmpeg` to finish encoding the video.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FrameExportError::FfmpegStatus` if `ffmpeg` failed.
+    pub fn finish(self) -> Result<(), FrameExportError> {
+        match self {
+            Self::Images(_) => Ok(()),
+            Self::Ffmpeg { mut child, stdin } => {
+                drop(stdin);
+                let status = child.wait().map_err(FrameExportError::Ffmpeg)?;
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(FrameExportError::FfmpegStatus(status))
+                }
+            }
+        }
+    }
+}
+
+/// Renders the frames of `export` headlessly and writes them to its output, returning the
+/// number of frames written.
+///
+/// Blocks until the last frame is written and, for videos, encoded.
+///
+/// # Errors
+///
+/// Returns a `FrameExportError` if the path is empty, if no adapter can render the frames, or
+/// if the output cannot be written.
+pub fn export_frames(export: &FrameExport) -> Result<u32, FrameExportError> {
+    if export.path.keyframes.is_empty() {
+        return Err(FrameExportError::EmptyPath);
+    }
+    let (width, height) = export.size;
+    if width == 0 || height == 0 {
+        return Err(FrameExportError::EmptySize);
+    }
+
+    let mut renderer = pollster::block_on(Renderer::new_headless(width, height))?;
+    renderer.set_determinism(Some(export.seed));
+    renderer.set_projection(Projection::default());
+    let mut sink = FrameSink::open(export)?;
+
+    let step = crate::Duration::from_secs_f64(1.0 / f64::from(export.frame_rate));
+    let mut camera = Camera::default();
+    let frames = export.frame_count();
+    let mut ticks = 0;
+    for index in 0..frames {
+        let time = index as f32 / export.frame_rate as f32;
+        if let Some(pose) = export.path.sample(time) {
+            camera.look_from(pose.eye, pose.forward, pose.focus_distance);
+        }
+        renderer.set_camera(camera.view(), camera.eye());
+        // The ticks are counted from the start rather than accumulated per frame, so they
+        // never fall behind, whatever the frame rate.
+        let elapsed_ticks = f64::from(index) / f64::from(export.frame_rate) / TICK.as_secs_f64();
+        let frame_ticks = elapsed_ticks as u64 - ticks;
+        ticks += frame_ticks;
+        renderer.tick_scene(frame_ticks as u32, elapsed_ticks.fract() as f32);
+        let elapsed = if index == 0 {
+            crate::Duration::ZERO
+        } else {
+            step
+        };
+        let pixels = renderer.render_to_buffer_after(elapsed)?;
+        sink.write(index, width, height, &pixels)?;
+    }
+    sink.finish()?;
+    Ok(frames)
+}
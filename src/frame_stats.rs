@@ -0,0 +1,170 @@
+//! # Frame Statistics Module
+//!
+//! The `frame_stats` module keeps the recent frame times of the renderer and summarizes them
+//! for the frame statistics overlay, toggled with F1.
+//!
+//! ## Overview
+//!
+//! [`FrameStats`] is fed by `Renderer::render_frame` with two times per frame:
+//!
+//! - **Frame time**: The time since the previous frame, from which the frame rate follows.
+//!   Its minimum, maximum, and percentiles show stutter that the mean hides.
+//! - **CPU time**: How long `render_frame` took to record and submit the frame.
+//!
+//! - **Input latency**: How long the oldest input handled by a frame waited until the frame was
+//!   presented, recorded by the `App` with [`FrameStats::record_input_latency`] for the frames
+//!   that handled input.
+//!
+//! Where the adapter supports timestamp queries, the GPU time of the frame and of each
+//! [`TimedPass`] measured by the `GpuTimer` is added as well. Those measurements lag by a
+//! few frames, and the latest one is shown instead of an average.
+//!
+//! [`FrameStats::summary`] condenses all of this into a [`FrameStatsSummary`], which the `App`
+//! copies into the `UiState` every frame.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut stats = FrameStats::default();
+//!
+//! // Once per frame:
+//! stats.record(delta_time, cpu_start.elapsed());
+//! let summary = stats.summary();
+//! println!("{:.0} FPS, 99th percentile {:?}", summary.fps, summary.p99);
+//! ```
+
+// Importing the frame time history, which keeps the latest frame times and their percentiles.
+use crate::quality::FrameTimeHistory;
+
+// Importing the timed passes, whose GPU times are part of the statistics.
+use crate::gpu_timer::TimedPass;
+
+/// The recent frame times of the renderer.
+#[derive(Debug)]
+pub struct FrameStats {
+    /// The times between the latest frames.
+    frame_times: FrameTimeHistory,
+
+    /// The CPU times of the latest frames.
+    cpu_times: FrameTimeHistory,
+
+    /// The input latencies of the latest frames that handled input.
+    input_latencies: FrameTimeHistory,
+
+    /// The GPU time of the most recently measured frame.
+    gpu_time: Option<crate::Duration>,
+
+    /// The GPU time of each pass in the most recently measured frame.
+    gpu_passes: Vec<(TimedPass, crate::Duration)>,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self {
+            frame_times: FrameTimeHistory::new(Self::WINDOW),
+            cpu_times: FrameTimeHistory::new(Self::WINDOW),
+            input_latencies: FrameTimeHistory::new(Self::WINDOW),
+            gpu_time: None,
+            gpu_passes: Vec::new(),
+        }
+    }
+}
+
+impl FrameStats {
+    /// The number of frames the statistics are computed over.
+    pub const WINDOW: usize = 240;
+
+    /// Adds a frame that came `frame_time` after the previous one and took `cpu_time` to
+    /// record and submit.
+    pub fn record(&mut self, frame_time: crate::Duration, cpu_time: crate::Duration) {
+        self.frame_times.push(frame_time);
+        self.cpu_times.push(cpu_time);
+    }
+
+    /// Adds the time from an input event until the frame showing it was presented.
+    pub fn record_input_latency(&mut self, latency: crate::Duration) {
+        self.input_latencies.push(latency);
+    }
+
+    /// Replaces the GPU times with the latest measurement of the `GpuTimer`.
+    pub fn set_gpu_times(
+        &mut self,
+        frame: Option<crate::Duration>,
+        passes: &[(TimedPass, crate::Duration)],
+    ) {
+        self.gpu_time = frame;
+        self.gpu_passes.clear();
+        self.gpu_passes.extend_from_slice(passes);
+    }
+
+    /// Summarizes the recorded frames.
+    pub fn summary(&self) -> FrameStatsSummary {
+        let Some(mean) = self.frame_times.mean() else {
+            return FrameStatsSummary::default();
+        };
+        let percentile = |fraction| self.frame_times.percentile(fraction).unwrap_or_default();
+        FrameStatsSummary {
+            frames: self.frame_times.len(),
+            fps: 1.0 / mean.as_secs_f32().max(f32::EPSILON),
+            mean,
+            min: percentile(0.0),
+            max: percentile(1.0),
+            p50: percentile(0.5),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            cpu: self.cpu_times.mean().unwrap_or_default(),
+            latest_cpu: self.cpu_times.latest().unwrap_or_default(),
+            input_latency: self.input_latencies.mean(),
+            gpu: self.gpu_time,
+            gpu_passes: self
+                .gpu_passes
+                .iter()
+                .map(|&(pass, time)| (pass.label(), time))
+                .collect(),
+        }
+    }
+}
+
+/// The frame rate and frame time statistics over the latest `FrameStats::WINDOW` frames.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FrameStatsSummary {
+    /// The number of frames the statistics cover, `0` before the first frame.
+    pub frames: usize,
+
+    /// The mean frame rate, in frames per second.
+    pub fps: f32,
+
+    /// The mean time between frames.
+    pub mean: crate::Duration,
+
+    /// The shortest time between frames.
+    pub min: crate::Duration,
+
+    /// The longest time between frames.
+    pub max: crate::Duration,
+
+    /// The median time between frames.
+    pub p50: crate::Duration,
+
+    /// The time between frames that 95% of the frames took at most.
+    pub p95: crate::Duration,
+
+    /// The time between frames that 99% of the frames took at most.
+    pub p99: crate::Duration,
+
+    /// The mean CPU time of recording and submitting a frame.
+    pub cpu: crate::Duration,
+
+    /// The CPU time of recording and submitting the latest frame.
+    pub latest_cpu: crate::Duration,
+
+    /// The mean time from an input event until the frame showing it was presented, or `None`
+    /// before any input was handled.
+    pub input_latency: Option<crate::Duration>,
+
+    /// The GPU time of the most recently measured frame, or `None` without timestamp queries.
+    pub gpu: Option<crate::Duration>,
+
+    /// The name and GPU time of each pass of the most recently measured frame.
+    pub gpu_passes: Vec<(&'static str, crate::Duration)>,
+}
@@ -0,0 +1,153 @@
+//! # Frustum Module
+//!
+//! The `frustum` module tests bounding volumes against the camera's view frustum, so that the
+//! scene skips the draws of meshes that are entirely off-screen.
+//!
+//! ## Overview
+//!
+//! - [`Frustum`] holds the six clip planes of a view-projection matrix, extracted from the
+//!   matrix's rows every frame (the Gribb-Hartmann method, for `wgpu`'s depth range of 0 to 1).
+//!   Their normals point into the frustum.
+//! - [`Frustum::intersects_sphere`] is the cheap test: a sphere is outside once its center is
+//!   more than its radius behind any plane.
+//! - [`Frustum::intersects_bounds`] is the tighter test of an axis-aligned box: it is outside
+//!   once its corner furthest along a plane's normal is behind that plane.
+//! - [`CullStats`] counts the meshes drawn and culled in a frame, shown in the draw statistics.
+//!
+//! Both tests are conservative: a volume near a corner of the frustum can be outside while
+//! being in front of every plane, and is then drawn anyway.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let frustum = Frustum::from_view_projection(&view_projection);
+//! let bounds = mesh.bounds.transformed(&world);
+//! if frustum.intersects_sphere(&bounds.center(), bounds.radius())
+//!     && frustum.intersects_bounds(&bounds)
+//! {
+//!     draw(mesh);
+//! }
+//! ```
+
+use crate::camera::Bounds;
+
+/// The six planes bounding what a view-projection matrix keeps on screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    /// The left, right, bottom, top, near, and far planes, each as the normal `[a, b, c]` and
+    /// the offset `d` of the points `p` with `a * p.x + b * p.y + c * p.z + d = 0`. Normals
+    /// are of unit length and point into the frustum.
+    planes: [[f32; 4]; 6],
+}
+
+impl Frustum {
+    /// Extracts the clip planes of `view_projection`, mapping depth to the range 0 to 1.
+    pub fn from_view_projection(view_projection: &crate::math::Mat4) -> Self {
+        let columns = crate::math::to_cols_array(view_projection);
+        let row =
+            |index: usize| std::array::from_fn::<f32, 4, _>(|column| columns[column * 4 + index]);
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+        let add = |a: [f32; 4], b: [f32; 4]| std::array::from_fn(|i| a[i] + b[i]);
+        let sub = |a: [f32; 4], b: [f32; 4]| std::array::from_fn(|i| a[i] - b[i]);
+        let planes = [add(w, x), sub(w, x), add(w, y), sub(w, y), z, sub(w, z)].map(|plane| {
+            let length = (plane[0] * plane[0] + plane[1] * plane[1] + plane[2] * plane[2]).sqrt();
+            if length > f32::EPSILON {
+                plane.map(|value| value / length)
+            } else {
+                plane
+            }
+        });
+        Self { planes }
+    }
+
+    /// Returns `true` unless the sphere around `center` with `radius` is entirely outside.
+    pub fn intersects_sphere(&self, center: &crate::math::Vec3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| Self::distance(plane, center) >= -radius)
+    }
+
+    /// Returns `true` unless `bounds` is entirely outside.
+    pub fn intersects_bounds(&self, bounds: &Bounds) -> bool {
+        self.planes.iter().all(|plane| {
+            let furthest = |axis: usize| {
+                if plane[axis] >= 0.0 {
+                    bounds.max[axis]
+                } else {
+                    bounds.min[axis]
+                }
+            };
+            let corner = crate::math::vec3(furthest(0), furthest(1), furthest(2));
+            Self::distance(plane, &corner) >= 0.0
+        })
+    }
+
+    /// Returns the signed distance of `point` from `plane`, positive inside the frustum.
+    fn distance(plane: &[f32; 4], point: &crate::math::Vec3) -> f32 {
+        plane[0] * point.x + plane[1] * point.y + plane[2] * point.z + plane[3]
+    }
+}
+
+/// The number of meshes the scene drew and culled in a frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CullStats {
+    /// The meshes at least partly inside the view frustum, which were drawn.
+    pub drawn: u32,
+
+    /// The meshes entirely outside the view frustum, whose draws were skipped.
+    pub culled: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::vec3;
+
+    /// Returns the frustum of a camera at `(0, 0, -5)` looking at the origin with a vertical
+    /// field of view of 90 degrees, so it is 5 units wide to each side at the origin.
+    fn frustum() -> Frustum {
+        let view = crate::math::look_at_lh(
+            &vec3(0.0, 0.0, -5.0),
+            &crate::math::zeros(),
+            &crate::math::up(),
+        );
+        let projection =
+            crate::math::perspective_lh_zo(1.0, std::f32::consts::FRAC_PI_2, 0.1, 100.0);
+        Frustum::from_view_projection(&(projection * view))
+    }
+
+    #[test]
+    fn spheres_inside_or_touching_the_frustum_intersect() {
+        let frustum = frustum();
+
+        assert!(frustum.intersects_sphere(&crate::math::zeros(), 1.0));
+        assert!(frustum.intersects_sphere(&vec3(5.5, 0.0, 0.0), 1.0));
+        assert!(frustum.intersects_sphere(&vec3(0.0, 0.0, 95.0), 1.0));
+    }
+
+    #[test]
+    fn spheres_outside_any_plane_are_culled() {
+        let frustum = frustum();
+
+        assert!(!frustum.intersects_sphere(&vec3(8.0, 0.0, 0.0), 1.0));
+        assert!(!frustum.intersects_sphere(&vec3(0.0, -8.0, 0.0), 1.0));
+        assert!(!frustum.intersects_sphere(&vec3(0.0, 0.0, -10.0), 1.0));
+        assert!(!frustum.intersects_sphere(&vec3(0.0, 0.0, 110.0), 1.0));
+    }
+
+    #[test]
+    fn bounds_are_culled_only_when_entirely_outside() {
+        let frustum = frustum();
+        let cube = |center: crate::math::Vec3| Bounds {
+            min: center - vec3(1.0, 1.0, 1.0),
+            max: center + vec3(1.0, 1.0, 1.0),
+        };
+
+        assert!(frustum.intersects_bounds(&cube(crate::math::zeros())));
+        assert!(frustum.intersects_bounds(&cube(vec3(-5.5, 0.0, 0.0))));
+        assert!(frustum.intersects_bounds(&cube(vec3(0.0, 0.0, -5.0))));
+        assert!(!frustum.intersects_bounds(&cube(vec3(-8.0, 0.0, 0.0))));
+        assert!(!frustum.intersects_bounds(&cube(vec3(0.0, 8.0, 0.0))));
+        assert!(!frustum.intersects_bounds(&cube(vec3(0.0, 0.0, -8.0))));
+    }
+}
@@ -0,0 +1,215 @@
+//! # Fullscreen Module
+//!
+//! The `fullscreen` module switches the window between windowed and fullscreen, either as a
+//! borderless window covering its monitor or in an exclusive video mode of the monitor.
+//!
+//! ## Overview
+//!
+//! - [`FullscreenMode`] chooses between borderless fullscreen, which keeps the monitor's
+//!   desktop video mode and switches instantly, and exclusive fullscreen, which changes the
+//!   monitor's resolution and refresh rate to one of its [`VideoMode`]s.
+//! - [`FullscreenControls`] holds the choices of the "Fullscreen" settings section: whether
+//!   the window is fullscreen, the mode, and the video mode among those of the window's
+//!   monitor. `F11` and `Alt+Enter` toggle fullscreen as well.
+//! - [`FullscreenController`] applies the controls to the window whenever they change. An
+//!   exclusive video mode that is no longer offered, or a platform without exclusive
+//!   fullscreen (the web and Wayland), falls back to borderless fullscreen.
+//!
+//! Entering or leaving fullscreen resizes the window, which the `App` handles like any other
+//! resize: the surface is reconfigured with the new size before the next frame acquires a
+//! texture, and a surface outdated by the video mode switch is configured again.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! controls.enabled = !controls.enabled;
+//! if controller.update(&mut controls, &window) {
+//!     // The window is resized once the platform has switched.
+//! }
+//! ```
+
+use winit::monitor::{MonitorHandle, VideoModeHandle};
+use winit::window::{Fullscreen, Window};
+
+/// How the window covers its monitor in fullscreen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FullscreenMode {
+    /// A borderless window as large as the monitor, which keeps its desktop video mode.
+    #[default]
+    Borderless,
+
+    /// The window owns the monitor in the selected video mode, which may lower the latency of
+    /// presenting frames on some platforms.
+    Exclusive,
+}
+
+impl FullscreenMode {
+    /// All modes, in the order they are listed in the GUI.
+    pub const ALL: [FullscreenMode; 2] = [FullscreenMode::Borderless, FullscreenMode::Exclusive];
+
+    /// Returns the name shown in the GUI.
+    pub fn label(self) -> &'static str {
+        match self {
+            FullscreenMode::Borderless => "Borderless",
+            FullscreenMode::Exclusive => "Exclusive",
+        }
+    }
+}
+
+/// A resolution, color depth, and refresh rate a monitor can be driven at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VideoMode {
+    /// The width and height in physical pixels.
+    pub size: (u32, u32),
+
+    /// The bits per pixel.
+    pub bit_depth: u16,
+
+    /// The refresh rate, in millihertz.
+    pub refresh_rate_millihertz: u32,
+}
+
+impl VideoMode {
+    /// Returns the description of `handle`.
+    pub fn of(handle: &VideoModeHandle) -> Self {
+        let size = handle.size();
+        Self {
+            size: (size.width, size.height),
+            bit_depth: handle.bit_depth(),
+            refresh_rate_millihertz: handle.refresh_rate_millihertz(),
+        }
+    }
+
+    /// Returns the name shown in the GUI, such as "1920x1080 @ 59.94 Hz".
+    pub fn label(&self) -> String {
+        let (width, height) = self.size;
+        let hertz = self.refresh_rate_millihertz as f32 / 1000.0;
+        if self.refresh_rate_millihertz % 1000 == 0 {
+            format!("{width}x{height} @ {hertz:.0} Hz")
+        } else {
+            format!("{width}x{height} @ {hertz:.2} Hz")
+        }
+    }
+}
+
+/// Returns the video modes of `monitor`, from the largest and fastest to the smallest and
+/// slowest, the deepest color first among otherwise equal modes.
+pub fn video_modes(monitor: &MonitorHandle) -> Vec<VideoModeHandle> {
+    let mut modes: Vec<_> = monitor.video_modes().collect();
+    modes.sort_by_key(|mode| {
+        let mode = VideoMode::of(mode);
+        let (width, height) = mode.size;
+        std::cmp::Reverse((
+            u64::from(width) * u64::from(height),
+            mode.refresh_rate_millihertz,
+            mode.bit_depth,
+        ))
+    });
+    modes
+}
+
+/// The fullscreen choices of the settings window.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FullscreenControls {
+    /// Whether the window is fullscreen, toggled with the checkbox, `F11`, or `Alt+Enter`.
+    pub enabled: bool,
+
+    /// How the window covers its monitor while fullscreen.
+    pub mode: FullscreenMode,
+
+    /// The video mode of exclusive fullscreen, or `None` for the largest and fastest one.
+    pub video_mode: Option<VideoMode>,
+
+    /// The video modes of the window's monitor, set by the `App` when the window is created
+    /// or moved.
+    pub video_modes: Vec<VideoMode>,
+
+    /// Whether the platform supports exclusive fullscreen, set by the `App`.
+    pub exclusive_supported: bool,
+}
+
+impl FullscreenControls {
+    /// Lists the video modes of the monitor `window` is on, and forgets the selected mode if
+    /// that monitor does not offer it.
+    pub fn refresh_video_modes(&mut self, window: &Window) {
+        self.video_modes = window
+            .current_monitor()
+            .map(|monitor| video_modes(&monitor).iter().map(VideoMode::of).collect())
+            .unwrap_or_default();
+        if self
+            .video_mode
+            .is_some_and(|mode| !self.video_modes.contains(&mode))
+        {
+            self.video_mode = None;
+        }
+    }
+}
+
+/// Applies the [`FullscreenControls`] to the window whenever they change.
+#[derive(Debug, Default)]
+pub struct FullscreenController {
+    /// The mode and video mode applied last, or `None` while windowed.
+    applied: Option<(FullscreenMode, Option<VideoMode>)>,
+}
+
+impl FullscreenController {
+    /// Makes `window` fullscreen as `controls` say, if that changed since the last call, and
+    /// returns whether it did.
+    ///
+    /// Exclusive fullscreen falls back to borderless, and `controls.mode` is set accordingly,
+    /// if the platform does not support it or the monitor no longer offers the video mode.
+    pub fn update(&mut self, controls: &mut FullscreenControls, window: &Window) -> bool {
+        let wanted = controls
+            .enabled
+            .then_some((controls.mode, controls.video_mode));
+        if wanted == self.applied {
+            return false;
+        }
+
+        let fullscreen = wanted.map(|(mode, video_mode)| {
+            let exclusive = (mode == FullscreenMode::Exclusive && controls.exclusive_supported)
+                .then(|| Self::exclusive(window, video_mode))
+                .flatten();
+            match exclusive {
+                Some(handle) => Fullscreen::Exclusive(handle),
+                None => {
+                    if mode == FullscreenMode::Exclusive {
+                        log::warn!("Exclusive fullscreen is unavailable; using borderless");
+                        controls.mode = FullscreenMode::Borderless;
+                    }
+                    Fullscreen::Borderless(window.current_monitor())
+                }
+            }
+        });
+        self.applied = controls
+            .enabled
+            .then_some((controls.mode, controls.video_mode));
+        window.set_fullscreen(fullscreen);
+        true
+    }
+
+    /// Returns the handle of `video_mode` on the monitor `window` is on, or of its largest and
+    /// fastest mode if `video_mode` is `None`.
+    fn exclusive(window: &Window, video_mode: Option<VideoMode>) -> Option<VideoModeHandle> {
+        let monitor = window.current_monitor()?;
+        video_modes(&monitor)
+            .into_iter()
+            .find(|handle| video_mode.is_none_or(|video_mode| VideoMode::of(handle) == video_mode))
+    }
+}
+
+/// Returns whether exclusive fullscreen is supported on the platform `event_loop` runs on. It
+/// is not on the web, where pages can only request borderless fullscreen, nor on Wayland.
+pub fn exclusive_supported(event_loop: &winit::event_loop::ActiveEventLoop) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        use winit::platform::wayland::ActiveEventLoopExtWayland;
+        !event_loop.is_wayland()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = event_loop;
+        cfg!(not(target_arch = "wasm32"))
+    }
+}
@@ -0,0 +1,102 @@
+//! # Gallery Module
+//!
+//! The `gallery` module holds the state of the Widget Gallery window, which shows a wide set of
+//! `egui` widgets, each bound to live renderer state. It serves as living documentation of how
+//! GUI controls reach GPU-side data in this architecture:
+//!
+//! 1. A widget edits a field of `UiState`, such as the environment's ambient color.
+//! 2. The `App` hands the field to the renderer every frame, for example with
+//!    `Renderer::set_environment`.
+//! 3. The renderer uploads it to a uniform buffer, or uses it to build the frame.
+//!
+//! Values flowing the other way, such as the draw statistics and the material preview texture,
+//! are copied into `UiState` by the `App` every frame and only displayed.
+//!
+//! ## Overview
+//!
+//! - [`WidgetGallery`] holds whether the window is shown and the recent frame times it plots.
+//! - [`plot_axis`] picks the top of the plot's vertical axis, a round value above the samples
+//!   and the target frame time, and the spacing of its grid lines.
+//!
+//! The window itself is built by `ui::show_widget_gallery`. `egui` no longer ships plots and
+//! tables, and `egui_plot` and `egui_extras` are not dependencies of this crate, so the plot is
+//! painted with the `egui::Painter`, and the tables are `egui::Grid`s.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! // Every frame, in the `App`:
+//! ui_state.gallery.record_frame_time(delta_time);
+//!
+//! // While building the GUI:
+//! ui::show_widget_gallery(&egui_context, &mut ui_state);
+//! ```
+
+// Importing `VecDeque` to drop the oldest frame time as a new one arrives.
+use std::collections::VecDeque;
+
+/// The state of the Widget Gallery window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WidgetGallery {
+    /// Whether the window is shown.
+    pub visible: bool,
+
+    /// Whether the plot stops recording, to inspect a spike.
+    pub paused: bool,
+
+    /// The latest frame times, in milliseconds, oldest first.
+    frame_times: VecDeque<f32>,
+}
+
+impl Default for WidgetGallery {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            paused: false,
+            frame_times: VecDeque::with_capacity(Self::PLOT_LENGTH),
+        }
+    }
+}
+
+impl WidgetGallery {
+    /// The number of frames the plot shows.
+    pub const PLOT_LENGTH: usize = 240;
+
+    /// Adds the time of the latest frame to the plot, unless it is paused.
+    pub fn record_frame_time(&mut self, frame_time: crate::Duration) {
+        if self.paused {
+            return;
+        }
+        if self.frame_times.len() == Self::PLOT_LENGTH {
+            self.frame_times.pop_front();
+        }
+        self.frame_times
+            .push_back(frame_time.as_secs_f32() * 1000.0);
+    }
+
+    /// Returns the plotted frame times, in milliseconds, oldest first.
+    pub fn frame_times(&self) -> impl ExactSizeIterator<Item = f32> + '_ {
+        self.frame_times.iter().copied()
+    }
+
+    /// Returns the mean and the largest plotted frame time, in milliseconds, or `None` if
+    /// nothing was recorded yet.
+    pub fn frame_time_summary(&self) -> Option<(f32, f32)> {
+        if self.frame_times.is_empty() {
+            return None;
+        }
+        let sum: f32 = self.frame_times.iter().sum();
+        let max = self.frame_times.iter().copied().fold(0.0, f32::max);
+        Some((sum / self.frame_times.len() as f32, max))
+    }
+}
+
+/// Returns the top of the frame time plot's vertical axis and the spacing of its grid lines,
+/// in milliseconds. The top is the smallest multiple of a round step above `max_sample` and
+/// `target`, so the target line is always visible and the axis does not jump with every frame.
+pub fn plot_axis(max_sample: f32, target: f32) -> (f32, f32) {
+    let max = max_sample.max(target).max(1.0);
+    // Aim for about four grid lines.
+    let step = crate::rulers::tick_step(max / 4.0, 1.0);
+    ((max / step).floor() * step + step, step)
+}
@@ -0,0 +1,554 @@
+//! # Gestures Module
+//!
+//! The `gestures` module turns raw multi-touch input into high-level gestures.
+//!
+//! ## Overview
+//!
+//! - [`GestureRecognizer`] tracks the touches reported by `winit` and produces [`Gesture`]s:
+//!   - **Tap**, **double tap**, and **long press** with a single finger that stays within
+//!     the tap slop.
+//!   - **Drag** when a single finger moves beyond the tap slop.
+//!   - **Pan**, **pinch**, and **rotate** with two fingers. Each is only recognized once its
+//!     accumulated change exceeds a threshold, so a pinch does not also rotate the view
+//!     slightly.
+//! - Continuous gestures carry their velocity, averaged over the recent touch events.
+//! - [`GestureSettings`] holds the thresholds, edited in the "Gestures" settings section.
+//!
+//! The `App` feeds touch events into the recognizer and applies the gestures every frame:
+//! drags look around, two-finger gestures pan, zoom, and turn the camera, a double tap resets
+//! it, a tap selects the node under it and probes its pixel, and a long press turns on the
+//! depth probe. The most recent gesture is shown in the settings window. Touches starting over
+//! the GUI are left to `egui`, which treats a single finger like the mouse, so its widgets can
+//! be tapped and dragged.
+//!
+//! On the web, the canvas disables the browser's own touch handling with `touch-action: none`
+//! in `index.html`, so mobile browsers report the touches instead of scrolling or zooming the
+//! page.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! recognizer.touch(touch.id, touch.phase, position, Instant::now());
+//! recognizer.update(Instant::now());
+//! for gesture in recognizer.drain() {
+//!     if let Gesture::Pinch { scale, .. } = gesture {
+//!         camera_input.pinch += scale.ln();
+//!     }
+//! }
+//! ```
+
+use web_time::Instant;
+
+/// The thresholds the [`GestureRecognizer`] recognizes gestures with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureSettings {
+    /// How far a finger may move and still tap or long-press, in pixels.
+    pub tap_slop: f32,
+
+    /// The longest touch that counts as a tap, in seconds.
+    pub tap_duration: f32,
+
+    /// The longest time between two taps of a double tap, in seconds.
+    pub double_tap_interval: f32,
+
+    /// How long a finger must rest to long-press, in seconds.
+    pub long_press_duration: f32,
+
+    /// How far two fingers must move together to pan, in pixels.
+    pub pan_threshold: f32,
+
+    /// How much two fingers must spread or close to pinch, as a fraction of their distance.
+    pub pinch_threshold: f32,
+
+    /// How far two fingers must turn to rotate, in degrees.
+    pub rotate_threshold: f32,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            tap_slop: 10.0,
+            tap_duration: 0.3,
+            double_tap_interval: 0.3,
+            long_press_duration: 0.5,
+            pan_threshold: 10.0,
+            pinch_threshold: 0.05,
+            rotate_threshold: 10.0,
+        }
+    }
+}
+
+/// A high-level gesture. Positions and distances are in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// A short touch that barely moved.
+    Tap { position: crate::math::Vec2 },
+
+    /// A second tap shortly after and close to the first.
+    DoubleTap { position: crate::math::Vec2 },
+
+    /// A touch that rested in place.
+    LongPress { position: crate::math::Vec2 },
+
+    /// A single finger moving.
+    Drag {
+        delta: crate::math::Vec2,
+        velocity: crate::math::Vec2,
+    },
+
+    /// Two fingers moving together; `delta` is the movement of their center.
+    Pan {
+        delta: crate::math::Vec2,
+        velocity: crate::math::Vec2,
+    },
+
+    /// Two fingers spreading (`scale > 1`) or closing (`scale < 1`) around `center`.
+    ///
+    /// `velocity` is the rate of change of the scale's logarithm, per second.
+    Pinch {
+        scale: f32,
+        center: crate::math::Vec2,
+        velocity: f32,
+    },
+
+    /// Two fingers turning by `angle` radians, clockwise on screen, at `velocity` radians
+    /// per second.
+    Rotate { angle: f32, velocity: f32 },
+}
+
+impl Gesture {
+    /// Returns the gesture's name, shown in the user interface.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Gesture::Tap { .. } => "Tap",
+            Gesture::DoubleTap { .. } => "Double Tap",
+            Gesture::LongPress { .. } => "Long Press",
+            Gesture::Drag { .. } => "Drag",
+            Gesture::Pan { .. } => "Pan",
+            Gesture::Pinch { .. } => "Pinch",
+            Gesture::Rotate { .. } => "Rotate",
+        }
+    }
+}
+
+/// A finger on the screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TouchPoint {
+    /// The `winit` touch id.
+    id: u64,
+
+    /// Where the touch started.
+    start: crate::math::Vec2,
+
+    /// Where the touch is now.
+    position: crate::math::Vec2,
+
+    /// When the touch started.
+    started: Instant,
+}
+
+/// The state of a two-finger gesture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TwoFingerState {
+    /// The accumulated movement of the fingers' center, in pixels.
+    pan: crate::math::Vec2,
+
+    /// The accumulated logarithm of the scale.
+    pinch: f32,
+
+    /// The accumulated angle, in radians.
+    rotate: f32,
+
+    /// Which of pan, pinch, and rotate have exceeded their thresholds.
+    recognized: [bool; 3],
+
+    /// The averaged velocities of pan, pinch, and rotate.
+    pan_velocity: crate::math::Vec2,
+    pinch_velocity: f32,
+    rotate_velocity: f32,
+}
+
+/// Recognizes [`Gesture`]s from touch events.
+#[derive(Debug, Clone)]
+pub struct GestureRecognizer {
+    /// The thresholds in use.
+    settings: GestureSettings,
+
+    /// The fingers on the screen, in the order they touched it. Two-finger gestures use the
+    /// first two.
+    touches: Vec<TouchPoint>,
+
+    /// Whether the single finger on the screen may still tap or long-press.
+    tap_candidate: bool,
+
+    /// The time and position of the last tap, while a double tap is possible.
+    last_tap: Option<(Instant, crate::math::Vec2)>,
+
+    /// The time of the last touch event, to compute velocities.
+    last_event: Option<Instant>,
+
+    /// The averaged velocity of the single-finger drag.
+    drag_velocity: crate::math::Vec2,
+
+    /// The state of the two-finger gesture, while two fingers are on the screen.
+    two_finger: Option<TwoFingerState>,
+
+    /// The gestures recognized since the last call to `drain`.
+    gestures: Vec<Gesture>,
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new(GestureSettings::default())
+    }
+}
+
+impl GestureRecognizer {
+    /// How much each new touch event contributes to the averaged velocities.
+    const VELOCITY_BLEND: f32 = 0.5;
+
+    /// Creates a recognizer using `settings`.
+    pub fn new(settings: GestureSettings) -> Self {
+        Self {
+            settings,
+            touches: Vec::new(),
+            tap_candidate: false,
+            last_tap: None,
+            last_event: None,
+            drag_velocity: crate::math::vec2(0.0, 0.0),
+            two_finger: None,
+            gestures: Vec::new(),
+        }
+    }
+
+    /// Replaces the thresholds, taking effect with the next touch event.
+    pub fn set_settings(&mut self, settings: GestureSettings) {
+        self.settings = settings;
+    }
+
+    /// Feeds a touch event at `position`, in physical pixels, that happened at `now`.
+    pub fn touch(
+        &mut self,
+        id: u64,
+        phase: winit::event::TouchPhase,
+        position: crate::math::Vec2,
+        now: Instant,
+    ) {
+        let delta_time = self
+            .last_event
+            .map_or(0.0, |last| now.duration_since(last).as_secs_f32());
+        self.last_event = Some(now);
+
+        match phase {
+            winit::event::TouchPhase::Started => self.started(id, position, now),
+            winit::event::TouchPhase::Moved => self.moved(id, position, delta_time),
+            winit::event::TouchPhase::Ended => self.ended(id, now, true),
+            winit::event::TouchPhase::Cancelled => self.ended(id, now, false),
+        }
+    }
+
+    /// Recognizes long presses, which happen without any touch event. Call this every frame.
+    pub fn update(&mut self, now: Instant) {
+        if let [touch] = self.touches[..] {
+            let held = now.duration_since(touch.started).as_secs_f32();
+            if self.tap_candidate && held >= self.settings.long_press_duration {
+                self.tap_candidate = false;
+                self.gestures.push(Gesture::LongPress {
+                    position: touch.position,
+                });
+            }
+        }
+    }
+
+    /// Returns and clears the gestures recognized since the last call.
+    pub fn drain(&mut self) -> Vec<Gesture> {
+        std::mem::take(&mut self.gestures)
+    }
+
+    fn started(&mut self, id: u64, position: crate::math::Vec2, now: Instant) {
+        self.touches.push(TouchPoint {
+            id,
+            start: position,
+            position,
+            started: now,
+        });
+        match self.touches.len() {
+            1 => {
+                self.tap_candidate = true;
+                self.drag_velocity = crate::math::vec2(0.0, 0.0);
+            }
+            2 => {
+                self.tap_candidate = false;
+                self.two_finger = Some(TwoFingerState {
+                    pan: crate::math::vec2(0.0, 0.0),
+                    pinch: 0.0,
+                    rotate: 0.0,
+                    recognized: [false; 3],
+                    pan_velocity: crate::math::vec2(0.0, 0.0),
+                    pinch_velocity: 0.0,
+                    rotate_velocity: 0.0,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn moved(&mut self, id: u64, position: crate::math::Vec2, delta_time: f32) {
+        let Some(index) = self.touches.iter().position(|touch| touch.id == id) else {
+            return;
+        };
+        let before = self.touches.clone();
+        self.touches[index].position = position;
+
+        if self.touches.len() == 1 {
+            let touch = self.touches[0];
+            if self.tap_candidate {
+                if crate::math::distance(&touch.start, &touch.position) <= self.settings.tap_slop {
+                    return;
+                }
+                self.tap_candidate = false;
+            }
+            let delta = position - before[0].position;
+            self.drag_velocity = blend_velocity(self.drag_velocity, delta, delta_time);
+            self.gestures.push(Gesture::Drag {
+                delta,
+                velocity: self.drag_velocity,
+            });
+        } else if index < 2 {
+            self.moved_two_fingers([before[0], before[1]], delta_time);
+        }
+    }
+
+    /// Updates the two-finger gesture after one of the first two fingers moved from
+    /// `before`.
+    fn moved_two_fingers(&mut self, before: [TouchPoint; 2], delta_time: f32) {
+        let Some(state) = self.two_finger.as_mut() else {
+            return;
+        };
+        let (old_a, old_b) = (before[0].position, before[1].position);
+        let (new_a, new_b) = (self.touches[0].position, self.touches[1].position);
+
+        let old_center = (old_a + old_b) * 0.5;
+        let center = (new_a + new_b) * 0.5;
+        let (old_span, span) = (old_b - old_a, new_b - new_a);
+        if crate::math::length(&old_span) < f32::EPSILON
+            || crate::math::length(&span) < f32::EPSILON
+        {
+            return;
+        }
+
+        let pan = center - old_center;
+        let pinch = (crate::math::length(&span) / crate::math::length(&old_span)).ln();
+        // Positive angles turn clockwise on screen, since pixel rows count downwards.
+        let rotate =
+            (old_span.x * span.y - old_span.y * span.x).atan2(crate::math::dot(&old_span, &span));
+
+        state.pan += pan;
+        state.pinch += pinch;
+        state.rotate += rotate;
+        state.pan_velocity = blend_velocity(state.pan_velocity, pan, delta_time);
+        state.pinch_velocity = blend_scalar_velocity(state.pinch_velocity, pinch, delta_time);
+        state.rotate_velocity = blend_scalar_velocity(state.rotate_velocity, rotate, delta_time);
+
+        let settings = &self.settings;
+        state.recognized[0] |= crate::math::length(&state.pan) > settings.pan_threshold;
+        state.recognized[1] |= state.pinch.abs() > (1.0 + settings.pinch_threshold).ln();
+        state.recognized[2] |= state.rotate.abs() > settings.rotate_threshold.to_radians();
+
+        if state.recognized[0] {
+            self.gestures.push(Gesture::Pan {
+                delta: pan,
+                velocity: state.pan_velocity,
+            });
+        }
+        if state.recognized[1] {
+            self.gestures.push(Gesture::Pinch {
+                scale: pinch.exp(),
+                center,
+                velocity: state.pinch_velocity,
+            });
+        }
+        if state.recognized[2] {
+            self.gestures.push(Gesture::Rotate {
+                angle: rotate,
+                velocity: state.rotate_velocity,
+            });
+        }
+    }
+
+    fn ended(&mut self, id: u64, now: Instant, completed: bool) {
+        let Some(index) = self.touches.iter().position(|touch| touch.id == id) else {
+            return;
+        };
+        let touch = self.touches.remove(index);
+
+        let held = now.duration_since(touch.started).as_secs_f32();
+        if completed && self.tap_candidate && held <= self.settings.tap_duration {
+            let double_tap = self.last_tap.is_some_and(|(time, position)| {
+                now.duration_since(time).as_secs_f32() <= self.settings.double_tap_interval
+                    && crate::math::distance(&position, &touch.position)
+                        <= self.settings.tap_slop * 2.0
+            });
+            if double_tap {
+                self.last_tap = None;
+                self.gestures.push(Gesture::DoubleTap {
+                    position: touch.position,
+                });
+            } else {
+                self.last_tap = Some((now, touch.position));
+                self.gestures.push(Gesture::Tap {
+                    position: touch.position,
+                });
+            }
+        }
+
+        // A finger left behind by a two-finger gesture neither taps nor drags from where the
+        // gesture ended.
+        self.tap_candidate = false;
+        if self.touches.len() < 2 {
+            self.two_finger = None;
+        }
+    }
+}
+
+/// Blends the velocity of a movement by `delta` over `delta_time` seconds into `velocity`.
+fn blend_velocity(
+    velocity: crate::math::Vec2,
+    delta: crate::math::Vec2,
+    delta_time: f32,
+) -> crate::math::Vec2 {
+    if delta_time <= 0.0 {
+        return velocity;
+    }
+    crate::math::lerp(
+        &velocity,
+        &(delta / delta_time),
+        GestureRecognizer::VELOCITY_BLEND,
+    )
+}
+
+/// Blends the velocity of a change by `delta` over `delta_time` seconds into `velocity`.
+fn blend_scalar_velocity(velocity: f32, delta: f32, delta_time: f32) -> f32 {
+    if delta_time <= 0.0 {
+        return velocity;
+    }
+    velocity + (delta / delta_time - velocity) * GestureRecognizer::VELOCITY_BLEND
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::vec2;
+    use winit::event::TouchPhase;
+
+    /// Returns the time `seconds` after `start`.
+    fn at(start: Instant, seconds: f32) -> Instant {
+        start + std::time::Duration::from_secs_f32(seconds)
+    }
+
+    /// Touches down and up at `position`, `seconds` after `start`, holding for 0.1 seconds.
+    fn tap(recognizer: &mut GestureRecognizer, start: Instant, seconds: f32, position: [f32; 2]) {
+        let position = vec2(position[0], position[1]);
+        recognizer.touch(0, TouchPhase::Started, position, at(start, seconds));
+        recognizer.touch(0, TouchPhase::Ended, position, at(start, seconds + 0.1));
+    }
+
+    #[test]
+    fn two_quick_taps_make_a_double_tap() {
+        let start = Instant::now();
+        let mut recognizer = GestureRecognizer::default();
+
+        tap(&mut recognizer, start, 0.0, [100.0, 100.0]);
+        tap(&mut recognizer, start, 0.2, [104.0, 100.0]);
+        tap(&mut recognizer, start, 1.0, [104.0, 100.0]);
+
+        assert_eq!(
+            recognizer.drain(),
+            [
+                Gesture::Tap {
+                    position: vec2(100.0, 100.0)
+                },
+                Gesture::DoubleTap {
+                    position: vec2(104.0, 100.0)
+                },
+                Gesture::Tap {
+                    position: vec2(104.0, 100.0)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn resting_finger_long_presses_instead_of_tapping() {
+        let start = Instant::now();
+        let mut recognizer = GestureRecognizer::default();
+        let position = vec2(50.0, 50.0);
+
+        recognizer.touch(0, TouchPhase::Started, position, start);
+        recognizer.update(at(start, 0.2));
+        assert!(recognizer.drain().is_empty());
+
+        recognizer.update(at(start, 0.6));
+        recognizer.touch(0, TouchPhase::Ended, position, at(start, 0.7));
+        assert_eq!(recognizer.drain(), [Gesture::LongPress { position }]);
+    }
+
+    #[test]
+    fn moving_beyond_the_slop_drags_instead_of_tapping() {
+        let start = Instant::now();
+        let mut recognizer = GestureRecognizer::default();
+
+        recognizer.touch(0, TouchPhase::Started, vec2(0.0, 0.0), start);
+        recognizer.touch(0, TouchPhase::Moved, vec2(5.0, 0.0), at(start, 0.05));
+        assert!(recognizer.drain().is_empty());
+
+        recognizer.touch(0, TouchPhase::Moved, vec2(20.0, 0.0), at(start, 0.1));
+        recognizer.touch(0, TouchPhase::Ended, vec2(20.0, 0.0), at(start, 0.15));
+        let gestures = recognizer.drain();
+        assert!(
+            matches!(gestures[..], [Gesture::Drag { delta, .. }] if delta == vec2(15.0, 0.0)),
+            "{gestures:?}"
+        );
+    }
+
+    #[test]
+    fn spreading_fingers_pinch_without_panning_or_rotating() {
+        let start = Instant::now();
+        let mut recognizer = GestureRecognizer::default();
+
+        recognizer.touch(0, TouchPhase::Started, vec2(100.0, 100.0), start);
+        recognizer.touch(1, TouchPhase::Started, vec2(200.0, 100.0), start);
+        recognizer.touch(0, TouchPhase::Moved, vec2(90.0, 100.0), at(start, 0.05));
+        recognizer.touch(1, TouchPhase::Moved, vec2(210.0, 100.0), at(start, 0.1));
+
+        let gestures = recognizer.drain();
+        assert!(!gestures.is_empty());
+        assert!(gestures
+            .iter()
+            .all(|gesture| matches!(gesture, Gesture::Pinch { scale, .. } if *scale > 1.0)));
+    }
+
+    #[test]
+    fn turning_fingers_rotate_clockwise_on_screen() {
+        let start = Instant::now();
+        let mut recognizer = GestureRecognizer::default();
+
+        recognizer.touch(0, TouchPhase::Started, vec2(-50.0, 0.0), start);
+        recognizer.touch(1, TouchPhase::Started, vec2(50.0, 0.0), start);
+        // Turns the fingers about their center in steps of 5 degrees, one finger at a time,
+        // with rows counting downwards.
+        for step in 1..=6 {
+            let (sin, cos) = (5.0 * step as f32).to_radians().sin_cos();
+            let offset = vec2(50.0 * cos, 50.0 * sin);
+            let time = at(start, 0.1 * step as f32);
+            recognizer.touch(0, TouchPhase::Moved, -offset, time);
+            recognizer.touch(1, TouchPhase::Moved, offset, at(time, 0.05));
+        }
+
+        let gestures = recognizer.drain();
+        assert!(!gestures.is_empty());
+        assert!(gestures
+            .iter()
+            .all(|gesture| matches!(gesture, Gesture::Rotate { angle, .. } if *angle > 0.0)));
+    }
+}
@@ -0,0 +1,518 @@
+//! # Gizmo Module
+//!
+//! The `gizmo` module provides a transform gizmo: handles drawn at the selected scene node
+//! that translate, rotate, or scale it when dragged with the mouse.
+//!
+//! ## Overview
+//!
+//! - [`TransformGizmo`] draws the handles of its [`GizmoMode`] with the line renderer, on top
+//!   of the scene, picks the handle under the cursor, and turns mouse drags into a new local
+//!   transform for the node. The `App` uses it for the node selected by clicking.
+//! - Translate handles are arrows along the axes of the node's parent, so dragging one moves
+//!   the node along a single coordinate of its position. Rotate handles are rings around the
+//!   node's own axes, and scale handles are lines along them, capped with small squares.
+//! - [`GizmoSnapping`] rounds translations, angles, and scale factors to fixed steps while
+//!   enabled. It is edited in the Inspector panel.
+//! - The handles keep the same size on screen, whatever the distance to the camera.
+//!
+//! Drags are measured from where they started, so they do not drift as the mouse moves, and
+//! every move returns the transform for the whole drag so far, applied to the transform the
+//! node had when it started.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let target = GizmoTarget { local: node.local, parent_world };
+//! if gizmo.pointer_button(cursor, true, Some(&target), &view_projection, viewport) {
+//!     // The press grabbed a handle.
+//! }
+//! if let Some(local) = gizmo.pointer_moved(cursor, Some(&target), &view_projection, viewport) {
+//!     node.local = local;
+//! }
+//! gizmo.draw(lines, &target, &view_projection, viewport);
+//! ```
+
+use crate::lines::LineRenderer;
+use crate::math::{Mat4, Vec2, Vec3};
+use crate::probe::project;
+
+/// What the handles of a [`TransformGizmo`] do.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoMode {
+    /// Arrows moving the node along its parent's axes.
+    #[default]
+    Translate,
+
+    /// Rings rotating the node around its own axes.
+    Rotate,
+
+    /// Lines scaling the node along its own axes.
+    Scale,
+}
+
+impl GizmoMode {
+    /// All modes, in the order they are listed in the Inspector.
+    pub const ALL: [GizmoMode; 3] = [GizmoMode::Translate, GizmoMode::Rotate, GizmoMode::Scale];
+
+    /// Returns the mode's name as shown in the Inspector.
+    pub fn label(self) -> &'static str {
+        match self {
+            GizmoMode::Translate => "Translate",
+            GizmoMode::Rotate => "Rotate",
+            GizmoMode::Scale => "Scale",
+        }
+    }
+}
+
+/// The steps the gizmo's drags are rounded to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GizmoSnapping {
+    /// Whether drags are rounded to the steps below.
+    pub enabled: bool,
+
+    /// The step of translations, in the units of the node's parent.
+    pub translation: f32,
+
+    /// The step of rotations, in degrees.
+    pub rotation_degrees: f32,
+
+    /// The step of scale factors.
+    pub scale: f32,
+}
+
+/// Snapping starts disabled, with steps of a quarter unit, 15 degrees, and a tenth.
+impl Default for GizmoSnapping {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            translation: 0.25,
+            rotation_degrees: 15.0,
+            scale: 0.1,
+        }
+    }
+}
+
+impl GizmoSnapping {
+    /// Rounds `value` to a multiple of `step`, if snapping is enabled and `step` is positive.
+    fn snap(&self, value: f32, step: f32) -> f32 {
+        if self.enabled && step > 0.0 {
+            (value / step).round() * step
+        } else {
+            value
+        }
+    }
+}
+
+/// The node a [`TransformGizmo`] manipulates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GizmoTarget {
+    /// The node's transform relative to its parent, which the gizmo edits.
+    pub local: Mat4,
+
+    /// The transform from the parent's space to world space, or the scene graph's transform
+    /// for a root node.
+    pub parent_world: Mat4,
+}
+
+impl GizmoTarget {
+    /// Returns the node's position in world space.
+    fn origin(&self) -> Vec3 {
+        transform_point(&(self.parent_world * self.local), &crate::math::zeros())
+    }
+
+    /// Returns the world direction of the handle along `axis`: the parent's axis when
+    /// translating, and the node's own axis otherwise.
+    fn axis(&self, mode: GizmoMode, axis: usize) -> Vec3 {
+        let frame = match mode {
+            GizmoMode::Translate => self.parent_world,
+            GizmoMode::Rotate | GizmoMode::Scale => self.parent_world * self.local,
+        };
+        transform_vector(&frame, &unit(axis))
+    }
+}
+
+/// A drag of one of the gizmo's handles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GizmoDrag {
+    /// The index of the dragged axis: 0 for X, 1 for Y, and 2 for Z.
+    axis: usize,
+
+    /// The node when the drag started.
+    start: GizmoTarget,
+
+    /// The cursor when the drag started.
+    start_cursor: Vec2,
+
+    /// The angle of the cursor around the node's position on screen at the last move, for
+    /// rotations.
+    cursor_angle: f32,
+
+    /// The angle the cursor has turned around the node's position since the drag started, in
+    /// radians.
+    turned: f32,
+}
+
+/// Draws the handles of a transform gizmo and turns drags of them into transforms.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TransformGizmo {
+    /// What the handles do.
+    pub mode: GizmoMode,
+
+    /// The steps drags are rounded to.
+    pub snapping: GizmoSnapping,
+
+    /// The axis whose handle is under the cursor, highlighted.
+    hovered: Option<usize>,
+
+    /// The drag in progress, if any.
+    drag: Option<GizmoDrag>,
+}
+
+impl TransformGizmo {
+    /// The length of the handles on screen, in pixels.
+    pub const SIZE: f32 = 80.0;
+
+    /// How close the cursor must be to a handle to pick it, in pixels.
+    pub const PICK_RADIUS: f32 = 8.0;
+
+    /// The shortest fraction of [`TransformGizmo::SIZE`] a translate or scale handle is shown
+    /// at. Handles pointing nearly at the camera are hidden, as dragging them along their
+    /// tiny on-screen length would jump.
+    const MIN_FORESHORTENING: f32 = 0.15;
+
+    /// The smallest scale factor a drag produces, so nodes cannot collapse or flip.
+    const MIN_SCALE: f32 = 0.01;
+
+    /// The number of segments of the rotate handles' rings.
+    const RING_SEGMENTS: usize = 48;
+
+    /// The width of the handles, in pixels.
+    const WIDTH: f32 = 3.0;
+
+    /// The colors of the X, Y, and Z handles.
+    const AXIS_COLORS: [[f32; 4]; 3] = [
+        [0.9, 0.2, 0.2, 1.0],
+        [0.3, 0.85, 0.3, 1.0],
+        [0.25, 0.45, 1.0, 1.0],
+    ];
+
+    /// The color of the handle under the cursor or being dragged.
+    const ACTIVE_COLOR: [f32; 4] = [1.0, 0.9, 0.2, 1.0];
+
+    /// Returns the axis of the handle closest to `cursor` within
+    /// [`TransformGizmo::PICK_RADIUS`].
+    pub fn pick(
+        &self,
+        cursor: (u32, u32),
+        target: &GizmoTarget,
+        view_projection: &Mat4,
+        viewport: (u32, u32),
+    ) -> Option<usize> {
+        let cursor = crate::math::vec2(cursor.0 as f32, cursor.1 as f32);
+        let length = self.handle_length(target, view_projection, viewport)?;
+        (0..3)
+            .filter(|&axis| self.shows_handle(target, axis, length, view_projection, viewport))
+            .filter_map(|axis| {
+                let distance = self
+                    .handle_polyline(target, axis, length)
+                    .windows(2)
+                    .filter_map(|pair| {
+                        let start = project(view_projection, &pair[0], viewport)?;
+                        let end = project(view_projection, &pair[1], viewport)?;
+                        Some(segment_distance(
+                            &cursor,
+                            &crate::math::xy(&start),
+                            &crate::math::xy(&end),
+                        ))
+                    })
+                    .min_by(f32::total_cmp)?;
+                (distance <= Self::PICK_RADIUS).then_some((axis, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(axis, _)| axis)
+    }
+
+    /// Handles a press (`pressed == true`) or release of the primary button at `cursor`.
+    ///
+    /// Pressing on a handle of `target` starts dragging it. Returns `true` if the press picked
+    /// a handle, so the caller can ignore it otherwise. Releases end the drag.
+    pub fn pointer_button(
+        &mut self,
+        cursor: (u32, u32),
+        pressed: bool,
+        target: Option<&GizmoTarget>,
+        view_projection: &Mat4,
+        viewport: (u32, u32),
+    ) -> bool {
+        self.drag = None;
+        let Some(target) = target.filter(|_| pressed) else {
+            return false;
+        };
+        let Some(axis) = self.pick(cursor, target, view_projection, viewport) else {
+            return false;
+        };
+        let start_cursor = crate::math::vec2(cursor.0 as f32, cursor.1 as f32);
+        let cursor_angle = project(view_projection, &target.origin(), viewport)
+            .map_or(0.0, |origin| {
+                screen_angle(&start_cursor, &crate::math::xy(&origin))
+            });
+        self.hovered = Some(axis);
+        self.drag = Some(GizmoDrag {
+            axis,
+            start: *target,
+            start_cursor,
+            cursor_angle,
+            turned: 0.0,
+        });
+        true
+    }
+
+    /// Follows the cursor to `cursor`.
+    ///
+    /// While a handle is dragged, returns the node's new local transform. Otherwise, the handle
+    /// of `target` under the cursor is highlighted and `None` is returned.
+    pub fn pointer_moved(
+        &mut self,
+        cursor: (u32, u32),
+        target: Option<&GizmoTarget>,
+        view_projection: &Mat4,
+        viewport: (u32, u32),
+    ) -> Option<Mat4> {
+        let Some(drag) = self.drag.as_mut() else {
+            self.hovered =
+                target.and_then(|target| self.pick(cursor, target, view_projection, viewport));
+            return None;
+        };
+        let cursor = crate::math::vec2(cursor.0 as f32, cursor.1 as f32);
+        let start = drag.start;
+        let origin = crate::math::xy(&project(view_projection, &start.origin(), viewport)?);
+
+        // Rotations follow the angle the cursor turns around the node, accumulated so they
+        // can exceed half a turn.
+        let angle = screen_angle(&cursor, &origin);
+        let delta = angle - drag.cursor_angle;
+        drag.turned +=
+            (delta + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+        drag.cursor_angle = angle;
+        let (axis, turned, start_cursor) = (drag.axis, drag.turned, drag.start_cursor);
+
+        // Translations and scales follow the cursor's movement along the handle on screen,
+        // converted to world units.
+        let length = self.handle_length(&start, view_projection, viewport)?;
+        let direction = crate::math::normalize(&start.axis(self.mode, axis));
+        let tip = project(
+            view_projection,
+            &(start.origin() + direction * length),
+            viewport,
+        )?;
+        let screen_axis = crate::math::xy(&tip) - origin;
+        let screen_length = crate::math::dot(&screen_axis, &screen_axis);
+        if screen_length <= f32::EPSILON {
+            return None;
+        }
+        let along =
+            crate::math::dot(&(cursor - start_cursor), &screen_axis) / screen_length * length;
+
+        let local = match self.mode {
+            GizmoMode::Translate => {
+                // The position is snapped rather than the offset, so it lands on the steps.
+                let parent_unit = crate::math::length(&start.axis(GizmoMode::Translate, axis));
+                let position = crate::math::to_cols_array(&start.local)[12 + axis];
+                let moved = self
+                    .snapping
+                    .snap(position + along / parent_unit, self.snapping.translation);
+                crate::math::translation(&(unit(axis) * (moved - position))) * start.local
+            }
+            GizmoMode::Rotate => {
+                // Rings seen from behind turn the other way on screen.
+                let (u, v) = perpendicular_basis(&direction);
+                let project_xy = |point: Vec3| {
+                    project(view_projection, &point, viewport).map(|p| crate::math::xy(&p))
+                };
+                let u = project_xy(start.origin() + u)? - origin;
+                let v = project_xy(start.origin() + v)? - origin;
+                let sign = if u.x * v.y - u.y * v.x < 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+                let degrees = self
+                    .snapping
+                    .snap((turned * sign).to_degrees(), self.snapping.rotation_degrees);
+                crate::math::rotate(&start.local, degrees.to_radians(), &unit(axis))
+            }
+            GizmoMode::Scale => {
+                let factor = self
+                    .snapping
+                    .snap(1.0 + along / length, self.snapping.scale)
+                    .max(Self::MIN_SCALE);
+                let mut factors = crate::math::vec3(1.0, 1.0, 1.0);
+                factors[axis] = factor;
+                start.local * crate::math::scaling(&factors)
+            }
+        };
+        Some(local)
+    }
+
+    /// Queues the handles at `target` on `lines`, drawn on top of the scene.
+    pub fn draw(
+        &self,
+        lines: &mut LineRenderer,
+        target: &GizmoTarget,
+        view_projection: &Mat4,
+        viewport: (u32, u32),
+    ) {
+        let Some(length) = self.handle_length(target, view_projection, viewport) else {
+            return;
+        };
+        let active = self.drag.map(|drag| drag.axis).or(self.hovered);
+        for axis in 0..3 {
+            if !self.shows_handle(target, axis, length, view_projection, viewport) {
+                continue;
+            }
+            let color = if active == Some(axis) {
+                Self::ACTIVE_COLOR
+            } else {
+                Self::AXIS_COLORS[axis]
+            };
+            let polyline = self.handle_polyline(target, axis, length);
+            for pair in polyline.windows(2) {
+                lines.overlay_line(pair[0], pair[1], Self::WIDTH, color);
+            }
+
+            // Arrowheads and squares at the ends of the translate and scale handles.
+            let direction = crate::math::normalize(&target.axis(self.mode, axis));
+            let (u, v) = perpendicular_basis(&direction);
+            let tip = target.origin() + direction * length;
+            let cap = length * 0.08;
+            match self.mode {
+                GizmoMode::Translate => {
+                    let base = tip - direction * (cap * 2.5);
+                    for side in [u * cap, -u * cap, v * cap, -v * cap] {
+                        lines.overlay_line(tip, base + side, Self::WIDTH, color);
+                    }
+                }
+                GizmoMode::Scale => {
+                    let corners = [u + v, u - v, -u - v, -u + v].map(|corner| tip + corner * cap);
+                    for index in 0..4 {
+                        let next = corners[(index + 1) % 4];
+                        lines.overlay_line(corners[index], next, Self::WIDTH, color);
+                    }
+                }
+                GizmoMode::Rotate => {}
+            }
+        }
+    }
+
+    /// Returns the length in world units that makes the handles [`TransformGizmo::SIZE`]
+    /// pixels long on screen, or `None` if the node is behind the camera.
+    fn handle_length(
+        &self,
+        target: &GizmoTarget,
+        view_projection: &Mat4,
+        viewport: (u32, u32),
+    ) -> Option<f32> {
+        let origin = target.origin();
+        let center = crate::math::xy(&project(view_projection, &origin, viewport)?);
+        // A unit along the world axis the least foreshortened by the view.
+        let pixels_per_unit = (0..3)
+            .filter_map(|axis| project(view_projection, &(origin + unit(axis)), viewport))
+            .map(|end| crate::math::distance(&crate::math::xy(&end), &center))
+            .fold(0.0, f32::max);
+        (pixels_per_unit > f32::EPSILON).then(|| Self::SIZE / pixels_per_unit)
+    }
+
+    /// Returns `false` for a translate or scale handle along `axis` that is too foreshortened
+    /// to drag, given the handles' `length` in world units.
+    fn shows_handle(
+        &self,
+        target: &GizmoTarget,
+        axis: usize,
+        length: f32,
+        view_projection: &Mat4,
+        viewport: (u32, u32),
+    ) -> bool {
+        if self.mode == GizmoMode::Rotate {
+            return true;
+        }
+        let [start, end] = [0.0, length].map(|offset| {
+            let direction = crate::math::normalize(&target.axis(self.mode, axis));
+            project(
+                view_projection,
+                &(target.origin() + direction * offset),
+                viewport,
+            )
+        });
+        let (Some(start), Some(end)) = (start, end) else {
+            return false;
+        };
+        let on_screen = crate::math::distance(&crate::math::xy(&start), &crate::math::xy(&end));
+        on_screen >= Self::SIZE * Self::MIN_FORESHORTENING
+    }
+
+    /// Returns the points of the handle along `axis`, `length` world units long: a line from
+    /// the node's position, or a closed ring around it for rotations.
+    fn handle_polyline(&self, target: &GizmoTarget, axis: usize, length: f32) -> Vec<Vec3> {
+        let origin = target.origin();
+        let direction = crate::math::normalize(&target.axis(self.mode, axis));
+        match self.mode {
+            GizmoMode::Translate | GizmoMode::Scale => vec![origin, origin + direction * length],
+            GizmoMode::Rotate => {
+                let (u, v) = perpendicular_basis(&direction);
+                (0..=Self::RING_SEGMENTS)
+                    .map(|index| {
+                        let angle =
+                            index as f32 / Self::RING_SEGMENTS as f32 * std::f32::consts::TAU;
+                        origin + (u * angle.cos() + v * angle.sin()) * length
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Returns the unit vector along `axis`: 0 for X, 1 for Y, and 2 for Z.
+fn unit(axis: usize) -> Vec3 {
+    let mut vector = crate::math::zeros();
+    vector[axis] = 1.0;
+    vector
+}
+
+/// Returns `point` transformed by `m`.
+fn transform_point(m: &Mat4, point: &Vec3) -> Vec3 {
+    crate::math::xyz(&(m * crate::math::vec4(point.x, point.y, point.z, 1.0)))
+}
+
+/// Returns the direction `vector` transformed by `m`, ignoring its translation.
+fn transform_vector(m: &Mat4, vector: &Vec3) -> Vec3 {
+    crate::math::xyz(&(m * crate::math::vec4(vector.x, vector.y, vector.z, 0.0)))
+}
+
+/// Returns two unit vectors perpendicular to the unit vector `axis` and to each other, such
+/// that rotating the first towards the second turns around `axis` in the positive direction.
+fn perpendicular_basis(axis: &Vec3) -> (Vec3, Vec3) {
+    let helper = if axis.x.abs() < 0.9 {
+        crate::math::vec3(1.0, 0.0, 0.0)
+    } else {
+        crate::math::vec3(0.0, 1.0, 0.0)
+    };
+    let u = crate::math::normalize(&crate::math::cross(axis, &helper));
+    (u, crate::math::cross(axis, &u))
+}
+
+/// Returns the angle of `point` around `center` on screen, in radians.
+fn screen_angle(point: &Vec2, center: &Vec2) -> f32 {
+    (point.y - center.y).atan2(point.x - center.x)
+}
+
+/// Returns the distance from `point` to the segment from `start` to `end`.
+fn segment_distance(point: &Vec2, start: &Vec2, end: &Vec2) -> f32 {
+    let segment = end - start;
+    let length = crate::math::dot(&segment, &segment);
+    let t = if length > 0.0 {
+        (crate::math::dot(&(point - start), &segment) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    crate::math::distance(point, &(start + segment * t))
+}
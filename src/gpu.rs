@@ -1,6 +1,6 @@
 //! # GPU Management Module
 //!
-//! The `gpu` module is responsible for setting up and managing GPU-related resources and configurations required for rendering in a graphics application. 
+//! The `gpu` module is responsible for setting up and managing GPU-related resources and configurations required for rendering in a graphics application.
 //!
 //! This module defines the `Gpu` struct, which encapsulates essential GPU components like the surface, device, and queue, and provides utility methods for resizing and creating GPU-dependent resources.
 //!
@@ -11,9 +11,13 @@
 //! ## Features
 //!
 //! - **Dynamic Surface Resizing**: Update the rendering surface dimensions and configuration dynamically.
+//! - **Headless Rendering**: Render into an offscreen texture instead of a window's surface, without any window or event loop.
+//! - **Present Mode Selection**: Switch between vsync and lower-latency present modes at runtime, validated against the surface's capabilities.
+//! - **Surface Recovery**: Re-query the surface's capabilities and fall back to the nearest supported present mode when the configured one stops working.
 //! - **Aspect Ratio Calculation**: Retrieve the aspect ratio of the rendering surface for content scaling.
 //! - **Depth Texture Creation**: Create depth textures needed for various rendering techniques.
 //! - **Asynchronous Initialization**: Enables initializing GPU resources asynchronously for better responsiveness in applications.
+//! - **Feature Negotiation**: Requests optional device features only when the adapter supports them and records what was granted.
 //!
 //! ## Example Usage
 //!
@@ -21,8 +25,9 @@
 //! use winit::window::Window;
 //! use gpu::Gpu;
 //!
-//! async fn create_gpu_instance(window: Window, width: u32, height: u32) -> Gpu {
-//!     Gpu::new_async(window, width, height).await
+//! async fn create_gpu_instance(window: Window, width: u32, height: u32) -> Result<Gpu, RenderError> {
+//!     let config = RendererConfig::default();
+//!     Gpu::new_async(window, width, height, false, GpuCapabilities::OPTIONAL_FEATURES, config).await
 //! }
 //! ```
 //!
@@ -48,35 +53,64 @@ use wasm_bindgen::prelude::*;
 // This descriptor is typically used when initializing `wgpu::Instance`, enabling customization of how the GPU interacts with the system.
 use wgpu::InstanceDescriptor;
 
+// Importing the validation helpers and error type, which are used to check the surface and
+// depth texture configuration against the adapter's capabilities before it reaches `wgpu`,
+// falling back to supported settings where possible.
+use crate::validation::{self, ValidationError};
+
+// Importing the crate-wide `RenderError`, which is returned instead of panicking when the
+// surface, adapter, or device cannot be created.
+use crate::error::RenderError;
+
+// Importing the feature negotiation helpers. Optional features are intersected with what the
+// adapter offers, and the granted set is recorded in `GpuCapabilities` for subsystems to query.
+use crate::capabilities::{self, GpuCapabilities, GpuInfo};
+
+// Importing the renderer options chosen by the application, which select the backends and the
+// initial present mode.
+use crate::config::{AdapterSelection, RendererConfig};
+
+// The belt streaming the per-frame buffer writes through reused staging buffers, declared here
+// as it belongs to the GPU's resources rather than to any one renderer subsystem.
+mod upload;
+pub use upload::UploadBelt;
+
 /// A struct representing the GPU-related resources and configurations required for rendering.
 ///
 /// This struct manages the GPU surface, device, queue, surface configuration, and provides utility methods
 /// for resizing, creating textures, and handling aspect ratios.
 ///
 /// # Fields
-/// - `surface` (`wgpu::Surface`): Represents the surface associated with the GPU rendering target, typically
-///   tied to a window or canvas.
+/// - `surface` (`Option<wgpu::Surface>`): Represents the surface associated with the GPU rendering target, typically
+///   tied to a window or canvas. It is `None` for a headless GPU.
+/// - `offscreen_target` (`Option<wgpu::Texture>`): The texture a headless GPU renders into instead of a surface.
 /// - `device` (`wgpu::Device`): The device instance that is used to create GPU-dependent resources and execute commands.
 /// - `queue` (`wgpu::Queue`): The command queue, used to submit command buffers to the GPU for execution.
 /// - `surface_config` (`wgpu::SurfaceConfiguration`): The configuration settings for the rendering surface,
 ///   such as its size, format, and other parameters.
 /// - `surface_format` (`wgpu::TextureFormat`): The texture format used by the surface, obtained from the surface's capabilities.
+/// - `adapter` (`wgpu::Adapter`): The adapter the device was created from, kept for capability queries.
+/// - `depth_texture_usages` (`wgpu::TextureUsages`): The validated usages depth textures are created with.
+/// - `validation_warnings` (`Vec<ValidationError>`): Recoverable configuration problems detected
+///   during initialization, for which a fallback was applied.
 ///
 /// # Methods
 /// - `aspect_ratio() -> f32`: Computes the aspect ratio of the rendering surface based on the current width and height.
 /// - `resize(width: u32, height: u32)`: Resizes the rendering surface to the specified dimensions and updates its configuration.
 /// - `create_depth_texture(width: u32, height: u32) -> wgpu::TextureView`: Creates and returns a depth texture
 ///   for use in rendering, based on the specified dimensions.
-/// - `new_async(window, width, height) -> Self`: Asynchronously initializes a `Gpu` instance with the specified
-///   window and dimensions.
+/// - `new_async(window, width, height, force_fallback_adapter) -> Result<Self, RenderError>`: Asynchronously
+///   initializes a `Gpu` instance with the specified window and dimensions.
+/// - `new_headless_async(width, height, force_fallback_adapter) -> Result<Self, RenderError>`: Asynchronously
+///   initializes a `Gpu` instance rendering into an offscreen texture of the specified dimensions.
 ///
 /// # Example
 /// ```rust
 /// use wgpu::Surface;
 ///
 /// // Create a Gpu instance using a window reference and dimensions.
-/// async fn create_gpu(window: winit::window::Window, width: u32, height: u32) -> Gpu {
-///     Gpu::new_async(window, width, height).await
+/// async fn create_gpu(window: winit::window::Window, width: u32, height: u32) -> Result<Gpu, RenderError> {
+///     Gpu::new_async(window, width, height, false).await
 /// }
 /// ```
 pub struct Gpu {
@@ -88,7 +122,14 @@ pub struct Gpu {
     ///
     /// The surface is used to configure the swap chain and ensures that rendered
     /// frames are presented on the screen.
-    pub surface: wgpu::Surface<'static>,
+    ///
+    /// A headless GPU has no surface and renders into `offscreen_target` instead.
+    pub surface: Option<wgpu::Surface<'static>>,
+
+    /// The texture frames are rendered into by a headless GPU, which has no `surface`.
+    ///
+    /// It has the size and format of `surface_config`, and is re-created by `resize`.
+    pub offscreen_target: Option<wgpu::Texture>,
 
     /// The device instance that is used to create GPU-dependent resources and execute commands.
     ///
@@ -147,12 +188,110 @@ pub struct Gpu {
     /// Common formats include `Bgra8Unorm` and `Rgba8Unorm`, depending on the system and
     /// rendering requirements.
     pub surface_format: wgpu::TextureFormat,
+
+    /// The adapter the `device` was requested from.
+    ///
+    /// The adapter is retained so that configurations requested after initialization
+    /// (such as MSAA sample counts or additional texture formats) can be validated
+    /// against its capabilities with the helpers in the `validation` module.
+    pub adapter: wgpu::Adapter,
+
+    /// The usages depth textures are created with.
+    ///
+    /// Depth textures are requested as both render attachments and sampled textures. If the
+    /// adapter cannot sample the depth format, the `TEXTURE_BINDING` usage is dropped and a
+    /// warning is recorded in `validation_warnings` instead of failing texture creation.
+    pub depth_texture_usages: wgpu::TextureUsages,
+
+    /// Recoverable configuration problems detected while initializing the GPU.
+    ///
+    /// Each entry describes a requested setting that the adapter or surface did not support
+    /// and for which a supported fallback was applied. The application drains this list and
+    /// presents the messages to the user as toasts.
+    pub validation_warnings: Vec<ValidationError>,
+
+    /// The optional features and limits granted to the `device`.
+    ///
+    /// Subsystems query this instead of assuming that a feature such as timestamp queries or
+    /// push constants is available.
+    pub capabilities: GpuCapabilities,
+
+    /// The adapter in use and the features and limits granted to the `device`, for display
+    /// and for applications deciding which features to use.
+    pub info: GpuInfo,
+
+    /// The present modes the surface supports, queried when it is configured. Empty for a
+    /// headless GPU, which presents nothing.
+    pub present_modes: Vec<wgpu::PresentMode>,
+
+    /// The instance the `adapter` was requested from, kept to create the surfaces of further
+    /// windows rendered with the same device.
+    pub instance: wgpu::Instance,
+}
+
+/// The texture a frame is rendered into, returned by [`Gpu::acquire_frame`].
+#[derive(Debug)]
+pub enum FrameTarget {
+    /// The next texture of the window's surface.
+    Surface(wgpu::SurfaceTexture),
+
+    /// The offscreen target of a headless GPU.
+    Offscreen(wgpu::Texture),
+}
+
+impl FrameTarget {
+    /// Returns the texture to render into.
+    pub fn texture(&self) -> &wgpu::Texture {
+        match self {
+            FrameTarget::Surface(surface_texture) => &surface_texture.texture,
+            FrameTarget::Offscreen(texture) => texture,
+        }
+    }
+
+    /// Presents a surface texture once the frame is rendered. Offscreen targets stay where
+    /// they are, to be read back.
+    pub fn present(self) {
+        if let FrameTarget::Surface(surface_texture) = self {
+            surface_texture.present();
+        }
+    }
 }
 
 /// Implementation block for the `Gpu` struct, providing utility functions
 /// to work with the GPU, such as querying the display's aspect ratio,
 /// resizing the rendering surface, and creating GPU-dependent resources.
 impl Gpu {
+    /// The format of the offscreen target of a headless GPU. Like the surface formats `egui`
+    /// is used with, it is not sRGB, so rendered frames look the same as in a window.
+    pub const OFFSCREEN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    /// The number of frames queued for presentation unless another is set: one being
+    /// rendered while the previous one waits to be shown.
+    pub const DEFAULT_FRAME_LATENCY: u32 = 2;
+
+    /// The smallest and largest frame latencies offered in the GUI.
+    pub const FRAME_LATENCY_RANGE: (u32, u32) = (1, 3);
+
+    /// The present modes offered in the GUI: vsync, vsync with the latest frame replacing
+    /// queued ones, and presenting immediately, which may tear.
+    pub const SELECTABLE_PRESENT_MODES: [wgpu::PresentMode; 3] = [
+        wgpu::PresentMode::Fifo,
+        wgpu::PresentMode::Mailbox,
+        wgpu::PresentMode::Immediate,
+    ];
+
+    /// Returns the name of `present_mode` shown in the GUI.
+    pub fn present_mode_label(present_mode: wgpu::PresentMode) -> &'static str {
+        match present_mode {
+            wgpu::PresentMode::AutoVsync => "Auto (VSync)",
+            wgpu::PresentMode::AutoNoVsync => "Auto (No VSync)",
+            wgpu::PresentMode::Fifo => "Fifo (VSync)",
+            wgpu::PresentMode::FifoRelaxed => "Fifo Relaxed",
+            wgpu::PresentMode::Immediate => "Immediate",
+            wgpu::PresentMode::Mailbox => "Mailbox",
+        }
+    }
+
     /// Calculates the aspect ratio of the rendering surface.
     ///
     /// The aspect ratio is determined by dividing the width of the surface by its height.
@@ -184,6 +323,9 @@ impl Gpu {
     /// It is typically used when the window is resized or when the rendering surface
     /// requires reconfiguration during runtime.
     ///
+    /// A width or height of zero, as reported for a minimized window, is ignored: a surface
+    /// without area cannot be configured, so the current configuration is kept.
+    ///
     /// # Parameters
     ///
     /// - `width`: The new width of the rendering surface, in pixels.
@@ -197,9 +339,154 @@ impl Gpu {
     /// gpu.resize(1280, 720);
     /// ```
     pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
         self.surface_config.width = width;
         self.surface_config.height = height;
-        self.surface.configure(&self.device, &self.surface_config);
+        match &self.surface {
+            Some(surface) => surface.configure(&self.device, &self.surface_config),
+            None => {
+                self.offscreen_target = Some(Self::create_offscreen_target(
+                    &self.device,
+                    &self.surface_config,
+                ));
+            }
+        }
+    }
+
+    /// Returns the texture to render the next frame into: the surface's next texture, or the
+    /// offscreen target of a headless GPU.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `wgpu::SurfaceError` if the surface's next texture cannot be acquired.
+    pub fn acquire_frame(&self) -> Result<FrameTarget, wgpu::SurfaceError> {
+        match (&self.surface, &self.offscreen_target) {
+            (Some(surface), _) => surface.get_current_texture().map(FrameTarget::Surface),
+            (None, Some(texture)) => Ok(FrameTarget::Offscreen(texture.clone())),
+            (None, None) => Err(wgpu::SurfaceError::Lost),
+        }
+    }
+
+    /// Drops the surface while the window cannot be presented to, as when an Android app is
+    /// suspended and its native window destroyed. The device and every resource created with
+    /// it are kept, so [`Gpu::resume_surface`] only has to create a new surface.
+    ///
+    /// No frames may be acquired until the surface is resumed.
+    pub fn suspend_surface(&mut self) {
+        self.surface = None;
+    }
+
+    /// Creates a new surface for `window` after [`Gpu::suspend_surface`], and configures it
+    /// as the previous one was.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if the surface cannot be created, no longer supports the
+    /// texture format the pipelines were created for, or reports no present or alpha modes.
+    pub fn resume_surface(
+        &mut self,
+        window: impl Into<wgpu::SurfaceTarget<'static>>,
+    ) -> Result<(), RenderError> {
+        let surface = self.instance.create_surface(window)?;
+        let capabilities = surface.get_capabilities(&self.adapter);
+        if !capabilities.formats.contains(&self.surface_format) {
+            return Err(RenderError::IncompatibleSurface);
+        }
+        self.surface = Some(surface);
+        self.offscreen_target = None;
+        self.reconfigure_surface()
+    }
+
+    /// Queries the surface's capabilities again and reconfigures it with them.
+    ///
+    /// Moving the window to another monitor or a driver change can withdraw support for the
+    /// configured present mode, after which acquiring surface textures keeps failing. If that
+    /// happened, the nearest supported present mode is selected and the change is recorded in
+    /// `validation_warnings`, so the user learns why presentation changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RenderError::IncompatibleSurface` if the surface reports no present modes or
+    /// alpha modes anymore.
+    pub fn reconfigure_surface(&mut self) -> Result<(), RenderError> {
+        let Some(surface) = &self.surface else {
+            return Ok(());
+        };
+        let capabilities = surface.get_capabilities(&self.adapter);
+        if capabilities.present_modes.is_empty() || capabilities.alpha_modes.is_empty() {
+            return Err(RenderError::IncompatibleSurface);
+        }
+
+        let (present_mode, warning) =
+            validation::resolve_present_mode(&capabilities, self.surface_config.present_mode);
+        if let Some(warning) = warning {
+            log::warn!("{warning}; switching to {present_mode:?}");
+            self.validation_warnings.push(warning);
+        }
+        self.surface_config.present_mode = present_mode;
+        self.present_modes = capabilities.present_modes;
+        if !capabilities
+            .alpha_modes
+            .contains(&self.surface_config.alpha_mode)
+        {
+            self.surface_config.alpha_mode = capabilities.alpha_modes[0];
+        }
+        surface.configure(&self.device, &self.surface_config);
+        Ok(())
+    }
+
+    /// Presents frames with `present_mode` from now on.
+    ///
+    /// If the surface does not support it, the nearest supported present mode is selected
+    /// instead and a warning is recorded in `validation_warnings`. A headless GPU presents
+    /// nothing, so it only records the mode.
+    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) {
+        let Some(surface) = &self.surface else {
+            self.surface_config.present_mode = present_mode;
+            return;
+        };
+        let capabilities = surface.get_capabilities(&self.adapter);
+        let (present_mode, warning) = validation::resolve_present_mode(&capabilities, present_mode);
+        if let Some(warning) = warning {
+            log::warn!("{warning}; switching to {present_mode:?}");
+            self.validation_warnings.push(warning);
+        }
+        self.surface_config.present_mode = present_mode;
+        self.present_modes = capabilities.present_modes;
+        surface.configure(&self.device, &self.surface_config);
+    }
+
+    /// Lets at most `latency` frames be queued for presentation from now on, at least one.
+    ///
+    /// Fewer queued frames show input sooner, but leave the GPU idle whenever the CPU is late
+    /// with a frame. A headless GPU presents nothing, so it only records the latency.
+    pub fn set_frame_latency(&mut self, latency: u32) {
+        self.surface_config.desired_maximum_frame_latency = latency.max(1);
+        if let Some(surface) = &self.surface {
+            surface.configure(&self.device, &self.surface_config);
+        }
+    }
+
+    /// Makes the surface transparent, composited over the desktop with per-pixel alpha, or
+    /// opaque.
+    ///
+    /// If the surface cannot be transparent, it stays opaque and a warning is recorded in
+    /// `validation_warnings`. The window must have been created transparent as well. The
+    /// offscreen target of a headless GPU always keeps the alpha of the rendered pixels.
+    pub fn set_transparent(&mut self, transparent: bool) {
+        let Some(surface) = &self.surface else {
+            return;
+        };
+        let capabilities = surface.get_capabilities(&self.adapter);
+        let (alpha_mode, warning) = validation::resolve_alpha_mode(&capabilities, transparent);
+        if let Some(warning) = warning {
+            log::warn!("{warning}");
+            self.validation_warnings.push(warning);
+        }
+        self.surface_config.alpha_mode = alpha_mode;
+        surface.configure(&self.device, &self.surface_config);
     }
 
     /// Creates a depth texture for the GPU rendering pipeline.
@@ -224,8 +511,8 @@ impl Gpu {
     ///
     /// - The depth texture uses the `Depth32Float` format, which stores a 32-bit
     ///   floating-point value for depth information.
-    /// - It supports `RENDER_ATTACHMENT` and `TEXTURE_BINDING` usages, making it
-    ///   suitable for rendering and sampling.
+    /// - It supports `RENDER_ATTACHMENT` and, where the adapter allows it, `TEXTURE_BINDING`
+    ///   usages (see `depth_texture_usages`), making it suitable for rendering and sampling.
     ///
     /// # Examples
     ///
@@ -233,6 +520,19 @@ impl Gpu {
     /// let depth_texture = gpu.create_depth_texture(1920, 1080);
     /// ```
     pub fn create_depth_texture(&self, width: u32, height: u32) -> wgpu::TextureView {
+        self.create_depth_target(width, height).1
+    }
+
+    /// Creates a depth texture like [`Gpu::create_depth_texture`], returning the texture
+    /// together with its view.
+    ///
+    /// The texture itself is needed to copy depth values out of it, for example by the depth
+    /// probe. This is only possible if `depth_texture_usages` contains `COPY_SRC`.
+    pub fn create_depth_target(
+        &self,
+        width: u32,
+        height: u32,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
         // The `texture` variable represents the GPU resource for the depth texture.
         //
         // It is created using the `create_texture` method, which defines the texture's
@@ -265,13 +565,12 @@ impl Gpu {
                 sample_count: 1,
                 dimension: wgpu::TextureDimension::D2,
                 format: wgpu::TextureFormat::Depth32Float,
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
-                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                usage: self.depth_texture_usages,
                 view_formats: &[],
             }),
         );
-        texture.create_view(&wgpu::TextureViewDescriptor {
-            label: None,
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Depth Texture View"),
             format: Some(wgpu::TextureFormat::Depth32Float),
             dimension: Some(wgpu::TextureViewDimension::D2),
             aspect: wgpu::TextureAspect::All,
@@ -280,6 +579,59 @@ impl Gpu {
             array_layer_count: None,
             mip_level_count: None,
             usage: None,
+        });
+        (texture, view)
+    }
+
+    /// Creates the object id texture the scene writes the id of each drawn object into.
+    ///
+    /// The texture has the given `format` (an unsigned integer format), the size of the
+    /// surface, and can be copied from, so single texels can be read back by the depth probe
+    /// and picking. It can also be bound, so the selection outline can read it.
+    pub fn create_object_id_target(
+        &self,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Object Id Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+
+    /// Creates the offscreen target of a headless GPU, with the size and format of `config`.
+    fn create_offscreen_target(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Target"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: config.usage,
+            view_formats: &[],
         })
     }
 
@@ -295,6 +647,12 @@ impl Gpu {
     /// - `window`: The target window for rendering. This is used to create the GPU surface.
     /// - `width`: The initial width of the rendering surface, in pixels.
     /// - `height`: The initial height of the rendering surface, in pixels.
+    /// - `force_fallback_adapter`: Requests a fallback (typically software) adapter instead of
+    ///   a hardware GPU. This is used as a degraded mode when no hardware adapter is available.
+    /// - `optional_features`: Features to enable if the adapter supports them. Missing ones
+    ///   are skipped rather than failing device creation; see `GpuCapabilities::OPTIONAL_FEATURES`.
+    /// - `config`: The backends the adapter is picked from, and the present mode the surface is
+    ///   configured with, or the nearest one it supports.
     ///
     /// # Returns
     ///
@@ -306,22 +664,35 @@ impl Gpu {
     /// - The method selects an adapter that is compatible with the provided window surface.
     /// - It ensures the surface configuration matches the surface's capabilities, selecting
     ///   a non-sRGB format for compatibility with `egui`.
-    /// - Errors are logged via the `log` crate if any GPU device or adapter setup fails.
+    /// - Recoverable configuration problems are recorded in `validation_warnings`.
+    /// - The optional features actually granted are recorded in `capabilities`.
     ///
     /// # Errors
     ///
-    /// Panics if it fails to create a surface, request an adapter, or request a device.
+    /// Returns a `RenderError` if it fails to create a surface, find a compatible adapter,
+    /// request a device, or if the surface cannot be used as a render attachment.
     ///
     /// # Examples
     ///
     /// ```
-    /// let gpu_context = GpuContext::new_async(window, 1920, 1080).await;
+    /// let gpu_context = GpuContext::new_async(
+    ///     window,
+    ///     1920,
+    ///     1080,
+    ///     false,
+    ///     GpuCapabilities::OPTIONAL_FEATURES,
+    ///     RendererConfig::default(),
+    /// )
+    /// .await?;
     /// ```
     pub async fn new_async(
         window: impl Into<wgpu::SurfaceTarget<'static>>,
         width: u32,
         height: u32,
-    ) -> Self {
+        force_fallback_adapter: bool,
+        optional_features: wgpu::Features,
+        config: RendererConfig,
+    ) -> Result<Self, RenderError> {
         // The `instance` variable represents a handle to the WGPU instance,
         // which is the entry point for interacting with the GPU.
         //
@@ -330,7 +701,11 @@ impl Gpu {
         // - The `Instance` is used to create GPU surfaces and query available adapters.
         // - It serves as the foundational object for setting up GPU-related resources.
         // - A single `Instance` can manage multiple surfaces and adapters.
-        let instance = wgpu::Instance::new(&InstanceDescriptor::default());
+        // - Only the configured backends are considered when looking for an adapter.
+        let instance = wgpu::Instance::new(&InstanceDescriptor {
+            backends: config.backends,
+            ..InstanceDescriptor::default()
+        });
 
         // The `surface` variable represents the rendering surface associated with the given window.
         //
@@ -343,7 +718,7 @@ impl Gpu {
         //
         // - The `Surface` is essential for rendering in windowed applications, as it allows
         //   the GPU to directly render content to the display.
-        let surface = instance.create_surface(window).unwrap();
+        let surface = instance.create_surface(window)?;
 
         // Represents a handle to the GPU adapter used for device creation.
         //
@@ -359,7 +734,8 @@ impl Gpu {
         // # Error Handling
         //
         // - If no suitable adapter is found that matches the provided options, the operation
-        //   will fail, causing the application to panic with an appropriate error message.
+        //   fails with `RenderError::NoAdapter`, letting the application's error policy decide
+        //   whether to retry with a fallback adapter.
         //
         // # Example Usage
         //
@@ -373,14 +749,152 @@ impl Gpu {
         //     .await
         //     .expect("Failed to request adapter!");
         // ```
+        //
+        // An adapter selected in the configuration is looked up among the enumerated adapters
+        // first, on native platforms.
+        let selected = if force_fallback_adapter {
+            None
+        } else {
+            Self::select_adapter(&instance, &surface, &config)
+        };
+        let adapter = match selected {
+            Some(adapter) => adapter,
+            None => instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: wgpu::PowerPreference::default(),
+                    compatible_surface: Some(&surface),
+                    force_fallback_adapter,
+                })
+                .await
+                .ok_or(RenderError::NoAdapter)?,
+        };
+
+        Self::from_adapter(
+            instance,
+            adapter,
+            Some(surface),
+            width,
+            height,
+            config.present_mode,
+            optional_features,
+        )
+        .await
+        .map(|mut gpu| {
+            if config.frame_latency != Self::DEFAULT_FRAME_LATENCY {
+                gpu.set_frame_latency(config.frame_latency);
+            }
+            gpu
+        })
+    }
+
+    /// Returns the adapter selected by `config` among the adapters of its backends that can
+    /// present to `surface`, or `None` to request the default adapter.
+    ///
+    /// A selection matching no adapter is logged with the list of adapters, and `None` is
+    /// returned. Adapters cannot be enumerated on the web, where the selection is ignored.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn select_adapter(
+        instance: &wgpu::Instance,
+        surface: &wgpu::Surface<'static>,
+        config: &RendererConfig,
+    ) -> Option<wgpu::Adapter> {
+        if config.adapter == AdapterSelection::Default {
+            return None;
+        }
+        let adapters: Vec<_> = instance
+            .enumerate_adapters(config.backends)
+            .into_iter()
+            .filter(|adapter| adapter.is_surface_supported(surface))
+            .collect();
+        let selected = adapters
+            .iter()
+            .enumerate()
+            .position(|(index, adapter)| config.adapter.matches(index, &adapter.get_info()));
+        match selected {
+            Some(index) => adapters.into_iter().nth(index),
+            None => {
+                let names: Vec<_> = adapters
+                    .iter()
+                    .enumerate()
+                    .map(|(index, adapter)| format!("{index}: {}", adapter.get_info().name))
+                    .collect();
+                log::warn!(
+                    "No adapter matches {:?}; using the default one. Adapters: [{}]",
+                    config.adapter,
+                    names.join(", ")
+                );
+                None
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn select_adapter(
+        _instance: &wgpu::Instance,
+        _surface: &wgpu::Surface<'static>,
+        config: &RendererConfig,
+    ) -> Option<wgpu::Adapter> {
+        if config.adapter != AdapterSelection::Default {
+            log::warn!("Adapters cannot be selected on the web; using the default one");
+        }
+        None
+    }
+
+    /// Creates a new headless GPU context asynchronously, which renders into an offscreen
+    /// texture instead of a window's surface.
+    ///
+    /// The adapter is not required to present to any surface. Frames are rendered into
+    /// `offscreen_target`, a `width` x `height` texture in `Rgba8Unorm` format that can be
+    /// copied from, and are not presented anywhere.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if no adapter is found or the device cannot be requested.
+    pub async fn new_headless_async(
+        width: u32,
+        height: u32,
+        force_fallback_adapter: bool,
+        optional_features: wgpu::Features,
+    ) -> Result<Self, RenderError> {
+        let instance = wgpu::Instance::new(&InstanceDescriptor::default());
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
                 power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
+                compatible_surface: None,
+                force_fallback_adapter,
             })
             .await
-            .expect("Failed to request adapter!");
+            .ok_or(RenderError::NoAdapter)?;
+
+        let present_mode = wgpu::PresentMode::Fifo;
+        Self::from_adapter(
+            instance,
+            adapter,
+            None,
+            width,
+            height,
+            present_mode,
+            optional_features,
+        )
+        .await
+    }
+
+    /// Shared implementation of [`Gpu::new_async`] and [`Gpu::new_headless_async`].
+    ///
+    /// Requests the device from `adapter` and configures `surface` with `present_mode`, or
+    /// the nearest mode it supports, or creates the offscreen target if there is no surface.
+    async fn from_adapter(
+        instance: wgpu::Instance,
+        adapter: wgpu::Adapter,
+        surface: Option<wgpu::Surface<'static>>,
+        width: u32,
+        height: u32,
+        present_mode: wgpu::PresentMode,
+        optional_features: wgpu::Features,
+    ) -> Result<Self, RenderError> {
+        // A surface without area cannot be configured, as for a window created minimized, so
+        // it starts at the smallest size until it is resized.
+        let (width, height) = (width.max(1), height.max(1));
 
         // Represents the GPU device used for rendering and computation.
         //
@@ -401,7 +915,7 @@ impl Gpu {
         // # Error Handling
         //
         // - If the GPU device fails to be created due to invalid configurations or hardware issues,
-        //   the operation will fail, and the application will panic with an error message.
+        //   the operation fails with `RenderError::RequestDevice`.
         //
         // ---
         //
@@ -423,26 +937,130 @@ impl Gpu {
         //
         // - If the `queue` fails to process commands due to invalid configurations or resource limitations,
         //   it may lead to rendering errors or interruptions in the application's graphical output.
+        // Recoverable configuration problems found while validating the requested setup
+        // against the adapter and surface capabilities. Each entry records a fallback that
+        // was applied so the application can inform the user.
+        let mut validation_warnings = Vec::new();
+
+        // The limits requested from the device.
+        //
+        // The baseline limits depend on the target (WebGL2 is far more restrictive than
+        // native backends or WebGPU). The number of bind groups is validated against what
+        // the adapter offers and clamped when necessary, since the scene only needs one.
+        #[cfg(not(target_arch = "wasm32"))]
+        let mut required_limits = wgpu::Limits::default().using_resolution(adapter.limits());
+        #[cfg(all(target_arch = "wasm32", feature = "webgpu"))]
+        let mut required_limits = wgpu::Limits::default().using_resolution(adapter.limits());
+        #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
+        let mut required_limits =
+            wgpu::Limits::downlevel_webgl2_defaults().using_resolution(adapter.limits());
+
+        if let Err(error) = validation::validate_bind_group_count(
+            &adapter.limits(),
+            required_limits.max_bind_groups,
+        ) {
+            required_limits.max_bind_groups = adapter.limits().max_bind_groups;
+            validation_warnings.push(error);
+        }
+
+        // The features requested from the device: the optional features the adapter supports.
+        // Push constants additionally need a non-zero limit, which defaults to zero.
+        let required_features =
+            capabilities::negotiate_features(adapter.features(), optional_features);
+        if required_features.contains(wgpu::Features::PUSH_CONSTANTS) {
+            required_limits.max_push_constant_size =
+                GpuCapabilities::PUSH_CONSTANT_SIZE.min(adapter.limits().max_push_constant_size);
+        }
+
         let (device, queue) = {
             log::info!("WGPU Adapter Features: {:#?}", adapter.features());
-            adapter
-                .request_device(
-                    &wgpu::DeviceDescriptor {
-                        label: Some("WGPU Device"),
-                        memory_hints: wgpu::MemoryHints::default(),
-                        required_features: wgpu::Features::default(),
-                        #[cfg(not(target_arch = "wasm32"))]
-                        required_limits: wgpu::Limits::default().using_resolution(adapter.limits()),
-                        #[cfg(all(target_arch = "wasm32", feature = "webgpu"))]
-                        required_limits: wgpu::Limits::default().using_resolution(adapter.limits()),
-                        #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
-                        required_limits: wgpu::Limits::downlevel_webgl2_defaults()
-                            .using_resolution(adapter.limits()),
-                    },
-                    None,
-                )
-                .await
-                .expect("Failed to request a device!")
+            let descriptor = wgpu::DeviceDescriptor {
+                label: Some("WGPU Device"),
+                memory_hints: wgpu::MemoryHints::default(),
+                required_features,
+                required_limits,
+            };
+            // Exporting frames needs extensions wgpu does not enable on its own.
+            #[cfg(all(feature = "interop", target_os = "linux"))]
+            let interop_device = crate::interop::request_device(&adapter, &descriptor);
+            #[cfg(not(all(feature = "interop", target_os = "linux")))]
+            let interop_device = None;
+            match interop_device {
+                Some(device) => device,
+                None => adapter.request_device(&descriptor, None).await?,
+            }
+        };
+
+        // The usages depth textures are created with. Sampling the depth buffer is optional,
+        // so `TEXTURE_BINDING` is dropped (with a warning) if the adapter does not allow it.
+        let mut depth_texture_usages =
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+        if let Err(error) = validation::validate_texture_format(
+            &adapter,
+            wgpu::TextureFormat::Depth32Float,
+            depth_texture_usages,
+        ) {
+            depth_texture_usages = wgpu::TextureUsages::RENDER_ATTACHMENT;
+            validation_warnings.push(error);
+        }
+
+        // Reading depth values back (for the depth probe) requires copying from the depth
+        // texture, which downlevel backends such as WebGL may not support.
+        if adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(wgpu::DownlevelFlags::DEPTH_TEXTURE_AND_BUFFER_COPIES)
+        {
+            depth_texture_usages |= wgpu::TextureUsages::COPY_SRC;
+        } else {
+            log::info!("Depth texture copies are not supported; the depth probe shows no depth");
+        }
+
+        let info = adapter.get_info();
+        log::info!(
+            "Using the {:?} adapter '{}' ({:?}), driver '{}' {}",
+            info.backend,
+            info.name,
+            info.device_type,
+            info.driver,
+            info.driver_info
+        );
+
+        let capabilities = GpuCapabilities::new(device.features(), device.limits());
+        log::info!(
+            "Granted optional GPU features: {:?}",
+            capabilities.features & optional_features
+        );
+        let info = GpuInfo::new(info, &capabilities);
+
+        // Without a surface, frames are rendered into an offscreen texture that can be read back.
+        let Some(surface) = surface else {
+            let surface_config = wgpu::SurfaceConfiguration {
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                format: Self::OFFSCREEN_FORMAT,
+                width,
+                height,
+                present_mode,
+                alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+                view_formats: vec![],
+                desired_maximum_frame_latency: Self::DEFAULT_FRAME_LATENCY,
+            };
+            let offscreen_target = Self::create_offscreen_target(&device, &surface_config);
+            return Ok(Self {
+                surface: None,
+                offscreen_target: Some(offscreen_target),
+                device,
+                queue,
+                surface_format: surface_config.format,
+                surface_config,
+                adapter,
+                depth_texture_usages,
+                validation_warnings,
+                capabilities,
+                info,
+                present_modes: Vec::new(),
+                instance,
+            });
         };
 
         // Represents the capabilities of the surface as determined by the selected GPU adapter.
@@ -479,6 +1097,14 @@ impl Gpu {
         // ```
         let surface_capabilities = surface.get_capabilities(&adapter);
 
+        // An empty capability list means the surface cannot be presented to by this adapter.
+        if surface_capabilities.formats.is_empty()
+            || surface_capabilities.present_modes.is_empty()
+            || surface_capabilities.alpha_modes.is_empty()
+        {
+            return Err(RenderError::IncompatibleSurface);
+        }
+
         // The surface texture format selected for rendering.
         //
         // # Remarks
@@ -562,25 +1188,61 @@ impl Gpu {
         //     desired_maximum_frame_latency: 2,
         // };
         // ```
+        // The surface must at least be usable as a render attachment. There is no fallback
+        // for this, so return the descriptive validation error rather than letting
+        // `surface.configure` fail with a generic validation panic.
+        validation::validate_surface_usage(
+            &surface_capabilities,
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+        )?;
+
+        // Exported, streamed, and captured frames are copied out of the surface textures.
+        let surface_usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+            | (surface_capabilities.usages & wgpu::TextureUsages::COPY_SRC);
+
+        // The configured present mode, until another one is selected.
+        let (present_mode, warning) =
+            validation::resolve_present_mode(&surface_capabilities, present_mode);
+        if let Some(warning) = warning {
+            log::warn!("{warning}; switching to {present_mode:?}");
+            validation_warnings.push(warning);
+        }
+
         let surface_config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage: surface_usage,
             format: surface_format,
             width,
             height,
-            present_mode: surface_capabilities.present_modes[0],
-            alpha_mode: surface_capabilities.alpha_modes[0],
+            present_mode,
+            // Opaque, until `set_transparent` composites the surface with the desktop.
+            alpha_mode: validation::resolve_alpha_mode(&surface_capabilities, false).0,
             view_formats: vec![],
-            desired_maximum_frame_latency: 2,
+            desired_maximum_frame_latency: Self::DEFAULT_FRAME_LATENCY,
         };
 
+        // The GPU error handler is only installed once the renderer exists, so a configuration
+        // the surface rejects is caught here rather than panicking in wgpu's default handler.
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
         surface.configure(&device, &surface_config);
+        if let Some(error) = device.pop_error_scope().await {
+            log::error!("Failed to configure the surface: {error}");
+            return Err(RenderError::IncompatibleSurface);
+        }
 
-        Self {
-            surface,
+        Ok(Self {
+            surface: Some(surface),
+            offscreen_target: None,
             device,
             queue,
             surface_config,
             surface_format,
-        }
+            adapter,
+            depth_texture_usages,
+            validation_warnings,
+            capabilities,
+            info,
+            present_modes: surface_capabilities.present_modes,
+            instance,
+        })
     }
 }
@@ -0,0 +1,224 @@
+//! # GPU Upload Belt Module
+//!
+//! The `gpu::upload` module streams the small buffer writes made every frame, such as the
+//! nodes' uniforms, the labels' glyph instances, and the debug lines, through a set of
+//! persistently reused staging buffers instead of one `Queue::write_buffer` per write.
+//!
+//! ## Overview
+//!
+//! [`UploadBelt`] owns staging buffers of [`UploadBelt::CHUNK_SIZE`] bytes, or larger for
+//! larger writes, that are mapped for writing. Each chunk goes around the belt:
+//!
+//! 1. **`write`**: Copies the data into the free space of a mapped chunk, and records a copy
+//!    from there into the target buffer on the belt's own command encoder.
+//! 2. **`finish`**: Unmaps the chunks written this frame and returns the recorded copies, to be
+//!    submitted ahead of the frame's own command buffers.
+//! 3. **`after_submit`**: Tags those chunks with the number of submissions made so far, and
+//!    registers a callback with `Queue::on_submitted_work_done` recording the submission as
+//!    completed, like the renderer's deletion queue.
+//! 4. **`recall`**: Maps the chunks whose submission has completed again, without blocking.
+//!    Once mapped, they are reused by the next writes.
+//!
+//! A frame's writes land before any of its passes run, and later writes to the same range win,
+//! just like `Queue::write_buffer`. New chunks are only created while every chunk is either
+//! full or still in flight, so after a few frames the belt allocates nothing.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut uploads = UploadBelt::new(&device);
+//! // Every frame:
+//! uploads.recall();
+//! uploads.write(&uniform_buffer, 0, bytemuck::bytes_of(&uniform));
+//! // ... encode the frame ...
+//! queue.submit(uploads.finish().into_iter().chain(std::iter::once(encoder.finish())));
+//! uploads.after_submit(&queue);
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A staging buffer of the [`UploadBelt`], and how much of it is written.
+#[derive(Debug)]
+struct Chunk {
+    /// The buffer, mapped for writing while the chunk is free or written.
+    buffer: wgpu::Buffer,
+
+    /// The offset of the chunk's free space.
+    offset: wgpu::BufferAddress,
+}
+
+impl Chunk {
+    /// Returns the offset `size` bytes can be written at, or `None` if they do not fit.
+    fn allocate(&self, size: wgpu::BufferAddress) -> Option<wgpu::BufferAddress> {
+        let offset = self.offset.next_multiple_of(wgpu::MAP_ALIGNMENT);
+        (offset + size <= self.buffer.size()).then_some(offset)
+    }
+}
+
+/// Streams per-frame buffer writes through reused staging buffers, reclaimed once the
+/// submissions reading them have completed.
+#[derive(Debug)]
+pub struct UploadBelt {
+    /// The device the staging buffers and the copies' command encoder are created with.
+    device: wgpu::Device,
+
+    /// The encoder recording the copies of the current frame, created by the first write.
+    encoder: Option<wgpu::CommandEncoder>,
+
+    /// The mapped chunks written this frame.
+    active: Vec<Chunk>,
+
+    /// The chunks unmapped by `finish`, whose copies are about to be submitted.
+    closed: Vec<Chunk>,
+
+    /// The submitted chunks, each with the number of submissions made when it was submitted,
+    /// oldest first.
+    in_flight: VecDeque<(u64, Chunk)>,
+
+    /// The mapped, empty chunks, filled in by the callbacks of the mappings started in
+    /// `recall`.
+    free: Arc<Mutex<Vec<Chunk>>>,
+
+    /// The number of submissions made.
+    submitted: u64,
+
+    /// The number of submissions the GPU has completed, raised by the callbacks registered in
+    /// `after_submit`.
+    completed: Arc<AtomicU64>,
+}
+
+impl UploadBelt {
+    /// The size of a staging buffer, unless a single write needs a larger one.
+    pub const CHUNK_SIZE: wgpu::BufferAddress = 64 * 1024;
+
+    /// Creates an empty belt, which creates its staging buffers with `device` as needed.
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self {
+            device: device.clone(),
+            encoder: None,
+            active: Vec::new(),
+            closed: Vec::new(),
+            in_flight: VecDeque::new(),
+            free: Arc::default(),
+            submitted: 0,
+            completed: Arc::default(),
+        }
+    }
+
+    /// Writes `data` to `target` at `offset` before the next submitted frame.
+    ///
+    /// As with `Queue::write_buffer`, `offset` and the length of `data` must be multiples of
+    /// `wgpu::COPY_BUFFER_ALIGNMENT`, and `target` must have the `COPY_DST` usage.
+    pub fn write(&mut self, target: &wgpu::Buffer, offset: wgpu::BufferAddress, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        let size = data.len() as wgpu::BufferAddress;
+        let (index, chunk_offset) = self.allocate(size);
+        let chunk = &mut self.active[index];
+        chunk
+            .buffer
+            .slice(chunk_offset..chunk_offset + size)
+            .get_mapped_range_mut()
+            .copy_from_slice(data);
+        chunk.offset = chunk_offset + size;
+
+        let encoder = self.encoder.get_or_insert_with(|| {
+            self.device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Upload Belt Encoder"),
+                })
+        });
+        encoder.copy_buffer_to_buffer(&chunk.buffer, chunk_offset, target, offset, size);
+    }
+
+    /// Unmaps the chunks written since the last call, and returns the copies recorded by
+    /// `write`, or `None` if nothing was written. Submit them before the command buffers
+    /// reading the written buffers, then call `after_submit`.
+    pub fn finish(&mut self) -> Option<wgpu::CommandBuffer> {
+        for chunk in self.active.drain(..) {
+            chunk.buffer.unmap();
+            self.closed.push(chunk);
+        }
+        self.encoder.take().map(|encoder| encoder.finish())
+    }
+
+    /// Counts a submission made to `queue`, which carries the copies returned by the last
+    /// `finish` and completes after the previous submissions.
+    pub fn after_submit(&mut self, queue: &wgpu::Queue) {
+        self.submitted += 1;
+        let submission = self.submitted;
+        self.in_flight
+            .extend(self.closed.drain(..).map(|chunk| (submission, chunk)));
+        let completed = Arc::clone(&self.completed);
+        queue.on_submitted_work_done(move || {
+            completed.fetch_max(submission, Ordering::Release);
+        });
+    }
+
+    /// Maps the chunks whose submissions have completed again, for the next writes to reuse
+    /// once mapped. This never blocks.
+    pub fn recall(&mut self) {
+        if self.in_flight.is_empty() {
+            return;
+        }
+
+        let _ = self.device.poll(wgpu::Maintain::Poll);
+        let completed = self.completed.load(Ordering::Acquire);
+        while self
+            .in_flight
+            .front()
+            .is_some_and(|(submission, _)| *submission <= completed)
+        {
+            let Some((_, chunk)) = self.in_flight.pop_front() else {
+                break;
+            };
+            let free = Arc::clone(&self.free);
+            let buffer = chunk.buffer.clone();
+            chunk
+                .buffer
+                .slice(..)
+                .map_async(wgpu::MapMode::Write, move |result| {
+                    // A chunk that cannot be mapped is dropped, and replaced by a new one.
+                    if result.is_ok() {
+                        if let Ok(mut free) = free.lock() {
+                            free.push(Chunk { buffer, offset: 0 });
+                        }
+                    }
+                });
+        }
+    }
+
+    /// Returns the index into `active` of a chunk with room for `size` bytes, and the offset
+    /// to write them at, taking a free chunk or creating one if no active chunk has room.
+    fn allocate(&mut self, size: wgpu::BufferAddress) -> (usize, wgpu::BufferAddress) {
+        if let Some((index, offset)) = self
+            .active
+            .iter()
+            .enumerate()
+            .find_map(|(index, chunk)| Some((index, chunk.allocate(size)?)))
+        {
+            return (index, offset);
+        }
+
+        let reused = self.free.lock().ok().and_then(|mut free| {
+            let index = free.iter().position(|chunk| chunk.buffer.size() >= size)?;
+            Some(free.swap_remove(index))
+        });
+        let chunk = reused.unwrap_or_else(|| Chunk {
+            buffer: self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Upload Belt Chunk"),
+                size: size
+                    .max(Self::CHUNK_SIZE)
+                    .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT),
+                usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: true,
+            }),
+            offset: 0,
+        });
+        self.active.push(chunk);
+        (self.active.len() - 1, 0)
+    }
+}
@@ -0,0 +1,212 @@
+//! # GPU Errors Module
+//!
+//! The `gpu_errors` module catches the errors `wgpu` reports for invalid or failed GPU
+//! operations, which `wgpu` otherwise raises as panics, and keeps the latest of them for the
+//! GUI's GPU Errors window.
+//!
+//! ## Overview
+//!
+//! - [`GpuErrorHandler::begin`] and [`GpuErrorHandler::end`] wrap an operation, such as
+//!   rendering a frame or resizing the render targets, in validation, out-of-memory, and
+//!   internal error scopes (`wgpu::Device::push_error_scope`), and record the errors they
+//!   caught under the operation's name.
+//! - [`GpuErrorHandler::capture_uncaptured`] installs the device's uncaptured error handler, so
+//!   an error raised outside of any scope is recorded and logged instead of panicking.
+//! - The records are kept in a ring buffer of the latest [`GpuErrorHandler::CAPACITY`]
+//!   distinct errors. An error repeating the previous one, as a broken pipeline does every
+//!   frame, only increments that record's count.
+//!
+//! Backends validating on the CPU report the scopes' errors as soon as they are popped. WebGPU
+//! in the browser reports them later, so unresolved scopes are polled again by
+//! [`GpuErrorHandler::poll`] once per frame.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut gpu_errors = GpuErrorHandler::default();
+//! gpu_errors.capture_uncaptured(&device);
+//!
+//! gpu_errors.begin(&device);
+//! queue.submit(Some(encoder.finish()));
+//! gpu_errors.end(&device, "render frame");
+//!
+//! for record in gpu_errors.records() {
+//!     println!("{}: {}", record.operation, record.message);
+//! }
+//! ```
+
+// Importing `VecDeque` to drop the oldest record as a new one arrives.
+use std::collections::VecDeque;
+
+// Importing `Arc` and `Mutex` to share the records with the uncaptured error handler, which
+// `wgpu` may call from any thread.
+use std::sync::{Arc, Mutex};
+
+// Importing the boxed future of an error scope that has not resolved yet. The futures are not
+// `Send` on WebAssembly.
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+
+/// The kind of a GPU error, after the `wgpu::ErrorFilter` catching it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GpuErrorKind {
+    /// An invalid use of the API, such as a pipeline not matching its shader.
+    Validation,
+
+    /// An allocation the device could not satisfy.
+    OutOfMemory,
+
+    /// Any other failure of the implementation or the driver.
+    Internal,
+}
+
+impl GpuErrorKind {
+    /// Returns the name shown in the user interface.
+    pub fn label(self) -> &'static str {
+        match self {
+            GpuErrorKind::Validation => "Validation",
+            GpuErrorKind::OutOfMemory => "Out of Memory",
+            GpuErrorKind::Internal => "Internal",
+        }
+    }
+}
+
+/// An error reported by `wgpu`, with the operation it occurred in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuErrorRecord {
+    /// The kind of error.
+    pub kind: GpuErrorKind,
+
+    /// The name of the operation the error occurred in, or `"uncaptured"` if it occurred
+    /// outside of any scope.
+    pub operation: &'static str,
+
+    /// The error's description.
+    pub message: String,
+
+    /// How many times in a row the error occurred.
+    pub count: u32,
+}
+
+impl GpuErrorRecord {
+    /// Creates the record of `error`, occurring once in `operation`.
+    fn new(operation: &'static str, error: &wgpu::Error) -> Self {
+        let kind = match error {
+            wgpu::Error::Validation { .. } => GpuErrorKind::Validation,
+            wgpu::Error::OutOfMemory { .. } => GpuErrorKind::OutOfMemory,
+            wgpu::Error::Internal { .. } => GpuErrorKind::Internal,
+        };
+        Self {
+            kind,
+            operation,
+            message: error.to_string(),
+            count: 1,
+        }
+    }
+}
+
+/// Catches the errors of GPU operations and keeps the latest of them.
+#[derive(Default)]
+pub struct GpuErrorHandler {
+    /// The latest distinct errors, oldest first, shared with the uncaptured error handler.
+    records: Arc<Mutex<VecDeque<GpuErrorRecord>>>,
+
+    /// The popped error scopes that have not resolved yet, with their operations.
+    pending: Vec<(&'static str, LocalBoxFuture<'static, Option<wgpu::Error>>)>,
+}
+
+impl GpuErrorHandler {
+    /// The number of distinct errors kept. Older ones are dropped.
+    pub const CAPACITY: usize = 64;
+
+    /// The filters of the scopes pushed by [`GpuErrorHandler::begin`], in push order.
+    const FILTERS: [wgpu::ErrorFilter; 3] = [
+        wgpu::ErrorFilter::Internal,
+        wgpu::ErrorFilter::OutOfMemory,
+        wgpu::ErrorFilter::Validation,
+    ];
+
+    /// Records the errors `device` raises outside of any scope, instead of panicking.
+    pub fn capture_uncaptured(&self, device: &wgpu::Device) {
+        let records = Arc::clone(&self.records);
+        device.on_uncaptured_error(Box::new(move |error| {
+            record(&records, GpuErrorRecord::new("uncaptured", &error));
+        }));
+    }
+
+    /// Starts catching the errors of the operations that follow on `device`, until
+    /// [`GpuErrorHandler::end`].
+    pub fn begin(&self, device: &wgpu::Device) {
+        for filter in Self::FILTERS {
+            device.push_error_scope(filter);
+        }
+    }
+
+    /// Stops catching errors on `device`, and records those caught since the matching
+    /// [`GpuErrorHandler::begin`] under `operation`.
+    pub fn end(&mut self, device: &wgpu::Device, operation: &'static str) {
+        for _ in Self::FILTERS {
+            let scope = device.pop_error_scope().boxed_local();
+            self.pending.push((operation, scope));
+        }
+        self.poll();
+    }
+
+    /// Records the errors of the scopes that resolved since the last call.
+    pub fn poll(&mut self) {
+        let mut context = std::task::Context::from_waker(futures::task::noop_waker_ref());
+        self.pending.retain_mut(|(operation, scope)| {
+            let std::task::Poll::Ready(error) = scope.poll_unpin(&mut context) else {
+                return true;
+            };
+            if let Some(error) = error {
+                record(&self.records, GpuErrorRecord::new(operation, &error));
+            }
+            false
+        });
+    }
+
+    /// Returns the latest distinct errors, oldest first.
+    pub fn records(&self) -> Vec<GpuErrorRecord> {
+        lock(&self.records).iter().cloned().collect()
+    }
+
+    /// Forgets every recorded error.
+    pub fn clear(&self) {
+        lock(&self.records).clear();
+    }
+}
+
+/// Adds `new` to `records`, or counts it as a repetition of the latest record, and logs it the
+/// first time.
+fn record(records: &Mutex<VecDeque<GpuErrorRecord>>, new: GpuErrorRecord) {
+    let mut records = lock(records);
+    if let Some(latest) = records.back_mut() {
+        if latest.kind == new.kind
+            && latest.operation == new.operation
+            && latest.message == new.message
+        {
+            latest.count = latest.count.saturating_add(1);
+            return;
+        }
+    }
+    log::error!(
+        "GPU {} error in {}: {}",
+        new.kind.label(),
+        new.operation,
+        new.message
+    );
+    if records.len() == GpuErrorHandler::CAPACITY {
+        records.pop_front();
+    }
+    records.push_back(new);
+}
+
+/// Locks `records`, recovering them if a thread panicked while holding the lock.
+fn lock(
+    records: &Mutex<VecDeque<GpuErrorRecord>>,
+) -> std::sync::MutexGuard<'_, VecDeque<GpuErrorRecord>> {
+    records
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,329 @@
+//! # GPU Timer Module
+//!
+//! The `gpu_timer` module measures how long the GPU spends on each pass of a frame with
+//! timestamp queries, and reads the measurements back without ever blocking the render thread.
+//!
+//! ## Overview
+//!
+//! [`GpuTimer`] owns a query set of two timestamps for each [`TimedPass`]: one written when
+//! the pass begins, and one when it ends. Passes made of several render passes, such as the
+//! bloom's mip chain, write the first timestamp in their first render pass and the second in
+//! their last. Like the depth probe, a measurement goes through three stages spread over
+//! several frames:
+//!
+//! 1. **`timestamp_writes`**: Returns the writes to attach to a timed render pass, and marks
+//!    the pass as written this frame, or returns `None` while no readback buffer is free.
+//! 2. **`encode_resolve`** and **`after_submit`**: Resolve the timestamps of the passes written
+//!    this frame, copy them into the frame's readback buffer, and start mapping it once the
+//!    frame was submitted.
+//! 3. **`poll`**: In a later frame, checks which mappings finished and converts the
+//!    differences of the timestamps into [`crate::Duration`]s: the time of each pass, and of
+//!    the frame from the beginning of its first pass to the end of its last.
+//!
+//! The timer cycles through [`GpuTimer::LATENCY`] readback buffers, so every frame is measured
+//! while the measurements of the previous frames are still being read back, and each
+//! measurement lags by a few frames. Passes that were skipped in a frame, such as the outline
+//! without a selection, are left out of its measurement. Timestamp queries are optional (see
+//! `GpuCapabilities::supports_timestamp_queries`); on adapters without them, such as WebGL,
+//! there is no timer.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! timer.poll(&device);
+//!
+//! let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+//!     timestamp_writes: timer.timestamp_writes(TimedPass::Scene),
+//!     ..descriptor
+//! });
+//! // ... draw ...
+//! drop(pass);
+//!
+//! timer.encode_resolve(&mut encoder);
+//! queue.submit(std::iter::once(encoder.finish()));
+//! timer.after_submit();
+//!
+//! for (pass, time) in timer.latest_passes() {
+//!     println!("The {} pass took {time:?}", pass.label());
+//! }
+//! ```
+
+// Importing an atomic to mark the passes written this frame from `timestamp_writes`, which
+// only borrows the timer while the render passes are recorded.
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::readback::Readback;
+
+/// A render pass, or a sequence of render passes, of the frame whose GPU time is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimedPass {
+    /// The scene pass, drawing the sky, the scene, and the lines.
+    Scene = 0,
+
+    /// The outline of the selected node, drawn on the HDR scene.
+    Outline = 1,
+
+    /// The bloom's prefilter, downsample, and upsample passes.
+    Bloom = 2,
+
+    /// The pass tonemapping the HDR scene onto the surface.
+    PostProcess = 3,
+
+    /// The GUI pass, drawing `egui` over the scene.
+    #[cfg(feature = "gui")]
+    Gui = 4,
+}
+
+impl TimedPass {
+    /// Every timed pass, in the order they are recorded.
+    pub const ALL: &'static [TimedPass] = &[
+        TimedPass::Scene,
+        TimedPass::Outline,
+        TimedPass::Bloom,
+        TimedPass::PostProcess,
+        #[cfg(feature = "gui")]
+        TimedPass::Gui,
+    ];
+
+    /// Returns the name shown in the frame statistics and the profiler.
+    pub fn label(self) -> &'static str {
+        match self {
+            TimedPass::Scene => "Scene",
+            TimedPass::Outline => "Outline",
+            TimedPass::Bloom => "Bloom",
+            TimedPass::PostProcess => "Post-Processing",
+            #[cfg(feature = "gui")]
+            TimedPass::Gui => "GUI",
+        }
+    }
+
+    /// Returns the pass's bit in the mask of the passes written in a frame.
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// A buffer the timestamps of a frame are copied into and mapped from.
+#[derive(Debug)]
+struct TimestampReadback {
+    /// The buffer, holding the two timestamps of each pass at four times its index.
+    buffer: wgpu::Buffer,
+
+    /// The measurement in the buffer, with the mask of the passes the frame wrote timestamps
+    /// for.
+    readback: Readback<u32>,
+}
+
+/// Measures the GPU time of frames and their passes with timestamp queries.
+#[derive(Debug)]
+pub struct GpuTimer {
+    /// The timestamps of the frame: the beginning of each pass at twice its index, and its
+    /// end right after.
+    query_set: wgpu::QuerySet,
+
+    /// The buffer the timestamps are resolved into, each pass's at its own aligned offset.
+    resolve_buffer: wgpu::Buffer,
+
+    /// The readback buffers, used in turn by consecutive frames.
+    readbacks: Vec<TimestampReadback>,
+
+    /// The index of the readback buffer the current frame's timestamps are copied into.
+    next: usize,
+
+    /// The mask of the passes `timestamp_writes` returned writes for this frame.
+    written: AtomicU32,
+
+    /// The number of nanoseconds per timestamp tick.
+    period: f32,
+
+    /// The most recent measurement.
+    latest: Option<crate::Duration>,
+
+    /// The time of each pass in the most recent measurement.
+    latest_passes: Vec<(TimedPass, crate::Duration)>,
+}
+
+impl GpuTimer {
+    /// The number of readback buffers, and so the number of frames a measurement can take to
+    /// be read back before frames go unmeasured.
+    pub const LATENCY: usize = 3;
+
+    /// The number of timestamps: two for each timed pass.
+    const QUERY_COUNT: u32 = 2 * TimedPass::ALL.len() as u32;
+
+    /// The size of the two timestamps of a pass, in bytes.
+    const PASS_SIZE: wgpu::BufferAddress = 2 * std::mem::size_of::<u64>() as wgpu::BufferAddress;
+
+    /// Creates a timer. `device` must have the `TIMESTAMP_QUERY` feature.
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Frame Timestamp Query Set"),
+            ty: wgpu::QueryType::Timestamp,
+            count: Self::QUERY_COUNT,
+        });
+        // Resolves must start at aligned offsets, and only the written passes are resolved.
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Timestamp Resolve Buffer"),
+            size: TimedPass::ALL.len() as wgpu::BufferAddress
+                * wgpu::QUERY_RESOLVE_BUFFER_ALIGNMENT,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readbacks = (0..Self::LATENCY)
+            .map(|_| TimestampReadback {
+                buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("Frame Timestamp Readback Buffer"),
+                    size: TimedPass::ALL.len() as wgpu::BufferAddress * Self::PASS_SIZE,
+                    usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                    mapped_at_creation: false,
+                }),
+                readback: Readback::default(),
+            })
+            .collect();
+
+        Self {
+            query_set,
+            resolve_buffer,
+            readbacks,
+            next: 0,
+            written: AtomicU32::new(0),
+            period: queue.get_timestamp_period(),
+            latest: None,
+            latest_passes: Vec::new(),
+        }
+    }
+
+    /// Returns the GPU time of the most recently measured frame.
+    pub fn latest(&self) -> Option<crate::Duration> {
+        self.latest
+    }
+
+    /// Returns the time of each pass in the most recently measured frame, in the order the
+    /// passes are recorded.
+    pub fn latest_passes(&self) -> &[(TimedPass, crate::Duration)] {
+        &self.latest_passes
+    }
+
+    /// Returns the timestamp writes of `pass`, written when it begins and ends, and marks it
+    /// as measured this frame. Returns `None` while every readback buffer is in use, in which
+    /// case the frame is not measured.
+    pub fn timestamp_writes(&self, pass: TimedPass) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
+        if !self.readbacks[self.next].readback.is_idle() {
+            return None;
+        }
+        self.written.fetch_or(pass.bit(), Ordering::Relaxed);
+        let begin = 2 * pass as u32;
+        Some(wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(begin),
+            end_of_pass_write_index: Some(begin + 1),
+        })
+    }
+
+    /// Records the resolve and the copy of the frame's timestamps, after the last pass
+    /// returned by `timestamp_writes` was recorded.
+    pub fn encode_resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let written = self.written.swap(0, Ordering::Relaxed);
+        let readback = &mut self.readbacks[self.next];
+        if !readback.readback.is_idle() || written == 0 {
+            return;
+        }
+
+        for &pass in TimedPass::ALL {
+            if written & pass.bit() == 0 {
+                continue;
+            }
+            let index = pass as u32;
+            let offset = wgpu::BufferAddress::from(index) * wgpu::QUERY_RESOLVE_BUFFER_ALIGNMENT;
+            encoder.resolve_query_set(
+                &self.query_set,
+                2 * index..2 * index + 2,
+                &self.resolve_buffer,
+                offset,
+            );
+            encoder.copy_buffer_to_buffer(
+                &self.resolve_buffer,
+                offset,
+                &readback.buffer,
+                wgpu::BufferAddress::from(index) * Self::PASS_SIZE,
+                Self::PASS_SIZE,
+            );
+        }
+        readback.readback.copied(readback.buffer.clone(), written);
+    }
+
+    /// Starts mapping the readback buffer once the frame containing the copy was submitted,
+    /// and moves on to the next buffer.
+    pub fn after_submit(&mut self) {
+        let readback = &mut self.readbacks[self.next].readback;
+        // A buffer still mapping from an earlier frame received no copy this frame.
+        if readback.is_mapping() {
+            return;
+        }
+
+        readback.after_submit();
+        if readback.is_mapping() {
+            self.next = (self.next + 1) % Self::LATENCY;
+        }
+    }
+
+    /// Finishes the measurements in flight whose buffers have been mapped, oldest first, so
+    /// the newest finished one is kept. This never blocks.
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        // The buffer the next frame uses is the one mapped longest ago.
+        for offset in 0..Self::LATENCY {
+            let index = (self.next + offset) % Self::LATENCY;
+            let result = self.readbacks[index]
+                .readback
+                .poll(device, |data, written| {
+                    let timestamps: Vec<u64> = data
+                        .chunks_exact(8)
+                        .map(bytemuck::pod_read_unaligned)
+                        .collect();
+                    (timestamps, written)
+                });
+            match result {
+                Some(Ok((timestamps, written))) => self.finish_measurement(&timestamps, written),
+                Some(Err(error)) => log::warn!("Frame timestamp readback failed: {error}"),
+                None => {}
+            }
+        }
+    }
+
+    /// Converts the `timestamps` of the passes in the mask `written` into the latest
+    /// measurement.
+    fn finish_measurement(&mut self, timestamps: &[u64], written: u32) {
+        let passes: Vec<_> = TimedPass::ALL
+            .iter()
+            .copied()
+            .filter(|pass| written & pass.bit() != 0)
+            .collect();
+        let (Some(first), Some(last)) = (passes.first(), passes.last()) else {
+            return;
+        };
+        if let Some(frame_time) = self.elapsed(
+            timestamps[2 * *first as usize],
+            timestamps[2 * *last as usize + 1],
+        ) {
+            self.latest = Some(frame_time);
+        }
+        self.latest_passes = passes
+            .into_iter()
+            .filter_map(|pass| {
+                let begin = 2 * pass as usize;
+                let elapsed = self.elapsed(timestamps[begin], timestamps[begin + 1])?;
+                Some((pass, elapsed))
+            })
+            .collect();
+    }
+
+    /// Converts the ticks from `begin` to `end` into a duration.
+    ///
+    /// Timestamps may wrap or be reset between passes on some drivers, which would give a
+    /// nonsensical time, so such measurements give `None`.
+    fn elapsed(&self, begin: u64, end: u64) -> Option<crate::Duration> {
+        let nanoseconds =
+            end.checked_sub(begin).filter(|&ticks| ticks > 0)? as f64 * f64::from(self.period);
+        Some(crate::Duration::from_nanos(nanoseconds as u64))
+    }
+}
@@ -0,0 +1,285 @@
+//! # Input Module
+//!
+//! The `input` module coalesces the pointer events of high-polling-rate mice, so they cost
+//! one pass of event handling per frame instead of one per event, and keeps the state of the
+//! keyboard and mouse for code that queries it instead of matching events.
+//!
+//! ## Overview
+//!
+//! - [`InputState`] tracks the held keys and mouse buttons, those pressed since the last
+//!   frame, the cursor position, and the scroll and raw mouse motion of the frame. The `App`
+//!   updates it from `window_event` and `device_event`, and its `ActionMap` decides from it
+//!   which actions were triggered. Presses the GUI used are not recorded,
+//!   while releases always are, so no key stays held after being released over a widget.
+//! - A mouse polled at 1000 Hz or more reports a `CursorMoved` event every millisecond, many
+//!   times per frame. [`InputCoalescer`] keeps only the latest of them and hands it out once
+//!   before the next other event, so `egui`, the depth probe, and the camera path editor see
+//!   one move per frame. Because it is flushed before button, key, and touch events, a click
+//!   is always handled at the position it happened at.
+//! - Redraws are requested at most once per frame, so a burst of events does not request the
+//!   same frame, and re-run the GUI, over and over.
+//! - The cameras look around with the raw mouse motion (`DeviceEvent::MouseMotion`) where the
+//!   platform reports it. Raw deltas are neither rounded to whole pixels nor stopped by the
+//!   window edge, so fast turns in the FPS camera stay precise. The coalesced cursor
+//!   position is used as a fallback on platforms without raw motion.
+//! - While the FPS camera looks around, [`set_mouse_look`] hides the cursor and locks it in
+//!   place, so it neither wanders over the GUI nor leaves the window during a long turn.
+//! - [`PointerEventCounts`] tells how many pointer events the last frame received and how many
+//!   were handled, shown in the input settings.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! input_state.handle_window_event(&event, gui_response.consumed);
+//! // Once per frame:
+//! if input_state.was_key_pressed(KeyCode::Escape) {
+//!     event_loop.exit();
+//! }
+//! input_state.end_frame();
+//!
+//! if matches!(event, WindowEvent::CursorMoved { .. }) {
+//!     coalescer.defer_cursor(event);
+//!     return;
+//! }
+//! if let Some(moved) = coalescer.take_cursor() {
+//!     handle(moved);
+//! }
+//! handle(event);
+//! ```
+
+// Importing `HashSet` to hold the keys and mouse buttons that are down.
+use std::collections::HashSet;
+
+use winit::event::{DeviceEvent, ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::keyboard::{KeyCode, PhysicalKey};
+use winit::window::{CursorGrabMode, Window};
+
+/// The state of the keyboard and mouse, updated from window and device events.
+#[derive(Debug, Default, Clone)]
+pub struct InputState {
+    /// The keys that are down.
+    keys_held: HashSet<KeyCode>,
+
+    /// The keys pressed since the last frame, without repeats.
+    keys_pressed: HashSet<KeyCode>,
+
+    /// The mouse buttons that are down.
+    buttons_held: HashSet<MouseButton>,
+
+    /// The mouse buttons pressed since the last frame.
+    buttons_pressed: HashSet<MouseButton>,
+
+    /// The cursor's position in physical pixels, or `None` while it is outside the window.
+    cursor_position: Option<(f64, f64)>,
+
+    /// The distance scrolled since the last frame, in lines.
+    scroll_delta: crate::math::Vec2,
+
+    /// The raw mouse motion since the last frame, in mouse counts.
+    mouse_delta: crate::math::Vec2,
+}
+
+impl InputState {
+    /// The pixels of a touchpad's scroll that count as one line.
+    pub const PIXELS_PER_LINE: f32 = 50.0;
+
+    /// Records `event`. If `captured`, the GUI used it: presses and scrolling are ignored, so
+    /// typing into a text field or scrolling a list does not reach the scene, but releases,
+    /// cursor moves, and focus changes are still recorded.
+    pub fn handle_window_event(&mut self, event: &WindowEvent, captured: bool) {
+        match event {
+            WindowEvent::KeyboardInput { event, .. } => {
+                let PhysicalKey::Code(key) = event.physical_key else {
+                    return;
+                };
+                match event.state {
+                    ElementState::Pressed if !captured => {
+                        if self.keys_held.insert(key) && !event.repeat {
+                            self.keys_pressed.insert(key);
+                        }
+                    }
+                    ElementState::Pressed => {}
+                    ElementState::Released => {
+                        self.keys_held.remove(&key);
+                    }
+                }
+            }
+            WindowEvent::MouseInput { state, button, .. } => match state {
+                ElementState::Pressed if !captured => {
+                    self.buttons_held.insert(*button);
+                    self.buttons_pressed.insert(*button);
+                }
+                ElementState::Pressed => {}
+                ElementState::Released => {
+                    self.buttons_held.remove(button);
+                }
+            },
+            WindowEvent::MouseWheel { delta, .. } if !captured => {
+                self.scroll_delta += match delta {
+                    MouseScrollDelta::LineDelta(x, y) => crate::math::vec2(*x, *y),
+                    MouseScrollDelta::PixelDelta(position) => {
+                        crate::math::vec2(position.x as f32, position.y as f32)
+                            / Self::PIXELS_PER_LINE
+                    }
+                };
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor_position = Some((position.x, position.y));
+            }
+            WindowEvent::CursorLeft { .. } => self.cursor_position = None,
+            // A release outside the window may never arrive.
+            WindowEvent::Focused(false) => {
+                self.keys_held.clear();
+                self.buttons_held.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Records the raw mouse motion of `event`.
+    pub fn handle_device_event(&mut self, event: &DeviceEvent) {
+        if let DeviceEvent::MouseMotion { delta } = event {
+            self.mouse_delta += crate::math::vec2(delta.0 as f32, delta.1 as f32);
+        }
+    }
+
+    /// Starts a new frame: the presses, scrolling, and mouse motion restart from nothing.
+    pub fn end_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.buttons_pressed.clear();
+        self.scroll_delta = crate::math::vec2(0.0, 0.0);
+        self.mouse_delta = crate::math::vec2(0.0, 0.0);
+    }
+
+    /// Returns `true` if `key` is down.
+    pub fn is_key_held(&self, key: KeyCode) -> bool {
+        self.keys_held.contains(&key)
+    }
+
+    /// Returns `true` if `key` was pressed since the last frame. Repeats do not count.
+    pub fn was_key_pressed(&self, key: KeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    /// Returns the keys pressed since the last frame, in no particular order.
+    pub fn keys_pressed(&self) -> impl Iterator<Item = KeyCode> + '_ {
+        self.keys_pressed.iter().copied()
+    }
+
+    /// Returns `true` if `button` is down.
+    pub fn is_button_held(&self, button: MouseButton) -> bool {
+        self.buttons_held.contains(&button)
+    }
+
+    /// Returns `true` if `button` was pressed since the last frame.
+    pub fn was_button_pressed(&self, button: MouseButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    /// Returns the mouse buttons pressed since the last frame, in no particular order.
+    pub fn buttons_pressed(&self) -> impl Iterator<Item = MouseButton> + '_ {
+        self.buttons_pressed.iter().copied()
+    }
+
+    /// Returns the cursor's position in physical pixels, or `None` while it is outside the
+    /// window.
+    pub fn cursor_position(&self) -> Option<(f64, f64)> {
+        self.cursor_position
+    }
+
+    /// Returns the distance scrolled since the last frame, in lines. Positive values scroll
+    /// up and to the right.
+    pub fn scroll_delta(&self) -> crate::math::Vec2 {
+        self.scroll_delta
+    }
+
+    /// Returns the raw mouse motion since the last frame, in mouse counts, where the platform
+    /// reports it.
+    pub fn mouse_delta(&self) -> crate::math::Vec2 {
+        self.mouse_delta
+    }
+}
+
+/// The pointer events received and handled during one frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PointerEventCounts {
+    /// The `CursorMoved` events reported by the platform.
+    pub received: u32,
+
+    /// The `CursorMoved` events handled after coalescing.
+    pub handled: u32,
+}
+
+/// Merges the cursor moves of each frame and limits redraw requests to one per frame.
+#[derive(Debug, Default)]
+pub struct InputCoalescer {
+    /// The latest cursor move that has not been handled yet.
+    pending_cursor: Option<WindowEvent>,
+
+    /// Whether a redraw was requested since the last frame started.
+    redraw_requested: bool,
+
+    /// The pointer events of the current frame.
+    counts: PointerEventCounts,
+
+    /// The pointer events of the last frame.
+    last_frame: PointerEventCounts,
+}
+
+impl InputCoalescer {
+    /// Stores `event`, a `CursorMoved` event, replacing the cursor move not yet handled.
+    pub fn defer_cursor(&mut self, event: WindowEvent) {
+        debug_assert!(matches!(event, WindowEvent::CursorMoved { .. }));
+        self.counts.received += 1;
+        self.pending_cursor = Some(event);
+    }
+
+    /// Takes the latest cursor move not yet handled, if any.
+    pub fn take_cursor(&mut self) -> Option<WindowEvent> {
+        let event = self.pending_cursor.take()?;
+        self.counts.handled += 1;
+        Some(event)
+    }
+
+    /// Returns `true` if a redraw should be requested, which is only the first time each frame.
+    pub fn request_redraw(&mut self) -> bool {
+        !std::mem::replace(&mut self.redraw_requested, true)
+    }
+
+    /// Forgets the redraw requested this frame, so the next one is requested even if the
+    /// platform dropped it, as it may while the window is hidden.
+    pub fn cancel_redraw(&mut self) {
+        self.redraw_requested = false;
+    }
+
+    /// Starts a new frame: redraws may be requested again and the event counts restart.
+    pub fn begin_frame(&mut self) {
+        self.redraw_requested = false;
+        self.last_frame = std::mem::take(&mut self.counts);
+    }
+
+    /// Returns the pointer events received and handled during the last frame.
+    pub fn last_frame(&self) -> PointerEventCounts {
+        self.last_frame
+    }
+}
+
+/// Hides the cursor and locks it in place while `enabled`, for mouse look, and releases and
+/// shows it again otherwise.
+///
+/// Platforms that cannot lock the cursor confine it to the window instead. If neither is
+/// supported, the cursor is only hidden, and turning falls back to the cursor movement until
+/// it reaches the window edge.
+pub fn set_mouse_look(window: &Window, enabled: bool) {
+    let grab = if enabled {
+        window
+            .set_cursor_grab(CursorGrabMode::Locked)
+            .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined))
+    } else {
+        window.set_cursor_grab(CursorGrabMode::None)
+    };
+    if let Err(error) = grab {
+        log::debug!("Could not change the cursor grab: {error}");
+    }
+    window.set_cursor_visible(!enabled);
+}
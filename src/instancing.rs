@@ -0,0 +1,134 @@
+//! # Instancing Module
+//!
+//! The `instancing` module draws the scene's meshes many times with one draw call each. Every
+//! [`Instance`] is a model matrix and a color in a vertex buffer stepped per instance, read by
+//! `instanced.wgsl`, so thousands of copies cost one buffer upload instead of one uniform
+//! buffer, bind group, and draw call per copy.
+//!
+//! ## Overview
+//!
+//! - [`Instance`] is one copy of the meshes, placed in the space of the node drawing them.
+//! - [`Scene::set_instances`](crate::scene::Scene::set_instances) uploads the instances. While
+//!   any are set, each node drawing a mesh draws it once per instance, with the instanced
+//!   pipeline.
+//! - [`grid_instances`] lays instances out on a square grid, for the [`GridDemo`].
+//! - [`GridDemo`] draws the same grid of triangles either instanced or as one scene graph node
+//!   per triangle, each with its own uniform buffer, so the frame statistics show the
+//!   difference.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let instances = grid_instances(GridDemo::SIDE, GridDemo::SPACING);
+//! scene.set_instances(&device, &instances);
+//! ```
+
+/// The WGSL source of the scene's shader with per-instance transforms and colors.
+pub const INSTANCED_SHADER_SOURCE: &str = include_str!("instanced.wgsl");
+
+/// One copy of the scene's meshes, drawn by [`Scene::set_instances`](crate::scene::Scene::set_instances).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Instance {
+    /// The transform into the space of the node drawing the mesh, column by column.
+    pub model: [f32; 16],
+
+    /// The color multiplied with the vertex colors, as RGBA.
+    pub color: [f32; 4],
+}
+
+impl Instance {
+    /// Creates an instance placed by `model` and tinted with `color`.
+    pub fn new(model: crate::math::Mat4, color: [f32; 4]) -> Self {
+        Self {
+            model: crate::math::to_cols_array(&model),
+            color,
+        }
+    }
+
+    /// Returns the instance's transform.
+    pub fn model(&self) -> crate::math::Mat4 {
+        crate::math::from_cols_array(&self.model)
+    }
+
+    /// Returns the per-instance vertex attributes, matching `InstanceInput` in the shader. They
+    /// follow the attributes of [`Vertex`](crate::vertex::Vertex), at locations 4 to 8.
+    pub fn vertex_attributes() -> Vec<wgpu::VertexAttribute> {
+        wgpu::vertex_attr_array![
+            4 => Float32x4,
+            5 => Float32x4,
+            6 => Float32x4,
+            7 => Float32x4,
+            8 => Float32x4
+        ]
+        .to_vec()
+    }
+
+    /// Returns the instance buffer layout for the given `attributes`.
+    pub fn description(attributes: &[wgpu::VertexAttribute]) -> wgpu::VertexBufferLayout {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Instance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes,
+        }
+    }
+}
+
+/// Returns `side` × `side` instances on a square grid in the XY plane, `spacing` apart and
+/// centered on the origin, with colors blending across the grid.
+pub fn grid_instances(side: u32, spacing: f32) -> Vec<Instance> {
+    let offset = (side.max(1) - 1) as f32 * spacing * 0.5;
+    let fraction = |index: u32| index as f32 / side.saturating_sub(1).max(1) as f32;
+    (0..side)
+        .flat_map(|row| (0..side).map(move |column| (row, column)))
+        .map(|(row, column)| {
+            let position = crate::math::vec3(
+                column as f32 * spacing - offset,
+                row as f32 * spacing - offset,
+                0.0,
+            );
+            let model = crate::math::translation(&position);
+            let color = [fraction(column), fraction(row), 1.0 - fraction(column), 1.0];
+            Instance::new(model, color)
+        })
+        .collect()
+}
+
+/// How the instancing demo draws its grid of triangles, selected in the settings window.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GridDemo {
+    /// The demo is off.
+    #[default]
+    Off,
+
+    /// The grid is drawn with one instanced draw call.
+    Instanced,
+
+    /// The grid is drawn with one scene graph node per triangle, each a draw call with its own
+    /// uniform buffer.
+    PerDrawUniforms,
+}
+
+impl GridDemo {
+    /// Every mode, in the order they are offered in the user interface.
+    pub const ALL: [GridDemo; 3] = [
+        GridDemo::Off,
+        GridDemo::Instanced,
+        GridDemo::PerDrawUniforms,
+    ];
+
+    /// The number of triangles along each side of the grid.
+    pub const SIDE: u32 = 64;
+
+    /// The distance between neighboring triangles, which span two units.
+    pub const SPACING: f32 = 2.5;
+
+    /// Returns the name shown in the user interface.
+    pub fn label(self) -> &'static str {
+        match self {
+            GridDemo::Off => "Off",
+            GridDemo::Instanced => "Instanced",
+            GridDemo::PerDrawUniforms => "Per-Draw Uniforms",
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! # Interop Module
+//!
+//! The `interop` module exports the rendered frame as a shared GPU texture, so that other
+//! processes, such as compositors, streaming tools, or video encoders, can consume the frames
+//! without reading them back to the CPU. It is only compiled with the `interop` feature.
+//!
+//! ## Overview
+//!
+//! - [`SharedFrame`] is a texture allocated in exportable memory, which the renderer copies
+//!   every frame into once exporting is enabled with `Renderer::export_frames`.
+//! - [`SharedHandle`] is the platform handle to that memory, passed to the consumer along with
+//!   the frame's size and layout.
+//! - [`SharedFrame::completed_frames`] counts the frames whose copy the GPU has finished, so
+//!   the consumer knows when the contents are complete.
+//!
+//! Only DMA-BUF on Linux with the Vulkan backend is implemented. The Vulkan device is then
+//! created with the `VK_KHR_external_memory_fd` and `VK_EXT_external_memory_dma_buf`
+//! extensions. Exporting IOSurfaces on macOS and DXGI shared handles on Windows is not
+//! implemented yet, so `Renderer::export_frames` returns [`InteropError::Unsupported`] there.
+//!
+//! The shared texture is recreated when the window is resized, with a new handle.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! renderer.export_frames()?;
+//!
+//! // After rendering a frame:
+//! if let Some(frame) = renderer.shared_frame() {
+//!     if let SharedHandle::DmaBuf(dma_buf) = frame.handle() {
+//!         send_to_compositor(dma_buf.fd.try_clone()?, dma_buf.fourcc, dma_buf.stride);
+//!     }
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Errors produced while exporting frames.
+#[derive(Debug, thiserror::Error)]
+pub enum InteropError {
+    /// The platform, backend, or device cannot export shared textures.
+    #[error("shared textures are not supported: {0}")]
+    Unsupported(&'static str),
+
+    /// The surface format has no equivalent for the shared texture's consumers.
+    #[error("frames in {0:?} cannot be shared")]
+    UnsupportedFormat(wgpu::TextureFormat),
+
+    /// The surface textures cannot be copied from, which is needed to export them.
+    #[error("the surface textures cannot be copied")]
+    SurfaceNotCopyable,
+
+    /// A Vulkan call failed while creating the shared texture.
+    #[cfg(target_os = "linux")]
+    #[error("failed to create the shared texture: {0}")]
+    Vulkan(#[from] ash::vk::Result),
+}
+
+/// A DMA-BUF holding a frame, in a single plane.
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct DmaBuf {
+    /// The DMA-BUF's file descriptor. Duplicate it with `try_clone` to pass it on.
+    pub fd: std::os::fd::OwnedFd,
+
+    /// The DRM fourcc code of the pixel format.
+    pub fourcc: u32,
+
+    /// The DRM format modifier; the texture is always linear.
+    pub modifier: u64,
+
+    /// The offset of the first row, in bytes.
+    pub offset: u64,
+
+    /// The distance between rows, in bytes.
+    pub stride: u64,
+}
+
+/// The platform handle to a shared frame's memory.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SharedHandle {
+    /// A Linux DMA-BUF.
+    #[cfg(target_os = "linux")]
+    DmaBuf(DmaBuf),
+}
+
+/// A texture in exportable memory that the rendered frames are copied into.
+#[derive(Debug)]
+pub struct SharedFrame {
+    /// The texture the frames are copied into.
+    texture: wgpu::Texture,
+
+    /// The handle to the texture's memory.
+    handle: SharedHandle,
+
+    /// The number of frames whose copy the GPU has finished.
+    completed_frames: Arc<AtomicU64>,
+}
+
+impl SharedFrame {
+    /// Creates a shared texture of `width` by `height` texels in `format`, the surface's.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InteropError` if the device cannot export textures in `format`.
+    pub fn new(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> Result<Self, InteropError> {
+        #[cfg(target_os = "linux")]
+        {
+            let (texture, handle) = vulkan::create_texture(device, width, height, format)?;
+            Ok(Self {
+                texture,
+                handle: SharedHandle::DmaBuf(handle),
+                completed_frames: Arc::default(),
+            })
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (device, width, height, format);
+            Err(InteropError::Unsupported(
+                "only DMA-BUF export on Linux is implemented",
+            ))
+        }
+    }
+
+    /// Returns the handle to pass to the consumer.
+    pub fn handle(&self) -> &SharedHandle {
+        &self.handle
+    }
+
+    /// Returns the texture's size, in texels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.texture.width(), self.texture.height())
+    }
+
+    /// Returns the texture's format.
+    pub fn format(&self) -> wgpu::TextureFormat {
+        self.texture.format()
+    }
+
+    /// Returns the number of frames whose copy into the texture the GPU has finished.
+    pub fn completed_frames(&self) -> u64 {
+        self.completed_frames.load(Ordering::Acquire)
+    }
+
+    /// Records a copy of the frame in `source`, the surface texture, into the shared texture.
+    pub fn encode_copy(&self, encoder: &mut wgpu::CommandEncoder, source: &wgpu::Texture) {
+        encoder.copy_texture_to_texture(
+            source.as_image_copy(),
+            self.texture.as_image_copy(),
+            self.texture.size(),
+        );
+    }
+
+    /// Counts the copied frame as completed once the GPU has finished the submission.
+    pub fn after_submit(&self, queue: &wgpu::Queue) {
+        let completed_frames = Arc::clone(&self.completed_frames);
+        queue.on_submitted_work_done(move || {
+            completed_frames.fetch_add(1, Ordering::Release);
+        });
+    }
+}
+
+/// Requests a device able to export DMA-BUFs, if the adapter is a Vulkan adapter supporting
+/// it. Returns `None` to fall back to a regular device otherwise.
+#[cfg(target_os = "linux")]
+pub(crate) fn request_device(
+    adapter: &wgpu::Adapter,
+    descriptor: &wgpu::DeviceDescriptor,
+) -> Option<(wgpu::Device, wgpu::Queue)> {
+    match vulkan::request_device(adapter, descriptor) {
+        Ok(device) => device,
+        Err(error) => {
+            log::warn!("Failed to create a device exporting DMA-BUFs: {error}");
+            None
+        }
+    }
+}
+
+/// The Vulkan implementation of DMA-BUF export.
+#[cfg(target_os = "linux")]
+mod vulkan {
+    use std::os::fd::{FromRawFd, OwnedFd};
+
+    use ash::vk;
+    use wgpu::hal;
+
+    use super::{DmaBuf, InteropError};
+
+    /// The device extensions needed to export DMA-BUFs.
+    const EXTENSIONS: [&std::ffi::CStr; 2] = [
+        ash::khr::external_memory_fd::NAME,
+        ash::ext::external_memory_dma_buf::NAME,
+    ];
+
+    /// The DRM format modifier of linear textures.
+    const DRM_FORMAT_MOD_LINEAR: u64 = 0;
+
+    /// Returns the DRM fourcc code for the little-endian bytes `code`.
+    const fn fourcc(code: &[u8; 4]) -> u32 {
+        u32::from_le_bytes(*code)
+    }
+
+    /// Returns the Vulkan format and DRM fourcc code of `format`.
+    fn map_format(format: wgpu::TextureFormat) -> Option<(vk::Format, u32)> {
+        use wgpu::TextureFormat;
+
+        Some(match format {
+            TextureFormat::Bgra8Unorm => (vk::Format::B8G8R8A8_UNORM, fourcc(b"AR24")),
+            TextureFormat::Bgra8UnormSrgb => (vk::Format::B8G8R8A8_SRGB, fourcc(b"AR24")),
+            TextureFormat::Rgba8Unorm => (vk::Format::R8G8B8A8_UNORM, fourcc(b"AB24")),
+            TextureFormat::Rgba8UnormSrgb => (vk::Format::R8G8B8A8_SRGB, fourcc(b"AB24")),
+            TextureFormat::Rgb10a2Unorm => (vk::Format::A2B10G10R10_UNORM_PACK32, fourcc(b"AB30")),
+            TextureFormat::Rgba16Float => (vk::Format::R16G16B16A16_SFLOAT, fourcc(b"AB4H")),
+            _ => return None,
+        })
+    }
+
+    /// Creates a Vulkan device with the export extensions enabled, as wgpu-hal would create
+    /// it otherwise. Returns `Ok(None)` if the adapter is not a Vulkan adapter supporting them.
+    pub(super) fn request_device(
+        adapter: &wgpu::Adapter,
+        descriptor: &wgpu::DeviceDescriptor,
+    ) -> Result<Option<(wgpu::Device, wgpu::Queue)>, Box<dyn std::error::Error>> {
+        // SAFETY: The raw device is created from this adapter, with the queue family,
+        // extensions, and features wgpu-hal requires, and handed over to wgpu-hal, which
+        // takes ownership of it.
+        let open_device = unsafe {
+            adapter.as_hal::<hal::api::Vulkan, _, _>(|hal_adapter| {
+                let Some(hal_adapter) = hal_adapter else {
+                    return Ok(None);
+                };
+                let capabilities = hal_adapter.physical_device_capabilities();
+                if !EXTENSIONS
+                    .iter()
+                    .all(|extension| capabilities.supports_extension(extension))
+                {
+                    return Ok(None);
+                }
+
+                let features = descriptor.required_features;
+                let mut extensions = hal_adapter.required_device_extensions(features);
+                extensions.extend(EXTENSIONS);
+                let mut physical_features =
+                    hal_adapter.physical_device_features(&extensions, features);
+                let family_index = 0;
+                let family_infos = [vk::DeviceQueueCreateInfo::default()
+                    .queue_family_index(family_index)
+                    .queue_priorities(&[1.0])];
+                let extension_names = extensions
+                    .iter()
+                    .map(|extension| extension.as_ptr())
+                    .collect::<Vec<_>>();
+                let info = physical_features.add_to_device_create(
+                    vk::DeviceCreateInfo::default()
+                        .queue_create_infos(&family_infos)
+                        .enabled_extension_names(&extension_names),
+                );
+                let raw_device = hal_adapter.shared_instance().raw_instance().create_device(
+                    hal_adapter.raw_physical_device(),
+                    &info,
+                    None,
+                )?;
+                let open_device = hal_adapter.device_from_raw(
+                    raw_device,
+                    None,
+                    &extensions,
+                    features,
+                    &descriptor.memory_hints,
+                    family_index,
+                    0,
+                )?;
+                Ok::<_, Box<dyn std::error::Error>>(Some(open_device))
+            })?
+        };
+        let Some(open_device) = open_device else {
+            return Ok(None);
+        };
+
+        // SAFETY: The device was opened from this adapter's hal adapter above.
+        let device = unsafe { adapter.create_device_from_hal(open_device, descriptor, None)? };
+        log::info!("Created a Vulkan device able to export DMA-BUFs");
+        Ok(Some(device))
+    }
+
+    /// Creates a linear texture in exportable memory, and exports the memory as a DMA-BUF.
+    pub(super) fn create_texture(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> Result<(wgpu::Texture, DmaBuf), InteropError> {
+        let (vk_format, fourcc) =
+            map_format(format).ok_or(InteropError::UnsupportedFormat(format))?;
+
+        // SAFETY: The image and memory are created from the device's raw handles, bound
+        // before use, and destroyed by the drop callback once wgpu is done with the texture.
+        let created = unsafe {
+            device.as_hal::<hal::api::Vulkan, _, _>(|hal_device| {
+                let hal_device = hal_device.ok_or(InteropError::Unsupported(
+                    "the device is not a Vulkan device",
+                ))?;
+                if !EXTENSIONS
+                    .iter()
+                    .all(|extension| hal_device.enabled_device_extensions().contains(extension))
+                {
+                    return Err(InteropError::Unsupported(
+                        "the device was created without the DMA-BUF export extensions",
+                    ));
+                }
+                let raw_device = hal_device.raw_device().clone();
+                let instance = hal_device.shared_instance().raw_instance();
+
+                let mut external_info = vk::ExternalMemoryImageCreateInfo::default()
+                    .handle_types(vk::ExternalMemoryHandleTypeFlags::DMA_BUF_EXT);
+                let image_info = vk::ImageCreateInfo::default()
+                    .image_type(vk::ImageType::TYPE_2D)
+                    .format(vk_format)
+                    .extent(vk::Extent3D {
+                        width,
+                        height,
+                        depth: 1,
+                    })
+                    .mip_levels(1)
+                    .array_layers(1)
+                    .samples(vk::SampleCountFlags::TYPE_1)
+                    .tiling(vk::ImageTiling::LINEAR)
+                    .usage(vk::ImageUsageFlags::TRANSFER_DST)
+                    .sharing_mode(vk::SharingMode::EXCLUSIVE)
+                    .initial_layout(vk::ImageLayout::UNDEFINED)
+                    .push_next(&mut external_info);
+                let image = raw_device.create_image(&image_info, None)?;
+
+                let requirements = raw_device.get_image_memory_requirements(image);
+                let memory_properties = instance
+                    .get_physical_device_memory_properties(hal_device.raw_physical_device());
+                let memory_type = memory_properties
+                    .memory_types_as_slice()
+                    .iter()
+                    .enumerate()
+                    .position(|(index, memory_type)| {
+                        requirements.memory_type_bits & (1 << index) != 0
+                            && memory_type
+                                .property_flags
+                                .contains(vk::MemoryPropertyFlags::DEVICE_LOCAL)
+                    })
+                    .or_else(|| {
+                        (0..memory_properties.memory_type_count)
+                            .position(|index| requirements.memory_type_bits & (1 << index) != 0)
+                    });
+                let Some(memory_type) = memory_type else {
+                    raw_device.destroy_image(image, None);
+                    return Err(InteropError::Unsupported(
+                        "no memory type can hold the shared texture",
+                    ));
+                };
+
+                let mut export_info = vk::ExportMemoryAllocateInfo::default()
+                    .handle_types(vk::ExternalMemoryHandleTypeFlags::DMA_BUF_EXT);
+                let mut dedicated_info = vk::MemoryDedicatedAllocateInfo::default().image(image);
+                let allocate_info = vk::MemoryAllocateInfo::default()
+                    .allocation_size(requirements.size)
+                    .memory_type_index(memory_type as u32)
+                    .push_next(&mut export_info)
+                    .push_next(&mut dedicated_info);
+                let memory = match raw_device.allocate_memory(&allocate_info, None) {
+                    Ok(memory) => memory,
+                    Err(error) => {
+                        raw_device.destroy_image(image, None);
+                        return Err(error.into());
+                    }
+                };
+                let destroy = {
+                    let raw_device = raw_device.clone();
+                    move || {
+                        raw_device.destroy_image(image, None);
+                        raw_device.free_memory(memory, None);
+                    }
+                };
+                if let Err(error) = raw_device.bind_image_memory(image, memory, 0) {
+                    destroy();
+                    return Err(error.into());
+                }
+
+                let fd_device = ash::khr::external_memory_fd::Device::new(instance, &raw_device);
+                let fd_info = vk::MemoryGetFdInfoKHR::default()
+                    .memory(memory)
+                    .handle_type(vk::ExternalMemoryHandleTypeFlags::DMA_BUF_EXT);
+                let fd = match fd_device.get_memory_fd(&fd_info) {
+                    Ok(fd) => OwnedFd::from_raw_fd(fd),
+                    Err(error) => {
+                        destroy();
+                        return Err(error.into());
+                    }
+                };
+                let layout = raw_device.get_image_subresource_layout(
+                    image,
+                    vk::ImageSubresource {
+                        aspect_mask: vk::ImageAspectFlags::COLOR,
+                        mip_level: 0,
+                        array_layer: 0,
+                    },
+                );
+
+                let hal_texture = hal::vulkan::Device::texture_from_raw(
+                    image,
+                    &hal::TextureDescriptor {
+                        label: Some("Shared Frame"),
+                        size: wgpu::Extent3d {
+                            width,
+                            height,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: wgpu::TextureDimension::D2,
+                        format,
+                        usage: hal::TextureUses::COPY_DST,
+                        memory_flags: hal::MemoryFlags::empty(),
+                        view_formats: Vec::new(),
+                    },
+                    Some(Box::new(destroy)),
+                );
+                let dma_buf = DmaBuf {
+                    fd,
+                    fourcc,
+                    modifier: DRM_FORMAT_MOD_LINEAR,
+                    offset: layout.offset,
+                    stride: layout.row_pitch,
+                };
+                Ok((hal_texture, dma_buf))
+            })
+        };
+        let (hal_texture, dma_buf) = created?;
+
+        // SAFETY: The hal texture was created from this device, matching the descriptor.
+        let texture = unsafe {
+            device.create_texture_from_hal::<hal::api::Vulkan>(
+                hal_texture,
+                &wgpu::TextureDescriptor {
+                    label: Some("Shared Frame"),
+                    size: wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsages::COPY_DST,
+                    view_formats: &[],
+                },
+            )
+        };
+        Ok((texture, dma_buf))
+    }
+}
@@ -0,0 +1,700 @@
+//! # Labels Module
+//!
+//! The `labels` module draws text in the 3D scene: labels anchored at points in the world,
+//! such as the names of the scene's nodes, which always face the camera.
+//!
+//! ## Overview
+//!
+//! - [`GlyphAtlas`] rasterizes the printable ASCII characters of the font `egui` ships with
+//!   (Ubuntu Light) once, with `ab_glyph`, into a single-channel coverage texture, and lays out
+//!   lines of text with the font's advances and kerning. Other characters are drawn as `?`.
+//! - [`Label`] is one line of text at a position in world space, or in the space of the node it
+//!   is attached to, so it follows the node. Labels are added to the scene with
+//!   `Scene::add_label`.
+//! - [`LabelRenderer`] turns the labels into one instance per glyph every frame and draws them
+//!   in the scene pass as quads facing the camera. Like line widths, label sizes are given in
+//!   logical pixels, so text stays readable at any distance and on any display. When the
+//!   window's scale factor grows past the atlas's, the glyphs are rasterized again at the new
+//!   scale with `LabelRenderer::set_scale_factor`, so the text stays sharp.
+//! - Each label is either depth tested against the scene, and hidden behind objects in front
+//!   of its anchor, or drawn on top of everything, for labels that must stay visible.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let id = scene.add_label(crate::math::vec3(0.0, 1.2, 0.0), "Apex");
+//! if let Some(label) = scene.label_mut(id) {
+//!     label.depth_test = false;
+//! }
+//! // ... once per frame:
+//! labels.prepare(&device, &mut uploads, scene.label_anchors(), &view_projection, (width, height));
+//! labels.render(&mut render_pass);
+//! ```
+
+use ab_glyph::{Font, ScaleFont};
+
+use crate::gpu::UploadBelt;
+use crate::renderer::Renderer;
+use crate::scene::Scene;
+use crate::scene_graph::NodeId;
+use crate::shader_bindings::labels as label_bindings;
+
+/// The WGSL source of the label shader.
+const LABEL_SHADER_SOURCE: &str = include_str!("labels.wgsl");
+
+/// Identifies a label of the scene, returned by `Scene::add_label`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LabelId(pub(crate) usize);
+
+/// A line of text drawn at a point of the scene.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    /// The text. Characters other than printable ASCII are drawn as `?`.
+    pub text: String,
+
+    /// The point the text is centered on, in world space, or in the space of `node`.
+    pub position: crate::math::Vec3,
+
+    /// The node the label is attached to and moves with, if any.
+    pub node: Option<NodeId>,
+
+    /// The text's color as RGBA.
+    pub color: [f32; 4],
+
+    /// The height of a line of text, in pixels.
+    pub size: f32,
+
+    /// Whether objects in front of the label's position hide it. Labels that are not depth
+    /// tested are drawn on top of the scene.
+    pub depth_test: bool,
+}
+
+impl Label {
+    /// The default height of a line of text, in pixels.
+    pub const DEFAULT_SIZE: f32 = 16.0;
+
+    /// Creates a white, depth tested label showing `text` at `position` in world space.
+    pub fn new(position: crate::math::Vec3, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            position,
+            node: None,
+            color: [1.0, 1.0, 1.0, 1.0],
+            size: Self::DEFAULT_SIZE,
+            depth_test: true,
+        }
+    }
+
+    /// Returns the label attached to `node`, its position then being in the node's space.
+    pub fn with_node(mut self, node: NodeId) -> Self {
+        self.node = Some(node);
+        self
+    }
+
+    /// Returns the label drawn in `color`.
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the label depth tested against the scene if `depth_test` is `true`, or drawn
+    /// on top of it otherwise.
+    pub fn with_depth_test(mut self, depth_test: bool) -> Self {
+        self.depth_test = depth_test;
+        self
+    }
+}
+
+/// Where a glyph is in the [`GlyphAtlas`], and how it is placed on a line of text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GlyphInfo {
+    /// The top left corner of the glyph's rectangle in the atlas, in texture coordinates.
+    uv_min: [f32; 2],
+
+    /// The bottom right corner of the glyph's rectangle in the atlas.
+    uv_max: [f32; 2],
+
+    /// The top left corner of the glyph relative to the pen on the baseline, in atlas pixels.
+    offset: [f32; 2],
+
+    /// The size of the glyph's rectangle, in atlas pixels.
+    size: [f32; 2],
+}
+
+/// The glyphs of the label font, rasterized into a coverage texture.
+#[derive(Debug)]
+pub struct GlyphAtlas {
+    /// The font the glyphs are laid out with.
+    font: ab_glyph::FontRef<'static>,
+
+    /// The height the glyphs are rasterized at, in pixels.
+    pixel_size: f32,
+
+    /// The width of the atlas in pixels.
+    width: u32,
+
+    /// The height of the atlas in pixels.
+    height: u32,
+
+    /// The coverage of each pixel, row by row.
+    pixels: Vec<u8>,
+
+    /// The glyph of each printable ASCII character, starting with the space. Glyphs without
+    /// an outline, such as the space, have none.
+    glyphs: Vec<Option<GlyphInfo>>,
+}
+
+impl GlyphAtlas {
+    /// The height the glyphs are rasterized at on a display with a scale factor of one, in
+    /// pixels. Labels of other sizes scale them.
+    pub const PIXEL_SIZE: f32 = 32.0;
+
+    /// The largest scale the glyphs are rasterized at, which keeps the atlas small on very
+    /// dense displays.
+    pub const MAX_SCALE: f32 = 4.0;
+
+    /// The width of the atlas in pixels.
+    const WIDTH: u32 = 512;
+
+    /// The empty pixels around each glyph, so filtering does not bleed into its neighbors.
+    const PADDING: u32 = 1;
+
+    /// The printable ASCII characters.
+    const CHARACTERS: std::ops::RangeInclusive<char> = ' '..='~';
+
+    /// Rasterizes the printable ASCII characters of the label font.
+    pub fn new() -> Self {
+        Self::with_scale(1.0)
+    }
+
+    /// Rasterizes the printable ASCII characters of the label font at `scale` times
+    /// `PIXEL_SIZE`, at most `MAX_SCALE` times.
+    pub fn with_scale(scale: f32) -> Self {
+        let font = ab_glyph::FontRef::try_from_slice(epaint_default_fonts::UBUNTU_LIGHT)
+            .expect("the font shipped with egui is valid");
+        let pixel_size = Self::PIXEL_SIZE * scale.clamp(1.0, Self::MAX_SCALE);
+        let scaled = font.as_scaled(pixel_size);
+
+        // Glyphs are packed left to right into rows as tall as the font's lines.
+        let row_height = (scaled.ascent() - scaled.descent()).ceil() as u32 + 2 * Self::PADDING;
+        let mut outlines = Vec::new();
+        let (mut x, mut y) = (0, 0);
+        for character in Self::CHARACTERS {
+            let glyph = scaled.scaled_glyph(character);
+            let Some(outline) = font.outline_glyph(glyph) else {
+                outlines.push(None);
+                continue;
+            };
+            let bounds = outline.px_bounds();
+            let width = bounds.width() as u32 + 2 * Self::PADDING;
+            if x + width > Self::WIDTH {
+                x = 0;
+                y += row_height;
+            }
+            outlines.push(Some((outline, x + Self::PADDING, y + Self::PADDING)));
+            x += width;
+        }
+        let height = (y + row_height).next_power_of_two();
+
+        let mut pixels = vec![0; (Self::WIDTH * height) as usize];
+        let atlas_size = [Self::WIDTH as f32, height as f32];
+        let glyphs = outlines
+            .into_iter()
+            .map(|outline| {
+                let (outline, left, top) = outline?;
+                outline.draw(|x, y, coverage| {
+                    let index = (top + y) * Self::WIDTH + left + x;
+                    pixels[index as usize] = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+                });
+                let bounds = outline.px_bounds();
+                let (left, top) = (left as f32, top as f32);
+                Some(GlyphInfo {
+                    uv_min: [left / atlas_size[0], top / atlas_size[1]],
+                    uv_max: [
+                        (left + bounds.width()) / atlas_size[0],
+                        (top + bounds.height()) / atlas_size[1],
+                    ],
+                    offset: [bounds.min.x, bounds.min.y],
+                    size: [bounds.width(), bounds.height()],
+                })
+            })
+            .collect();
+
+        Self {
+            font,
+            pixel_size,
+            width: Self::WIDTH,
+            height,
+            pixels,
+            glyphs,
+        }
+    }
+
+    /// Returns the height the glyphs are rasterized at, in pixels.
+    pub fn pixel_size(&self) -> f32 {
+        self.pixel_size
+    }
+
+    /// Returns the size of the atlas in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the coverage of each pixel of the atlas, row by row.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Lays out `label` as one instance per visible glyph, centered on `anchor`.
+    fn layout(&self, anchor: crate::math::Vec3, label: &Label) -> Vec<GlyphInstance> {
+        let scale = label.size / self.pixel_size;
+        let scaled = self.font.as_scaled(self.pixel_size);
+        let characters: Vec<char> = label
+            .text
+            .chars()
+            .map(|character| {
+                if Self::CHARACTERS.contains(&character) {
+                    character
+                } else {
+                    '?'
+                }
+            })
+            .collect();
+
+        // The pen positions of the characters, kerned, and the width of the whole line.
+        let mut pens = Vec::with_capacity(characters.len());
+        let mut pen = 0.0;
+        let mut previous = None;
+        for &character in &characters {
+            let id = scaled.glyph_id(character);
+            if let Some(previous) = previous {
+                pen += scaled.kern(previous, id);
+            }
+            pens.push(pen);
+            pen += scaled.h_advance(id);
+            previous = Some(id);
+        }
+
+        // The line is centered on the anchor, horizontally and between its ascent and descent.
+        let left = -pen * 0.5;
+        let baseline = (scaled.ascent() + scaled.descent()) * 0.5;
+        characters
+            .iter()
+            .zip(pens)
+            .filter_map(|(&character, pen)| {
+                let index = character as usize - *Self::CHARACTERS.start() as usize;
+                let glyph = self.glyphs[index]?;
+                Some(GlyphInstance {
+                    anchor: anchor.into(),
+                    offset: [
+                        (left + pen + glyph.offset[0]) * scale,
+                        (baseline + glyph.offset[1]) * scale,
+                    ],
+                    size: [glyph.size[0] * scale, glyph.size[1] * scale],
+                    uv_min: glyph.uv_min,
+                    uv_max: glyph.uv_max,
+                    color: label.color,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The default atlas rasterizes the label font.
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A glyph drawn by the [`LabelRenderer`], uploaded as instance data.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+struct GlyphInstance {
+    /// The label's anchor in world space.
+    anchor: [f32; 3],
+
+    /// The top left corner of the glyph relative to the anchor, in pixels, Y pointing down.
+    offset: [f32; 2],
+
+    /// The size of the glyph in pixels.
+    size: [f32; 2],
+
+    /// The top left corner of the glyph's rectangle in the atlas.
+    uv_min: [f32; 2],
+
+    /// The bottom right corner of the glyph's rectangle in the atlas.
+    uv_max: [f32; 2],
+
+    /// The text's color as RGBA.
+    color: [f32; 4],
+}
+
+impl GlyphInstance {
+    /// The per-instance vertex attributes, matching `GlyphInput` in the shader.
+    const ATTRIBUTES: [wgpu::VertexAttribute; 6] = wgpu::vertex_attr_array![
+        0 => Float32x3,
+        1 => Float32x2,
+        2 => Float32x2,
+        3 => Float32x2,
+        4 => Float32x2,
+        5 => Float32x4,
+    ];
+}
+
+/// The uniform data of the label shader.
+#[repr(C)]
+#[derive(Default, Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct LabelUniform {
+    /// The matrix transforming world space into clip space.
+    view_projection: crate::math::Mat4,
+
+    /// The size of the render target in logical pixels, which label sizes refer to.
+    viewport: [f32; 2],
+
+    /// Pads the struct to the 16 byte alignment of WGSL uniforms.
+    _padding: [f32; 2],
+}
+
+/// Draws the scene's labels.
+#[derive(Debug)]
+pub struct LabelRenderer {
+    /// The glyphs the labels are laid out with.
+    atlas: GlyphAtlas,
+
+    /// The pipeline drawing depth tested labels.
+    pipeline: wgpu::RenderPipeline,
+
+    /// The pipeline drawing labels on top of the scene, without depth testing.
+    overlay_pipeline: wgpu::RenderPipeline,
+
+    /// The buffer holding the `LabelUniform`.
+    uniform_buffer: wgpu::Buffer,
+
+    /// The layout of `bind_group`, kept to bind a new atlas.
+    bind_group_layout: wgpu::BindGroupLayout,
+
+    /// The bind group exposing `uniform_buffer` and the atlas to the shader.
+    bind_group: wgpu::BindGroup,
+
+    /// The physical pixels per logical pixel of the render target.
+    scale_factor: f32,
+
+    /// The instance buffer, grown as needed.
+    instance_buffer: wgpu::Buffer,
+
+    /// The number of depth tested glyphs uploaded by the last `prepare`.
+    instance_count: u32,
+
+    /// The number of glyphs drawn on top uploaded by the last `prepare`, after the others.
+    overlay_count: u32,
+}
+
+impl LabelRenderer {
+    /// The number of glyphs the instance buffer has room for initially.
+    const INITIAL_CAPACITY: u64 = 256;
+
+    /// Rasterizes the glyph atlas, uploads it with `queue`, and creates the pipelines drawing
+    /// into the scene pass's color targets of `color_format`, compiled through `cache` if one
+    /// is given.
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        color_format: wgpu::TextureFormat,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let atlas = GlyphAtlas::new();
+        let uniform_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Label Uniform Buffer"),
+                contents: bytemuck::bytes_of(&LabelUniform::default()),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+        let bind_group_layout = label_bindings::BindGroup0::create_layout(device);
+        let bind_group =
+            Self::create_bind_group(device, queue, &atlas, &uniform_buffer, &bind_group_layout);
+
+        let pipeline =
+            Self::create_pipeline(device, color_format, &bind_group_layout, false, cache);
+        let overlay_pipeline =
+            Self::create_pipeline(device, color_format, &bind_group_layout, true, cache);
+
+        Self {
+            atlas,
+            pipeline,
+            overlay_pipeline,
+            uniform_buffer,
+            bind_group_layout,
+            bind_group,
+            scale_factor: 1.0,
+            instance_buffer: Self::create_instance_buffer(device, Self::INITIAL_CAPACITY),
+            instance_count: 0,
+            overlay_count: 0,
+        }
+    }
+
+    /// Draws the labels for a render target with `scale_factor` physical pixels per logical
+    /// pixel. The glyphs are rasterized at the scale factor rounded up, so when that changes,
+    /// the atlas is rasterized again and uploaded with `queue`.
+    pub fn set_scale_factor(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        scale_factor: f32,
+    ) {
+        self.scale_factor = scale_factor.max(f32::EPSILON);
+        let scale = scale_factor.ceil().clamp(1.0, GlyphAtlas::MAX_SCALE);
+        if scale * GlyphAtlas::PIXEL_SIZE == self.atlas.pixel_size() {
+            return;
+        }
+        self.atlas = GlyphAtlas::with_scale(scale);
+        self.bind_group = Self::create_bind_group(
+            device,
+            queue,
+            &self.atlas,
+            &self.uniform_buffer,
+            &self.bind_group_layout,
+        );
+    }
+
+    /// Uploads `atlas` into a new texture with `queue`, and creates the bind group exposing it
+    /// and `uniform_buffer` to the shader.
+    fn create_bind_group(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        atlas: &GlyphAtlas,
+        uniform_buffer: &wgpu::Buffer,
+        layout: &wgpu::BindGroupLayout,
+    ) -> wgpu::BindGroup {
+        let (width, height) = atlas.size();
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Glyph Atlas"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            atlas.pixels(),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(width),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Glyph Atlas Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        label_bindings::BindGroup0 {
+            ubo: uniform_buffer.as_entire_buffer_binding(),
+            atlas: &view,
+            atlas_sampler: &sampler,
+        }
+        .create(device, layout)
+    }
+
+    /// Lays out `labels`, each with its anchor in world space, and uploads them and the camera
+    /// for drawing through `uploads`.
+    ///
+    /// `viewport` is the size of the render target in physical pixels. Label sizes refer to
+    /// logical pixels, `set_scale_factor` times smaller.
+    pub fn prepare<'a>(
+        &mut self,
+        device: &wgpu::Device,
+        uploads: &mut UploadBelt,
+        labels: impl IntoIterator<Item = (crate::math::Vec3, &'a Label)>,
+        view_projection: &crate::math::Mat4,
+        viewport: (u32, u32),
+    ) {
+        uploads.write(
+            &self.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&LabelUniform {
+                view_projection: *view_projection,
+                viewport: [
+                    viewport.0 as f32 / self.scale_factor,
+                    viewport.1 as f32 / self.scale_factor,
+                ],
+                _padding: [0.0; 2],
+            }),
+        );
+
+        // Depth tested glyphs come first, so each pipeline draws one range of instances.
+        let mut glyphs = Vec::new();
+        let mut overlay = Vec::new();
+        for (anchor, label) in labels {
+            let target = if label.depth_test {
+                &mut glyphs
+            } else {
+                &mut overlay
+            };
+            target.extend(self.atlas.layout(anchor, label));
+        }
+        self.instance_count = glyphs.len() as u32;
+        self.overlay_count = overlay.len() as u32;
+        glyphs.append(&mut overlay);
+        if glyphs.is_empty() {
+            return;
+        }
+
+        let size = std::mem::size_of_val(glyphs.as_slice()) as wgpu::BufferAddress;
+        if size > self.instance_buffer.size() {
+            let capacity = (glyphs.len() as u64).next_power_of_two();
+            self.instance_buffer = Self::create_instance_buffer(device, capacity);
+        }
+        uploads.write(&self.instance_buffer, 0, bytemuck::cast_slice(&glyphs));
+    }
+
+    /// Draws the glyphs uploaded by the last `prepare`.
+    pub fn render<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        if self.is_empty() {
+            return;
+        }
+        self.bind_pipeline(render_pass);
+        self.bind_material(render_pass);
+        self.draw(render_pass);
+    }
+
+    /// Returns `true` if the last `prepare` uploaded no glyphs, so there is nothing to draw.
+    pub fn is_empty(&self) -> bool {
+        self.instance_count == 0 && self.overlay_count == 0
+    }
+
+    /// Sets the pipeline of the depth tested labels.
+    pub fn bind_pipeline<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_pipeline(&self.pipeline);
+    }
+
+    /// Binds the view uniforms and the glyph atlas, the labels' material, at set 0.
+    pub fn bind_material<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+    }
+
+    /// Draws the uploaded glyphs, after the pipeline and material are bound.
+    ///
+    /// Labels drawn on top come last, switching to their pipeline, which stays bound.
+    pub fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+        if self.instance_count > 0 {
+            render_pass.draw(0..4, 0..self.instance_count);
+        }
+        if self.overlay_count > 0 {
+            render_pass.set_pipeline(&self.overlay_pipeline);
+            render_pass.draw(
+                0..4,
+                self.instance_count..self.instance_count + self.overlay_count,
+            );
+        }
+    }
+
+    /// Creates an instance buffer with room for `capacity` glyphs.
+    fn create_instance_buffer(device: &wgpu::Device, capacity: u64) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Label Instance Buffer"),
+            size: capacity * std::mem::size_of::<GlyphInstance>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Creates the pipeline drawing glyphs as quads facing the camera.
+    ///
+    /// Labels are depth tested at their anchor, unless `overlay` is set, but never write
+    /// depth, and they leave the object id target untouched.
+    fn create_pipeline(
+        device: &wgpu::Device,
+        color_format: wgpu::TextureFormat,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        overlay: bool,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> wgpu::RenderPipeline {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Label Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(LABEL_SHADER_SOURCE)),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Label Pipeline Layout"),
+            bind_group_layouts: &[bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(if overlay {
+                "Overlay Label Pipeline"
+            } else {
+                "Label Pipeline"
+            }),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vertex_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<GlyphInstance>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &GlyphInstance::ATTRIBUTES,
+                }],
+                compilation_options: Default::default(),
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: Renderer::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: if overlay {
+                    wgpu::CompareFunction::Always
+                } else {
+                    wgpu::CompareFunction::Less
+                },
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fragment_main"),
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: color_format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(wgpu::ColorTargetState {
+                        format: Scene::OBJECT_ID_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::empty(),
+                    }),
+                ],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        })
+    }
+}
@@ -0,0 +1,79 @@
+// Billboard text labels.
+//
+// Every glyph is one instance drawn as a 4-vertex triangle strip. The vertex shader projects
+// the label's anchor point, then offsets the glyph's corners in screen space, so the text
+// always faces the camera and keeps its size in pixels regardless of depth. The fragment
+// shader reads the glyph's coverage from the atlas as the text's alpha.
+
+struct LabelUniform {
+    view_projection: mat4x4<f32>,
+    // The size of the render target in pixels.
+    viewport: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> ubo: LabelUniform;
+
+// The coverage of every glyph, rasterized once.
+@group(0) @binding(1)
+var atlas: texture_2d<f32>;
+
+@group(0) @binding(2)
+var atlas_sampler: sampler;
+
+struct GlyphInput {
+    @builtin(vertex_index) vertex_index: u32,
+    // The label's anchor in world space.
+    @location(0) anchor: vec3<f32>,
+    // The top left corner of the glyph relative to the anchor, in pixels, Y pointing down.
+    @location(1) offset: vec2<f32>,
+    // The size of the glyph in pixels.
+    @location(2) size: vec2<f32>,
+    // The glyph's rectangle in the atlas.
+    @location(3) uv_min: vec2<f32>,
+    @location(4) uv_max: vec2<f32>,
+    @location(5) color: vec4<f32>,
+};
+
+struct GlyphOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vertex_main(in: GlyphInput) -> GlyphOutput {
+    var out: GlyphOutput;
+    out.color = in.color;
+
+    // Labels behind the camera are dropped rather than mirrored in front of it.
+    let anchor = ubo.view_projection * vec4<f32>(in.anchor, 1.0);
+    if anchor.w <= 0.0 {
+        out.position = vec4<f32>(0.0, 0.0, -1.0, 1.0);
+        return out;
+    }
+
+    // Vertices 0 and 1 are the top corners, 2 and 3 the bottom ones; odd vertices on the right.
+    let corner = vec2<f32>(f32(in.vertex_index & 1u), f32(in.vertex_index >> 1u));
+    let pixel = in.offset + corner * in.size;
+    let offset = vec2<f32>(pixel.x, -pixel.y) / (0.5 * ubo.viewport) * anchor.w;
+    out.position = vec4<f32>(anchor.xy + offset, anchor.zw);
+    out.uv = mix(in.uv_min, in.uv_max, corner);
+    return out;
+}
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    // Labels do not belong to an object; the pipeline masks out writes to the id target.
+    @location(1) object_id: u32,
+};
+
+@fragment
+fn fragment_main(in: GlyphOutput) -> FragmentOutput {
+    let coverage = textureSample(atlas, atlas_sampler, in.uv).r;
+
+    var out: FragmentOutput;
+    out.color = vec4<f32>(in.color.rgb, in.color.a * coverage);
+    out.object_id = 0u;
+    return out;
+}
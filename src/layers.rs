@@ -0,0 +1,129 @@
+//! # Layers Module
+//!
+//! The `layers` module sorts the objects of the scene into render layers, so a camera can
+//! include or exclude whole categories of objects, such as the editor's helpers.
+//!
+//! ## Overview
+//!
+//! - [`RenderLayers`] is a 32-bit mask, one bit per layer. An object is on every layer whose
+//!   bit is set in its mask, and a camera draws every object on at least one of the layers
+//!   set in its own mask.
+//! - [`RenderLayers::NAMED`] lists the layers the demo uses: `Default` for regular content,
+//!   `Editor` for editor-only helpers, and `Characters` for the crowd demo.
+//! - [`LayerMasks`] holds the mask of the main camera and of each object the renderer draws,
+//!   edited in the Inspector panel and applied with `Renderer::set_layer_masks`.
+//!
+//! Editor-only helpers, the ground grid, the debug lines, and the camera path, default to the
+//! `Editor` layer. A camera that should not show them, such as one capturing the scene for a
+//! screenshot, leaves that layer out of its mask.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut masks = LayerMasks::default();
+//! // Hide the grid and the debug lines from the main camera.
+//! masks.camera.set(RenderLayers::EDITOR, false);
+//! renderer.set_layer_masks(&masks);
+//! ```
+
+/// A set of render layers, one per bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RenderLayers(pub u32);
+
+impl RenderLayers {
+    /// On no layer. Objects with this mask are never drawn, and cameras with it draw nothing.
+    pub const NONE: RenderLayers = RenderLayers(0);
+
+    /// On every layer.
+    pub const ALL: RenderLayers = RenderLayers(u32::MAX);
+
+    /// The layer of regular content, such as the scene's triangle.
+    pub const DEFAULT: RenderLayers = RenderLayers(1 << 0);
+
+    /// The layer of editor-only helpers: the ground grid, the debug lines, and the camera path.
+    pub const EDITOR: RenderLayers = RenderLayers(1 << 1);
+
+    /// The layer of animated characters, such as the crowd demo.
+    pub const CHARACTERS: RenderLayers = RenderLayers(1 << 2);
+
+    /// The layers with a name, in the order they are offered in the user interface.
+    pub const NAMED: [(RenderLayers, &'static str); 3] = [
+        (RenderLayers::DEFAULT, "Default"),
+        (RenderLayers::EDITOR, "Editor"),
+        (RenderLayers::CHARACTERS, "Characters"),
+    ];
+
+    /// Returns whether every layer of `other` is in this set.
+    pub fn contains(self, other: RenderLayers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns whether this set and `other` have at least one layer in common.
+    pub fn intersects(self, other: RenderLayers) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Adds the layers of `other` to this set if `enabled` is `true`, and removes them
+    /// otherwise.
+    pub fn set(&mut self, other: RenderLayers, enabled: bool) {
+        if enabled {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+}
+
+impl Default for RenderLayers {
+    /// Returns the `DEFAULT` layer, the layer of regular content.
+    fn default() -> Self {
+        RenderLayers::DEFAULT
+    }
+}
+
+impl std::ops::BitOr for RenderLayers {
+    type Output = RenderLayers;
+
+    fn bitor(self, other: RenderLayers) -> RenderLayers {
+        RenderLayers(self.0 | other.0)
+    }
+}
+
+/// The layer masks of the main camera and of the objects the renderer draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayerMasks {
+    /// The layers the main camera draws. Everything by default.
+    pub camera: RenderLayers,
+
+    /// The layers of the scene's triangle.
+    pub scene: RenderLayers,
+
+    /// The layers of the crowd demo.
+    pub crowd: RenderLayers,
+
+    /// The layers of the particle demo.
+    pub particles: RenderLayers,
+
+    /// The layers of the editor's helpers: the ground grid, the debug lines, and the camera
+    /// path.
+    pub helpers: RenderLayers,
+}
+
+impl LayerMasks {
+    /// Returns whether the main camera draws an object on the `object` layers.
+    pub fn shows(&self, object: RenderLayers) -> bool {
+        self.camera.intersects(object)
+    }
+}
+
+impl Default for LayerMasks {
+    fn default() -> Self {
+        Self {
+            camera: RenderLayers::ALL,
+            scene: RenderLayers::DEFAULT,
+            crowd: RenderLayers::CHARACTERS,
+            particles: RenderLayers::DEFAULT,
+            helpers: RenderLayers::EDITOR,
+        }
+    }
+}
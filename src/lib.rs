@@ -10,10 +10,90 @@
 //! - [`app`]: Handles application setup, event loop, and user input integration.
 //! - [`renderer`]: Manages the rendering pipeline including shaders, vertex buffers, and GPU commands.
 //! - [`gpu`]: Initializes and manages GPU resources such as devices, queues, and surface configuration.
+//! - [`gpu::upload`]: Streams the per-frame buffer writes through reused staging buffers, reclaimed once their frame has finished on the GPU.
+//! - [`cli`]: Parses the desktop binary's subcommands and options, rejecting unknown arguments with a usage message, on native targets.
+//! - [`config`]: The window and renderer options an application creates the `App` with, such as the title, size, icon, backend, adapter, and vsync.
 //! - [`scene`]: Encapsulates the scene data including objects, transformations, and lighting.
+//! - [`scene_graph`]: Places the scene's objects in a hierarchy of nodes with parent-relative transforms.
+//! - [`gpu_errors`]: Catches GPU validation, out-of-memory, and internal errors in error scopes instead of panicking, for the GPU Errors window.
+//! - [`shader_editor`]: Holds the Shader Editor window's WGSL source, applied to the scene's pipelines with errors shown inline.
+//! - [`instancing`]: Draws the scene's meshes once per instance with a single draw call, and the grid demo comparing it with per-draw uniforms.
 //! - [`vertex`]: Defines the vertex structure and data used for rendering.
+//! - [`texture`]: Decodes PNG and JPEG images into textures sampled by the scene's shader.
 //! - [`uniform_buffer`]: Manages uniform buffer resources, such as transformation matrices.
 //! - [`uniform_binding`]: Manages bindings for shaders to access uniform buffer data.
+//! - [`model`]: Loads glTF and GLB models into meshes drawn by the scene, with the `import-gltf` feature.
+//! - [`asset`]: Loads asset bytes from local files or HTTP(S) URLs, with caching and ETag revalidation.
+//! - [`validation`]: Checks requested configurations against adapter and surface capabilities.
+//! - [`toast`]: Shows short-lived notifications, such as validation warnings, with `egui`.
+//! - [`error`]: Defines the crate-wide `RenderError` and the policy hooks used to recover from it.
+//! - [`backend`]: Defines the `RendererBackend` trait, letting alternate renderers replace the wgpu one.
+//! - [`capabilities`]: Negotiates optional device features and records the granted ones in `GpuCapabilities`, and describes the adapter in `GpuInfo`.
+//! - [`simulation`]: Advances the scene's animation, on a dedicated thread with triple-buffered snapshots on native.
+//! - [`lines`]: Draws wide, anti-aliased lines as camera-facing quads, for debug drawing and the ground grid.
+//! - [`debug_draw`]: An infinite ground grid fading out with distance, and the world axes, drawn with their own unlit pipelines.
+//! - [`labels`]: Text labels at points of the scene, such as node names, drawn as camera-facing glyph quads from a font atlas.
+//! - [`curves`]: Bezier and Catmull-Rom curves with adaptive tessellation, and an editor for their control points.
+//! - [`gizmo`]: A transform gizmo translating, rotating, and scaling the selected node with mouse drags, with optional snapping.
+//! - [`debug_view`]: Lists the debug visualizations of the scene, selected in the settings window.
+//! - [`overdraw`]: Renders the overdraw heatmap debug view, counting fragment writes per pixel.
+//! - [`crowd`]: A gallery demo of hundreds of skinned, instanced characters with two levels of detail.
+//! - [`particles`]: A gallery demo of a hundred thousand particles simulated in a compute shader and drawn as instanced quads.
+//! - [`camera`]: Orbit, arcball, and FPS cameras with frame-rate independent damping, their input settings, and the projection.
+//! - [`frustum`]: Extracts the view frustum's planes and tests bounding spheres and boxes against them, culling off-screen meshes.
+//! - [`environment`]: Ambient light, sky, fog, exposure, and IBL settings with presets, and the sky pass applying them.
+//! - [`lighting`]: The scene's directional light, which the materials are shaded with, edited in the Inspector panel.
+//! - [`material`]: Metallic-roughness PBR materials with optional textures, bound per material and edited in the Inspector panel.
+//! - [`post_process`]: Draws the scene into an HDR texture and displays it with bloom, tonemapping, gamma correction, and a vignette, toggled in the Post FX window.
+//! - [`bloom`]: Blurs the scene's brightest pixels into a glow over a half-resolution mip chain, for the post-processing pass to add.
+//! - [`gestures`]: Recognizes taps, long presses, drags, and two-finger pan, pinch, and rotate gestures from touch input.
+//! - [`safe_area`]: Queries the platform's safe-area insets (notches, browser UI) the GUI is laid out within.
+//! - [`viewport`]: Locks the scene's viewport to a fixed aspect ratio, with letterbox or pillarbox bars around it.
+//! - [`rulers`]: Measures the viewport in pixels and world units for the rulers, and places the guide lines.
+//! - [`probe`]: Reads back the depth, world position, and object under the cursor for the depth probe readout.
+//! - [`picking`]: Reads back the object id under a clicked pixel, to select the scene's node drawn there.
+//! - [`outline`]: Outlines the selected object's silhouette on the HDR scene with a fullscreen pass.
+//! - [`settings`]: Persists user preferences, such as the GUI scale, between sessions.
+//! - [`titlebar`]: Describes the actions of the title bar drawn with `egui` for undecorated windows.
+//! - [`compute`]: Storage buffers and compute passes created from the `Gpu`, dispatched over any number of invocations.
+//! - [`background`]: Time-slices long-running compute jobs across frames, and reports their progress.
+//! - [`upload`]: Streams large buffer and texture uploads in chunks, within a time budget per frame.
+//! - [`interop`]: Exports rendered frames as shared GPU textures, with the `interop` feature.
+//! - [`streaming`]: Serves the rendered frames over HTTP to remote browsers, on native targets.
+//! - [`determinism`]: Seeded RNG streams, fixed timesteps, and per-tick state hashes for reproducible runs.
+//! - [`pipeline_cache`]: Persists compiled pipelines to disk per adapter, avoiding shader compile stalls at startup.
+//! - [`pack`]: Bundles a project's assets into one compressed pack file, mounted by the asset loader.
+//! - [`input`]: Coalesces high-rate pointer events per frame, tracks the keyboard and mouse state, and feeds raw mouse motion to the cameras.
+//! - [`redraw`]: Renders frames continuously, on demand, or at a fixed rate, letting the event loop sleep in between, and optionally paces frames to the display's refresh to lower input latency.
+//! - [`actions`]: Maps keys and mouse buttons to named actions such as "quit" and "camera_forward", rebindable at runtime and saved with the settings.
+//! - [`fullscreen`]: Toggles borderless or exclusive fullscreen with F11 or Alt+Enter, in a video mode of the window's monitor chosen in the settings window.
+//! - [`windows`]: Opens secondary windows, such as a detached Inspector, each with its own surface, renderer, and GUI, on native targets.
+//! - [`viewports`]: Shows `egui` viewports, such as detached GUI windows, in native windows drawn with the main renderer's device, on native targets.
+//! - [`preview`]: Renders material and mesh thumbnails into cached textures for the Inspector and Assets panels.
+//! - [`math`]: The vector, matrix, and quaternion types, backed by `nalgebra-glm` or, with the `glam` feature, `glam`.
+//! - [`camera_exchange`]: Exports and imports the viewport camera as JSON in the USD/glTF camera conventions, for sharing with DCC tools.
+//! - [`depth_binding`]: Binds the scene's depth for sampling in passes after the scene pass, such as soft particles, SSAO, and fog.
+//! - [`software`]: Rasterizes a simplified scene and the GUI on the CPU when no GPU adapter is available, with the `software` feature.
+//! - [`layers`]: Sorts objects into render layers, so cameras can include or exclude categories of objects such as editor helpers.
+//! - [`camera_path`]: Records camera keyframes and plays them back as a Catmull-Rom flythrough, exported as JSON for demos and benchmarks.
+//! - [`frame_export`]: Renders a camera path headlessly into numbered PNGs or, through `ffmpeg`, a video, at a fixed timestep and any resolution, on native targets.
+//! - `android`: The `android_main` entry point run by an Android `NativeActivity`, with the `android` feature.
+//! - [`benchmark`]: Runs a growing instanced workload for a fixed time without vsync and reports the frame, CPU, and GPU times as CSV or JSON, on native targets.
+//! - [`dolly_zoom`]: Animates the field of view while dollying the camera, keeping the focus plane's size on screen.
+//! - [`shader_bindings`]: Bind group structs and layouts generated from the WGSL shaders at build time.
+//! - [`gpu_timer`]: Measures the GPU time of frames and of each pass with timestamp queries, read back a few frames later without stalling.
+//! - [`cpu_profiler`]: Records named CPU scopes per frame, toggled at runtime, for the in-app profiler and Chrome trace export.
+//! - [`frame_stats`]: Frame rate, frame time percentiles, and CPU and GPU times for the overlay toggled with F1.
+//! - [`quality`]: Steps quality settings up and down within user-defined bounds to hold a target frame rate.
+//! - [`deletion_queue`]: Releases replaced GPU resources only after the frames referencing them have finished.
+//! - [`render_queue`]: Sorts a pass's draws by a packed pipeline, material, and depth key to minimize state changes.
+//! - [`gallery`]: The state of the Widget Gallery, a window of `egui` widgets bound to live renderer settings.
+//! - [`test_matrix`]: Renders the demo gallery headlessly on every adapter and compares the screenshots across backends.
+//! - [`frame_capture`]: Saves screenshots of rendered frames as PNGs, or downloads them on the web.
+//! - [`readback`]: Reads buffers back from the GPU over a few frames without stalling, for the probes, captures, streamer, and GPU timer.
+//! - [`ui_capture`]: Captures the GUI alone over a transparent background into a PNG, for UI documentation.
+//! - [`scene_panel`]: Renders the scene into a texture shown in an `egui` central panel, for editor-style layouts.
+//! - [`ui`]: Builds the `egui` settings window and panels, and tests them headlessly against snapshots.
 //!
 //! ## Constants
 //!
@@ -36,6 +116,21 @@
 //! - **Transformations**: Uses uniform buffers for applying model-view-projection transformations.
 //! - **Event Handling**: Integrates with input and window handling via `winit`.
 //!
+//! ## Cargo Features
+//!
+//! Whole subsystems can be compiled out to slim builds that only need the wgpu plumbing. All of
+//! them are enabled by default:
+//!
+//! - `gui`: The `egui` layer: the settings window and panels, toasts, the custom title bar, and
+//!   GUI capture. Without it, the `App` renders the scene alone and all input drives the camera.
+//! - `scene3d`: The 3D demo content around the basic scene: the [`crowd`] and [`particles`] demos and the sky.
+//! - `postfx`: Reserved for optional post-processing passes. Nothing is gated on it yet: the
+//!   [`post_process`] chain is always built, since the scene is drawn through its HDR texture.
+//! - `import-gltf`: The [`model`] loader for glTF models, and downloading and caching large
+//!   assets with `reqwest` in the [`asset`] loader, which otherwise only reads local files and
+//!   packs on desktop platforms.
+//! - `profiling`: Profiling views: the [`overdraw`] heatmap debug view.
+//!
 //! ## Usage
 //!
 //! 1. **Initialize the App**: Start by creating an instance of [`App`], which serves as the main entry point.
@@ -80,23 +175,253 @@
 //! - Update the uniform buffer appropriately for transformations to ensure proper rendering.
 //! - Ensure input vertex data matches the shader's expected layout to avoid runtime errors.
 
+mod actions;
+#[cfg(all(feature = "android", target_os = "android"))]
+mod android;
 mod app;
-mod renderer;
+mod asset;
+mod backend;
+mod background;
+#[cfg(not(target_arch = "wasm32"))]
+mod benchmark;
+mod bloom;
+mod camera;
+mod camera_exchange;
+mod camera_path;
+mod capabilities;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+mod compute;
+mod config;
+mod cpu_profiler;
+#[cfg(feature = "scene3d")]
+mod crowd;
+mod curves;
+mod debug_draw;
+mod debug_view;
+mod deletion_queue;
+mod depth_binding;
+mod determinism;
+mod dolly_zoom;
+mod environment;
+mod error;
+mod frame_capture;
+#[cfg(not(target_arch = "wasm32"))]
+mod frame_export;
+mod frame_stats;
+mod frustum;
+mod fullscreen;
+mod gallery;
+mod gestures;
+mod gizmo;
 mod gpu;
+mod gpu_errors;
+mod gpu_timer;
+mod input;
+mod instancing;
+#[cfg(feature = "interop")]
+mod interop;
+mod labels;
+mod layers;
+mod lighting;
+mod lines;
+mod material;
+mod math;
+#[cfg(feature = "import-gltf")]
+mod model;
+mod outline;
+#[cfg(feature = "profiling")]
+mod overdraw;
+mod pack;
+mod particles;
+mod picking;
+mod pipeline_cache;
+mod post_process;
+#[cfg(feature = "gui")]
+mod preview;
+mod probe;
+mod quality;
+mod readback;
+mod redraw;
+mod render_queue;
+mod renderer;
+mod rulers;
+mod safe_area;
 mod scene;
-mod vertex;
-mod uniform_buffer;
+mod scene_graph;
+#[cfg(feature = "gui")]
+mod scene_panel;
+mod settings;
+mod shader_bindings;
+mod shader_editor;
+mod simulation;
+#[cfg(all(feature = "software", not(target_arch = "wasm32")))]
+mod software;
+#[cfg(not(target_arch = "wasm32"))]
+mod streaming;
+#[cfg(not(target_arch = "wasm32"))]
+mod test_matrix;
+mod texture;
+#[cfg(feature = "gui")]
+mod titlebar;
+mod toast;
+mod ui;
+#[cfg(feature = "gui")]
+mod ui_capture;
 mod uniform_binding;
+mod uniform_buffer;
+mod upload;
+mod validation;
+mod vertex;
+mod viewport;
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+mod viewports;
+#[cfg(not(target_arch = "wasm32"))]
+mod windows;
 
 use web_time::Duration;
 
+pub use crate::actions::{Action, ActionMap, Binding};
 pub use crate::app::App;
+pub use crate::asset::{AssetError, AssetLoader};
+pub use crate::backend::{BackendFactory, NullBackend, RendererBackend};
+pub use crate::background::{
+    BackgroundBudget, BackgroundJob, BackgroundScheduler, JobStatus, SlicedDispatch, TaskProgress,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::benchmark::{
+    Benchmark, BenchmarkError, BenchmarkReport, BenchmarkRun, BenchmarkSample, BenchmarkStage,
+};
+pub use crate::bloom::{Bloom, BloomUniform};
+pub use crate::camera::{
+    smoothing_factor, ArcballCamera, Bounds, Camera, CameraInput, CameraMode, CameraSettings,
+    FpsCamera, OrbitCamera, Projection,
+};
+pub use crate::camera_exchange::{CameraExchange, CameraExchangeError};
+pub use crate::camera_path::{
+    CameraKeyframe, CameraPath, CameraPathError, Flythrough, FlythroughControls, PathPose,
+};
+pub use crate::capabilities::{negotiate_features, GpuCapabilities, GpuInfo};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::cli::{BenchOptions, CliError, Command, ExportOptions, Options, USAGE};
+pub use crate::compute::{ComputePass, StorageBuffer};
+pub use crate::config::{
+    AdapterSelection, AppConfig, BackendSelection, ConfigError, RendererConfig,
+};
+pub use crate::cpu_profiler::{CpuFrame, CpuProfiler, CpuScope, CpuScopeGuard};
+#[cfg(feature = "scene3d")]
+pub use crate::crowd::{Bone, CrowdDemo, CrowdInstance, CrowdVertex};
+pub use crate::curves::{default_flight_path, Curve, CurveEditor};
+pub use crate::debug_draw::DebugDraw;
+pub use crate::debug_view::DebugView;
+pub use crate::deletion_queue::{DeletionQueue, Retired};
+pub use crate::depth_binding::{linearize_depth, DepthBinding, DepthPass, DepthPassFrame};
+pub use crate::determinism::{DeterministicRng, FixedTimestep, StateHasher, TickLog};
+pub use crate::dolly_zoom::{
+    dolly_distance, focus_width, DollyZoom, DollyZoomAnimation, DollyZoomPose,
+};
+#[cfg(feature = "scene3d")]
+pub use crate::environment::SkyRenderer;
+pub use crate::environment::{
+    Environment, EnvironmentBinding, EnvironmentPreset, EnvironmentUniform, Fog, Sky,
+};
+pub use crate::error::{default_error_policy, ErrorAction, ErrorPhase, ErrorPolicy, RenderError};
+pub use crate::frame_capture::{FrameCapture, FrameCaptureError};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::frame_export::{export_frames, FrameExport, FrameExportError, FrameSink};
+pub use crate::frame_stats::{FrameStats, FrameStatsSummary};
+pub use crate::frustum::{CullStats, Frustum};
+pub use crate::fullscreen::{FullscreenController, FullscreenControls, FullscreenMode, VideoMode};
+pub use crate::gallery::{plot_axis, WidgetGallery};
+pub use crate::gestures::{Gesture, GestureRecognizer, GestureSettings};
+pub use crate::gizmo::{GizmoMode, GizmoSnapping, GizmoTarget, TransformGizmo};
+pub use crate::gpu::{FrameTarget, Gpu, UploadBelt};
+pub use crate::gpu_errors::{GpuErrorHandler, GpuErrorKind, GpuErrorRecord};
+pub use crate::gpu_timer::{GpuTimer, TimedPass};
+pub use crate::input::{InputCoalescer, InputState, PointerEventCounts};
+pub use crate::instancing::{grid_instances, GridDemo, Instance};
+#[cfg(all(feature = "interop", target_os = "linux"))]
+pub use crate::interop::DmaBuf;
+#[cfg(feature = "interop")]
+pub use crate::interop::{InteropError, SharedFrame, SharedHandle};
+pub use crate::labels::{GlyphAtlas, Label, LabelId, LabelRenderer};
+pub use crate::layers::{LayerMasks, RenderLayers};
+pub use crate::lighting::{Light, LightBinding, LightUniform};
+pub use crate::lines::{grid_lines, LineRenderer, LineSegment};
+pub use crate::material::{
+    Material, MaterialBinding, MaterialHandle, MaterialLibrary, MaterialTextures, MaterialUniform,
+    SceneMaterials, TextureHandle,
+};
+pub use crate::math::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+#[cfg(feature = "import-gltf")]
+pub use crate::model::{Model, ModelError, ModelLoader};
+pub use crate::outline::{OutlineUniform, SelectionOutline};
+#[cfg(feature = "profiling")]
+pub use crate::overdraw::OverdrawView;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::pack::pack_directory;
+pub use crate::pack::{AssetPack, PackBuilder, PackError};
+pub use crate::particles::{Particle, ParticleDemo};
+pub use crate::picking::Picker;
+pub use crate::pipeline_cache::{PipelineCacheError, PipelineCacheStore};
+pub use crate::post_process::{PostEffects, PostProcess, PostUniform, Tonemapping};
+#[cfg(feature = "gui")]
+pub use crate::preview::{PreviewGeometry, PreviewRenderer, PreviewVertex};
+pub use crate::probe::{
+    project, unproject, DepthProbe, DepthProbeSample, BACKGROUND_OBJECT_ID, OBJECT_KIND_BITS,
+};
+pub use crate::quality::{
+    AdaptiveQuality, FrameTimeHistory, QualityBounds, QualityDecision, QualityLevels,
+    QualityScaler, QualitySetting,
+};
+pub use crate::redraw::{FramePacer, RedrawPolicy, RedrawScheduler};
+pub use crate::render_queue::{DrawStats, RenderQueue, SortKey, StateChange};
 pub use crate::renderer::Renderer;
-pub use crate::gpu::Gpu;
-pub use crate::scene::Scene;
-pub use crate::vertex::{Vertex, VERTICES};
-pub use crate::uniform_buffer::UniformBuffer;
+pub use crate::rulers::{
+    tick_decimals, tick_indices, tick_step, world_units_per_pixel, Guide, GuideAxis,
+};
+pub use crate::safe_area::SafeAreaInsets;
+pub use crate::scene::{MeshData, PendingShader, Rasterization, RenderMode, Scene, SceneMesh};
+pub use crate::scene_graph::{MeshHandle, Node, NodeId, SceneGraph};
+#[cfg(feature = "gui")]
+pub use crate::scene_panel::ScenePanel;
+pub use crate::settings::{CameraPose, GuiScale, Settings, SettingsError, WindowGeometry};
+pub use crate::shader_editor::{ShaderEditor, ShaderError};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::simulation::SimulationThread;
+pub use crate::simulation::{
+    animate, simulate, FixedStepSimulation, SceneSnapshot, TripleBuffer, TICK,
+};
+#[cfg(all(feature = "software", not(target_arch = "wasm32")))]
+pub use crate::software::SoftwareBackend;
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::streaming::{FrameStreamer, StreamError};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::test_matrix::{
+    psnr, run_test_matrix, ssim, Comparison, GalleryScene, Screenshot, TestMatrixError,
+    TestMatrixReport, SCREENSHOT_SIZE, SSIM_THRESHOLD,
+};
+pub use crate::texture::{Texture, TextureError};
+#[cfg(feature = "gui")]
+pub use crate::titlebar::{TitlebarAction, TITLEBAR_HEIGHT};
+pub use crate::toast::{Toast, ToastLevel, Toasts};
+pub use crate::ui::UiState;
+#[cfg(feature = "gui")]
+pub use crate::ui_capture::{UiCapture, UiCaptureError};
 pub use crate::uniform_binding::UniformBinding;
+pub use crate::uniform_buffer::UniformBuffer;
+pub use crate::upload::UploadScheduler;
+pub use crate::validation::{
+    resolve_alpha_mode, resolve_present_mode, resolve_sample_count, validate_bind_group_count,
+    validate_limit, validate_present_mode, validate_sample_count, validate_surface_format,
+    validate_surface_usage, validate_texture_format, ValidationError,
+};
+pub use crate::vertex::{Vertex, VERTICES};
+pub use crate::viewport::{AspectLock, Viewport};
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+pub use crate::viewports::{ViewportSurface, ViewportWindows};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::windows::{WindowError, WindowKind, WindowState};
 
 /// An array of indices defining the order of vertices to draw a triangle.
 ///
@@ -121,8 +446,10 @@ pub const INDICES: [u32; 3] = [0, 1, 2]; // Clockwise winding order
 /// ### Uniform
 ///
 /// The shader declares a uniform buffer:
-/// - `ubo`: Contains a single 4x4 matrix named `mvp` (model-view-projection matrix) used to
-///   transform vertex positions in the vertex stage.
+/// - `ubo`: Contains a 4x4 matrix named `mvp` (model-view-projection matrix) used to
+///   transform vertex positions in the vertex stage, and the `model` matrix placing positions
+///   and normals in world space for lighting.
+/// - `light`: The scene's directional light (`@group(3)`), see the `lighting` module.
 ///
 /// ### Vertex Stage
 ///
@@ -130,10 +457,14 @@ pub const INDICES: [u32; 3] = [0, 1, 2]; // Clockwise winding order
 /// via `VertexInput`:
 /// - `@location(0) position`: The position of the vertex as a 4D vector `[x, y, z, w]`.
 /// - `@location(1) color`: The color of the vertex as a 4D vector `[r, g, b, a]`.
+/// - `@location(2) uv`: The texture coordinates of the vertex.
+/// - `@location(3) normal`: The unit normal of the surface at the vertex.
 ///
 /// The output of the vertex stage, `VertexOutput`, includes:
 /// - `@builtin(position) position`: The transformed position of the vertex.
 /// - `@location(0) color`: The color passed through to the fragment shader.
+/// - `@location(3) world_position` and `@location(4) normal`: The position and normal in
+///   world space, for lighting.
 ///
 /// The vertex shader applies the `mvp` matrix to the vertex position to calculate
 /// the transformed position of the vertex.
@@ -143,6 +474,11 @@ pub const INDICES: [u32; 3] = [0, 1, 2]; // Clockwise winding order
 /// The fragment shader (`fragment_main`) receives as input the interpolated outputs
 /// from the vertex stage:
 /// - `@location(0) color`: The interpolated color of the triangle.
+/// - `@location(2) uv`: The interpolated texture coordinates, at which the material's
+///   textures (`@group(2)`) are sampled and multiplied with its base color, metallic,
+///   roughness, and emissive color.
+/// - `@location(3) world_position` and `@location(4) normal`: The interpolated world-space
+///   position and normal, with which the color is shaded by the metallic-roughness model.
 ///
 /// The fragment shader outputs:
 /// - `@location(0) vec4<f32>`: The final color of the rendered fragment.
@@ -0,0 +1,155 @@
+//! # Lighting Module
+//!
+//! The `lighting` module holds the scene's directional light, with which the scene's shaders
+//! shade the materials of the meshes, so surfaces turned towards the light are brighter than
+//! those turned away and shiny highlights follow the camera.
+//!
+//! ## Overview
+//!
+//! - [`Light`] is the light's direction, color, and strength, edited in the Inspector panel.
+//!   Its direction is given by an azimuth and an elevation, which are easier to edit than a
+//!   vector.
+//! - [`LightBinding`] uploads the light, reduced to a [`LightUniform`], to a uniform buffer
+//!   bound at set 3 of the scene's pipelines.
+//!
+//! Each fragment is lit by the ambient light of the environment, scaled by
+//! [`Light::ambient`], and by the directional light, as its material reflects them (see the
+//! `material` module). Fog and exposure are applied afterwards, as set by the environment.
+//!
+//! The default light matches the key light models were lit with when their lighting was baked
+//! into their vertex colors, so models look much as before until the light is changed.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let light = Light {
+//!     azimuth: 30.0,
+//!     elevation: 20.0,
+//!     ..Light::default()
+//! };
+//! renderer.set_light(&light);
+//! ```
+
+use crate::shader_bindings::shader_source;
+
+/// A directional light, such as the sun, shining on the whole scene from one direction.
+///
+/// Missing fields take their default values when deserializing, so saved lights keep loading
+/// when settings are added.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Light {
+    /// The angle of the light around the vertical axis, in degrees. At 0 the light shines from
+    /// the default camera's side, and positive angles move it to the camera's left.
+    pub azimuth: f32,
+
+    /// The angle of the light above the horizon, in degrees, from -90 (below) to 90 (above).
+    pub elevation: f32,
+
+    /// The linear color of the light.
+    pub color: [f32; 3],
+
+    /// The strength of the diffuse light.
+    pub intensity: f32,
+
+    /// The share of the environment's ambient light that reaches the surfaces.
+    pub ambient: f32,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Self {
+            azimuth: -41.6,
+            elevation: 53.1,
+            color: [1.0, 1.0, 1.0],
+            intensity: 0.65,
+            ambient: 0.35,
+        }
+    }
+}
+
+impl Light {
+    /// Returns the unit direction towards the light, in the scene's coordinate system.
+    pub fn direction(&self) -> [f32; 3] {
+        let (azimuth, elevation) = (self.azimuth.to_radians(), self.elevation.to_radians());
+        [
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+            elevation.cos() * azimuth.cos(),
+        ]
+    }
+
+    /// Reduces the light to the values the shaders use, for a camera at `eye`.
+    pub fn uniform(&self, eye: &crate::math::Vec3) -> LightUniform {
+        let [x, y, z] = self.direction();
+        let [r, g, b] = self.color.map(|channel| channel * self.intensity);
+        LightUniform {
+            direction: [x, y, z, self.ambient],
+            color: [r, g, b, 1.0],
+            eye: [eye.x, eye.y, eye.z, 1.0],
+        }
+    }
+}
+
+/// The light as read by the shaders.
+///
+/// Vectors are stored in `vec4`s to match WGSL's uniform layout rules.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightUniform {
+    /// The unit direction towards the light in `xyz`, and the ambient share in `w`.
+    pub direction: [f32; 4],
+
+    /// The light's color times its intensity in `rgb`.
+    pub color: [f32; 4],
+
+    /// The camera's position in world space in `xyz`.
+    pub eye: [f32; 4],
+}
+
+/// The uniform buffer holding the [`LightUniform`], and its bind group.
+#[derive(Debug)]
+pub struct LightBinding {
+    /// The buffer holding the `LightUniform`.
+    pub buffer: wgpu::Buffer,
+
+    /// The layout of `bind_group`, at set 3 of the scene's pipelines.
+    pub bind_group_layout: wgpu::BindGroupLayout,
+
+    /// Exposes `buffer` to the shaders.
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl LightBinding {
+    /// Creates the binding, holding the default light seen from the default camera.
+    pub fn new(device: &wgpu::Device) -> Self {
+        let buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Light Uniform Buffer"),
+                contents: bytemuck::bytes_of(
+                    &Light::default().uniform(&crate::math::vec3(0.0, 0.0, 3.0)),
+                ),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+
+        // `instanced.wgsl` declares the same group as `shader_source.wgsl`.
+        let bind_group_layout = shader_source::BindGroup3::create_layout(device);
+        let bind_group = shader_source::BindGroup3 {
+            light: buffer.as_entire_buffer_binding(),
+        }
+        .create(device, &bind_group_layout);
+
+        Self {
+            buffer,
+            bind_group_layout,
+            bind_group,
+        }
+    }
+
+    /// Uploads `light` for a camera at `eye`.
+    pub fn update(&self, queue: &wgpu::Queue, light: &Light, eye: &crate::math::Vec3) {
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&light.uniform(eye)));
+    }
+}
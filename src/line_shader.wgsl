@@ -0,0 +1,102 @@
+// Wide, anti-aliased lines.
+//
+// Every line segment is one instance drawn as a 4-vertex triangle strip. The vertex shader
+// projects both endpoints, and moves each corner of the quad away from the segment in screen
+// space, so the quad always faces the camera and is `width` pixels wide regardless of depth.
+// The fragment shader fades the outermost pixel on either side for smooth edges.
+
+struct LineUniform {
+    view_projection: mat4x4<f32>,
+    // The size of the render target in pixels.
+    viewport: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> ubo: LineUniform;
+
+struct LineInput {
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) start: vec3<f32>,
+    @location(1) width: f32,
+    @location(2) end: vec3<f32>,
+    @location(3) color: vec4<f32>,
+};
+
+struct LineOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    // The signed distance from the center of the line, in pixels.
+    @location(1) distance: f32,
+    @location(2) half_width: f32,
+};
+
+// The width of the anti-aliased edge, in pixels.
+const FEATHER: f32 = 1.0;
+
+@vertex
+fn vertex_main(in: LineInput) -> LineOutput {
+    var out: LineOutput;
+    out.color = in.color;
+
+    var start = ubo.view_projection * vec4<f32>(in.start, 1.0);
+    var end = ubo.view_projection * vec4<f32>(in.end, 1.0);
+
+    // Clip the segment against the near plane (z = 0), so endpoints behind the camera do not
+    // flip to the other side of the screen when dividing by w.
+    if start.z < 0.0 && end.z < 0.0 {
+        out.position = vec4<f32>(0.0, 0.0, -1.0, 1.0);
+        return out;
+    }
+    if start.z < 0.0 {
+        start = mix(start, end, start.z / (start.z - end.z));
+    }
+    if end.z < 0.0 {
+        end = mix(end, start, end.z / (end.z - start.z));
+    }
+
+    // The segment's direction and normal in pixels.
+    let screen_start = start.xy / start.w * 0.5 * ubo.viewport;
+    let screen_end = end.xy / end.w * 0.5 * ubo.viewport;
+    var direction = vec2<f32>(1.0, 0.0);
+    if distance(screen_start, screen_end) > 1e-6 {
+        direction = normalize(screen_end - screen_start);
+    }
+    let normal = vec2<f32>(-direction.y, direction.x);
+
+    // Lines thinner than a pixel keep a one pixel wide core and fade out instead.
+    out.half_width = in.width * 0.5;
+    let extent = max(out.half_width, 0.5) + FEATHER;
+
+    // Vertices 0 and 1 sit at the start, 2 and 3 at the end; odd vertices on the left.
+    var side = -1.0;
+    if (in.vertex_index & 1u) == 1u {
+        side = 1.0;
+    }
+    var position = start;
+    if in.vertex_index >= 2u {
+        position = end;
+    }
+
+    let offset = normal * side * extent / (0.5 * ubo.viewport) * position.w;
+    out.position = vec4<f32>(position.xy + offset, position.zw);
+    out.distance = side * extent;
+    return out;
+}
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    // Lines do not belong to an object; the pipeline masks out writes to the id target.
+    @location(1) object_id: u32,
+};
+
+@fragment
+fn fragment_main(in: LineOutput) -> FragmentOutput {
+    let core = max(in.half_width, 0.5);
+    let coverage = clamp(core + FEATHER * 0.5 - abs(in.distance), 0.0, 1.0)
+        * min(in.half_width * 2.0, 1.0);
+
+    var out: FragmentOutput;
+    out.color = vec4<f32>(in.color.rgb, in.color.a * coverage);
+    out.object_id = 0u;
+    return out;
+}
@@ -0,0 +1,472 @@
+//! # Lines Module
+//!
+//! The `lines` module draws wide, anti-aliased line segments. `wgpu`'s `LineList` topology
+//! only produces one pixel wide, aliased lines, so each segment is instead expanded into a
+//! camera-facing quad in the vertex shader (`line_shader.wgsl`), with its width given in
+//! logical pixels, scaled by the window's scale factor, and its edges faded out over one
+//! logical pixel.
+//!
+//! ## Overview
+//!
+//! - [`LineSegment`] is one line with its own width and color, uploaded as instance data.
+//! - [`LineRenderer`] collects segments for the current frame and draws them in one
+//!   instanced draw call. It is used for immediate-mode debug drawing: segments are queued
+//!   with [`LineRenderer::line`] every frame and discarded once they have been drawn.
+//! - [`LineRenderer::overlay_line`] queues a segment drawn on top of the scene, without depth
+//!   testing, for handles that must stay visible inside the objects they manipulate.
+//! - [`LineRenderer::curve`] queues a Bezier curve or Catmull-Rom spline, tessellated for the
+//!   current camera (see the `curves` module).
+//! - [`grid_lines`] builds the segments of the ground grid, which the `Renderer` queues when
+//!   the grid is shown.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! lines.line(
+//!     nalgebra_glm::vec3(0.0, 0.0, 0.0),
+//!     nalgebra_glm::vec3(0.0, 1.0, 0.0),
+//!     3.0,
+//!     [1.0, 1.0, 0.0, 1.0],
+//! );
+//! lines.prepare(&device, &mut uploads, &view_projection, (width, height));
+//! lines.render(&mut render_pass);
+//! ```
+
+// Importing the WGSL source of the wide line shader.
+const LINE_SHADER_SOURCE: &str = include_str!("line_shader.wgsl");
+
+use crate::camera::Bounds;
+use crate::curves::Curve;
+use crate::gpu::UploadBelt;
+use crate::renderer::Renderer;
+use crate::scene::Scene;
+use crate::shader_bindings::line_shader;
+
+/// A line segment drawn by the [`LineRenderer`].
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LineSegment {
+    /// The start of the segment in world space.
+    pub start: [f32; 3],
+
+    /// The width of the line in pixels. Widths below one pixel are faded out.
+    pub width: f32,
+
+    /// The end of the segment in world space.
+    pub end: [f32; 3],
+
+    /// The color of the line as RGBA. The alpha channel is multiplied with the edge coverage.
+    pub color: [f32; 4],
+}
+
+impl LineSegment {
+    /// Creates a segment from `start` to `end`, `width` pixels wide.
+    pub fn new(
+        start: crate::math::Vec3,
+        end: crate::math::Vec3,
+        width: f32,
+        color: [f32; 4],
+    ) -> Self {
+        Self {
+            start: start.into(),
+            width,
+            end: end.into(),
+            color,
+        }
+    }
+
+    /// Returns the per-instance vertex attributes, matching `LineInput` in the shader.
+    pub fn vertex_attributes() -> Vec<wgpu::VertexAttribute> {
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32, 2 => Float32x3, 3 => Float32x4]
+            .to_vec()
+    }
+
+    /// Returns the instance buffer layout for the given `attributes`.
+    pub fn description(attributes: &[wgpu::VertexAttribute]) -> wgpu::VertexBufferLayout {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<LineSegment>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes,
+        }
+    }
+}
+
+/// The color of the grid's regular lines.
+const GRID_COLOR: [f32; 4] = [0.6, 0.6, 0.6, 0.5];
+
+/// The colors of the grid lines along the X and Z axes.
+const GRID_AXIS_COLORS: [[f32; 4]; 2] = [[0.9, 0.2, 0.2, 1.0], [0.2, 0.4, 0.9, 1.0]];
+
+/// Builds a square grid on the XZ plane at the given `height`.
+///
+/// The grid has `2 * cells + 1` lines in each direction, `spacing` world units apart and
+/// centered on the Y axis. The two lines through the origin are highlighted in the axis
+/// colors and drawn twice as wide.
+pub fn grid_lines(cells: u32, spacing: f32, height: f32, width: f32) -> Vec<LineSegment> {
+    let cells = cells as i32;
+    let extent = cells as f32 * spacing;
+    let mut lines = Vec::with_capacity(2 * (2 * cells as usize + 1));
+    for index in -cells..=cells {
+        let offset = index as f32 * spacing;
+        let (x_color, z_color, line_width) = if index == 0 {
+            (GRID_AXIS_COLORS[0], GRID_AXIS_COLORS[1], width * 2.0)
+        } else {
+            (GRID_COLOR, GRID_COLOR, width)
+        };
+        // A line parallel to the X axis, then one parallel to the Z axis.
+        lines.push(LineSegment::new(
+            crate::math::vec3(-extent, height, offset),
+            crate::math::vec3(extent, height, offset),
+            line_width,
+            x_color,
+        ));
+        lines.push(LineSegment::new(
+            crate::math::vec3(offset, height, -extent),
+            crate::math::vec3(offset, height, extent),
+            line_width,
+            z_color,
+        ));
+    }
+    lines
+}
+
+/// The uniform data of the line shader.
+#[repr(C)]
+#[derive(Default, Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct LineUniform {
+    /// The matrix transforming world space into clip space.
+    view_projection: crate::math::Mat4,
+
+    /// The size of the render target in logical pixels, which line widths refer to.
+    viewport: [f32; 2],
+
+    /// Pads the struct to the 16 byte alignment of WGSL uniforms.
+    _padding: [f32; 2],
+}
+
+/// Draws the line segments queued for the current frame.
+#[derive(Debug)]
+pub struct LineRenderer {
+    /// The pipeline expanding each segment into a quad.
+    pipeline: wgpu::RenderPipeline,
+
+    /// The variant of `pipeline` drawing overlay segments, without depth testing.
+    overlay_pipeline: wgpu::RenderPipeline,
+
+    /// The buffer holding the `LineUniform`.
+    uniform_buffer: wgpu::Buffer,
+
+    /// The bind group exposing `uniform_buffer` to the shader.
+    bind_group: wgpu::BindGroup,
+
+    /// The instance buffer, grown as needed.
+    instance_buffer: wgpu::Buffer,
+
+    /// The segments queued since the last `prepare`.
+    queued: Vec<LineSegment>,
+
+    /// The overlay segments queued since the last `prepare`.
+    queued_overlay: Vec<LineSegment>,
+
+    /// The number of depth tested segments uploaded by the last `prepare`.
+    instance_count: u32,
+
+    /// The number of overlay segments uploaded by the last `prepare`, after the others.
+    overlay_count: u32,
+
+    /// The physical pixels per logical pixel of the render target.
+    scale_factor: f32,
+}
+
+impl LineRenderer {
+    /// The number of segments the instance buffer has room for initially.
+    const INITIAL_CAPACITY: u64 = 256;
+
+    /// The maximum on-screen distance between a curve and its tessellation, in pixels.
+    pub const CURVE_TOLERANCE: f32 = 0.5;
+
+    /// Creates a line renderer drawing into the scene pass's color targets, compiling its
+    /// pipeline through `cache` if one is given.
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let uniform_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Line Uniform Buffer"),
+                contents: bytemuck::cast_slice(&[LineUniform::default()]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+
+        let bind_group_layout = line_shader::BindGroup0::create_layout(device);
+        let bind_group = line_shader::BindGroup0 {
+            ubo: uniform_buffer.as_entire_buffer_binding(),
+        }
+        .create(device, &bind_group_layout);
+
+        let pipeline =
+            Self::create_pipeline(device, surface_format, &bind_group_layout, false, cache);
+        let overlay_pipeline =
+            Self::create_pipeline(device, surface_format, &bind_group_layout, true, cache);
+
+        Self {
+            pipeline,
+            overlay_pipeline,
+            uniform_buffer,
+            bind_group,
+            instance_buffer: Self::create_instance_buffer(device, Self::INITIAL_CAPACITY),
+            queued: Vec::new(),
+            queued_overlay: Vec::new(),
+            instance_count: 0,
+            overlay_count: 0,
+            scale_factor: 1.0,
+        }
+    }
+
+    /// Draws the lines for a render target with `scale_factor` physical pixels per logical
+    /// pixel, so they keep their width on screen on displays of any density.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor.max(f32::EPSILON);
+    }
+
+    /// Queues a segment from `start` to `end`, `width` pixels wide, for the current frame.
+    pub fn line(
+        &mut self,
+        start: crate::math::Vec3,
+        end: crate::math::Vec3,
+        width: f32,
+        color: [f32; 4],
+    ) {
+        self.queued.push(LineSegment::new(start, end, width, color));
+    }
+
+    /// Queues a segment like [`LineRenderer::line`], but drawn on top of the scene: overlay
+    /// segments are not depth tested, and are drawn after all other segments.
+    pub fn overlay_line(
+        &mut self,
+        start: crate::math::Vec3,
+        end: crate::math::Vec3,
+        width: f32,
+        color: [f32; 4],
+    ) {
+        self.queued_overlay
+            .push(LineSegment::new(start, end, width, color));
+    }
+
+    /// Queues `curve` for the current frame, tessellated for the current camera.
+    pub fn curve(
+        &mut self,
+        curve: &Curve,
+        view_projection: &crate::math::Mat4,
+        viewport: (u32, u32),
+        width: f32,
+        color: [f32; 4],
+    ) {
+        let polyline = curve.tessellate(view_projection, viewport, Self::CURVE_TOLERANCE);
+        self.extend(
+            polyline
+                .windows(2)
+                .map(|pair| LineSegment::new(pair[0], pair[1], width, color)),
+        );
+    }
+
+    /// Queues several segments for the current frame.
+    pub fn extend(&mut self, segments: impl IntoIterator<Item = LineSegment>) {
+        self.queued.extend(segments);
+    }
+
+    /// Returns the bounds of the segments queued so far, or `None` if none are queued.
+    ///
+    /// Overlay segments are left out, as they annotate the scene rather than belong to it.
+    pub fn queued_bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(self.queued.iter().flat_map(|segment| {
+            [
+                crate::math::Vec3::from(segment.start),
+                crate::math::Vec3::from(segment.end),
+            ]
+        }))
+    }
+
+    /// Uploads the queued segments and the camera for drawing through `uploads`, and clears
+    /// the queue.
+    ///
+    /// `viewport` is the size of the render target in physical pixels. Line widths refer to
+    /// logical pixels, `set_scale_factor` times smaller.
+    pub fn prepare(
+        &mut self,
+        device: &wgpu::Device,
+        uploads: &mut UploadBelt,
+        view_projection: &crate::math::Mat4,
+        viewport: (u32, u32),
+    ) {
+        uploads.write(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[LineUniform {
+                view_projection: *view_projection,
+                viewport: [
+                    viewport.0 as f32 / self.scale_factor,
+                    viewport.1 as f32 / self.scale_factor,
+                ],
+                _padding: [0.0; 2],
+            }]),
+        );
+
+        // Overlay segments are uploaded after the others, so both are drawn from one buffer.
+        let count = self.queued.len() + self.queued_overlay.len();
+        let size = (count * std::mem::size_of::<LineSegment>()) as wgpu::BufferAddress;
+        if size > self.instance_buffer.size() {
+            let capacity = (count as u64).next_power_of_two();
+            self.instance_buffer = Self::create_instance_buffer(device, capacity);
+        }
+        if !self.queued.is_empty() {
+            uploads.write(&self.instance_buffer, 0, bytemuck::cast_slice(&self.queued));
+        }
+        if !self.queued_overlay.is_empty() {
+            uploads.write(
+                &self.instance_buffer,
+                std::mem::size_of_val(self.queued.as_slice()) as wgpu::BufferAddress,
+                bytemuck::cast_slice(&self.queued_overlay),
+            );
+        }
+
+        self.instance_count = self.queued.len() as u32;
+        self.overlay_count = self.queued_overlay.len() as u32;
+        self.queued.clear();
+        self.queued_overlay.clear();
+    }
+
+    /// Draws the segments uploaded by the last `prepare`.
+    pub fn render<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        if self.is_empty() {
+            return;
+        }
+        self.bind_pipeline(render_pass);
+        self.bind_material(render_pass);
+        self.draw(render_pass);
+    }
+
+    /// Returns `true` if the last `prepare` uploaded no segments, so there is nothing to draw.
+    pub fn is_empty(&self) -> bool {
+        self.instance_count == 0 && self.overlay_count == 0
+    }
+
+    /// Sets the line pipeline.
+    pub fn bind_pipeline<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_pipeline(&self.pipeline);
+    }
+
+    /// Binds the view uniforms, the lines' material, at set 0.
+    pub fn bind_material<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+    }
+
+    /// Draws the uploaded segments, after the pipeline and material are bound.
+    ///
+    /// Overlay segments are drawn last, switching to the overlay pipeline, which stays bound.
+    pub fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+        if self.instance_count > 0 {
+            render_pass.draw(0..4, 0..self.instance_count);
+        }
+        if self.overlay_count > 0 {
+            render_pass.set_pipeline(&self.overlay_pipeline);
+            render_pass.draw(
+                0..4,
+                self.instance_count..self.instance_count + self.overlay_count,
+            );
+        }
+    }
+
+    /// Creates an instance buffer with room for `capacity` segments.
+    fn create_instance_buffer(device: &wgpu::Device, capacity: u64) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Line Instance Buffer"),
+            size: capacity * std::mem::size_of::<LineSegment>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Creates the pipeline expanding segments into anti-aliased quads.
+    ///
+    /// Lines are depth tested against the scene, unless `overlay` is set, but never write
+    /// depth, and they leave the object id target untouched, so the depth probe reports the
+    /// geometry behind them.
+    fn create_pipeline(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        overlay: bool,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> wgpu::RenderPipeline {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Line Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(LINE_SHADER_SOURCE)),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Line Pipeline Layout"),
+            bind_group_layouts: &[bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(if overlay {
+                "Overlay Line Pipeline"
+            } else {
+                "Line Pipeline"
+            }),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vertex_main"),
+                buffers: &[LineSegment::description(&LineSegment::vertex_attributes())],
+                compilation_options: Default::default(),
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+                unclipped_depth: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: Renderer::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: if overlay {
+                    wgpu::CompareFunction::Always
+                } else {
+                    wgpu::CompareFunction::Less
+                },
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fragment_main"),
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: surface_format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(wgpu::ColorTargetState {
+                        format: Scene::OBJECT_ID_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::empty(),
+                    }),
+                ],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        })
+    }
+}
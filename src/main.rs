@@ -23,14 +23,46 @@
 //! ```sh
 //! $ cargo run --release
 //! ```
+//!
+//! To bundle the `assets` directory into the asset pack `assets.pak`:
+//! ```sh
+//! $ cargo run --release -- pack assets assets.pak
+//! ```
+//!
+//! To read the model and the texture from that pack, a path or an HTTP(S) URL:
+//! ```sh
+//! $ cargo run --release -- --pack assets.pak --model models/Duck.glb
+//! ```
+//!
+//! To pick the graphics backend and adapter, by index or by a part of its name:
+//! ```sh
+//! $ cargo run --release -- --backend vulkan --adapter 1
+//! $ WGPU_BACKEND=dx12 WGPU_ADAPTER=nvidia cargo run --release
+//! ```
+//!
+//! To render the camera path exported from the "Flythrough" settings into a video or, for any
+//! other output, a directory of PNGs:
+//! ```sh
+//! $ cargo run --release -- --export frames.mp4 --size 1920x1080 --fps 60
+//! ```
+//!
+//! To benchmark a growing instanced workload without vsync and write the frame times as CSV:
+//! ```sh
+//! $ cargo run --release -- --bench bench.csv --backend vulkan
+//! ```
+//!
+//! To render the demo gallery on every adapter and compare the screenshots across backends:
+//! ```sh
+//! $ cargo run --release -- test-matrix
+//! ```
 
 // #![windows_subsystem = "windows"] // uncomment this to suppress terminal on windows
 
 /// The main entry point of the application.
 ///
 /// # Overview
-/// This function initializes the event loop using the `winit` crate, creates an instance of 
-/// `app_core::App`, and then runs the application using the event loop. The control flow of 
+/// This function initializes the event loop using the `winit` crate, creates an instance of
+/// `app_core::App`, and then runs the application using the event loop. The control flow of
 /// the event loop is set to `Poll` mode, which continuously polls for events.
 ///
 /// # Returns
@@ -38,7 +70,7 @@
 /// or execution of the event loop using `winit::error::EventLoopError`.
 ///
 /// # Platform-Specific Notes
-/// On Windows platforms, the console window can be suppressed by uncommenting the 
+/// On Windows platforms, the console window can be suppressed by uncommenting the
 /// `#![windows_subsystem = "windows"]` attribute at the top of this file.
 ///
 /// # Errors
@@ -53,6 +85,111 @@
 /// To suppress the console window on Windows:
 /// Uncomment the `#![windows_subsystem = "windows"]` line at the top of this file.
 fn main() -> Result<(), winit::error::EventLoopError> {
+    // Every argument is parsed up front, and an unknown one or an invalid value prints the
+    // usage message and exits, so a misspelled option is never silently ignored:
+    //
+    // ```sh
+    // $ cargo run --release -- --help
+    // ```
+    #[cfg(not(target_arch = "wasm32"))]
+    let options = match app_core::Command::parse(std::env::args().skip(1), |name| {
+        std::env::var(name).ok()
+    }) {
+        Ok(app_core::Command::Run(options)) => options,
+
+        // The `pack` subcommand bundles a directory of assets into one asset pack and exits,
+        // without opening a window:
+        //
+        // ```sh
+        // $ cargo run --release -- pack [SOURCE_DIR] [OUTPUT]
+        // ```
+        //
+        // The source directory defaults to `assets` and the output to `assets.pak`.
+        Ok(app_core::Command::Pack {
+            source,
+            destination,
+        }) => {
+            match app_core::pack_directory(&source, &destination) {
+                Ok(count) => println!("Packed {count} files from '{source}' into '{destination}'"),
+                Err(error) => {
+                    eprintln!("Failed to pack '{source}': {error}");
+                    std::process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+
+        // The `test-matrix` subcommand renders the demo gallery on every adapter, writes the
+        // screenshots and a comparison report into the output directory (`test-matrix` by
+        // default), and fails if any backend differs from the reference:
+        //
+        // ```sh
+        // $ cargo run --release -- test-matrix [OUTPUT_DIR]
+        // ```
+        Ok(app_core::Command::TestMatrix { output }) => {
+            match app_core::run_test_matrix(&output) {
+                Ok(report) => {
+                    println!("{}", report.to_markdown());
+                    println!("Wrote the screenshots and report to '{output}'");
+                    if !report.passed() {
+                        std::process::exit(1);
+                    }
+                }
+                Err(error) => {
+                    eprintln!("Failed to run the test matrix: {error}");
+                    std::process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        Ok(app_core::Command::Help) => {
+            println!("{}", app_core::USAGE);
+            return Ok(());
+        }
+        Err(error) => {
+            eprintln!("{error}\n\n{}", app_core::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    // The `--export` option renders a camera path, exported from the "Flythrough"
+    // settings, into a video (with `ffmpeg`) or a directory of PNGs, and exits:
+    //
+    // ```sh
+    // $ cargo run --release -- --export frames.mp4 [--camera-path camera_path.json]
+    //       [--size 1920x1080] [--fps 60] [--frames N] [--seed 0]
+    // ```
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(options) = &options.export {
+        let exit = |message: String| -> ! {
+            eprintln!("{message}");
+            std::process::exit(1);
+        };
+        let camera_path = &options.camera_path;
+        let path = std::fs::read_to_string(camera_path)
+            .map_err(app_core::CameraPathError::from)
+            .and_then(|json| app_core::CameraPath::from_json(&json))
+            .unwrap_or_else(|error| exit(format!("Failed to read '{camera_path}': {error}")));
+        let mut export = app_core::FrameExport::new(&options.output, path);
+        if let Some((width, height)) = options.size {
+            export = export.with_size(width, height);
+        }
+        if let Some(frame_rate) = options.frame_rate {
+            export = export.with_frame_rate(frame_rate);
+        }
+        if let Some(frames) = options.frames {
+            export = export.with_frames(frames);
+        }
+        if let Some(seed) = options.seed {
+            export = export.with_seed(seed);
+        }
+        match app_core::export_frames(&export) {
+            Ok(frames) => println!("Exported {frames} frames to '{}'", options.output),
+            Err(error) => exit(format!("Failed to export the frames: {error}")),
+        }
+        return Ok(());
+    }
+
     // The event loop that drives the application by continuously polling for events.
     //
     // # Overview
@@ -64,14 +201,15 @@ fn main() -> Result<(), winit::error::EventLoopError> {
     // The control flow of this event loop is explicitly set to `Poll` mode. In this mode,
     // the event loop continuously polls for events, ensuring that the application remains
     // responsive. This is particularly useful for rendering applications where high frame
-    // rates are important.
+    // rates are important. Once running, the `App` switches to waiting whenever its redraw
+    // policy (`On Demand` or `Fixed Rate`) lets the event loop sleep between frames.
     //
     // # Errors
     // If an error occurs during the creation of the event loop, it will return
     // a `winit::error::EventLoopError`.
     //
     // # Platform-Specific Notes
-    // On Windows platforms, suppress the console window by uncommenting the 
+    // On Windows platforms, suppress the console window by uncommenting the
     // `#![windows_subsystem = "windows"]` line at the top of the file.
     let event_loop = winit::event_loop::EventLoop::builder().build()?;
 
@@ -99,6 +237,99 @@ fn main() -> Result<(), winit::error::EventLoopError> {
     // ```
     let mut app = app_core::App::default();
 
+    // The `--model` option draws a glTF or GLB model, a path or an HTTP(S) URL, in place of
+    // the triangle:
+    //
+    // ```sh
+    // $ cargo run --release -- --model assets/models/Duck.glb
+    // ```
+    //
+    // The `--texture` option textures the scene with a PNG or JPEG image:
+    //
+    // ```sh
+    // $ cargo run --release -- --texture assets/textures/checker.png
+    // ```
+    //
+    // The `--backend` option (`auto`, `vulkan`, `dx12`, `metal`, or `gl`) and the `--adapter`
+    // option (an index or a part of the adapter's name) pick the GPU the renderer uses. They
+    // default to the `WGPU_BACKEND` and `WGPU_ADAPTER` environment variables:
+    //
+    // ```sh
+    // $ cargo run --release -- --backend vulkan --adapter 1
+    // ```
+    //
+    // The `--pack` option mounts an asset pack, a path or an HTTP(S) URL, which the model and
+    // the texture are read from first:
+    //
+    // ```sh
+    // $ cargo run --release -- --pack assets.pak --model models/Duck.glb
+    // ```
+    //
+    // The `--bench` option runs the benchmark without vsync, writes its report (`bench.csv`
+    // by default, or JSON for a `.json` path), prints a summary, and exits. The
+    // `--bench-duration` option sets its length in seconds, and `--bench-stages` the number
+    // of times the instanced workload grows:
+    //
+    // ```sh
+    // $ cargo run --release -- --bench bench.json --backend dx12 --bench-duration 32
+    // ```
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let mut config = app_core::AppConfig::default();
+        if let Some(pack) = options.pack {
+            config = config.with_asset_pack(pack);
+        }
+        if let Some(backend) = options.backend {
+            config = config.with_backend(backend);
+        }
+        if let Some(adapter) = options.adapter {
+            config = config.with_adapter(adapter);
+        }
+        if let Some(bench) = options.bench {
+            let mut benchmark = app_core::Benchmark::new(bench.output);
+            if let Some(duration) = bench.duration {
+                benchmark = benchmark.with_duration(duration);
+            }
+            if let Some(stages) = bench.stages {
+                benchmark = benchmark.with_stages(stages);
+            }
+            config = config
+                .with_vsync(false)
+                .with_redraw_policy(app_core::RedrawPolicy::Continuous);
+            app = app.with_benchmark(benchmark);
+        }
+        app = app.with_config(config);
+        #[cfg(feature = "import-gltf")]
+        if let Some(source) = options.model {
+            app = app.with_model(source);
+        }
+        if let Some(source) = options.texture {
+            app = app.with_texture(source);
+        }
+    }
+
+    // On the web, the page's query string picks the model, the texture, and the asset pack
+    // they are read from, as in `index.html?pack=assets.pak&model=models/Duck.glb`. Relative
+    // sources are resolved against the page URL.
+    #[cfg(target_arch = "wasm32")]
+    {
+        let params = web_sys::window()
+            .and_then(|window| window.location().search().ok())
+            .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok());
+        if let Some(params) = params {
+            if let Some(pack) = params.get("pack") {
+                app = app.with_config(app_core::AppConfig::default().with_asset_pack(pack));
+            }
+            #[cfg(feature = "import-gltf")]
+            if let Some(model) = params.get("model") {
+                app = app.with_model(model);
+            }
+            if let Some(texture) = params.get("texture") {
+                app = app.with_texture(texture);
+            }
+        }
+    }
+
     event_loop.run_app(&mut app)?;
 
     Ok(())
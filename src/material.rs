@@ -0,0 +1,462 @@
+//! # Material Module
+//!
+//! The `material` module describes how the surfaces of the scene's meshes reflect light, in
+//! the metallic-roughness model of physically based rendering (PBR) used by glTF, and binds
+//! each material to the scene's shaders.
+//!
+//! ## Overview
+//!
+//! - [`Material`] holds a base color, how metallic and how rough the surface is, the light it
+//!   emits, and optional textures multiplying each of them.
+//! - [`MaterialHandle`] picks one of the scene's materials for a mesh, and [`TextureHandle`]
+//!   one of the textures added to the scene for a material.
+//! - [`MaterialBinding`] uploads a material, reduced to a [`MaterialUniform`], to a uniform
+//!   buffer, and binds it together with its textures at set 2 of the scene's pipelines. Each
+//!   material has its own bind group, bound before the meshes drawn with it.
+//! - [`SceneMaterials`] holds the scene's material bindings and the textures they sample.
+//! - [`MaterialLibrary`] is the list of the scene's materials edited in the Inspector panel,
+//!   with the one selected for editing.
+//!
+//! Textures a material does not set fall back to textures that leave the material's values
+//! unchanged: the scene's texture (white until one is loaded) for the base color, and a white
+//! texel for the others.
+//!
+//! The base color and emissive textures hold sRGB colors. The metallic-roughness texture holds
+//! the roughness in its green and the metallic in its blue channel, as in glTF, and should be
+//! created with [`Texture::from_rgba8_linear`] or [`Texture::from_bytes_linear`] so they are
+//! read unchanged.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let bricks = Texture::from_bytes(&device, &queue, &bytes, "Bricks")?;
+//! let bricks = renderer.add_scene_texture(bricks);
+//! let materials = [
+//!     Material::default(),
+//!     Material {
+//!         base_color_texture: Some(bricks),
+//!         roughness: 0.9,
+//!         ..Material::default()
+//!     },
+//! ];
+//! renderer.set_scene_materials(&materials);
+//! ```
+
+// Importing the bind group generated from `shader_source.wgsl`, whose set 2 binds a material.
+use crate::shader_bindings::shader_source;
+
+// Importing the textures a material is sampled from.
+use crate::texture::Texture;
+
+/// The index of a material in the scene's materials, drawn on the meshes holding it.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct MaterialHandle(pub usize);
+
+/// The index of a texture added to the scene for its materials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct TextureHandle(pub usize);
+
+/// How a surface reflects light, in the metallic-roughness model, edited in the Inspector
+/// panel.
+///
+/// Missing fields take their default values when deserializing, so saved materials keep
+/// loading when settings are added.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Material {
+    /// The linear RGB color of the surface, multiplied with the vertex colors.
+    pub base_color: [f32; 3],
+
+    /// How metallic the surface is, from `0.0` (dielectric) to `1.0` (metal).
+    pub metallic: f32,
+
+    /// How rough the surface is, from `0.0` (mirror-like) to `1.0` (matte).
+    pub roughness: f32,
+
+    /// The linear RGB color of the light the surface emits, added regardless of the lighting.
+    pub emissive: [f32; 3],
+
+    /// The texture multiplied with the base color, instead of the scene's texture.
+    pub base_color_texture: Option<TextureHandle>,
+
+    /// The texture whose green and blue channels multiply the roughness and the metallic.
+    pub metallic_roughness_texture: Option<TextureHandle>,
+
+    /// The texture multiplied with the emissive color.
+    pub emissive_texture: Option<TextureHandle>,
+}
+
+/// The default material is a white, somewhat rough dielectric that shows the vertex colors
+/// unchanged.
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            base_color: [1.0, 1.0, 1.0],
+            metallic: 0.0,
+            roughness: 0.5,
+            emissive: [0.0, 0.0, 0.0],
+            base_color_texture: None,
+            metallic_roughness_texture: None,
+            emissive_texture: None,
+        }
+    }
+}
+
+impl Material {
+    /// Reduces the material to the values the shaders use.
+    pub fn uniform(&self) -> MaterialUniform {
+        let [red, green, blue] = self.base_color;
+        let [emissive_red, emissive_green, emissive_blue] = self.emissive;
+        MaterialUniform {
+            base_color: [red, green, blue, 1.0],
+            emissive: [emissive_red, emissive_green, emissive_blue, 0.0],
+            params: [
+                self.metallic.clamp(0.0, 1.0),
+                self.roughness.clamp(0.0, 1.0),
+                0.0,
+                0.0,
+            ],
+        }
+    }
+
+    /// Returns the textures of the material, which its bind group depends on.
+    fn textures(&self) -> [Option<TextureHandle>; 3] {
+        [
+            self.base_color_texture,
+            self.metallic_roughness_texture,
+            self.emissive_texture,
+        ]
+    }
+}
+
+/// The material as read by the shaders.
+///
+/// Colors are stored in `vec4`s to match WGSL's uniform layout rules.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct MaterialUniform {
+    /// The base color in `rgb`, and `1.0` in `a`.
+    pub base_color: [f32; 4],
+
+    /// The emissive color in `rgb`.
+    pub emissive: [f32; 4],
+
+    /// The metallic in `x` and the roughness in `y`, both clamped to `[0, 1]`.
+    pub params: [f32; 4],
+}
+
+/// The textures materials are bound with.
+#[derive(Debug, Clone, Copy)]
+pub struct MaterialTextures<'a> {
+    /// The textures added to the scene, indexed by [`TextureHandle`].
+    pub textures: &'a [Texture],
+
+    /// The scene's texture, for materials without a base color texture.
+    pub scene_texture: &'a Texture,
+
+    /// A single white texel, for materials without the other textures.
+    pub white: &'a Texture,
+}
+
+impl MaterialTextures<'_> {
+    /// Returns the texture of `handle`, or `fallback` if there is none or it is out of range.
+    fn get<'a>(&'a self, handle: Option<TextureHandle>, fallback: &'a Texture) -> &'a Texture {
+        handle
+            .and_then(|handle| self.textures.get(handle.0))
+            .unwrap_or(fallback)
+    }
+}
+
+/// A material's uniform buffer, and the bind group exposing it and its textures at set 2.
+#[derive(Debug)]
+pub struct MaterialBinding {
+    /// The material, as last uploaded.
+    pub material: Material,
+
+    /// The buffer holding the `MaterialUniform`.
+    pub buffer: wgpu::Buffer,
+
+    /// Exposes `buffer` and the material's textures to the fragment shader.
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl MaterialBinding {
+    /// Creates the layout of the materials' bind groups.
+    pub fn create_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        shader_source::BindGroup2::create_layout(device)
+    }
+
+    /// Uploads `material` and binds it with its `textures`, using `layout`.
+    pub fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        material: &Material,
+        textures: &MaterialTextures,
+    ) -> Self {
+        let buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Material Uniform Buffer"),
+                contents: bytemuck::bytes_of(&material.uniform()),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+        let bind_group = Self::create_bind_group(device, layout, &buffer, material, textures);
+        Self {
+            material: *material,
+            buffer,
+            bind_group,
+        }
+    }
+
+    /// Uploads `material` if it differs from the current one. Returns the replaced bind group,
+    /// which frames in flight may still use, if the material's textures changed.
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: &wgpu::BindGroupLayout,
+        material: &Material,
+        textures: &MaterialTextures,
+    ) -> Option<wgpu::BindGroup> {
+        if self.material == *material {
+            return None;
+        }
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&material.uniform()));
+        let rebind = self.material.textures() != material.textures();
+        self.material = *material;
+        rebind.then(|| self.rebind(device, layout, textures))
+    }
+
+    /// Binds the material's textures again, after one of them was replaced, and returns the
+    /// replaced bind group, which frames in flight may still use.
+    pub fn rebind(
+        &mut self,
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        textures: &MaterialTextures,
+    ) -> wgpu::BindGroup {
+        let bind_group =
+            Self::create_bind_group(device, layout, &self.buffer, &self.material, textures);
+        std::mem::replace(&mut self.bind_group, bind_group)
+    }
+
+    /// Creates a bind group exposing `buffer` and the textures of `material` with `layout`.
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        buffer: &wgpu::Buffer,
+        material: &Material,
+        textures: &MaterialTextures,
+    ) -> wgpu::BindGroup {
+        let base_color = textures.get(material.base_color_texture, textures.scene_texture);
+        shader_source::BindGroup2 {
+            material: buffer.as_entire_buffer_binding(),
+            base_color_texture: &base_color.view,
+            metallic_roughness_texture: &textures
+                .get(material.metallic_roughness_texture, textures.white)
+                .view,
+            emissive_texture: &textures.get(material.emissive_texture, textures.white).view,
+            material_sampler: &base_color.sampler,
+        }
+        .create(device, layout)
+    }
+}
+
+/// The scene's materials bound to the GPU, and the textures they are sampled from.
+#[derive(Debug)]
+pub struct SceneMaterials {
+    /// The layout of the materials' bind groups, at set 2 of the scene's pipelines.
+    pub bind_group_layout: wgpu::BindGroupLayout,
+
+    /// The materials, indexed by [`MaterialHandle`]. There is always at least one.
+    pub bindings: Vec<MaterialBinding>,
+
+    /// The textures added to the scene, indexed by [`TextureHandle`].
+    pub textures: Vec<Texture>,
+
+    /// The texture of materials without a base color texture. A single white texel until a
+    /// texture is set with [`SceneMaterials::set_scene_texture`].
+    pub scene_texture: Texture,
+
+    /// A single white texel, for materials without the other textures.
+    pub white: Texture,
+}
+
+impl SceneMaterials {
+    /// Creates the default material, sampling white textures.
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let bind_group_layout = MaterialBinding::create_layout(device);
+        let scene_texture = Texture::white(device, queue);
+        let white = Texture::white(device, queue);
+        let binding = MaterialBinding::new(
+            device,
+            &bind_group_layout,
+            &Material::default(),
+            &MaterialTextures {
+                textures: &[],
+                scene_texture: &scene_texture,
+                white: &white,
+            },
+        );
+        Self {
+            bind_group_layout,
+            bindings: vec![binding],
+            textures: Vec::new(),
+            scene_texture,
+            white,
+        }
+    }
+
+    /// Returns the bind group of the material of `handle`, or of the first material if there
+    /// is no such material.
+    pub fn bind_group(&self, handle: MaterialHandle) -> &wgpu::BindGroup {
+        &self
+            .bindings
+            .get(handle.0)
+            .unwrap_or(&self.bindings[0])
+            .bind_group
+    }
+
+    /// Uploads `materials`, updating the existing bindings in place, and returns the replaced
+    /// bind groups and the removed bindings, which frames in flight may still use.
+    ///
+    /// The default material is kept if `materials` is empty.
+    pub fn set_materials(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        materials: &[Material],
+    ) -> (Vec<wgpu::BindGroup>, Vec<MaterialBinding>) {
+        let default = [Material::default()];
+        let materials = if materials.is_empty() {
+            &default[..]
+        } else {
+            materials
+        };
+        let textures = MaterialTextures {
+            textures: &self.textures,
+            scene_texture: &self.scene_texture,
+            white: &self.white,
+        };
+        let layout = &self.bind_group_layout;
+        let replaced = self
+            .bindings
+            .iter_mut()
+            .zip(materials)
+            .filter_map(|(binding, material)| {
+                binding.update(device, queue, layout, material, &textures)
+            })
+            .collect();
+        let removed = self
+            .bindings
+            .split_off(materials.len().min(self.bindings.len()));
+        for material in &materials[self.bindings.len()..] {
+            self.bindings
+                .push(MaterialBinding::new(device, layout, material, &textures));
+        }
+        (replaced, removed)
+    }
+
+    /// Adds `texture` for materials to sample, and returns its handle and the bind groups of
+    /// the materials already holding the handle, replaced to sample it, which frames in flight
+    /// may still use.
+    pub fn add_texture(
+        &mut self,
+        device: &wgpu::Device,
+        texture: Texture,
+    ) -> (TextureHandle, Vec<wgpu::BindGroup>) {
+        let handle = TextureHandle(self.textures.len());
+        self.textures.push(texture);
+        let replaced = self.rebind(device, |material| {
+            material.textures().contains(&Some(handle))
+        });
+        (handle, replaced)
+    }
+
+    /// Sets the texture of materials without a base color texture, and returns the replaced
+    /// texture and the replaced bind groups, which frames in flight may still use.
+    pub fn set_scene_texture(
+        &mut self,
+        device: &wgpu::Device,
+        texture: Texture,
+    ) -> (Texture, Vec<wgpu::BindGroup>) {
+        let replaced_texture = std::mem::replace(&mut self.scene_texture, texture);
+        let count = self.textures.len();
+        let replaced = self.rebind(device, |material| {
+            material
+                .base_color_texture
+                .is_none_or(|handle| handle.0 >= count)
+        });
+        (replaced_texture, replaced)
+    }
+
+    /// Binds the textures of the materials matching `filter` again, and returns their
+    /// replaced bind groups.
+    fn rebind(
+        &mut self,
+        device: &wgpu::Device,
+        filter: impl Fn(&Material) -> bool,
+    ) -> Vec<wgpu::BindGroup> {
+        let textures = MaterialTextures {
+            textures: &self.textures,
+            scene_texture: &self.scene_texture,
+            white: &self.white,
+        };
+        self.bindings
+            .iter_mut()
+            .filter(|binding| filter(&binding.material))
+            .map(|binding| binding.rebind(device, &self.bind_group_layout, &textures))
+            .collect()
+    }
+}
+
+/// The scene's materials, as edited in the Inspector panel, with the one selected for
+/// editing. There is always at least one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialLibrary {
+    /// The materials, indexed by [`MaterialHandle`].
+    materials: Vec<Material>,
+
+    /// The index of the material selected for editing.
+    pub selected: usize,
+}
+
+/// The default library holds the default material, like a new scene.
+impl Default for MaterialLibrary {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl MaterialLibrary {
+    /// Creates a library of `materials`, or of the default material if there are none, with
+    /// the first one selected.
+    pub fn new(mut materials: Vec<Material>) -> Self {
+        if materials.is_empty() {
+            materials.push(Material::default());
+        }
+        Self {
+            materials,
+            selected: 0,
+        }
+    }
+
+    /// Returns the materials, indexed by [`MaterialHandle`].
+    pub fn materials(&self) -> &[Material] {
+        &self.materials
+    }
+
+    /// Returns the selected material.
+    pub fn selected(&self) -> &Material {
+        &self.materials[self.selected.min(self.materials.len() - 1)]
+    }
+
+    /// Returns the selected material to edit.
+    pub fn selected_mut(&mut self) -> &mut Material {
+        let index = self.selected.min(self.materials.len() - 1);
+        &mut self.materials[index]
+    }
+}
@@ -0,0 +1,477 @@
+//! # Math Module
+//!
+//! The `math` module is the crate's math layer: the vector, matrix, and quaternion types and
+//! the functions on them, backed by either `nalgebra-glm` or `glam`.
+//!
+//! ## Overview
+//!
+//! - By default, the types are those of `nalgebra-glm`. With the `glam` feature, they are
+//!   those of `glam` instead, so the crate can be benchmarked with either and used by projects
+//!   that standardize on `glam` without converting at every call.
+//! - The rest of the crate only names the types through this module and only calls the free
+//!   functions below, which have the same signatures for both backends and agree up to
+//!   floating-point rounding. Fields (`x`, `y`, `z`, `w`) and the arithmetic operators work
+//!   the same on both, too. Methods of one backend, such as `norm` or `length`, are not used.
+//! - Both backends store matrices column-major, and their `bytemuck` casts produce the same
+//!   bytes, so uniform buffers and shaders are unaffected by the choice.
+//! - Projections and views are left-handed with depth mapped to `0..1`, as in `wgpu`.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! use crate::math;
+//!
+//! let view = math::look_at_lh(&math::vec3(0.0, 0.0, 3.0), &math::zeros(), &math::up());
+//! let projection = math::perspective_lh_zo(16.0 / 9.0, 80f32.to_radians(), 0.1, 100.0);
+//! let view_projection = projection * view;
+//! ```
+
+#[cfg(not(feature = "glam"))]
+pub use nalgebra_glm::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+
+#[cfg(feature = "glam")]
+pub use glam::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+
+/// Returns the vector `(x, y)`.
+pub fn vec2(x: f32, y: f32) -> Vec2 {
+    Vec2::new(x, y)
+}
+
+/// Returns the vector `(x, y, z)`.
+pub fn vec3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3::new(x, y, z)
+}
+
+/// Returns the vector `(x, y, z, w)`.
+pub fn vec4(x: f32, y: f32, z: f32, w: f32) -> Vec4 {
+    Vec4::new(x, y, z, w)
+}
+
+/// Returns the zero vector.
+pub fn zeros() -> Vec3 {
+    vec3(0.0, 0.0, 0.0)
+}
+
+/// Returns the unit vector along +Y, the world's up direction.
+pub fn up() -> Vec3 {
+    vec3(0.0, 1.0, 0.0)
+}
+
+/// Returns the `x` and `y` components of `v`.
+pub fn xy(v: &Vec3) -> Vec2 {
+    vec2(v.x, v.y)
+}
+
+/// Returns the `x`, `y`, and `z` components of `v`.
+pub fn xyz(v: &Vec4) -> Vec3 {
+    vec3(v.x, v.y, v.z)
+}
+
+/// Returns the 4×4 identity matrix.
+#[cfg(not(feature = "glam"))]
+pub fn identity() -> Mat4 {
+    Mat4::identity()
+}
+
+/// Returns the 4×4 identity matrix.
+#[cfg(feature = "glam")]
+pub fn identity() -> Mat4 {
+    Mat4::IDENTITY
+}
+
+/// Returns the cross product of `a` and `b`.
+#[cfg(not(feature = "glam"))]
+pub fn cross(a: &Vec3, b: &Vec3) -> Vec3 {
+    a.cross(b)
+}
+
+/// Returns the cross product of `a` and `b`.
+#[cfg(feature = "glam")]
+pub fn cross(a: &Vec3, b: &Vec3) -> Vec3 {
+    a.cross(*b)
+}
+
+/// Returns `v` scaled to unit length.
+pub fn normalize(v: &Vec3) -> Vec3 {
+    v.normalize()
+}
+
+/// Returns the dot product of `a` and `b`, of any dimension.
+pub fn dot<V: Vector>(a: &V, b: &V) -> f32 {
+    a.dot_with(b)
+}
+
+/// Returns the length of `v`, of any dimension.
+pub fn length<V: Vector>(v: &V) -> f32 {
+    v.magnitude()
+}
+
+/// Returns the distance between the points `a` and `b`, of any dimension.
+pub fn distance<V: Vector>(a: &V, b: &V) -> f32 {
+    (*a - *b).magnitude()
+}
+
+/// The vectors the generic functions above apply to: [`Vec2`] and [`Vec3`].
+pub trait Vector: Copy + std::ops::Sub<Output = Self> {
+    /// Returns the dot product with `other`.
+    fn dot_with(&self, other: &Self) -> f32;
+
+    /// Returns the length.
+    fn magnitude(&self) -> f32;
+}
+
+macro_rules! impl_vector {
+    ($($vector:ty),*) => {$(
+        #[cfg(not(feature = "glam"))]
+        impl Vector for $vector {
+            fn dot_with(&self, other: &Self) -> f32 {
+                self.dot(other)
+            }
+
+            fn magnitude(&self) -> f32 {
+                self.norm()
+            }
+        }
+
+        #[cfg(feature = "glam")]
+        impl Vector for $vector {
+            fn dot_with(&self, other: &Self) -> f32 {
+                self.dot(*other)
+            }
+
+            fn magnitude(&self) -> f32 {
+                self.length()
+            }
+        }
+    )*};
+}
+
+impl_vector!(Vec2, Vec3);
+
+/// Returns the point the fraction `t` of the way from `a` to `b`.
+pub fn lerp<V>(a: &V, b: &V, t: f32) -> V
+where
+    V: Copy
+        + std::ops::Add<Output = V>
+        + std::ops::Sub<Output = V>
+        + std::ops::Mul<f32, Output = V>,
+{
+    *a + (*b - *a) * t
+}
+
+/// Returns the smallest of each component of `a` and `b`.
+pub fn min(a: &Vec3, b: &Vec3) -> Vec3 {
+    vec3(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z))
+}
+
+/// Returns the largest of each component of `a` and `b`.
+pub fn max(a: &Vec3, b: &Vec3) -> Vec3 {
+    vec3(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))
+}
+
+/// Returns `v` with each component clamped between those of `min` and `max`.
+pub fn clamp_vec(v: &Vec3, min: &Vec3, max: &Vec3) -> Vec3 {
+    vec3(
+        v.x.clamp(min.x, max.x),
+        v.y.clamp(min.y, max.y),
+        v.z.clamp(min.z, max.z),
+    )
+}
+
+/// Returns the inverse of `m`, or a matrix of NaNs if it is singular.
+#[cfg(not(feature = "glam"))]
+pub fn inverse(m: &Mat4) -> Mat4 {
+    nalgebra_glm::inverse(m)
+}
+
+/// Returns the inverse of `m`, or a matrix of NaNs if it is singular.
+#[cfg(feature = "glam")]
+pub fn inverse(m: &Mat4) -> Mat4 {
+    m.inverse()
+}
+
+/// Returns the left-handed view matrix of a camera at `eye` looking at `center`.
+#[cfg(not(feature = "glam"))]
+pub fn look_at_lh(eye: &Vec3, center: &Vec3, up: &Vec3) -> Mat4 {
+    nalgebra_glm::look_at_lh(eye, center, up)
+}
+
+/// Returns the left-handed view matrix of a camera at `eye` looking at `center`.
+#[cfg(feature = "glam")]
+pub fn look_at_lh(eye: &Vec3, center: &Vec3, up: &Vec3) -> Mat4 {
+    Mat4::look_at_lh(*eye, *center, *up)
+}
+
+/// Returns the left-handed perspective matrix with a vertical field of view of `fov_y`
+/// radians, mapping depth between `near` and `far` to `0..1`.
+#[cfg(not(feature = "glam"))]
+pub fn perspective_lh_zo(aspect_ratio: f32, fov_y: f32, near: f32, far: f32) -> Mat4 {
+    nalgebra_glm::perspective_lh_zo(aspect_ratio, fov_y, near, far)
+}
+
+/// Returns the left-handed perspective matrix with a vertical field of view of `fov_y`
+/// radians, mapping depth between `near` and `far` to `0..1`.
+#[cfg(feature = "glam")]
+pub fn perspective_lh_zo(aspect_ratio: f32, fov_y: f32, near: f32, far: f32) -> Mat4 {
+    Mat4::perspective_lh(fov_y, aspect_ratio, near, far)
+}
+
+/// Returns the rotation by `angle` radians around `axis`.
+#[cfg(not(feature = "glam"))]
+pub fn rotation(angle: f32, axis: &Vec3) -> Mat4 {
+    nalgebra_glm::rotation(angle, axis)
+}
+
+/// Returns the rotation by `angle` radians around `axis`.
+#[cfg(feature = "glam")]
+pub fn rotation(angle: f32, axis: &Vec3) -> Mat4 {
+    Mat4::from_axis_angle(axis.normalize(), angle)
+}
+
+/// Returns the translation by `offset`.
+#[cfg(not(feature = "glam"))]
+pub fn translation(offset: &Vec3) -> Mat4 {
+    nalgebra_glm::translation(offset)
+}
+
+/// Returns the translation by `offset`.
+#[cfg(feature = "glam")]
+pub fn translation(offset: &Vec3) -> Mat4 {
+    Mat4::from_translation(*offset)
+}
+
+/// Returns the scaling by `factors` along the X, Y and Z axes.
+#[cfg(not(feature = "glam"))]
+pub fn scaling(factors: &Vec3) -> Mat4 {
+    nalgebra_glm::scaling(factors)
+}
+
+/// Returns the scaling by `factors` along the X, Y and Z axes.
+#[cfg(feature = "glam")]
+pub fn scaling(factors: &Vec3) -> Mat4 {
+    Mat4::from_scale(*factors)
+}
+
+/// Returns `m` followed by a rotation by `angle` radians around `axis`, in `m`'s space.
+pub fn rotate(m: &Mat4, angle: f32, axis: &Vec3) -> Mat4 {
+    *m * rotation(angle, axis)
+}
+
+/// Returns the 16 elements of `m`, column by column.
+#[cfg(not(feature = "glam"))]
+pub fn to_cols_array(m: &Mat4) -> [f32; 16] {
+    let mut elements = [0.0; 16];
+    elements.copy_from_slice(m.as_slice());
+    elements
+}
+
+/// Returns the 16 elements of `m`, column by column.
+#[cfg(feature = "glam")]
+pub fn to_cols_array(m: &Mat4) -> [f32; 16] {
+    m.to_cols_array()
+}
+
+/// Returns the matrix with the 16 `elements`, column by column.
+#[cfg(not(feature = "glam"))]
+pub fn from_cols_array(elements: &[f32; 16]) -> Mat4 {
+    Mat4::from_column_slice(elements)
+}
+
+/// Returns the matrix with the 16 `elements`, column by column.
+#[cfg(feature = "glam")]
+pub fn from_cols_array(elements: &[f32; 16]) -> Mat4 {
+    Mat4::from_cols_array(elements)
+}
+
+/// Returns the 3×3 matrix with the columns `x`, `y`, and `z`.
+#[cfg(not(feature = "glam"))]
+pub fn mat3_from_columns(x: &Vec3, y: &Vec3, z: &Vec3) -> Mat3 {
+    Mat3::from_columns(&[*x, *y, *z])
+}
+
+/// Returns the 3×3 matrix with the columns `x`, `y`, and `z`.
+#[cfg(feature = "glam")]
+pub fn mat3_from_columns(x: &Vec3, y: &Vec3, z: &Vec3) -> Mat3 {
+    Mat3::from_cols(*x, *y, *z)
+}
+
+/// Returns the quaternion `x i + y j + z k + w`.
+#[cfg(not(feature = "glam"))]
+pub fn quat(x: f32, y: f32, z: f32, w: f32) -> Quat {
+    nalgebra_glm::quat(x, y, z, w)
+}
+
+/// Returns the quaternion `x i + y j + z k + w`.
+#[cfg(feature = "glam")]
+pub fn quat(x: f32, y: f32, z: f32, w: f32) -> Quat {
+    Quat::from_xyzw(x, y, z, w)
+}
+
+/// Returns the components `[x, y, z, w]` of `q`.
+#[cfg(not(feature = "glam"))]
+pub fn quat_to_array(q: &Quat) -> [f32; 4] {
+    q.coords.into()
+}
+
+/// Returns the components `[x, y, z, w]` of `q`.
+#[cfg(feature = "glam")]
+pub fn quat_to_array(q: &Quat) -> [f32; 4] {
+    q.to_array()
+}
+
+/// Returns `q` scaled to unit length.
+#[cfg(not(feature = "glam"))]
+pub fn quat_normalize(q: &Quat) -> Quat {
+    nalgebra_glm::quat_normalize(q)
+}
+
+/// Returns `q` scaled to unit length.
+#[cfg(feature = "glam")]
+pub fn quat_normalize(q: &Quat) -> Quat {
+    q.normalize()
+}
+
+/// Returns `v` rotated by the unit quaternion `q`.
+#[cfg(not(feature = "glam"))]
+pub fn quat_rotate_vec3(q: &Quat, v: &Vec3) -> Vec3 {
+    nalgebra_glm::quat_rotate_vec3(q, v)
+}
+
+/// Returns `v` rotated by the unit quaternion `q`.
+#[cfg(feature = "glam")]
+pub fn quat_rotate_vec3(q: &Quat, v: &Vec3) -> Vec3 {
+    *q * *v
+}
+
+/// Returns the unit quaternion of the rotation matrix `m`.
+#[cfg(not(feature = "glam"))]
+pub fn mat3_to_quat(m: &Mat3) -> Quat {
+    nalgebra_glm::mat3_to_quat(m)
+}
+
+/// Returns the unit quaternion of the rotation matrix `m`.
+#[cfg(feature = "glam")]
+pub fn mat3_to_quat(m: &Mat3) -> Quat {
+    Quat::from_mat3(m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that `a` and `b` are the same point, up to rounding.
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!(distance(&a, &b) < 1e-5, "{a:?} != {b:?}");
+    }
+
+    /// Returns `point` transformed by `m`, divided by `w`.
+    fn transform_point(m: &Mat4, point: &Vec3) -> Vec3 {
+        let clip = *m * vec4(point.x, point.y, point.z, 1.0);
+        xyz(&clip) / clip.w
+    }
+
+    #[test]
+    fn matrices_are_stored_column_major() {
+        let m = translation(&vec3(1.0, 2.0, 3.0));
+        let elements = to_cols_array(&m);
+
+        assert_eq!(elements[12..], [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(from_cols_array(&elements), m);
+    }
+
+    #[test]
+    fn view_projection_is_left_handed_with_depth_from_zero_to_one() {
+        let view = look_at_lh(&vec3(0.0, 0.0, -5.0), &zeros(), &up());
+        let projection = perspective_lh_zo(1.0, std::f32::consts::FRAC_PI_2, 1.0, 9.0);
+        let view_projection = projection * view;
+
+        assert_close(
+            transform_point(&view_projection, &vec3(0.0, 0.0, -4.0)),
+            zeros(),
+        );
+        assert_close(
+            transform_point(&view_projection, &vec3(0.0, 0.0, 4.0)),
+            vec3(0.0, 0.0, 1.0),
+        );
+        // With +Y up and +Z forward, +X is to the right.
+        assert_close(
+            transform_point(&view_projection, &vec3(5.0, 5.0, 0.0)),
+            vec3(1.0, 1.0, transform_point(&view_projection, &zeros()).z),
+        );
+    }
+
+    #[test]
+    fn rotations_follow_the_right_hand_rule() {
+        let quarter = std::f32::consts::FRAC_PI_2;
+        let m = rotation(quarter, &up());
+
+        assert_close(
+            transform_point(&m, &vec3(1.0, 0.0, 0.0)),
+            vec3(0.0, 0.0, -1.0),
+        );
+        assert_close(
+            transform_point(&rotate(&m, quarter, &up()), &vec3(1.0, 0.0, 0.0)),
+            vec3(-1.0, 0.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn quaternions_rotate_like_their_matrices() {
+        // A quarter turn around Y, as the columns of its rotation matrix.
+        let m = mat3_from_columns(
+            &vec3(0.0, 0.0, -1.0),
+            &vec3(0.0, 1.0, 0.0),
+            &vec3(1.0, 0.0, 0.0),
+        );
+        let q = mat3_to_quat(&m);
+        let [x, y, z, w] = quat_to_array(&q);
+
+        assert_close(
+            quat_rotate_vec3(&q, &vec3(1.0, 0.0, 0.0)),
+            vec3(0.0, 0.0, -1.0),
+        );
+        assert_close(
+            vec3(x, y, z),
+            vec3(0.0, std::f32::consts::FRAC_1_SQRT_2, 0.0),
+        );
+        assert!((w - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-5);
+        assert_eq!(
+            quat_normalize(&quat(0.0, 2.0, 0.0, 0.0)),
+            quat(0.0, 1.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn inverse_undoes_a_transform() {
+        let m = translation(&vec3(1.0, -2.0, 3.0))
+            * rotation(0.5, &vec3(1.0, 1.0, 0.0))
+            * scaling(&vec3(2.0, 3.0, 4.0));
+        let point = vec3(0.25, 0.5, -0.75);
+
+        assert_close(transform_point(&(inverse(&m) * m), &point), point);
+        assert_close(
+            transform_point(&inverse(&m), &transform_point(&m, &point)),
+            point,
+        );
+    }
+
+    #[test]
+    fn vector_helpers_work_in_two_and_three_dimensions() {
+        assert_eq!(length(&vec2(3.0, 4.0)), 5.0);
+        assert_eq!(distance(&vec3(1.0, 0.0, 0.0), &vec3(1.0, 3.0, 4.0)), 5.0);
+        assert_eq!(dot(&vec2(1.0, 2.0), &vec2(3.0, 4.0)), 11.0);
+        assert_close(cross(&vec3(1.0, 0.0, 0.0), &up()), vec3(0.0, 0.0, 1.0));
+        assert_close(
+            lerp(&zeros(), &vec3(2.0, 4.0, 6.0), 0.5),
+            vec3(1.0, 2.0, 3.0),
+        );
+        assert_close(
+            clamp_vec(
+                &vec3(-2.0, 0.5, 2.0),
+                &vec3(-1.0, -1.0, -1.0),
+                &vec3(1.0, 1.0, 1.0),
+            ),
+            vec3(-1.0, 0.5, 1.0),
+        );
+    }
+}
@@ -0,0 +1,866 @@
+//! # Model Module
+//!
+//! The `model` module loads glTF 2.0 models, in both the `.gltf` (JSON with external or
+//! embedded buffers) and the `.glb` (binary container) forms, into meshes the [`Scene`] can
+//! draw in place of its triangle.
+//!
+//! ## Overview
+//!
+//! - [`ModelLoader`] reads a model through the [`AssetLoader`], so models can be local files,
+//!   entries of a mounted asset pack, or HTTP(S) URLs cached on disk. Buffers referenced by
+//!   relative URIs are loaded relative to the model, and `data:` URIs are decoded in place.
+//! - [`Model`] holds one [`MeshData`] per mesh primitive, with the node transforms of the
+//!   default scene baked into the vertices, and the model's materials, which the meshes
+//!   refer to.
+//!
+//! The loader supports the subset of glTF the scene's pipeline can draw: triangle lists with
+//! positions, and optionally normals, texture coordinates, and vertex colors. The scene's
+//! vertices carry a position, a color, the `TEXCOORD_0` texture coordinates, and a normal:
+//!
+//! - The color is `COLOR_0`, if any, and white otherwise. The material's base color factor
+//!   multiplies it in the shader.
+//! - The normal is `NORMAL`, if any. Without normals, each vertex gets the average normal of
+//!   the triangles sharing it, so the model is still lit by the scene's light.
+//!
+//! Each glTF material becomes a [`Material`] with its base color, metallic, roughness, and
+//! emissive factors. Primitives without a material use a default one added after them.
+//!
+//! The model's own textures, skins, morph targets, and sparse accessors are not supported; the
+//! texture coordinates sample the scene's texture, set with `Renderer::set_scene_texture`. Primitives drawn
+//! as points or lines are skipped with a warning.
+//!
+//! glTF is right-handed, while the scene's camera looks down the left-handed view space. The
+//! loader mirrors the X axis, so models are not shown mirrored, which also turns the
+//! counter-clockwise front faces of glTF into the clockwise ones of the scene's pipeline.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let assets = AssetLoader::new();
+//! let loader = ModelLoader::new(&assets);
+//! let model = loader.load("models/Duck.glb").await?.centered();
+//! renderer.set_scene_meshes(&model.meshes);
+//! renderer.set_scene_materials(&model.materials);
+//! ```
+//!
+//! [`Scene`]: crate::Scene
+//! [`Material`]: crate::Material
+//! [`AssetLoader`]: crate::AssetLoader
+
+// Importing the asset loader, which reads the model and its buffers from files, packs, or URLs.
+use crate::asset::{AssetError, AssetLoader};
+
+// Importing the scene's vertex and mesh types, which the loaded primitives are converted to.
+use crate::scene::MeshData;
+use crate::vertex::Vertex;
+
+// Importing the materials the loaded glTF materials are converted to.
+use crate::material::MaterialHandle;
+
+// Importing the bounds, used to center models on the origin.
+use crate::camera::Bounds;
+
+/// Errors that can occur while loading a model.
+#[derive(Debug, thiserror::Error)]
+pub enum ModelError {
+    /// The model or one of its buffers could not be loaded.
+    #[error(transparent)]
+    Asset(#[from] AssetError),
+
+    /// The GLB container is malformed.
+    #[error("invalid GLB container: {0}")]
+    Glb(&'static str),
+
+    /// The glTF JSON could not be parsed.
+    #[error("invalid glTF JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The model references data that is missing or out of range.
+    #[error("invalid glTF model: {0}")]
+    Invalid(String),
+
+    /// The model uses a feature the loader does not support.
+    #[error("unsupported glTF feature: {0}")]
+    Unsupported(String),
+
+    /// The model holds no triangles.
+    #[error("the model has no triangle meshes")]
+    Empty,
+}
+
+/// A loaded model: the meshes of its default scene, in the scene's coordinate system.
+#[derive(Debug, Clone, Default)]
+pub struct Model {
+    /// One mesh per glTF mesh primitive and node instancing it.
+    pub meshes: Vec<MeshData>,
+
+    /// The materials the meshes refer to by their [`MaterialHandle`].
+    pub materials: Vec<crate::material::Material>,
+}
+
+impl Model {
+    /// Returns the bounds of the model's vertices, or `None` if it has none.
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(
+            self.meshes
+                .iter()
+                .flat_map(|mesh| mesh.vertices.iter().map(Vertex::position)),
+        )
+    }
+
+    /// Returns the number of triangles of all meshes.
+    pub fn triangle_count(&self) -> usize {
+        self.meshes.iter().map(|mesh| mesh.indices.len() / 3).sum()
+    }
+
+    /// Moves the model so the center of its bounds is at the origin, so the scene's animation
+    /// spins it in place.
+    pub fn centered(mut self) -> Self {
+        if let Some(bounds) = self.bounds() {
+            let center = bounds.center();
+            for mesh in &mut self.meshes {
+                for vertex in &mut mesh.vertices {
+                    let position = vertex.position() - center;
+                    *vertex = Vertex::new([position.x, position.y, position.z], vertex.color())
+                        .with_uv(vertex.uv())
+                        .with_normal(vertex.normal());
+                }
+            }
+        }
+        self
+    }
+}
+
+/// Loads glTF and GLB models through an [`AssetLoader`].
+pub struct ModelLoader<'a> {
+    /// The loader the model and its external buffers are read with.
+    assets: &'a AssetLoader,
+}
+
+impl<'a> ModelLoader<'a> {
+    /// Creates a model loader reading through `assets`.
+    pub fn new(assets: &'a AssetLoader) -> Self {
+        Self { assets }
+    }
+
+    /// Loads the model at `source`, a path or URL as accepted by [`AssetLoader::load`], along
+    /// with the buffers it references.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ModelError` if the model or one of its buffers cannot be loaded, if it is
+    /// malformed or uses an unsupported feature, or if it holds no triangles.
+    pub async fn load(&self, source: &str) -> Result<Model, ModelError> {
+        let bytes = self.assets.load(source).await?;
+        let (document, binary) = Document::parse(&bytes)?;
+
+        let mut buffers = Vec::with_capacity(document.buffers.len());
+        for (index, buffer) in document.buffers.iter().enumerate() {
+            let data = match embedded_buffer(buffer, index, binary)? {
+                Some(data) => data,
+                None => {
+                    let uri = buffer.uri.as_deref().unwrap_or_default();
+                    self.assets.load(&resolve_uri(source, uri)).await?
+                }
+            };
+            buffers.push(data);
+        }
+        document.build(&buffers)
+    }
+
+    /// Parses a self-contained model: a GLB file, or a glTF file whose buffers are all
+    /// embedded as `data:` URIs.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ModelError::Unsupported` if the model references an external buffer, and the
+    /// errors of [`ModelLoader::load`] otherwise.
+    pub fn parse(bytes: &[u8]) -> Result<Model, ModelError> {
+        let (document, binary) = Document::parse(bytes)?;
+        let buffers = document
+            .buffers
+            .iter()
+            .enumerate()
+            .map(|(index, buffer)| {
+                embedded_buffer(buffer, index, binary)?.ok_or_else(|| {
+                    ModelError::Unsupported(format!(
+                        "external buffer '{}' without a loader",
+                        buffer.uri.as_deref().unwrap_or_default()
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        document.build(&buffers)
+    }
+}
+
+/// Returns the data of `buffer` if it is the GLB binary chunk or a `data:` URI, and `None` if
+/// it must be loaded from its URI.
+fn embedded_buffer(
+    buffer: &Buffer,
+    index: usize,
+    binary: Option<&[u8]>,
+) -> Result<Option<Vec<u8>>, ModelError> {
+    let data = match buffer.uri.as_deref() {
+        None => binary
+            .ok_or_else(|| ModelError::Invalid(format!("buffer {index} has no data")))?
+            .to_vec(),
+        Some(uri) if uri.starts_with("data:") => {
+            let (_, payload) = uri
+                .split_once(";base64,")
+                .ok_or_else(|| ModelError::Unsupported("data URI without base64".into()))?;
+            decode_base64(payload)
+                .ok_or_else(|| ModelError::Invalid(format!("buffer {index} is not valid base64")))?
+        }
+        Some(_) => return Ok(None),
+    };
+    if data.len() < buffer.byte_length {
+        return Err(ModelError::Invalid(format!(
+            "buffer {index} holds {} of {} bytes",
+            data.len(),
+            buffer.byte_length
+        )));
+    }
+    Ok(Some(data))
+}
+
+/// Resolves a buffer's relative `uri` against the directory of the model at `source`.
+fn resolve_uri(source: &str, uri: &str) -> String {
+    let uri = uri.replace("%20", " ");
+    match source.rfind('/') {
+        Some(slash) => format!("{}/{uri}", &source[..slash]),
+        None => uri,
+    }
+}
+
+/// Decodes standard base64, ignoring padding. Returns `None` on invalid characters.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut bits, mut count) = (0u32, 0);
+    for character in text.bytes().filter(|&character| character != b'=') {
+        let value = match character {
+            b'A'..=b'Z' => character - b'A',
+            b'a'..=b'z' => character - b'a' + 26,
+            b'0'..=b'9' => character - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        bits = (bits << 6) | u32::from(value);
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes.push((bits >> count) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// The subset of the glTF JSON the loader reads.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct Document {
+    scene: Option<usize>,
+    scenes: Vec<SceneNodes>,
+    nodes: Vec<Node>,
+    meshes: Vec<Mesh>,
+    materials: Vec<Material>,
+    accessors: Vec<Accessor>,
+    #[serde(rename = "bufferViews")]
+    buffer_views: Vec<BufferView>,
+    buffers: Vec<Buffer>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct SceneNodes {
+    nodes: Vec<usize>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+struct Node {
+    mesh: Option<usize>,
+    children: Vec<usize>,
+    matrix: Option<Matrix>,
+    translation: [f32; 3],
+    rotation: [f32; 4],
+    scale: [f32; 3],
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Self {
+            mesh: None,
+            children: Vec::new(),
+            matrix: None,
+            translation: [0.0; 3],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0; 3],
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct Mesh {
+    name: Option<String>,
+    primitives: Vec<Primitive>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Primitive {
+    attributes: std::collections::HashMap<String, usize>,
+    indices: Option<usize>,
+    material: Option<usize>,
+    #[serde(default = "Primitive::triangles")]
+    mode: u32,
+}
+
+impl Primitive {
+    /// The mode of triangle lists, the default.
+    const TRIANGLES: u32 = 4;
+
+    fn triangles() -> u32 {
+        Self::TRIANGLES
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Material {
+    pbr_metallic_roughness: Option<PbrMetallicRoughness>,
+    emissive_factor: [f32; 3],
+}
+
+impl Material {
+    /// Converts the material's factors to the scene's material.
+    fn to_material(&self) -> crate::material::Material {
+        let pbr = self.pbr_metallic_roughness.as_ref();
+        let [red, green, blue, _] = pbr.map_or([1.0; 4], |pbr| pbr.base_color_factor);
+        crate::material::Material {
+            base_color: [red, green, blue],
+            metallic: pbr.map_or(1.0, |pbr| pbr.metallic_factor),
+            roughness: pbr.map_or(1.0, |pbr| pbr.roughness_factor),
+            emissive: self.emissive_factor,
+            ..crate::material::Material::default()
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct PbrMetallicRoughness {
+    base_color_factor: [f32; 4],
+    metallic_factor: f32,
+    roughness_factor: f32,
+}
+
+impl Default for PbrMetallicRoughness {
+    fn default() -> Self {
+        Self {
+            base_color_factor: [1.0; 4],
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Accessor {
+    buffer_view: Option<usize>,
+    #[serde(default)]
+    byte_offset: usize,
+    component_type: u32,
+    #[serde(default)]
+    normalized: bool,
+    count: usize,
+    #[serde(rename = "type")]
+    kind: String,
+    sparse: Option<serde_json::Value>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BufferView {
+    buffer: usize,
+    #[serde(default)]
+    byte_offset: usize,
+    byte_length: usize,
+    byte_stride: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Buffer {
+    uri: Option<String>,
+    byte_length: usize,
+}
+
+/// A column-major 4x4 matrix, as stored by glTF.
+type Matrix = [f32; 16];
+
+/// The identity matrix.
+const IDENTITY: Matrix = [
+    1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+];
+
+impl Document {
+    /// The magic number at the start of GLB files, `glTF` in ASCII.
+    const GLB_MAGIC: u32 = 0x4654_6C67;
+
+    /// The type of the GLB chunk holding the JSON, `JSON` in ASCII.
+    const JSON_CHUNK: u32 = 0x4E4F_534A;
+
+    /// The type of the GLB chunk holding the binary buffer, `BIN\0` in ASCII.
+    const BINARY_CHUNK: u32 = 0x004E_4942;
+
+    /// Parses a glTF JSON document, or a GLB container along with its binary chunk.
+    fn parse(bytes: &[u8]) -> Result<(Self, Option<&[u8]>), ModelError> {
+        let word = |offset: usize| {
+            bytes
+                .get(offset..offset + 4)
+                .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        };
+        if word(0) != Some(Self::GLB_MAGIC) {
+            return Ok((serde_json::from_slice(bytes)?, None));
+        }
+
+        if word(4) != Some(2) {
+            return Err(ModelError::Unsupported("GLB versions other than 2".into()));
+        }
+        let length = word(8).ok_or(ModelError::Glb("truncated header"))? as usize;
+        let bytes = bytes
+            .get(..length)
+            .ok_or(ModelError::Glb("truncated file"))?;
+
+        let (mut json, mut binary) = (None, None);
+        let mut offset = 12;
+        while offset < bytes.len() {
+            let chunk_length = word(offset).ok_or(ModelError::Glb("truncated chunk"))? as usize;
+            let chunk_type = word(offset + 4).ok_or(ModelError::Glb("truncated chunk"))?;
+            let start = offset + 8;
+            let chunk = bytes
+                .get(start..start + chunk_length)
+                .ok_or(ModelError::Glb("truncated chunk"))?;
+            match chunk_type {
+                Self::JSON_CHUNK if json.is_none() => json = Some(chunk),
+                Self::BINARY_CHUNK if binary.is_none() => binary = Some(chunk),
+                _ => {}
+            }
+            offset = start + chunk_length;
+        }
+
+        let json = json.ok_or(ModelError::Glb("missing JSON chunk"))?;
+        Ok((serde_json::from_slice(json)?, binary))
+    }
+
+    /// Converts the meshes of the default scene into the scene's coordinate system, reading
+    /// their data from `buffers`.
+    fn build(&self, buffers: &[Vec<u8>]) -> Result<Model, ModelError> {
+        let mut instances = Vec::new();
+        if self.nodes.is_empty() {
+            instances.extend((0..self.meshes.len()).map(|mesh| (mesh, IDENTITY)));
+        } else {
+            for root in self.root_nodes() {
+                self.collect_instances(root, &IDENTITY, &mut instances, 0)?;
+            }
+        }
+
+        let mut meshes = Vec::new();
+        for (index, transform) in instances {
+            let mesh = self
+                .meshes
+                .get(index)
+                .ok_or_else(|| ModelError::Invalid(format!("mesh {index} does not exist")))?;
+            for (number, primitive) in mesh.primitives.iter().enumerate() {
+                if primitive.mode != Primitive::TRIANGLES {
+                    log::warn!(
+                        "Skipping primitive {number} of mesh {index}, drawn in mode {}",
+                        primitive.mode
+                    );
+                    continue;
+                }
+                let name = match &mesh.name {
+                    Some(name) => format!("{name} #{number}"),
+                    None => format!("Mesh {index} #{number}"),
+                };
+                meshes.push(self.primitive_mesh(primitive, &transform, name, buffers)?);
+            }
+        }
+
+        if meshes.iter().all(|mesh| mesh.indices.is_empty()) {
+            return Err(ModelError::Empty);
+        }
+        let mut materials: Vec<_> = self.materials.iter().map(Material::to_material).collect();
+        let default = MaterialHandle(materials.len());
+        if meshes.iter().any(|mesh| mesh.material == default) {
+            materials.push(crate::material::Material::default());
+        }
+        Ok(Model { meshes, materials })
+    }
+
+    /// Returns the root nodes of the default scene, or of all nodes if there are no scenes.
+    fn root_nodes(&self) -> Vec<usize> {
+        if let Some(scene) = self.scenes.get(self.scene.unwrap_or(0)) {
+            return scene.nodes.clone();
+        }
+        let mut is_child = vec![false; self.nodes.len()];
+        for child in self.nodes.iter().flat_map(|node| &node.children) {
+            if let Some(is_child) = is_child.get_mut(*child) {
+                *is_child = true;
+            }
+        }
+        (0..self.nodes.len())
+            .filter(|node| !is_child[*node])
+            .collect()
+    }
+
+    /// Adds the meshes of `node` and its descendants to `instances`, with their transforms
+    /// to the model's space.
+    fn collect_instances(
+        &self,
+        node: usize,
+        parent: &Matrix,
+        instances: &mut Vec<(usize, Matrix)>,
+        depth: usize,
+    ) -> Result<(), ModelError> {
+        // glTF forbids cycles, but a malformed file must not overflow the stack.
+        if depth > self.nodes.len() {
+            return Err(ModelError::Invalid("the node hierarchy has a cycle".into()));
+        }
+        let entry = self
+            .nodes
+            .get(node)
+            .ok_or_else(|| ModelError::Invalid(format!("node {node} does not exist")))?;
+        let transform = multiply(parent, &entry.local_transform());
+        if let Some(mesh) = entry.mesh {
+            instances.push((mesh, transform));
+        }
+        for child in &entry.children {
+            self.collect_instances(*child, &transform, instances, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a primitive's geometry, transformed by `transform` and converted to the scene's
+    /// coordinate system. Primitives without a valid material refer to the material after the
+    /// model's own ones.
+    fn primitive_mesh(
+        &self,
+        primitive: &Primitive,
+        transform: &Matrix,
+        name: String,
+        buffers: &[Vec<u8>],
+    ) -> Result<MeshData, ModelError> {
+        let position = primitive
+            .attributes
+            .get("POSITION")
+            .ok_or_else(|| ModelError::Invalid(format!("'{name}' has no positions")))?;
+        let (positions, components) = self.read(*position, buffers)?;
+        if components != 3 {
+            return Err(ModelError::Invalid(format!("'{name}' has no 3D positions")));
+        }
+        let count = positions.len() / 3;
+
+        let normals = match primitive.attributes.get("NORMAL") {
+            Some(normal) => match self.read(*normal, buffers)? {
+                (normals, 3) if normals.len() == count * 3 => Some(normals),
+                _ => return Err(ModelError::Invalid(format!("'{name}' has invalid normals"))),
+            },
+            None => None,
+        };
+        let colors = match primitive.attributes.get("COLOR_0") {
+            Some(color) => match self.read(*color, buffers)? {
+                (colors, components @ (3 | 4)) if colors.len() == count * components => {
+                    Some((colors, components))
+                }
+                _ => return Err(ModelError::Invalid(format!("'{name}' has invalid colors"))),
+            },
+            None => None,
+        };
+        let uvs = match primitive.attributes.get("TEXCOORD_0") {
+            Some(uv) => match self.read(*uv, buffers)? {
+                (uvs, 2) if uvs.len() == count * 2 => Some(uvs),
+                _ => {
+                    return Err(ModelError::Invalid(format!(
+                        "'{name}' has invalid texture coordinates"
+                    )))
+                }
+            },
+            None => None,
+        };
+        let material = primitive
+            .material
+            .filter(|material| *material < self.materials.len())
+            .map_or(MaterialHandle(self.materials.len()), MaterialHandle);
+
+        let mut vertices = Vec::with_capacity(count);
+        for vertex in 0..count {
+            let point = transform_point(transform, &positions[vertex * 3..vertex * 3 + 3]);
+
+            let color = match &colors {
+                Some((colors, components)) => {
+                    let start = vertex * components;
+                    [colors[start], colors[start + 1], colors[start + 2]]
+                }
+                None => [1.0; 3],
+            };
+
+            let uv = uvs
+                .as_ref()
+                .map_or([0.0; 2], |uvs| [uvs[vertex * 2], uvs[vertex * 2 + 1]]);
+            let mut vertex_data = Vertex::new([-point[0], point[1], point[2]], color).with_uv(uv);
+            if let Some(normals) = &normals {
+                vertex_data = vertex_data.with_normal(transform_normal(
+                    transform,
+                    &normals[vertex * 3..vertex * 3 + 3],
+                ));
+            }
+            vertices.push(vertex_data);
+        }
+
+        let indices = match primitive.indices {
+            Some(accessor) => self.read_indices(accessor, buffers)?,
+            None => (0..count as u32).collect(),
+        };
+        if let Some(index) = indices.iter().find(|index| **index as usize >= count) {
+            return Err(ModelError::Invalid(format!(
+                "'{name}' indexes vertex {index} of {count}"
+            )));
+        }
+        let triangles = indices.len() / 3 * 3;
+        if normals.is_none() {
+            smooth_normals(&mut vertices, &indices[..triangles]);
+        }
+
+        Ok(MeshData {
+            name,
+            vertices,
+            indices: indices[..triangles].to_vec(),
+            material,
+        })
+    }
+
+    /// Reads the float or normalized integer elements of an accessor, flattened, along with
+    /// the number of components of each element.
+    fn read(&self, index: usize, buffers: &[Vec<u8>]) -> Result<(Vec<f32>, usize), ModelError> {
+        let accessor = self.accessor(index)?;
+        let components = match accessor.kind.as_str() {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" => 4,
+            kind => return Err(ModelError::Unsupported(format!("accessor type {kind}"))),
+        };
+        let size = component_size(accessor.component_type)?;
+        let mut values = Vec::with_capacity(accessor.count * components);
+        let Some(elements) = self.elements(accessor, size * components, buffers)? else {
+            values.resize(accessor.count * components, 0.0);
+            return Ok((values, components));
+        };
+        for element in elements {
+            for component in element.chunks_exact(size).take(components) {
+                values.push(read_component(
+                    component,
+                    accessor.component_type,
+                    accessor.normalized,
+                ));
+            }
+        }
+        Ok((values, components))
+    }
+
+    /// Reads the unsigned integer elements of an index accessor.
+    fn read_indices(&self, index: usize, buffers: &[Vec<u8>]) -> Result<Vec<u32>, ModelError> {
+        let accessor = self.accessor(index)?;
+        let size = component_size(accessor.component_type)?;
+        let Some(elements) = self.elements(accessor, size, buffers)? else {
+            return Ok(vec![0; accessor.count]);
+        };
+        elements
+            .map(|element| match accessor.component_type {
+                5121 => Ok(u32::from(element[0])),
+                5123 => Ok(u32::from(u16::from_le_bytes([element[0], element[1]]))),
+                5125 => Ok(u32::from_le_bytes([
+                    element[0], element[1], element[2], element[3],
+                ])),
+                other => Err(ModelError::Invalid(format!("index component type {other}"))),
+            })
+            .collect()
+    }
+
+    /// Returns the accessor at `index`, if it exists and is not sparse.
+    fn accessor(&self, index: usize) -> Result<&Accessor, ModelError> {
+        let accessor = self
+            .accessors
+            .get(index)
+            .ok_or_else(|| ModelError::Invalid(format!("accessor {index} does not exist")))?;
+        if accessor.sparse.is_some() {
+            return Err(ModelError::Unsupported("sparse accessors".into()));
+        }
+        Ok(accessor)
+    }
+
+    /// Returns the bytes of each element of `accessor`, `element_size` bytes long, or `None` if
+    /// the accessor has no buffer view and is all zeros.
+    fn elements<'a>(
+        &self,
+        accessor: &Accessor,
+        element_size: usize,
+        buffers: &'a [Vec<u8>],
+    ) -> Result<Option<impl Iterator<Item = &'a [u8]>>, ModelError> {
+        let Some(view) = accessor.buffer_view else {
+            return Ok(None);
+        };
+        let view = self
+            .buffer_views
+            .get(view)
+            .ok_or_else(|| ModelError::Invalid(format!("buffer view {view} does not exist")))?;
+        let buffer = buffers
+            .get(view.buffer)
+            .ok_or_else(|| ModelError::Invalid(format!("buffer {} does not exist", view.buffer)))?;
+
+        let stride = view.byte_stride.unwrap_or(element_size);
+        let start = view.byte_offset + accessor.byte_offset;
+        let end = match accessor.count {
+            0 => start,
+            count => start + stride * (count - 1) + element_size,
+        };
+        if end > view.byte_offset + view.byte_length || end > buffer.len() {
+            return Err(ModelError::Invalid(
+                "an accessor reads past its buffer view".into(),
+            ));
+        }
+
+        let count = accessor.count;
+        Ok(Some((0..count).map(move |element| {
+            let offset = start + element * stride;
+            &buffer[offset..offset + element_size]
+        })))
+    }
+}
+
+impl Node {
+    /// Returns the node's transform relative to its parent.
+    fn local_transform(&self) -> Matrix {
+        if let Some(matrix) = self.matrix {
+            return matrix;
+        }
+        let [x, y, z, w] = self.rotation;
+        let [sx, sy, sz] = self.scale;
+        let [tx, ty, tz] = self.translation;
+        [
+            (1.0 - 2.0 * (y * y + z * z)) * sx,
+            (2.0 * (x * y + z * w)) * sx,
+            (2.0 * (x * z - y * w)) * sx,
+            0.0,
+            (2.0 * (x * y - z * w)) * sy,
+            (1.0 - 2.0 * (x * x + z * z)) * sy,
+            (2.0 * (y * z + x * w)) * sy,
+            0.0,
+            (2.0 * (x * z + y * w)) * sz,
+            (2.0 * (y * z - x * w)) * sz,
+            (1.0 - 2.0 * (x * x + y * y)) * sz,
+            0.0,
+            tx,
+            ty,
+            tz,
+            1.0,
+        ]
+    }
+}
+
+/// Returns the size in bytes of a component of an accessor's `component_type`.
+fn component_size(component_type: u32) -> Result<usize, ModelError> {
+    match component_type {
+        5120 | 5121 => Ok(1),
+        5122 | 5123 => Ok(2),
+        5125 | 5126 => Ok(4),
+        other => Err(ModelError::Invalid(format!("component type {other}"))),
+    }
+}
+
+/// Reads one component as a float, mapping normalized integers into `[0, 1]` or `[-1, 1]`.
+fn read_component(bytes: &[u8], component_type: u32, normalized: bool) -> f32 {
+    let (value, scale) = match component_type {
+        5120 => (f32::from(bytes[0] as i8), 127.0),
+        5121 => (f32::from(bytes[0]), 255.0),
+        5122 => (f32::from(i16::from_le_bytes([bytes[0], bytes[1]])), 32767.0),
+        5123 => (f32::from(u16::from_le_bytes([bytes[0], bytes[1]])), 65535.0),
+        5125 => (
+            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+            1.0,
+        ),
+        _ => (
+            f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            1.0,
+        ),
+    };
+    if normalized {
+        (value / scale).max(-1.0)
+    } else {
+        value
+    }
+}
+
+/// Returns `a * b`.
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut product = [0.0; 16];
+    for column in 0..4 {
+        for row in 0..4 {
+            product[column * 4 + row] = (0..4).map(|k| a[k * 4 + row] * b[column * 4 + k]).sum();
+        }
+    }
+    product
+}
+
+/// Transforms a point by `matrix`.
+fn transform_point(matrix: &Matrix, point: &[f32]) -> [f32; 3] {
+    std::array::from_fn(|row| {
+        matrix[row] * point[0]
+            + matrix[4 + row] * point[1]
+            + matrix[8 + row] * point[2]
+            + matrix[12 + row]
+    })
+}
+
+/// Sets the normal of each vertex to the average normal of the triangles sharing it, weighted
+/// by their area. Vertices of no triangle keep their normal.
+///
+/// The positions are already mirrored, so the clockwise front faces of the scene's pipeline
+/// face the side the normals point to.
+fn smooth_normals(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut sums = vec![[0.0f32; 3]; vertices.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|corner| vertices[triangle[corner] as usize].position());
+        let (ab, ac) = (b - a, c - a);
+        // The cross product's length is twice the triangle's area.
+        let normal = crate::math::cross(&ac, &ab);
+        for &index in triangle {
+            let sum = &mut sums[index as usize];
+            sum[0] += normal.x;
+            sum[1] += normal.y;
+            sum[2] += normal.z;
+        }
+    }
+    for (vertex, [x, y, z]) in vertices.iter_mut().zip(sums) {
+        let length = (x * x + y * y + z * z).sqrt();
+        if length > f32::EPSILON {
+            *vertex = vertex.with_normal([x / length, y / length, z / length]);
+        }
+    }
+}
+
+/// Transforms a normal by `matrix` and normalizes it, mirrored along X like the positions.
+///
+/// This uses the matrix itself rather than its inverse transpose, which is exact for the
+/// rotations and uniform scales of most models.
+fn transform_normal(matrix: &Matrix, normal: &[f32]) -> [f32; 3] {
+    let [x, y, z]: [f32; 3] = std::array::from_fn(|row| {
+        matrix[row] * normal[0] + matrix[4 + row] * normal[1] + matrix[8 + row] * normal[2]
+    });
+    let length = (x * x + y * y + z * z).sqrt().max(f32::EPSILON);
+    [-x / length, y / length, z / length]
+}
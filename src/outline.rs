@@ -0,0 +1,224 @@
+//! # Outline Module
+//!
+//! The `outline` module highlights the selected object with an outline around its silhouette.
+//!
+//! ## Overview
+//!
+//! [`SelectionOutline::render`] runs a fullscreen pass after the scene pass, which reads the
+//! object id target the scene was drawn with. Pixels within [`SelectionOutline::WIDTH`] pixels
+//! of the selected object, but not covered by it, are drawn in the outline's color onto the
+//! HDR scene, before post-processing. Since the object id target only holds what is visible,
+//! the outline follows the visible silhouette, and parts hidden behind other objects are
+//! outlined where they disappear.
+//!
+//! Objects are selected by their object id, such as the id of a scene node returned by
+//! `Scene::node_object_id`. Nothing is drawn while no object is selected.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let outline = SelectionOutline::new(&device, &object_id_texture_view, None);
+//! // ... once per frame, after the scene pass:
+//! outline.update(&queue, Some(Scene::node_object_id(selected)));
+//! outline.render(&mut encoder, post_process.hdr_texture_view(), None);
+//! ```
+
+use crate::deletion_queue::DeletionQueue;
+use crate::gpu_timer::{GpuTimer, TimedPass};
+use crate::post_process::PostProcess;
+use crate::shader_bindings::outline as outline_bindings;
+
+/// The WGSL source of the outline pass.
+const OUTLINE_SHADER_SOURCE: &str = include_str!("outline.wgsl");
+
+/// The outline settings as read by the shader.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct OutlineUniform {
+    /// The outline's color, in linear HDR.
+    pub color: [f32; 4],
+
+    /// The object id of the selected object.
+    pub object_id: u32,
+
+    /// The outline's width in pixels.
+    pub width: u32,
+
+    /// Pads the struct to a multiple of 16 bytes.
+    pub padding: [u32; 2],
+}
+
+/// The pipeline and bind group of the selection outline.
+#[derive(Debug)]
+pub struct SelectionOutline {
+    /// Draws the outline onto the HDR scene.
+    pipeline: wgpu::RenderPipeline,
+
+    /// The layout of `bind_group`, needed to recreate it when resizing.
+    bind_group_layout: wgpu::BindGroupLayout,
+
+    /// The buffer holding the `OutlineUniform`.
+    buffer: wgpu::Buffer,
+
+    /// Exposes the settings and the object id target to the pass.
+    bind_group: wgpu::BindGroup,
+
+    /// Whether an object is selected, as last uploaded.
+    selected: bool,
+}
+
+impl SelectionOutline {
+    /// The outline's color: a bright orange, above 1.0 so it stays vivid after tonemapping.
+    pub const COLOR: [f32; 4] = [2.0, 0.9, 0.1, 1.0];
+
+    /// The outline's width in pixels.
+    pub const WIDTH: u32 = 2;
+
+    /// Creates the outline's pipeline, compiled through `cache` if one is given, reading the
+    /// object id target `object_id_view`.
+    pub fn new(
+        device: &wgpu::Device,
+        object_id_view: &wgpu::TextureView,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Outline Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(OUTLINE_SHADER_SOURCE)),
+        });
+
+        let bind_group_layout = outline_bindings::BindGroup0::create_layout(device);
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Outline Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Outline Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("outline_vertex"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("outline_fragment"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: PostProcess::HDR_FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        });
+
+        let buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Outline Uniform Buffer"),
+                contents: bytemuck::bytes_of(&Self::uniform(0)),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+        let bind_group =
+            Self::create_bind_group(device, &bind_group_layout, &buffer, object_id_view);
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            buffer,
+            bind_group,
+            selected: false,
+        }
+    }
+
+    /// Reads the object id target `object_id_view` from now on, after it was recreated for a
+    /// new size. The replaced bind group is retired to `deletion_queue`.
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        object_id_view: &wgpu::TextureView,
+        deletion_queue: &mut DeletionQueue,
+    ) {
+        let bind_group = Self::create_bind_group(
+            device,
+            &self.bind_group_layout,
+            &self.buffer,
+            object_id_view,
+        );
+        deletion_queue.retire(std::mem::replace(&mut self.bind_group, bind_group));
+    }
+
+    /// Uploads the object id of the selected object for the next pass, or turns the outline
+    /// off with `None`.
+    pub fn update(&mut self, queue: &wgpu::Queue, object_id: Option<u32>) {
+        self.selected = object_id.is_some();
+        if let Some(object_id) = object_id {
+            queue.write_buffer(
+                &self.buffer,
+                0,
+                bytemuck::bytes_of(&Self::uniform(object_id)),
+            );
+        }
+    }
+
+    /// Encodes the outline pass onto `target`, the HDR scene, if an object is selected, timed
+    /// by `timer` if one is given.
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        timer: Option<&GpuTimer>,
+    ) {
+        if !self.selected {
+            return;
+        }
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Outline Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: timer.and_then(|timer| timer.timestamp_writes(TimedPass::Outline)),
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// Returns the settings outlining the object `object_id`.
+    fn uniform(object_id: u32) -> OutlineUniform {
+        OutlineUniform {
+            color: Self::COLOR,
+            object_id,
+            width: Self::WIDTH,
+            padding: [0; 2],
+        }
+    }
+
+    /// Creates the bind group exposing `buffer` and the object id target `object_id_view`.
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        buffer: &wgpu::Buffer,
+        object_id_view: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        outline_bindings::BindGroup0 {
+            settings: buffer.as_entire_buffer_binding(),
+            object_ids: object_id_view,
+        }
+        .create(device, layout)
+    }
+}
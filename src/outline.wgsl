@@ -0,0 +1,49 @@
+// Selection outline.
+//
+// A fullscreen pass over the object id target of the scene pass: pixels not covered by the
+// selected object, but within the outline's width of a pixel that is, are drawn in the
+// outline's color onto the HDR scene. Every other pixel is discarded, so the outline follows
+// the visible silhouette of the selected object.
+
+struct OutlineSettings {
+    // The outline's color, in linear HDR.
+    color: vec4<f32>,
+    // The object id of the selected object.
+    object_id: u32,
+    // The outline's width in pixels.
+    width: u32,
+    padding: vec2<u32>,
+};
+
+@group(0) @binding(0)
+var<uniform> settings: OutlineSettings;
+
+// The object ids written by the scene pass.
+@group(0) @binding(1)
+var object_ids: texture_2d<u32>;
+
+// Draws a single triangle covering the whole target.
+@vertex
+fn outline_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn outline_fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let last = vec2<i32>(textureDimensions(object_ids)) - 1;
+    let pixel = vec2<i32>(position.xy);
+    if textureLoad(object_ids, pixel, 0).r == settings.object_id {
+        discard;
+    }
+    let width = i32(settings.width);
+    for (var y = -width; y <= width; y++) {
+        for (var x = -width; x <= width; x++) {
+            let neighbor = clamp(pixel + vec2<i32>(x, y), vec2<i32>(0), last);
+            if textureLoad(object_ids, neighbor, 0).r == settings.object_id {
+                return settings.color;
+            }
+        }
+    }
+    discard;
+}
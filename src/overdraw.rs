@@ -0,0 +1,271 @@
+//! # Overdraw Module
+//!
+//! The `overdraw` module implements the [`DebugView::Overdraw`](crate::debug_view::DebugView)
+//! heatmap, which shows how many fragments were written to each pixel.
+//!
+//! ## Overview
+//!
+//! The heatmap is drawn in two passes by [`OverdrawView::render`]:
+//!
+//! 1. **Count pass**: The scene's geometry is drawn into an `R8Unorm` texture with additive
+//!    blending and without depth testing. Each fragment adds `1/255`, so the texture holds the
+//!    number of fragments written to every pixel (up to 255).
+//! 2. **Ramp pass**: A fullscreen triangle reads the counts and maps them to a color ramp
+//!    from black (no fragments) over blue, green, yellow, and red to white (eight or more).
+//!
+//! The ramp pass overwrites the surface, so the GUI drawn afterwards stays on top.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let overdraw = OverdrawView::new(&device, surface_format, &scene, width, height);
+//! // ... once per frame, after the scene pass:
+//! overdraw.render(&mut encoder, Some(&scene), &surface_texture_view, viewport);
+//! ```
+
+use crate::scene::Scene;
+use crate::vertex::Vertex;
+use crate::viewport::Viewport;
+
+/// The WGSL source of the count and ramp passes.
+const OVERDRAW_SHADER_SOURCE: &str = include_str!("overdraw.wgsl");
+
+/// The GPU resources of the overdraw heatmap.
+#[derive(Debug)]
+pub struct OverdrawView {
+    /// Draws the scene's geometry additively into the count texture.
+    count_pipeline: wgpu::RenderPipeline,
+
+    /// Maps the counts to colors on the surface.
+    ramp_pipeline: wgpu::RenderPipeline,
+
+    /// The layout of `ramp_bind_group`, needed to recreate it when resizing.
+    ramp_bind_group_layout: wgpu::BindGroupLayout,
+
+    /// The view of the count texture the count pass renders into.
+    count_texture_view: wgpu::TextureView,
+
+    /// Exposes the count texture to the ramp pass.
+    ramp_bind_group: wgpu::BindGroup,
+}
+
+impl OverdrawView {
+    /// The format of the count texture. Eight bits count up to 255 fragments per pixel.
+    pub const COUNT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+
+    /// Creates the heatmap's pipelines, compiled through `cache` if one is given, and a count
+    /// texture of `width` x `height` pixels.
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        scene: &Scene,
+        width: u32,
+        height: u32,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Overdraw Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(OVERDRAW_SHADER_SOURCE)),
+        });
+
+        let count_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Overdraw Count Pipeline Layout"),
+            bind_group_layouts: &[&scene.uniform.bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Every fragment adds its value to the target, whatever is already there.
+        let additive = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        };
+
+        let count_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Overdraw Count Pipeline"),
+            layout: Some(&count_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("count_vertex"),
+                buffers: &[Vertex::description(&Vertex::vertex_attributes())],
+                compilation_options: Default::default(),
+            },
+            // The topology must match the scene's pipeline, since the scene's geometry is drawn.
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Cw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+                unclipped_depth: false,
+            },
+            // No depth test, so hidden fragments are counted as well.
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("count_fragment"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: Self::COUNT_FORMAT,
+                    blend: Some(wgpu::BlendState {
+                        color: additive,
+                        alpha: additive,
+                    }),
+                    write_mask: wgpu::ColorWrites::RED,
+                })],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        });
+
+        let ramp_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("overdraw_ramp_bind_group_layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                }],
+            });
+
+        let ramp_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Overdraw Ramp Pipeline Layout"),
+            bind_group_layouts: &[&ramp_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let ramp_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Overdraw Ramp Pipeline"),
+            layout: Some(&ramp_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("ramp_vertex"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("ramp_fragment"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        });
+
+        let (count_texture_view, ramp_bind_group) =
+            Self::create_count_target(device, &ramp_bind_group_layout, width, height);
+
+        Self {
+            count_pipeline,
+            ramp_pipeline,
+            ramp_bind_group_layout,
+            count_texture_view,
+            ramp_bind_group,
+        }
+    }
+
+    /// Recreates the count texture for a surface of `width` x `height` pixels.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        (self.count_texture_view, self.ramp_bind_group) =
+            Self::create_count_target(device, &self.ramp_bind_group_layout, width, height);
+    }
+
+    /// Counts the fragments of `scene` drawn into `viewport` and draws the heatmap onto
+    /// `target`, replacing its contents. Without a scene, for example when the camera
+    /// excludes its layers, no fragments are counted.
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        scene: Option<&Scene>,
+        target: &wgpu::TextureView,
+        viewport: Viewport,
+    ) {
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Overdraw Count Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.count_texture_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            viewport.apply(&mut render_pass);
+            render_pass.set_pipeline(&self.count_pipeline);
+            if let Some(scene) = scene {
+                scene.draw_geometry(&mut render_pass);
+            }
+        }
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Overdraw Ramp Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.ramp_pipeline);
+        render_pass.set_bind_group(0, &self.ramp_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// Creates the count texture's view and the bind group exposing it to the ramp pass.
+    fn create_count_target(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        width: u32,
+        height: u32,
+    ) -> (wgpu::TextureView, wgpu::BindGroup) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Overdraw Count Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::COUNT_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("overdraw_ramp_bind_group"),
+            layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&view),
+            }],
+        });
+        (view, bind_group)
+    }
+}
@@ -0,0 +1,66 @@
+// Overdraw heatmap.
+//
+// The count pass draws the scene's geometry into an 8-bit target with additive blending,
+// adding one step (1/255) per fragment, without depth testing, so every fragment written to
+// a pixel is counted. The ramp pass then maps each pixel's count to a color.
+
+struct Uniform {
+    mvp: mat4x4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> ubo: Uniform;
+
+struct VertexInput {
+    @location(0) position: vec4<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn count_vertex(vert: VertexInput) -> @builtin(position) vec4<f32> {
+    return ubo.mvp * vert.position;
+}
+
+@fragment
+fn count_fragment() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0 / 255.0, 0.0, 0.0, 0.0);
+}
+
+// The counts written by the count pass.
+@group(0) @binding(1)
+var counts: texture_2d<f32>;
+
+// Draws a single triangle covering the whole screen.
+@vertex
+fn ramp_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+// Maps a fragment count to a color: no fragments are black, then blue, green, yellow, and
+// red for one to four fragments, fading to white at eight or more.
+fn ramp(count: f32) -> vec3<f32> {
+    var stops = array<vec3<f32>, 6>(
+        vec3<f32>(0.0, 0.0, 0.0),
+        vec3<f32>(0.1, 0.2, 0.9),
+        vec3<f32>(0.1, 0.8, 0.2),
+        vec3<f32>(0.95, 0.9, 0.1),
+        vec3<f32>(0.95, 0.2, 0.1),
+        vec3<f32>(1.0, 1.0, 1.0),
+    );
+    // The last stop is reached at eight fragments instead of five.
+    var position = count;
+    if count > 4.0 {
+        position = 4.0 + (count - 4.0) / 4.0;
+    }
+    position = clamp(position, 0.0, 5.0);
+    let index = u32(floor(position));
+    let next = min(index + 1u, 5u);
+    return mix(stops[index], stops[next], position - f32(index));
+}
+
+@fragment
+fn ramp_fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let count = textureLoad(counts, vec2<i32>(position.xy), 0).r * 255.0;
+    return vec4<f32>(ramp(round(count)), 1.0);
+}
@@ -0,0 +1,527 @@
+//! # Pack Module
+//!
+//! The `pack` module defines the asset pack, a single file bundling all of a project's
+//! assets, and the packer that builds it.
+//!
+//! ## Overview
+//!
+//! Loading dozens of small files is slow on the web, where every one of them is a separate
+//! request, and tedious to distribute on desktop. An asset pack holds them all, so the web
+//! build loads its assets with one fetch and the desktop build ships one file next to the
+//! binary. It is mounted into the `AssetLoader`, which then answers relative paths from the
+//! pack before falling back to the file system.
+//!
+//! The format is little-endian throughout:
+//!
+//! | Section | Contents                                                                   |
+//! | ------- | -------------------------------------------------------------------------- |
+//! | Header  | The magic bytes `WGPK`, the format version (`u32`), the entry count (`u32`) |
+//! | Index   | Per entry: path length (`u16`), UTF-8 path, compression (`u8`), offset,    |
+//! |         | stored size, and original size (`u64` each)                                |
+//! | Blobs   | The contents of each entry, at its offset from the start of the file       |
+//!
+//! Paths use `/` as separator and are relative to the packed directory. Each blob is
+//! compressed with DEFLATE, unless that would not make it smaller, in which case it is
+//! stored as is. Already compressed files, such as PNG textures, are therefore not inflated
+//! again on every load.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! // Offline, usually through `cargo run -- pack assets assets.pak`:
+//! pack_directory("assets", "assets.pak")?;
+//!
+//! // At startup:
+//! let mut loader = AssetLoader::new();
+//! loader.mount_pack("assets.pak").await?;
+//! let shader = loader.load("shaders/sky.wgsl").await?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+/// Errors that can occur while reading or writing an asset pack.
+#[derive(Debug, thiserror::Error)]
+pub enum PackError {
+    /// The data does not start with the pack's magic bytes.
+    #[error("not an asset pack")]
+    InvalidMagic,
+
+    /// The pack was written by a newer, incompatible packer.
+    #[error("unsupported asset pack version {0}")]
+    UnsupportedVersion(u32),
+
+    /// The data ends before the header, the index, or a blob does.
+    #[error("asset pack is truncated")]
+    Truncated,
+
+    /// An entry's path is not valid UTF-8, or its compression method is unknown.
+    #[error("asset pack index is corrupt")]
+    CorruptIndex,
+
+    /// An entry's blob could not be decompressed to its original size.
+    #[error("asset pack entry '{path}' is corrupt")]
+    CorruptEntry {
+        /// The path of the entry.
+        path: String,
+    },
+
+    /// A file could not be read while packing, or the pack could not be written.
+    #[error("failed to access '{path}': {source}")]
+    Io {
+        /// The path of the file that could not be accessed.
+        path: String,
+
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+}
+
+/// How an entry's blob is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    /// The blob is the entry's contents.
+    Stored,
+
+    /// The blob is the entry's contents compressed with raw DEFLATE.
+    Deflate,
+}
+
+impl Compression {
+    /// Returns the compression method for its tag in the index.
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Self::Stored),
+            1 => Some(Self::Deflate),
+            _ => None,
+        }
+    }
+
+    /// Returns the tag stored in the index.
+    fn tag(self) -> u8 {
+        match self {
+            Self::Stored => 0,
+            Self::Deflate => 1,
+        }
+    }
+}
+
+/// The location of an entry's blob in the pack.
+#[derive(Debug, Clone)]
+struct PackEntry {
+    /// How the blob is stored.
+    compression: Compression,
+
+    /// The byte range of the blob.
+    range: std::ops::Range<usize>,
+
+    /// The size of the entry's contents, once decompressed.
+    size: usize,
+}
+
+/// A mounted asset pack: the whole file in memory, and its index.
+#[derive(Debug)]
+pub struct AssetPack {
+    /// The pack file.
+    data: Vec<u8>,
+
+    /// The entries, by path.
+    entries: BTreeMap<String, PackEntry>,
+}
+
+impl AssetPack {
+    /// The magic bytes every pack starts with.
+    pub const MAGIC: [u8; 4] = *b"WGPK";
+
+    /// The version of the format written by [`PackBuilder`].
+    pub const VERSION: u32 = 1;
+
+    /// Parses the header and the index of the pack file `data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PackError` if `data` is not a pack of a supported version, or if its index
+    /// is truncated or corrupt. Blobs are only checked when they are read.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, PackError> {
+        let mut reader = ByteReader::new(&data);
+        if reader.take(4)? != Self::MAGIC {
+            return Err(PackError::InvalidMagic);
+        }
+        let version = reader.u32()?;
+        if version != Self::VERSION {
+            return Err(PackError::UnsupportedVersion(version));
+        }
+
+        let count = reader.u32()?;
+        let mut entries = BTreeMap::new();
+        for _ in 0..count {
+            let path_len = usize::from(reader.u16()?);
+            let path = std::str::from_utf8(reader.take(path_len)?)
+                .map_err(|_| PackError::CorruptIndex)?
+                .to_owned();
+            let compression = Compression::from_tag(reader.u8()?).ok_or(PackError::CorruptIndex)?;
+            let offset = reader.usize()?;
+            let stored_size = reader.usize()?;
+            let size = reader.usize()?;
+            let end = offset
+                .checked_add(stored_size)
+                .ok_or(PackError::Truncated)?;
+            if end > data.len() {
+                return Err(PackError::Truncated);
+            }
+            entries.insert(
+                path,
+                PackEntry {
+                    compression,
+                    range: offset..end,
+                    size,
+                },
+            );
+        }
+
+        Ok(Self { data, entries })
+    }
+
+    /// Returns `true` if the pack holds an entry at `path`.
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries.contains_key(path)
+    }
+
+    /// Returns the paths of all entries, in lexicographic order.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Returns the contents of the entry at `path`, or `None` if the pack holds no such entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PackError::CorruptEntry` if the entry's blob cannot be decompressed to the
+    /// size recorded in the index.
+    pub fn read(&self, path: &str) -> Result<Option<Vec<u8>>, PackError> {
+        let Some(entry) = self.entries.get(path) else {
+            return Ok(None);
+        };
+        let blob = &self.data[entry.range.clone()];
+        let corrupt = || PackError::CorruptEntry {
+            path: path.to_owned(),
+        };
+
+        let bytes = match entry.compression {
+            Compression::Stored => blob.to_vec(),
+            Compression::Deflate => {
+                // A corrupt or hostile blob may inflate without bound, so no more than one
+                // byte past the recorded size is decompressed, enough to detect the mismatch.
+                let limit = entry.size as u64 + 1;
+                let mut bytes = Vec::with_capacity(entry.size.min(blob.len().saturating_mul(4)));
+                flate2::read::DeflateDecoder::new(blob)
+                    .take(limit)
+                    .read_to_end(&mut bytes)
+                    .map_err(|_| corrupt())?;
+                bytes
+            }
+        };
+        if bytes.len() != entry.size {
+            return Err(corrupt());
+        }
+        Ok(Some(bytes))
+    }
+}
+
+/// Collects files and writes them as an asset pack.
+#[derive(Debug, Default)]
+pub struct PackBuilder {
+    /// The contents of the files to pack, by path.
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl PackBuilder {
+    /// Creates a builder without any files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `bytes` as the entry at `path`, replacing any entry added at the same path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is longer than `u16::MAX` bytes.
+    pub fn add(&mut self, path: impl Into<String>, bytes: Vec<u8>) {
+        let path = path.into();
+        assert!(
+            path.len() <= usize::from(u16::MAX),
+            "asset path too long: {path}"
+        );
+        self.files.insert(path, bytes);
+    }
+
+    /// Adds every file below `root`, at its path relative to `root`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PackError::Io` if a directory or file cannot be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_directory(&mut self, root: &std::path::Path) -> Result<(), PackError> {
+        let io_error = |path: &std::path::Path| {
+            let path = path.display().to_string();
+            move |source| PackError::Io { path, source }
+        };
+
+        let mut directories = vec![root.to_path_buf()];
+        while let Some(directory) = directories.pop() {
+            for entry in std::fs::read_dir(&directory).map_err(io_error(&directory))? {
+                let path = entry.map_err(io_error(&directory))?.path();
+                if path.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+                let bytes = std::fs::read(&path).map_err(io_error(&path))?;
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                let name = relative
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                self.add(name, bytes);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of files added so far.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns `true` if no files have been added.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Compresses the files and returns the pack file.
+    pub fn finish(&self) -> Vec<u8> {
+        let blobs: Vec<(Compression, Vec<u8>)> = self
+            .files
+            .values()
+            .map(|bytes| {
+                let compressed = deflate(bytes);
+                if compressed.len() < bytes.len() {
+                    (Compression::Deflate, compressed)
+                } else {
+                    (Compression::Stored, bytes.clone())
+                }
+            })
+            .collect();
+
+        let index_size: usize = self
+            .files
+            .keys()
+            .map(|path| 2 + path.len() + 1 + 3 * 8)
+            .sum();
+        let mut offset = (4 + 4 + 4 + index_size) as u64;
+
+        let mut pack = Vec::new();
+        pack.extend_from_slice(&AssetPack::MAGIC);
+        pack.extend_from_slice(&AssetPack::VERSION.to_le_bytes());
+        pack.extend_from_slice(&(self.files.len() as u32).to_le_bytes());
+        for ((path, bytes), (compression, blob)) in self.files.iter().zip(&blobs) {
+            pack.extend_from_slice(&(path.len() as u16).to_le_bytes());
+            pack.extend_from_slice(path.as_bytes());
+            pack.push(compression.tag());
+            pack.extend_from_slice(&offset.to_le_bytes());
+            pack.extend_from_slice(&(blob.len() as u64).to_le_bytes());
+            pack.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+            offset += blob.len() as u64;
+        }
+        for (_, blob) in &blobs {
+            pack.extend_from_slice(blob);
+        }
+        pack
+    }
+}
+
+/// Packs every file below the directory `source` into the pack file `destination`, and
+/// returns the number of files packed. This is what the `pack` subcommand runs.
+///
+/// # Errors
+///
+/// Returns `PackError::Io` if a file cannot be read or the pack cannot be written.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn pack_directory(
+    source: impl AsRef<std::path::Path>,
+    destination: impl AsRef<std::path::Path>,
+) -> Result<usize, PackError> {
+    let destination = destination.as_ref();
+    let mut builder = PackBuilder::new();
+    builder.add_directory(source.as_ref())?;
+    std::fs::write(destination, builder.finish()).map_err(|source| PackError::Io {
+        path: destination.display().to_string(),
+        source,
+    })?;
+    Ok(builder.len())
+}
+
+/// Compresses `bytes` with raw DEFLATE at the best compression level.
+fn deflate(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder
+        .write_all(bytes)
+        .and_then(|()| encoder.finish())
+        .expect("writing to a Vec cannot fail")
+}
+
+/// Reads little-endian values from the front of a byte slice.
+struct ByteReader<'a> {
+    /// The bytes not read yet.
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    /// Creates a reader starting at the front of `bytes`.
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    /// Reads the next `len` bytes.
+    fn take(&mut self, len: usize) -> Result<&'a [u8], PackError> {
+        if self.bytes.len() < len {
+            return Err(PackError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Reads the next `N` bytes as an array.
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], PackError> {
+        Ok(self.take(N)?.try_into().expect("took exactly N bytes"))
+    }
+
+    /// Reads a `u8`.
+    fn u8(&mut self) -> Result<u8, PackError> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Reads a `u16`.
+    fn u16(&mut self) -> Result<u16, PackError> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    /// Reads a `u32`.
+    fn u32(&mut self) -> Result<u32, PackError> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    /// Reads a `u64` offset or size, which must fit in memory.
+    fn usize(&mut self) -> Result<usize, PackError> {
+        let value = self.array().map(u64::from_le_bytes)?;
+        usize::try_from(value).map_err(|_| PackError::Truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a pack of a compressible text file and an incompressible binary file.
+    fn sample_pack() -> Vec<u8> {
+        let mut builder = PackBuilder::new();
+        builder.add("shaders/sky.wgsl", b"fn main() {}\n".repeat(64));
+        builder.add("noise.bin", (0..=255u8).collect());
+        builder.finish()
+    }
+
+    /// Returns the offset of the first entry's original size in the index of `pack`.
+    fn first_size_offset(pack: &[u8]) -> usize {
+        let path_len = usize::from(u16::from_le_bytes([pack[12], pack[13]]));
+        12 + 2 + path_len + 1 + 8 + 8
+    }
+
+    #[test]
+    fn round_trips_the_packed_files() {
+        let pack = AssetPack::from_bytes(sample_pack()).unwrap();
+        assert_eq!(
+            pack.paths().collect::<Vec<_>>(),
+            ["noise.bin", "shaders/sky.wgsl"]
+        );
+        assert_eq!(
+            pack.read("shaders/sky.wgsl").unwrap(),
+            Some(b"fn main() {}\n".repeat(64))
+        );
+        assert_eq!(pack.read("noise.bin").unwrap(), Some((0..=255u8).collect()));
+        assert!(!pack.contains("missing.png"));
+        assert_eq!(pack.read("missing.png").unwrap(), None);
+    }
+
+    #[test]
+    fn compresses_only_what_gets_smaller() {
+        let pack = AssetPack::from_bytes(sample_pack()).unwrap();
+        assert_eq!(
+            pack.entries["shaders/sky.wgsl"].compression,
+            Compression::Deflate
+        );
+        assert_eq!(pack.entries["noise.bin"].compression, Compression::Stored);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut pack = sample_pack();
+        pack[0] = b'X';
+        assert!(matches!(
+            AssetPack::from_bytes(pack),
+            Err(PackError::InvalidMagic)
+        ));
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        let mut pack = sample_pack();
+        pack[4..8].copy_from_slice(&2u32.to_le_bytes());
+        assert!(matches!(
+            AssetPack::from_bytes(pack),
+            Err(PackError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[test]
+    fn rejects_a_truncated_index() {
+        let pack = sample_pack();
+        for len in [0, 6, 12, 20] {
+            assert!(matches!(
+                AssetPack::from_bytes(pack[..len].to_vec()),
+                Err(PackError::Truncated | PackError::InvalidMagic)
+            ));
+        }
+        assert!(matches!(
+            AssetPack::from_bytes(pack[..pack.len() - 1].to_vec()),
+            Err(PackError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn rejects_entries_of_the_wrong_size() {
+        // The first entry, `noise.bin`, is stored as is.
+        let mut pack = sample_pack();
+        let offset = first_size_offset(&pack);
+        pack[offset..offset + 8].copy_from_slice(&255u64.to_le_bytes());
+        let pack = AssetPack::from_bytes(pack).unwrap();
+        assert!(matches!(
+            pack.read("noise.bin"),
+            Err(PackError::CorruptEntry { path }) if path == "noise.bin"
+        ));
+    }
+
+    #[test]
+    fn stops_inflating_past_the_recorded_size() {
+        let mut builder = PackBuilder::new();
+        builder.add("zeros.bin", vec![0; 1 << 20]);
+        let mut pack = builder.finish();
+        let offset = first_size_offset(&pack);
+        pack[offset..offset + 8].copy_from_slice(&16u64.to_le_bytes());
+        let pack = AssetPack::from_bytes(pack).unwrap();
+        assert!(matches!(
+            pack.read("zeros.bin"),
+            Err(PackError::CorruptEntry { .. })
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+// Particle simulation.
+//
+// One invocation advances one particle of the fountain by a frame: gravity pulls it down, it
+// bounces off the ground, and once it has lived out its lifetime it is launched again from the
+// emitter, in a direction hashed from its index and the time.
+
+struct Particle {
+    position: vec3<f32>,
+    // The time since the particle was launched, in seconds. Negative until its first launch.
+    age: f32,
+    velocity: vec3<f32>,
+    // The time the particle lives before it is launched again, in seconds.
+    lifetime: f32,
+};
+
+struct SimulationUniform {
+    // The point the particles are launched from.
+    emitter: vec3<f32>,
+    // The time since the last step, in seconds.
+    delta_time: f32,
+    // The time since the demo started, in seconds.
+    time: f32,
+    // The height of the ground the particles bounce off.
+    ground: f32,
+    padding: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> simulation: SimulationUniform;
+
+@group(0) @binding(1)
+var<storage, read_write> particles: array<Particle>;
+
+// Must match `ParticleDemo::WORKGROUP_SIZE`.
+const WORKGROUP_SIZE: u32 = 64u;
+
+const GRAVITY: vec3<f32> = vec3<f32>(0.0, -4.0, 0.0);
+
+// The fraction of the vertical speed kept when bouncing off the ground.
+const RESTITUTION: f32 = 0.4;
+
+// Hashes `value` into a pseudo-random number in [0, 1).
+fn hash(value: u32) -> f32 {
+    var x = value;
+    x ^= x >> 16u;
+    x *= 0x7feb352du;
+    x ^= x >> 15u;
+    x *= 0x846ca68bu;
+    x ^= x >> 16u;
+    return f32(x) / 4294967296.0;
+}
+
+// Returns the launch velocity of particle `index`: upwards, in a narrow cone.
+fn launch_velocity(index: u32) -> vec3<f32> {
+    let seed = index * 3u + bitcast<u32>(simulation.time);
+    let angle = hash(seed) * 6.2831855;
+    let spread = sqrt(hash(seed + 1u)) * 0.7;
+    let speed = 3.5 + hash(seed + 2u) * 1.5;
+    return vec3<f32>(cos(angle) * spread, 1.0, sin(angle) * spread) * speed;
+}
+
+@compute @workgroup_size(64)
+fn simulate(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(num_workgroups) workgroups: vec3<u32>,
+) {
+    let index = id.x + id.y * workgroups.x * WORKGROUP_SIZE;
+    if index >= arrayLength(&particles) {
+        return;
+    }
+
+    var particle = particles[index];
+    let delta_time = simulation.delta_time;
+    particle.age += delta_time;
+    if particle.age < 0.0 {
+        // Not launched yet: it waits at the emitter with its first velocity.
+    } else if particle.age >= particle.lifetime {
+        particle.age = particle.age % particle.lifetime;
+        particle.position = simulation.emitter;
+        particle.velocity = launch_velocity(index);
+    } else {
+        particle.velocity += GRAVITY * delta_time;
+        particle.position += particle.velocity * delta_time;
+        if particle.position.y < simulation.ground {
+            particle.position.y = simulation.ground;
+            particle.velocity.y = abs(particle.velocity.y) * RESTITUTION;
+            particle.velocity.x *= 0.8;
+            particle.velocity.z *= 0.8;
+        }
+    }
+    particles[index] = particle;
+}
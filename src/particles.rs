@@ -0,0 +1,389 @@
+//! # Particles Module
+//!
+//! The `particles` module is a gallery demo of the `compute` module: a fountain of a hundred
+//! thousand particles, simulated entirely on the GPU and drawn with one instanced draw call.
+//!
+//! ## Overview
+//!
+//! - **Simulation**: Each [`Particle`] lives in a [`StorageBuffer`]. Every frame, a
+//!   [`ComputePass`] running `particle_simulate.wgsl` advances all of them at once: gravity
+//!   pulls them down, they bounce off the ground, and once they have lived out their lifetime
+//!   they are launched again from the emitter. The CPU only uploads the time step.
+//! - **Rendering**: `particles.wgsl` reads the same buffer in its vertex shader and draws
+//!   every particle as a camera-facing quad. The quads glow in HDR colors and are added onto
+//!   the scene without writing depth, so with bloom enabled the fountain glows.
+//!
+//! The simulation is recorded into the frame's encoder before the scene pass, which reads its
+//! results. It requires compute shaders and storage buffers in vertex shaders, so
+//! [`ParticleDemo::is_supported`] must be checked before creating the demo.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut particles =
+//!     ParticleDemo::new(&gpu, target_format, ParticleDemo::DEFAULT_COUNT, seed, None);
+//!
+//! // Once per frame:
+//! particles.update(&queue, &view_projection, &view, delta_time);
+//! particles.simulate(&mut encoder);
+//! // ... in the scene pass:
+//! particles.render(&mut render_pass);
+//! ```
+
+use crate::camera::Bounds;
+use crate::capabilities::GpuCapabilities;
+use crate::compute::{ComputePass, StorageBuffer};
+use crate::determinism::DeterministicRng;
+use crate::gpu::Gpu;
+use crate::renderer::Renderer;
+use crate::scene::Scene;
+use crate::shader_bindings::particle_simulate as simulate_bindings;
+use crate::shader_bindings::particles as particle_bindings;
+
+/// The WGSL source of the particle simulation.
+const SIMULATE_SHADER_SOURCE: &str = include_str!("particle_simulate.wgsl");
+
+/// The WGSL source of the particle shader.
+const PARTICLE_SHADER_SOURCE: &str = include_str!("particles.wgsl");
+
+/// One particle of the fountain, stored in the particle storage buffer.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Particle {
+    /// The position in world space.
+    pub position: [f32; 3],
+
+    /// The time since the particle was launched, in seconds. Negative until its first launch.
+    pub age: f32,
+
+    /// The velocity, in world units per second.
+    pub velocity: [f32; 3],
+
+    /// The time the particle lives before it is launched again, in seconds.
+    pub lifetime: f32,
+}
+
+/// The uniform data of the simulation.
+#[repr(C)]
+#[derive(Default, Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct SimulationUniform {
+    emitter: [f32; 3],
+    delta_time: f32,
+    time: f32,
+    ground: f32,
+    _padding: [f32; 2],
+}
+
+/// The uniform data of the particle shader.
+#[repr(C)]
+#[derive(Default, Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct ParticleUniform {
+    view_projection: crate::math::Mat4,
+    view: crate::math::Mat4,
+    size: f32,
+    intensity: f32,
+    _padding: [f32; 2],
+}
+
+/// The particle demo's GPU resources and simulation state.
+#[derive(Debug)]
+pub struct ParticleDemo {
+    /// The particles, written by the simulation and read by the particle shader.
+    particles: StorageBuffer,
+
+    /// Advances every particle by a frame.
+    simulate: ComputePass,
+
+    simulation_buffer: wgpu::Buffer,
+    simulation_bind_group: wgpu::BindGroup,
+
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+
+    /// The time since the demo started, in seconds.
+    time: f32,
+}
+
+impl ParticleDemo {
+    /// The number of particles the demo is shown with.
+    pub const DEFAULT_COUNT: usize = 100_000;
+
+    /// The number of particles each workgroup of the simulation advances, matching
+    /// `particle_simulate.wgsl`.
+    pub const WORKGROUP_SIZE: u32 = 64;
+
+    /// The point the particles are launched from, on the ground below the scene.
+    const EMITTER: [f32; 3] = [0.0, -1.0, 0.0];
+
+    /// The height of the ground the particles bounce off, matching the ground grid.
+    const GROUND_HEIGHT: f32 = -1.0;
+
+    /// The half-width of a particle's quad, in world units.
+    const SIZE: f32 = 0.008;
+
+    /// The brightness of a newly launched particle, in linear HDR units.
+    const INTENSITY: f32 = 0.8;
+
+    /// The longest time step simulated at once, so a stalled frame does not scatter the
+    /// fountain.
+    const MAX_STEP: f32 = 0.1;
+
+    /// How far from the emitter the particles get horizontally.
+    const REACH: f32 = 4.0;
+
+    /// How high above the emitter the particles get.
+    const HEIGHT: f32 = 3.5;
+
+    /// Returns `true` if the device can run the simulation and read its results in the
+    /// vertex shader.
+    pub fn is_supported(
+        capabilities: &GpuCapabilities,
+        downlevel: &wgpu::DownlevelCapabilities,
+    ) -> bool {
+        capabilities.supports_compute()
+            && downlevel
+                .flags
+                .contains(wgpu::DownlevelFlags::VERTEX_STORAGE)
+            && capabilities.limits.max_storage_buffers_per_shader_stage >= 1
+    }
+
+    /// Creates a fountain of `count` particles drawn into `target_format`, whose launches are
+    /// staggered over their first lifetime with variations drawn from the "particles" stream of
+    /// `seed`. The pipelines are compiled through `cache` if one is given.
+    pub fn new(
+        gpu: &Gpu,
+        target_format: wgpu::TextureFormat,
+        count: usize,
+        seed: u64,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let device = &gpu.device;
+        let initial = Self::launch(count, &mut DeterministicRng::stream(seed, "particles"));
+        let particles =
+            gpu.create_storage_buffer("Particle Buffer", &initial, wgpu::BufferUsages::empty());
+
+        let simulation_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Particle Simulation Buffer"),
+                contents: bytemuck::bytes_of(&SimulationUniform::default()),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+        let simulation_layout = simulate_bindings::BindGroup0::create_layout(device);
+        let simulation_bind_group = simulate_bindings::BindGroup0 {
+            simulation: simulation_buffer.as_entire_buffer_binding(),
+            particles: particles.binding(),
+        }
+        .create(device, &simulation_layout);
+        let simulate = gpu.create_compute_pass(
+            "Simulate Particles",
+            SIMULATE_SHADER_SOURCE,
+            "simulate",
+            &[&simulation_layout],
+            Self::WORKGROUP_SIZE,
+            cache,
+        );
+
+        let uniform_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Particle Uniform Buffer"),
+                contents: bytemuck::bytes_of(&ParticleUniform::default()),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+        let bind_group_layout = particle_bindings::BindGroup0::create_layout(device);
+        let bind_group = particle_bindings::BindGroup0 {
+            ubo: uniform_buffer.as_entire_buffer_binding(),
+            particles: particles.binding(),
+        }
+        .create(device, &bind_group_layout);
+        let pipeline = Self::create_pipeline(device, target_format, &bind_group_layout, cache);
+
+        Self {
+            particles,
+            simulate,
+            simulation_buffer,
+            simulation_bind_group,
+            pipeline,
+            uniform_buffer,
+            bind_group,
+            time: 0.0,
+        }
+    }
+
+    /// Returns the number of particles.
+    pub fn count(&self) -> u32 {
+        self.particles.len()
+    }
+
+    /// Returns the bounds of the space the particles can reach, in world space.
+    pub fn bounds(&self) -> Bounds {
+        let emitter = crate::math::Vec3::from(Self::EMITTER);
+        Bounds {
+            min: emitter - crate::math::vec3(Self::REACH, 0.0, Self::REACH),
+            max: emitter + crate::math::vec3(Self::REACH, Self::HEIGHT, Self::REACH),
+        }
+    }
+
+    /// Uploads the camera and a time step of `delta_time` seconds for the next frame.
+    pub fn update(
+        &mut self,
+        queue: &wgpu::Queue,
+        view_projection: &crate::math::Mat4,
+        view: &crate::math::Mat4,
+        delta_time: f32,
+    ) {
+        let delta_time = delta_time.clamp(0.0, Self::MAX_STEP);
+        self.time += delta_time;
+        queue.write_buffer(
+            &self.simulation_buffer,
+            0,
+            bytemuck::bytes_of(&SimulationUniform {
+                emitter: Self::EMITTER,
+                delta_time,
+                time: self.time,
+                ground: Self::GROUND_HEIGHT,
+                _padding: [0.0; 2],
+            }),
+        );
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&ParticleUniform {
+                view_projection: *view_projection,
+                view: *view,
+                size: Self::SIZE,
+                intensity: Self::INTENSITY,
+                _padding: [0.0; 2],
+            }),
+        );
+    }
+
+    /// Records the simulation of the uploaded time step into `encoder`, ahead of the pass
+    /// drawing the particles.
+    pub fn simulate(&self, encoder: &mut wgpu::CommandEncoder) {
+        self.simulate
+            .dispatch(encoder, &[&self.simulation_bind_group], self.count());
+    }
+
+    /// Draws the particles with one instanced draw call.
+    pub fn render<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        self.bind_pipeline(render_pass);
+        self.bind_material(render_pass);
+        self.draw(render_pass);
+    }
+
+    /// Sets the particles' pipeline.
+    pub fn bind_pipeline<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_pipeline(&self.pipeline);
+    }
+
+    /// Binds the particles and the camera at set 0.
+    pub fn bind_material<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+    }
+
+    /// Draws a quad per particle, after the pipeline and material are bound.
+    pub fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        render_pass.draw(0..6, 0..self.count());
+    }
+
+    /// Creates `count` particles waiting at the emitter with their first velocity, launched
+    /// one after another over their first lifetime, with velocities and lifetimes drawn from
+    /// `rng`.
+    fn launch(count: usize, rng: &mut DeterministicRng) -> Vec<Particle> {
+        (0..count)
+            .map(|_| {
+                let lifetime = rng.range(2.5..4.0);
+                let angle = rng.next_f32() * std::f32::consts::TAU;
+                let spread = rng.next_f32().sqrt() * 0.35;
+                let speed = rng.range(3.5..5.0);
+                Particle {
+                    position: Self::EMITTER,
+                    age: -rng.next_f32() * lifetime,
+                    velocity: [
+                        angle.cos() * spread * speed,
+                        speed,
+                        angle.sin() * spread * speed,
+                    ],
+                    lifetime,
+                }
+            })
+            .collect()
+    }
+
+    /// Creates the pipeline adding the particles onto the scene pass's targets.
+    fn create_pipeline(
+        device: &wgpu::Device,
+        target_format: wgpu::TextureFormat,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> wgpu::RenderPipeline {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Particle Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(PARTICLE_SHADER_SOURCE)),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Particle Pipeline Layout"),
+            bind_group_layouts: &[bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // The particles are added onto what is behind them, so they need no sorting, and they
+        // are tested against the scene's depth without writing it.
+        let additive = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        };
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Particle Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vertex_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: Renderer::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fragment_main"),
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: target_format,
+                        blend: Some(wgpu::BlendState {
+                            color: additive,
+                            alpha: additive,
+                        }),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(wgpu::ColorTargetState {
+                        format: Scene::OBJECT_ID_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::empty(),
+                    }),
+                ],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        })
+    }
+}
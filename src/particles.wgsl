@@ -0,0 +1,93 @@
+// GPU particles.
+//
+// Every particle is one instance of a camera-facing quad, whose center is read from the
+// storage buffer the simulation writes. The particles glow in HDR colors, from white-hot at
+// launch to a dim red as they age, and are added onto the scene.
+
+struct Particle {
+    position: vec3<f32>,
+    // The time since the particle was launched, in seconds. Negative until its first launch.
+    age: f32,
+    velocity: vec3<f32>,
+    // The time the particle lives before it is launched again, in seconds.
+    lifetime: f32,
+};
+
+struct ParticleUniform {
+    view_projection: mat4x4<f32>,
+    // The world-to-view transform, whose rows are the camera's axes.
+    view: mat4x4<f32>,
+    // The half-width of a particle's quad, in world units.
+    size: f32,
+    // The brightness of a newly launched particle, in linear HDR units.
+    intensity: f32,
+    padding: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> ubo: ParticleUniform;
+
+@group(0) @binding(1)
+var<storage, read> particles: array<Particle>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // The position within the quad, from -1 to 1 along each axis.
+    @location(0) corner: vec2<f32>,
+    @location(1) color: vec3<f32>,
+};
+
+@vertex
+fn vertex_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
+    // Two triangles forming a quad.
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+    let particle = particles[instance_index];
+
+    var out: VertexOutput;
+    out.corner = corner;
+    if particle.age < 0.0 {
+        // Not launched yet: a degenerate quad, discarded before rasterization.
+        out.clip_position = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+        out.color = vec3<f32>(0.0);
+        return out;
+    }
+
+    let right = vec3<f32>(ubo.view[0].x, ubo.view[1].x, ubo.view[2].x);
+    let up = vec3<f32>(ubo.view[0].y, ubo.view[1].y, ubo.view[2].y);
+    let position = particle.position + (right * corner.x + up * corner.y) * ubo.size;
+    out.clip_position = ubo.view_projection * vec4<f32>(position, 1.0);
+
+    let t = clamp(particle.age / particle.lifetime, 0.0, 1.0);
+    let hot = vec3<f32>(1.0, 0.85, 0.6);
+    let cool = vec3<f32>(0.9, 0.15, 0.05);
+    out.color = mix(hot, cool, t) * ubo.intensity * (1.0 - t);
+    return out;
+}
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    // The object id target, which the pipeline leaves unchanged.
+    @location(1) object_id: u32,
+};
+
+@fragment
+fn fragment_main(in: VertexOutput) -> FragmentOutput {
+    // A round spot fading towards its edge.
+    let falloff = max(1.0 - dot(in.corner, in.corner), 0.0);
+
+    var out: FragmentOutput;
+    out.color = vec4<f32>(in.color * falloff * falloff, 0.0);
+    out.object_id = 0u;
+    return out;
+}
@@ -0,0 +1,101 @@
+//! # Picking Module
+//!
+//! The `picking` module finds the object under a clicked pixel, so it can be selected.
+//!
+//! ## Overview
+//!
+//! [`Picker`] reads back the object id target the scene pass writes, like the depth probe
+//! does, but once per click rather than continuously: [`Picker::pick`] requests the pixel,
+//! and a frame or two later [`Picker::take_picked`] returns the id of the object drawn there.
+//! The id is then resolved to a node with `Scene::object_node`, which tells the scene's nodes
+//! apart since each writes its own id (see `Scene::node_object_id`).
+//!
+//! Reading the id back from the GPU finds exactly the object visible at the pixel, whatever
+//! its shape, with no geometry kept on the CPU.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! picker.pick((x, y));
+//!
+//! // Once per frame:
+//! picker.poll(&device);
+//! if let Some(object_id) = picker.take_picked() {
+//!     selected = scene.object_node(object_id);
+//! }
+//! // ... render the scene into `depth_texture` and `object_id_texture` ...
+//! picker.encode_copy(&mut encoder, &depth_texture, &object_id_texture, viewport);
+//! queue.submit(std::iter::once(encoder.finish()));
+//! picker.after_submit();
+//! ```
+
+use crate::probe::DepthProbe;
+use crate::viewport::Viewport;
+
+/// Reads back the object id under a clicked pixel.
+#[derive(Debug)]
+pub struct Picker {
+    /// Reads back the object id under the requested pixel, without the depth.
+    probe: DepthProbe,
+
+    /// The pixel whose object id is being read back, if any.
+    pending: Option<(u32, u32)>,
+
+    /// The object id read back for the last pick, until it is taken.
+    picked: Option<u32>,
+}
+
+impl Picker {
+    /// Creates a picker with nothing to pick.
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self {
+            probe: DepthProbe::new(device, false),
+            pending: None,
+            picked: None,
+        }
+    }
+
+    /// Requests the object id under `pixel`, in the same pixels as the scene's render
+    /// targets. A pick still in flight is abandoned.
+    pub fn pick(&mut self, pixel: (u32, u32)) {
+        self.pending = Some(pixel);
+        self.probe.set_cursor(Some(pixel));
+    }
+
+    /// Finishes a readback in flight, if its buffer has been mapped. This never blocks.
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        let Some(pixel) = self.pending else {
+            return;
+        };
+        // The world position is not needed, so any matrix unprojects the depth.
+        self.probe.poll(device, &crate::math::identity());
+        if let Some(sample) = self.probe.latest().filter(|sample| sample.pixel == pixel) {
+            self.picked = Some(sample.object_id);
+            self.pending = None;
+            self.probe.set_cursor(None);
+        }
+    }
+
+    /// Returns the object id read back for the last pick, once, after it completed.
+    pub fn take_picked(&mut self) -> Option<u32> {
+        self.picked.take()
+    }
+
+    /// Records a copy of the texel under the requested pixel, if a pick is pending and no
+    /// readback is in flight. See [`DepthProbe::encode_copy`].
+    pub fn encode_copy(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        depth_texture: &wgpu::Texture,
+        object_id_texture: &wgpu::Texture,
+        viewport: Viewport,
+    ) {
+        self.probe
+            .encode_copy(encoder, depth_texture, object_id_texture, viewport);
+    }
+
+    /// Starts mapping the readback buffer once the frame containing the copy was submitted.
+    pub fn after_submit(&mut self) {
+        self.probe.after_submit();
+    }
+}
@@ -0,0 +1,152 @@
+//! # Pipeline Cache Module
+//!
+//! The `pipeline_cache` module persists the driver's compiled pipelines between runs, so the
+//! first frames after startup do not stall for seconds while shaders compile.
+//!
+//! ## Overview
+//!
+//! - [`PipelineCacheStore`] creates a `wgpu::PipelineCache` when the device was granted the
+//!   `PIPELINE_CACHE` feature, seeded with the data saved by the previous run on the same
+//!   adapter. Every pipeline the renderer creates passes the cache in its descriptor.
+//! - The renderer creates all of its pipelines up front, as a warm-up step, and then saves the
+//!   cache data with [`PipelineCacheStore::save`]. Pipelines created later, such as the crowd
+//!   demo's, save the cache again.
+//! - The data is stored in the platform cache directory (for example
+//!   `~/.cache/wgpu-example/pipelines` on Linux), in one file per adapter named after
+//!   `wgpu::util::pipeline_cache_key`. Data from an outdated driver is detected and discarded
+//!   by `wgpu`, which then starts over with an empty cache.
+//!
+//! wgpu currently supports pipeline caches on Vulkan only. On other backends and on the web,
+//! the store holds no cache and pipelines compile as before; D3D12 and Metal drivers keep
+//! their own shader caches.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut pipeline_cache = PipelineCacheStore::open(&device, &adapter.get_info());
+//! let scene = Scene::new(&device, &queue, surface_format, pipeline_cache.cache());
+//! if let Err(error) = pipeline_cache.save() {
+//!     log::warn!("{error}");
+//! }
+//! ```
+
+/// Errors produced while saving the pipeline cache.
+#[derive(Debug, thiserror::Error)]
+pub enum PipelineCacheError {
+    /// The cache file could not be written.
+    #[error("failed to write the pipeline cache '{path}': {source}")]
+    Io {
+        /// The path of the cache file.
+        path: String,
+
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+}
+
+/// A pipeline cache loaded from and saved to disk, keyed by adapter.
+#[derive(Debug, Default)]
+pub struct PipelineCacheStore {
+    /// The cache passed to pipeline descriptors, if the device supports one.
+    cache: Option<wgpu::PipelineCache>,
+
+    /// The file the cache data is stored in, if there is a cache directory.
+    #[cfg(not(target_arch = "wasm32"))]
+    path: Option<std::path::PathBuf>,
+
+    /// Whether the cache was seeded with data saved by a previous run.
+    warm: bool,
+
+    /// The size of the data saved last, to skip saving when no pipelines were added.
+    #[cfg(not(target_arch = "wasm32"))]
+    saved_len: usize,
+}
+
+impl PipelineCacheStore {
+    /// Creates the pipeline cache of `device`, seeded with the data saved for the adapter
+    /// described by `adapter_info`. Without `PIPELINE_CACHE` support, the store holds no cache.
+    #[cfg_attr(target_arch = "wasm32", allow(unused_variables))]
+    pub fn open(device: &wgpu::Device, adapter_info: &wgpu::AdapterInfo) -> Self {
+        if !device.features().contains(wgpu::Features::PIPELINE_CACHE) {
+            return Self::default();
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(key) = wgpu::util::pipeline_cache_key(adapter_info) else {
+                return Self::default();
+            };
+            let path = dirs::cache_dir()
+                .map(|directory| directory.join("wgpu-example").join("pipelines").join(key));
+            let data = path.as_ref().and_then(|path| std::fs::read(path).ok());
+
+            // SAFETY: The data was returned by `PipelineCache::get_data` of a previous run, as
+            // only `save` writes the file, and it is keyed by the adapter it was created on.
+            // wgpu discards data from other drivers or versions because `fallback` is set.
+            let cache = unsafe {
+                device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                    label: Some("Pipeline Cache"),
+                    data: data.as_deref(),
+                    fallback: true,
+                })
+            };
+
+            Self {
+                cache: Some(cache),
+                path,
+                warm: data.is_some(),
+                saved_len: data.map_or(0, |data| data.len()),
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        Self::default()
+    }
+
+    /// Returns the cache to pass to pipeline descriptors, or `None` if the device has none.
+    pub fn cache(&self) -> Option<&wgpu::PipelineCache> {
+        self.cache.as_ref()
+    }
+
+    /// Returns `true` if the cache was seeded with the data saved by a previous run.
+    pub fn is_warm(&self) -> bool {
+        self.warm
+    }
+
+    /// Writes the cache data to disk, if pipelines were added since it was last saved.
+    ///
+    /// The data is written to a temporary file that then replaces the cache file, so a crash
+    /// while saving never leaves a truncated cache behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PipelineCacheError::Io` if the cache file cannot be written.
+    pub fn save(&mut self) -> Result<(), PipelineCacheError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let (Some(cache), Some(path)) = (&self.cache, &self.path) else {
+                return Ok(());
+            };
+            let Some(data) = cache.get_data() else {
+                return Ok(());
+            };
+            if data.len() == self.saved_len {
+                return Ok(());
+            }
+
+            let io_error = |source| PipelineCacheError::Io {
+                path: path.display().to_string(),
+                source,
+            };
+            if let Some(directory) = path.parent() {
+                std::fs::create_dir_all(directory).map_err(io_error)?;
+            }
+            let temporary = path.with_extension("tmp");
+            std::fs::write(&temporary, &data).map_err(io_error)?;
+            std::fs::rename(&temporary, path).map_err(io_error)?;
+            log::info!("Saved {} bytes of pipeline cache data", data.len());
+            self.saved_len = data.len();
+        }
+        Ok(())
+    }
+}
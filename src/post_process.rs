@@ -0,0 +1,397 @@
+//! # Post-Processing Module
+//!
+//! The `post_process` module turns the scene, drawn in linear high dynamic range (HDR)
+//! colors, into the colors shown on the surface.
+//!
+//! ## Overview
+//!
+//! The scene pass draws into an offscreen [`PostProcess::HDR_FORMAT`] texture instead of the
+//! surface, so colors brighter than white survive until they are displayed. A fullscreen
+//! triangle then reads it and writes the surface, applying in order:
+//!
+//! 1. **Bloom**: Adds the glow of the brightest pixels, blurred by the `bloom` module's
+//!    passes, which run first.
+//! 2. **Tonemapping**: [`Tonemapping`] compresses the HDR colors into the displayable range,
+//!    with the Reinhard operator or a fit of the ACES filmic curve.
+//! 3. **Vignette**: Darkens the image towards its corners.
+//! 4. **Gamma correction**: Encodes the linear colors as sRGB, since the surface is not an
+//!    sRGB format. On sRGB surfaces the hardware encodes them instead, and the setting has no
+//!    effect.
+//!
+//! Each effect is toggled by [`PostEffects`], edited in the Post FX window. The pass runs
+//! before the GUI's, so the GUI is drawn over the processed image, unchanged.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! renderer.set_post_effects(&PostEffects {
+//!     tonemapping: Tonemapping::Reinhard,
+//!     vignette: true,
+//!     ..PostEffects::default()
+//! });
+//! ```
+
+use crate::bloom::Bloom;
+use crate::deletion_queue::DeletionQueue;
+use crate::gpu_timer::{GpuTimer, TimedPass};
+use crate::shader_bindings::post_process as post_bindings;
+
+/// The WGSL source of the post-processing pass.
+const POST_SHADER_SOURCE: &str = include_str!("post_process.wgsl");
+
+/// The curve compressing HDR colors into the displayable range.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum Tonemapping {
+    /// No curve: colors brighter than white are clipped.
+    None,
+
+    /// The Reinhard operator, `color / (1 + color)`, which compresses highlights gently but
+    /// also dims the midtones.
+    Reinhard,
+
+    /// Krzysztof Narkowicz's fit of the ACES filmic curve, with more contrast and saturation.
+    #[default]
+    Aces,
+}
+
+impl Tonemapping {
+    /// Every curve, in the order they are offered in the user interface.
+    pub const ALL: [Tonemapping; 3] = [Tonemapping::None, Tonemapping::Reinhard, Tonemapping::Aces];
+
+    /// Returns the name shown in the user interface.
+    pub fn label(self) -> &'static str {
+        match self {
+            Tonemapping::None => "None",
+            Tonemapping::Reinhard => "Reinhard",
+            Tonemapping::Aces => "ACES",
+        }
+    }
+}
+
+/// The effects applied to the scene before it is shown, edited in the Post FX window.
+///
+/// Missing fields take their default values when deserializing, so saved settings keep
+/// loading when effects are added.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PostEffects {
+    /// The curve compressing the HDR colors into the displayable range.
+    pub tonemapping: Tonemapping,
+
+    /// Whether the linear colors are encoded as sRGB.
+    pub gamma: bool,
+
+    /// Whether the image is darkened towards its corners.
+    pub vignette: bool,
+
+    /// How much the corners are darkened by the vignette, from `0.0` to `1.0`.
+    pub vignette_strength: f32,
+
+    /// Whether the brightest pixels glow.
+    pub bloom: bool,
+
+    /// The brightness, in linear HDR units, above which pixels glow.
+    pub bloom_threshold: f32,
+
+    /// How strongly the glow is added to the scene.
+    pub bloom_intensity: f32,
+}
+
+impl Default for PostEffects {
+    fn default() -> Self {
+        Self {
+            tonemapping: Tonemapping::default(),
+            gamma: true,
+            vignette: false,
+            vignette_strength: 0.4,
+            bloom: false,
+            bloom_threshold: 1.0,
+            bloom_intensity: 0.3,
+        }
+    }
+}
+
+impl PostEffects {
+    /// Reduces the effects to the values the shader uses, for a surface that encodes sRGB
+    /// itself if `srgb_surface` is set.
+    pub fn uniform(&self, srgb_surface: bool) -> PostUniform {
+        PostUniform {
+            tonemapping: match self.tonemapping {
+                Tonemapping::None => 0,
+                Tonemapping::Reinhard => 1,
+                Tonemapping::Aces => 2,
+            },
+            gamma: u32::from(self.gamma && !srgb_surface),
+            vignette: if self.vignette {
+                self.vignette_strength.clamp(0.0, 1.0)
+            } else {
+                0.0
+            },
+            bloom: if self.bloom {
+                self.bloom_intensity.max(0.0)
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+/// The post effects as read by the shader.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PostUniform {
+    /// `0` without tonemapping, `1` for Reinhard, and `2` for ACES.
+    pub tonemapping: u32,
+
+    /// `1` to encode the colors as sRGB, `0` to write them unchanged.
+    pub gamma: u32,
+
+    /// How much the corners are darkened, `0.0` without a vignette.
+    pub vignette: f32,
+
+    /// The scale of the bloom added to the scene, `0.0` without bloom.
+    pub bloom: f32,
+}
+
+/// The HDR target the scene is drawn into, and the passes displaying it on the surface.
+#[derive(Debug)]
+pub struct PostProcess {
+    /// Draws the fullscreen triangle applying the effects.
+    pipeline: wgpu::RenderPipeline,
+
+    /// The layout of `bind_group`, needed to recreate it when resizing.
+    bind_group_layout: wgpu::BindGroupLayout,
+
+    /// The buffer holding the `PostUniform`.
+    buffer: wgpu::Buffer,
+
+    /// The HDR texture the scene is drawn into.
+    hdr_texture: wgpu::Texture,
+
+    /// The view of `hdr_texture` the scene pass renders into.
+    hdr_texture_view: wgpu::TextureView,
+
+    /// Exposes `buffer`, `hdr_texture`, and the bloom to the shader.
+    bind_group: wgpu::BindGroup,
+
+    /// Blurs the brightest pixels of `hdr_texture` into a glow.
+    bloom: Bloom,
+
+    /// Whether the bloom passes run, as last uploaded.
+    bloom_enabled: bool,
+
+    /// Whether the surface encodes sRGB itself, so the shader must not.
+    srgb_surface: bool,
+}
+
+impl PostProcess {
+    /// The format of the HDR texture, and of the targets of every pipeline of the scene pass.
+    pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+    /// Creates the pass writing to a `surface_format` surface, compiled through `cache` if one
+    /// is given, and an HDR texture of `width` x `height` pixels.
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Post-Processing Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(POST_SHADER_SOURCE)),
+        });
+
+        let bind_group_layout = post_bindings::BindGroup0::create_layout(device);
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post-Processing Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Post-Processing Pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("post_vertex"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("post_fragment"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        });
+
+        let srgb_surface = surface_format.is_srgb();
+        let buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Post-Processing Uniform Buffer"),
+                contents: bytemuck::bytes_of(&PostEffects::default().uniform(srgb_surface)),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+
+        let (hdr_texture, hdr_texture_view) = Self::create_hdr_texture(device, width, height);
+        let bloom = Bloom::new(device, &hdr_texture_view, width, height, cache);
+        let bind_group = Self::create_bind_group(
+            device,
+            &bind_group_layout,
+            &buffer,
+            &hdr_texture_view,
+            &bloom,
+        );
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            buffer,
+            hdr_texture,
+            hdr_texture_view,
+            bind_group,
+            bloom,
+            bloom_enabled: false,
+            srgb_surface,
+        }
+    }
+
+    /// Returns the view of the HDR texture, the color target of the scene pass.
+    pub fn hdr_texture_view(&self) -> &wgpu::TextureView {
+        &self.hdr_texture_view
+    }
+
+    /// Recreates the HDR texture and the bloom's mip chain for a surface of `width` x
+    /// `height` pixels. The replaced resources are retired to `deletion_queue`.
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        deletion_queue: &mut DeletionQueue,
+    ) {
+        let (texture, view) = Self::create_hdr_texture(device, width, height);
+        deletion_queue.retire(std::mem::replace(&mut self.hdr_texture, texture));
+        deletion_queue.retire(std::mem::replace(&mut self.hdr_texture_view, view));
+        self.bloom.resize(
+            device,
+            &self.hdr_texture_view,
+            width,
+            height,
+            deletion_queue,
+        );
+        let bind_group = Self::create_bind_group(
+            device,
+            &self.bind_group_layout,
+            &self.buffer,
+            &self.hdr_texture_view,
+            &self.bloom,
+        );
+        deletion_queue.retire(std::mem::replace(&mut self.bind_group, bind_group));
+    }
+
+    /// Uploads `effects` for the next passes.
+    pub fn update(&mut self, queue: &wgpu::Queue, effects: &PostEffects) {
+        queue.write_buffer(
+            &self.buffer,
+            0,
+            bytemuck::bytes_of(&effects.uniform(self.srgb_surface)),
+        );
+        self.bloom_enabled = effects.bloom;
+        if effects.bloom {
+            self.bloom.update(queue, effects);
+        }
+    }
+
+    /// Runs the bloom passes, if enabled, and draws the HDR texture onto `target` with the
+    /// uploaded effects, replacing its contents. Both are timed by `timer` if one is given.
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &wgpu::TextureView,
+        timer: Option<&GpuTimer>,
+    ) {
+        if self.bloom_enabled {
+            self.bloom.render(
+                encoder,
+                timer.and_then(|timer| timer.timestamp_writes(TimedPass::Bloom)),
+            );
+        }
+
+        let timestamp_writes =
+            timer.and_then(|timer| timer.timestamp_writes(TimedPass::PostProcess));
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Post-Processing Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// Creates the HDR texture of `width` x `height` pixels, and its view.
+    fn create_hdr_texture(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("HDR Scene Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::HDR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+
+    /// Creates the bind group exposing `buffer`, the HDR texture's `hdr_view`, and the
+    /// `bloom` to the shader.
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        buffer: &wgpu::Buffer,
+        hdr_view: &wgpu::TextureView,
+        bloom: &Bloom,
+    ) -> wgpu::BindGroup {
+        post_bindings::BindGroup0 {
+            settings: buffer.as_entire_buffer_binding(),
+            hdr_texture: hdr_view,
+            bloom_texture: bloom.view(),
+            bloom_sampler: bloom.sampler(),
+        }
+        .create(device, layout)
+    }
+}
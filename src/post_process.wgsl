@@ -0,0 +1,90 @@
+// Post-processing.
+//
+// A fullscreen triangle reads the HDR scene texture pixel by pixel and writes the displayed
+// color: with the bloom added, tonemapped into [0, 1], darkened towards the corners by the
+// vignette, and converted from linear to sRGB.
+
+struct PostSettings {
+    // 0: none (clamped), 1: Reinhard, 2: ACES.
+    tonemapping: u32,
+    // 1 to encode the linear colors as sRGB, 0 to write them unchanged.
+    gamma: u32,
+    // The darkening at the corners, 0 without a vignette.
+    vignette: f32,
+    // The scale of the bloom added to the scene, 0 without bloom.
+    bloom: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> settings: PostSettings;
+
+// The scene, drawn in linear HDR colors.
+@group(0) @binding(1)
+var hdr_texture: texture_2d<f32>;
+
+// The glow of the scene's bright pixels, at half resolution.
+@group(0) @binding(2)
+var bloom_texture: texture_2d<f32>;
+
+@group(0) @binding(3)
+var bloom_sampler: sampler;
+
+// Draws a single triangle covering the whole screen.
+@vertex
+fn post_vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (1.0 + color);
+}
+
+// Krzysztof Narkowicz's fit of the ACES filmic curve.
+fn aces(color: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return (color * (a * color + b)) / (color * (c * color + d) + e);
+}
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+@fragment
+fn post_fragment(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let size = vec2<f32>(textureDimensions(hdr_texture));
+    let texel = textureLoad(hdr_texture, vec2<i32>(position.xy), 0);
+    var color = max(texel.rgb, vec3<f32>(0.0));
+
+    if settings.bloom > 0.0 {
+        let bloom = textureSampleLevel(bloom_texture, bloom_sampler, position.xy / size, 0.0);
+        color += bloom.rgb * settings.bloom;
+    }
+
+    switch settings.tonemapping {
+        case 1u: {
+            color = reinhard(color);
+        }
+        case 2u: {
+            color = aces(color);
+        }
+        default: {}
+    }
+    color = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+
+    if settings.vignette > 0.0 {
+        let offset = position.xy / size * 2.0 - 1.0;
+        color *= 1.0 - settings.vignette * smoothstep(0.5, 1.5, dot(offset, offset));
+    }
+
+    if settings.gamma != 0u {
+        color = linear_to_srgb(color);
+    }
+    return vec4<f32>(color, texel.a);
+}
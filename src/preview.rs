@@ -0,0 +1,534 @@
+//! # Preview Module
+//!
+//! The `preview` module renders the small thumbnails shown in the Inspector and Assets
+//! panels into textures that `egui` draws like images.
+//!
+//! ## Overview
+//!
+//! - A [`Material`] is previewed on a sphere under a directional light, so its color, metallic,
+//!   and roughness can be judged before it is used.
+//! - Meshes, described as [`PreviewGeometry`], are previewed from a three-quarter view. The
+//!   mesh under the cursor turns on a turntable, and turns back once the cursor leaves it.
+//! - [`PreviewRenderer`] keeps a texture for every preview and only renders it again when
+//!   what it shows changes: the material was edited or the mesh turned. Otherwise the cached
+//!   texture is shown, so the previews cost nothing in the frames they do not change.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let meshes = vec![("Triangle", PreviewGeometry::from_triangles(&positions, &colors, &indices))];
+//! let mut previews = PreviewRenderer::new(&device, &mut egui_renderer, meshes, None);
+//!
+//! // Once per frame, while the panels are shown:
+//! previews.update(&device, &queue, &material, hovered_mesh, delta_time);
+//! let image = egui::Image::new((previews.material_texture(), egui::vec2(128.0, 128.0)));
+//! ```
+
+use crate::camera::{Bounds, Projection};
+use crate::material::Material;
+use crate::renderer::Renderer;
+use crate::shader_bindings::preview as preview_bindings;
+
+/// The WGSL source of the preview shader.
+const PREVIEW_SHADER_SOURCE: &str = include_str!("preview.wgsl");
+
+/// A vertex of preview geometry.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PreviewVertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+    color: [f32; 3],
+}
+
+impl PreviewVertex {
+    /// Returns the vertex attributes, matching `VertexInput` in the shader.
+    pub fn vertex_attributes() -> Vec<wgpu::VertexAttribute> {
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x3].to_vec()
+    }
+
+    /// Returns the vertex buffer layout for the given `attributes`.
+    pub fn description(attributes: &[wgpu::VertexAttribute]) -> wgpu::VertexBufferLayout {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<PreviewVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes,
+        }
+    }
+}
+
+/// Triangles shown in a preview, scaled to fit into the unit sphere around the origin.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PreviewGeometry {
+    vertices: Vec<PreviewVertex>,
+    indices: Vec<u32>,
+}
+
+impl PreviewGeometry {
+    /// Creates flat-shaded geometry from the triangles `indices` of `positions`, colored with
+    /// the `colors` of their vertices. Triangles with an index out of range are skipped.
+    pub fn from_triangles(
+        positions: &[crate::math::Vec3],
+        colors: &[[f32; 3]],
+        indices: &[u32],
+    ) -> Self {
+        let Some(bounds) = Bounds::from_points(positions.iter().copied()) else {
+            return Self::default();
+        };
+        let (center, scale) = (bounds.center(), 1.0 / bounds.radius());
+
+        let mut geometry = Self::default();
+        for triangle in indices.chunks_exact(3) {
+            let corners = [0, 1, 2].map(|corner| {
+                let index = triangle[corner] as usize;
+                Some((*positions.get(index)?, *colors.get(index)?))
+            });
+            let [Some(a), Some(b), Some(c)] = corners else {
+                continue;
+            };
+            let normal = crate::math::cross(&(b.0 - a.0), &(c.0 - a.0));
+            let normal = if crate::math::length(&normal) > 0.0 {
+                normal.normalize()
+            } else {
+                normal
+            };
+            for (position, color) in [a, b, c] {
+                geometry.indices.push(geometry.vertices.len() as u32);
+                geometry.vertices.push(PreviewVertex {
+                    position: ((position - center) * scale).into(),
+                    normal: normal.into(),
+                    color,
+                });
+            }
+        }
+        geometry
+    }
+
+    /// Creates a white sphere of radius 1 with `rings` rings of `segments` quads each.
+    pub fn sphere(rings: u32, segments: u32) -> Self {
+        let (rings, segments) = (rings.max(2), segments.max(3));
+        let mut geometry = Self::default();
+        for ring in 0..=rings {
+            let polar = std::f32::consts::PI * ring as f32 / rings as f32;
+            for segment in 0..=segments {
+                let azimuth = std::f32::consts::TAU * segment as f32 / segments as f32;
+                let normal = [
+                    polar.sin() * azimuth.cos(),
+                    polar.cos(),
+                    polar.sin() * azimuth.sin(),
+                ];
+                geometry.vertices.push(PreviewVertex {
+                    position: normal,
+                    normal,
+                    color: [1.0; 3],
+                });
+            }
+        }
+        let row = segments + 1;
+        for ring in 0..rings {
+            for segment in 0..segments {
+                let top = ring * row + segment;
+                let bottom = top + row;
+                geometry
+                    .indices
+                    .extend([top, bottom, top + 1, top + 1, bottom, bottom + 1]);
+            }
+        }
+        geometry
+    }
+
+    /// Returns `true` if the geometry has no triangles.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+/// The uniform data of the preview shader.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct PreviewUniform {
+    view_projection: crate::math::Mat4,
+    model: crate::math::Mat4,
+    base_color: [f32; 4],
+    params: [f32; 4],
+    light_direction: [f32; 4],
+    eye: [f32; 4],
+}
+
+/// Preview geometry uploaded to the GPU.
+#[derive(Debug)]
+struct PreviewMesh {
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    index_count: u32,
+}
+
+impl PreviewMesh {
+    fn new(device: &wgpu::Device, label: &str, geometry: &PreviewGeometry) -> Self {
+        let vertex_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("{label} Preview Vertex Buffer")),
+                contents: bytemuck::cast_slice(&geometry.vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            },
+        );
+        let index_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("{label} Preview Index Buffer")),
+                contents: bytemuck::cast_slice(&geometry.indices),
+                usage: wgpu::BufferUsages::INDEX,
+            },
+        );
+        Self {
+            vertex_buffer,
+            index_buffer,
+            index_count: geometry.indices.len() as u32,
+        }
+    }
+}
+
+/// The texture a preview is rendered into, registered with `egui`.
+#[derive(Debug)]
+struct PreviewTarget {
+    view: wgpu::TextureView,
+    texture_id: egui::TextureId,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+/// A mesh preview and the turntable angle its texture shows.
+#[derive(Debug)]
+struct MeshPreview {
+    name: &'static str,
+    mesh: PreviewMesh,
+    target: PreviewTarget,
+    shown_angle: Option<f32>,
+}
+
+/// Renders material and mesh previews into cached textures.
+#[derive(Debug)]
+pub struct PreviewRenderer {
+    pipeline: wgpu::RenderPipeline,
+    depth_view: wgpu::TextureView,
+    sphere: PreviewMesh,
+    material_target: PreviewTarget,
+
+    /// The material the material preview shows, or `None` before it is first rendered.
+    shown_material: Option<Material>,
+
+    meshes: Vec<MeshPreview>,
+
+    /// How far the turntable has turned the mesh under the cursor, in radians.
+    turntable_angle: f32,
+}
+
+impl PreviewRenderer {
+    /// The width and height of every preview texture, in pixels.
+    pub const SIZE: u32 = 128;
+
+    /// The format of the preview textures, the format `egui` uses for its own textures.
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// The angle a mesh is shown at while it is not turning, in radians.
+    pub const REST_ANGLE: f32 = -0.6;
+
+    /// How fast the turntable turns, in radians per second.
+    pub const TURNTABLE_SPEED: f32 = 1.2;
+
+    /// The background color of the previews.
+    const CLEAR_COLOR: wgpu::Color = wgpu::Color {
+        r: 0.03,
+        g: 0.035,
+        b: 0.045,
+        a: 1.0,
+    };
+
+    /// Creates the renderer and a texture, registered with `egui_renderer`, for the material
+    /// preview and for each of the named `meshes`.
+    pub fn new(
+        device: &wgpu::Device,
+        egui_renderer: &mut egui_wgpu::Renderer,
+        meshes: Vec<(&'static str, PreviewGeometry)>,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        let bind_group_layout = preview_bindings::BindGroup0::create_layout(device);
+
+        let size = wgpu::Extent3d {
+            width: Self::SIZE,
+            height: Self::SIZE,
+            depth_or_array_layers: 1,
+        };
+        let depth_view = device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some("Preview Depth Texture"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: Renderer::DEPTH_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut target = |label: &str| {
+            let view = device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(&format!("{label} Preview Texture")),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: Self::FORMAT,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default());
+            let texture_id =
+                egui_renderer.register_native_texture(device, &view, wgpu::FilterMode::Linear);
+            let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(&format!("{label} Preview Uniform Buffer")),
+                size: std::mem::size_of::<PreviewUniform>() as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let bind_group = preview_bindings::BindGroup0 {
+                ubo: uniform_buffer.as_entire_buffer_binding(),
+            }
+            .create(device, &bind_group_layout);
+            PreviewTarget {
+                view,
+                texture_id,
+                uniform_buffer,
+                bind_group,
+            }
+        };
+
+        let material_target = target("Material");
+        let meshes = meshes
+            .into_iter()
+            .map(|(name, geometry)| MeshPreview {
+                name,
+                mesh: PreviewMesh::new(device, name, &geometry),
+                target: target(name),
+                shown_angle: None,
+            })
+            .collect();
+
+        Self {
+            pipeline: Self::create_pipeline(device, &bind_group_layout, cache),
+            depth_view,
+            sphere: PreviewMesh::new(device, "Sphere", &PreviewGeometry::sphere(24, 48)),
+            material_target,
+            shown_material: None,
+            meshes,
+            turntable_angle: 0.0,
+        }
+    }
+
+    /// Returns the texture of the material preview.
+    pub fn material_texture(&self) -> egui::TextureId {
+        self.material_target.texture_id
+    }
+
+    /// Returns the name and texture of each mesh preview.
+    pub fn mesh_textures(&self) -> Vec<(&'static str, egui::TextureId)> {
+        self.meshes
+            .iter()
+            .map(|preview| (preview.name, preview.target.texture_id))
+            .collect()
+    }
+
+    /// Renders the previews whose content changed: the material preview if `material` differs
+    /// from the one it shows, and the mesh named `turntable`, which turns by `delta_time`
+    /// seconds. A mesh that stopped turning is rendered once more at its rest angle.
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        material: &Material,
+        turntable: Option<&str>,
+        delta_time: f32,
+    ) {
+        let turning = self
+            .meshes
+            .iter()
+            .any(|preview| Some(preview.name) == turntable);
+        self.turntable_angle = if turning {
+            (self.turntable_angle + Self::TURNTABLE_SPEED * delta_time) % std::f32::consts::TAU
+        } else {
+            0.0
+        };
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Preview Encoder"),
+        });
+        let mut rendered = false;
+
+        if self.shown_material != Some(*material) {
+            let uniform = Self::uniform(material, Self::REST_ANGLE);
+            self.render(
+                queue,
+                &mut encoder,
+                &self.material_target,
+                &self.sphere,
+                &uniform,
+            );
+            self.shown_material = Some(*material);
+            rendered = true;
+        }
+
+        // Meshes are shown with their own colors, on a neutral rough material.
+        let neutral = Material {
+            base_color: [1.0; 3],
+            metallic: 0.0,
+            roughness: 0.8,
+            ..Material::default()
+        };
+        for index in 0..self.meshes.len() {
+            let preview = &self.meshes[index];
+            let angle = match Some(preview.name) == turntable {
+                true => Self::REST_ANGLE + self.turntable_angle,
+                false => Self::REST_ANGLE,
+            };
+            if preview.shown_angle != Some(angle) {
+                let uniform = Self::uniform(&neutral, angle);
+                self.render(
+                    queue,
+                    &mut encoder,
+                    &preview.target,
+                    &preview.mesh,
+                    &uniform,
+                );
+                self.meshes[index].shown_angle = Some(angle);
+                rendered = true;
+            }
+        }
+
+        if rendered {
+            queue.submit(Some(encoder.finish()));
+        }
+    }
+
+    /// Returns the uniform data showing geometry with `material`, turned by `angle` radians
+    /// around the vertical axis.
+    fn uniform(material: &Material, angle: f32) -> PreviewUniform {
+        // The unit sphere fills the preview with a small margin.
+        let projection = Projection {
+            field_of_view: 40.0,
+            near: 0.1,
+            far: 10.0,
+            auto_clip: false,
+        };
+        let eye = crate::math::vec3(0.0, 1.0, -3.0);
+        let view = crate::math::look_at_lh(&eye, &crate::math::zeros(), &crate::math::up());
+        let [red, green, blue] = material.base_color;
+        PreviewUniform {
+            view_projection: projection.matrix(1.0) * view,
+            model: crate::math::rotation(angle, &crate::math::up()),
+            base_color: [red, green, blue, material.metallic.clamp(0.0, 1.0)],
+            params: [material.roughness.clamp(0.0, 1.0), 0.0, 0.0, 0.0],
+            light_direction: [-0.5, 0.8, -0.4, 0.0],
+            eye: [eye.x, eye.y, eye.z, 1.0],
+        }
+    }
+
+    /// Records the rendering of `mesh` into `target` with `uniform`.
+    fn render(
+        &self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &PreviewTarget,
+        mesh: &PreviewMesh,
+        uniform: &PreviewUniform,
+    ) {
+        queue.write_buffer(&target.uniform_buffer, 0, bytemuck::bytes_of(uniform));
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Preview Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(Self::CLEAR_COLOR),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        if mesh.index_count > 0 {
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &target.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..mesh.index_count, 0, 0..1);
+        }
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> wgpu::RenderPipeline {
+        let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Preview Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(PREVIEW_SHADER_SOURCE)),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Preview Pipeline Layout"),
+            bind_group_layouts: &[bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Preview Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: Some("vertex_main"),
+                buffers: &[PreviewVertex::description(
+                    &PreviewVertex::vertex_attributes(),
+                )],
+                compilation_options: Default::default(),
+            },
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: Renderer::DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: Some("fragment_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: Self::FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            multiview: None,
+            cache,
+        })
+    }
+}
@@ -0,0 +1,78 @@
+// The preview shader: a thumbnail of geometry with a material, lit by a single directional
+// light (see `preview.rs`).
+
+struct Uniform {
+    view_projection: mat4x4<f32>,
+    model: mat4x4<f32>,
+    // rgb: base color, a: metallic.
+    base_color: vec4<f32>,
+    // x: roughness.
+    params: vec4<f32>,
+    // xyz: the direction towards the light.
+    light_direction: vec4<f32>,
+    // xyz: the camera position.
+    eye: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> ubo: Uniform;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) color: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) color: vec3<f32>,
+};
+
+@vertex
+fn vertex_main(vert: VertexInput) -> VertexOutput {
+    let world_position = ubo.model * vec4<f32>(vert.position, 1.0);
+
+    var out: VertexOutput;
+    out.position = ubo.view_projection * world_position;
+    out.world_position = world_position.xyz;
+    // The model matrix only rotates, so it transforms normals as well.
+    out.normal = (ubo.model * vec4<f32>(vert.normal, 0.0)).xyz;
+    out.color = vert.color;
+    return out;
+}
+
+const PI: f32 = 3.14159265;
+
+// The light reaching surfaces that face away from the light.
+const AMBIENT: f32 = 0.15;
+
+@fragment
+fn fragment_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let to_light = normalize(ubo.light_direction.xyz);
+    let to_eye = normalize(ubo.eye.xyz - in.world_position);
+    // Open meshes are lit from both sides, whichever way their triangles wind.
+    var normal = normalize(in.normal);
+    if dot(normal, to_eye) < 0.0 {
+        normal = -normal;
+    }
+    let halfway = normalize(to_light + to_eye);
+
+    let base_color = ubo.base_color.rgb * in.color;
+    let metallic = ubo.base_color.a;
+    let roughness = clamp(ubo.params.x, 0.08, 1.0);
+
+    // Normalized Blinn-Phong, with the exponent derived from the roughness.
+    let alpha = roughness * roughness;
+    let shininess = 2.0 / (alpha * alpha) - 2.0;
+    let highlight = (shininess + 8.0) / (8.0 * PI) * pow(max(dot(normal, halfway), 0.0), shininess);
+
+    let f0 = mix(vec3<f32>(0.04), base_color, metallic);
+    let fresnel = f0 + (1.0 - f0) * pow(1.0 - max(dot(halfway, to_eye), 0.0), 5.0);
+
+    let diffuse = base_color * (1.0 - metallic);
+    let direct = (diffuse + fresnel * highlight) * max(dot(normal, to_light), 0.0);
+    let ambient = AMBIENT * mix(base_color, f0, metallic);
+    return vec4<f32>(direct + ambient, 1.0);
+}
@@ -0,0 +1,294 @@
+//! # Depth Probe Module
+//!
+//! The `probe` module reads back what lies under the mouse cursor: the depth value, the world
+//! position it corresponds to, and the id of the object drawn there.
+//!
+//! ## Overview
+//!
+//! - [`DepthProbeSample`] is the result of one readback, shown by the UI next to the cursor.
+//! - [`DepthProbe`] copies the texels under the cursor from the depth and object id textures
+//!   into a tiny buffer and maps it without ever blocking the render thread.
+//!
+//! A readback goes through three stages spread over several frames:
+//!
+//! 1. **`encode_copy`**: Records a copy of the 1x1 region under the cursor into the readback
+//!    buffer, as part of the frame's command encoder.
+//! 2. **`after_submit`**: Once the frame is submitted, starts mapping the readback buffer.
+//! 3. **`poll`**: In a later frame, checks whether the mapping finished, decodes the values,
+//!    and unprojects the depth into a world position.
+//!
+//! While a readback is in flight no new copy is recorded, so at most one frame's worth of
+//! data (8 bytes) is transferred at a time, and the readout lags the cursor by a frame or two.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! probe.set_cursor(Some((x, y)));
+//! probe.poll(&device, &inverse_view_projection);
+//!
+//! // ... render the scene into `depth_texture` and `object_id_texture` ...
+//! probe.encode_copy(&mut encoder, &depth_texture, &object_id_texture, viewport);
+//! queue.submit(std::iter::once(encoder.finish()));
+//! probe.after_submit();
+//!
+//! if let Some(sample) = probe.latest() {
+//!     println!("{} at depth {:?}", sample.object_name(), sample.depth);
+//! }
+//! ```
+
+#[cfg(feature = "scene3d")]
+use crate::crowd::CrowdDemo;
+use crate::readback::Readback;
+use crate::scene::Scene;
+use crate::viewport::Viewport;
+
+/// The object id written where no object was drawn.
+pub const BACKGROUND_OBJECT_ID: u32 = 0;
+
+/// The number of low bits of an object id telling the kind of object drawn, such as
+/// [`Scene::OBJECT_ID`]. The bits above tell objects of the same kind apart, such as the
+/// nodes of the scene.
+pub const OBJECT_KIND_BITS: u32 = 8;
+
+/// What lies under the cursor, as read back from the GPU.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthProbeSample {
+    /// The probed pixel as `(x, y)` in physical pixels, from the top left.
+    pub pixel: (u32, u32),
+
+    /// The value of the depth buffer, from `0.0` (near plane) to `1.0` (far plane).
+    ///
+    /// `None` if the backend cannot copy from depth textures (for example WebGL).
+    pub depth: Option<f32>,
+
+    /// The world position under the cursor, or `None` if nothing was drawn there.
+    pub world_position: Option<crate::math::Vec3>,
+
+    /// The id of the object under the cursor. [`BACKGROUND_OBJECT_ID`] means no object.
+    pub object_id: u32,
+}
+
+impl DepthProbeSample {
+    /// Returns a human-readable name for the object under the cursor.
+    pub fn object_name(&self) -> &'static str {
+        match self.object_id & ((1 << OBJECT_KIND_BITS) - 1) {
+            BACKGROUND_OBJECT_ID => "Background",
+            Scene::OBJECT_ID => Scene::OBJECT_NAME,
+            #[cfg(feature = "scene3d")]
+            CrowdDemo::OBJECT_ID => CrowdDemo::OBJECT_NAME,
+            _ => "Unknown",
+        }
+    }
+}
+
+/// Converts a pixel and its depth value back into a world position.
+///
+/// `size` is the size of the render target in pixels and `inverse_view_projection` the
+/// inverse of the view-projection matrix the pixel was rendered with. The depth is expected in
+/// the `0.0..=1.0` range used by `wgpu`.
+pub fn unproject(
+    inverse_view_projection: &crate::math::Mat4,
+    pixel: (u32, u32),
+    size: (u32, u32),
+    depth: f32,
+) -> crate::math::Vec3 {
+    // Sample at the pixel's center and flip Y, since pixels count downwards while normalized
+    // device coordinates count upwards.
+    let x = (pixel.0 as f32 + 0.5) / size.0 as f32 * 2.0 - 1.0;
+    let y = 1.0 - (pixel.1 as f32 + 0.5) / size.1 as f32 * 2.0;
+    let world = inverse_view_projection * crate::math::vec4(x, y, depth, 1.0);
+    crate::math::xyz(&world) / world.w
+}
+
+/// Projects a world position onto the screen, the inverse of [`unproject`].
+///
+/// Returns the pixel coordinates (from the top left, in pixel units) as `x` and `y`, and the
+/// depth value as `z`, or `None` if the point is behind the camera.
+pub fn project(
+    view_projection: &crate::math::Mat4,
+    point: &crate::math::Vec3,
+    size: (u32, u32),
+) -> Option<crate::math::Vec3> {
+    let clip = view_projection * crate::math::vec4(point.x, point.y, point.z, 1.0);
+    if clip.w <= 0.0 {
+        return None;
+    }
+    let ndc = crate::math::xyz(&clip) / clip.w;
+    Some(crate::math::vec3(
+        (ndc.x + 1.0) * 0.5 * size.0 as f32 - 0.5,
+        (1.0 - ndc.y) * 0.5 * size.1 as f32 - 0.5,
+        ndc.z,
+    ))
+}
+
+/// Reads back the depth and object id under the cursor without stalling the GPU.
+#[derive(Debug)]
+pub struct DepthProbe {
+    /// The buffer the texels are copied into: the depth at offset 0 and the id at offset 4.
+    buffer: wgpu::Buffer,
+
+    /// Whether depth values can be copied, i.e. the depth texture has `COPY_SRC` usage.
+    copy_depth: bool,
+
+    /// The cursor position in physical pixels, or `None` while the probe is off.
+    cursor: Option<(u32, u32)>,
+
+    /// The readback in flight, of the probed pixel and the viewport the scene was drawn into.
+    readback: Readback<((u32, u32), Viewport)>,
+
+    /// The most recent sample, cleared when the cursor is removed.
+    latest: Option<DepthProbeSample>,
+}
+
+impl DepthProbe {
+    /// The offset of the object id in the readback buffer, in bytes.
+    const OBJECT_ID_OFFSET: wgpu::BufferAddress = 4;
+
+    /// Creates a probe. `copy_depth` tells whether the depth texture can be copied from.
+    pub fn new(device: &wgpu::Device, copy_depth: bool) -> Self {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Depth Probe Readback Buffer"),
+            size: 2 * std::mem::size_of::<u32>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            buffer,
+            copy_depth,
+            cursor: None,
+            readback: Readback::default(),
+            latest: None,
+        }
+    }
+
+    /// Sets the probed pixel, or turns the probe off with `None`.
+    pub fn set_cursor(&mut self, cursor: Option<(u32, u32)>) {
+        self.cursor = cursor;
+        if cursor.is_none() {
+            self.latest = None;
+        }
+    }
+
+    /// Returns the most recent sample, if the probe is on and a readback completed.
+    pub fn latest(&self) -> Option<DepthProbeSample> {
+        self.latest
+    }
+
+    /// Finishes a readback in flight, if its buffer has been mapped.
+    ///
+    /// `inverse_view_projection` must be the inverse of the view-projection matrix used for
+    /// the frame the copy was recorded in. This never blocks.
+    pub fn poll(&mut self, device: &wgpu::Device, inverse_view_projection: &crate::math::Mat4) {
+        let result = self.readback.poll(device, |data, copy| {
+            let depth: f32 = bytemuck::pod_read_unaligned(&data[..4]);
+            let object_id: u32 = bytemuck::pod_read_unaligned(&data[4..8]);
+            (depth, object_id, copy)
+        });
+        let (depth, object_id, (pixel, viewport)) = match result {
+            Some(Ok(sample)) => sample,
+            Some(Err(error)) => {
+                log::warn!("Depth probe readback failed: {error}");
+                return;
+            }
+            None => return,
+        };
+
+        // The probe may have been turned off while the readback was in flight.
+        if self.cursor.is_none() {
+            return;
+        }
+
+        let depth = self.copy_depth.then_some(depth);
+        // The depth buffer is cleared to 1.0, so only depths in front of the far plane belong
+        // to drawn geometry. The scene was projected into the viewport, so the pixel is
+        // unprojected relative to it.
+        let world_position = depth.filter(|depth| *depth < 1.0).and_then(|depth| {
+            let local = viewport.to_local(pixel)?;
+            Some(unproject(
+                inverse_view_projection,
+                local,
+                viewport.size(),
+                depth,
+            ))
+        });
+
+        self.latest = Some(DepthProbeSample {
+            pixel,
+            depth,
+            world_position,
+            object_id,
+        });
+    }
+
+    /// Records a copy of the texels under the cursor, unless a readback is still in flight or
+    /// the cursor lies outside the textures.
+    ///
+    /// `depth_texture` and `object_id_texture` must have the same size, and the scene must have
+    /// been drawn into `viewport` of them.
+    pub fn encode_copy(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        depth_texture: &wgpu::Texture,
+        object_id_texture: &wgpu::Texture,
+        viewport: Viewport,
+    ) {
+        let Some(pixel) = self.cursor else {
+            return;
+        };
+        let size = (object_id_texture.width(), object_id_texture.height());
+        if !self.readback.is_idle() || pixel.0 >= size.0 || pixel.1 >= size.1 {
+            return;
+        }
+
+        let texel = wgpu::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        let origin = wgpu::Origin3d {
+            x: pixel.0,
+            y: pixel.1,
+            z: 0,
+        };
+
+        if self.copy_depth {
+            encoder.copy_texture_to_buffer(
+                wgpu::TexelCopyTextureInfo {
+                    texture: depth_texture,
+                    mip_level: 0,
+                    origin,
+                    aspect: wgpu::TextureAspect::DepthOnly,
+                },
+                wgpu::TexelCopyBufferInfo {
+                    buffer: &self.buffer,
+                    layout: wgpu::TexelCopyBufferLayout::default(),
+                },
+                texel,
+            );
+        }
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: object_id_texture,
+                mip_level: 0,
+                origin,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &self.buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: Self::OBJECT_ID_OFFSET,
+                    ..Default::default()
+                },
+            },
+            texel,
+        );
+
+        self.readback.copied(self.buffer.clone(), (pixel, viewport));
+    }
+
+    /// Starts mapping the readback buffer once the frame containing the copy was submitted.
+    pub fn after_submit(&mut self) {
+        self.readback.after_submit();
+    }
+}
@@ -0,0 +1,379 @@
+//! # Quality Module
+//!
+//! The `quality` module holds the frame rate by scaling the renderer's quality settings up and
+//! down as the GPU frame time changes.
+//!
+//! ## Overview
+//!
+//! - [`QualitySetting`] lists the settings the scaler controls, and [`QualityLevels`] holds
+//!   the level of each, from `0` (cheapest) to [`QualitySetting::MAX_LEVEL`], applied with
+//!   `Renderer::set_quality_levels`. The crowd demo's detail (the distance up to which the
+//!   characters use the detailed mesh) and density (the fraction of the characters drawn)
+//!   are the settings with a cost in this renderer. Render scale, shadow resolution, and SSAO
+//!   samples become settings of their own once those passes exist.
+//! - [`AdaptiveQuality`] holds the user's choices, edited in the settings window: whether the
+//!   scaler runs, the frame rate it aims for, and the lowest and highest level of each setting.
+//! - [`FrameTimeHistory`] keeps the latest frame times and returns their percentiles.
+//! - [`QualityScaler`] collects a window of frame times, then compares their 95th percentile
+//!   against the target frame time. It lowers one setting by one level when the frame is too
+//!   slow, and raises one when there is room to spare, logging each decision to the console.
+//!
+//! The scaler has two kinds of hysteresis, so it does not oscillate between two levels:
+//!
+//! - Between 75% and 105% of the target frame time, nothing changes.
+//! - After every change, the history is cleared, and the next decision waits for a full window
+//!   of frames measured with the new levels.
+//!
+//! Settings are lowered in the order of [`QualitySetting::ALL`] and raised in the reverse
+//! order, so the setting lowered last is the first to come back.
+//!
+//! The frame time is the GPU time of the frame where timestamp queries are supported (see
+//! `Renderer::gpu_frame_time`), and the time between frames otherwise. The latter includes
+//! waiting for vertical sync, so without timestamps the scaler never sees room to spare beyond
+//! the display's refresh rate.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut scaler = QualityScaler::default();
+//!
+//! // Once per frame:
+//! let frame_time = renderer.gpu_frame_time().unwrap_or(delta_time);
+//! scaler.update(&settings, frame_time);
+//! renderer.set_quality_levels(scaler.levels());
+//! ```
+
+// Importing `VecDeque` to drop the oldest frame time as a new one arrives.
+use std::collections::VecDeque;
+
+/// A renderer setting the quality scaler steps up and down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualitySetting {
+    /// The distance up to which the crowd's characters use the detailed mesh.
+    CrowdDetail = 0,
+
+    /// The fraction of the crowd's characters drawn.
+    CrowdDensity = 1,
+}
+
+impl QualitySetting {
+    /// Every setting, in the order they are lowered.
+    pub const ALL: [QualitySetting; 2] =
+        [QualitySetting::CrowdDetail, QualitySetting::CrowdDensity];
+
+    /// The highest level of every setting.
+    pub const MAX_LEVEL: u32 = 4;
+
+    /// Returns the name shown in the user interface and the log.
+    pub fn label(self) -> &'static str {
+        match self {
+            QualitySetting::CrowdDetail => "Crowd Detail",
+            QualitySetting::CrowdDensity => "Crowd Density",
+        }
+    }
+}
+
+/// The level of every [`QualitySetting`], from `0` to [`QualitySetting::MAX_LEVEL`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QualityLevels([u32; 2]);
+
+impl QualityLevels {
+    /// The crowd's level of detail distance at each level of `CrowdDetail`.
+    const CROWD_LOD_DISTANCES: [f32; 5] = [0.0, 3.0, 6.0, 10.0, 16.0];
+
+    /// Returns the level of `setting`.
+    pub fn get(&self, setting: QualitySetting) -> u32 {
+        self.0[setting as usize]
+    }
+
+    /// Sets the level of `setting`, clamped to [`QualitySetting::MAX_LEVEL`].
+    pub fn set(&mut self, setting: QualitySetting, level: u32) {
+        self.0[setting as usize] = level.min(QualitySetting::MAX_LEVEL);
+    }
+
+    /// Returns the distance up to which the crowd's characters use the detailed mesh.
+    pub fn crowd_lod_distance(&self) -> f32 {
+        Self::CROWD_LOD_DISTANCES[self.get(QualitySetting::CrowdDetail) as usize]
+    }
+
+    /// Returns the fraction of the crowd's characters drawn, from a fifth to all of them.
+    pub fn crowd_density(&self) -> f32 {
+        (self.get(QualitySetting::CrowdDensity) + 1) as f32 / (QualitySetting::MAX_LEVEL + 1) as f32
+    }
+}
+
+impl Default for QualityLevels {
+    /// Returns the levels the renderer uses without the scaler: the crowd's default level of
+    /// detail distance, with every character drawn.
+    fn default() -> Self {
+        Self([2, QualitySetting::MAX_LEVEL])
+    }
+}
+
+/// The lowest and highest level the scaler may choose for a setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QualityBounds {
+    /// The lowest level.
+    pub min: u32,
+
+    /// The highest level, at least `min`.
+    pub max: u32,
+}
+
+impl QualityBounds {
+    /// Returns `level` moved into the bounds.
+    pub fn clamp(&self, level: u32) -> u32 {
+        level.clamp(self.min, self.max.max(self.min))
+    }
+}
+
+impl Default for QualityBounds {
+    fn default() -> Self {
+        Self {
+            min: 0,
+            max: QualitySetting::MAX_LEVEL,
+        }
+    }
+}
+
+/// The user's settings of the quality scaler.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveQuality {
+    /// Whether the scaler changes the quality levels.
+    pub enabled: bool,
+
+    /// The frame rate the scaler aims for, in frames per second.
+    pub target_fps: f32,
+
+    /// The bounds of each setting, indexed by `QualitySetting as usize`.
+    pub bounds: [QualityBounds; 2],
+}
+
+impl Default for AdaptiveQuality {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_fps: 60.0,
+            bounds: [QualityBounds::default(); 2],
+        }
+    }
+}
+
+impl AdaptiveQuality {
+    /// The lowest and highest target frame rate offered in the settings window.
+    pub const TARGET_FPS_RANGE: (f32, f32) = (15.0, 240.0);
+
+    /// Returns the time a frame may take at the target frame rate.
+    pub fn target_frame_time(&self) -> crate::Duration {
+        let (min_fps, max_fps) = Self::TARGET_FPS_RANGE;
+        crate::Duration::from_secs_f32(1.0 / self.target_fps.clamp(min_fps, max_fps))
+    }
+}
+
+/// The latest frame times, oldest first.
+#[derive(Debug, Clone)]
+pub struct FrameTimeHistory {
+    /// The frame times, at most `capacity` of them.
+    samples: VecDeque<crate::Duration>,
+
+    /// The number of frame times kept.
+    capacity: usize,
+}
+
+impl FrameTimeHistory {
+    /// Creates an empty history keeping the latest `capacity` frame times.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Adds the time of a frame, dropping the oldest one if the history is full.
+    pub fn push(&mut self, frame_time: crate::Duration) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(frame_time);
+    }
+
+    /// Returns the number of frame times in the history.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns the most recently added frame time, or `None` if the history is empty.
+    pub fn latest(&self) -> Option<crate::Duration> {
+        self.samples.back().copied()
+    }
+
+    /// Returns `true` if the history holds no frame times.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns `true` if the history holds `capacity` frame times.
+    pub fn is_full(&self) -> bool {
+        self.samples.len() == self.capacity
+    }
+
+    /// Forgets every frame time.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns the mean frame time, or `None` if the history is empty.
+    pub fn mean(&self) -> Option<crate::Duration> {
+        let count = u32::try_from(self.samples.len())
+            .ok()
+            .filter(|&count| count > 0)?;
+        Some(self.samples.iter().sum::<crate::Duration>() / count)
+    }
+
+    /// Returns the frame time that `fraction` of the frames, from `0.0` to `1.0`, took at
+    /// most, or `None` if the history is empty.
+    pub fn percentile(&self, fraction: f32) -> Option<crate::Duration> {
+        let mut sorted: Vec<_> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let last = sorted.len().checked_sub(1)?;
+        let index = (fraction.clamp(0.0, 1.0) * last as f32).round() as usize;
+        Some(sorted[index])
+    }
+}
+
+/// A change of one setting by the quality scaler.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityDecision {
+    /// The setting that changed.
+    pub setting: QualitySetting,
+
+    /// The level before the change.
+    pub from: u32,
+
+    /// The level after the change.
+    pub to: u32,
+
+    /// The frame time percentile the decision was based on.
+    pub frame_time: crate::Duration,
+
+    /// The target frame time.
+    pub target: crate::Duration,
+}
+
+impl std::fmt::Display for QualityDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (verb, comparison) = if self.to < self.from {
+            ("Lowered", "over")
+        } else {
+            ("Raised", "under")
+        };
+        write!(
+            f,
+            "{verb} {} from {} to {}: 95th percentile frame time {:.1} ms, {comparison} the \
+             {:.1} ms target",
+            self.setting.label(),
+            self.from,
+            self.to,
+            self.frame_time.as_secs_f32() * 1000.0,
+            self.target.as_secs_f32() * 1000.0,
+        )
+    }
+}
+
+/// Steps the quality levels up and down to hold the target frame rate.
+#[derive(Debug, Clone)]
+pub struct QualityScaler {
+    /// The frame times measured with the current levels.
+    history: FrameTimeHistory,
+
+    /// The current level of every setting.
+    levels: QualityLevels,
+}
+
+impl Default for QualityScaler {
+    fn default() -> Self {
+        Self {
+            history: FrameTimeHistory::new(Self::WINDOW),
+            levels: QualityLevels::default(),
+        }
+    }
+}
+
+impl QualityScaler {
+    /// The number of frames each decision is based on.
+    pub const WINDOW: usize = 90;
+
+    /// The percentile of the frame times compared against the target.
+    pub const PERCENTILE: f32 = 0.95;
+
+    /// A setting is lowered when the percentile exceeds this fraction of the target.
+    pub const LOWER_THRESHOLD: f32 = 1.05;
+
+    /// A setting is raised when the percentile is below this fraction of the target.
+    pub const RAISE_THRESHOLD: f32 = 0.75;
+
+    /// Returns the current level of every setting.
+    pub fn levels(&self) -> &QualityLevels {
+        &self.levels
+    }
+
+    /// Adds the time of the latest frame and changes one setting by one level if a full
+    /// window of frames was too slow, or fast enough to afford more.
+    ///
+    /// The levels are first moved into the bounds of `settings`, which the user may have
+    /// changed. Returns the change, which is also logged, if any. While the scaler is
+    /// disabled the levels stay as they are.
+    pub fn update(
+        &mut self,
+        settings: &AdaptiveQuality,
+        frame_time: crate::Duration,
+    ) -> Option<QualityDecision> {
+        if !settings.enabled {
+            self.history.clear();
+            return None;
+        }
+
+        let mut levels = self.levels;
+        for setting in QualitySetting::ALL {
+            let bounds = settings.bounds[setting as usize];
+            levels.set(setting, bounds.clamp(levels.get(setting)));
+        }
+        if levels != self.levels {
+            self.levels = levels;
+            self.history.clear();
+        }
+
+        self.history.push(frame_time);
+        if !self.history.is_full() {
+            return None;
+        }
+
+        let frame_time = self.history.percentile(Self::PERCENTILE)?;
+        let target = settings.target_frame_time();
+        let (setting, to) = if frame_time > target.mul_f32(Self::LOWER_THRESHOLD) {
+            QualitySetting::ALL.into_iter().find_map(|setting| {
+                let level = self.levels.get(setting);
+                (level > settings.bounds[setting as usize].min).then(|| (setting, level - 1))
+            })?
+        } else if frame_time < target.mul_f32(Self::RAISE_THRESHOLD) {
+            QualitySetting::ALL.into_iter().rev().find_map(|setting| {
+                let level = self.levels.get(setting);
+                (level < settings.bounds[setting as usize].max).then_some((setting, level + 1))
+            })?
+        } else {
+            return None;
+        };
+
+        let decision = QualityDecision {
+            setting,
+            from: self.levels.get(setting),
+            to,
+            frame_time,
+            target,
+        };
+        self.levels.set(setting, to);
+        self.history.clear();
+        log::info!("Quality: {decision}");
+        Some(decision)
+    }
+}
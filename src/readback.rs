@@ -0,0 +1,171 @@
+//! # Readback Module
+//!
+//! The `readback` module reads buffers back from the GPU without stalling the render thread,
+//! for the depth probe and picking, the frame and GUI captures, the frame streamer, and the
+//! GPU timer.
+//!
+//! ## Overview
+//!
+//! A [`Readback`] follows a buffer through three stages, which its owner drives every frame:
+//!
+//! 1. **`copied`**: The owner recorded a copy into the buffer, and describes what it copied
+//!    with a value of its own, such as the probed pixel or the layout of a captured frame.
+//! 2. **`after_submit`**: Once the frame containing the copy is submitted, the buffer starts
+//!    mapping.
+//! 3. **`poll`**: In a later frame, once the buffer is mapped, its contents and the copy's
+//!    description are handed to a closure, and the buffer is unmapped again.
+//!
+//! Only one copy is in flight at a time; a readback is idle again once polled, so owners
+//! skip copying while it is not.
+//!
+//! Headless rendering and tests, which may stall, read buffers at once with [`read_blocking`].
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! if readback.is_idle() {
+//!     encoder.copy_texture_to_buffer(/* ... */);
+//!     readback.copied(buffer.clone(), pixel);
+//! }
+//! queue.submit(std::iter::once(encoder.finish()));
+//! readback.after_submit();
+//!
+//! // In a later frame:
+//! if let Some(Ok(id)) = readback.poll(&device, |data, pixel| read_id(data, pixel)) {
+//!     select(id);
+//! }
+//! ```
+
+// Importing `Arc` and `Mutex` to share the result of `map_async` between its callback and
+// the readback.
+use std::sync::{Arc, Mutex};
+
+/// The result reported by the `map_async` callback, if it ran already.
+type MapResult = Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>;
+
+/// Maps `buffer`, which must have `MAP_READ` usage, waiting for the GPU to finish the work
+/// submitted so far, and returns what `read` makes of its contents.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn read_blocking<R>(
+    device: &wgpu::Device,
+    buffer: &wgpu::Buffer,
+    read: impl FnOnce(&[u8]) -> R,
+) -> Result<R, wgpu::BufferAsyncError> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+    let _ = device.poll(wgpu::Maintain::Wait);
+    receiver.recv().unwrap_or(Err(wgpu::BufferAsyncError))?;
+
+    let value = read(&buffer.slice(..).get_mapped_range());
+    buffer.unmap();
+    Ok(value)
+}
+
+/// Where a readback is in its copy/map cycle.
+#[derive(Debug)]
+enum ReadbackState<T> {
+    /// No copy is in flight.
+    Idle,
+
+    /// A copy described by `copy` was recorded into `buffer`, but not submitted yet.
+    Copied { buffer: wgpu::Buffer, copy: T },
+
+    /// `buffer` is being mapped to read the copy described by `copy`.
+    Mapping { buffer: wgpu::Buffer, copy: T },
+}
+
+/// Reads a buffer back from the GPU over a few frames, without blocking.
+///
+/// `T` describes each copy, and is handed back with the buffer's contents.
+#[derive(Debug)]
+pub(crate) struct Readback<T> {
+    /// The stage of the copy in flight.
+    state: ReadbackState<T>,
+
+    /// Filled by the `map_async` callback.
+    map_result: MapResult,
+}
+
+impl<T> Default for Readback<T> {
+    fn default() -> Self {
+        Self {
+            state: ReadbackState::Idle,
+            map_result: Arc::default(),
+        }
+    }
+}
+
+impl<T> Readback<T> {
+    /// Returns `true` if no copy is in flight, so a new one may be recorded.
+    pub(crate) fn is_idle(&self) -> bool {
+        matches!(self.state, ReadbackState::Idle)
+    }
+
+    /// Returns `true` if a copy is being mapped, waiting to be polled.
+    pub(crate) fn is_mapping(&self) -> bool {
+        matches!(self.state, ReadbackState::Mapping { .. })
+    }
+
+    /// Notes that a copy described by `copy` was recorded into `buffer`, which must have
+    /// `MAP_READ` usage. Ignored, and the buffer dropped, while another copy is in flight.
+    pub(crate) fn copied(&mut self, buffer: wgpu::Buffer, copy: T) {
+        if self.is_idle() {
+            self.state = ReadbackState::Copied { buffer, copy };
+        }
+    }
+
+    /// Starts mapping the buffer once the frame containing the copy was submitted.
+    pub(crate) fn after_submit(&mut self) {
+        let ReadbackState::Copied { buffer, copy } =
+            std::mem::replace(&mut self.state, ReadbackState::Idle)
+        else {
+            return;
+        };
+
+        let map_result = self.map_result.clone();
+        buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                *map_result.lock().unwrap_or_else(|error| error.into_inner()) = Some(result);
+            });
+        self.state = ReadbackState::Mapping { buffer, copy };
+    }
+
+    /// Finishes the copy in flight if its buffer has been mapped, returning what `read` makes
+    /// of the buffer's contents and the copy's description. The buffer is unmapped again, and
+    /// the readback is idle.
+    ///
+    /// Returns `None` while no copy is mapping or the mapping has not finished; this never
+    /// blocks.
+    pub(crate) fn poll<R>(
+        &mut self,
+        device: &wgpu::Device,
+        read: impl FnOnce(&[u8], T) -> R,
+    ) -> Option<Result<R, wgpu::BufferAsyncError>> {
+        if !self.is_mapping() {
+            return None;
+        }
+
+        let _ = device.poll(wgpu::Maintain::Poll);
+        let result = self
+            .map_result
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .take()?;
+        let ReadbackState::Mapping { buffer, copy } =
+            std::mem::replace(&mut self.state, ReadbackState::Idle)
+        else {
+            return None;
+        };
+
+        Some(result.map(|()| {
+            let value = read(&buffer.slice(..).get_mapped_range(), copy);
+            buffer.unmap();
+            value
+        }))
+    }
+}
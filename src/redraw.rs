@@ -0,0 +1,295 @@
+//! # Redraw Module
+//!
+//! The `redraw` module decides when the `App` renders frames, so an idle window does not keep
+//! the CPU and GPU busy.
+//!
+//! ## Overview
+//!
+//! - [`RedrawPolicy`] chooses between rendering continuously, only when something changed, or
+//!   at a fixed rate. It is set with `AppConfig::with_redraw_policy` and can be changed in the
+//!   Inspector panel.
+//! - [`RedrawScheduler`] follows the policy. The `App` marks it dirty whenever an event
+//!   arrives or the scene is still changing (the camera gliding to its goal, an animation
+//!   running, or a background task loading), tells it when the GUI asked to be repainted, and
+//!   asks it whether to request a redraw and how long the event loop may wait.
+//!
+//! - [`FramePacer`] optionally delays each frame until just before the next present slot, as
+//!   estimated from the display's refresh rate and the time the latest frame was presented.
+//!   A frame started late handles input that arrived while the event loop slept, so input is
+//!   shown sooner at the cost of idle time. It is enabled with the "Frame Pacing" checkbox in
+//!   the Inspector panel, next to the frame latency that limits the frames queued after it.
+//!
+//! With `OnDemand` and `FixedRate`, the event loop sleeps between frames instead of polling.
+//! The scene's own animation only advances with the frames that are rendered.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! scheduler.set_policy(RedrawPolicy::OnDemand);
+//!
+//! // For every event other than `RedrawRequested`:
+//! scheduler.mark_dirty();
+//!
+//! // After presenting a frame, to pace the next one:
+//! scheduler.end_frame(Instant::now());
+//!
+//! // Before the event loop waits:
+//! if scheduler.wants_redraw(Instant::now()) {
+//!     window.request_redraw();
+//! }
+//! event_loop.set_control_flow(scheduler.control_flow());
+//! ```
+
+use web_time::{Duration, Instant};
+use winit::event_loop::ControlFlow;
+
+// Importing the frame time history, which keeps the latest render times and their percentiles.
+use crate::quality::FrameTimeHistory;
+
+/// When frames are rendered.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RedrawPolicy {
+    /// Render frames back to back, as fast as the present mode allows.
+    #[default]
+    Continuous,
+
+    /// Render a frame only after input, while the scene is changing, or when the GUI asks to
+    /// be repainted, and sleep otherwise.
+    OnDemand,
+
+    /// Render frames at this rate, in hertz, whether anything changed or not. Input is handled
+    /// as it arrives but only shown with the next frame.
+    FixedRate(u32),
+}
+
+impl RedrawPolicy {
+    /// The rate `FixedRate` starts at when it is selected in the GUI, in hertz.
+    pub const DEFAULT_RATE: u32 = 30;
+
+    /// Returns the name shown in the GUI.
+    pub fn label(self) -> &'static str {
+        match self {
+            RedrawPolicy::Continuous => "Continuous",
+            RedrawPolicy::OnDemand => "On Demand",
+            RedrawPolicy::FixedRate(_) => "Fixed Rate",
+        }
+    }
+
+    /// Returns the time between frames of `FixedRate`, or `None` for the other policies. A
+    /// rate of zero is treated as one frame per second.
+    pub fn frame_interval(self) -> Option<Duration> {
+        match self {
+            RedrawPolicy::FixedRate(rate) => Some(Duration::from_secs(1) / rate.max(1)),
+            RedrawPolicy::Continuous | RedrawPolicy::OnDemand => None,
+        }
+    }
+}
+
+/// Delays frames until just before the next present slot, to shorten the time from input to
+/// the frame showing it.
+///
+/// Each frame is expected to take as long as 95% of the latest frames took from
+/// `RedrawScheduler::begin_frame` to `RedrawScheduler::end_frame`, plus
+/// [`FramePacer::SAFETY_MARGIN`]. The next frame starts that long before the slot one refresh
+/// interval after the latest present, or right away if it would not fit. Pacing works best
+/// with a frame latency of one, or a present mode that does not block on vsync, since a
+/// frame waiting for the display counts as render time.
+#[derive(Debug)]
+pub struct FramePacer {
+    /// Whether frames are paced.
+    enabled: bool,
+
+    /// The time between present slots: one refresh of the display.
+    refresh_interval: Duration,
+
+    /// The time the current frame started, if one is being rendered.
+    frame_start: Option<Instant>,
+
+    /// The time the next frame should start at, if it is delayed.
+    wake_at: Option<Instant>,
+
+    /// How long the latest frames took from start to present.
+    render_times: FrameTimeHistory,
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            refresh_interval: Duration::from_secs(1) / Self::DEFAULT_REFRESH_RATE,
+            frame_start: None,
+            wake_at: None,
+            render_times: FrameTimeHistory::new(Self::WINDOW),
+        }
+    }
+}
+
+impl FramePacer {
+    /// The refresh rate assumed until the display reports its own, in hertz.
+    pub const DEFAULT_REFRESH_RATE: u32 = 60;
+
+    /// The time added to the expected render time, so a frame slightly slower than usual still
+    /// makes its slot.
+    pub const SAFETY_MARGIN: Duration = Duration::from_millis(1);
+
+    /// The number of frames the render time is estimated from.
+    pub const WINDOW: usize = 60;
+
+    /// Returns whether frames are paced.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the time the next frame should start at, or `None` if it may start right away.
+    pub fn wake_at(&self) -> Option<Instant> {
+        self.wake_at
+    }
+
+    /// Returns whether the next frame may start at `now`.
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.wake_at.is_none_or(|time| time <= now)
+    }
+
+    /// Returns the time a frame is expected to take from start to present, including the
+    /// safety margin.
+    pub fn budget(&self) -> Duration {
+        self.render_times.percentile(0.95).unwrap_or_default() + Self::SAFETY_MARGIN
+    }
+}
+
+/// Decides when redraws are requested and how long the event loop waits, following a
+/// [`RedrawPolicy`].
+#[derive(Debug, Default)]
+pub struct RedrawScheduler {
+    /// The policy followed.
+    policy: RedrawPolicy,
+
+    /// Whether an event arrived or the scene changed since the last frame started.
+    dirty: bool,
+
+    /// The time the GUI asked to be repainted at, if it did.
+    repaint_at: Option<Instant>,
+
+    /// The time the next frame of `FixedRate` is due, or `None` if it is due now.
+    next_frame: Option<Instant>,
+
+    /// The pacing of frames to the display's present slots.
+    pacer: FramePacer,
+}
+
+impl RedrawScheduler {
+    /// Follows `policy` from now on.
+    pub fn set_policy(&mut self, policy: RedrawPolicy) {
+        if policy != self.policy {
+            self.policy = policy;
+            self.next_frame = None;
+        }
+    }
+
+    /// Returns the policy followed.
+    pub fn policy(&self) -> RedrawPolicy {
+        self.policy
+    }
+
+    /// Paces frames to the display's present slots, or renders them as soon as the policy
+    /// allows.
+    pub fn set_frame_pacing(&mut self, enabled: bool) {
+        self.pacer.enabled = enabled;
+        if !enabled {
+            self.pacer.wake_at = None;
+        }
+    }
+
+    /// Sets the refresh rate of the display frames are paced to, in millihertz as reported by
+    /// winit, or `None` if it is unknown and `FramePacer::DEFAULT_REFRESH_RATE` is assumed.
+    pub fn set_refresh_rate(&mut self, millihertz: Option<u32>) {
+        self.pacer.refresh_interval = match millihertz {
+            Some(millihertz) if millihertz > 0 => {
+                Duration::from_secs_f64(1000.0 / f64::from(millihertz))
+            }
+            _ => Duration::from_secs(1) / FramePacer::DEFAULT_REFRESH_RATE,
+        };
+    }
+
+    /// Returns the pacing of frames to the display's present slots.
+    pub fn pacer(&self) -> &FramePacer {
+        &self.pacer
+    }
+
+    /// Records that something changed which the next frame should show.
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Records that the GUI asked to be repainted `delay` after `now`. A delay of zero repaints
+    /// right away, and `Duration::MAX` (egui's "no repaint") is ignored.
+    pub fn repaint_after(&mut self, now: Instant, delay: Duration) {
+        if delay.is_zero() {
+            self.dirty = true;
+        } else if let Some(time) = now.checked_add(delay) {
+            self.repaint_at = Some(self.repaint_at.map_or(time, |earlier| earlier.min(time)));
+        }
+    }
+
+    /// Starts a frame at `now`: the changes so far are shown by it, and the next frame of
+    /// `FixedRate` is scheduled one interval later.
+    pub fn begin_frame(&mut self, now: Instant) {
+        self.dirty = false;
+        self.repaint_at = None;
+        self.pacer.frame_start = Some(now);
+        self.pacer.wake_at = None;
+        if let Some(interval) = self.policy.frame_interval() {
+            // After a late frame, the following ones do not try to catch up.
+            let next = self.next_frame.unwrap_or(now) + interval;
+            self.next_frame = Some(if next <= now { now + interval } else { next });
+        }
+    }
+
+    /// Ends the frame started by `begin_frame` once it was presented at `now`. With frame
+    /// pacing, the next frame is delayed until just before the following present slot.
+    pub fn end_frame(&mut self, now: Instant) {
+        let Some(start) = self.pacer.frame_start.take() else {
+            return;
+        };
+        self.pacer
+            .render_times
+            .push(now.saturating_duration_since(start));
+        if self.pacer.enabled {
+            let slot = now + self.pacer.refresh_interval;
+            self.pacer.wake_at = slot
+                .checked_sub(self.pacer.budget())
+                .filter(|&wake_at| wake_at > now);
+        }
+    }
+
+    /// Returns whether a redraw should be requested at `now`.
+    pub fn wants_redraw(&self, now: Instant) -> bool {
+        if !self.pacer.is_due(now) {
+            return false;
+        }
+        match self.policy {
+            RedrawPolicy::Continuous => true,
+            RedrawPolicy::OnDemand => self.dirty || self.repaint_at.is_some_and(|time| time <= now),
+            RedrawPolicy::FixedRate(_) => self.next_frame.is_none_or(|time| time <= now),
+        }
+    }
+
+    /// Returns how the event loop waits for the next event: not at all while rendering
+    /// continuously, until the next frame or requested repaint is due, or until an event
+    /// arrives. A frame delayed by frame pacing is waited for as well.
+    pub fn control_flow(&self) -> ControlFlow {
+        let paced = self.pacer.wake_at;
+        let later = |time: Instant| paced.map_or(time, |paced| paced.max(time));
+        let wake_up = match self.policy {
+            RedrawPolicy::Continuous => paced,
+            RedrawPolicy::OnDemand if self.dirty => paced,
+            RedrawPolicy::OnDemand => self.repaint_at.map(later),
+            RedrawPolicy::FixedRate(_) => self.next_frame.map(later).or(paced),
+        };
+        match (self.policy, wake_up) {
+            (_, Some(time)) => ControlFlow::WaitUntil(time),
+            (RedrawPolicy::Continuous, None) => ControlFlow::Poll,
+            (_, None) => ControlFlow::Wait,
+        }
+    }
+}
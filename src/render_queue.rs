@@ -0,0 +1,320 @@
+//! # Render Queue Module
+//!
+//! The `render_queue` module orders the draws of a pass by a packed sort key, so that draws
+//! sharing a pipeline and material are encoded together and the pass changes state as rarely
+//! as possible.
+//!
+//! ## Overview
+//!
+//! - [`SortKey`] packs a draw's pipeline id, material id, and depth into a `u64`, in that order
+//!   of significance. Sorting by the key groups the draws by pipeline, then by material within
+//!   a pipeline, then front to back within a material, which lets the depth test reject hidden
+//!   fragments early.
+//! - [`RenderQueue`] collects the draws of a frame as `(SortKey, T)` pairs, where `T` says what
+//!   to draw, and sorts them before they are encoded.
+//! - [`DrawStats`] counts the pipeline and bind group switches the sorted order needs, next to
+//!   the switches the order the draws were queued in would have needed.
+//!
+//! Pipeline ids double as the order of the pipelines in the pass: a pipeline that must come
+//! first, such as a background that ignores the depth buffer, gets the lowest id, and blended
+//! pipelines get the highest ids so they are drawn over the opaque ones.
+//!
+//! While encoding, a draw rebinds its pipeline only if its pipeline id differs from the
+//! previous draw's, and its material only if its pipeline or material id differs. See
+//! [`RenderQueue::draws`].
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! queue.clear();
+//! queue.push(SortKey::new(SKY, 0, 0.0), Draw::Sky);
+//! queue.push(SortKey::new(MESH, material, depth), Draw::Mesh(index));
+//! queue.sort();
+//!
+//! for (draw, change) in queue.draws() {
+//!     if change.pipeline {
+//!         bind_pipeline(&mut render_pass, draw);
+//!     }
+//!     if change.material {
+//!         bind_material(&mut render_pass, draw);
+//!     }
+//!     encode(&mut render_pass, draw);
+//! }
+//! println!("{:?}", queue.stats());
+//! ```
+
+/// A draw's pipeline id, material id, and depth, packed so that comparing keys orders draws
+/// by pipeline, then material, then depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortKey(u64);
+
+impl SortKey {
+    /// The number of bits of the depth, the least significant field.
+    const DEPTH_BITS: u32 = 24;
+
+    /// The number of bits of the material id, between the depth and the pipeline id.
+    const MATERIAL_BITS: u32 = 24;
+
+    /// The largest material id; larger ids are clamped to it.
+    pub const MAX_MATERIAL: u32 = (1 << Self::MATERIAL_BITS) - 1;
+
+    /// Creates a key from a pipeline id, a material id up to [`SortKey::MAX_MATERIAL`], and a
+    /// depth from `0.0` (nearest) to `1.0` (farthest), clamped into that range.
+    pub fn new(pipeline: u16, material: u32, depth: f32) -> Self {
+        let max_depth = (1u64 << Self::DEPTH_BITS) - 1;
+        let depth = (depth.clamp(0.0, 1.0) as f64 * max_depth as f64).round() as u64;
+        let material = u64::from(material.min(Self::MAX_MATERIAL));
+        Self(
+            (u64::from(pipeline) << (Self::MATERIAL_BITS + Self::DEPTH_BITS))
+                | (material << Self::DEPTH_BITS)
+                | depth,
+        )
+    }
+
+    /// Returns the pipeline id.
+    pub fn pipeline(self) -> u16 {
+        (self.0 >> (Self::MATERIAL_BITS + Self::DEPTH_BITS)) as u16
+    }
+
+    /// Returns the material id.
+    pub fn material(self) -> u32 {
+        ((self.0 >> Self::DEPTH_BITS) & u64::from(Self::MAX_MATERIAL)) as u32
+    }
+
+    /// Returns the packed key.
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+}
+
+/// The state a draw must bind before it is encoded, compared to the previous draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateChange {
+    /// Whether the draw uses another pipeline than the previous draw, or is the first one.
+    pub pipeline: bool,
+
+    /// Whether the draw uses another pipeline or material than the previous draw, or is the
+    /// first one. Materials are rebound along with the pipeline.
+    pub material: bool,
+}
+
+impl StateChange {
+    /// Returns what changes from the draw with key `previous`, if any, to the one with `key`.
+    fn between(previous: Option<SortKey>, key: SortKey) -> Self {
+        let pipeline = previous.is_none_or(|previous| previous.pipeline() != key.pipeline());
+        let material =
+            pipeline || previous.is_some_and(|previous| previous.material() != key.material());
+        Self { pipeline, material }
+    }
+}
+
+/// The number of draws in a pass and the state switches between them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DrawStats {
+    /// The number of draws.
+    pub draws: u32,
+
+    /// The pipeline switches in the sorted order, counting the first pipeline.
+    pub pipeline_switches: u32,
+
+    /// The material bind group switches in the sorted order, counting the first material.
+    pub bind_group_switches: u32,
+
+    /// The pipeline switches the draws would have needed in the order they were queued.
+    pub unsorted_pipeline_switches: u32,
+
+    /// The material bind group switches the draws would have needed in the order they were
+    /// queued.
+    pub unsorted_bind_group_switches: u32,
+
+    /// The scene's meshes drawn, being at least partly inside the view frustum. Filled in by
+    /// the renderer, as the queue does not know the meshes.
+    pub meshes_drawn: u32,
+
+    /// The scene's meshes outside the view frustum, whose draws were skipped.
+    pub meshes_culled: u32,
+}
+
+impl DrawStats {
+    /// Counts the draws with `keys`, and the pipeline and material switches between them in
+    /// their order.
+    fn count(keys: impl Iterator<Item = SortKey>) -> (u32, u32, u32) {
+        let mut previous = None;
+        let (mut draws, mut pipelines, mut materials) = (0, 0, 0);
+        for key in keys {
+            let change = StateChange::between(previous, key);
+            draws += 1;
+            pipelines += u32::from(change.pipeline);
+            materials += u32::from(change.material);
+            previous = Some(key);
+        }
+        (draws, pipelines, materials)
+    }
+}
+
+/// The draws of a pass, sorted by their [`SortKey`] before they are encoded.
+#[derive(Debug, Clone)]
+pub struct RenderQueue<T> {
+    /// The queued draws, in the order they were pushed until `sort` is called.
+    draws: Vec<(SortKey, T)>,
+
+    /// The statistics computed by the last `sort`.
+    stats: DrawStats,
+}
+
+impl<T> Default for RenderQueue<T> {
+    fn default() -> Self {
+        Self {
+            draws: Vec::new(),
+            stats: DrawStats::default(),
+        }
+    }
+}
+
+impl<T: Copy> RenderQueue<T> {
+    /// Removes every draw, keeping the allocation for the next frame.
+    pub fn clear(&mut self) {
+        self.draws.clear();
+    }
+
+    /// Queues `draw` with `key`.
+    pub fn push(&mut self, key: SortKey, draw: T) {
+        self.draws.push((key, draw));
+    }
+
+    /// Returns the number of queued draws.
+    pub fn len(&self) -> usize {
+        self.draws.len()
+    }
+
+    /// Returns `true` if no draws are queued.
+    pub fn is_empty(&self) -> bool {
+        self.draws.is_empty()
+    }
+
+    /// Sorts the draws by their keys, and counts the state switches before and after.
+    ///
+    /// The sort is stable, so draws with equal keys stay in the order they were queued.
+    pub fn sort(&mut self) {
+        let (_, unsorted_pipelines, unsorted_materials) =
+            DrawStats::count(self.draws.iter().map(|(key, _)| *key));
+        self.draws.sort_by_key(|(key, _)| *key);
+        let (draws, pipelines, materials) =
+            DrawStats::count(self.draws.iter().map(|(key, _)| *key));
+        self.stats = DrawStats {
+            draws,
+            pipeline_switches: pipelines,
+            bind_group_switches: materials,
+            unsorted_pipeline_switches: unsorted_pipelines,
+            unsorted_bind_group_switches: unsorted_materials,
+            ..DrawStats::default()
+        };
+    }
+
+    /// Returns the draws in their current order, each with the state it must bind first.
+    pub fn draws(&self) -> impl Iterator<Item = (T, StateChange)> + '_ {
+        let previous = std::iter::once(None).chain(self.draws.iter().map(|(key, _)| Some(*key)));
+        self.draws
+            .iter()
+            .zip(previous)
+            .map(|((key, draw), previous)| (*draw, StateChange::between(previous, *key)))
+    }
+
+    /// Returns the statistics of the draws as of the last `sort`.
+    pub fn stats(&self) -> DrawStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_key_orders_by_pipeline_then_material_then_depth() {
+        let near = SortKey::new(1, 7, 0.25);
+        assert_eq!(near.pipeline(), 1);
+        assert_eq!(near.material(), 7);
+
+        assert!(SortKey::new(0, 9, 1.0) < SortKey::new(1, 0, 0.0));
+        assert!(SortKey::new(1, 6, 1.0) < SortKey::new(1, 7, 0.0));
+        assert!(near < SortKey::new(1, 7, 0.5));
+    }
+
+    #[test]
+    fn sort_key_clamps_material_and_depth() {
+        let key = SortKey::new(2, u32::MAX, 2.0);
+        assert_eq!(key.pipeline(), 2);
+        assert_eq!(key.material(), SortKey::MAX_MATERIAL);
+        assert_eq!(key, SortKey::new(2, SortKey::MAX_MATERIAL, 1.0));
+        assert_eq!(SortKey::new(2, 0, -1.0), SortKey::new(2, 0, 0.0));
+    }
+
+    #[test]
+    fn state_change_rebinds_the_material_with_the_pipeline() {
+        let key = SortKey::new(1, 3, 0.5);
+        let first = StateChange::between(None, key);
+        assert!(first.pipeline && first.material);
+
+        let same = StateChange::between(Some(key), SortKey::new(1, 3, 0.9));
+        assert!(!same.pipeline && !same.material);
+
+        let material = StateChange::between(Some(key), SortKey::new(1, 4, 0.5));
+        assert!(!material.pipeline && material.material);
+
+        let pipeline = StateChange::between(Some(key), SortKey::new(2, 3, 0.5));
+        assert!(pipeline.pipeline && pipeline.material);
+    }
+
+    #[test]
+    fn sorting_groups_draws_and_counts_the_switches() {
+        let mut queue = RenderQueue::default();
+        queue.push(SortKey::new(1, 0, 0.5), 'a');
+        queue.push(SortKey::new(0, 0, 0.0), 'b');
+        queue.push(SortKey::new(1, 1, 0.5), 'c');
+        queue.push(SortKey::new(1, 0, 0.2), 'd');
+        queue.sort();
+
+        let draws: Vec<_> = queue.draws().collect();
+        let order: String = draws.iter().map(|(draw, _)| *draw).collect();
+        assert_eq!(order, "bdac");
+        assert_eq!(
+            draws
+                .iter()
+                .map(|(_, change)| change.pipeline)
+                .collect::<Vec<_>>(),
+            [true, true, false, false]
+        );
+        assert_eq!(
+            draws
+                .iter()
+                .map(|(_, change)| change.material)
+                .collect::<Vec<_>>(),
+            [true, true, false, true]
+        );
+        assert_eq!(
+            queue.stats(),
+            DrawStats {
+                draws: 4,
+                pipeline_switches: 2,
+                bind_group_switches: 3,
+                unsorted_pipeline_switches: 3,
+                unsorted_bind_group_switches: 4,
+                ..DrawStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn sorting_keeps_draws_with_equal_keys_in_queued_order() {
+        let mut queue = RenderQueue::default();
+        for draw in 0..4 {
+            queue.push(SortKey::new(0, 0, 0.0), draw);
+        }
+        queue.sort();
+        assert_eq!(
+            queue.draws().map(|(draw, _)| draw).collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+    }
+}
@@ -4,7 +4,7 @@
 //! which provides the backbone for rendering both graphical 3D content and GUI elements in the application.
 //!
 //! This module integrates the `wgpu` framework for high-performance 3D rendering and the `egui_wgpu` library
-//! for GUI processing. By combining both rendering techniques into a cohesive pipeline, it ensures 
+//! for GUI processing. By combining both rendering techniques into a cohesive pipeline, it ensures
 //! smooth real-time rendering performance and seamless integration of interactive user interfaces with
 //! application-specific 3D scenes.
 //!
@@ -24,18 +24,23 @@
 //!
 //! ## Components
 //!
-//! - **`Renderer` Struct**: 
+//! - **`Renderer` Struct**:
 //!   - Orchestrates the rendering process by maintaining all graphical resources and executing rendering
 //!     commands during the application's runtime.
 //!   - Fields include GPU resources (`Gpu`), depth textures, `egui` renderer, and the `Scene` object.
 //!
-//! - **Constants**: 
+//! - **Constants**:
 //!   - `DEPTH_FORMAT`: Defines the texture format for the depth buffer, ensuring proper depth testing for 3D scenes.
 //!
-//! - **Methods**: 
+//! - **Methods**:
 //!   - `new`: Initializes the renderer and allocates necessary GPU resources.
 //!   - `resize`: Rescales the rendering resources when the window or surface size changes.
 //!   - `render_frame`: Coordinates 3D and GUI rendering into a single, composite frame for display.
+//!   - `tick_scene`: Advances the scene's animation by the fixed ticks the `App` accumulated,
+//!     and sets how far past the last tick the next frames draw it.
+//!   - `new_headless` and `render_to_buffer`: Render the scene into an offscreen texture and read
+//!     its pixels back, without any window or event loop. `render_to_buffer_after` advances the
+//!     animations first, for frame sequences rendered at a fixed timestep.
 //!
 //! ## Usage
 //!
@@ -53,7 +58,7 @@
 //!     let (window, width, height) = initialize_window();
 //!
 //!     // Create the Renderer
-//!     let mut renderer = Renderer::new(&window, width, height).await.unwrap();
+//!     let mut renderer = Renderer::new(&window, width, height).await?;
 //!
 //!     // Main render loop
 //!     loop {
@@ -65,19 +70,171 @@
 //!         });
 //!
 //!         // Update and render the frame
-//!         renderer.render_frame();
+//!         renderer.render_frame()?;
 //!     }
 //! }
 //! ```
 
-// Imports the `Gpu` struct from the `gpu` module, which provides GPU-related resources 
-// and utilities necessary for managing the rendering process in the `Renderer`.
-use crate::gpu::Gpu;
+// Imports the `Gpu` struct from the `gpu` module, which provides GPU-related resources
+// and utilities necessary for managing the rendering process in the `Renderer`, and the
+// `UploadBelt` streaming the per-frame buffer writes.
+use crate::gpu::{Gpu, UploadBelt};
 
 // Brings the `Scene` struct into scope, which represents a 3D scene containing
 // the model, buffers, and rendering pipeline configuration. It is used within
 // the `Renderer` to manage the 3D content and transformations during rendering.
-use crate::scene::Scene;
+use crate::scene::{MeshData, PendingShader, Rasterization, RenderMode, Scene};
+
+// Importing the scene graph types, to add, remove, and query the scene's nodes.
+use crate::scene_graph::{MeshHandle, Node, NodeId, SceneGraph};
+
+// Importing the per-instance data of the scene's meshes, and the demo drawing a grid of them.
+use crate::instancing::{grid_instances, GridDemo, Instance};
+
+// Importing the error returned when the Shader Editor's source cannot replace the scene's shader.
+use crate::shader_editor::ShaderError;
+
+// Importing the handler catching GPU errors in scopes around rendering and resource creation.
+use crate::gpu_errors::{GpuErrorHandler, GpuErrorRecord};
+
+// Importing the texture the scene's vertex colors are multiplied with.
+use crate::texture::{Texture, TextureError};
+
+// Imports the crate-wide `RenderError`, returned by renderer creation and frame rendering
+// instead of panicking so the application can apply its error policy.
+use crate::error::RenderError;
+
+// Importing `GpuCapabilities`, which lists the optional device features requested by default
+// and records the ones that were granted.
+use crate::capabilities::{GpuCapabilities, GpuInfo};
+
+// Importing the renderer options chosen by the application, passed on to the GPU setup.
+use crate::config::RendererConfig;
+
+// Importing the simulation thread, which advances the scene's animation off the render thread
+// on native platforms and hands the results over through a triple buffer.
+#[cfg(not(target_arch = "wasm32"))]
+use crate::simulation::SimulationThread;
+
+// Importing the fixed-step simulation and the seeded RNG, which make determinism mode
+// reproducible.
+#[cfg(feature = "scene3d")]
+use crate::determinism::DeterministicRng;
+use crate::determinism::TickLog;
+use crate::simulation::FixedStepSimulation;
+
+// Importing the pipeline cache, which persists compiled pipelines between runs.
+use crate::pipeline_cache::{PipelineCacheError, PipelineCacheStore};
+
+// Importing the depth probe, which reads back the depth and object id under the cursor.
+use crate::probe::{DepthProbe, DepthProbeSample};
+
+// Importing the picker, which reads back the object under a clicked pixel.
+use crate::picking::Picker;
+
+// Importing the selection outline, which highlights the selected node.
+use crate::outline::SelectionOutline;
+
+// Importing the selected node's transforms, which the transform gizmo edits.
+use crate::gizmo::GizmoTarget;
+
+// Importing the GPU timer, which measures the GPU time of frames with timestamp queries.
+use crate::gpu_timer::{GpuTimer, TimedPass};
+
+// Importing the CPU profiler, which records how long the parts of a frame take on the CPU.
+use crate::cpu_profiler::CpuProfiler;
+
+// Importing the frame statistics, which summarize the frame times shown in their overlay.
+use crate::frame_stats::{FrameStats, FrameStatsSummary};
+
+// Importing the quality levels, which scale the cost of the scene to hold the frame rate.
+use crate::quality::QualityLevels;
+
+// Importing the deletion queue, which keeps replaced resources alive while frames use them.
+use crate::deletion_queue::{DeletionQueue, Retired};
+
+// Importing the depth binding, which exposes the scene's depth to passes after the scene pass.
+use crate::depth_binding::{DepthBinding, DepthPass, DepthPassFrame};
+
+// Importing the render layers, which decide what the camera draws.
+use crate::layers::LayerMasks;
+
+// Importing the render queue, which orders the scene pass's draws to minimize state changes.
+use crate::render_queue::{DrawStats, RenderQueue, SortKey, StateChange};
+// Importing the wide line renderer, used for debug drawing and the ground grid.
+use crate::lines::{grid_lines, LineRenderer};
+// Importing the infinite ground grid and world axes, drawn with their own unlit pipelines.
+use crate::debug_draw::DebugDraw;
+// Importing the text labels drawn at points of the scene, such as the names of its nodes.
+use crate::labels::{Label, LabelId, LabelRenderer};
+
+// Importing the debug views and the overdraw heatmap implementing one of them.
+use crate::debug_view::DebugView;
+#[cfg(feature = "profiling")]
+use crate::overdraw::OverdrawView;
+
+// Importing the crowd demo, drawn in the scene pass while it is enabled.
+#[cfg(feature = "scene3d")]
+use crate::crowd::CrowdDemo;
+
+// Importing the GPU particle demo, simulated in a compute pass before the scene pass draws it.
+#[cfg(feature = "scene3d")]
+use crate::particles::ParticleDemo;
+
+// Importing the environment settings and the sky drawn behind the scene.
+use crate::environment::Environment;
+#[cfg(feature = "scene3d")]
+use crate::environment::SkyRenderer;
+
+// Importing the directional light the scene is shaded with.
+use crate::lighting::Light;
+
+// Importing the post-processing pass, which displays the scene's HDR target on the surface.
+use crate::post_process::{PostEffects, PostProcess};
+
+// Importing the viewport, which letterboxes the scene to a locked aspect ratio.
+use crate::viewport::{AspectLock, Viewport};
+
+// Importing the projection and bounds, which fit the clip planes to the scene's content.
+use crate::camera::{Bounds, Projection};
+
+// Importing the frame capture, which saves screenshots of rendered frames as PNGs.
+#[cfg(not(target_arch = "wasm32"))]
+use crate::frame_capture::{straight_rgba, CaptureLayout};
+use crate::frame_capture::{FrameCapture, FrameCaptureError};
+
+// Importing the GUI capture, which renders the GUI alone into a transparent PNG.
+#[cfg(feature = "gui")]
+use crate::ui_capture::{UiCapture, UiCaptureError};
+
+// Importing the scene panel, which shows the scene inside the GUI instead of behind it.
+#[cfg(feature = "gui")]
+use crate::scene_panel::ScenePanel;
+
+// Importing the preview renderer, which renders the thumbnails of the Inspector and Assets panels.
+#[cfg(feature = "gui")]
+use crate::preview::{PreviewGeometry, PreviewRenderer};
+
+// Importing the materials the scene's meshes are shaded with.
+use crate::material::{Material, TextureHandle};
+
+// Importing the background scheduler, which time-slices long compute jobs across frames.
+use crate::background::{BackgroundBudget, BackgroundJob, BackgroundScheduler, TaskProgress};
+
+// Importing the upload scheduler, which streams large uploads in chunks across frames.
+use crate::upload::UploadScheduler;
+
+// Importing the shared frame, which exports rendered frames to other processes.
+#[cfg(feature = "interop")]
+use crate::interop::{InteropError, SharedFrame};
+
+// Importing the frame streamer, which serves the rendered frames over HTTP.
+#[cfg(not(target_arch = "wasm32"))]
+use crate::streaming::{FrameStreamer, StreamError};
+
+// Importing the surfaces of the windows `egui` viewports are detached into.
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+use crate::viewports::ViewportSurface;
 
 /// The `Renderer` struct is responsible for rendering the application's graphical content,
 /// including the 3D scene and GUI, using the `wgpu` and `egui_wgpu` frameworks.
@@ -130,6 +287,189 @@ pub struct Renderer {
     /// needed when the window or surface size changes.
     depth_texture_view: wgpu::TextureView,
 
+    /// The depth texture behind `depth_texture_view`, copied from by the depth probe.
+    depth_texture: wgpu::Texture,
+
+    /// The object id texture the scene writes the id of each drawn object into.
+    object_id_texture: wgpu::Texture,
+
+    /// The view of `object_id_texture` used as the scene pass's second color attachment.
+    object_id_texture_view: wgpu::TextureView,
+
+    /// Reads back the depth and object id under the cursor while the probe is enabled.
+    depth_probe: DepthProbe,
+
+    /// Reads back the object id under the last clicked pixel, to select the node there.
+    picker: Picker,
+
+    /// The scene's node selected by the last pick, or `None` if the background was picked.
+    selected_node: Option<NodeId>,
+
+    /// Outlines `selected_node` on the HDR scene.
+    selection_outline: SelectionOutline,
+
+    /// Measures the GPU time of frames, or `None` if the adapter has no timestamp queries.
+    gpu_timer: Option<GpuTimer>,
+
+    /// Whether the next frame is captured in the attached graphics debugger, requested with
+    /// `trigger_capture`.
+    gpu_capture_requested: bool,
+
+    /// Records the uniform updates and the command submission of each frame while enabled.
+    /// Replaced with the `App`'s profiler through `set_cpu_profiler`.
+    cpu_profiler: CpuProfiler,
+
+    /// The recent frame times, recorded by every `render_frame`.
+    frame_stats: FrameStats,
+
+    /// Binds `depth_texture` for sampling, or `None` if the adapter cannot sample it.
+    depth_binding: Option<DepthBinding>,
+
+    /// Releases replaced resources, such as the render targets after a resize, once the frames
+    /// using them have finished on the GPU.
+    deletion_queue: DeletionQueue,
+
+    /// The scene shader of the last `set_scene_shader`, until the backend validated it.
+    pending_shader: Option<PendingShader>,
+
+    /// Streams the per-frame buffer writes, such as the nodes' uniforms and the lines, through
+    /// reused staging buffers, submitted ahead of each frame.
+    upload_belt: UploadBelt,
+
+    /// Records the GPU errors of rendering and resource creation instead of panicking, for the
+    /// GPU Errors window.
+    gpu_errors: GpuErrorHandler,
+
+    /// The passes sampling the scene's depth, encoded after the scene pass every frame.
+    depth_passes: Vec<Box<dyn DepthPass>>,
+
+    /// The pipeline cache every pipeline is compiled through, saved to disk after warm-up.
+    pipeline_cache: PipelineCacheStore,
+
+    /// Captures rendered frames into PNGs when requested.
+    frame_capture: FrameCapture,
+
+    /// Captures the GUI over a transparent background when requested.
+    #[cfg(feature = "gui")]
+    ui_capture: UiCapture,
+
+    /// The texture the scene is shown from inside the GUI, while it is shown in a panel. The
+    /// scene's render targets then have the panel's size rather than the surface's.
+    #[cfg(feature = "gui")]
+    scene_panel: Option<ScenePanel>,
+
+    /// Renders the material and mesh thumbnails shown in the Inspector and Assets panels.
+    #[cfg(feature = "gui")]
+    previews: PreviewRenderer,
+
+    /// The surfaces of the windows showing `egui` viewports, whose GUI is drawn with the
+    /// `egui_renderer` of the main window.
+    #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+    viewports: std::collections::HashMap<egui::ViewportId, ViewportSurface>,
+
+    /// Runs a slice of the queued background compute jobs after every frame.
+    background: BackgroundScheduler,
+
+    /// Writes a chunk of the queued uploads at the start of every frame.
+    uploads: UploadScheduler,
+
+    /// The shared texture every frame is copied into, while frames are exported.
+    #[cfg(feature = "interop")]
+    shared_frame: Option<SharedFrame>,
+
+    /// Reads back and serves the rendered frames over HTTP, while streaming.
+    #[cfg(not(target_arch = "wasm32"))]
+    streamer: Option<FrameStreamer>,
+
+    /// Draws wide, anti-aliased lines queued for the current frame, such as debug lines and
+    /// the ground grid.
+    lines: LineRenderer,
+
+    /// Whether the ground grid is queued on the `lines` every frame.
+    grid_visible: bool,
+
+    /// Draws the infinite ground grid and the world axes.
+    debug_draw: DebugDraw,
+
+    /// Whether the `debug_draw` grid and axes are drawn.
+    world_grid_visible: bool,
+
+    /// Draws the scene's labels, and the names of its nodes while `node_labels_visible`.
+    labels: LabelRenderer,
+
+    /// Whether every named node of the scene is labeled with its name.
+    node_labels_visible: bool,
+
+    /// The layers the camera draws, and the layers of the scene, the crowd demo, and the
+    /// `lines`. Objects on none of the camera's layers are skipped in the scene pass.
+    layer_masks: LayerMasks,
+
+    /// The levels of the settings scaling the cost of the scene, such as the crowd's detail.
+    quality_levels: QualityLevels,
+
+    /// The draws of the scene pass, queued and sorted every frame.
+    render_queue: RenderQueue<SceneDraw>,
+
+    /// How the scene is drawn, for example as an overdraw heatmap.
+    debug_view: DebugView,
+
+    /// The resources of the overdraw heatmap, used while `debug_view` is `Overdraw`.
+    #[cfg(feature = "profiling")]
+    overdraw: OverdrawView,
+
+    /// The skinned crowd demo, created when it is first enabled.
+    #[cfg(feature = "scene3d")]
+    crowd: Option<CrowdDemo>,
+
+    /// Set once the crowd demo was requested on an adapter that cannot run it, so the
+    /// warning is only logged once.
+    #[cfg(feature = "scene3d")]
+    crowd_unsupported: bool,
+
+    /// The GPU particle demo, created when it is first enabled.
+    #[cfg(feature = "scene3d")]
+    particles: Option<ParticleDemo>,
+
+    /// Set once the particle demo was requested on an adapter that cannot run it, so the
+    /// warning is only logged once.
+    #[cfg(feature = "scene3d")]
+    particles_unsupported: bool,
+
+    /// How the instancing demo draws its grid, set with [`Renderer::set_grid_demo`].
+    grid_demo: GridDemo,
+
+    /// The nodes added for [`GridDemo::PerDrawUniforms`], removed when the mode changes.
+    grid_demo_nodes: Vec<NodeId>,
+
+    /// The aspect ratio the scene's viewport is locked to, if any.
+    aspect_lock: AspectLock,
+
+    /// Whether the surface is composited over the desktop with per-pixel alpha. The scene is
+    /// then drawn over a transparent background instead of the sky.
+    transparent: bool,
+
+    /// The color the surface is cleared to, shown behind the scene where no sky is drawn.
+    clear_color: wgpu::Color,
+
+    /// The global environment, used unless the scene overrides it.
+    environment: Environment,
+
+    /// The directional light the scene is shaded with.
+    light: Light,
+
+    /// The HDR target the scene is drawn into, and the pass displaying it on the surface.
+    post_process: PostProcess,
+
+    /// The effects the post-processing pass applies.
+    post_effects: PostEffects,
+
+    /// The projection set by the application, fitted to the content every frame.
+    projection: Projection,
+
+    /// Draws the environment's sky behind the scene.
+    #[cfg(feature = "scene3d")]
+    sky: SkyRenderer,
+
     /// A renderer instance for rendering GUI elements created with `egui`.
     ///
     /// This component is responsible for translating `egui`'s GUI
@@ -144,6 +484,7 @@ pub struct Renderer {
     ///
     /// This field is initialized during the creation of the `Renderer`
     /// struct and updated dynamically to reflect GUI state changes.
+    #[cfg(feature = "gui")]
     egui_renderer: egui_wgpu::Renderer,
 
     /// The application's 3D scene, responsible for managing objects, transformations,
@@ -160,6 +501,29 @@ pub struct Renderer {
     /// The scene is updated during the rendering process (`render_frame`), where it processes
     /// changes in the application's state or interactions initiated by the user.
     scene: Scene,
+
+    /// The thread simulating the scene on native platforms.
+    ///
+    /// The scene's animation runs on this thread at a fixed tick rate, and each frame draws
+    /// the state between the two most recent snapshots it produced. WebAssembly and headless
+    /// renderers have no such thread and advance the scene with `tick_scene` instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    simulation: SimulationThread,
+
+    /// The seed of determinism mode, or `None` when it is off.
+    determinism_seed: Option<u64>,
+
+    /// The render thread's fixed-step simulation in determinism mode, used when there is no
+    /// simulation thread.
+    fixed_step: Option<FixedStepSimulation>,
+
+    /// The fraction of a tick passed since the last of `tick_scene`, from `0.0` to `1.0`, by
+    /// which the scene is drawn between its last two ticks on the render thread.
+    tick_alpha: f32,
+
+    /// Whether rendering is suspended because the renderer was resized to a width or height of
+    /// zero, as when the window is minimized. Frames are skipped until it is resized again.
+    suspended: bool,
 }
 
 /// Implementation of the `Renderer` struct, which provides methods for managing
@@ -206,6 +570,23 @@ impl Renderer {
     /// depth calculations, such as rendering large, complex 3D environments.
     pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 
+    /// The default background color behind the scene.
+    pub const CLEAR_COLOR: wgpu::Color = wgpu::Color {
+        r: 0.19,
+        g: 0.24,
+        b: 0.42,
+        a: 1.0,
+    };
+
+    /// The number of ground grid cells on either side of the origin.
+    pub(crate) const GRID_CELLS: u32 = 10;
+
+    /// The size of a ground grid cell, in world units.
+    pub(crate) const GRID_SPACING: f32 = 0.5;
+
+    /// The height of the ground grid, just below the scene's triangle.
+    pub(crate) const GRID_HEIGHT: f32 = -1.0;
+
     /// Creates a new instance of the `Renderer` struct, initializing all required components.
     ///
     /// # Parameters
@@ -229,20 +610,92 @@ impl Renderer {
     /// # Example
     ///
     /// ```rust
-    /// let renderer = Renderer::new(window, 800, 600).await;
+    /// let renderer = Renderer::new(window, 800, 600).await?;
     /// ```
     ///
     /// In this example, a `Renderer` is initialized with a window surface and initial dimensions.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// This function may panic if GPU resources or the rendering environment cannot
+    /// Returns a `RenderError` if GPU resources or the rendering environment cannot
     /// be properly initialized.
     pub async fn new(
         window: impl Into<wgpu::SurfaceTarget<'static>>,
         width: u32,
         height: u32,
-    ) -> Self {
+    ) -> Result<Self, RenderError> {
+        Self::new_with_config(window, width, height, false, RendererConfig::default()).await
+    }
+
+    /// Creates a new `Renderer` using a fallback (typically software) adapter.
+    ///
+    /// This is a degraded mode used when no hardware adapter is available, for example on
+    /// virtual machines or CI runners without GPU drivers. Apart from the adapter selection
+    /// it behaves exactly like [`Renderer::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if no fallback adapter is available either, or if the
+    /// rendering environment cannot otherwise be initialized.
+    pub async fn new_with_fallback_adapter(
+        window: impl Into<wgpu::SurfaceTarget<'static>>,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, RenderError> {
+        Self::new_with_config(window, width, height, true, RendererConfig::default()).await
+    }
+
+    /// Creates a new headless `Renderer`, which renders into an offscreen texture of
+    /// `width` x `height` pixels instead of a window's surface.
+    ///
+    /// No window or event loop is needed: frames are rendered with `render_to_buffer`, which
+    /// returns their pixels. If no hardware adapter is available, a fallback adapter is used.
+    ///
+    /// The frames are not rendered in real time, so the scene's animation has no simulation
+    /// thread and only advances with `tick_scene`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if no adapter is available, or if the rendering environment
+    /// cannot otherwise be initialized.
+    pub async fn new_headless(width: u32, height: u32) -> Result<Self, RenderError> {
+        let features = GpuCapabilities::OPTIONAL_FEATURES;
+        let gpu = match Gpu::new_headless_async(width, height, false, features).await {
+            Err(RenderError::NoAdapter) => {
+                Gpu::new_headless_async(width, height, true, features).await?
+            }
+            result => result?,
+        };
+        #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+        let mut renderer = Self::from_gpu(gpu, width, height);
+        #[cfg(not(target_arch = "wasm32"))]
+        renderer.simulation.stop();
+        Ok(renderer)
+    }
+
+    /// Creates a new `Renderer` picking its adapter from the backends of `config`, and
+    /// presenting with its present mode or the nearest one the surface supports.
+    ///
+    /// With `force_fallback_adapter`, a fallback adapter is used as in
+    /// [`Renderer::new_with_fallback_adapter`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if no adapter of the configured backends is available, or if the
+    /// rendering environment cannot otherwise be initialized.
+    pub async fn new_with_config(
+        window: impl Into<wgpu::SurfaceTarget<'static>>,
+        width: u32,
+        height: u32,
+        force_fallback_adapter: bool,
+        config: RendererConfig,
+    ) -> Result<Self, RenderError> {
+        // A window created minimized has no area, and neither a surface nor the depth and
+        // object id targets can have a size of zero. They start at the smallest size instead,
+        // with rendering suspended until the window is resized.
+        let suspended = width == 0 || height == 0;
+        let (width, height) = (width.max(1), height.max(1));
+
         // The GPU instance, responsible for managing the device, queue, and other
         // rendering-related resources required for interacting with the graphics hardware.
         //
@@ -254,8 +707,24 @@ impl Renderer {
         // The `Gpu` struct is a key component for all rendering operations, abstracting
         // low-level GPU interactions and providing higher-level methods for resource creation
         // and management.
-        let gpu = Gpu::new_async(window, width, height).await;
+        let gpu = Gpu::new_async(
+            window,
+            width,
+            height,
+            force_fallback_adapter,
+            GpuCapabilities::OPTIONAL_FEATURES,
+            config,
+        )
+        .await?;
 
+        let mut renderer = Self::from_gpu(gpu, width, height);
+        renderer.suspended = suspended;
+        Ok(renderer)
+    }
+
+    /// Creates the rendering components on top of `gpu`, whose surface or offscreen target is
+    /// `width` x `height` pixels.
+    fn from_gpu(gpu: Gpu, width: u32, height: u32) -> Self {
         // The texture view for the depth buffer used during 3D rendering.
         //
         // This texture view represents a depth buffer that is used to store depth
@@ -270,7 +739,31 @@ impl Renderer {
         //
         // This resource is critical for 3D rendering and is used as part of the
         // pipeline configuration.
-        let depth_texture_view = gpu.create_depth_texture(width, height);
+        let (depth_texture, depth_texture_view) = gpu.create_depth_target(width, height);
+
+        let (object_id_texture, object_id_texture_view) =
+            gpu.create_object_id_target(width, height, Scene::OBJECT_ID_FORMAT);
+
+        let depth_probe = DepthProbe::new(
+            &gpu.device,
+            gpu.depth_texture_usages
+                .contains(wgpu::TextureUsages::COPY_SRC),
+        );
+
+        let gpu_timer = gpu
+            .capabilities
+            .supports_timestamp_queries()
+            .then(|| GpuTimer::new(&gpu.device, &gpu.queue));
+
+        let depth_binding = gpu
+            .depth_texture_usages
+            .contains(wgpu::TextureUsages::TEXTURE_BINDING)
+            .then(|| DepthBinding::new(&gpu.device, &depth_texture));
+
+        // Errors raised outside of the scopes around rendering and resizing are recorded too,
+        // rather than panicking as `wgpu` does by default.
+        let gpu_errors = GpuErrorHandler::default();
+        gpu_errors.capture_uncaptured(&gpu.device);
 
         // The `egui_renderer` is used to render the GUI elements within the application.
         //
@@ -289,7 +782,8 @@ impl Renderer {
         //
         // This renderer is essential for applications with graphical interfaces, providing a bridge
         // between the interactive GUI and the underlying rendering engine.
-        let egui_renderer = egui_wgpu::Renderer::new(
+        #[cfg(feature = "gui")]
+        let mut egui_renderer = egui_wgpu::Renderer::new(
             &gpu.device,
             gpu.surface_config.format,
             Some(Self::DEPTH_FORMAT),
@@ -314,13 +808,150 @@ impl Renderer {
         // The `scene` is updated and rendered as part of the rendering loop, reacting
         // to user input, animations, or external state to create an interactive and
         // dynamic 3D experience.
-        let scene = Scene::new(&gpu.device, gpu.surface_format);
+        //
+        // Creating the scene and the passes below compiles all of the renderer's pipelines up
+        // front, as a warm-up step, through the pipeline cache saved by the previous run. The
+        // cache is saved again once they are compiled.
+        let mut pipeline_cache = PipelineCacheStore::open(&gpu.device, &gpu.adapter.get_info());
+        let cache = pipeline_cache.cache();
+        let warm_up_start = web_time::Instant::now();
+
+        // The scene pass draws into the HDR target, which the post-processing pass then
+        // displays on the surface.
+        let post_process = PostProcess::new(&gpu.device, gpu.surface_format, width, height, cache);
+
+        let scene = Scene::new(&gpu.device, &gpu.queue, PostProcess::HDR_FORMAT, cache);
+
+        let selection_outline = SelectionOutline::new(&gpu.device, &object_id_texture_view, cache);
+        let picker = Picker::new(&gpu.device);
+
+        let lines = LineRenderer::new(&gpu.device, PostProcess::HDR_FORMAT, cache);
+        let debug_draw = DebugDraw::new(
+            &gpu.device,
+            PostProcess::HDR_FORMAT,
+            Self::GRID_HEIGHT,
+            Self::GRID_SPACING,
+            cache,
+        );
+        let labels = LabelRenderer::new(&gpu.device, &gpu.queue, PostProcess::HDR_FORMAT, cache);
+        let upload_belt = UploadBelt::new(&gpu.device);
+
+        #[cfg(feature = "scene3d")]
+        let sky = SkyRenderer::new(&gpu.device, PostProcess::HDR_FORMAT, &scene, cache);
+
+        #[cfg(feature = "profiling")]
+        let overdraw = OverdrawView::new(
+            &gpu.device,
+            gpu.surface_format,
+            &scene,
+            width,
+            height,
+            cache,
+        );
+
+        #[cfg(feature = "gui")]
+        let previews = PreviewRenderer::new(
+            &gpu.device,
+            &mut egui_renderer,
+            Self::preview_meshes(),
+            cache,
+        );
+
+        log::info!(
+            "Compiled pipelines in {:.0?} ({} pipeline cache)",
+            warm_up_start.elapsed(),
+            match (cache.is_some(), pipeline_cache.is_warm()) {
+                (false, _) => "no",
+                (true, false) => "cold",
+                (true, true) => "warm",
+            },
+        );
+        if let Err(error) = pipeline_cache.save() {
+            log::warn!("{error}");
+        }
+
+        // On native platforms, the scene's animation is simulated on a dedicated thread
+        // starting from the initial transform of the scene's graph.
+        #[cfg(not(target_arch = "wasm32"))]
+        let simulation = SimulationThread::spawn(scene.graph().transform);
 
         Self {
             gpu,
             depth_texture_view,
+            depth_texture,
+            object_id_texture,
+            object_id_texture_view,
+            depth_probe,
+            picker,
+            selected_node: None,
+            selection_outline,
+            gpu_timer,
+            gpu_capture_requested: false,
+            cpu_profiler: CpuProfiler::default(),
+            frame_stats: FrameStats::default(),
+            depth_binding,
+            deletion_queue: DeletionQueue::default(),
+            pending_shader: None,
+            upload_belt,
+            gpu_errors,
+            depth_passes: Vec::new(),
+            pipeline_cache,
+            frame_capture: FrameCapture::default(),
+            #[cfg(feature = "gui")]
+            ui_capture: UiCapture::default(),
+            #[cfg(feature = "gui")]
+            scene_panel: None,
+            #[cfg(feature = "gui")]
+            previews,
+            #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+            viewports: std::collections::HashMap::new(),
+            background: BackgroundScheduler::default(),
+            uploads: UploadScheduler::default(),
+            #[cfg(feature = "interop")]
+            shared_frame: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            streamer: None,
+            lines,
+            grid_visible: false,
+            debug_draw,
+            world_grid_visible: false,
+            labels,
+            node_labels_visible: false,
+            layer_masks: LayerMasks::default(),
+            quality_levels: QualityLevels::default(),
+            render_queue: RenderQueue::default(),
+            debug_view: DebugView::default(),
+            #[cfg(feature = "profiling")]
+            overdraw,
+            #[cfg(feature = "scene3d")]
+            crowd: None,
+            #[cfg(feature = "scene3d")]
+            crowd_unsupported: false,
+            #[cfg(feature = "scene3d")]
+            particles: None,
+            #[cfg(feature = "scene3d")]
+            particles_unsupported: false,
+            grid_demo: GridDemo::Off,
+            grid_demo_nodes: Vec::new(),
+            aspect_lock: AspectLock::default(),
+            transparent: false,
+            clear_color: Self::CLEAR_COLOR,
+            environment: Environment::default(),
+            light: Light::default(),
+            post_process,
+            post_effects: PostEffects::default(),
+            projection: Projection::default(),
+            #[cfg(feature = "scene3d")]
+            sky,
+            #[cfg(feature = "gui")]
             egui_renderer,
             scene,
+            #[cfg(not(target_arch = "wasm32"))]
+            simulation,
+            determinism_seed: None,
+            fixed_step: None,
+            tick_alpha: 1.0,
+            suspended: false,
         }
     }
 
@@ -338,6 +969,10 @@ impl Renderer {
     /// proper rendering by keeping the depth buffer and other components in sync
     /// with the current surface size.
     ///
+    /// Resizing to the current size does nothing: neither the surface nor the targets are
+    /// recreated. Resizing to a width or height of zero, as reported for a minimized window,
+    /// suspends rendering instead, keeping the current size until the next resize.
+    ///
     /// This function is typically called within the application when the user
     /// resizes the window, ensuring that the renderer properly updates to fit
     /// the new size.
@@ -351,89 +986,1849 @@ impl Renderer {
     /// In this example, the renderer resizes its internal GPU resources to accommodate
     /// the updated surface dimensions.
     pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            self.suspended = true;
+            return;
+        }
+        self.suspended = false;
+        let config = &self.gpu.surface_config;
+        if (config.width, config.height) == (width, height) {
+            return;
+        }
+        self.gpu_errors.begin(&self.gpu.device);
+        let offscreen_target = self.gpu.offscreen_target.take();
         self.gpu.resize(width, height);
-        self.depth_texture_view = self.gpu.create_depth_texture(width, height);
+        // The old targets may still be drawn into by frames in flight.
+        if let Some(offscreen_target) = offscreen_target {
+            self.deletion_queue.retire(offscreen_target);
+        }
+        // In a panel, the scene keeps the panel's size, and only the GUI's depth follows the
+        // surface.
+        #[cfg(feature = "gui")]
+        if let Some(panel) = self.scene_panel.as_mut() {
+            panel.resize_surface(&self.gpu, width, height, &mut self.deletion_queue);
+        }
+        let (scene_width, scene_height) = self.scene_size();
+        self.resize_scene_targets(scene_width, scene_height);
+        #[cfg(feature = "interop")]
+        if self.shared_frame.is_some() {
+            self.shared_frame = None;
+            if let Err(error) = self.export_frames() {
+                log::warn!("Stopped exporting frames: {error}");
+            }
+        }
+        self.gpu_errors.end(&self.gpu.device, "resize");
     }
 
-    /// Renders a single frame, combining 3D scene rendering and `egui` GUI rendering.
+    /// Returns the size of the scene's render targets: the size of the scene panel while the
+    /// scene is shown in one, and the surface's otherwise.
+    fn scene_size(&self) -> (u32, u32) {
+        #[cfg(feature = "gui")]
+        if let Some(panel) = &self.scene_panel {
+            return panel.rect().size();
+        }
+        (
+            self.gpu.surface_config.width,
+            self.gpu.surface_config.height,
+        )
+    }
+
+    /// Recreates the scene's render targets for `width` x `height` pixels, unless they already
+    /// have that size.
+    fn resize_scene_targets(&mut self, width: u32, height: u32) {
+        if (self.depth_texture.width(), self.depth_texture.height()) == (width, height) {
+            return;
+        }
+        let (depth_texture, depth_texture_view) = self.gpu.create_depth_target(width, height);
+        let (object_id_texture, object_id_texture_view) =
+            self.gpu
+                .create_object_id_target(width, height, Scene::OBJECT_ID_FORMAT);
+        let queue = &mut self.deletion_queue;
+        queue.retire(std::mem::replace(
+            &mut self.depth_texture_view,
+            depth_texture_view,
+        ));
+        queue.retire(std::mem::replace(&mut self.depth_texture, depth_texture));
+        queue.retire(std::mem::replace(
+            &mut self.object_id_texture_view,
+            object_id_texture_view,
+        ));
+        queue.retire(std::mem::replace(
+            &mut self.object_id_texture,
+            object_id_texture,
+        ));
+        if let Some(depth_binding) = &mut self.depth_binding {
+            depth_binding.update(&self.gpu.device, &self.depth_texture, queue);
+        }
+        self.selection_outline
+            .resize(&self.gpu.device, &self.object_id_texture_view, queue);
+        self.post_process
+            .resize(&self.gpu.device, width, height, queue);
+        #[cfg(feature = "profiling")]
+        self.overdraw.resize(&self.gpu.device, width, height);
+    }
+
+    /// Shows the scene inside the GUI, in a panel covering `rect` of the surface in physical
+    /// pixels, or behind the GUI again with `None`.
     ///
-    /// # Parameters
+    /// While the panel is shown, the scene is rendered into a texture of its size, which the
+    /// GUI shows with the id returned by `scene_panel_texture`.
+    #[cfg(feature = "gui")]
+    pub fn set_scene_panel(&mut self, rect: Option<Viewport>) {
+        let rect = rect.map(|rect| {
+            let max = self.gpu.device.limits().max_texture_dimension_2d;
+            Viewport {
+                width: rect.width.clamp(1, max),
+                height: rect.height.clamp(1, max),
+                ..rect
+            }
+        });
+        let surface_size = (
+            self.gpu.surface_config.width,
+            self.gpu.surface_config.height,
+        );
+        match (rect, self.scene_panel.as_mut()) {
+            (None, None) => return,
+            (None, Some(_)) => {
+                if let Some(panel) = self.scene_panel.take() {
+                    panel.release(&mut self.egui_renderer, &mut self.deletion_queue);
+                }
+            }
+            (Some(rect), None) => {
+                self.scene_panel = Some(ScenePanel::new(
+                    &self.gpu,
+                    &mut self.egui_renderer,
+                    rect,
+                    surface_size,
+                ));
+            }
+            (Some(rect), Some(panel)) => {
+                let resized = panel.set_rect(
+                    &self.gpu,
+                    &mut self.egui_renderer,
+                    rect,
+                    &mut self.deletion_queue,
+                );
+                if !resized {
+                    return;
+                }
+            }
+        }
+        let (width, height) = self.scene_size();
+        self.resize_scene_targets(width, height);
+    }
+
+    /// Returns the texture the GUI shows the scene with, while it is shown in a panel.
+    #[cfg(feature = "gui")]
+    pub fn scene_panel_texture(&self) -> Option<egui::TextureId> {
+        self.scene_panel.as_ref().map(ScenePanel::texture_id)
+    }
+
+    /// Returns whether rendering is suspended because the renderer was last resized to a width
+    /// or height of zero.
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Reconfigures the surface after its capabilities may have changed.
     ///
-    /// - `screen_descriptor`: Describes the screen properties, such as size and DPI,
-    ///   which are required by the `egui` renderer to render correctly.
-    /// - `paint_jobs`: A collection of `egui` primitives (draw instructions)
-    ///   to be rendered on the screen.
-    /// - `textures_delta`: Contains information about added or removed textures
-    ///   that are used by `egui` for rendering.
-    /// - `delta_time`: The time elapsed since the last frame, which is used
-    ///   for animations and time-dependent logic in the 3D scene.
+    /// See `Gpu::reconfigure_surface`: an unsupported present mode is replaced with the nearest
+    /// supported one, and the replacement is reported through `take_validation_warnings`.
     ///
-    /// # Details
+    /// # Errors
     ///
-    /// This function coordinates the rendering of the GUI and the 3D scene. It updates
-    /// the scene based on the elapsed time, updates `egui` textures, and manages the
-    /// GPU command encoder for rendering to the surface. It performs the following steps:
+    /// Returns `RenderError::IncompatibleSurface` if the surface can no longer be presented to.
+    pub fn reconfigure_surface(&mut self) -> Result<(), crate::error::RenderError> {
+        self.gpu.reconfigure_surface()
+    }
+
+    /// Draws lines and labels for a window with `scale_factor` physical pixels per logical
+    /// pixel, so their widths and sizes, given in logical pixels, look the same on any display.
+    /// The labels' glyph atlas is rasterized again if the scale factor changed enough.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.lines.set_scale_factor(scale_factor);
+        self.labels
+            .set_scale_factor(&self.gpu.device, &self.gpu.queue, scale_factor);
+    }
+
+    /// Drops the surface while the window cannot be presented to, keeping the device and all
+    /// GPU resources. See `Gpu::suspend_surface`.
+    pub fn suspend_surface(&mut self) {
+        self.gpu.suspend_surface();
+    }
+
+    /// Creates the surface for `window` again after `suspend_surface`, and resizes the render
+    /// targets if the window's size changed meanwhile.
     ///
-    /// 1. Updates the 3D scene's state based on the `delta_time` to account for animations
-    ///    or any other time-dependent behavior.
-    /// 2. Updates and synchronizes `egui` textures (loading or freeing them as needed).
-    /// 3. Executes `egui`'s paint jobs and integrates them with the rendered output.
-    /// 4. Renders the frame to the surface, using a render pass that includes a color attachment
-    ///    for the main scene and a depth attachment for proper depth-testing.
+    /// # Errors
     ///
-    /// # Panics
+    /// Returns a `RenderError` if the new surface cannot be created or configured.
+    pub fn resume_surface(
+        &mut self,
+        window: std::sync::Arc<winit::window::Window>,
+    ) -> Result<(), crate::error::RenderError> {
+        let size = window.inner_size();
+        self.gpu.resume_surface(window)?;
+        let config = &self.gpu.surface_config;
+        if (size.width, size.height) != (config.width, config.height) {
+            self.resize(size.width, size.height);
+        }
+        Ok(())
+    }
+
+    /// Starts exporting every rendered frame into a shared texture, which other processes can
+    /// read through the handle returned by `shared_frame`.
     ///
-    /// This function will panic if the process of retrieving the surface texture fails. This
-    /// can occur if the surface becomes invalid (e.g., if the window is resized or destroyed).
+    /// # Errors
     ///
-    /// # Example
+    /// Returns an `InteropError` if the platform, device, or surface cannot export frames.
+    #[cfg(feature = "interop")]
+    pub fn export_frames(&mut self) -> Result<(), InteropError> {
+        let config = &self.gpu.surface_config;
+        if !config.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(InteropError::SurfaceNotCopyable);
+        }
+        let frame = SharedFrame::new(&self.gpu.device, config.width, config.height, config.format)?;
+        self.shared_frame = Some(frame);
+        Ok(())
+    }
+
+    /// Stops exporting frames, releasing the shared texture.
+    #[cfg(feature = "interop")]
+    pub fn stop_exporting_frames(&mut self) {
+        self.shared_frame = None;
+    }
+
+    /// Returns the shared texture the frames are exported into, while they are exported.
     ///
-    /// ```rust
-    /// renderer.render_frame(
-    ///     screen_descriptor,
-    ///     paint_jobs,
-    ///     textures_delta,
-    ///     delta_time,
-    /// );
-    /// ```
+    /// The texture, and with it the handle, is replaced when the surface is resized.
+    #[cfg(feature = "interop")]
+    pub fn shared_frame(&self) -> Option<&SharedFrame> {
+        self.shared_frame.as_ref()
+    }
+
+    /// Starts serving the rendered frames over HTTP on `address`, and returns the address the
+    /// server listens on. Streaming again replaces the previous server.
     ///
-    /// In this example, the function is called to render one frame, using the provided
-    /// `egui` render data, screen dimensions, and the time elapsed since the previous frame.
-    pub fn render_frame(
+    /// # Errors
+    ///
+    /// Returns a `StreamError` if the surface textures cannot be read back, or if the server
+    /// cannot listen on `address`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_streaming(
         &mut self,
-        screen_descriptor: egui_wgpu::ScreenDescriptor,
-        paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
-        textures_delta: egui::TexturesDelta,
-        delta_time: crate::Duration,
-    ) {
-        // The elapsed time since the last frame, in seconds, represented as a 32-bit floating-point number.
-        // This variable is used to update the state of the 3D scene, animations, and other
-        // time-dependent logic within the render pipeline.
-        //
-        // `delta_time` facilitates smooth animations and transitions by allowing computations
-        // to take the elapsed time into account, ensuring consistent behavior regardless of frame rate.
-        let delta_time = delta_time.as_secs_f32();
+        address: std::net::SocketAddr,
+    ) -> Result<std::net::SocketAddr, StreamError> {
+        let config = &self.gpu.surface_config;
+        if !config.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(StreamError::SurfaceNotCopyable);
+        }
+        self.streamer = None;
+        let streamer = FrameStreamer::start(address, config.format)?;
+        let local_addr = streamer.local_addr();
+        self.streamer = Some(streamer);
+        Ok(local_addr)
+    }
 
-        self.scene
-            .update(&self.gpu.queue, self.gpu.aspect_ratio(), delta_time);
+    /// Stops serving the rendered frames, disconnecting all viewers.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn stop_streaming(&mut self) {
+        self.streamer = None;
+    }
 
-        // This loop iterates over all texture changes in the `textures_delta.set` map,
-        // where `id` is the unique identifier for a texture and `image_delta` describes
-        // the changes to be applied to that texture. For each entry, it updates
-        // the corresponding texture in the `egui_renderer` using the provided
-        // `gpu.device` and `gpu.queue`. This ensures that `egui` textures are
-        // synchronized with changes made to them.
-        for (id, image_delta) in &textures_delta.set {
-            self.egui_renderer
-                .update_texture(&self.gpu.device, &self.gpu.queue, *id, image_delta);
-        }
+    /// Sets the pixel probed by the depth probe, or turns the probe off with `None`.
+    ///
+    /// `cursor` is in physical pixels from the top left of the surface. While the scene is
+    /// shown in a panel, cursors outside of it probe nothing.
+    pub fn set_depth_probe_cursor(&mut self, cursor: Option<(u32, u32)>) {
+        #[cfg(feature = "gui")]
+        let cursor = match &self.scene_panel {
+            Some(panel) => cursor.and_then(|cursor| panel.rect().to_local(cursor)),
+            None => cursor,
+        };
+        self.depth_probe.set_cursor(cursor);
+    }
 
-        // Iterate through all texture updates specified in the `textures_delta.set` map.
-        // Each entry in the map consists of a texture `id` (a unique identifier) and
-        // an `image_delta` (describing how the texture should be updated). The `egui_renderer`
-        // is then instructed to update these textures using the provided `gpu.device` and
+    /// Returns what the depth probe most recently read back under the cursor.
+    pub fn depth_probe_sample(&self) -> Option<DepthProbeSample> {
+        self.depth_probe.latest()
+    }
+
+    /// Captures the next rendered frame in the graphics debugger the application was launched
+    /// from, such as RenderDoc, with its debug groups and labeled resources.
+    ///
+    /// Without an attached debugger, or on backends without capture support such as WebGPU,
+    /// nothing is captured.
+    pub fn trigger_capture(&mut self) {
+        self.gpu_capture_requested = true;
+    }
+
+    /// Captures the next rendered frame, as shown in the window, into a PNG. On the web, the
+    /// browser downloads it.
+    ///
+    /// The capture finishes a few frames later; its result is returned by
+    /// `take_frame_capture`. It fails if the surface textures cannot be copied.
+    pub fn capture_frame(&mut self) {
+        let copyable = self
+            .gpu
+            .surface_config
+            .usage
+            .contains(wgpu::TextureUsages::COPY_SRC);
+        self.frame_capture.request(copyable);
+    }
+
+    /// Returns where the last screenshot was saved, or why it failed, once it finished.
+    pub fn take_frame_capture(&mut self) -> Option<Result<String, FrameCaptureError>> {
+        self.frame_capture.take_finished()
+    }
+
+    /// Renders a frame into the offscreen target of a headless renderer and returns its
+    /// pixels: straight RGBA with 8 bits per channel, row by row from the top.
+    ///
+    /// The frame is rendered like `render_frame` would, with no elapsed time and without any
+    /// GUI, and the call blocks until its pixels are read back.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RenderError::NotHeadless` if the renderer was created for a window, or
+    /// `RenderError::Readback` if the pixels cannot be read back.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_to_buffer(&mut self) -> Result<Vec<u8>, RenderError> {
+        self.render_to_buffer_after(crate::Duration::ZERO)
+    }
+
+    /// Renders a frame like `render_to_buffer`, after advancing the animations by `elapsed`,
+    /// such as one step of a fixed timestep when rendering a frame sequence. The scene's own
+    /// animation advances with `tick_scene` instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `render_to_buffer`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_to_buffer_after(
+        &mut self,
+        elapsed: crate::Duration,
+    ) -> Result<Vec<u8>, RenderError> {
+        let Some(texture) = self.gpu.offscreen_target.clone() else {
+            return Err(RenderError::NotHeadless);
+        };
+        let config = &self.gpu.surface_config;
+        let layout = CaptureLayout::new(config.width, config.height, config.format)
+            .expect("the offscreen target has an 8-bit RGBA format");
+
+        self.render_frame(
+            #[cfg(feature = "gui")]
+            egui_wgpu::ScreenDescriptor {
+                size_in_pixels: [layout.width, layout.height],
+                pixels_per_point: 1.0,
+            },
+            #[cfg(feature = "gui")]
+            Vec::new(),
+            #[cfg(feature = "gui")]
+            egui::TexturesDelta::default(),
+            elapsed,
+        )?;
+
+        let buffer = self.gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Headless Readback Buffer"),
+            size: (layout.padded_bytes_per_row * layout.height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self
+            .gpu
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Headless Readback Encoder"),
+            });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(layout.padded_bytes_per_row),
+                    rows_per_image: Some(layout.height),
+                },
+            },
+            texture.size(),
+        );
+        self.gpu.queue.submit(std::iter::once(encoder.finish()));
+
+        let pixels = crate::readback::read_blocking(&self.gpu.device, &buffer, |data| {
+            straight_rgba(data, layout)
+        })?;
+        Ok(pixels)
+    }
+
+    /// Captures the GUI of the next rendered frame into a PNG with alpha, without the scene.
+    ///
+    /// The capture finishes a few frames later; its result is returned by `take_ui_capture`.
+    #[cfg(feature = "gui")]
+    pub fn capture_ui(&mut self) {
+        self.ui_capture.request();
+    }
+
+    /// Returns where the last GUI capture was saved, or why it failed, once it finished.
+    #[cfg(feature = "gui")]
+    pub fn take_ui_capture(&mut self) -> Option<Result<String, UiCaptureError>> {
+        self.ui_capture.take_finished()
+    }
+
+    /// Returns the meshes previewed in the Assets panel: the scene's geometry and, with the
+    /// `scene3d` feature, the crowd's character.
+    #[cfg(feature = "gui")]
+    fn preview_meshes() -> Vec<(&'static str, PreviewGeometry)> {
+        let positions: Vec<_> = crate::VERTICES
+            .iter()
+            .map(crate::Vertex::position)
+            .collect();
+        let colors: Vec<_> = crate::VERTICES.iter().map(crate::Vertex::color).collect();
+        #[cfg_attr(not(feature = "scene3d"), allow(unused_mut))]
+        let mut meshes = vec![(
+            "Triangle",
+            PreviewGeometry::from_triangles(&positions, &colors, &crate::INDICES),
+        )];
+        #[cfg(feature = "scene3d")]
+        meshes.push(("Character", crate::crowd::character_preview()));
+        meshes
+    }
+
+    /// Renders the previews whose content changed since they were last rendered: the material
+    /// preview when `material` was edited, and the mesh preview named `turntable`, which turns
+    /// by `delta_time` seconds.
+    #[cfg(feature = "gui")]
+    pub fn update_previews(
+        &mut self,
+        material: &Material,
+        turntable: Option<&str>,
+        delta_time: f32,
+    ) {
+        self.previews.update(
+            &self.gpu.device,
+            &self.gpu.queue,
+            material,
+            turntable,
+            delta_time,
+        );
+    }
+
+    /// Returns the texture of the material preview, drawn by `egui`.
+    #[cfg(feature = "gui")]
+    pub fn material_preview(&self) -> egui::TextureId {
+        self.previews.material_texture()
+    }
+
+    /// Returns the name and texture of each mesh preview, drawn by `egui`.
+    #[cfg(feature = "gui")]
+    pub fn mesh_previews(&self) -> Vec<(&'static str, egui::TextureId)> {
+        self.previews.mesh_textures()
+    }
+
+    /// Starts rendering the `egui` viewport `id` into a surface created for `window`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if the window's surface cannot be created, or cannot be
+    /// presented to in the format of the main window's surface.
+    #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+    pub fn add_viewport(
+        &mut self,
+        id: egui::ViewportId,
+        window: std::sync::Arc<winit::window::Window>,
+    ) -> Result<(), RenderError> {
+        let surface = ViewportSurface::new(&self.gpu, window)?;
+        self.viewports.insert(id, surface);
+        Ok(())
+    }
+
+    /// Resizes the surface of viewport `id`.
+    #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+    pub fn resize_viewport(&mut self, id: egui::ViewportId, width: u32, height: u32) {
+        if let Some(surface) = self.viewports.get_mut(&id) {
+            surface.resize(&self.gpu, width, height);
+        }
+    }
+
+    /// Stops rendering viewport `id`, dropping its surface.
+    #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+    pub fn remove_viewport(&mut self, id: egui::ViewportId) {
+        self.viewports.remove(&id);
+    }
+
+    /// Renders the GUI of viewport `id` into its surface, over the clear color, and presents
+    /// it. The scene is only drawn into the main window.
+    ///
+    /// The texture updates are applied even if the viewport is unknown or has no area, since
+    /// the `egui_renderer` and its textures are shared with the main window.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RenderError::Surface` if the next texture of the surface cannot be acquired.
+    #[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+    pub fn render_viewport(
+        &mut self,
+        id: egui::ViewportId,
+        screen_descriptor: egui_wgpu::ScreenDescriptor,
+        paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
+        textures_delta: egui::TexturesDelta,
+    ) -> Result<(), RenderError> {
+        for (texture_id, image_delta) in &textures_delta.set {
+            self.egui_renderer.update_texture(
+                &self.gpu.device,
+                &self.gpu.queue,
+                *texture_id,
+                image_delta,
+            );
+        }
+
+        let mut result = Ok(());
+        let surface = self.viewports.get(&id);
+        if let Some(surface) = surface.filter(|_| !screen_descriptor.size_in_pixels.contains(&0)) {
+            self.gpu_errors.begin(&self.gpu.device);
+            result = surface
+                .acquire()
+                .map_err(RenderError::from)
+                .map(|surface_texture| {
+                    let view = surface_texture
+                        .texture
+                        .create_view(&wgpu::TextureViewDescriptor::default());
+                    let mut encoder =
+                        self.gpu
+                            .device
+                            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                                label: Some("Viewport Encoder"),
+                            });
+                    self.egui_renderer.update_buffers(
+                        &self.gpu.device,
+                        &self.gpu.queue,
+                        &mut encoder,
+                        &paint_jobs,
+                        &screen_descriptor,
+                    );
+                    let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: Some("Viewport Render Pass"),
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view: &view,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(self.clear_color),
+                                store: wgpu::StoreOp::Store,
+                            },
+                        })],
+                        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                            view: surface.depth_texture_view(),
+                            depth_ops: Some(wgpu::Operations {
+                                load: wgpu::LoadOp::Clear(1.0),
+                                store: wgpu::StoreOp::Store,
+                            }),
+                            stencil_ops: None,
+                        }),
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    });
+                    self.egui_renderer.render(
+                        &mut render_pass.forget_lifetime(),
+                        &paint_jobs,
+                        &screen_descriptor,
+                    );
+                    self.gpu.queue.submit(std::iter::once(encoder.finish()));
+                    surface_texture.present();
+                });
+            self.gpu_errors.end(&self.gpu.device, "render viewport");
+        }
+
+        for texture_id in &textures_delta.free {
+            self.egui_renderer.free_texture(texture_id);
+        }
+        result
+    }
+
+    /// Shows or hides the ground grid.
+    pub fn set_grid_visible(&mut self, visible: bool) {
+        self.grid_visible = visible;
+    }
+
+    /// Shows or hides the infinite ground grid and the world axes, drawn with the helpers.
+    pub fn set_world_grid_visible(&mut self, visible: bool) {
+        self.world_grid_visible = visible;
+    }
+
+    /// Shows or hides a label with the name of each of the scene's nodes, drawn with the
+    /// helpers on top of the scene.
+    pub fn set_node_labels_visible(&mut self, visible: bool) {
+        self.node_labels_visible = visible;
+    }
+
+    /// Sets the layers the camera draws and the layers of each object.
+    pub fn set_layer_masks(&mut self, masks: &LayerMasks) {
+        self.layer_masks = *masks;
+    }
+
+    /// Sets the levels of the settings scaling the cost of the scene.
+    pub fn set_quality_levels(&mut self, levels: &QualityLevels) {
+        self.quality_levels = *levels;
+    }
+
+    /// Returns the number of draws in the most recent scene pass, the pipeline and bind group
+    /// switches between them with and without sorting, and the scene's meshes drawn and culled
+    /// against the view frustum.
+    pub fn draw_stats(&self) -> DrawStats {
+        let culling = self.scene.cull_stats();
+        DrawStats {
+            meshes_drawn: culling.drawn,
+            meshes_culled: culling.culled,
+            ..self.render_queue.stats()
+        }
+    }
+
+    /// Returns the GPU time of the most recently measured frame, from the beginning of its
+    /// first timed pass to the end of its last, or `None` if the adapter has no timestamp
+    /// queries.
+    pub fn gpu_frame_time(&self) -> Option<crate::Duration> {
+        self.gpu_timer.as_ref().and_then(GpuTimer::latest)
+    }
+
+    /// Summarizes the recent frame times and the GPU time of each pass, shown in the frame
+    /// statistics overlay.
+    pub fn frame_stats(&self) -> FrameStatsSummary {
+        self.frame_stats.summary()
+    }
+
+    /// Records the scopes of the following frames with `profiler`, shared with the `App`.
+    pub fn set_cpu_profiler(&mut self, profiler: CpuProfiler) {
+        self.cpu_profiler = profiler;
+    }
+
+    /// Selects how the scene is drawn.
+    pub fn set_debug_view(&mut self, debug_view: DebugView) {
+        self.debug_view = debug_view;
+    }
+
+    /// Selects the render mode and depth test the scene is drawn with. Render modes the device
+    /// does not support fall back to [`RenderMode::Filled`].
+    pub fn set_rasterization(&mut self, rasterization: Rasterization) {
+        self.scene.set_rasterization(rasterization);
+    }
+
+    /// Returns the render modes the device supports, in the order they are offered in the
+    /// user interface.
+    pub fn render_modes(&self) -> Vec<RenderMode> {
+        RenderMode::ALL
+            .into_iter()
+            .filter(|mode| self.scene.supports_render_mode(*mode))
+            .collect()
+    }
+
+    /// Locks the scene's viewport to an aspect ratio, leaving bars around it, or unlocks it.
+    pub fn set_aspect_lock(&mut self, aspect_lock: AspectLock) {
+        self.aspect_lock = aspect_lock;
+    }
+
+    /// Presents frames with `present_mode`, trading latency against tearing.
+    ///
+    /// The surface is only reconfigured when the mode changes. An unsupported mode is replaced
+    /// with the nearest supported one, returned by `present_mode`, and reported as a
+    /// validation warning.
+    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) {
+        if present_mode != self.gpu.surface_config.present_mode {
+            self.gpu.set_present_mode(present_mode);
+        }
+    }
+
+    /// Returns the present mode frames are presented with.
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.gpu.surface_config.present_mode
+    }
+
+    /// Returns the present modes the surface supports.
+    pub fn present_modes(&self) -> Vec<wgpu::PresentMode> {
+        self.gpu.present_modes.clone()
+    }
+
+    /// Lets at most `latency` frames be queued for presentation, at least one.
+    ///
+    /// The surface is only reconfigured when the latency changes.
+    pub fn set_frame_latency(&mut self, latency: u32) {
+        if latency.max(1) != self.gpu.surface_config.desired_maximum_frame_latency {
+            self.gpu.set_frame_latency(latency);
+        }
+    }
+
+    /// Returns the number of frames that may be queued for presentation.
+    pub fn frame_latency(&self) -> u32 {
+        self.gpu.surface_config.desired_maximum_frame_latency
+    }
+
+    /// Adds the time from an input event until the frame showing it was presented to the frame
+    /// statistics.
+    pub fn record_input_latency(&mut self, latency: crate::Duration) {
+        self.frame_stats.record_input_latency(latency);
+    }
+
+    /// Returns the adapter the renderer uses and the features and limits granted to its
+    /// device, so an application can decide which features to use.
+    pub fn gpu_info(&self) -> &GpuInfo {
+        &self.gpu.info
+    }
+
+    /// Makes the surface transparent, showing what lies behind the window wherever neither the
+    /// scene nor the GUI is drawn, or opaque again.
+    ///
+    /// The window must have been created transparent. The surface is only reconfigured when
+    /// the setting changes; unsupported transparency is reported as a validation warning.
+    pub fn set_transparent(&mut self, transparent: bool) {
+        if transparent != self.transparent {
+            self.transparent = transparent;
+            self.gpu.set_transparent(transparent);
+        }
+    }
+
+    /// Sets the color the surface is cleared to, as linear RGBA with unmultiplied alpha.
+    ///
+    /// The color is shown behind the scene while the sky is [`Sky::Backdrop`](crate::Sky),
+    /// and behind the GUI alone. Its alpha is only used in a transparent window, which shows
+    /// the desktop through the background as the alpha falls to zero.
+    pub fn set_clear_color(&mut self, color: wgpu::Color) {
+        self.clear_color = color;
+    }
+
+    /// Returns the color the surface is cleared to.
+    pub fn clear_color(&self) -> wgpu::Color {
+        self.clear_color
+    }
+
+    /// Returns the clear color as written to the surface: opaque unless the surface is
+    /// transparent, and then with its alpha premultiplied unless the compositor expects it
+    /// unmultiplied.
+    fn surface_clear_color(&self) -> wgpu::Color {
+        let wgpu::Color { r, g, b, a } = self.clear_color;
+        let alpha_mode = self.gpu.surface_config.alpha_mode;
+        if self.transparent && alpha_mode == wgpu::CompositeAlphaMode::PostMultiplied {
+            self.clear_color
+        } else if self.transparent {
+            wgpu::Color {
+                r: r * a,
+                g: g * a,
+                b: b * a,
+                a,
+            }
+        } else {
+            wgpu::Color { r, g, b, a: 1.0 }
+        }
+    }
+
+    /// Sets the global environment: ambient light, sky, fog, exposure, and image-based
+    /// lighting.
+    pub fn set_environment(&mut self, environment: &Environment) {
+        self.environment = *environment;
+    }
+
+    /// Sets the scene's own environment, used instead of the global one, or removes it.
+    pub fn set_scene_environment(&mut self, environment: Option<Environment>) {
+        self.scene.environment_override = environment;
+    }
+
+    /// Sets the directional light the scene is shaded with.
+    pub fn set_light(&mut self, light: &Light) {
+        self.light = *light;
+    }
+
+    /// Sets the effects applied to the scene before it is shown: tonemapping, gamma
+    /// correction, and vignette.
+    pub fn set_post_effects(&mut self, effects: &PostEffects) {
+        self.post_effects = *effects;
+    }
+
+    /// Returns the rectangle of the surface the scene is drawn into, within the scene panel
+    /// while the scene is shown in one.
+    pub fn viewport(&self) -> Viewport {
+        let viewport = self.scene_viewport();
+        #[cfg(feature = "gui")]
+        if let Some(panel) = &self.scene_panel {
+            let rect = panel.rect();
+            return Viewport {
+                x: rect.x + viewport.x,
+                y: rect.y + viewport.y,
+                ..viewport
+            };
+        }
+        viewport
+    }
+
+    /// Returns the rectangle of the scene's render targets the scene is drawn into.
+    fn scene_viewport(&self) -> Viewport {
+        Viewport::fit(self.scene_size(), self.aspect_lock)
+    }
+
+    /// Shows or hides the skinned crowd demo.
+    ///
+    /// The demo's resources are created when it is first shown and released when it is hidden.
+    /// On adapters without storage buffers in vertex shaders the demo stays hidden.
+    #[cfg(feature = "scene3d")]
+    pub fn set_crowd_demo_enabled(&mut self, enabled: bool) {
+        if enabled == self.crowd.is_some() || (enabled && self.crowd_unsupported) {
+            return;
+        }
+        if !enabled {
+            if let Some(crowd) = self.crowd.take() {
+                self.deletion_queue.retire(Retired::other(crowd));
+            }
+            return;
+        }
+        if !CrowdDemo::is_supported(
+            &self.gpu.adapter.get_downlevel_capabilities(),
+            &self.gpu.device.limits(),
+        ) {
+            log::warn!("The crowd demo requires storage buffers in vertex shaders");
+            self.crowd_unsupported = true;
+            return;
+        }
+        self.crowd = Some(CrowdDemo::new(
+            &self.gpu.device,
+            PostProcess::HDR_FORMAT,
+            CrowdDemo::DEFAULT_COUNT,
+            self.determinism_seed
+                .unwrap_or(DeterministicRng::DEFAULT_SEED),
+            self.pipeline_cache.cache(),
+        ));
+        if let Err(error) = self.pipeline_cache.save() {
+            log::warn!("{error}");
+        }
+    }
+
+    /// Shows or hides the GPU particle demo.
+    ///
+    /// The demo's resources are created when it is first shown and released when it is hidden.
+    /// On adapters without compute shaders or storage buffers in vertex shaders the demo stays
+    /// hidden.
+    #[cfg(feature = "scene3d")]
+    pub fn set_particle_demo_enabled(&mut self, enabled: bool) {
+        if enabled == self.particles.is_some() || (enabled && self.particles_unsupported) {
+            return;
+        }
+        if !enabled {
+            if let Some(particles) = self.particles.take() {
+                self.deletion_queue.retire(Retired::other(particles));
+            }
+            return;
+        }
+        if !ParticleDemo::is_supported(
+            &self.gpu.capabilities,
+            &self.gpu.adapter.get_downlevel_capabilities(),
+        ) {
+            log::warn!(
+                "The particle demo requires compute shaders and storage buffers in vertex shaders"
+            );
+            self.particles_unsupported = true;
+            return;
+        }
+        self.particles = Some(ParticleDemo::new(
+            &self.gpu,
+            PostProcess::HDR_FORMAT,
+            ParticleDemo::DEFAULT_COUNT,
+            self.determinism_seed
+                .unwrap_or(DeterministicRng::DEFAULT_SEED),
+            self.pipeline_cache.cache(),
+        ));
+        if let Err(error) = self.pipeline_cache.save() {
+            log::warn!("{error}");
+        }
+    }
+
+    /// Turns determinism mode on with the given seed, or off.
+    ///
+    /// Turning it on restarts the simulation from the scene's initial state, advancing it only
+    /// in fixed ticks, and recreates the crowd and particle demos with variations drawn from
+    /// the seed. Runs
+    /// with the same seed then record identical [`TickLog`]s. Nothing happens if the seed is
+    /// unchanged.
+    pub fn set_determinism(&mut self, seed: Option<u64>) {
+        if seed == self.determinism_seed {
+            return;
+        }
+        self.determinism_seed = seed;
+        self.fixed_step = None;
+        let Some(seed) = seed else {
+            log::info!("Determinism mode off");
+            return;
+        };
+        log::info!("Determinism mode on, seed {seed}");
+
+        let model = crate::math::identity();
+        self.scene.reset_animation(model);
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.simulation.is_running() {
+            self.simulation = SimulationThread::spawn(model);
+        }
+        self.fixed_step = Some(FixedStepSimulation::new(model));
+        #[cfg(feature = "scene3d")]
+        if let Some(crowd) = self.crowd.take() {
+            self.deletion_queue.retire(Retired::other(crowd));
+            self.set_crowd_demo_enabled(true);
+        }
+        #[cfg(feature = "scene3d")]
+        if let Some(particles) = self.particles.take() {
+            self.deletion_queue.retire(Retired::other(particles));
+            self.set_particle_demo_enabled(true);
+        }
+    }
+
+    /// Advances the scene's animation by `ticks` fixed ticks of `TICK` on the render thread,
+    /// and draws the following frames `alpha` of a tick past the last one, between the last
+    /// two ticks.
+    ///
+    /// The `App` calls this every frame with the ticks its `FixedTimestep` split the frame's
+    /// delta time into. The ticks are recorded in determinism mode. While the simulation
+    /// thread runs on native platforms, it advances the animation instead, and the ticks are
+    /// ignored.
+    pub fn tick_scene(&mut self, ticks: u32, alpha: f32) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.simulation.is_running() {
+            return;
+        }
+        self.tick_alpha = alpha;
+        for _ in 0..ticks {
+            match &mut self.fixed_step {
+                Some(fixed_step) => fixed_step.step(),
+                None => self.scene.tick(crate::simulation::TICK.as_secs_f32()),
+            }
+        }
+    }
+
+    /// Returns the state hash after every simulated tick, to compare runs in determinism mode.
+    ///
+    /// On native platforms the simulation thread records the log. The log is empty while
+    /// determinism mode is off and the simulation runs on the render thread.
+    pub fn tick_log(&self) -> TickLog {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let tick_log = self.simulation.tick_log();
+            if tick_log.last().is_some() {
+                return tick_log;
+            }
+        }
+        self.fixed_step
+            .as_ref()
+            .map(|fixed_step| fixed_step.tick_log().clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the view-projection matrix the scene was most recently rendered with.
+    pub fn view_projection(&self) -> crate::math::Mat4 {
+        self.scene.view_projection
+    }
+
+    /// Sets the camera's view matrix and position for the next frame.
+    pub fn set_camera(&mut self, view: crate::math::Mat4, eye: crate::math::Vec3) {
+        self.scene.set_camera(view, eye);
+    }
+
+    /// Sets the camera's field of view and clip planes for the next frame.
+    ///
+    /// The values are sanitized before use (see [`Projection::sanitized`]). While `auto_clip`
+    /// is set, the clip planes are derived from [`Renderer::scene_bounds`] every frame.
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    /// Replaces the meshes drawn by the scene, such as with the meshes of a loaded glTF model.
+    /// The replaced meshes are released once the frames drawing them have finished.
+    ///
+    /// Meshes without triangles are skipped, and if none remains, the meshes are kept.
+    pub fn set_scene_meshes(&mut self, meshes: &[MeshData]) {
+        self.gpu_errors.begin(&self.gpu.device);
+        for mesh in self.scene.set_meshes(&self.gpu.device, meshes) {
+            self.deletion_queue.retire(mesh.vertex_buffer);
+            self.deletion_queue.retire(mesh.index_buffer);
+        }
+        self.gpu_errors.end(&self.gpu.device, "upload meshes");
+    }
+
+    /// Uploads `mesh` for nodes added with [`Renderer::add_scene_node`] to draw, and returns
+    /// its handle, or `None` if it has no triangles.
+    pub fn add_scene_mesh(&mut self, mesh: &MeshData) -> Option<MeshHandle> {
+        self.scene.add_mesh(&self.gpu.device, mesh)
+    }
+
+    /// Adds `node` to the scene as a child of `parent`, or as a root if `parent` is `None`,
+    /// and returns its id. Returns `None` if `parent` is not in the scene.
+    pub fn add_scene_node(&mut self, parent: Option<NodeId>, node: Node) -> Option<NodeId> {
+        self.scene.add_node(&self.gpu.device, parent, node)
+    }
+
+    /// Removes the node `id` and its descendants from the scene, and returns them.
+    pub fn remove_scene_node(&mut self, id: NodeId) -> Vec<Node> {
+        self.scene.remove_node(id)
+    }
+
+    /// Draws each of the scene's meshes once per instance in `instances`, or once if it is
+    /// empty. The replaced instances are released once the frames drawing them have finished.
+    pub fn set_scene_instances(&mut self, instances: &[Instance]) {
+        if let Some(buffer) = self.scene.set_instances(&self.gpu.device, instances) {
+            self.deletion_queue.retire(buffer);
+        }
+    }
+
+    /// Selects how the instancing demo draws its grid of [`GridDemo::SIDE`] × [`GridDemo::SIDE`]
+    /// copies of the scene's first mesh, replacing the grid of the previous mode.
+    ///
+    /// [`GridDemo::Instanced`] sets the grid as the scene's instances, and
+    /// [`GridDemo::PerDrawUniforms`] adds a root node per copy. The frame statistics then show
+    /// what one draw call saves over thousands.
+    pub fn set_grid_demo(&mut self, demo: GridDemo) {
+        if demo == self.grid_demo {
+            return;
+        }
+        match self.grid_demo {
+            GridDemo::Off => {}
+            GridDemo::Instanced => self.set_scene_instances(&[]),
+            GridDemo::PerDrawUniforms => {
+                for node in std::mem::take(&mut self.grid_demo_nodes) {
+                    self.scene.remove_node(node);
+                }
+            }
+        }
+        self.grid_demo = demo;
+
+        let instances = grid_instances(GridDemo::SIDE, GridDemo::SPACING);
+        match demo {
+            GridDemo::Off => {}
+            GridDemo::Instanced => self.set_scene_instances(&instances),
+            GridDemo::PerDrawUniforms => {
+                for instance in &instances {
+                    let node = Node::new("Grid Triangle")
+                        .with_local(instance.model())
+                        .with_mesh(MeshHandle(0));
+                    if let Some(id) = self.scene.add_node(&self.gpu.device, None, node) {
+                        self.grid_demo_nodes.push(id);
+                    }
+                }
+            }
+        }
+        log::info!("Grid demo: {}", demo.label());
+    }
+
+    /// Recompiles the scene's pipelines from the WGSL `source`, such as the text of the Shader
+    /// Editor. They replace the scene's pipelines once the backend validated them, which
+    /// [`Renderer::take_scene_shader_result`] reports. A shader still being validated is
+    /// discarded.
+    pub fn set_scene_shader(&mut self, source: &str) {
+        self.pending_shader = Some(self.scene.set_shader(
+            &self.gpu.device,
+            PostProcess::HDR_FORMAT,
+            source,
+            self.pipeline_cache.cache(),
+        ));
+    }
+
+    /// Installs the shader of the last `set_scene_shader` once the backend validated it, and
+    /// returns the result once: right away on desktop platforms, a few frames later in the
+    /// browser. The replaced pipelines are released once the frames using them have finished.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ShaderError` with the compilation or validation error if the shader cannot
+    /// replace the scene's shader, which is then kept.
+    pub fn take_scene_shader_result(&mut self) -> Option<Result<(), ShaderError>> {
+        let result = self.pending_shader.as_mut()?.take()?;
+        self.pending_shader = None;
+        Some(result.map(|pipelines| {
+            let replaced = self.scene.install_shader(pipelines);
+            self.deletion_queue.retire(Retired::other(replaced));
+            log::info!("Applied the scene shader");
+        }))
+    }
+
+    /// Returns the latest distinct GPU errors, oldest first, for the GPU Errors window.
+    pub fn gpu_errors(&self) -> Vec<GpuErrorRecord> {
+        self.gpu_errors.records()
+    }
+
+    /// Forgets the recorded GPU errors.
+    pub fn clear_gpu_errors(&mut self) {
+        self.gpu_errors.clear();
+    }
+
+    /// Returns the scene's nodes.
+    pub fn scene_graph(&self) -> &SceneGraph {
+        self.scene.graph()
+    }
+
+    /// Returns the scene's node `id` to modify, or `None` if it is not in the scene.
+    pub fn scene_node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.scene.node_mut(id)
+    }
+
+    /// Adds a label showing `text` at `position` in world space to the scene, and returns its
+    /// id. See [`Scene::add_label`].
+    pub fn add_scene_label(
+        &mut self,
+        position: crate::math::Vec3,
+        text: impl Into<String>,
+    ) -> LabelId {
+        self.scene.add_label(position, text)
+    }
+
+    /// Returns the scene's label `id` to modify, or `None` if it was removed.
+    pub fn scene_label_mut(&mut self, id: LabelId) -> Option<&mut Label> {
+        self.scene.label_mut(id)
+    }
+
+    /// Removes the label `id` from the scene, and returns it.
+    pub fn remove_scene_label(&mut self, id: LabelId) -> Option<Label> {
+        self.scene.remove_label(id)
+    }
+
+    /// Selects the scene's node drawn at `cursor`, or clears the selection if none is drawn
+    /// there, once the object id under it has been read back a frame or two later.
+    ///
+    /// `cursor` is in physical pixels from the top left of the surface. While the scene is
+    /// shown in a panel, clicks outside of it are ignored.
+    pub fn pick(&mut self, cursor: (u32, u32)) {
+        #[cfg(feature = "gui")]
+        let cursor = match &self.scene_panel {
+            Some(panel) => panel.rect().to_local(cursor),
+            None => Some(cursor),
+        };
+        #[cfg(not(feature = "gui"))]
+        let cursor = Some(cursor);
+        if let Some(cursor) = cursor {
+            self.picker.pick(cursor);
+        }
+    }
+
+    /// Returns the selected node of the scene, if it is still in the scene.
+    pub fn selected_node(&self) -> Option<NodeId> {
+        self.selected_node
+            .filter(|&id| self.scene.graph().get(id).is_some())
+    }
+
+    /// Selects the scene's node `id`, outlined from the next frame on, or clears the selection
+    /// with `None`.
+    pub fn set_selected_node(&mut self, id: Option<NodeId>) {
+        self.selected_node = id;
+    }
+
+    /// Returns the name of the selected node of the scene.
+    pub fn selected_node_name(&self) -> Option<String> {
+        let id = self.selected_node()?;
+        Some(self.scene_graph().get(id)?.name.clone())
+    }
+
+    /// Returns the selected node of the scene for the transform gizmo.
+    pub fn gizmo_target(&self) -> Option<GizmoTarget> {
+        let graph = self.scene_graph();
+        let node = graph.get(self.selected_node()?)?;
+        let parent_world = match node.parent() {
+            Some(parent) => graph.get(parent)?.world(),
+            None => graph.transform,
+        };
+        Some(GizmoTarget {
+            local: node.local,
+            parent_world,
+        })
+    }
+
+    /// Replaces the local transform of the selected node of the scene, as dragged with the
+    /// transform gizmo.
+    pub fn set_selected_node_local(&mut self, local: crate::math::Mat4) {
+        if let Some(node) = self.selected_node().and_then(|id| self.scene_node_mut(id)) {
+            node.local = local;
+        }
+    }
+
+    /// Replaces the texture multiplied with the scene's vertex colors. The replaced texture is
+    /// released once the frames sampling it have finished.
+    pub fn set_scene_texture(&mut self, texture: Texture) {
+        self.gpu_errors.begin(&self.gpu.device);
+        let (texture, bind_groups) = self.scene.set_texture(&self.gpu.device, texture);
+        for bind_group in bind_groups {
+            self.deletion_queue.retire(bind_group);
+        }
+        self.deletion_queue.retire(texture.view);
+        self.deletion_queue.retire(texture.texture);
+        self.deletion_queue.retire(Retired::other(texture.sampler));
+        self.gpu_errors.end(&self.gpu.device, "set texture");
+    }
+
+    /// Adds `texture` for the scene's materials to sample, and returns its handle.
+    pub fn add_scene_texture(&mut self, texture: Texture) -> TextureHandle {
+        let (handle, bind_groups) = self.scene.add_texture(&self.gpu.device, texture);
+        for bind_group in bind_groups {
+            self.deletion_queue.retire(bind_group);
+        }
+        handle
+    }
+
+    /// Sets the materials the scene's meshes are shaded with, indexed by their
+    /// [`MaterialHandle`](crate::MaterialHandle). Only the materials that changed are
+    /// uploaded, so it can be called every frame while the materials are edited.
+    pub fn set_scene_materials(&mut self, materials: &[Material]) {
+        let (bind_groups, removed) =
+            self.scene
+                .set_materials(&self.gpu.device, &self.gpu.queue, materials);
+        for bind_group in bind_groups {
+            self.deletion_queue.retire(bind_group);
+        }
+        for binding in removed {
+            self.deletion_queue.retire(binding.bind_group);
+            self.deletion_queue.retire(binding.buffer);
+        }
+    }
+
+    /// Decodes a PNG or JPEG image and draws the scene with it, replacing the current texture.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TextureError` if the image cannot be decoded, or is too large for the device.
+    pub fn load_scene_texture(&mut self, bytes: &[u8]) -> Result<(), TextureError> {
+        let texture =
+            Texture::from_bytes(&self.gpu.device, &self.gpu.queue, bytes, "Scene Texture")?;
+        self.set_scene_texture(texture);
+        Ok(())
+    }
+
+    /// Returns the bounds of the content drawn in the scene: the scene's geometry and, while
+    /// they are shown, the crowd and particle demos.
+    pub fn scene_bounds(&self) -> Bounds {
+        #[cfg_attr(not(feature = "scene3d"), allow(unused_mut))]
+        let mut bounds = self.scene.bounds();
+        #[cfg(feature = "scene3d")]
+        if let Some(crowd) = self.crowd.as_ref().and_then(CrowdDemo::bounds) {
+            bounds = bounds.union(&crowd);
+        }
+        #[cfg(feature = "scene3d")]
+        if let Some(particles) = &self.particles {
+            bounds = bounds.union(&particles.bounds());
+        }
+        bounds
+    }
+
+    /// Returns the line renderer, to queue debug lines for the next frame.
+    ///
+    /// Queued lines are drawn once, by the next call to `render_frame`.
+    pub fn debug_lines(&mut self) -> &mut LineRenderer {
+        &mut self.lines
+    }
+
+    /// Returns the optional features and limits granted to the GPU device.
+    ///
+    /// Use this to decide whether optional code paths (timestamp queries, wireframe rendering,
+    /// push constants, or indirect multi-draw) can be used on the current adapter.
+    pub fn capabilities(&self) -> &GpuCapabilities {
+        &self.gpu.capabilities
+    }
+
+    /// Returns the GPU device, to create the resources of background jobs.
+    pub fn device(&self) -> &wgpu::Device {
+        &self.gpu.device
+    }
+
+    /// Returns the format of the surface frames are drawn into, to create the pipelines of
+    /// depth passes.
+    pub fn surface_format(&self) -> wgpu::TextureFormat {
+        self.gpu.surface_format
+    }
+
+    /// Returns the scene's depth bound for sampling, or `None` if the adapter cannot sample
+    /// the depth format.
+    ///
+    /// The bind group is re-created when the surface is resized, so it should be taken from
+    /// here (or from the [`DepthPassFrame`]) every frame. Its layout stays valid for the
+    /// lifetime of the renderer. The depth is only complete after the scene pass, which is
+    /// when passes added with [`Renderer::add_depth_pass`] run.
+    pub fn scene_depth(&self) -> Option<&DepthBinding> {
+        self.depth_binding.as_ref()
+    }
+
+    /// Adds a `pass` sampling the scene's depth, encoded every frame after the scene pass and
+    /// before the GUI.
+    ///
+    /// Passes run in the order they were added. If the adapter cannot sample the depth
+    /// format, the pass is dropped and a warning is logged.
+    pub fn add_depth_pass(&mut self, pass: Box<dyn DepthPass>) {
+        if self.depth_binding.is_some() {
+            self.depth_passes.push(pass);
+        } else {
+            log::warn!("Dropping depth pass: the depth texture cannot be sampled");
+        }
+    }
+
+    /// Returns the pipeline cache to compile the pipelines of background jobs through, or
+    /// `None` if the device has none. See [`Renderer::save_pipeline_cache`].
+    pub fn pipeline_cache(&self) -> Option<&wgpu::PipelineCache> {
+        self.pipeline_cache.cache()
+    }
+
+    /// Saves the pipeline cache to disk, so pipelines created since startup are compiled
+    /// faster by the next run. Nothing is written if no pipelines were added.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PipelineCacheError` if the cache file cannot be written.
+    pub fn save_pipeline_cache(&mut self) -> Result<(), PipelineCacheError> {
+        self.pipeline_cache.save()
+    }
+
+    /// Queues a long-running compute `job`, which runs a slice at a time after every frame.
+    ///
+    /// Jobs run one after another, in the order they were spawned. On adapters without compute
+    /// shaders (WebGL), the job is dropped and a warning is logged.
+    pub fn spawn_background_job(&mut self, job: Box<dyn BackgroundJob>) {
+        if self.gpu.capabilities.supports_compute() {
+            self.background.spawn(job);
+        } else {
+            log::warn!(
+                "Dropping background job \"{}\": compute shaders are not supported",
+                job.progress().label
+            );
+        }
+    }
+
+    /// Queues an upload of `data` into `buffer` at `offset`, written in chunks over the next
+    /// frames within the upload budget.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` or the length of `data` is not a multiple of
+    /// `wgpu::COPY_BUFFER_ALIGNMENT`.
+    pub fn upload_buffer(
+        &mut self,
+        label: &str,
+        buffer: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+        data: Vec<u8>,
+    ) {
+        self.uploads.upload_buffer(label, buffer, offset, data);
+    }
+
+    /// Queues an upload of `data` into the top mip level of the 2D `texture`, written in bands
+    /// of rows over the next frames within the upload budget.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` does not hold exactly `texture.height()` rows of `bytes_per_row` bytes.
+    pub fn upload_texture(
+        &mut self,
+        label: &str,
+        texture: &wgpu::Texture,
+        data: Vec<u8>,
+        bytes_per_row: u32,
+    ) {
+        self.uploads
+            .upload_texture(label, texture, data, bytes_per_row);
+    }
+
+    /// Sets how long writing upload chunks may take per frame.
+    pub fn set_upload_budget(&mut self, budget: crate::Duration) {
+        self.uploads.set_budget(budget);
+    }
+
+    /// Returns the progress of the uploads and background jobs in progress, oldest first.
+    pub fn task_progress(&self) -> Vec<TaskProgress> {
+        let mut tasks = self.uploads.progress();
+        tasks.extend(self.background.progress());
+        tasks
+    }
+
+    /// Returns the budget background job slices are sized by, to tune its target frame time.
+    pub fn background_budget_mut(&mut self) -> &mut BackgroundBudget {
+        self.background.budget_mut()
+    }
+
+    /// Takes the recoverable configuration warnings collected during GPU initialization.
+    ///
+    /// Each returned `ValidationError` describes a requested setting the adapter or surface
+    /// did not support and for which a fallback was applied. The list is emptied by this
+    /// call, so each warning is only reported once.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for warning in renderer.take_validation_warnings() {
+    ///     toasts.warning(warning.to_string());
+    /// }
+    /// ```
+    pub fn take_validation_warnings(&mut self) -> Vec<crate::validation::ValidationError> {
+        std::mem::take(&mut self.gpu.validation_warnings)
+    }
+
+    /// Waits for all submitted GPU work to finish before the renderer is dropped.
+    ///
+    /// Called when the application exits, so that no command buffers are still in flight
+    /// while the surface and device are destroyed.
+    pub fn shutdown(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.simulation.stop();
+        let _ = self.gpu.device.poll(wgpu::Maintain::Wait);
+        self.deletion_queue.flush();
+    }
+
+    /// Returns the sky of the environment in effect: the scene's own, or the global one.
+    #[cfg(feature = "scene3d")]
+    fn sky(&self) -> crate::environment::Sky {
+        self.scene
+            .environment_override
+            .as_ref()
+            .unwrap_or(&self.environment)
+            .sky
+    }
+
+    /// Queues the draws of the scene pass on the camera's layers and sorts them.
+    ///
+    /// Opaque draws within a pipeline and material are ordered front to back by the distance
+    /// of their content from the camera, relative to the far clip plane.
+    fn queue_scene_draws(&mut self) {
+        let masks = self.layer_masks;
+        let eye = self.scene.camera_position;
+        let far = self.scene.projection.far.max(f32::EPSILON);
+        let depth = |center: &crate::math::Vec3| crate::math::distance(&eye, center) / far;
+
+        self.render_queue.clear();
+        // The backdrop is the clear color itself, so it needs no draw.
+        #[cfg(feature = "scene3d")]
+        if !self.transparent && self.sky() != crate::environment::Sky::Backdrop {
+            let draw = SceneDraw::Sky;
+            self.render_queue
+                .push(SortKey::new(draw.pipeline_id(), 0, 1.0), draw);
+        }
+        if masks.shows(masks.scene) {
+            let draw = SceneDraw::Scene;
+            let center = self.scene.bounds().center();
+            self.render_queue
+                .push(SortKey::new(draw.pipeline_id(), 0, depth(&center)), draw);
+        }
+        #[cfg(feature = "scene3d")]
+        if let Some(crowd) = self.crowd.as_ref().filter(|_| masks.shows(masks.crowd)) {
+            let center = crowd.bounds().map_or(eye, |bounds| bounds.center());
+            for lod in crowd.visible_lods() {
+                let draw = SceneDraw::CrowdLod(lod);
+                self.render_queue.push(
+                    SortKey::new(draw.pipeline_id(), lod as u32, depth(&center)),
+                    draw,
+                );
+            }
+        }
+        #[cfg(feature = "scene3d")]
+        if let Some(particles) = self
+            .particles
+            .as_ref()
+            .filter(|_| masks.shows(masks.particles))
+        {
+            let draw = SceneDraw::Particles;
+            let center = particles.bounds().center();
+            self.render_queue
+                .push(SortKey::new(draw.pipeline_id(), 0, depth(&center)), draw);
+        }
+        if masks.shows(masks.helpers) && self.world_grid_visible {
+            for draw in [SceneDraw::Grid, SceneDraw::Axes] {
+                self.render_queue
+                    .push(SortKey::new(draw.pipeline_id(), 0, 0.0), draw);
+            }
+        }
+        if masks.shows(masks.helpers) && !self.lines.is_empty() {
+            let draw = SceneDraw::Lines;
+            self.render_queue
+                .push(SortKey::new(draw.pipeline_id(), 0, 0.0), draw);
+        }
+        if !self.labels.is_empty() {
+            let draw = SceneDraw::Labels;
+            self.render_queue
+                .push(SortKey::new(draw.pipeline_id(), 0, 0.0), draw);
+        }
+        self.render_queue.sort();
+    }
+
+    /// Encodes `draw` into the scene pass, binding its pipeline and material first if `change`
+    /// says they differ from the previous draw's.
+    fn encode_scene_draw<'rpass>(
+        &'rpass self,
+        render_pass: &mut wgpu::RenderPass<'rpass>,
+        draw: SceneDraw,
+        change: StateChange,
+    ) {
+        match draw {
+            #[cfg(feature = "scene3d")]
+            SceneDraw::Sky => {
+                if change.pipeline {
+                    self.sky.bind_pipeline(render_pass);
+                }
+                if change.material {
+                    SkyRenderer::bind_material(render_pass, &self.scene);
+                }
+                SkyRenderer::draw(render_pass);
+            }
+            SceneDraw::Scene => {
+                if change.pipeline {
+                    self.scene.bind_pipeline(render_pass);
+                }
+                if change.material {
+                    self.scene.bind_material(render_pass);
+                }
+                self.scene.draw(render_pass);
+            }
+            #[cfg(feature = "scene3d")]
+            SceneDraw::CrowdLod(lod) => {
+                let Some(crowd) = self.crowd.as_ref() else {
+                    return;
+                };
+                if change.pipeline {
+                    crowd.bind_pipeline(render_pass);
+                }
+                if change.material {
+                    crowd.bind_lod(render_pass, lod);
+                }
+                crowd.draw_lod(render_pass, lod);
+            }
+            #[cfg(feature = "scene3d")]
+            SceneDraw::Particles => {
+                let Some(particles) = self.particles.as_ref() else {
+                    return;
+                };
+                if change.pipeline {
+                    particles.bind_pipeline(render_pass);
+                }
+                if change.material {
+                    particles.bind_material(render_pass);
+                }
+                particles.draw(render_pass);
+            }
+            SceneDraw::Grid => {
+                if change.pipeline {
+                    self.debug_draw.bind_grid_pipeline(render_pass);
+                }
+                if change.material {
+                    self.debug_draw.bind_material(render_pass);
+                }
+                DebugDraw::draw_grid(render_pass);
+            }
+            SceneDraw::Axes => {
+                if change.pipeline {
+                    self.debug_draw.bind_axes_pipeline(render_pass);
+                }
+                if change.material {
+                    self.debug_draw.bind_material(render_pass);
+                }
+                DebugDraw::draw_axes(render_pass);
+            }
+            SceneDraw::Lines => {
+                if change.pipeline {
+                    self.lines.bind_pipeline(render_pass);
+                }
+                if change.material {
+                    self.lines.bind_material(render_pass);
+                }
+                self.lines.draw(render_pass);
+            }
+            SceneDraw::Labels => {
+                if change.pipeline {
+                    self.labels.bind_pipeline(render_pass);
+                }
+                if change.material {
+                    self.labels.bind_material(render_pass);
+                }
+                self.labels.draw(render_pass);
+            }
+        }
+    }
+
+    /// Renders a single frame, combining 3D scene rendering and `egui` GUI rendering.
+    ///
+    /// # Parameters
+    ///
+    /// - `screen_descriptor`: Describes the screen properties, such as size and DPI,
+    ///   which are required by the `egui` renderer to render correctly.
+    /// - `paint_jobs`: A collection of `egui` primitives (draw instructions)
+    ///   to be rendered on the screen.
+    /// - `textures_delta`: Contains information about added or removed textures
+    ///   that are used by `egui` for rendering.
+    /// - `delta_time`: The time elapsed since the last frame, which is used
+    ///   for animations and time-dependent logic in the 3D scene.
+    ///
+    /// Without the `gui` feature, only `delta_time` is taken and no GUI is drawn.
+    ///
+    /// # Details
+    ///
+    /// This function coordinates the rendering of the GUI and the 3D scene. It updates
+    /// the scene based on the elapsed time, updates `egui` textures, and manages the
+    /// GPU command encoder for rendering to the surface. It performs the following steps:
+    ///
+    /// 1. Updates the 3D scene's state based on the `delta_time` to account for animations
+    ///    or any other time-dependent behavior.
+    /// 2. Updates and synchronizes `egui` textures (loading or freeing them as needed).
+    /// 3. Executes `egui`'s paint jobs and integrates them with the rendered output.
+    /// 4. Renders the frame to the surface, using a render pass that includes a color attachment
+    ///    for the main scene and a depth attachment for proper depth-testing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RenderError::Surface` if the surface texture for this frame cannot be acquired.
+    /// This can occur if the surface becomes invalid (e.g., if the window is resized or destroyed),
+    /// in which case nothing is presented and the caller decides how to recover.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// renderer.render_frame(
+    ///     screen_descriptor,
+    ///     paint_jobs,
+    ///     textures_delta,
+    ///     delta_time,
+    /// )?;
+    /// ```
+    ///
+    /// In this example, the function is called to render one frame, using the provided
+    /// `egui` render data, screen dimensions, and the time elapsed since the previous frame.
+    ///
+    /// GPU errors raised while rendering are recorded under "render frame" (see
+    /// [`Renderer::gpu_errors`]) instead of panicking.
+    ///
+    /// While rendering is suspended (see [`Renderer::is_suspended`]), nothing is rendered and
+    /// `Ok(())` is returned. After [`Renderer::trigger_capture`], the frame is recorded by the
+    /// attached graphics debugger.
+    pub fn render_frame(
+        &mut self,
+        #[cfg(feature = "gui")] screen_descriptor: egui_wgpu::ScreenDescriptor,
+        #[cfg(feature = "gui")] paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
+        #[cfg(feature = "gui")] textures_delta: egui::TexturesDelta,
+        delta_time: crate::Duration,
+    ) -> Result<(), RenderError> {
+        if self.suspended {
+            return Ok(());
+        }
+        self.gpu_errors.begin(&self.gpu.device);
+        let capture = std::mem::take(&mut self.gpu_capture_requested);
+        if capture {
+            log::info!("Capturing the frame in the graphics debugger");
+            self.gpu.device.start_capture();
+        }
+        let result = self.render_frame_in_scope(
+            #[cfg(feature = "gui")]
+            screen_descriptor,
+            #[cfg(feature = "gui")]
+            paint_jobs,
+            #[cfg(feature = "gui")]
+            textures_delta,
+            delta_time,
+        );
+        if capture {
+            self.gpu.device.stop_capture();
+        }
+        self.gpu_errors.end(&self.gpu.device, "render frame");
+        result
+    }
+
+    /// Renders one frame for [`Renderer::render_frame`], inside its error scopes.
+    fn render_frame_in_scope(
+        &mut self,
+        #[cfg(feature = "gui")] screen_descriptor: egui_wgpu::ScreenDescriptor,
+        #[cfg(feature = "gui")] paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
+        #[cfg(feature = "gui")] textures_delta: egui::TexturesDelta,
+        delta_time: crate::Duration,
+    ) -> Result<(), RenderError> {
+        // The elapsed time since the last frame.
+        // This variable is used to update the state of the 3D scene, animations, and other
+        // time-dependent logic within the render pipeline.
+        //
+        // `frame_time` facilitates smooth animations and transitions by allowing computations
+        // to take the elapsed time into account, ensuring consistent behavior regardless of frame rate.
+        let frame_time = delta_time;
+        let cpu_start = web_time::Instant::now();
+
+        // Stream the next chunks of the queued uploads, which land before this frame's work.
+        self.uploads.run(&self.gpu.queue);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(streamer) = self.streamer.as_mut() {
+            streamer.poll(&self.gpu.device);
+        }
+
+        // Finish the depth probe's readback before the scene is updated, since the copy was
+        // recorded with the previous frame's view-projection matrix.
+        let inverse_view_projection = crate::math::inverse(&self.scene.view_projection);
+        self.depth_probe
+            .poll(&self.gpu.device, &inverse_view_projection);
+        self.picker.poll(&self.gpu.device);
+        if let Some(object_id) = self.picker.take_picked() {
+            self.selected_node = self.scene.object_node(object_id);
+        }
+        self.frame_capture.poll(&self.gpu.device);
+        #[cfg(feature = "gui")]
+        self.ui_capture.poll(&self.gpu.device);
+        if let Some(gpu_timer) = self.gpu_timer.as_mut() {
+            gpu_timer.poll(&self.gpu.device);
+            self.frame_stats
+                .set_gpu_times(gpu_timer.latest(), gpu_timer.latest_passes());
+        }
+        self.deletion_queue.collect(&self.gpu.device);
+        self.upload_belt.recall();
+
+        // The scene is drawn into the viewport, so its projection uses the viewport's aspect
+        // ratio rather than the surface's.
+        let viewport = self.scene_viewport();
+        let aspect_ratio = viewport.aspect_ratio();
+
+        if self.grid_visible {
+            self.lines.extend(grid_lines(
+                Self::GRID_CELLS,
+                Self::GRID_SPACING,
+                Self::GRID_HEIGHT,
+                1.0,
+            ));
+        }
+
+        // Fit the clip planes around everything drawn this frame: the content, and the grids
+        // and debug lines queued above unless the camera excludes them.
+        let masks = self.layer_masks;
+        let mut bounds = self.scene_bounds();
+        if let Some(lines) = self
+            .lines
+            .queued_bounds()
+            .filter(|_| masks.shows(masks.helpers))
+        {
+            bounds = bounds.union(&lines);
+        }
+        if self.world_grid_visible && masks.shows(masks.helpers) {
+            bounds = bounds.union(&self.debug_draw.bounds(&self.scene.camera_position));
+        }
+        self.scene.projection = self
+            .projection
+            .fitted(&self.scene.camera_position, Some(&bounds));
+
+        // Draw the scene between its last two ticks, so the motion stays smooth at frame rates
+        // that are not a multiple of the tick rate. On native platforms the ticks are the
+        // latest two snapshots of the simulation thread, read without waiting for it. If the
+        // thread could not be started, and always on WebAssembly, they are the ticks of
+        // `tick_scene` on the render thread instead, recorded in determinism mode.
+        let uniform_scope = self.cpu_profiler.scope("Uniform Updates");
+        #[cfg(not(target_arch = "wasm32"))]
+        let snapshots = self.simulation.latest_interpolated();
+        #[cfg(target_arch = "wasm32")]
+        let snapshots = None;
+        if let Some((previous, snapshot, alpha)) = snapshots {
+            self.scene.apply_snapshot(
+                &mut self.upload_belt,
+                aspect_ratio,
+                &previous,
+                &snapshot,
+                alpha,
+            );
+        } else if let Some(fixed_step) = &self.fixed_step {
+            self.scene.apply_snapshot(
+                &mut self.upload_belt,
+                aspect_ratio,
+                fixed_step.previous(),
+                fixed_step.snapshot(),
+                self.tick_alpha,
+            );
+        } else {
+            self.scene
+                .update_interpolated(&mut self.upload_belt, aspect_ratio, self.tick_alpha);
+        }
+
+        self.scene
+            .upload_environment(&self.gpu.queue, &self.environment);
+        self.scene.upload_light(&self.gpu.queue, &self.light);
+        // A scene panel whose texture the GUI cannot decode from sRGB keeps linear colors.
+        #[cfg(feature = "gui")]
+        let post_effects = PostEffects {
+            gamma: self.post_effects.gamma
+                && !self
+                    .scene_panel
+                    .as_ref()
+                    .is_some_and(ScenePanel::stores_linear),
+            ..self.post_effects
+        };
+        #[cfg(not(feature = "gui"))]
+        let post_effects = self.post_effects;
+        self.post_process.update(&self.gpu.queue, &post_effects);
+
+        #[cfg(feature = "scene3d")]
+        if let Some(crowd) = self.crowd.as_mut() {
+            crowd.set_detail(
+                self.quality_levels.crowd_lod_distance(),
+                self.quality_levels.crowd_density(),
+            );
+            crowd.update(
+                &self.gpu.queue,
+                &self.scene.view_projection,
+                &self.scene.camera_position,
+                frame_time.as_secs_f32(),
+            );
+        }
+        #[cfg(feature = "scene3d")]
+        if let Some(particles) = self.particles.as_mut() {
+            particles.update(
+                &self.gpu.queue,
+                &self.scene.view_projection,
+                &self.scene.view,
+                frame_time.as_secs_f32(),
+            );
+        }
+
+        self.lines.prepare(
+            &self.gpu.device,
+            &mut self.upload_belt,
+            &self.scene.view_projection,
+            viewport.size(),
+        );
+        if self.world_grid_visible {
+            self.debug_draw.update(
+                &self.gpu.queue,
+                &self.scene.view_projection,
+                &self.scene.camera_position,
+            );
+        }
+
+        // The node names are labeled for this frame only, on top of the scene's own labels.
+        let node_labels: Vec<_> = if self.node_labels_visible && masks.shows(masks.helpers) {
+            self.scene
+                .graph()
+                .iter()
+                .filter(|(_, node)| !node.name.is_empty())
+                .map(|(id, node)| {
+                    Label::new(crate::math::zeros(), node.name.clone())
+                        .with_node(id)
+                        .with_depth_test(false)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let labels = node_labels
+            .iter()
+            .filter_map(|label| Some((self.scene.label_anchor(label)?, label)))
+            .chain(self.scene.label_anchors());
+        self.labels.prepare(
+            &self.gpu.device,
+            &mut self.upload_belt,
+            labels,
+            &self.scene.view_projection,
+            viewport.size(),
+        );
+        drop(uniform_scope);
+        self.queue_scene_draws();
+
+        // This loop iterates over all texture changes in the `textures_delta.set` map,
+        // where `id` is the unique identifier for a texture and `image_delta` describes
+        // the changes to be applied to that texture. For each entry, it updates
+        // the corresponding texture in the `egui_renderer` using the provided
+        // `gpu.device` and `gpu.queue`. This ensures that `egui` textures are
+        // synchronized with changes made to them.
+        #[cfg(feature = "gui")]
+        for (id, image_delta) in &textures_delta.set {
+            self.egui_renderer
+                .update_texture(&self.gpu.device, &self.gpu.queue, *id, image_delta);
+        }
+
+        // Iterate through all texture updates specified in the `textures_delta.set` map.
+        // Each entry in the map consists of a texture `id` (a unique identifier) and
+        // an `image_delta` (describing how the texture should be updated). The `egui_renderer`
+        // is then instructed to update these textures using the provided `gpu.device` and
         // `gpu.queue` for GPU operations, ensuring `egui` texture data is synchronized with
         // the latest changes.
+        #[cfg(feature = "gui")]
         for id in &textures_delta.free {
             self.egui_renderer.free_texture(id);
         }
@@ -460,6 +2855,7 @@ impl Renderer {
                 label: Some("Render Encoder"),
             });
 
+        #[cfg(feature = "gui")]
         self.egui_renderer.update_buffers(
             &self.gpu.device,
             &self.gpu.queue,
@@ -470,22 +2866,20 @@ impl Renderer {
 
         // Represents the texture for the current frame, retrieved from the surface.
         //
-        // The `surface_texture` is obtained using the `get_current_texture()` method on the GPU surface.
+        // The `surface_texture` is obtained using the `acquire_frame()` method on the GPU.
         // It represents the texture that will be used as the rendering target for the current frame.
         // Rendering commands are issued to draw content onto this texture.
         //
         // # Details
         //
-        // - This texture is tied to the window or canvas the application is rendering to.
+        // - This texture is tied to the window or canvas the application is rendering to, or is
+        //   the offscreen target of a headless renderer.
         // - The texture is presented using the `present()` method once rendering has finished.
-        // - If acquiring the texture fails (e.g., due to a lost surface), an error will be raised.
+        // - If acquiring the texture fails (e.g., due to a lost surface), `RenderError::Surface`
+        //   is returned and the recorded commands are discarded.
         //
         // The `surface_texture` is vital for ensuring rendered frames are output to the display.
-        let surface_texture = self
-            .gpu
-            .surface
-            .get_current_texture()
-            .expect("Failed to get surface texture!");
+        let surface_texture = self.gpu.acquire_frame()?;
 
         // Represents a view of the texture for the current frame.
         //
@@ -504,9 +2898,9 @@ impl Renderer {
         //   the frame onto the screen.
         let surface_texture_view =
             surface_texture
-                .texture
+                .texture()
                 .create_view(&wgpu::TextureViewDescriptor {
-                    label: wgpu::Label::default(),
+                    label: Some("Surface Texture View"),
                     aspect: wgpu::TextureAspect::default(),
                     format: Some(self.gpu.surface_format),
                     dimension: None,
@@ -517,7 +2911,18 @@ impl Renderer {
                     usage: None,
                 });
 
-        encoder.insert_debug_marker("Render scene");
+        // Each stage of the frame is a debug group, so graphics debuggers such as RenderDoc
+        // show the frame as a tree of named stages.
+        //
+        // The particles are simulated before the scene pass draws them.
+        #[cfg(feature = "scene3d")]
+        if let Some(particles) = &self.particles {
+            encoder.push_debug_group("Particle Simulation");
+            particles.simulate(&mut encoder);
+            encoder.pop_debug_group();
+        }
+
+        encoder.push_debug_group("Scene");
 
         // This scope around the crate::render_pass prevents the
         // crate::render_pass from holding a borrow to the encoder,
@@ -541,25 +2946,202 @@ impl Renderer {
             //
             // This render pass is scoped to ensure the `'encode` lifetime of the encoder is not held after
             // the render pass completes, allowing the `encoder` to be finalized with the `.finish()` method.
+            //
+            // Besides the surface, the scene writes the id of each drawn object into the object
+            // id texture, cleared to the background id, for the depth probe to read back.
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
+                color_attachments: &[
+                    Some(wgpu::RenderPassColorAttachment {
+                        view: self.post_process.hdr_texture_view(),
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(self.surface_clear_color()),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    }),
+                    Some(wgpu::RenderPassColorAttachment {
+                        view: &self.object_id_texture_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color {
+                                r: crate::probe::BACKGROUND_OBJECT_ID as f64,
+                                g: 0.0,
+                                b: 0.0,
+                                a: 0.0,
+                            }),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    }),
+                ],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_texture_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: self
+                    .gpu_timer
+                    .as_ref()
+                    .and_then(|timer| timer.timestamp_writes(TimedPass::Scene)),
+                occlusion_query_set: None,
+            });
+            viewport.apply(&mut render_pass);
+            for (draw, change) in self.render_queue.draws() {
+                render_pass.push_debug_group(draw.label());
+                self.encode_scene_draw(&mut render_pass, draw, change);
+                render_pass.pop_debug_group();
+            }
+        }
+        encoder.pop_debug_group();
+
+        encoder.push_debug_group("Readback Copies");
+
+        self.depth_probe.encode_copy(
+            &mut encoder,
+            &self.depth_texture,
+            &self.object_id_texture,
+            viewport,
+        );
+        self.picker.encode_copy(
+            &mut encoder,
+            &self.depth_texture,
+            &self.object_id_texture,
+            viewport,
+        );
+
+        encoder.pop_debug_group();
+
+        // The scene pass has ended, so its depth is complete and may be sampled.
+        if let Some(depth) = &self.depth_binding {
+            let frame = DepthPassFrame {
+                target: self.post_process.hdr_texture_view(),
+                depth,
+                viewport,
+                projection: self.scene.projection,
+                view_projection: self.scene.view_projection,
+            };
+            encoder.push_debug_group("Depth Passes");
+            for pass in &mut self.depth_passes {
+                pass.encode(&self.gpu.queue, &mut encoder, &frame);
+            }
+            encoder.pop_debug_group();
+        }
+
+        // The selected node is outlined on the HDR scene after the depth passes, so the outline
+        // is post-processed along with the scene but not fogged.
+        let selected = self.selected_node().map(Scene::node_object_id);
+        self.selection_outline.update(&self.gpu.queue, selected);
+        encoder.push_debug_group("Selection Outline");
+        self.selection_outline.render(
+            &mut encoder,
+            self.post_process.hdr_texture_view(),
+            self.gpu_timer.as_ref(),
+        );
+        encoder.pop_debug_group();
+
+        // Display the HDR scene on the surface, or in the scene panel's texture, with bloom,
+        // tonemapped, and gamma-corrected.
+        #[cfg(feature = "gui")]
+        let scene_target = self
+            .scene_panel
+            .as_ref()
+            .map_or(&surface_texture_view, ScenePanel::view);
+        #[cfg(not(feature = "gui"))]
+        let scene_target = &surface_texture_view;
+        encoder.push_debug_group("Post-Processing");
+        self.post_process
+            .render(&mut encoder, scene_target, self.gpu_timer.as_ref());
+        encoder.pop_debug_group();
+
+        // Debug views replace the shaded scene on the surface, after post-processing so their
+        // colors are shown unchanged. The scene pass above still ran, so the depth and object
+        // id textures stay valid for the depth probe.
+        match self.debug_view {
+            DebugView::Shaded => {}
+            #[cfg(feature = "profiling")]
+            DebugView::Overdraw => {
+                let scene = Some(&self.scene).filter(|_| masks.shows(masks.scene));
+                encoder.push_debug_group("Overdraw");
+                self.overdraw
+                    .render(&mut encoder, scene, scene_target, viewport);
+                encoder.pop_debug_group();
+            }
+        }
+
+        // The GUI is drawn in a second pass on top of the scene, since its pipeline only has
+        // the surface as color target. While the scene is shown in a panel, nothing was drawn
+        // on the surface yet, so the pass clears it, and it uses a depth texture of its own.
+        #[cfg(feature = "gui")]
+        let gui_depth_texture_view = self
+            .scene_panel
+            .as_ref()
+            .map_or(&self.depth_texture_view, ScenePanel::gui_depth_texture_view);
+        #[cfg(feature = "gui")]
+        {
+            encoder.push_debug_group("GUI");
+            let load = match self.scene_panel {
+                Some(_) => wgpu::LoadOp::Clear(self.surface_clear_color()),
+                None => wgpu::LoadOp::Load,
+            };
+            let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("GUI Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &surface_texture_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.19,
-                            g: 0.24,
-                            b: 0.42,
-                            a: 1.0,
-                        }),
+                        load,
                         store: wgpu::StoreOp::Store,
                     },
                 })],
                 depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: &self.depth_texture_view,
+                    view: gui_depth_texture_view,
                     depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: self
+                    .gpu_timer
+                    .as_ref()
+                    .and_then(|timer| timer.timestamp_writes(TimedPass::Gui)),
+                occlusion_query_set: None,
+            });
+
+            self.egui_renderer.render(
+                &mut render_pass.forget_lifetime(),
+                &paint_jobs,
+                &screen_descriptor,
+            );
+            encoder.pop_debug_group();
+        }
+
+        // For a GUI capture, the GUI is drawn once more, alone, over a transparent background.
+        #[cfg(feature = "gui")]
+        if let Some(capture_view) = self.ui_capture.begin(
+            &self.gpu.device,
+            self.gpu.surface_config.width,
+            self.gpu.surface_config.height,
+            self.gpu.surface_config.format,
+        ) {
+            encoder.push_debug_group("GUI Capture");
+            let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("GUI Capture Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &capture_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: gui_depth_texture_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
                         store: wgpu::StoreOp::Store,
                     }),
                     stencil_ops: None,
@@ -567,16 +3149,135 @@ impl Renderer {
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
-            self.scene.render(&mut render_pass);
 
             self.egui_renderer.render(
                 &mut render_pass.forget_lifetime(),
                 &paint_jobs,
                 &screen_descriptor,
             );
+            self.ui_capture.encode_copy(&self.gpu.device, &mut encoder);
+            encoder.pop_debug_group();
         }
 
-        self.gpu.queue.submit(std::iter::once(encoder.finish()));
+        encoder.push_debug_group("Frame Copies");
+        if let Some(gpu_timer) = self.gpu_timer.as_mut() {
+            gpu_timer.encode_resolve(&mut encoder);
+        }
+        #[cfg(feature = "interop")]
+        if let Some(shared_frame) = &self.shared_frame {
+            shared_frame.encode_copy(&mut encoder, surface_texture.texture());
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(streamer) = self.streamer.as_mut() {
+            streamer.encode_copy(&self.gpu.device, &mut encoder, surface_texture.texture());
+        }
+        self.frame_capture
+            .encode_copy(&self.gpu.device, &mut encoder, surface_texture.texture());
+        encoder.pop_debug_group();
+
+        // The streamed uploads are copied before any of the frame's passes read them.
+        let submission_scope = self.cpu_profiler.scope("Command Submission");
+        let uploads = self.upload_belt.finish();
+        self.gpu
+            .queue
+            .submit(uploads.into_iter().chain(std::iter::once(encoder.finish())));
+        self.upload_belt.after_submit(&self.gpu.queue);
+        self.frame_stats.record(frame_time, cpu_start.elapsed());
+        self.depth_probe.after_submit();
+        self.picker.after_submit();
+        self.deletion_queue.after_submit(&self.gpu.queue);
+        if let Some(gpu_timer) = self.gpu_timer.as_mut() {
+            gpu_timer.after_submit();
+        }
+        self.frame_capture.after_submit();
+        #[cfg(feature = "gui")]
+        self.ui_capture.after_submit();
+        #[cfg(feature = "interop")]
+        if let Some(shared_frame) = &self.shared_frame {
+            shared_frame.after_submit(&self.gpu.queue);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(streamer) = self.streamer.as_mut() {
+            streamer.after_submit();
+        }
         surface_texture.present();
+        drop(submission_scope);
+
+        // Background work is submitted behind the frame, so it never delays it.
+        self.background
+            .run(&self.gpu.device, &self.gpu.queue, frame_time);
+
+        Ok(())
+    }
+}
+
+/// A draw of the scene pass, ordered by the renderer's [`RenderQueue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SceneDraw {
+    /// The sky behind everything.
+    #[cfg(feature = "scene3d")]
+    Sky,
+
+    /// The scene's mesh.
+    Scene,
+
+    /// The crowd's characters at one level of detail.
+    #[cfg(feature = "scene3d")]
+    CrowdLod(usize),
+
+    /// The particles of the particle demo.
+    #[cfg(feature = "scene3d")]
+    Particles,
+
+    /// The infinite ground grid.
+    Grid,
+
+    /// The world axes.
+    Axes,
+
+    /// The grid and debug lines.
+    Lines,
+
+    /// The scene's labels, and the node names.
+    Labels,
+}
+
+impl SceneDraw {
+    /// Returns the id of the draw's pipeline, which orders the pipelines in the pass.
+    ///
+    /// The sky ignores the depth buffer, so it must come first, and the particles, the
+    /// infinite grid and axes, the lines, and the labels are blended over the opaque geometry,
+    /// so they come last.
+    fn pipeline_id(self) -> u16 {
+        match self {
+            #[cfg(feature = "scene3d")]
+            SceneDraw::Sky => 0,
+            SceneDraw::Scene => 1,
+            #[cfg(feature = "scene3d")]
+            SceneDraw::CrowdLod(_) => 2,
+            #[cfg(feature = "scene3d")]
+            SceneDraw::Particles => 3,
+            SceneDraw::Grid => 4,
+            SceneDraw::Axes => 5,
+            SceneDraw::Lines => 6,
+            SceneDraw::Labels => 7,
+        }
+    }
+
+    /// Returns the name of the draw's debug group in the scene pass.
+    fn label(self) -> &'static str {
+        match self {
+            #[cfg(feature = "scene3d")]
+            SceneDraw::Sky => "Sky",
+            SceneDraw::Scene => "Scene Meshes",
+            #[cfg(feature = "scene3d")]
+            SceneDraw::CrowdLod(_) => "Crowd",
+            #[cfg(feature = "scene3d")]
+            SceneDraw::Particles => "Particles",
+            SceneDraw::Grid => "Grid",
+            SceneDraw::Axes => "Axes",
+            SceneDraw::Lines => "Lines",
+            SceneDraw::Labels => "Labels",
+        }
     }
 }
@@ -0,0 +1,81 @@
+//! # Rulers Module
+//!
+//! The `rulers` module provides the measurements behind the in-viewport rulers and the
+//! screen-space guides used to compose captures and align HUD elements.
+//!
+//! ## Overview
+//!
+//! - The top and left rulers measure the viewport in pixels, starting at its top-left corner.
+//! - The bottom and right rulers measure world units at the camera's focus plane, starting at
+//!   the viewport's center. [`world_units_per_pixel`] converts between the two.
+//! - [`tick_step`] picks a round tick spacing (1, 2, or 5 times a power of ten) that keeps the
+//!   labels readable at any zoom, and [`tick_indices`] enumerates the ticks in a range.
+//! - A [`Guide`] is a horizontal or vertical line across the viewport. Guides are dragged out
+//!   of the rulers, moved by dragging, and removed by dragging them back onto a ruler or out of
+//!   the viewport. Their positions are fractions of the viewport, so they keep their place in
+//!   the composition when the window is resized.
+//!
+//! The rulers and guides are painted on the GUI's 2D overlay by `ui::show_rulers`, and toggled
+//! from the View menu.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let units_per_pixel = world_units_per_pixel(
+//!     Scene::FIELD_OF_VIEW_DEGREES.to_radians(),
+//!     camera.focus_distance(),
+//!     viewport.height,
+//! );
+//! let step = tick_step(units_per_pixel, 50.0);
+//! ```
+
+/// The direction a [`Guide`] runs in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GuideAxis {
+    /// A line from left to right, at a height.
+    Horizontal,
+
+    /// A line from top to bottom, at a horizontal position.
+    Vertical,
+}
+
+/// A guide line across the viewport.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guide {
+    /// The direction the guide runs in.
+    pub axis: GuideAxis,
+
+    /// The guide's position across its axis, as a fraction of the viewport's height (for
+    /// horizontal guides) or width (for vertical guides), from the top-left corner.
+    pub position: f32,
+}
+
+/// Returns the size of one physical pixel in world units, at `focus_distance` in front of a
+/// camera with the vertical field of view `fov_y` (in radians), in a viewport `height` pixels
+/// high.
+pub fn world_units_per_pixel(fov_y: f32, focus_distance: f32, height: u32) -> f32 {
+    2.0 * focus_distance * (fov_y * 0.5).tan() / height.max(1) as f32
+}
+
+/// Returns the smallest round step (1, 2, or 5 times a power of ten, in units) whose ticks are
+/// at least `min_spacing` apart, when one unit spans `1 / units_per_point` points.
+pub fn tick_step(units_per_point: f32, min_spacing: f32) -> f32 {
+    let min_step = (units_per_point * min_spacing).max(f32::MIN_POSITIVE);
+    let magnitude = 10_f32.powf(min_step.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= min_step)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Returns the indices `k` of the ticks `k * step` between `start` and `end`, inclusive.
+pub fn tick_indices(start: f32, end: f32, step: f32) -> std::ops::RangeInclusive<i64> {
+    let (low, high) = (start.min(end), start.max(end));
+    (low / step).ceil() as i64..=(high / step).floor() as i64
+}
+
+/// Returns the number of decimals needed to label ticks `step` apart.
+pub fn tick_decimals(step: f32) -> usize {
+    (-step.log10().floor()).max(0.0) as usize
+}
@@ -0,0 +1,153 @@
+//! # Safe Area Module
+//!
+//! The `safe_area` module reports the platform's safe-area insets: the margins along the
+//! window's edges that are covered by hardware or system UI, such as the notch and home
+//! indicator on iOS, or browser UI overlapping a fullscreen page.
+//!
+//! ## Overview
+//!
+//! - [`SafeAreaInsets`] holds the four insets in points, the unit `egui` lays out in.
+//! - [`SafeAreaInsets::query`] reads them from the platform:
+//!   - **iOS**: `winit` reports the safe area as the window's inner rectangle, so the insets
+//!     are the distances between the inner and the outer rectangle.
+//!   - **Web**: The browser exposes the insets as the CSS `env(safe-area-inset-*)` values,
+//!     read from the computed padding of a hidden probe element.
+//!   - **Other platforms**: There are no insets.
+//!
+//! The `App` queries the insets whenever the window changes size or scale, and exposes them
+//! with `App::safe_area_insets`. The GUI keeps its panels and windows inside the safe region and
+//! can visualize the insets in a debug overlay. To test layouts on a desktop, the "Simulate
+//! Notch" setting replaces the insets with [`SafeAreaInsets::SIMULATED_NOTCH`].
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let insets = SafeAreaInsets::query(&window);
+//! if !insets.is_zero() {
+//!     log::info!("Safe area insets: {insets:?}");
+//! }
+//! ```
+
+/// The margins along the window's edges that are not safe to place content in, in points.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SafeAreaInsets {
+    /// The inset from the top edge.
+    pub top: f32,
+
+    /// The inset from the right edge.
+    pub right: f32,
+
+    /// The inset from the bottom edge.
+    pub bottom: f32,
+
+    /// The inset from the left edge.
+    pub left: f32,
+}
+
+impl SafeAreaInsets {
+    /// The insets of a phone in portrait orientation with a notch and a home indicator, used
+    /// to preview safe-area layouts on platforms without insets.
+    pub const SIMULATED_NOTCH: SafeAreaInsets = SafeAreaInsets {
+        top: 47.0,
+        right: 0.0,
+        bottom: 34.0,
+        left: 0.0,
+    };
+
+    /// Returns the insets multiplied by `factor`, such as to convert them to the points of a
+    /// zoomed GUI.
+    pub fn scaled(&self, factor: f32) -> Self {
+        Self {
+            top: self.top * factor,
+            right: self.right * factor,
+            bottom: self.bottom * factor,
+            left: self.left * factor,
+        }
+    }
+
+    /// Returns `true` if no edge is inset.
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Reads the current insets of `window` from the platform.
+    pub fn query(window: &winit::window::Window) -> Self {
+        #[cfg(target_os = "ios")]
+        {
+            Self::from_inner_and_outer(window)
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = window;
+            Self::from_css().unwrap_or_default()
+        }
+
+        #[cfg(not(any(target_os = "ios", target_arch = "wasm32")))]
+        {
+            let _ = window;
+            Self::default()
+        }
+    }
+
+    /// Derives the insets from the safe inner rectangle within the window's outer rectangle,
+    /// as reported by `winit` on iOS.
+    #[cfg(target_os = "ios")]
+    fn from_inner_and_outer(window: &winit::window::Window) -> Self {
+        let (Ok(inner), Ok(outer)) = (window.inner_position(), window.outer_position()) else {
+            return Self::default();
+        };
+        let (inner_size, outer_size) = (window.inner_size(), window.outer_size());
+        let scale_factor = window.scale_factor() as f32;
+
+        let left = (inner.x - outer.x) as f32;
+        let top = (inner.y - outer.y) as f32;
+        let right = outer_size.width as f32 - inner_size.width as f32 - left;
+        let bottom = outer_size.height as f32 - inner_size.height as f32 - top;
+        Self {
+            top: top.max(0.0) / scale_factor,
+            right: right.max(0.0) / scale_factor,
+            bottom: bottom.max(0.0) / scale_factor,
+            left: left.max(0.0) / scale_factor,
+        }
+    }
+
+    /// Reads the CSS `env(safe-area-inset-*)` values, which are in CSS pixels (points).
+    ///
+    /// The browser only reports insets if the page's viewport meta tag contains
+    /// `viewport-fit=cover`.
+    #[cfg(target_arch = "wasm32")]
+    fn from_css() -> Option<Self> {
+        const PROBE_STYLE: &str = "position: fixed; visibility: hidden; pointer-events: none; \
+            padding: env(safe-area-inset-top, 0px) env(safe-area-inset-right, 0px) \
+            env(safe-area-inset-bottom, 0px) env(safe-area-inset-left, 0px);";
+
+        let window = web_sys::window()?;
+        let document = window.document()?;
+        let probe = document.create_element("div").ok()?;
+        probe.set_attribute("style", PROBE_STYLE).ok()?;
+        document.body()?.append_child(&probe).ok()?;
+
+        let insets = window
+            .get_computed_style(&probe)
+            .ok()
+            .flatten()
+            .map(|style| {
+                let inset = |property: &str| {
+                    style
+                        .get_property_value(property)
+                        .ok()
+                        .and_then(|value| value.trim_end_matches("px").parse::<f32>().ok())
+                        .unwrap_or(0.0)
+                };
+                Self {
+                    top: inset("padding-top"),
+                    right: inset("padding-right"),
+                    bottom: inset("padding-bottom"),
+                    left: inset("padding-left"),
+                }
+            });
+        probe.remove();
+        insets
+    }
+}
@@ -27,10 +27,10 @@
 //! ## Example Usage
 //! ```rust
 //! // 1. Create a new scene instance.
-//! let scene = Scene::new(&device, surface_format);
+//! let scene = Scene::new(&device, &queue, surface_format, None);
 //!
 //! // 2. Update the scene with the current frame parameters.
-//! scene.update(&queue, aspect_ratio, delta_time);
+//! scene.update(&mut uploads, aspect_ratio, delta_time);
 //!
 //! // 3. Render the scene.
 //! let mut render_pass = encoder.begin_render_pass(&render_pass_descriptor);
@@ -51,15 +51,25 @@
 //! components needed for real-time rendering. Below is a summary of its key fields and functions:
 //!
 //! ### Fields
-//! - **`model (Mat4)`**: Stores the transformation (local-to-world) applied to the 3D object.
-//! - **`vertex_buffer (wgpu::Buffer)`**: Holds vertex geometry data (positions, colors, etc.).
-//! - **`index_buffer (wgpu::Buffer)`**: Stores indices for efficient vertex reuse.
-//! - **`uniform (UniformBinding)`**: Manages the uniform buffer for shader parameters.
-//! - **`pipeline (wgpu::RenderPipeline)`**: Specifies how the GPU renders using shaders and other settings.
+//! - **`graph (SceneGraph)`**: The nodes of the scene, each with a local transform, children,
+//!   and optionally a mesh. See the `scene_graph` module.
+//! - **`meshes (Vec<SceneMesh>)`**: The meshes drawn, each with vertex and index buffers. The
+//!   triangle by default, or the meshes of a model loaded with the `model` module.
+//! - **`uniform (UniformBinding)`**: Manages the uniform buffer for shader parameters, holding
+//!   the MVP matrix of the first node drawn. Every further node drawing a mesh has a uniform
+//!   buffer of its own.
+//! - **`materials (SceneMaterials)`**: The materials the meshes are shaded with, each bound
+//!   before the meshes holding it, and the textures they sample. See the `material` module.
+//! - **`pipelines (HashMap<Rasterization, wgpu::RenderPipeline>)`**: Specify how the GPU renders using
+//!   shaders and other settings, one pipeline per supported [`RenderMode`] with and without the
+//!   depth test.
+//! - **`instances (Option<InstanceBuffer>)`**: The instances each mesh is drawn with, set with
+//!   `Scene::set_instances` and drawn with pipelines of their own. See the `instancing` module.
 //!
 //! ### Methods
 //! - **`new()`**: Creates and initializes a new `Scene` instance, including buffers and pipeline.
 //! - **`update()`**: Adjusts the scene's state, such as the transformation matrix, to reflect changes in time or user input.
+//! - **`add_node()`**, **`remove_node()`**, **`add_mesh()`**: Compose the scene of several objects.
 //! - **`render()`**: Issues draw commands to render the `Scene` using the initialized GPU state.
 //!
 //! ## Design Goals
@@ -67,20 +77,20 @@
 //! with an easy-to-use API for rendering objects in 3D. While providing flexibility for customizations,
 //! it also ensures performance is optimized when interacting with the GPU.
 
-// Importing the `Renderer` struct from the `renderer` module, which interacts with this module 
-// to render the 3D scene by utilizing the GPU resources and rendering pipelines provided 
+// Importing the `Renderer` struct from the `renderer` module, which interacts with this module
+// to render the 3D scene by utilizing the GPU resources and rendering pipelines provided
 // by the `Renderer`. This integration enables seamless rendering of the scene alongside GUI elements.
 use crate::renderer::Renderer;
 
 // Importing the `Vertex` struct and the `VERTICES` array from the `vertex` module.
-// - `Vertex` represents the structure for a single vertex, typically containing position, 
+// - `Vertex` represents the structure for a single vertex, typically containing position,
 //   normal, color, or texture coordinate data. It defines the layout of our vertex data.
 // - `VERTICES` is an array that contains the actual vertex data used to construct
 //   the geometry of the 3D objects in the scene. This data is uploaded to the GPU
 //   and utilized in the rendering process.
 use crate::vertex::{Vertex, VERTICES};
 
-// Importing the `UniformBuffer` struct, which represents the uniform buffer used 
+// Importing the `UniformBuffer` struct, which represents the uniform buffer used
 // to pass data such as the Model-View-Projection (MVP) matrix from the CPU to the GPU.
 // It is used in the `Scene` module to manage per-frame transformation data for rendering.
 use crate::uniform_buffer::UniformBuffer;
@@ -100,6 +110,58 @@ use crate::INDICES;
 // to transform vertices and determine the color of rendered fragments.
 use crate::SHADER_SOURCE;
 
+// Importing the simulation step and snapshot type. The scene's animation is defined in the
+// `simulation` module so it can run either here or on a dedicated simulation thread.
+use crate::simulation::{animate, SceneSnapshot, TICK};
+
+// Importing the scene graph, which places the scene's objects relative to each other.
+use crate::scene_graph::{MeshHandle, Node, NodeId, SceneGraph};
+
+// Importing the bind group of `shader_source.wgsl`, to bind a uniform buffer per drawn node.
+use crate::shader_bindings::shader_source;
+
+// Importing the environment settings and their uniform buffer, which light the scene and
+// apply fog and exposure.
+use crate::environment::{Environment, EnvironmentBinding};
+
+// Importing the scene's directional light and its uniform buffer, which shade the surfaces.
+use crate::lighting::{Light, LightBinding};
+
+// Importing the projection and bounds, which derive the clip planes from the scene's extent.
+use crate::camera::{Bounds, Projection};
+
+// Importing the scene's texture, sampled by materials without a texture of their own.
+use crate::texture::Texture;
+
+// Importing the materials the meshes are shaded with, and their bind groups.
+use crate::material::{Material, MaterialBinding, MaterialHandle, SceneMaterials, TextureHandle};
+
+// Importing the per-instance data and the shader reading it, for drawing the meshes many times
+// with one draw call.
+use crate::instancing::{Instance, INSTANCED_SHADER_SOURCE};
+
+// Importing the error returned when an edited shader cannot replace the scene's shader.
+use crate::shader_editor::ShaderError;
+
+// Importing the pending load awaiting the validation of an edited shader, which the browser
+// reports in a later frame.
+use crate::asset::PendingLoad;
+
+// Importing the width of the kind of object ids, below the index of the node writing them.
+use crate::probe::OBJECT_KIND_BITS;
+
+// Importing the text labels drawn at points of the scene.
+use crate::labels::{Label, LabelId};
+
+// Importing the belt the per-frame uniform writes are streamed through.
+use crate::gpu::UploadBelt;
+
+// Importing the view frustum the meshes are culled against, and the counts of culled meshes.
+use crate::frustum::{CullStats, Frustum};
+
+// Importing `HashMap` to look up the pipeline of the selected render mode.
+use std::collections::HashMap;
+
 /// Represents a 3D scene that contains a model, its associated buffers, and the
 /// rendering pipeline configuration.
 ///
@@ -109,14 +171,15 @@ use crate::SHADER_SOURCE;
 ///
 /// # Fields
 ///
-/// - `model`: The model transformation matrix (4x4) used to apply transformations
-///   like translation, rotation, and scaling to the scene's object.
-/// - `vertex_buffer`: A `wgpu::Buffer` that contains the vertex data for the object.
-/// - `index_buffer`: A `wgpu::Buffer` used to store the index data defining
-///   the object's geometry.
+/// - `graph`: The scene's nodes, whose world transforms apply translation, rotation, and
+///   scaling to the meshes they draw.
+/// - `meshes`: The meshes drawn, each with a vertex buffer and an index buffer defining
+///   its geometry.
 /// - `uniform`: A `UniformBinding` that manages the uniform buffer for shaders.
 ///   This typically includes the model-view-projection (MVP) matrix.
-/// - `pipeline`: A `wgpu::RenderPipeline` that defines how the scene is rendered.
+/// - `pipelines`: The `wgpu::RenderPipeline`s that define how the scene is rendered, one per
+///   supported rasterization.
+/// - `rasterization`: The render mode and depth test the scene is drawn with.
 ///
 /// # Methods
 ///
@@ -130,40 +193,39 @@ use crate::SHADER_SOURCE;
 ///
 /// ```rust
 /// // Create a new scene with the device and surface format.
-/// let scene = Scene::new(&device, surface_format);
+/// let scene = Scene::new(&device, &queue, surface_format, None);
 ///
 /// // Update the scene before rendering.
-/// scene.update(&queue, aspect_ratio, delta_time);
+/// scene.update(&mut uploads, aspect_ratio, delta_time);
 ///
 /// // Render the scene.
 /// let mut render_pass = encoder.begin_render_pass(&render_pass_descriptor);
 /// scene.render(&mut render_pass);
 /// ```
 pub struct Scene {
-    /// The model transformation matrix (4x4) used for applying transformations
-    /// such as translation, rotation, and scaling to the objects in the scene.
-    /// This matrix defines the local-to-world space transformation of the
-    /// rendered object and impacts how the object is positioned, oriented, and
-    /// scaled within the 3D world. It is typically updated in the `update` method
-    /// of the `Scene` based on the current frame's parameters (e.g., elapsed time).
-    pub model: nalgebra_glm::Mat4,
-
-    /// A `wgpu::Buffer` that stores the vertex data for the objects in the scene.
-    ///
-    /// This buffer contains the positions, normals, texture coordinates, or other
-    /// attributes of the vertices that define the geometry of the object(s) being rendered.
-    /// It is used by the GPU during the rendering process to accurately
-    /// display the 3D object. The data in this buffer is set during the creation
-    /// of the `Scene` instance and remains immutable during runtime.
-    pub vertex_buffer: wgpu::Buffer,
+    /// The hierarchy of nodes placing the scene's objects, queried with [`Scene::graph`] and
+    /// changed with [`Scene::add_node`], [`Scene::remove_node`], and [`Scene::node_mut`].
+    ///
+    /// The graph's transform is animated by the simulation in the `tick` method, and the
+    /// world matrices of the nodes are propagated from it before every upload.
+    graph: SceneGraph,
+
+    /// The time the nodes have been spinning for, in seconds.
+    time: f32,
+
+    /// The graph's transform and `time` before the last [`Scene::tick`], from which the
+    /// rendered state is interpolated towards the current one.
+    previous_state: (crate::math::Mat4, f32),
 
-    /// A `wgpu::Buffer` used to store the index data for defining the geometry of the object(s) in the scene.
+    /// The length of the last [`Scene::tick`], in seconds.
+    tick_step: f32,
+
+    /// The meshes drawn by the scene's nodes, all with the same pipeline.
     ///
-    /// The index buffer contains indices pointing to entries in the vertex buffer, enabling the GPU to render
-    /// complex shapes efficiently by reusing vertices. This technique is called indexed drawing and minimizes
-    /// the amount of vertex data stored and transferred to the GPU. The data in this buffer is immutable during
-    /// runtime and is set during the creation of the `Scene` instance.
-    pub index_buffer: wgpu::Buffer,
+    /// The scene starts with the triangle defined by `VERTICES` and `INDICES`. The meshes are
+    /// replaced with [`Scene::set_meshes`], such as with the meshes of a loaded glTF model, and
+    /// added to with [`Scene::add_mesh`].
+    pub meshes: Vec<SceneMesh>,
 
     /// A `UniformBinding` that manages the uniform buffer for shaders.
     ///
@@ -176,17 +238,75 @@ pub struct Scene {
     /// are reflected appropriately in the rendered scene.
     pub uniform: UniformBinding,
 
-    /// The `wgpu::RenderPipeline` used to define how the scene is rendered.
+    /// The uniform buffers of the nodes drawn after the first, one per node in drawing order,
+    /// with bind groups of the layout of `uniform`. Grown as nodes are added, never shrunk.
+    node_uniforms: Vec<NodeUniform>,
+
+    /// The `wgpu::RenderPipeline`s used to define how the scene is rendered, one for each
+    /// rasterization the device supports.
     ///
-    /// This pipeline encapsulates the GPU state and specifies the shader programs,
+    /// Each pipeline encapsulates the GPU state and specifies the shader programs,
     /// rasterizer settings, blending modes, and other configurations required
-    /// during the rendering process. The pipeline ensures that the GPU renders
-    /// the scene consistently according to the specified graphics pipeline state.
+    /// during the rendering process. The pipelines differ only in how triangles are
+    /// rasterized and whether they are depth tested.
     ///
-    /// It is created during the initialization of the `Scene` via the `create_pipeline`
+    /// They are created during the initialization of the `Scene` via the `create_pipelines`
     /// method, which sets up the vertex and fragment shaders, as well as defines how
     /// the vertex data and output color formats are processed.
-    pub pipeline: wgpu::RenderPipeline,
+    pipelines: HashMap<Rasterization, wgpu::RenderPipeline>,
+
+    /// The pipelines drawing the meshes once per instance, one for each rasterization the
+    /// device supports, used instead of `pipelines` while instances are set.
+    instanced_pipelines: HashMap<Rasterization, wgpu::RenderPipeline>,
+
+    /// The instances the meshes are drawn with, set with [`Scene::set_instances`], or `None`
+    /// to draw each mesh once.
+    instances: Option<InstanceBuffer>,
+
+    /// The render mode and depth test the scene is drawn with, set with
+    /// [`Scene::set_rasterization`].
+    rasterization: Rasterization,
+
+    /// The view-projection matrix used for the most recent frame.
+    ///
+    /// Kept on the CPU so screen positions (such as the depth probe's cursor position) can be
+    /// unprojected back into world space.
+    pub view_projection: crate::math::Mat4,
+
+    /// The position of the camera in world space, used for distance-based level of detail.
+    pub camera_position: crate::math::Vec3,
+
+    /// The camera's view matrix, set with [`Scene::set_camera`].
+    pub view: crate::math::Mat4,
+
+    /// The camera's field of view and clip planes, sanitized before every use.
+    pub projection: Projection,
+
+    /// The uniform buffer holding the environment (ambient light, fog, exposure, and sky)
+    /// the scene is drawn with, bound at set 1.
+    pub environment: EnvironmentBinding,
+
+    /// The scene's own environment, used instead of the renderer's global one if set.
+    pub environment_override: Option<Environment>,
+
+    /// The materials the meshes are shaded with, bound at set 2 before the meshes holding
+    /// them, and the textures they sample.
+    pub materials: SceneMaterials,
+
+    /// The uniform buffer holding the directional light the scene is shaded with, bound at
+    /// set 3.
+    pub light: LightBinding,
+
+    /// The scene's text labels, added with [`Scene::add_label`]. Removed labels leave an
+    /// empty slot, so the ids of the others stay valid.
+    labels: Vec<Option<Label>>,
+
+    /// Whether each node drawing a mesh is at least partly inside the view frustum, in drawing
+    /// order, as of the last upload. Nodes outside are skipped by [`Scene::draw`].
+    visible: Vec<bool>,
+
+    /// The number of meshes drawn and culled as of the last upload.
+    cull_stats: CullStats,
 }
 
 /// Implementation of methods for the `Scene` struct.
@@ -209,6 +329,19 @@ pub struct Scene {
 /// This implementation relies on external tools and libraries such as `nalgebra-glm`
 /// for matrix math and `wgpu` for interfacing with the GPU.
 impl Scene {
+    /// The kind of object id the scene's nodes write to the object id target. `0` means
+    /// background. See [`Scene::node_object_id`].
+    pub const OBJECT_ID: u32 = 1;
+
+    /// The name shown for [`Scene::OBJECT_ID`] by the depth probe.
+    pub const OBJECT_NAME: &'static str = "Triangle";
+
+    /// The format of the object id target written alongside the color target.
+    pub const OBJECT_ID_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
+    /// The camera's default vertical field of view, in degrees. See [`Projection`].
+    pub const FIELD_OF_VIEW_DEGREES: f32 = 80.0;
+
     /// Creates a new `Scene` instance with the necessary GPU resources for rendering.
     ///
     /// This method sets up the vertex buffer, index buffer, uniform buffer, and
@@ -218,7 +351,9 @@ impl Scene {
     /// # Parameters
     ///
     /// - `device`: A reference to the `wgpu::Device`, which is used to create and manage GPU resources.
+    /// - `queue`: The `wgpu::Queue` uploading the default white texture.
     /// - `surface_format`: The `wgpu::TextureFormat` that defines the texture format for the rendering target.
+    /// - `cache`: The pipeline cache to compile the render pipeline through, if any.
     ///
     /// # Returns
     ///
@@ -232,51 +367,22 @@ impl Scene {
     /// # Examples
     ///
     /// ```
-    /// let scene = Scene::new(&device, surface_format);
+    /// let scene = Scene::new(&device, &queue, surface_format, pipeline_cache.cache());
     /// ```
-    pub fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
-        // The `wgpu::Buffer` that stores vertex data for the `Scene`.
-        //
-        // This buffer contains the vertices required to define the shape or geometry
-        // of the 3D object(s) in the scene. The data includes attributes like position,
-        // color, normals, or texture coordinates, depending on the defined vertex structure.
-        //
-        // The vertex buffer is uploaded to GPU memory and is used during the rendering process
-        // in the vertex shader stage.
-        //
-        // It is initialized using `wgpu::util::DeviceExt::create_buffer_init` for simplicity,
-        // and the contents are sourced from the `VERTICES` array.
-        let vertex_buffer = wgpu::util::DeviceExt::create_buffer_init(
-            device,
-            &wgpu::util::BufferInitDescriptor {
-                label: Some("Vertex Buffer"),
-                contents: bytemuck::cast_slice(&VERTICES),
-                usage: wgpu::BufferUsages::VERTEX,
-            },
-        );
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        surface_format: wgpu::TextureFormat,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> Self {
+        // The scene's meshes, the triangle defined by the `VERTICES` and `INDICES` arrays
+        // until a model is loaded. Each mesh uploads its vertex data, and the indices that
+        // define how its vertices are connected into triangles, into GPU buffers.
+        let meshes = vec![SceneMesh::new(device, &MeshData::triangle())];
 
-        // The `wgpu::Buffer` that stores index data for the `Scene`.
-        //
-        // This buffer contains the indices that define how the vertices in the vertex buffer
-        // are connected to form geometric primitives, such as triangles, lines, or points.
-        //
-        // Using an index buffer allows for efficient reuse of vertex data by referencing
-        // shared vertices rather than duplicating them for each primitive.
-        //
-        // The index buffer is allocated in GPU memory and is used during the rendering process
-        // in conjunction with the vertex buffer. It is especially useful when rendering
-        // complex models or scenes with a high degree of shared geometry.
-        //
-        // It is initialized using `wgpu::util::DeviceExt::create_buffer_init` for ease of
-        // creation, and its contents are sourced from the `INDICES` array.
-        let index_buffer = wgpu::util::DeviceExt::create_buffer_init(
-            device,
-            &wgpu::util::BufferInitDescriptor {
-                label: Some("index Buffer"),
-                contents: bytemuck::cast_slice(&INDICES),
-                usage: wgpu::BufferUsages::INDEX,
-            },
-        );
+        // A single node drawing the triangle.
+        let mut graph = SceneGraph::default();
+        graph.add(None, Node::new(Self::OBJECT_NAME).with_mesh(MeshHandle(0)));
 
         // The `UniformBinding` structure that handles the uniform buffer and its associated bind group.
         //
@@ -308,17 +414,457 @@ impl Scene {
         //
         // The `RenderPipeline` is a core component of the rendering process, binding together
         // the rendering state and ensuring that the `Scene` is drawn correctly.
-        let pipeline = Self::create_pipeline(device, surface_format, &uniform);
+        let environment = EnvironmentBinding::new(device);
+
+        // The default material, sampling white textures so the vertex colors show unchanged
+        // until materials and a texture are set.
+        let materials = SceneMaterials::new(device, queue);
+
+        // The directional light, holding the default light until one is uploaded.
+        let light = LightBinding::new(device);
+
+        let pipelines = Self::create_pipelines(
+            device,
+            surface_format,
+            &uniform,
+            &environment,
+            &materials.bind_group_layout,
+            &light,
+            cache,
+            SceneShader::Single(SHADER_SOURCE),
+        );
+        let instanced_pipelines = Self::create_pipelines(
+            device,
+            surface_format,
+            &uniform,
+            &environment,
+            &materials.bind_group_layout,
+            &light,
+            cache,
+            SceneShader::Instanced,
+        );
 
         Self {
-            model: nalgebra_glm::Mat4::identity(),
+            previous_state: (graph.transform, 0.0),
+            graph,
+            time: 0.0,
+            tick_step: 0.0,
             uniform,
-            pipeline,
-            vertex_buffer,
-            index_buffer,
+            node_uniforms: Vec::new(),
+            pipelines,
+            instanced_pipelines,
+            instances: None,
+            rasterization: Rasterization::default(),
+            meshes,
+            view_projection: crate::math::identity(),
+            camera_position: crate::math::vec3(0.0, 0.0, 3.0),
+            view: crate::math::look_at_lh(
+                &crate::math::vec3(0.0, 0.0, 3.0),
+                &crate::math::zeros(),
+                &crate::math::up(),
+            ),
+            projection: Projection::default(),
+            environment,
+            environment_override: None,
+            materials,
+            light,
+            labels: Vec::new(),
+            visible: Vec::new(),
+            cull_stats: CullStats::default(),
         }
     }
 
+    /// Uploads the environment the scene is drawn with: its own override if set, and `global`
+    /// otherwise.
+    ///
+    /// Call this after updating the scene, so the sky follows the current camera.
+    pub fn upload_environment(&self, queue: &wgpu::Queue, global: &Environment) {
+        let environment = self.environment_override.as_ref().unwrap_or(global);
+        self.environment
+            .update(queue, environment, &self.view_projection);
+    }
+
+    /// Uploads the directional light the scene is shaded with, seen from the camera set with
+    /// [`Scene::set_camera`].
+    pub fn upload_light(&self, queue: &wgpu::Queue, light: &Light) {
+        self.light.update(queue, light, &self.camera_position);
+    }
+
+    /// Returns the bounds of the scene's geometry in world space.
+    ///
+    /// The animation spins the geometry around its center, so the bounds cover the geometry
+    /// in every orientation rather than following the graph's transform frame by frame.
+    pub fn bounds(&self) -> Bounds {
+        let local = self
+            .graph
+            .bounds(|mesh| match &self.instances {
+                Some(instances) => Some(instances.bounds),
+                None => self.meshes.get(mesh.0).map(|mesh| mesh.bounds),
+            })
+            .unwrap_or(Bounds {
+                min: crate::math::zeros(),
+                max: crate::math::zeros(),
+            });
+        Self::spun_bounds(&local)
+    }
+
+    /// Returns the same bounds as [`Scene::bounds`] for the triangle without a `Scene`, for
+    /// backends drawing its geometry without the GPU.
+    #[cfg(all(feature = "software", not(target_arch = "wasm32")))]
+    pub(crate) fn geometry_bounds() -> Bounds {
+        let local = MeshData::triangle()
+            .bounds()
+            .expect("the triangle has vertices");
+        Self::spun_bounds(&local)
+    }
+
+    /// Returns bounds covering `local` in every orientation around its center.
+    fn spun_bounds(local: &Bounds) -> Bounds {
+        let radius = crate::math::vec3(local.radius(), local.radius(), local.radius());
+        Bounds {
+            min: local.center() - radius,
+            max: local.center() + radius,
+        }
+    }
+
+    /// Replaces the scene's meshes with `meshes`, uploaded to the GPU, and returns the replaced
+    /// ones. Frames in flight may still draw the replaced meshes, so their buffers must be
+    /// released through the renderer's deletion queue.
+    ///
+    /// The scene's nodes are replaced, too, by one root node per mesh drawing it.
+    ///
+    /// Meshes without triangles are skipped. If no mesh remains, the meshes are kept and
+    /// nothing is returned.
+    pub fn set_meshes(&mut self, device: &wgpu::Device, meshes: &[MeshData]) -> Vec<SceneMesh> {
+        let meshes: Vec<_> = meshes
+            .iter()
+            .filter_map(|mesh| SceneMesh::new_checked(device, mesh))
+            .collect();
+        if meshes.is_empty() {
+            return Vec::new();
+        }
+        self.graph.clear();
+        for (index, mesh) in meshes.iter().enumerate() {
+            let node = Node::new(mesh.name.clone()).with_mesh(MeshHandle(index));
+            self.graph.add(None, node);
+        }
+        self.reserve_node_uniforms(device);
+        let replaced = std::mem::replace(&mut self.meshes, meshes);
+        self.update_instance_bounds();
+        replaced
+    }
+
+    /// Uploads `mesh` and adds it to the scene's meshes, without drawing it, and returns its
+    /// handle to draw it with nodes added by [`Scene::add_node`].
+    ///
+    /// Returns `None` if the mesh has no triangles.
+    pub fn add_mesh(&mut self, device: &wgpu::Device, mesh: &MeshData) -> Option<MeshHandle> {
+        let mesh = SceneMesh::new_checked(device, mesh)?;
+        self.meshes.push(mesh);
+        self.update_instance_bounds();
+        Some(MeshHandle(self.meshes.len() - 1))
+    }
+
+    /// Draws every mesh once per instance in `instances`, in the space of the node drawing
+    /// it, or once without instances if `instances` is empty. Returns the replaced instance
+    /// buffer, which frames in flight may still use.
+    pub fn set_instances(
+        &mut self,
+        device: &wgpu::Device,
+        instances: &[Instance],
+    ) -> Option<wgpu::Buffer> {
+        let replaced = if instances.is_empty() {
+            self.instances.take()
+        } else {
+            let buffer = wgpu::util::DeviceExt::create_buffer_init(
+                device,
+                &wgpu::util::BufferInitDescriptor {
+                    label: Some("Instance Buffer"),
+                    contents: bytemuck::cast_slice(instances),
+                    usage: wgpu::BufferUsages::VERTEX,
+                },
+            );
+            let models = instances.iter().map(Instance::model).collect();
+            self.instances.replace(InstanceBuffer {
+                buffer,
+                count: instances.len() as u32,
+                models,
+                bounds: Bounds {
+                    min: crate::math::zeros(),
+                    max: crate::math::zeros(),
+                },
+            })
+        };
+        self.update_instance_bounds();
+        replaced.map(|instances| instances.buffer)
+    }
+
+    /// Recompiles the pipelines drawing each mesh once from the WGSL `source`, to be installed
+    /// with [`Scene::install_shader`] once [`PendingShader::take`] reports them valid.
+    ///
+    /// The shader must have the vertex inputs, bind groups, targets, and entry points of
+    /// `shader_source.wgsl`. The pipelines are created inside a validation error scope, so an
+    /// invalid shader is reported as a `ShaderError` rather than raised as an uncaptured
+    /// error, and the scene keeps its previous pipelines.
+    pub fn set_shader(
+        &self,
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        source: &str,
+        cache: Option<&wgpu::PipelineCache>,
+    ) -> PendingShader {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let pipelines = Self::create_pipelines(
+            device,
+            surface_format,
+            &self.uniform,
+            &self.environment,
+            &self.materials.bind_group_layout,
+            &self.light,
+            cache,
+            SceneShader::Single(source),
+        );
+        // Backends validating on the CPU resolve the scope right away, while WebGPU in the
+        // browser resolves it in a later frame.
+        PendingShader {
+            pipelines,
+            validation: PendingLoad::spawn(device.pop_error_scope()),
+        }
+    }
+
+    /// Replaces the pipelines drawing each mesh once with the validated `pipelines`, and
+    /// returns the replaced pipelines, which frames in flight may still use.
+    pub fn install_shader(
+        &mut self,
+        pipelines: HashMap<Rasterization, wgpu::RenderPipeline>,
+    ) -> HashMap<Rasterization, wgpu::RenderPipeline> {
+        std::mem::replace(&mut self.pipelines, pipelines)
+    }
+
+    /// Returns the number of instances each mesh is drawn with, `0` if none are set.
+    pub fn instance_count(&self) -> u32 {
+        self.instances
+            .as_ref()
+            .map_or(0, |instances| instances.count)
+    }
+
+    /// Recomputes the bounds of the meshes placed by every instance, after the meshes or the
+    /// instances changed.
+    fn update_instance_bounds(&mut self) {
+        let Some(instances) = self.instances.as_mut() else {
+            return;
+        };
+        let Some(meshes) = self
+            .meshes
+            .iter()
+            .map(|mesh| mesh.bounds)
+            .reduce(|bounds, mesh| bounds.union(&mesh))
+        else {
+            return;
+        };
+        if let Some(bounds) = instances
+            .models
+            .iter()
+            .map(|model| meshes.transformed(model))
+            .reduce(|bounds, instance| bounds.union(&instance))
+        {
+            instances.bounds = bounds;
+        }
+    }
+
+    /// Returns the number of meshes drawn and culled against the view frustum as of the last
+    /// update.
+    pub fn cull_stats(&self) -> CullStats {
+        self.cull_stats
+    }
+
+    /// Returns the scene's nodes.
+    pub fn graph(&self) -> &SceneGraph {
+        &self.graph
+    }
+
+    /// Returns the id the node `id` writes to the object id target: [`Scene::OBJECT_ID`] in
+    /// the low [`OBJECT_KIND_BITS`] bits, and the node's index above them.
+    pub fn node_object_id(id: NodeId) -> u32 {
+        Self::OBJECT_ID | ((id.index() as u32) << OBJECT_KIND_BITS)
+    }
+
+    /// Returns the node that wrote `object_id` to the object id target, or `None` if no node of
+    /// the scene wrote it.
+    pub fn object_node(&self, object_id: u32) -> Option<NodeId> {
+        if object_id & ((1 << OBJECT_KIND_BITS) - 1) != Self::OBJECT_ID {
+            return None;
+        }
+        let id = NodeId::from_index((object_id >> OBJECT_KIND_BITS) as usize);
+        self.graph.get(id).map(|_| id)
+    }
+
+    /// Returns the node `id` to modify, such as to move it, or `None` if it is not in the
+    /// scene. Changes take effect with the next update.
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.graph.get_mut(id)
+    }
+
+    /// Adds `node` as a child of `parent`, or as a root if `parent` is `None`, and returns its
+    /// id. Root nodes are placed by the animated transform the scene spins with.
+    ///
+    /// Returns `None`, without adding the node, if `parent` is not in the scene.
+    pub fn add_node(
+        &mut self,
+        device: &wgpu::Device,
+        parent: Option<NodeId>,
+        node: Node,
+    ) -> Option<NodeId> {
+        let id = self.graph.add(parent, node)?;
+        self.reserve_node_uniforms(device);
+        Some(id)
+    }
+
+    /// Removes the node `id` and its descendants from the scene, and returns them. Their
+    /// meshes stay in the scene, to be drawn by other nodes.
+    pub fn remove_node(&mut self, id: NodeId) -> Vec<Node> {
+        self.graph.remove(id)
+    }
+
+    /// Adds a white, depth tested label showing `text` at `position` in world space, and
+    /// returns its id. The label can be changed, such as to attach it to a node, with
+    /// [`Scene::label_mut`].
+    pub fn add_label(&mut self, position: crate::math::Vec3, text: impl Into<String>) -> LabelId {
+        self.labels.push(Some(Label::new(position, text)));
+        LabelId(self.labels.len() - 1)
+    }
+
+    /// Returns the label `id` to modify, or `None` if it was removed.
+    pub fn label_mut(&mut self, id: LabelId) -> Option<&mut Label> {
+        self.labels.get_mut(id.0)?.as_mut()
+    }
+
+    /// Removes the label `id` from the scene, and returns it.
+    pub fn remove_label(&mut self, id: LabelId) -> Option<Label> {
+        self.labels.get_mut(id.0)?.take()
+    }
+
+    /// Returns the scene's labels with their ids.
+    pub fn labels(&self) -> impl Iterator<Item = (LabelId, &Label)> {
+        self.labels
+            .iter()
+            .enumerate()
+            .filter_map(|(index, label)| Some((LabelId(index), label.as_ref()?)))
+    }
+
+    /// Returns the point `label` is centered on in world space, following the node it is
+    /// attached to, or `None` if that node is no longer in the scene.
+    pub fn label_anchor(&self, label: &Label) -> Option<crate::math::Vec3> {
+        let position = label.position;
+        let Some(node) = label.node else {
+            return Some(position);
+        };
+        let world = self.graph.get(node)?.world();
+        Some(crate::math::xyz(
+            &(world * crate::math::vec4(position.x, position.y, position.z, 1.0)),
+        ))
+    }
+
+    /// Returns the scene's labels with the points they are centered on in world space, as
+    /// drawn by the `LabelRenderer`. Labels of removed nodes are skipped.
+    pub fn label_anchors(&self) -> impl Iterator<Item = (crate::math::Vec3, &Label)> {
+        self.labels()
+            .filter_map(|(_, label)| Some((self.label_anchor(label)?, label)))
+    }
+
+    /// Restarts the animation from `transform`, with every node's spin undone.
+    pub fn reset_animation(&mut self, transform: crate::math::Mat4) {
+        self.graph.transform = transform;
+        self.time = 0.0;
+        self.previous_state = (transform, 0.0);
+        self.tick_step = 0.0;
+    }
+
+    /// Creates the uniform buffers missing for the nodes drawing a mesh.
+    fn reserve_node_uniforms(&mut self, device: &wgpu::Device) {
+        let count = self.graph.drawables().count().saturating_sub(1);
+        while self.node_uniforms.len() < count {
+            self.node_uniforms
+                .push(NodeUniform::new(device, &self.uniform.bind_group_layout));
+        }
+    }
+
+    /// Returns the uniform bind group of the `index`-th node drawn, if it has one.
+    fn node_bind_group(&self, index: usize) -> Option<&wgpu::BindGroup> {
+        match index {
+            0 => Some(&self.uniform.bind_group),
+            _ => Some(&self.node_uniforms.get(index - 1)?.bind_group),
+        }
+    }
+
+    /// Sets the texture multiplied with the base color of materials without a base color
+    /// texture, and returns the replaced texture and bind groups, which frames in flight may
+    /// still use.
+    pub fn set_texture(
+        &mut self,
+        device: &wgpu::Device,
+        texture: Texture,
+    ) -> (Texture, Vec<wgpu::BindGroup>) {
+        self.materials.set_scene_texture(device, texture)
+    }
+
+    /// Adds `texture` for the materials to sample, and returns its handle and the replaced
+    /// bind groups of materials already holding the handle.
+    pub fn add_texture(
+        &mut self,
+        device: &wgpu::Device,
+        texture: Texture,
+    ) -> (TextureHandle, Vec<wgpu::BindGroup>) {
+        self.materials.add_texture(device, texture)
+    }
+
+    /// Sets the materials the meshes are shaded with, indexed by their [`MaterialHandle`],
+    /// and returns the replaced bind groups and the removed bindings. Meshes whose material
+    /// does not exist are shaded with the first one.
+    pub fn set_materials(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        materials: &[Material],
+    ) -> (Vec<wgpu::BindGroup>, Vec<MaterialBinding>) {
+        self.materials.set_materials(device, queue, materials)
+    }
+
+    /// Returns whether the device supports drawing the scene in `mode`.
+    pub fn supports_render_mode(&self, mode: RenderMode) -> bool {
+        let rasterization = Rasterization {
+            mode,
+            depth_test: true,
+        };
+        self.pipelines.contains_key(&rasterization)
+            && self.instanced_pipelines.contains_key(&rasterization)
+    }
+
+    /// Returns the render mode and depth test the scene is drawn with.
+    pub fn rasterization(&self) -> Rasterization {
+        self.rasterization
+    }
+
+    /// Sets the render mode and depth test the scene is drawn with from the next frame on.
+    ///
+    /// Render modes the device does not support fall back to [`RenderMode::Filled`].
+    pub fn set_rasterization(&mut self, rasterization: Rasterization) {
+        self.rasterization = if self.pipelines.contains_key(&rasterization) {
+            rasterization
+        } else {
+            Rasterization {
+                mode: RenderMode::Filled,
+                ..rasterization
+            }
+        };
+    }
+
+    /// Sets the camera's view matrix and position, used from the next update on.
+    pub fn set_camera(&mut self, view: crate::math::Mat4, eye: crate::math::Vec3) {
+        self.view = view;
+        self.camera_position = eye;
+    }
+
     /// Encodes and submits rendering commands for the `Scene` to the given `wgpu::RenderPass`.
     ///
     /// This method takes a mutable reference to a `wgpu::RenderPass` and binds the vertex
@@ -332,7 +878,8 @@ impl Scene {
     /// # How it works
     ///
     /// 1. Configures the render pass with the render pipeline stored in this `Scene`.
-    /// 2. Binds the uniform bind group at the appropriate binding point (set 0).
+    /// 2. Binds the uniform bind group at the appropriate binding point (set 0), the
+    ///    environment at set 1, the light at set 3, and each mesh's material at set 2.
     /// 3. Sets up the vertex and index buffers for the GPU.
     /// 4. Issues the draw command using the index buffer.
     ///
@@ -343,13 +890,79 @@ impl Scene {
     /// scene.render(&mut render_pass);
     /// ```
     pub fn render<'rpass>(&'rpass self, renderpass: &mut wgpu::RenderPass<'rpass>) {
-        renderpass.set_pipeline(&self.pipeline);
-        renderpass.set_bind_group(0, &self.uniform.bind_group, &[]);
+        self.bind_pipeline(renderpass);
+        self.bind_material(renderpass);
+        self.draw(renderpass);
+    }
 
-        renderpass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        renderpass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+    /// Sets the pipeline of the scene's rasterization, the first step of `render`, skipped by
+    /// the render queue when the previous draw used the same pipeline. While instances are
+    /// set, the pipeline is the instanced one.
+    pub fn bind_pipeline<'rpass>(&'rpass self, renderpass: &mut wgpu::RenderPass<'rpass>) {
+        let pipelines = match self.instances {
+            Some(_) => &self.instanced_pipelines,
+            None => &self.pipelines,
+        };
+        renderpass.set_pipeline(&pipelines[&self.rasterization]);
+    }
 
-        renderpass.draw_indexed(0..(INDICES.len() as _), 0, 0..1);
+    /// Binds the environment at set 1 and the light at set 3, shared by all meshes. The
+    /// meshes' own materials are bound at set 2 while drawing.
+    pub fn bind_material<'rpass>(&'rpass self, renderpass: &mut wgpu::RenderPass<'rpass>) {
+        renderpass.set_bind_group(1, &self.environment.bind_group, &[]);
+        renderpass.set_bind_group(3, &self.light.bind_group, &[]);
+    }
+
+    /// Binds the uniforms (set 0) of each node drawing a mesh, and the vertex and index
+    /// buffers of its mesh, and draws them with whatever pipeline is currently set.
+    ///
+    /// Used by debug views, such as the overdraw heatmap, that draw the scene's geometry with
+    /// their own pipeline. The pipeline must use the scene's vertex layout, topology, and
+    /// uniform bind group layout. The instances and materials are not bound, and each mesh is
+    /// drawn once.
+    pub fn draw_geometry<'rpass>(&'rpass self, renderpass: &mut wgpu::RenderPass<'rpass>) {
+        self.draw_nodes(renderpass, None, false);
+    }
+
+    /// Draws the scene's nodes like [`Scene::draw_geometry`], each mesh with its material and
+    /// once per instance while instances are set, with the pipeline set by
+    /// [`Scene::bind_pipeline`].
+    pub fn draw<'rpass>(&'rpass self, renderpass: &mut wgpu::RenderPass<'rpass>) {
+        self.draw_nodes(renderpass, self.instances.as_ref(), true);
+    }
+
+    /// Draws each node's mesh, once per instance in `instances` if given, bound at vertex
+    /// buffer slot 1, and with its material bound at set 2 if `materials` is set.
+    fn draw_nodes<'rpass>(
+        &'rpass self,
+        renderpass: &mut wgpu::RenderPass<'rpass>,
+        instances: Option<&'rpass InstanceBuffer>,
+        materials: bool,
+    ) {
+        let instance_count = match instances {
+            Some(instances) => {
+                renderpass.set_vertex_buffer(1, instances.buffer.slice(..));
+                instances.count
+            }
+            None => 1,
+        };
+        for (index, (mesh, _)) in self.graph.drawables().enumerate() {
+            if !self.visible.get(index).copied().unwrap_or(true) {
+                continue;
+            }
+            let (Some(bind_group), Some(mesh)) =
+                (self.node_bind_group(index), self.meshes.get(mesh.0))
+            else {
+                continue;
+            };
+            renderpass.set_bind_group(0, bind_group, &[]);
+            if materials {
+                renderpass.set_bind_group(2, self.materials.bind_group(mesh.material), &[]);
+            }
+            renderpass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            renderpass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            renderpass.draw_indexed(0..mesh.index_count, 0, 0..instance_count);
+        }
     }
 
     /// Updates the transformation and uniform data of the `Scene`.
@@ -359,7 +972,7 @@ impl Scene {
     ///
     /// # Parameters
     ///
-    /// - `queue`: A reference to the `wgpu::Queue` used to upload updated uniform data to the GPU.
+    /// - `uploads`: The `UploadBelt` the updated uniform data is streamed to the GPU through.
     /// - `aspect_ratio`: The aspect ratio of the rendering surface (width / height).
     /// - `delta_time`: The time elapsed since the last update, in seconds. Used for animated transformations.
     ///
@@ -367,64 +980,148 @@ impl Scene {
     ///
     /// 1. Calculates a perspective projection matrix based on the specified `aspect_ratio` and a fixed field of view.
     /// 2. Creates a view matrix for a fixed camera position and look-at target.
-    /// 3. Updates the model matrix by applying a rotation around the Y-axis. The speed of the rotation is scaled by `delta_time`.
-    /// 4. Combines the projection, view, and model matrices to create the MVP matrix.
-    /// 5. Updates the uniform buffer with the newly-calculated MVP matrix using the provided `queue`.
+    /// 3. Updates the graph's transform by applying a rotation around the Y-axis. The speed of the rotation is scaled by `delta_time`.
+    /// 4. Propagates the world matrices of the nodes, spinning them by `delta_time`.
+    /// 5. Combines the projection and view matrices with each drawn node's world matrix to create its MVP matrix.
+    /// 6. Writes the newly-calculated MVP matrices to the uniform buffers through `uploads`.
     ///
     /// # Example
     ///
     /// ```rust
-    /// // Assuming `scene` is an instance of `Scene`, `queue` is a valid wgpu::Queue,
+    /// // Assuming `scene` is an instance of `Scene`, `uploads` is an `UploadBelt`,
     /// // `aspect_ratio` is a float, and `delta_time` has been calculated.
-    /// scene.update(&queue, aspect_ratio, delta_time);
+    /// scene.update(&mut uploads, aspect_ratio, delta_time);
     /// ```
-    pub fn update(&mut self, queue: &wgpu::Queue, aspect_ratio: f32, delta_time: f32) {
+    ///
+    /// The animation advances by exactly `delta_time`, so it follows the frame rate's hiccups.
+    /// For frame-rate independent animation, advance it in fixed steps with [`Scene::tick`]
+    /// and upload with [`Scene::update_interpolated`] instead.
+    pub fn update(&mut self, uploads: &mut UploadBelt, aspect_ratio: f32, delta_time: f32) {
+        self.tick(delta_time);
+        self.update_interpolated(uploads, aspect_ratio, 1.0);
+    }
+
+    /// Advances the animation by one step of `step` seconds, without uploading anything.
+    ///
+    /// Called zero or more times per frame with a fixed step, the animated states only depend
+    /// on the number of ticks, not on the frame rate.
+    pub fn tick(&mut self, step: f32) {
+        self.previous_state = (self.graph.transform, self.time);
+        self.tick_step = step;
+        self.graph.transform = animate(&self.graph.transform, step);
+        self.time += step;
+    }
+
+    /// Uploads the uniforms of the state `alpha` of the way from the state before the last
+    /// [`Scene::tick`] to the current one, where `alpha` is the fraction of a step that has
+    /// passed since the last tick.
+    ///
+    /// The animation itself is not changed, so the next ticks continue from the current state.
+    pub fn update_interpolated(&mut self, uploads: &mut UploadBelt, aspect_ratio: f32, alpha: f32) {
+        let current_state = (self.graph.transform, self.time);
+        let (previous_transform, previous_time) = self.previous_state;
+        // The spin is a constant rotation, so advancing the previous state by part of the step
+        // interpolates exactly.
+        let step = self.tick_step * alpha.clamp(0.0, 1.0);
+        self.graph.transform = animate(&previous_transform, step);
+        self.time = previous_time + step;
+        self.upload_uniforms(uploads, aspect_ratio);
+        (self.graph.transform, self.time) = current_state;
+    }
+
+    /// Applies the state `alpha` of the way between two snapshots simulated elsewhere, such as
+    /// on the simulation thread, and uploads the resulting uniforms.
+    ///
+    /// This is the counterpart of [`Scene::tick`] and [`Scene::update_interpolated`] for a
+    /// simulation outside the scene: instead of advancing the animation itself, the scene takes
+    /// the simulated transform, and spins its nodes by the snapshots' number of ticks.
+    ///
+    /// # Parameters
+    ///
+    /// - `uploads`: The `UploadBelt` the updated uniform data is streamed to the GPU through.
+    /// - `aspect_ratio`: The aspect ratio of the rendering surface (width / height).
+    /// - `previous`: The simulated state before `snapshot`.
+    /// - `snapshot`: The most recent simulated state.
+    /// - `alpha`: The fraction of a tick passed since `snapshot`, from `0.0` to `1.0`.
+    pub fn apply_snapshot(
+        &mut self,
+        uploads: &mut UploadBelt,
+        aspect_ratio: f32,
+        previous: &SceneSnapshot,
+        snapshot: &SceneSnapshot,
+        alpha: f32,
+    ) {
+        // As in `update_interpolated`, the spin is a constant rotation, so advancing the
+        // previous state by part of the ticks between the snapshots interpolates exactly.
+        let ticks = snapshot.tick.saturating_sub(previous.tick) as f32;
+        let step = ticks * TICK.as_secs_f32() * alpha.clamp(0.0, 1.0);
+        self.graph.transform = animate(&previous.model, step);
+        self.time = previous.tick as f32 * TICK.as_secs_f32() + step;
+        self.upload_uniforms(uploads, aspect_ratio);
+    }
+
+    /// Propagates the world matrices of the nodes, uploads the MVP matrix of each node drawing
+    /// a mesh to its uniform buffer, and culls the nodes whose meshes are outside the view
+    /// frustum.
+    fn upload_uniforms(&mut self, uploads: &mut UploadBelt, aspect_ratio: f32) {
         // A perspective projection matrix.
         //
         // This matrix converts 3D coordinates into 2D clip space coordinates
         // by applying a perspective transformation. It is calculated using the
-        // aspect ratio of the rendering surface, a fixed field of view (in radians),
-        // and near and far clipping planes.
-        //
-        // - `aspect_ratio`: The ratio of the rendering surface's width to its height.
-        // - `80_f32.to_radians()`: The field of view (FOV) in radians, representing the vertical angle of the camera's view.
-        // - `0.1`: The near clipping plane, representing the minimum distance from the camera where objects are visible.
-        // - `1000.0`: The far clipping plane, representing the maximum distance from the camera where objects are visible.
-        let projection =
-            nalgebra_glm::perspective_lh_zo(aspect_ratio, 80_f32.to_radians(), 0.1, 1000.0);
-
-        // A view matrix for the camera.
-        //
-        // The view matrix transforms world coordinates into the camera's coordinate space.
-        // It is calculated based on the camera's position, the target point it is looking at,
-        // and an up direction vector.
-        //
-        // - `&nalgebra_glm::vec3(0.0, 0.0, 3.0)`: The position of the camera in world space.
-        // - `&nalgebra_glm::vec3(0.0, 0.0, 0.0)`: The target point that the camera is looking at.
-        // - `&nalgebra_glm::Vec3::y()`: The up direction vector, which aligns the camera's orientation.
-        let view = nalgebra_glm::look_at_lh(
-            &nalgebra_glm::vec3(0.0, 0.0, 3.0),
-            &nalgebra_glm::vec3(0.0, 0.0, 0.0),
-            &nalgebra_glm::Vec3::y(),
-        );
+        // aspect ratio of the rendering surface, and the field of view and the
+        // near and far clipping planes of `self.projection`, which are sanitized
+        // so that extreme values cannot exhaust the depth buffer's precision.
+        let projection = self.projection.matrix(aspect_ratio);
 
-        self.model = nalgebra_glm::rotate(
-            &self.model,
-            30_f32.to_radians() * delta_time,
-            &nalgebra_glm::Vec3::y(),
-        );
-        self.uniform.update_buffer(
-            queue,
-            0,
-            UniformBuffer {
-                mvp: projection * view * self.model,
-            },
-        );
+        // The view matrix comes from the camera, set with `set_camera`.
+        self.view_projection = projection * self.view;
+
+        self.graph.update_world_matrices(self.time);
+        let frustum = Frustum::from_view_projection(&self.view_projection);
+        self.visible.clear();
+        let drawables = self.graph.iter().filter(|(_, node)| node.mesh.is_some());
+        for (index, (id, node)) in drawables.enumerate() {
+            let world = node.world();
+            let local = match (&self.instances, node.mesh) {
+                (Some(instances), _) => Some(instances.bounds),
+                (None, Some(mesh)) => self.meshes.get(mesh.0).map(|mesh| mesh.bounds),
+                (None, None) => None,
+            };
+            // Nodes without bounds have nothing to draw, so they count as culled.
+            let visible = local.is_some_and(|local| {
+                let bounds = local.transformed(&world);
+                frustum.intersects_sphere(&bounds.center(), bounds.radius())
+                    && frustum.intersects_bounds(&bounds)
+            });
+            self.visible.push(visible);
+            let uniform = UniformBuffer {
+                mvp: self.view_projection * world,
+                model: world,
+                object_id: Self::node_object_id(id),
+                padding: [0; 3],
+            };
+            let buffer = match index {
+                0 => Some(&self.uniform.buffer),
+                _ => self
+                    .node_uniforms
+                    .get(index - 1)
+                    .map(|node_uniform| &node_uniform.buffer),
+            };
+            if let Some(buffer) = buffer {
+                uploads.write(buffer, 0, bytemuck::bytes_of(&uniform));
+            }
+        }
+        let drawn = self.visible.iter().filter(|&&visible| visible).count() as u32;
+        self.cull_stats = CullStats {
+            drawn,
+            culled: self.visible.len() as u32 - drawn,
+        };
     }
 
-    /// Creates a render pipeline for the `Scene`.
+    /// Creates the render pipelines for the `Scene`, one for each [`Rasterization`] the
+    /// device supports.
     ///
-    /// This function sets up a graphics pipeline that specifies how vertices and
+    /// This function sets up graphics pipelines that specify how vertices and
     /// fragments will be processed and rendered to the screen. It takes configuration
     /// parameters like the surface format and uniform bind group layout needed for
     /// the pipeline's creation.
@@ -436,22 +1133,31 @@ impl Scene {
     ///   determines the format of the frame buffer to render into.
     /// - `uniform`: A reference to the `UniformBinding` object, which provides the
     ///   bind group layout used to bind the uniform buffer for shaders.
+    /// - `environment`: The `EnvironmentBinding`, whose bind group layout is used at set 1.
+    /// - `material_layout`: The layout of the materials' bind groups, used at set 2.
+    /// - `light`: The `LightBinding`, whose bind group layout is used at set 3.
+    /// - `cache`: The pipeline cache to compile the pipelines through, if any.
+    /// - `shader`: The shader to compile. With [`SceneShader::Instanced`], the pipelines also
+    ///   read an [`Instance`] per instance at vertex buffer slot 1.
     ///
     /// # How it Works
     ///
     /// 1. Compiles the shaders using the provided WGSL shader source.
     /// 2. Creates a pipeline layout with the uniform bind group layout defined in
-    ///    the `UniformBinding` object.
+    ///    the `UniformBinding` object, followed by the environment's, the materials', and the
+    ///    light's bind group layouts.
     /// 3. Configures the vertex state, including the vertex attributes and the
     ///    buffer layout.
-    /// 4. Defines the primitive state, including the topology (triangle strip), cull mode, and front face.
-    /// 5. Optionally configures a depth-stencil state for depth testing and writing.
+    /// 4. Defines the primitive state of each render mode, including the topology, polygon
+    ///    mode, cull mode, and front face.
+    /// 5. Configures a depth-stencil state, testing and writing depth only if the
+    ///    rasterization's depth test is enabled.
     /// 6. Specifies the fragment state, including the blending and render target format.
-    /// 7. Assembles the render pipeline with all these configurations.
+    /// 7. Assembles a render pipeline for every supported combination of the above.
     ///
     /// # Returns
     ///
-    /// A `wgpu::RenderPipeline` instance that can be used for subsequent rendering.
+    /// The `wgpu::RenderPipeline` of each supported rasterization, for subsequent rendering.
     ///
     /// # Example
     ///
@@ -459,13 +1165,28 @@ impl Scene {
     /// // Assuming `device` is an instance of `wgpu::Device`,
     /// // `surface_format` is a valid wgpu::TextureFormat,
     /// // and `uniform` is an instance of `UniformBinding`.
-    /// let pipeline = Scene::create_pipeline(&device, surface_format, &uniform);
+    /// let pipelines = Scene::create_pipelines(
+    ///     &device,
+    ///     surface_format,
+    ///     &uniform,
+    ///     &environment,
+    ///     &materials.bind_group_layout,
+    ///     &light,
+    ///     None,
+    ///     shader,
+    /// );
     /// ```
-    fn create_pipeline(
+    #[allow(clippy::too_many_arguments)]
+    fn create_pipelines(
         device: &wgpu::Device,
         surface_format: wgpu::TextureFormat,
         uniform: &UniformBinding,
-    ) -> wgpu::RenderPipeline {
+        environment: &EnvironmentBinding,
+        material_layout: &wgpu::BindGroupLayout,
+        light: &LightBinding,
+        cache: Option<&wgpu::PipelineCache>,
+        shader: SceneShader,
+    ) -> HashMap<Rasterization, wgpu::RenderPipeline> {
         // The shader module contains the compiled SPIR-V or WGSL shader code that runs on the GPU.
         //
         // It serves as the container for the vertex and fragment shaders used in the rendering pipeline.
@@ -479,10 +1200,28 @@ impl Scene {
         // The shaders define how vertex data is transformed and rasterized into fragment data, as well
         // as how fragments are finally processed into pixels on the render target.
         let shader_module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: None,
-            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(SHADER_SOURCE)),
+            label: Some(match shader {
+                SceneShader::Single(_) => "Scene Shader",
+                SceneShader::Instanced => "Instanced Scene Shader",
+            }),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(match shader {
+                SceneShader::Single(source) => source,
+                SceneShader::Instanced => INSTANCED_SHADER_SOURCE,
+            })),
         });
 
+        // The vertex buffers: the mesh's vertices, followed by the instances if instanced.
+        let vertex_attributes = Vertex::vertex_attributes();
+        let instance_attributes = Instance::vertex_attributes();
+        let vertex_buffers = [
+            Vertex::description(&vertex_attributes),
+            Instance::description(&instance_attributes),
+        ];
+        let vertex_buffers = match shader {
+            SceneShader::Single(_) => &vertex_buffers[..1],
+            SceneShader::Instanced => &vertex_buffers[..],
+        };
+
         // The pipeline layout defines the structure of resources (such as uniform buffers and
         // textures) available to the shader programs in the rendering pipeline.
         //
@@ -499,58 +1238,344 @@ impl Scene {
         // - The `bind_group_layouts` define the layouts for all bind groups used in the pipeline.
         // - `push_constant_ranges` allows for defining push constants, though it's empty in this case.
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: None,
-            bind_group_layouts: &[&uniform.bind_group_layout],
+            label: Some("Scene Pipeline Layout"),
+            bind_group_layouts: &[
+                &uniform.bind_group_layout,
+                &environment.bind_group_layout,
+                material_layout,
+                &light.bind_group_layout,
+            ],
             push_constant_ranges: &[],
         });
 
-        // Creates and configures a render pipeline, which defines the sequence of operations
-        // for rendering, including how vertex and fragment shaders process data, how primitives
-        // are assembled, and how the final output is rendered to the screen or a render target.
-        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            // Configures and creates a render pipeline, which defines how vertex and fragment shaders process
-            // the geometry and how the final output is rendered onto the screen or target surface.
-            label: None,
-            layout: Some(&pipeline_layout), // Specifies the pipeline layout, including bind group layouts.
-            vertex: wgpu::VertexState {
-                module: &shader_module, // References the compiled vertex shader.
-                entry_point: Some("vertex_main"), // Specifies the entry point for the vertex shader.
-                buffers: &[Vertex::description(&Vertex::vertex_attributes())], // Defines vertex buffer layout and attributes.
-                compilation_options: Default::default(),
-            },
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip, // Specifies how vertices are assembled (triangle strip in this case).
-                strip_index_format: Some(wgpu::IndexFormat::Uint32), // Index format used with triangle strips.
-                front_face: wgpu::FrontFace::Cw, // Specifies the front-facing direction for culling (clockwise).
-                cull_mode: None, // Disables face culling.
-                polygon_mode: wgpu::PolygonMode::Fill, // Draws filled polygons.
-                conservative: false,
-                unclipped_depth: false,
+        // Creates and configures a render pipeline for each supported rasterization, which defines
+        // the sequence of operations for rendering, including how vertex and fragment shaders
+        // process data, how primitives are assembled, and how the final output is rendered to the
+        // screen or a render target.
+        let features = device.features();
+        let mut pipelines = HashMap::new();
+        for rasterization in Rasterization::all() {
+            if !rasterization.mode.is_supported(features) {
+                continue;
+            }
+            let (topology, polygon_mode) = rasterization.mode.primitive(features);
+            let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                // Configures and creates a render pipeline, which defines how vertex and fragment shaders process
+                // the geometry and how the final output is rendered onto the screen or target surface.
+                label: Some("Scene Pipeline"),
+                layout: Some(&pipeline_layout), // Specifies the pipeline layout, including bind group layouts.
+                vertex: wgpu::VertexState {
+                    module: &shader_module,           // References the compiled vertex shader.
+                    entry_point: Some("vertex_main"), // Specifies the entry point for the vertex shader.
+                    buffers: vertex_buffers, // Defines vertex buffer layout and attributes.
+                    compilation_options: Default::default(),
+                },
+                primitive: wgpu::PrimitiveState {
+                    topology, // Specifies how vertices are assembled (a list of separate triangles, as loaded models use, or points without point polygons).
+                    strip_index_format: None, // Only used with strip topologies.
+                    front_face: wgpu::FrontFace::Cw, // Specifies the front-facing direction for culling (clockwise).
+                    cull_mode: None,                 // Disables face culling.
+                    polygon_mode, // Draws filled polygons, their edges, or their corners, per render mode.
+                    conservative: false,
+                    unclipped_depth: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: Renderer::DEPTH_FORMAT, // Specifies the format of the depth buffer for depth testing.
+                    depth_write_enabled: rasterization.depth_test, // Enables depth writes to the depth buffer.
+                    depth_compare: if rasterization.depth_test {
+                        wgpu::CompareFunction::Less // Configures depth testing to write only closer fragments.
+                    } else {
+                        wgpu::CompareFunction::Always // Draws every fragment, in submission order.
+                    },
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 1, // Anti-aliasing sample count (1 = no anti-aliasing).
+                    mask: !0, // All samples are active when rasterizing.
+                    alpha_to_coverage_enabled: false,
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader_module,             // References the compiled fragment shader.
+                    entry_point: Some("fragment_main"), // Specifies the entry point for the fragment shader.
+                    targets: &[
+                        Some(wgpu::ColorTargetState {
+                            format: surface_format, // Specifies the format of the render target (framebuffer).
+                            blend: Some(wgpu::BlendState::ALPHA_BLENDING), // Enables alpha blending for transparency effects.
+                            write_mask: wgpu::ColorWrites::ALL, // Allows writing to all color channels (RGBA).
+                        }),
+                        Some(wgpu::ColorTargetState {
+                            format: Self::OBJECT_ID_FORMAT, // The object id target read back by the depth probe.
+                            blend: None,                    // Integer targets cannot be blended.
+                            write_mask: wgpu::ColorWrites::ALL,
+                        }),
+                    ],
+                    compilation_options: Default::default(),
+                }),
+                multiview: None,
+                cache,
+            });
+            pipelines.insert(rasterization, pipeline);
+        }
+        pipelines
+    }
+}
+
+/// The shader a set of the scene's pipelines is compiled from.
+#[derive(Debug, Clone, Copy)]
+enum SceneShader<'a> {
+    /// A shader drawing each mesh once: `shader_source.wgsl`, or an edited copy of it.
+    Single(&'a str),
+
+    /// `instanced.wgsl`, drawing each mesh once per instance.
+    Instanced,
+}
+
+/// How the scene's triangles are rasterized, selected in the Inspector panel.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderMode {
+    /// Filled triangles, as the scene normally looks.
+    #[default]
+    Filled,
+
+    /// The edges of the triangles, with `wgpu::PolygonMode::Line`. Only available with
+    /// `wgpu::Features::POLYGON_MODE_LINE`, which WebGL does not offer.
+    Wireframe,
+
+    /// The corners of the triangles, with `wgpu::PolygonMode::Point`. Without
+    /// `wgpu::Features::POLYGON_MODE_POINT`, such as on WebGL, the indexed vertices are drawn
+    /// as a point list instead.
+    Points,
+}
+
+impl RenderMode {
+    /// Every render mode, in the order they are offered in the user interface.
+    pub const ALL: [RenderMode; 3] = [
+        RenderMode::Filled,
+        RenderMode::Wireframe,
+        RenderMode::Points,
+    ];
+
+    /// Returns the name shown in the user interface.
+    pub fn label(self) -> &'static str {
+        match self {
+            RenderMode::Filled => "Filled",
+            RenderMode::Wireframe => "Wireframe",
+            RenderMode::Points => "Points",
+        }
+    }
+
+    /// Returns whether a device with `features` can draw in this mode.
+    pub fn is_supported(self, features: wgpu::Features) -> bool {
+        match self {
+            RenderMode::Filled | RenderMode::Points => true,
+            RenderMode::Wireframe => features.contains(wgpu::Features::POLYGON_MODE_LINE),
+        }
+    }
+
+    /// Returns the topology and polygon mode drawing this mode on a device with `features`.
+    fn primitive(self, features: wgpu::Features) -> (wgpu::PrimitiveTopology, wgpu::PolygonMode) {
+        let triangles = wgpu::PrimitiveTopology::TriangleList;
+        match self {
+            RenderMode::Filled => (triangles, wgpu::PolygonMode::Fill),
+            RenderMode::Wireframe => (triangles, wgpu::PolygonMode::Line),
+            RenderMode::Points if features.contains(wgpu::Features::POLYGON_MODE_POINT) => {
+                (triangles, wgpu::PolygonMode::Point)
+            }
+            RenderMode::Points => (wgpu::PrimitiveTopology::PointList, wgpu::PolygonMode::Fill),
+        }
+    }
+}
+
+/// The render mode and depth test the scene is drawn with, each a pipeline of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rasterization {
+    /// How the triangles are rasterized.
+    pub mode: RenderMode,
+
+    /// Whether fragments are tested against and written to the depth buffer. Without it,
+    /// triangles are drawn over each other in submission order.
+    pub depth_test: bool,
+}
+
+impl Default for Rasterization {
+    fn default() -> Self {
+        Self {
+            mode: RenderMode::Filled,
+            depth_test: true,
+        }
+    }
+}
+
+impl Rasterization {
+    /// Returns every combination of render mode and depth test.
+    fn all() -> impl Iterator<Item = Self> {
+        RenderMode::ALL
+            .into_iter()
+            .flat_map(|mode| [true, false].map(|depth_test| Self { mode, depth_test }))
+    }
+}
+
+/// The geometry of one mesh on the CPU: vertices, and a triangle list indexing them.
+#[derive(Debug, Clone)]
+pub struct MeshData {
+    /// The mesh's name, for logs and the GUI.
+    pub name: String,
+
+    /// The vertices, in model space.
+    pub vertices: Vec<Vertex>,
+
+    /// The vertex indices, three per triangle, with clockwise front faces.
+    pub indices: Vec<u32>,
+
+    /// The material the mesh is shaded with.
+    pub material: MaterialHandle,
+}
+
+impl MeshData {
+    /// Returns the scene's default triangle, defined by `VERTICES` and `INDICES`.
+    pub fn triangle() -> Self {
+        Self {
+            name: Scene::OBJECT_NAME.to_string(),
+            vertices: VERTICES.to_vec(),
+            indices: INDICES.to_vec(),
+            material: MaterialHandle::default(),
+        }
+    }
+
+    /// Returns the bounds of the vertices, or `None` if there are none.
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(self.vertices.iter().map(Vertex::position))
+    }
+}
+
+/// A mesh drawn by the [`Scene`], uploaded to the GPU.
+pub struct SceneMesh {
+    /// The mesh's name.
+    pub name: String,
+
+    /// The vertex buffer, holding the positions and colors of the vertices.
+    pub vertex_buffer: wgpu::Buffer,
+
+    /// The index buffer, holding `u32` indices into the vertex buffer.
+    pub index_buffer: wgpu::Buffer,
+
+    /// The number of indices drawn.
+    pub index_count: u32,
+
+    /// The material the mesh is shaded with.
+    pub material: MaterialHandle,
+
+    /// The bounds of the vertices, in the space of the nodes drawing the mesh.
+    pub bounds: Bounds,
+}
+
+impl SceneMesh {
+    /// Uploads `mesh` into new vertex and index buffers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mesh has no vertices. See [`SceneMesh::new_checked`].
+    pub fn new(device: &wgpu::Device, mesh: &MeshData) -> Self {
+        Self::new_checked(device, mesh).expect("the mesh has vertices")
+    }
+
+    /// Uploads `mesh` into new vertex and index buffers, or returns `None` if it has no
+    /// triangles.
+    pub fn new_checked(device: &wgpu::Device, mesh: &MeshData) -> Option<Self> {
+        if mesh.indices.len() < 3 {
+            return None;
+        }
+        let bounds = mesh.bounds()?;
+        let vertex_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Vertex Buffer"),
+                contents: bytemuck::cast_slice(&mesh.vertices),
+                usage: wgpu::BufferUsages::VERTEX,
             },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: Renderer::DEPTH_FORMAT, // Specifies the format of the depth buffer for depth testing.
-                depth_write_enabled: true, // Enables depth writes to the depth buffer.
-                depth_compare: wgpu::CompareFunction::Less, // Configures depth testing to write only closer fragments.
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState {
-                count: 1, // Anti-aliasing sample count (1 = no anti-aliasing).
-                mask: !0, // All samples are active when rasterizing.
-                alpha_to_coverage_enabled: false,
+        );
+        let index_buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("index Buffer"),
+                contents: bytemuck::cast_slice(&mesh.indices),
+                usage: wgpu::BufferUsages::INDEX,
             },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader_module, // References the compiled fragment shader.
-                entry_point: Some("fragment_main"), // Specifies the entry point for the fragment shader.
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: surface_format, // Specifies the format of the render target (framebuffer).
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING), // Enables alpha blending for transparency effects.
-                    write_mask: wgpu::ColorWrites::ALL, // Allows writing to all color channels (RGBA).
-                })],
-                compilation_options: Default::default(),
-            }),
-            multiview: None,
-            cache: None,
+        );
+        Some(Self {
+            name: mesh.name.clone(),
+            vertex_buffer,
+            index_buffer,
+            index_count: mesh.indices.len() as u32,
+            material: mesh.material,
+            bounds,
+        })
+    }
+}
+
+/// The pipelines recompiled by [`Scene::set_shader`], waiting for the backend to validate them.
+pub struct PendingShader {
+    /// The recompiled pipelines, drawing each mesh once.
+    pipelines: HashMap<Rasterization, wgpu::RenderPipeline>,
+
+    /// Receives the validation error of the pipelines, or `None` if they are valid.
+    validation: PendingLoad<Option<wgpu::Error>>,
+}
+
+impl PendingShader {
+    /// Returns the pipelines to install with [`Scene::install_shader`], or the error making
+    /// them invalid, once the backend validated them, and `None` until then.
+    pub fn take(
+        &mut self,
+    ) -> Option<Result<HashMap<Rasterization, wgpu::RenderPipeline>, ShaderError>> {
+        Some(match self.validation.take()? {
+            None => Ok(std::mem::take(&mut self.pipelines)),
+            Some(error) => Err(ShaderError::Invalid(error.to_string())),
         })
     }
 }
+
+/// The instances the scene's meshes are drawn with, uploaded to the GPU.
+struct InstanceBuffer {
+    /// The vertex buffer holding the instances, stepped per instance.
+    buffer: wgpu::Buffer,
+
+    /// The number of instances in `buffer`.
+    count: u32,
+
+    /// The transforms of the instances, to compute `bounds`.
+    models: Vec<crate::math::Mat4>,
+
+    /// The bounds of every mesh placed by every instance, in the space of the nodes.
+    bounds: Bounds,
+}
+
+/// The uniform buffer holding the MVP matrix of a node drawn after the scene's first, and
+/// its bind group at set 0.
+struct NodeUniform {
+    /// The buffer holding one `UniformBuffer`.
+    buffer: wgpu::Buffer,
+
+    /// The bind group binding `buffer`, of the layout of the scene's `UniformBinding`.
+    bind_group: wgpu::BindGroup,
+}
+
+impl NodeUniform {
+    /// Creates the buffer and its bind group of `layout`.
+    fn new(device: &wgpu::Device, layout: &wgpu::BindGroupLayout) -> Self {
+        let buffer = wgpu::util::DeviceExt::create_buffer_init(
+            device,
+            &wgpu::util::BufferInitDescriptor {
+                label: Some("Node Uniform Buffer"),
+                contents: bytemuck::cast_slice(&[UniformBuffer::default()]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            },
+        );
+        let bind_group = shader_source::BindGroup0 {
+            ubo: buffer.as_entire_buffer_binding(),
+        }
+        .create(device, layout);
+        Self { buffer, bind_group }
+    }
+}
@@ -0,0 +1,331 @@
+//! # Scene Graph Module
+//!
+//! The `scene_graph` module arranges the objects drawn by the [`Scene`](crate::scene::Scene)
+//! in a hierarchy of nodes, so a scene can hold several objects, each placed relative to its
+//! parent.
+//!
+//! ## Overview
+//!
+//! - [`Node`] holds a local transform relative to its parent, an optional spin around its own
+//!   Y axis, an optional [`MeshHandle`] naming the mesh it draws, and its children.
+//! - [`SceneGraph`] owns the nodes, addressed by [`NodeId`]s. Nodes are added under a parent
+//!   or as roots, removed together with their descendants, and queried by id.
+//! - [`SceneGraph::update_world_matrices`] propagates the transforms from the roots down,
+//!   once per update. Every root is placed by the graph's [`SceneGraph::transform`], the
+//!   matrix animated by the `simulation` module, so the whole scene still spins as one.
+//!
+//! Ids are never reused, so an id kept after its node was removed does not name another node.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut graph = SceneGraph::default();
+//! let sun = graph.add(None, Node::new("Sun").with_mesh(MeshHandle(0)).with_spin(45.0));
+//! let planet = Node::new("Planet")
+//!     .with_mesh(MeshHandle(0))
+//!     .with_local(planet_offset);
+//! graph.add(Some(sun), planet);
+//!
+//! graph.update_world_matrices(elapsed_seconds);
+//! ```
+
+// Importing the bounds, to compute the extent of the nodes' meshes in world space.
+use crate::camera::Bounds;
+
+/// The id of a node in a [`SceneGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    /// Returns the id's index among the ids handed out by its graph.
+    pub(crate) fn index(self) -> usize {
+        self.0
+    }
+
+    /// Returns the id at `index` among the ids handed out by a graph, which may not name a
+    /// node of it.
+    pub(crate) fn from_index(index: usize) -> Self {
+        Self(index)
+    }
+}
+
+/// The index of a mesh in the scene's meshes, drawn by the nodes holding it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MeshHandle(pub usize);
+
+/// An object of the scene: a transform relative to its parent and, optionally, a mesh.
+#[derive(Debug, Clone)]
+pub struct Node {
+    /// The node's name, for logs and the GUI.
+    pub name: String,
+
+    /// The transform from the node's space to its parent's space.
+    pub local: crate::math::Mat4,
+
+    /// The rotation speed around the node's own Y axis, in degrees per second, applied after
+    /// `local`. The node's children spin along with it.
+    pub spin: f32,
+
+    /// The mesh drawn at the node, if any.
+    pub mesh: Option<MeshHandle>,
+
+    /// The node's parent, or `None` for a root.
+    parent: Option<NodeId>,
+
+    /// The node's children, in the order they were added.
+    children: Vec<NodeId>,
+
+    /// The transform from the node's space to world space, as of the last
+    /// [`SceneGraph::update_world_matrices`].
+    world: crate::math::Mat4,
+}
+
+impl Node {
+    /// Creates a node named `name` with an identity transform, no spin, and no mesh.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            local: crate::math::identity(),
+            spin: 0.0,
+            mesh: None,
+            parent: None,
+            children: Vec::new(),
+            world: crate::math::identity(),
+        }
+    }
+
+    /// Returns the node with `local` as its transform relative to its parent.
+    pub fn with_local(mut self, local: crate::math::Mat4) -> Self {
+        self.local = local;
+        self
+    }
+
+    /// Returns the node spinning at `degrees_per_second` around its own Y axis.
+    pub fn with_spin(mut self, degrees_per_second: f32) -> Self {
+        self.spin = degrees_per_second;
+        self
+    }
+
+    /// Returns the node drawing `mesh`.
+    pub fn with_mesh(mut self, mesh: MeshHandle) -> Self {
+        self.mesh = Some(mesh);
+        self
+    }
+
+    /// Returns the node's parent, or `None` for a root.
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    /// Returns the node's children.
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+
+    /// Returns the transform from the node's space to world space, as of the last update.
+    pub fn world(&self) -> crate::math::Mat4 {
+        self.world
+    }
+
+    /// Returns the node's local transform with its spin after `time` seconds applied.
+    fn animated_local(&self, time: f32) -> crate::math::Mat4 {
+        if self.spin == 0.0 {
+            return self.local;
+        }
+        crate::math::rotate(
+            &self.local,
+            (self.spin * time).to_radians(),
+            &crate::math::up(),
+        )
+    }
+}
+
+/// The hierarchy of nodes drawn by the scene.
+#[derive(Debug, Clone)]
+pub struct SceneGraph {
+    /// The transform placing every root node in world space, animated by the simulation.
+    pub transform: crate::math::Mat4,
+
+    /// The nodes, indexed by their id. Removed nodes leave an empty slot so ids stay unique.
+    nodes: Vec<Option<Node>>,
+
+    /// The nodes without a parent, in the order they were added.
+    roots: Vec<NodeId>,
+}
+
+impl Default for SceneGraph {
+    fn default() -> Self {
+        Self {
+            transform: crate::math::identity(),
+            nodes: Vec::new(),
+            roots: Vec::new(),
+        }
+    }
+}
+
+impl SceneGraph {
+    /// Adds `node` as the last child of `parent`, or as a root if `parent` is `None`, and
+    /// returns its id.
+    ///
+    /// Returns `None`, without adding the node, if `parent` is not in the graph.
+    pub fn add(&mut self, parent: Option<NodeId>, mut node: Node) -> Option<NodeId> {
+        let id = NodeId(self.nodes.len());
+        match parent {
+            Some(parent) => self.get_mut(parent)?.children.push(id),
+            None => self.roots.push(id),
+        }
+        node.parent = parent;
+        node.children.clear();
+        node.world = match parent.and_then(|parent| self.get(parent)) {
+            Some(parent) => parent.world * node.local,
+            None => self.transform * node.local,
+        };
+        self.nodes.push(Some(node));
+        Some(id)
+    }
+
+    /// Removes the node `id` and all its descendants, and returns them, the node first.
+    ///
+    /// Returns nothing if `id` is not in the graph.
+    pub fn remove(&mut self, id: NodeId) -> Vec<Node> {
+        let Some(parent) = self.get(id).map(Node::parent) else {
+            return Vec::new();
+        };
+        match parent {
+            Some(parent) => {
+                if let Some(parent) = self.get_mut(parent) {
+                    parent.children.retain(|&child| child != id);
+                }
+            }
+            None => self.roots.retain(|&root| root != id),
+        }
+
+        let mut removed = Vec::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if let Some(node) = self.nodes[id.0].take() {
+                pending.extend(node.children.iter().rev());
+                removed.push(node);
+            }
+        }
+        removed
+    }
+
+    /// Removes every node. Ids handed out before stay unused.
+    pub fn clear(&mut self) {
+        self.nodes.iter_mut().for_each(|node| *node = None);
+        self.roots.clear();
+    }
+
+    /// Returns the node `id`, or `None` if it is not in the graph.
+    pub fn get(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(id.0)?.as_ref()
+    }
+
+    /// Returns the node `id` to modify, or `None` if it is not in the graph.
+    ///
+    /// Changes to its transform take effect with the next update.
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.nodes.get_mut(id.0)?.as_mut()
+    }
+
+    /// Returns the nodes without a parent.
+    pub fn roots(&self) -> &[NodeId] {
+        &self.roots
+    }
+
+    /// Returns the first node named `name`, if any.
+    pub fn find(&self, name: &str) -> Option<NodeId> {
+        self.iter()
+            .find(|(_, node)| node.name == name)
+            .map(|(id, _)| id)
+    }
+
+    /// Returns every node with its id, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &Node)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, node)| Some((NodeId(index), node.as_ref()?)))
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns whether the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Returns the nodes drawing a mesh, with their world transforms, in the order they were
+    /// added.
+    pub fn drawables(&self) -> impl Iterator<Item = (MeshHandle, crate::math::Mat4)> + '_ {
+        self.iter()
+            .filter_map(|(_, node)| Some((node.mesh?, node.world)))
+    }
+
+    /// Recomputes the world transform of every node from the graph's transform, the local
+    /// transforms, and the spins after `time` seconds.
+    pub fn update_world_matrices(&mut self, time: f32) {
+        let mut pending: Vec<_> = self
+            .roots
+            .iter()
+            .map(|&root| (root, self.transform))
+            .collect();
+        while let Some((id, parent_world)) = pending.pop() {
+            let Some(node) = self.nodes[id.0].as_mut() else {
+                continue;
+            };
+            node.world = parent_world * node.animated_local(time);
+            pending.extend(node.children.iter().map(|&child| (child, node.world)));
+        }
+    }
+
+    /// Returns the bounds of the drawn meshes relative to the graph's transform, covering
+    /// every orientation of the spinning nodes, or `None` if no node draws a mesh.
+    ///
+    /// `mesh_bounds` returns the bounds of a mesh in its own space. As they do not depend on
+    /// the animation, the bounds only change when the graph does.
+    pub fn bounds(&self, mesh_bounds: impl Fn(MeshHandle) -> Option<Bounds>) -> Option<Bounds> {
+        self.roots
+            .iter()
+            .filter_map(|&root| self.subtree_bounds(root, &mesh_bounds))
+            .reduce(|bounds, other| bounds.union(&other))
+    }
+
+    /// Returns the bounds of the meshes of the node `id` and its descendants, in its parent's
+    /// space.
+    fn subtree_bounds(
+        &self,
+        id: NodeId,
+        mesh_bounds: &impl Fn(MeshHandle) -> Option<Bounds>,
+    ) -> Option<Bounds> {
+        let node = self.get(id)?;
+        let bounds = node
+            .children
+            .iter()
+            .filter_map(|&child| self.subtree_bounds(child, mesh_bounds))
+            .chain(node.mesh.and_then(mesh_bounds))
+            .reduce(|bounds, other| bounds.union(&other))?;
+        let bounds = if node.spin == 0.0 {
+            bounds
+        } else {
+            spun_around_y(&bounds)
+        };
+        Some(bounds.transformed(&node.local))
+    }
+}
+
+/// Returns bounds covering `bounds` in every rotation around the Y axis.
+fn spun_around_y(bounds: &Bounds) -> Bounds {
+    let radius = [bounds.min.x, bounds.max.x]
+        .into_iter()
+        .flat_map(|x| [bounds.min.z, bounds.max.z].map(|z| (x * x + z * z).sqrt()))
+        .fold(0.0, f32::max);
+    Bounds {
+        min: crate::math::vec3(-radius, bounds.min.y, -radius),
+        max: crate::math::vec3(radius, bounds.max.y, radius),
+    }
+}
@@ -0,0 +1,226 @@
+//! # Scene Panel Module
+//!
+//! The `scene_panel` module shows the 3D scene inside the GUI, in the central panel left free
+//! by the side panels, instead of behind it. This is the layout of editors, where the scene is
+//! one panel among others rather than the backdrop of the whole window.
+//!
+//! ## Overview
+//!
+//! - [`ScenePanel`] is an offscreen texture of the panel's size, registered with the
+//!   `egui_wgpu::Renderer` as an `egui::TextureId`. The post-processing pass writes the scene
+//!   into it instead of the surface, and the GUI shows it as an `egui::Image` filling the
+//!   central panel.
+//! - While the panel is shown, the scene's render targets (the HDR, depth, and object id
+//!   textures) have the panel's size, so the scene is rendered at the resolution it is shown
+//!   at. The GUI keeps a depth texture of the surface's size for its own pass.
+//!
+//! The `App` reads the central panel's rectangle from each GUI pass and passes it to
+//! `Renderer::set_scene_panel` in physical pixels. When it changes size, the texture is
+//! recreated and re-registered under the same id, so the GUI shows it from the next pass on.
+//! `Renderer::viewport` then returns the scene's viewport within the panel, so cursor positions
+//! map into the scene as before.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! // After the GUI pass, with the rectangle of the central panel:
+//! renderer.set_scene_panel(Some(Viewport { x, y, width, height }));
+//!
+//! // In the next GUI pass:
+//! if let Some(texture) = renderer.scene_panel_texture() {
+//!     ui.add(egui::Image::new((texture, ui.available_size())));
+//! }
+//! ```
+
+use crate::deletion_queue::DeletionQueue;
+use crate::gpu::Gpu;
+use crate::viewport::Viewport;
+
+/// The offscreen texture the scene is shown from inside the GUI.
+#[derive(Debug)]
+pub struct ScenePanel {
+    /// The rectangle of the surface the panel covers, in physical pixels.
+    rect: Viewport,
+
+    /// The texture the post-processing pass writes the scene into, in the surface's format.
+    texture: wgpu::Texture,
+
+    /// The view of `texture` the post-processing pass renders into.
+    view: wgpu::TextureView,
+
+    /// The view of `texture` the GUI samples, in the sRGB variant of the surface's format.
+    ///
+    /// The post-processing pass writes sRGB-encoded colors to non-sRGB surfaces, while `egui`
+    /// expects its textures to decode to linear colors, as its sRGB textures do. Devices
+    /// without `DownlevelFlags::VIEW_FORMATS` sample `texture` in its own format instead.
+    sampled_view: wgpu::TextureView,
+
+    /// Whether `sampled_view` cannot decode sRGB, so the post-processing pass must write linear
+    /// colors into `texture`.
+    stores_linear: bool,
+
+    /// The id `texture` is registered under with the `egui_wgpu::Renderer`.
+    texture_id: egui::TextureId,
+
+    /// The depth texture the GUI is drawn with, of the surface's size, since the scene's depth
+    /// texture has the panel's size.
+    gui_depth_texture_view: wgpu::TextureView,
+}
+
+impl ScenePanel {
+    /// Creates the texture for a panel covering `rect`, registers it with `egui_renderer`, and
+    /// creates the GUI's depth texture for a surface of `surface_size`.
+    pub fn new(
+        gpu: &Gpu,
+        egui_renderer: &mut egui_wgpu::Renderer,
+        rect: Viewport,
+        surface_size: (u32, u32),
+    ) -> Self {
+        let (texture, view, sampled_view) = Self::create_texture(gpu, rect.size());
+        let stores_linear = !sampled_view_format(gpu).is_srgb();
+        let texture_id = egui_renderer.register_native_texture(
+            &gpu.device,
+            &sampled_view,
+            wgpu::FilterMode::Linear,
+        );
+        Self {
+            rect,
+            texture,
+            view,
+            sampled_view,
+            stores_linear,
+            texture_id,
+            gui_depth_texture_view: gpu.create_depth_texture(surface_size.0, surface_size.1),
+        }
+    }
+
+    /// Returns the rectangle of the surface the panel covers, in physical pixels.
+    pub fn rect(&self) -> Viewport {
+        self.rect
+    }
+
+    /// Returns the view the post-processing pass writes the scene into.
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// Returns `true` if the GUI samples the texture as linear colors, so the post-processing
+    /// pass must not gamma-encode the scene.
+    pub fn stores_linear(&self) -> bool {
+        self.stores_linear
+    }
+
+    /// Returns the id the GUI shows the scene with.
+    pub fn texture_id(&self) -> egui::TextureId {
+        self.texture_id
+    }
+
+    /// Returns the depth texture the GUI is drawn with.
+    pub fn gui_depth_texture_view(&self) -> &wgpu::TextureView {
+        &self.gui_depth_texture_view
+    }
+
+    /// Moves the panel to `rect`. If its size changed, the texture is recreated and
+    /// re-registered with `egui_renderer` under the same id, the replaced one is retired to
+    /// `deletion_queue`, and `true` is returned.
+    pub fn set_rect(
+        &mut self,
+        gpu: &Gpu,
+        egui_renderer: &mut egui_wgpu::Renderer,
+        rect: Viewport,
+        deletion_queue: &mut DeletionQueue,
+    ) -> bool {
+        let resized = rect.size() != self.rect.size();
+        self.rect = rect;
+        if !resized {
+            return false;
+        }
+
+        let (texture, view, sampled_view) = Self::create_texture(gpu, rect.size());
+        egui_renderer.update_egui_texture_from_wgpu_texture(
+            &gpu.device,
+            &sampled_view,
+            wgpu::FilterMode::Linear,
+            self.texture_id,
+        );
+        deletion_queue.retire(std::mem::replace(&mut self.texture, texture));
+        deletion_queue.retire(std::mem::replace(&mut self.view, view));
+        deletion_queue.retire(std::mem::replace(&mut self.sampled_view, sampled_view));
+        true
+    }
+
+    /// Recreates the GUI's depth texture for a surface of `width` x `height` pixels, retiring
+    /// the replaced one to `deletion_queue`.
+    pub fn resize_surface(
+        &mut self,
+        gpu: &Gpu,
+        width: u32,
+        height: u32,
+        deletion_queue: &mut DeletionQueue,
+    ) {
+        deletion_queue.retire(std::mem::replace(
+            &mut self.gui_depth_texture_view,
+            gpu.create_depth_texture(width, height),
+        ));
+    }
+
+    /// Unregisters the texture from `egui_renderer` and retires the panel's resources to
+    /// `deletion_queue`.
+    pub fn release(
+        self,
+        egui_renderer: &mut egui_wgpu::Renderer,
+        deletion_queue: &mut DeletionQueue,
+    ) {
+        egui_renderer.free_texture(&self.texture_id);
+        deletion_queue.retire(self.texture);
+        deletion_queue.retire(self.view);
+        deletion_queue.retire(self.sampled_view);
+        deletion_queue.retire(self.gui_depth_texture_view);
+    }
+
+    /// Creates the panel's texture of `size` pixels, with the view rendered into and the view
+    /// sampled by the GUI.
+    fn create_texture(
+        gpu: &Gpu,
+        size: (u32, u32),
+    ) -> (wgpu::Texture, wgpu::TextureView, wgpu::TextureView) {
+        let format = gpu.surface_format;
+        let sampled_format = sampled_view_format(gpu);
+        let texture = gpu.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Scene Panel Texture"),
+            size: wgpu::Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: if sampled_format == format {
+                &[]
+            } else {
+                std::slice::from_ref(&sampled_format)
+            },
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampled_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Scene Panel Sampled View"),
+            format: Some(sampled_format),
+            ..Default::default()
+        });
+        (texture, view, sampled_view)
+    }
+}
+
+/// Returns the format the GUI samples the panel's texture in: the sRGB variant of the surface's
+/// format, if the device can view textures in a format of their own.
+fn sampled_view_format(gpu: &Gpu) -> wgpu::TextureFormat {
+    let downlevel = gpu.adapter.get_downlevel_capabilities();
+    if downlevel.flags.contains(wgpu::DownlevelFlags::VIEW_FORMATS) {
+        gpu.surface_format.add_srgb_suffix()
+    } else {
+        gpu.surface_format
+    }
+}
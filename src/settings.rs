@@ -0,0 +1,233 @@
+//! # Settings Module
+//!
+//! The `settings` module persists user preferences and the state of the last session between
+//! sessions.
+//!
+//! ## Overview
+//!
+//! - [`Settings`] holds the persisted preferences and is stored as JSON:
+//!   - **Desktop**: In `settings.json` in the platform configuration directory (for example
+//!     `~/.config/wgpu-example` on Linux).
+//!   - **Web**: In the browser's local storage.
+//! - Loading never fails: a missing or unreadable file yields the defaults, and missing fields
+//!   keep their default values, so older settings files remain loadable.
+//! - The background color is only saved once it is changed, so until then the default keeps
+//!   following whether the window is transparent.
+//! - The window's size and position, the camera's pose, and whether the panels are shown are
+//!   saved when the application exits and restored when it starts, so it reopens as it was
+//!   left. The window's geometry is ignored on the web, where the canvas decides the size.
+//! - The key bindings are saved as an object from action names to keys or mouse buttons (see
+//!   the `actions` module).
+//! - [`GuiScale`] is the GUI zoom, applied to `egui` independently of the operating system's
+//!   DPI scaling. It is validated to stay within [`GuiScale::MIN`] and [`GuiScale::MAX`], also
+//!   when loaded from a hand-edited settings file.
+//!
+//! The `App` loads the settings when it starts and saves them whenever they change and when
+//! it exits.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut settings = Settings::load();
+//! settings.gui_scale = settings.gui_scale.zoomed_in();
+//! if let Err(error) = settings.save() {
+//!     log::warn!("{error}");
+//! }
+//! ```
+
+/// Errors produced while saving the settings.
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    /// The settings could not be serialized.
+    #[error("failed to serialize the settings: {0}")]
+    Serialize(#[from] serde_json::Error),
+
+    /// The settings file could not be written.
+    #[error("failed to write the settings file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// There is no place to store the settings, such as a configuration directory or the
+    /// browser's local storage.
+    #[error("no settings storage is available")]
+    Unavailable,
+}
+
+/// The GUI zoom factor: how many points `egui` lays out per native point.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "f32", into = "f32")]
+pub struct GuiScale(f32);
+
+impl Default for GuiScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl From<f32> for GuiScale {
+    fn from(scale: f32) -> Self {
+        Self::new(scale)
+    }
+}
+
+impl From<GuiScale> for f32 {
+    fn from(scale: GuiScale) -> Self {
+        scale.0
+    }
+}
+
+impl GuiScale {
+    /// The smallest scale.
+    pub const MIN: f32 = 0.5;
+
+    /// The largest scale.
+    pub const MAX: f32 = 3.0;
+
+    /// The change per zoom shortcut.
+    pub const STEP: f32 = 0.1;
+
+    /// Creates a scale, clamped to [`GuiScale::MIN`] and [`GuiScale::MAX`]. Non-finite values
+    /// yield the default scale.
+    pub fn new(scale: f32) -> Self {
+        if scale.is_finite() {
+            Self(scale.clamp(Self::MIN, Self::MAX))
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Returns the scale factor.
+    pub fn get(self) -> f32 {
+        self.0
+    }
+
+    /// Returns the next larger scale, rounded to a multiple of [`GuiScale::STEP`].
+    pub fn zoomed_in(self) -> Self {
+        Self::new(((self.0 + Self::STEP) / Self::STEP).round() * Self::STEP)
+    }
+
+    /// Returns the next smaller scale, rounded to a multiple of [`GuiScale::STEP`].
+    pub fn zoomed_out(self) -> Self {
+        Self::new(((self.0 - Self::STEP) / Self::STEP).round() * Self::STEP)
+    }
+}
+
+/// The size and position of the window, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WindowGeometry {
+    /// The size of the window's client area.
+    pub size: [u32; 2],
+
+    /// The position of the window's top-left corner on the desktop, where the platform
+    /// reports it.
+    pub position: Option<[i32; 2]>,
+
+    /// Whether the window was maximized. The size and position are then the ones it had
+    /// before being maximized.
+    pub maximized: bool,
+}
+
+/// The pose of the camera.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CameraPose {
+    /// The camera's position in the renderer's left-handed world.
+    pub eye: [f32; 3],
+
+    /// The direction the camera looks in.
+    pub forward: [f32; 3],
+
+    /// The distance to the point the orbit camera circles.
+    pub focus_distance: f32,
+
+    /// The vertical field of view, in degrees.
+    pub field_of_view: f32,
+}
+
+/// The preferences persisted between sessions.
+#[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The GUI zoom factor.
+    pub gui_scale: GuiScale,
+
+    /// The background color as linear RGBA with unmultiplied alpha, or `None` for the
+    /// application's default.
+    pub clear_color: Option<[f32; 4]>,
+
+    /// The keys and mouse buttons bound to the actions.
+    pub bindings: crate::actions::ActionMap,
+
+    /// Whether the top, left, right, and bottom panels are shown.
+    pub panels_visible: bool,
+
+    /// The window's geometry when the application last exited, or `None` for the platform's
+    /// default.
+    pub window: Option<WindowGeometry>,
+
+    /// The camera's pose when the application last exited, or `None` for the default view.
+    pub camera: Option<CameraPose>,
+}
+
+impl Settings {
+    /// The name of the settings file, or the local storage key on the web.
+    const NAME: &'static str = "settings.json";
+
+    /// Loads the saved settings, or the defaults if there are none or they cannot be read.
+    pub fn load() -> Self {
+        let Some(json) = Self::read() else {
+            return Self::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|error| {
+            log::warn!("Ignoring invalid settings: {error}");
+            Self::default()
+        })
+    }
+
+    /// Saves the settings, replacing the saved ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SettingsError` if the settings could not be written.
+    pub fn save(&self) -> Result<(), SettingsError> {
+        Self::write(&serde_json::to_string_pretty(self)?)
+    }
+
+    /// Returns the path of the settings file.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn path() -> Option<std::path::PathBuf> {
+        Some(dirs::config_dir()?.join("wgpu-example").join(Self::NAME))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read() -> Option<String> {
+        std::fs::read_to_string(Self::path()?).ok()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write(json: &str) -> Result<(), SettingsError> {
+        let path = Self::path().ok_or(SettingsError::Unavailable)?;
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Returns the browser's local storage.
+    #[cfg(target_arch = "wasm32")]
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok().flatten()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn read() -> Option<String> {
+        Self::storage()?.get_item(Self::NAME).ok().flatten()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn write(json: &str) -> Result<(), SettingsError> {
+        Self::storage()
+            .ok_or(SettingsError::Unavailable)?
+            .set_item(Self::NAME, json)
+            .map_err(|_| SettingsError::Unavailable)
+    }
+}
@@ -0,0 +1,39 @@
+//! # Shader Bindings Module
+//!
+//! The `shader_bindings` module holds the Rust side of the shaders' bind groups, generated at
+//! build time from the WGSL sources, so the layouts can no longer drift from the shaders.
+//!
+//! ## Overview
+//!
+//! The build script (`build.rs`) parses and validates every `src/*.wgsl` shader with `naga`.
+//! Each shader becomes a module named after its file, such as [`shader_source`] for
+//! `shader_source.wgsl`, with a `BindGroupN` struct for each `@group(N)`:
+//!
+//! - One field per binding, named after the WGSL variable: a `wgpu::BufferBinding` for
+//!   uniform and storage buffers, a `&wgpu::TextureView` for textures, and a `&wgpu::Sampler`
+//!   for samplers.
+//! - `LAYOUT_ENTRIES`, the layout entries. Each binding is visible to the stages whose entry
+//!   points use it, and buffers require at least the size of their WGSL type.
+//! - `create_layout`, which creates the group's layout, and `create`, which creates a bind
+//!   group of the struct's resources.
+//!
+//! WGSL does not tell whether a float texture is filtered, so float textures and samplers are
+//! generated as filterable. Groups binding unfilterable textures, such as the overdraw
+//! heatmap's counts, and groups shared by pipelines using only some of their bindings keep a
+//! hand-written layout.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! // WGSL: @group(0) @binding(0) var<uniform> ubo: Uniform;
+//! let bind_group_layout = shader_source::BindGroup0::create_layout(&device);
+//! let bind_group = shader_source::BindGroup0 {
+//!     ubo: buffer.as_entire_buffer_binding(),
+//! }
+//! .create(&device, &bind_group_layout);
+//! ```
+
+// Not every generated group is bound from Rust, and some shaders are behind features.
+#![allow(dead_code)]
+
+include!(concat!(env!("OUT_DIR"), "/shader_bindings.rs"));
@@ -0,0 +1,110 @@
+//! # Shader Editor Module
+//!
+//! The `shader_editor` module holds the state of the Shader Editor window, which edits the WGSL
+//! source of the scene's shader while the application runs.
+//!
+//! ## Overview
+//!
+//! - [`ShaderEditor`] holds the edited source, whether the window is shown, and the error of
+//!   the last attempt to apply the source, shown below the editor.
+//! - Pressing "Apply" requests the source to be applied. The `App` takes the request with
+//!   [`ShaderEditor::take_apply_request`] and hands the source to
+//!   `Renderer::set_scene_shader`, which recompiles the scene's pipelines inside a
+//!   `wgpu` error scope.
+//! - The error scope resolves right away on desktop platforms, and in a later frame with
+//!   WebGPU in the browser. The `App` polls `Renderer::take_scene_shader_result` every frame,
+//!   which installs the new pipelines once they are valid, and hands the result to
+//!   [`ShaderEditor::set_result`]. The window shows that the shader is being checked until
+//!   then.
+//! - [`ShaderError`] is returned instead of the new pipelines when the shader does not compile
+//!   or does not match the scene's vertex layout and bind groups. The scene keeps drawing with
+//!   its previous pipelines.
+//!
+//! Only the pipelines drawing each mesh once are rebuilt. The instanced pipelines (see the
+//! `instancing` module) keep the shader of `instanced.wgsl`.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! // Every frame, in the `App`:
+//! if let Some(source) = ui_state.shader_editor.take_apply_request() {
+//!     renderer.set_scene_shader(&source);
+//! }
+//! if let Some(result) = renderer.take_scene_shader_result() {
+//!     ui_state.shader_editor.set_result(result);
+//! }
+//! ```
+
+// Importing the built-in WGSL source of the scene's shader, the editor's initial text.
+use crate::SHADER_SOURCE;
+
+/// The state of the Shader Editor window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderEditor {
+    /// Whether the window is shown.
+    pub visible: bool,
+
+    /// The edited WGSL source.
+    pub source: String,
+
+    /// The error of the last attempt to apply the source, or `None` if it was applied.
+    pub error: Option<String>,
+
+    /// Whether the source was handed to the renderer and its result has not arrived yet.
+    pub checking: bool,
+
+    /// Whether "Apply" was pressed since the request was last taken.
+    apply_requested: bool,
+}
+
+impl Default for ShaderEditor {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            source: SHADER_SOURCE.to_string(),
+            error: None,
+            checking: false,
+            apply_requested: false,
+        }
+    }
+}
+
+impl ShaderEditor {
+    /// Requests the edited source to be applied with the next frame.
+    pub fn request_apply(&mut self) {
+        self.apply_requested = true;
+    }
+
+    /// Replaces the edited source with the built-in shader and requests it to be applied.
+    pub fn reset(&mut self) {
+        self.source = SHADER_SOURCE.to_string();
+        self.request_apply();
+    }
+
+    /// Returns the source to apply if "Apply" was pressed, once per press, and marks it as
+    /// being checked until [`ShaderEditor::set_result`].
+    pub fn take_apply_request(&mut self) -> Option<String> {
+        let source = std::mem::take(&mut self.apply_requested).then(|| self.source.clone());
+        self.checking |= source.is_some();
+        source
+    }
+
+    /// Records the result of applying the source, to show its error below the editor.
+    pub fn set_result(&mut self, result: Result<(), ShaderError>) {
+        self.checking = false;
+        self.error = result.err().map(|error| error.to_string());
+    }
+}
+
+/// Errors that can occur while replacing the scene's shader.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ShaderError {
+    /// The shader failed to compile, or the pipelines could not be created from it. The
+    /// message is the `wgpu` validation error, with the location in the source if known.
+    #[error("{0}")]
+    Invalid(String),
+
+    /// The renderer backend does not draw with shaders.
+    #[error("this renderer does not support custom shaders")]
+    Unsupported,
+}
@@ -1,17 +1,81 @@
 struct Uniform {
     mvp: mat4x4<f32>,
+    // Places positions and normals in world space, where the light is shaded.
+    model: mat4x4<f32>,
+    // The id of the node written to the object id target, read back by the depth probe and
+    // picking; 0 is reserved for the background.
+    object_id: u32,
 };
 
 @group(0) @binding(0)
 var<uniform> ubo: Uniform;
 
+// The environment settings, shared with the sky (see `environment.wgsl`).
+struct Environment {
+    // rgb: ambient and image-based light, a: exposure scale.
+    ambient: vec4<f32>,
+    // rgb: fog color, a: fog density (0 disables fog).
+    fog: vec4<f32>,
+    sky_horizon: vec4<f32>,
+    sky_zenith: vec4<f32>,
+    inverse_view_projection: mat4x4<f32>,
+};
+
+@group(1) @binding(0)
+var<uniform> environment: Environment;
+
+// The material of the mesh being drawn (see `material.rs`), in the metallic-roughness model.
+struct Material {
+    // rgb: base color, multiplied with the vertex colors.
+    base_color: vec4<f32>,
+    // rgb: emitted light.
+    emissive: vec4<f32>,
+    // x: metallic, y: roughness.
+    params: vec4<f32>,
+};
+
+@group(2) @binding(0)
+var<uniform> material: Material;
+// The material's base color texture, or the scene's texture (white until one is loaded).
+@group(2) @binding(1)
+var base_color_texture: texture_2d<f32>;
+// Roughness in green, metallic in blue. White unless the material has one.
+@group(2) @binding(2)
+var metallic_roughness_texture: texture_2d<f32>;
+// Multiplied with the emissive color. White unless the material has one.
+@group(2) @binding(3)
+var emissive_texture: texture_2d<f32>;
+@group(2) @binding(4)
+var material_sampler: sampler;
+
+// The scene's directional light (see `lighting.rs`).
+struct Light {
+    // xyz: unit direction towards the light, w: share of the ambient light.
+    direction: vec4<f32>,
+    // rgb: light color times intensity.
+    color: vec4<f32>,
+    // xyz: camera position in world space.
+    eye: vec4<f32>,
+};
+
+@group(3) @binding(0)
+var<uniform> light: Light;
+
 struct VertexInput {
     @location(0) position: vec4<f32>,
     @location(1) color: vec4<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) normal: vec3<f32>,
 };
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) color: vec4<f32>,
+    // The distance along the view direction, for fog.
+    @location(1) view_depth: f32,
+    @location(2) uv: vec2<f32>,
+    @location(3) world_position: vec3<f32>,
+    @location(4) normal: vec3<f32>,
+    @location(5) @interpolate(flat) object_id: u32,
 };
 
 @vertex
@@ -19,10 +83,95 @@ fn vertex_main(vert: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.color = vert.color;
     out.position = ubo.mvp * vert.position;
+    out.view_depth = out.position.w;
+    out.uv = vert.uv;
+    out.world_position = (ubo.model * vert.position).xyz;
+    out.object_id = ubo.object_id;
+    out.normal = (ubo.model * vec4<f32>(vert.normal, 0.0)).xyz;
     return out;
 };
 
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) object_id: u32,
+};
+
+const PI: f32 = 3.14159265;
+
+// A stub for image-based lighting: the light arriving from `direction`, blurred by
+// `roughness`. Until the environment provides prefiltered maps, the environment's ambient
+// light, which already includes the sky's light, stands in for every direction.
+fn environment_light(direction: vec3<f32>, roughness: f32) -> vec3<f32> {
+    return environment.ambient.rgb;
+}
+
+// Schlick's approximation of the Fresnel reflectance at `cos_theta`, for a surface
+// reflecting `f0` head-on.
+fn fresnel_schlick(cos_theta: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (1.0 - f0) * pow(1.0 - cos_theta, 5.0);
+}
+
+// Shades a surface of `base_color`, `metallic`, and `roughness` at `world_position` with the
+// Cook-Torrance BRDF: GGX distribution, Smith-Schlick geometry, and Schlick's Fresnel term.
+// The scene's light is direct light, and the environment lights the surface from all
+// directions. Faces are not culled, so each face is lit on the side the camera sees.
+fn shade(
+    base_color: vec3<f32>,
+    metallic: f32,
+    roughness: f32,
+    world_position: vec3<f32>,
+    vertex_normal: vec3<f32>,
+) -> vec3<f32> {
+    let to_eye = normalize(light.eye.xyz - world_position);
+    var normal = normalize(vertex_normal);
+    if dot(normal, to_eye) < 0.0 {
+        normal = -normal;
+    }
+    let to_light = light.direction.xyz;
+    let halfway = normalize(to_light + to_eye);
+    let n_dot_l = max(dot(normal, to_light), 0.0);
+    let n_dot_v = max(dot(normal, to_eye), 1e-4);
+    let n_dot_h = max(dot(normal, halfway), 0.0);
+
+    // Perfectly smooth surfaces would reflect the light in a single point.
+    let alpha = max(roughness * roughness, 0.002);
+    let alpha2 = alpha * alpha;
+    let d = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+    let distribution = alpha2 / (PI * d * d);
+    let k = (roughness + 1.0) * (roughness + 1.0) / 8.0;
+    let geometry = n_dot_l / (n_dot_l * (1.0 - k) + k) * n_dot_v / (n_dot_v * (1.0 - k) + k);
+    let f0 = mix(vec3<f32>(0.04), base_color, metallic);
+    let fresnel = fresnel_schlick(max(dot(halfway, to_eye), 0.0), f0);
+    let specular = distribution * geometry * fresnel / max(4.0 * n_dot_l * n_dot_v, 1e-4);
+
+    // The light's color is its irradiance on a surface facing it, scaled by pi, so a white
+    // dielectric facing a white light of intensity 1 is lit to about its base color.
+    let diffuse = (1.0 - fresnel) * (1.0 - metallic) * base_color;
+    let direct = (diffuse + PI * specular) * light.color.rgb * n_dot_l;
+
+    let ambient_fresnel = fresnel_schlick(n_dot_v, f0);
+    let reflected = reflect(-to_eye, normal);
+    let ambient = (1.0 - ambient_fresnel) * (1.0 - metallic) * base_color
+        * environment_light(normal, 1.0)
+        + ambient_fresnel * environment_light(reflected, roughness);
+    return direct + ambient * light.direction.w;
+}
+
 @fragment
-fn fragment_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(in.color);
-}
\ No newline at end of file
+fn fragment_main(in: VertexOutput) -> FragmentOutput {
+    let base_color = in.color * material.base_color
+        * textureSample(base_color_texture, material_sampler, in.uv);
+    let metallic_roughness = textureSample(metallic_roughness_texture, material_sampler, in.uv);
+    let metallic = material.params.x * metallic_roughness.b;
+    let roughness = material.params.y * metallic_roughness.g;
+    let emissive = material.emissive.rgb
+        * textureSample(emissive_texture, material_sampler, in.uv).rgb;
+    let lit = shade(base_color.rgb, metallic, roughness, in.world_position, in.normal) + emissive;
+    let fog = 1.0 - exp(-environment.fog.a * in.view_depth);
+    let color = mix(lit, environment.fog.rgb, fog) * environment.ambient.a;
+
+    var out: FragmentOutput;
+    out.color = vec4<f32>(color, base_color.a);
+    out.object_id = in.object_id;
+    return out;
+}
@@ -0,0 +1,430 @@
+//! # Simulation Module
+//!
+//! The `simulation` module advances the scene's state (animation and, in the future, physics)
+//! independently of rendering.
+//!
+//! ## Overview
+//!
+//! - [`animate`] advances the scene's model matrix by a time step. It is pure CPU code with no
+//!   GPU access, so it can run on any thread.
+//! - [`SceneSnapshot`] is an immutable copy of the simulated state, handed to the renderer.
+//! - [`TripleBuffer`] passes snapshots from a producer to a consumer without either side ever
+//!   waiting for the other to finish its work.
+//! - [`SimulationThread`] (native only) runs `animate` at a fixed tick rate on a dedicated
+//!   thread and publishes a snapshot after every tick, with the one before it and the time it
+//!   was published, so the renderer can draw the state between the two.
+//! - [`FixedStepSimulation`] advances the scene in the same fixed ticks on the render thread,
+//!   for determinism mode where no simulation thread is available.
+//! - [`simulate`] runs a number of ticks synchronously and returns their state hashes, for
+//!   replay tests.
+//!
+//! Every tick's [`SceneSnapshot::state_hash`] is recorded in a [`TickLog`], so two runs can be
+//! compared tick by tick.
+//!
+//! On native platforms the renderer reads the two most recent snapshots each frame, so a slow
+//! simulation step never delays input handling or presentation, and a slow frame never delays
+//! the simulation. It draws the state between them by the time passed since the later one,
+//! so the motion stays smooth when the frame rate is not a multiple of the tick rate.
+//!
+//! On WebAssembly, where threads are not available by default, the scene is advanced on the
+//! render thread in the same fixed ticks. The `App` splits each frame's delta time into ticks
+//! with a `FixedTimestep` and hands them to `Renderer::tick_scene`, which calls
+//! `Scene::tick` (or [`FixedStepSimulation::step`] in determinism mode) once per tick and draws
+//! the state between the last two ticks by the time the timestep has left over.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut simulation = SimulationThread::spawn(scene.model);
+//!
+//! // Once per frame on the render thread:
+//! if let Some((previous, snapshot, alpha)) = simulation.latest_interpolated() {
+//!     scene.apply_snapshot(&mut uploads, aspect_ratio, &previous, &snapshot, alpha);
+//! }
+//! ```
+
+// Importing `Mutex` to guard the middle slot of the triple buffer. The lock is only held for
+// a pointer swap, so neither side can be blocked by the other's work.
+use std::sync::Mutex;
+
+use crate::determinism::{StateHasher, TickLog};
+use crate::Duration;
+
+/// The rotation speed of the scene's model, in degrees per second.
+const ROTATION_DEGREES_PER_SECOND: f32 = 30.0;
+
+/// The duration of one simulation tick (120 ticks per second).
+pub const TICK: Duration = Duration::from_nanos(1_000_000_000 / 120);
+
+/// Advances the scene's `model` matrix by `delta_time` seconds.
+///
+/// The model spins around the Y axis at a constant rate.
+pub fn animate(model: &crate::math::Mat4, delta_time: f32) -> crate::math::Mat4 {
+    crate::math::rotate(
+        model,
+        ROTATION_DEGREES_PER_SECOND.to_radians() * delta_time,
+        &crate::math::up(),
+    )
+}
+
+/// An immutable copy of the simulated scene state at one tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneSnapshot {
+    /// The model transformation matrix of the scene's object.
+    pub model: crate::math::Mat4,
+
+    /// The number of simulation ticks that produced this snapshot.
+    pub tick: u64,
+}
+
+impl SceneSnapshot {
+    /// Creates the snapshot before the first tick.
+    pub fn new(model: crate::math::Mat4) -> Self {
+        Self { model, tick: 0 }
+    }
+
+    /// Advances the state by one [`TICK`].
+    pub fn step(&mut self) {
+        self.model = animate(&self.model, TICK.as_secs_f32());
+        self.tick += 1;
+    }
+
+    /// Returns a hash of the simulated state, equal for two snapshots only if every value in
+    /// them is bit-identical.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+        hasher.write_u64(self.tick);
+        for value in crate::math::to_cols_array(&self.model) {
+            hasher.write_f32(value);
+        }
+        hasher.finish()
+    }
+}
+
+/// Runs `ticks` ticks starting from `model`, and returns the state hash after each of them.
+///
+/// The run is independent of wall-clock time, so two calls with the same arguments must return
+/// identical logs.
+pub fn simulate(model: crate::math::Mat4, ticks: u64) -> TickLog {
+    let mut snapshot = SceneSnapshot::new(model);
+    let mut log = TickLog::default();
+    for _ in 0..ticks {
+        snapshot.step();
+        log.push(snapshot.tick, snapshot.state_hash());
+    }
+    log
+}
+
+/// Advances the scene in fixed ticks on the render thread.
+///
+/// The caller splits each frame's delta time into whole ticks with a `FixedTimestep` and
+/// steps the simulation once per tick, so the simulated states do not depend on the frame rate.
+#[derive(Debug, Clone)]
+pub struct FixedStepSimulation {
+    /// The state before the most recent tick, to interpolate the drawn state from.
+    previous: SceneSnapshot,
+
+    /// The state after the most recent tick.
+    snapshot: SceneSnapshot,
+
+    /// The state hash after every tick.
+    log: TickLog,
+}
+
+impl FixedStepSimulation {
+    /// Creates a simulation starting from the given `model` matrix.
+    pub fn new(model: crate::math::Mat4) -> Self {
+        let snapshot = SceneSnapshot::new(model);
+        Self {
+            previous: snapshot,
+            snapshot,
+            log: TickLog::default(),
+        }
+    }
+
+    /// Simulates one [`TICK`] and records its state hash.
+    pub fn step(&mut self) {
+        self.previous = self.snapshot;
+        self.snapshot.step();
+        self.log
+            .push(self.snapshot.tick, self.snapshot.state_hash());
+    }
+
+    /// Returns the state before the most recent tick.
+    pub fn previous(&self) -> &SceneSnapshot {
+        &self.previous
+    }
+
+    /// Returns the state after the most recent tick.
+    pub fn snapshot(&self) -> &SceneSnapshot {
+        &self.snapshot
+    }
+
+    /// Returns the state hash after every tick simulated so far.
+    pub fn tick_log(&self) -> &TickLog {
+        &self.log
+    }
+}
+
+/// A triple buffer passing values from one producer to one consumer.
+///
+/// The producer works on its own back buffer and publishes a finished value into the shared
+/// middle slot. The consumer swaps the middle slot with its own front buffer when a new value
+/// was published. Each side therefore owns one buffer exclusively, and only the exchange with
+/// the middle slot is synchronized, so the consumer always sees the most recent complete value
+/// and never waits for the producer to finish its work (and vice versa).
+#[derive(Debug)]
+pub struct TripleBuffer<T> {
+    /// The value most recently published, and whether the consumer has not yet taken it.
+    middle: Mutex<(T, bool)>,
+}
+
+impl<T> TripleBuffer<T> {
+    /// Creates a triple buffer whose middle slot holds `initial`, not yet marked as published.
+    pub fn new(initial: T) -> Self {
+        Self {
+            middle: Mutex::new((initial, false)),
+        }
+    }
+
+    /// Publishes a finished `value`, replacing any value the consumer has not taken yet.
+    ///
+    /// Returns the value previously held by the middle slot, so its allocation can be reused
+    /// as the producer's next back buffer.
+    pub fn publish(&self, value: T) -> T {
+        let mut middle = self
+            .middle
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        middle.1 = true;
+        std::mem::replace(&mut middle.0, value)
+    }
+
+    /// Swaps the consumer's `front` buffer with the most recently published value.
+    ///
+    /// Returns `true` if a new value was published since the last call. Otherwise `front` is
+    /// left unchanged.
+    pub fn consume(&self, front: &mut T) -> bool {
+        let mut middle = self
+            .middle
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        if !middle.1 {
+            return false;
+        }
+        std::mem::swap(&mut middle.0, front);
+        middle.1 = false;
+        true
+    }
+}
+
+/// A snapshot published by the [`SimulationThread`], with the state before it.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy)]
+struct PublishedSnapshot {
+    /// The state before the tick of `snapshot`.
+    previous: SceneSnapshot,
+
+    /// The state after the tick.
+    snapshot: SceneSnapshot,
+
+    /// When the tick finished.
+    published: std::time::Instant,
+}
+
+/// A dedicated thread stepping the simulation at a fixed rate.
+///
+/// Each tick advances the scene with [`animate`] and publishes a [`SceneSnapshot`] through a
+/// [`TripleBuffer`], and records the snapshot's state hash. The thread is stopped and joined
+/// when the `SimulationThread` is dropped.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct SimulationThread {
+    /// The buffer snapshots are published through.
+    buffer: std::sync::Arc<TripleBuffer<PublishedSnapshot>>,
+
+    /// The render thread's copy of the latest snapshot.
+    front: PublishedSnapshot,
+
+    /// The state hash after every tick.
+    log: std::sync::Arc<Mutex<TickLog>>,
+
+    /// Set to ask the thread to stop.
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+
+    /// The handle used to join the thread.
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SimulationThread {
+    /// The duration of one simulation tick (120 ticks per second).
+    pub const TICK: std::time::Duration = TICK;
+
+    /// The most ticks the thread runs back to back to catch up after falling behind. Beyond
+    /// that, the missed time is dropped, so a long stall does not cause a burst of ticks.
+    pub const MAX_CATCH_UP_TICKS: u32 = 8;
+
+    /// Spawns the simulation thread, starting from the given `model` matrix.
+    pub fn spawn(model: crate::math::Mat4) -> Self {
+        let front = PublishedSnapshot {
+            previous: SceneSnapshot::new(model),
+            snapshot: SceneSnapshot::new(model),
+            published: std::time::Instant::now(),
+        };
+        let buffer = std::sync::Arc::new(TripleBuffer::new(front));
+        let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let log = std::sync::Arc::new(Mutex::new(TickLog::default()));
+
+        let handle = {
+            let buffer = buffer.clone();
+            let stop = stop.clone();
+            let log = log.clone();
+            std::thread::Builder::new()
+                .name("simulation".to_string())
+                .spawn(move || {
+                    // The producer's back buffer, holding the authoritative simulation state.
+                    let mut back = front;
+                    let mut next_tick = std::time::Instant::now() + Self::TICK;
+                    while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                        back.previous = back.snapshot;
+                        back.snapshot.step();
+                        back.published = std::time::Instant::now();
+                        buffer.publish(back);
+                        log.lock()
+                            .unwrap_or_else(|error| error.into_inner())
+                            .push(back.snapshot.tick, back.snapshot.state_hash());
+
+                        // Sleep until the next tick. If steps overran, the missed ticks run
+                        // back to back, unless the thread fell further behind than the catch-up
+                        // bound, in which case the missed time is dropped.
+                        let now = std::time::Instant::now();
+                        if next_tick > now {
+                            std::thread::sleep(next_tick - now);
+                        } else if now - next_tick > Self::TICK * Self::MAX_CATCH_UP_TICKS {
+                            next_tick = now;
+                        }
+                        next_tick += Self::TICK;
+                    }
+                })
+                .map_err(|error| log::error!("Failed to spawn simulation thread: {error}"))
+                .ok()
+        };
+
+        Self {
+            buffer,
+            front,
+            log,
+            stop,
+            handle,
+        }
+    }
+
+    /// Returns the most recent snapshot, or `None` if the thread could not be spawned.
+    ///
+    /// This never waits for the simulation: if no new tick completed since the last call, the
+    /// previous snapshot is returned again.
+    pub fn latest(&mut self) -> Option<&SceneSnapshot> {
+        self.handle.as_ref()?;
+        self.buffer.consume(&mut self.front);
+        Some(&self.front.snapshot)
+    }
+
+    /// Returns the snapshot before the most recent one, the most recent one, and the fraction
+    /// of a tick passed since the latter was published, from `0.0` to `1.0`, to draw the state
+    /// between them. Returns `None` if the thread could not be spawned.
+    ///
+    /// Like [`SimulationThread::latest`], this never waits for the simulation.
+    pub fn latest_interpolated(&mut self) -> Option<(SceneSnapshot, SceneSnapshot, f32)> {
+        self.latest()?;
+        let elapsed = self.front.published.elapsed().as_secs_f32();
+        let alpha = (elapsed / Self::TICK.as_secs_f32()).min(1.0);
+        Some((self.front.previous, self.front.snapshot, alpha))
+    }
+
+    /// Returns whether the thread is running, advancing the scene in place of the render
+    /// thread.
+    pub fn is_running(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// Returns a copy of the state hash after every tick simulated so far.
+    pub fn tick_log(&self) -> TickLog {
+        self.log
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone()
+    }
+
+    /// Stops the thread and waits for it to finish its current tick.
+    pub fn stop(&mut self) {
+        self.stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                log::error!("Simulation thread panicked");
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for SimulationThread {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::determinism::FixedTimestep;
+
+    /// Advances a simulation by `frames` frames of `frame` each, split into ticks like the
+    /// `App` does, and returns its tick log.
+    fn run_frames(frame: Duration, frames: u32) -> TickLog {
+        let mut timestep = FixedTimestep::new(TICK);
+        let mut simulation = FixedStepSimulation::new(crate::math::identity());
+        for _ in 0..frames {
+            for _ in 0..timestep.advance(frame) {
+                simulation.step();
+            }
+        }
+        simulation.tick_log().clone()
+    }
+
+    #[test]
+    fn simulate_is_reproducible() {
+        let log = simulate(crate::math::identity(), 240);
+
+        assert_eq!(log.last().map(|(tick, _)| tick), Some(240));
+        assert_eq!(
+            log.first_divergence(&simulate(crate::math::identity(), 240)),
+            None
+        );
+    }
+
+    #[test]
+    fn fixed_steps_do_not_depend_on_the_frame_rate() {
+        let expected = simulate(crate::math::identity(), 120);
+
+        for (frame, frames) in [(TICK, 120), (Duration::from_millis(7), 150), (TICK * 3, 40)] {
+            let log = run_frames(frame, frames);
+            assert!(log.last().is_some_and(|(tick, _)| tick >= 100));
+            assert_eq!(log.first_divergence(&expected), None, "frames of {frame:?}");
+        }
+    }
+
+    #[test]
+    fn steps_keep_the_state_before_the_last_tick() {
+        let mut simulation = FixedStepSimulation::new(crate::math::identity());
+        assert_eq!(simulation.previous(), simulation.snapshot());
+
+        simulation.step();
+        simulation.step();
+        let mut expected = SceneSnapshot::new(crate::math::identity());
+        expected.step();
+        assert_eq!(*simulation.previous(), expected);
+        expected.step();
+        assert_eq!(*simulation.snapshot(), expected);
+    }
+}
@@ -0,0 +1,531 @@
+//! # Software Rendering Module
+//!
+//! The `software` module provides [`SoftwareBackend`], a [`RendererBackend`] that rasterizes
+//! on the CPU and presents through `softbuffer`. It keeps the application usable on virtual
+//! machines and CI runners without GPU drivers, where no `wgpu` adapter (not even a fallback
+//! one) can be created.
+//!
+//! ## Overview
+//!
+//! The backend draws a simplified version of the frame:
+//!
+//! - The scene's triangle, spun by the same [`animate`] ticks as on the GPU, drawn between the
+//!   last two of them, and projected with the camera and [`Projection`] set by the `App`, with
+//!   perspective-correct vertex colors and a depth test.
+//! - The `egui` paint jobs, when the `gui` feature is enabled, with their textures sampled
+//!   nearest-neighbor and blended in gamma space, as `egui` expects.
+//!
+//! The grid, sky, lighting, crowd demo, debug lines, and every readback (depth probe, previews,
+//! UI capture) are not supported, and their trait methods keep their default no-op behavior.
+//!
+//! The default [`ErrorPolicy`](crate::ErrorPolicy) switches to this backend when neither a
+//! hardware nor a fallback adapter is found. It can also be selected up front.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! // Always render on the CPU, for example to compare against the GPU output.
+//! let mut app = App::default().with_backend::<SoftwareBackend>();
+//! event_loop.run_app(&mut app)?;
+//! ```
+
+// Importing `HashMap` to look up the `egui` textures by their id.
+#[cfg(feature = "gui")]
+use std::collections::HashMap;
+// Importing `NonZeroU32`, the size type `softbuffer` surfaces are resized with.
+use std::num::NonZeroU32;
+// Importing `Arc` to share the window between the `App` and the `softbuffer` surface.
+use std::sync::Arc;
+
+// Importing the window type that the backend presents to.
+use winit::window::Window;
+
+use crate::backend::RendererBackend;
+use crate::camera::{Bounds, Projection};
+use crate::config::RendererConfig;
+use crate::error::RenderError;
+use crate::layers::LayerMasks;
+use crate::renderer::Renderer;
+use crate::scene::Scene;
+use crate::simulation::{animate, TICK};
+use crate::vertex::VERTICES;
+use crate::viewport::{AspectLock, Viewport};
+use crate::INDICES;
+
+/// Clip-space `w` values at or below this are behind (or on) the camera. Triangles with such
+/// a vertex are skipped rather than clipped against the near plane.
+const MIN_CLIP_W: f32 = 1e-5;
+
+/// A texture uploaded by `egui`, kept on the CPU.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone)]
+struct SoftwareTexture {
+    /// The width in texels.
+    width: usize,
+
+    /// The height in texels.
+    height: usize,
+
+    /// The premultiplied sRGB texels, row by row.
+    pixels: Vec<egui::Color32>,
+}
+
+#[cfg(feature = "gui")]
+impl SoftwareTexture {
+    /// Returns the texel nearest to `uv`, with coordinates clamped to the edges.
+    fn sample(&self, uv: [f32; 2]) -> egui::Color32 {
+        let x = ((uv[0] * self.width as f32) as usize).min(self.width.saturating_sub(1));
+        let y = ((uv[1] * self.height as f32) as usize).min(self.height.saturating_sub(1));
+        self.pixels
+            .get(y * self.width + x)
+            .copied()
+            .unwrap_or(egui::Color32::WHITE)
+    }
+}
+
+/// A backend rasterizing the scene and GUI on the CPU, for machines without a GPU adapter.
+///
+/// Each frame is drawn into a buffer of `0RGB` words and copied into the window's `softbuffer`
+/// surface, so no GPU API is involved at any point.
+pub struct SoftwareBackend {
+    /// The window's surface, presented once per frame.
+    surface: softbuffer::Surface<Arc<Window>, Arc<Window>>,
+
+    /// The surface size as `(width, height)`, at least one pixel each.
+    size: (u32, u32),
+
+    /// The color of every pixel, in the `0RGB` format of `softbuffer`.
+    color: Vec<u32>,
+
+    /// The depth of the closest surface drawn so far, per pixel, from `0.0` to `1.0`.
+    depth: Vec<f32>,
+
+    /// The color the buffer is cleared to. Its alpha is ignored, as the surface is opaque.
+    clear_color: wgpu::Color,
+
+    /// The model matrix of the scene's triangle, after the last tick of `tick_scene`.
+    model: crate::math::Mat4,
+
+    /// The model matrix before the last tick, which the triangle is drawn `tick_alpha` of a
+    /// tick past.
+    previous_model: crate::math::Mat4,
+
+    /// The fraction of a tick passed since the last one, from `0.0` to `1.0`.
+    tick_alpha: f32,
+
+    /// The camera's view matrix.
+    view: crate::math::Mat4,
+
+    /// The camera's position, used to fit the clip planes.
+    eye: crate::math::Vec3,
+
+    /// The projection set by the application, fitted to the scene every frame.
+    projection: Projection,
+
+    /// The aspect ratio the scene's viewport is locked to.
+    aspect_lock: AspectLock,
+
+    /// The layers the camera draws and the layers of the scene's triangle.
+    layer_masks: LayerMasks,
+
+    /// The view-projection matrix of the most recent frame.
+    view_projection: crate::math::Mat4,
+
+    /// The `egui` textures, by id.
+    #[cfg(feature = "gui")]
+    textures: HashMap<egui::TextureId, SoftwareTexture>,
+}
+
+impl std::fmt::Debug for SoftwareBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SoftwareBackend")
+            .field("size", &self.size)
+            .field("projection", &self.projection)
+            .field("aspect_lock", &self.aspect_lock)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SoftwareBackend {
+    /// Creates a software backend presenting to `window`, with a surface of `width` x `height`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `RenderError` if `softbuffer` does not support the window's platform.
+    pub fn new(window: Arc<Window>, width: u32, height: u32) -> Result<Self, RenderError> {
+        let context = softbuffer::Context::new(window.clone())?;
+        let surface = softbuffer::Surface::new(&context, window)?;
+        let mut backend = Self {
+            surface,
+            size: (0, 0),
+            color: Vec::new(),
+            depth: Vec::new(),
+            clear_color: Renderer::CLEAR_COLOR,
+            model: crate::math::identity(),
+            previous_model: crate::math::identity(),
+            tick_alpha: 1.0,
+            view: crate::math::identity(),
+            eye: crate::math::zeros(),
+            projection: Projection::default(),
+            aspect_lock: AspectLock::default(),
+            layer_masks: LayerMasks::default(),
+            view_projection: crate::math::identity(),
+            #[cfg(feature = "gui")]
+            textures: HashMap::new(),
+        };
+        backend.resize_surface(width, height)?;
+        Ok(backend)
+    }
+
+    /// Returns the rectangle of the surface the scene is drawn into.
+    pub fn viewport(&self) -> Viewport {
+        Viewport::fit(self.size, self.aspect_lock)
+    }
+
+    /// Resizes the surface and the color and depth buffers.
+    fn resize_surface(&mut self, width: u32, height: u32) -> Result<(), RenderError> {
+        let (width, height) = (width.max(1), height.max(1));
+        let non_zero = |value: u32| NonZeroU32::new(value).unwrap_or(NonZeroU32::MIN);
+        self.surface.resize(non_zero(width), non_zero(height))?;
+        self.size = (width, height);
+        self.color = vec![0; width as usize * height as usize];
+        self.depth = vec![1.0; width as usize * height as usize];
+        Ok(())
+    }
+
+    /// Clears the color and depth buffers, and rasterizes the scene's triangle within the
+    /// viewport unless the camera excludes its layers.
+    fn draw_scene(&mut self) {
+        let clear_color = pack_linear([
+            self.clear_color.r as f32,
+            self.clear_color.g as f32,
+            self.clear_color.b as f32,
+        ]);
+        self.color.fill(clear_color);
+        self.depth.fill(1.0);
+        if !self.layer_masks.shows(self.layer_masks.scene) {
+            return;
+        }
+
+        let viewport = self.viewport();
+        let width = self.size.0 as usize;
+        let clip_rect = [
+            viewport.x as f32,
+            viewport.y as f32,
+            (viewport.x + viewport.width) as f32,
+            (viewport.y + viewport.height) as f32,
+        ];
+        // The spin is a constant rotation, so advancing the previous model by part of a tick
+        // interpolates exactly.
+        let model = animate(&self.previous_model, TICK.as_secs_f32() * self.tick_alpha);
+        let model_view_projection = self.view_projection * model;
+
+        for triangle in INDICES.chunks_exact(3) {
+            let vertices = [0, 1, 2].map(|corner| &VERTICES[triangle[corner] as usize]);
+            let clip = vertices.map(|vertex| {
+                let position = vertex.position();
+                model_view_projection * crate::math::vec4(position.x, position.y, position.z, 1.0)
+            });
+            if clip.iter().any(|clip| clip.w <= MIN_CLIP_W) {
+                continue;
+            }
+
+            // Project to pixels (Y pointing down) and keep 1/w for perspective-correct
+            // interpolation of the colors.
+            let inverse_w = clip.map(|clip| 1.0 / clip.w);
+            let depths = [0, 1, 2].map(|corner| clip[corner].z * inverse_w[corner]);
+            let points = [0, 1, 2].map(|corner| {
+                let ndc_x = clip[corner].x * inverse_w[corner];
+                let ndc_y = clip[corner].y * inverse_w[corner];
+                [
+                    viewport.x as f32 + (ndc_x + 1.0) * 0.5 * viewport.width as f32,
+                    viewport.y as f32 + (1.0 - ndc_y) * 0.5 * viewport.height as f32,
+                ]
+            });
+            let colors = vertices.map(|vertex| vertex.color());
+
+            let (pixels, depth_buffer) = (&mut self.color, &mut self.depth);
+            rasterize(points, clip_rect, |x, y, weights| {
+                let index = y * width + x;
+                let depth =
+                    weights[0] * depths[0] + weights[1] * depths[1] + weights[2] * depths[2];
+                if !(0.0..=1.0).contains(&depth) || depth >= depth_buffer[index] {
+                    return;
+                }
+                depth_buffer[index] = depth;
+
+                let perspective = [0, 1, 2].map(|corner| weights[corner] * inverse_w[corner]);
+                let total = perspective[0] + perspective[1] + perspective[2];
+                let channel = |channel: usize| {
+                    (0..3)
+                        .map(|corner| perspective[corner] * colors[corner][channel])
+                        .sum::<f32>()
+                        / total
+                };
+                pixels[index] = pack_linear([channel(0), channel(1), channel(2)]);
+            });
+        }
+    }
+
+    /// Applies the texture uploads of `textures_delta`. Freed textures are removed separately,
+    /// after painting.
+    #[cfg(feature = "gui")]
+    fn update_textures(&mut self, textures_delta: &egui::TexturesDelta) {
+        for (id, delta) in &textures_delta.set {
+            let [width, height] = delta.image.size();
+            let pixels: Vec<egui::Color32> = match &delta.image {
+                egui::ImageData::Color(image) => image.pixels.clone(),
+                egui::ImageData::Font(image) => image.srgba_pixels(None).collect(),
+            };
+            match delta.pos {
+                None => {
+                    self.textures.insert(
+                        *id,
+                        SoftwareTexture {
+                            width,
+                            height,
+                            pixels,
+                        },
+                    );
+                }
+                Some([x, y]) => {
+                    let Some(texture) = self.textures.get_mut(id) else {
+                        log::warn!("Ignoring a partial update of unknown texture {id:?}");
+                        continue;
+                    };
+                    if x + width > texture.width || y + height > texture.height {
+                        log::warn!("Ignoring an out-of-bounds update of texture {id:?}");
+                        continue;
+                    }
+                    for row in 0..height {
+                        let start = (y + row) * texture.width + x;
+                        texture.pixels[start..start + width]
+                            .copy_from_slice(&pixels[row * width..(row + 1) * width]);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rasterizes the `egui` meshes, blending them over the scene.
+    ///
+    /// Paint callbacks need a GPU and are skipped.
+    #[cfg(feature = "gui")]
+    fn draw_gui(&mut self, pixels_per_point: f32, paint_jobs: &[egui::epaint::ClippedPrimitive]) {
+        let (width, height) = (self.size.0 as f32, self.size.1 as f32);
+        let stride = self.size.0 as usize;
+        let pixels = &mut self.color;
+        for job in paint_jobs {
+            let egui::epaint::Primitive::Mesh(mesh) = &job.primitive else {
+                continue;
+            };
+            let Some(texture) = self.textures.get(&mesh.texture_id) else {
+                continue;
+            };
+            let clip_rect = [
+                (job.clip_rect.min.x * pixels_per_point).clamp(0.0, width),
+                (job.clip_rect.min.y * pixels_per_point).clamp(0.0, height),
+                (job.clip_rect.max.x * pixels_per_point).clamp(0.0, width),
+                (job.clip_rect.max.y * pixels_per_point).clamp(0.0, height),
+            ];
+
+            for triangle in mesh.indices.chunks_exact(3) {
+                let Some(vertices) = [0, 1, 2]
+                    .map(|corner| mesh.vertices.get(triangle[corner] as usize))
+                    .into_iter()
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    continue;
+                };
+                let points = [0, 1, 2].map(|corner| {
+                    [
+                        vertices[corner].pos.x * pixels_per_point,
+                        vertices[corner].pos.y * pixels_per_point,
+                    ]
+                });
+
+                rasterize(points, clip_rect, |x, y, weights| {
+                    let interpolate = |value: &dyn Fn(&egui::epaint::Vertex) -> f32| {
+                        (0..3)
+                            .map(|corner| weights[corner] * value(vertices[corner]))
+                            .sum()
+                    };
+                    let texel = texture.sample([
+                        interpolate(&|vertex| vertex.uv.x),
+                        interpolate(&|vertex| vertex.uv.y),
+                    ]);
+                    // Both the vertex color and the texel are premultiplied, so their product
+                    // is too.
+                    let source = [0, 1, 2, 3].map(|channel| {
+                        interpolate(&|vertex| vertex.color[channel] as f32) / 255.0
+                            * (texel[channel] as f32 / 255.0)
+                    });
+                    let index = y * stride + x;
+                    pixels[index] = blend_premultiplied(pixels[index], source);
+                });
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait(?Send)]
+impl RendererBackend for SoftwareBackend {
+    async fn init(
+        window: Arc<Window>,
+        width: u32,
+        height: u32,
+        _force_fallback_adapter: bool,
+        _config: RendererConfig,
+    ) -> Result<Self, RenderError> {
+        log::warn!("Using the software renderer backend; only a simplified scene is drawn");
+        Self::new(window, width, height)
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        if (width.max(1), height.max(1)) == self.size {
+            return;
+        }
+        if let Err(error) = self.resize_surface(width, height) {
+            log::error!("Failed to resize the software surface: {error}");
+        }
+    }
+
+    fn render(
+        &mut self,
+        #[cfg(feature = "gui")] screen_descriptor: egui_wgpu::ScreenDescriptor,
+        #[cfg(feature = "gui")] paint_jobs: Vec<egui::epaint::ClippedPrimitive>,
+        #[cfg(feature = "gui")] textures_delta: egui::TexturesDelta,
+        _delta_time: crate::Duration,
+    ) -> Result<(), RenderError> {
+        let projection = self
+            .projection
+            .fitted(&self.eye, Some(&Scene::geometry_bounds()));
+        self.view_projection = projection.matrix(self.viewport().aspect_ratio()) * self.view;
+
+        self.draw_scene();
+        #[cfg(feature = "gui")]
+        {
+            self.update_textures(&textures_delta);
+            self.draw_gui(screen_descriptor.pixels_per_point, &paint_jobs);
+            for id in &textures_delta.free {
+                self.textures.remove(id);
+            }
+        }
+
+        let mut buffer = self.surface.buffer_mut()?;
+        buffer.copy_from_slice(&self.color);
+        buffer.present()?;
+        Ok(())
+    }
+
+    fn viewport(&self) -> Option<Viewport> {
+        Some(SoftwareBackend::viewport(self))
+    }
+
+    fn set_aspect_lock(&mut self, aspect_lock: AspectLock) {
+        self.aspect_lock = aspect_lock;
+    }
+
+    fn set_clear_color(&mut self, color: wgpu::Color) {
+        self.clear_color = color;
+    }
+
+    fn set_camera(&mut self, view: crate::math::Mat4, eye: crate::math::Vec3) {
+        self.view = view;
+        self.eye = eye;
+    }
+
+    fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    fn tick_scene(&mut self, ticks: u32, alpha: f32) {
+        for _ in 0..ticks {
+            self.previous_model = self.model;
+            self.model = animate(&self.model, TICK.as_secs_f32());
+        }
+        self.tick_alpha = alpha;
+    }
+
+    fn set_layer_masks(&mut self, masks: &LayerMasks) {
+        self.layer_masks = *masks;
+    }
+
+    fn scene_bounds(&self) -> Option<Bounds> {
+        Some(Scene::geometry_bounds())
+    }
+
+    fn view_projection(&self) -> Option<crate::math::Mat4> {
+        Some(self.view_projection)
+    }
+}
+
+/// Calls `shade` with the pixel coordinates and barycentric weights of every pixel whose
+/// center lies in the triangle with corners `points`, in pixels, and inside `clip_rect`.
+///
+/// `clip_rect` is `[min_x, min_y, max_x, max_y]` in pixels and must lie within the target.
+/// Both windings are drawn; degenerate triangles are skipped.
+fn rasterize(
+    points: [[f32; 2]; 3],
+    clip_rect: [f32; 4],
+    mut shade: impl FnMut(usize, usize, [f32; 3]),
+) {
+    let edge = |a: [f32; 2], b: [f32; 2], p: [f32; 2]| {
+        (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+    };
+    let area = edge(points[0], points[1], points[2]);
+    if area.abs() < f32::EPSILON || !area.is_finite() {
+        return;
+    }
+
+    // The pixels covered by the triangle's bounding box along `axis`, within the clip rect.
+    let range = |axis: usize| {
+        let [a, b, c] = points.map(|point| point[axis]);
+        let start = a.min(b).min(c).max(clip_rect[axis]).floor() as usize;
+        let end = a.max(b).max(c).min(clip_rect[axis + 2]).ceil() as usize;
+        start..end
+    };
+
+    let x_range = range(0);
+    for y in range(1) {
+        for x in x_range.clone() {
+            let center = [x as f32 + 0.5, y as f32 + 0.5];
+            let weights = [
+                edge(points[1], points[2], center) / area,
+                edge(points[2], points[0], center) / area,
+                edge(points[0], points[1], center) / area,
+            ];
+            if weights.iter().all(|weight| *weight >= 0.0) {
+                shade(x, y, weights);
+            }
+        }
+    }
+}
+
+/// Converts a linear color channel to an 8-bit sRGB value.
+fn linear_to_srgb(linear: f32) -> u32 {
+    let linear = linear.clamp(0.0, 1.0);
+    let srgb = if linear <= 0.003_130_8 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+    (srgb * 255.0).round() as u32
+}
+
+/// Packs a linear RGB color into the `0RGB` pixel format of `softbuffer`.
+fn pack_linear(color: [f32; 3]) -> u32 {
+    (linear_to_srgb(color[0]) << 16) | (linear_to_srgb(color[1]) << 8) | linear_to_srgb(color[2])
+}
+
+/// Blends the premultiplied sRGB `source` color (channels from `0.0` to `1.0`) over the
+/// `0RGB` pixel `destination`.
+#[cfg(feature = "gui")]
+fn blend_premultiplied(destination: u32, source: [f32; 4]) -> u32 {
+    let channel = |shift: u32, value: f32| {
+        let destination = ((destination >> shift) & 0xff) as f32 / 255.0;
+        let blended = value + destination * (1.0 - source[3]);
+        ((blended.clamp(0.0, 1.0) * 255.0).round() as u32) << shift
+    };
+    channel(16, source[0]) | channel(8, source[1]) | channel(0, source[2])
+}
@@ -0,0 +1,475 @@
+//! # Streaming Module
+//!
+//! The `streaming` module serves the rendered frames over HTTP, so that a remote browser can
+//! watch the renderer's output, for example when rendering on a server.
+//!
+//! ## Overview
+//!
+//! [`FrameStreamer`] reads the frames back without blocking the render thread, in the same
+//! stages as the GUI capture, at most [`FrameStreamer::DEFAULT_FRAME_RATE`] times a second:
+//!
+//! 1. **`encode_copy`**: Records a copy of the surface texture into a readback buffer.
+//! 2. **`after_submit`**: Once the frame is submitted, starts mapping the readback buffer.
+//! 3. **`poll`**: In a later frame, hands the pixels to the encoder thread.
+//!
+//! The encoder thread compresses the frames into JPEGs of [`FrameStreamer::JPEG_QUALITY`],
+//! and an HTTP server thread serves them:
+//!
+//! - **`/`**: A page showing the stream.
+//! - **`/stream`**: The frames as a Motion JPEG `multipart/x-mixed-replace` stream, which
+//!   browsers display as a continuously updating image.
+//! - **`/frame.jpg`**: The latest frame alone.
+//!
+//! Frames are dropped rather than queued when the encoder falls behind, so a slow connection
+//! lowers the stream's frame rate but never delays rendering. At most
+//! [`FrameStreamer::MAX_CLIENTS`] clients are served at once, each on its own thread, and
+//! further connections are turned away until one disconnects. Streaming is only available on
+//! native targets.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let address = renderer.start_streaming("0.0.0.0:8080".parse()?)?;
+//! log::info!("Streaming at http://{address}/");
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Condvar, Mutex};
+
+use web_time::Instant;
+
+use crate::readback::Readback;
+use crate::Duration;
+
+/// Errors produced while starting to stream frames.
+#[derive(Debug, thiserror::Error)]
+pub enum StreamError {
+    /// The server could not listen on the address.
+    #[error("failed to start the stream server: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The surface format cannot be converted to an 8-bit RGB JPEG.
+    #[error("streaming is not supported with the {0:?} surface format")]
+    UnsupportedFormat(wgpu::TextureFormat),
+
+    /// The surface textures cannot be copied from, which is needed to read them back.
+    #[error("the surface textures cannot be copied")]
+    SurfaceNotCopyable,
+
+    /// The rendering backend cannot stream its frames.
+    #[error("the rendering backend cannot stream frames")]
+    Unsupported,
+}
+
+/// The size and layout of a frame in its readback buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FrameLayout {
+    width: u32,
+    height: u32,
+
+    /// The bytes per row in the buffer, padded to `wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`.
+    padded_bytes_per_row: u32,
+
+    /// Whether the pixels are stored in BGRA order.
+    bgra: bool,
+}
+
+/// The most recently encoded frame, shared between the encoder and the client threads.
+#[derive(Debug, Default)]
+struct LatestFrame {
+    /// The JPEG, if a frame has been encoded yet.
+    jpeg: Option<Arc<Vec<u8>>>,
+
+    /// Incremented with every new frame.
+    sequence: u64,
+}
+
+/// The latest frame and the condition variable signaling new ones.
+type SharedLatestFrame = Arc<(Mutex<LatestFrame>, Condvar)>;
+
+/// Reads back rendered frames and serves them over HTTP.
+#[derive(Debug)]
+pub struct FrameStreamer {
+    /// The address the server listens on.
+    local_addr: SocketAddr,
+
+    /// The readback of the frame in flight, in the layout it was copied with.
+    readback: Readback<FrameLayout>,
+
+    /// The readback buffer of the last frame, reused while the size does not change.
+    buffer: Option<(wgpu::Buffer, FrameLayout)>,
+
+    /// Sends tightly packed RGB frames to the encoder thread.
+    frames: SyncSender<(Vec<u8>, FrameLayout)>,
+
+    /// The minimum time between two streamed frames.
+    frame_interval: Duration,
+
+    /// When the last frame was read back.
+    last_capture: Option<Instant>,
+
+    /// Set when the streamer is dropped, to stop the server threads.
+    shutdown: Arc<AtomicBool>,
+
+    /// Woken when the streamer is dropped, so waiting clients disconnect.
+    latest: SharedLatestFrame,
+}
+
+impl FrameStreamer {
+    /// The default maximum number of frames streamed per second.
+    pub const DEFAULT_FRAME_RATE: u32 = 30;
+
+    /// The quality the frames are encoded with, from 1 to 100.
+    pub const JPEG_QUALITY: u8 = 80;
+
+    /// The maximum number of clients served at once. A client that disconnects from the
+    /// stream frees its slot once the next frame fails to reach it.
+    pub const MAX_CLIENTS: usize = 8;
+
+    /// Starts serving the frames on `address`, read back from surface textures in `format`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `StreamError` if `format` is not an 8-bit RGBA or BGRA format, or if the
+    /// server cannot listen on `address`.
+    pub fn start(address: SocketAddr, format: wgpu::TextureFormat) -> Result<Self, StreamError> {
+        if !matches!(
+            format,
+            wgpu::TextureFormat::Bgra8Unorm
+                | wgpu::TextureFormat::Bgra8UnormSrgb
+                | wgpu::TextureFormat::Rgba8Unorm
+                | wgpu::TextureFormat::Rgba8UnormSrgb
+        ) {
+            return Err(StreamError::UnsupportedFormat(format));
+        }
+
+        let listener = TcpListener::bind(address)?;
+        let local_addr = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let latest = SharedLatestFrame::default();
+        let (frames, receiver) = mpsc::sync_channel(1);
+
+        let encoder_latest = Arc::clone(&latest);
+        std::thread::Builder::new()
+            .name("Stream Encoder".into())
+            .spawn(move || encode_frames(receiver, encoder_latest))?;
+        let server_latest = Arc::clone(&latest);
+        let server_shutdown = Arc::clone(&shutdown);
+        std::thread::Builder::new()
+            .name("Stream Server".into())
+            .spawn(move || serve(listener, server_latest, server_shutdown))?;
+
+        log::info!("Streaming frames at http://{local_addr}/");
+        Ok(Self {
+            local_addr,
+            readback: Readback::default(),
+            buffer: None,
+            frames,
+            frame_interval: Duration::from_secs(1) / Self::DEFAULT_FRAME_RATE,
+            last_capture: None,
+            shutdown,
+            latest,
+        })
+    }
+
+    /// Returns the address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Sets the maximum number of frames streamed per second.
+    pub fn set_frame_rate(&mut self, frame_rate: u32) {
+        self.frame_interval = Duration::from_secs(1) / frame_rate.max(1);
+    }
+
+    /// Records a copy of the frame in `texture`, the surface texture, into a readback buffer,
+    /// unless a readback is in flight or the last frame was streamed too recently.
+    pub fn encode_copy(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) {
+        let due = self
+            .last_capture
+            .is_none_or(|last| last.elapsed() >= self.frame_interval);
+        if !self.readback.is_idle() || !due {
+            return;
+        }
+        self.last_capture = Some(Instant::now());
+
+        let layout = FrameLayout {
+            width: texture.width(),
+            height: texture.height(),
+            padded_bytes_per_row: (texture.width() * 4)
+                .next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+            bgra: matches!(
+                texture.format(),
+                wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+            ),
+        };
+        // The buffer is only copied into while no readback is in flight, so it is unmapped.
+        let buffer = match self.buffer.take() {
+            Some((buffer, buffer_layout)) if buffer_layout == layout => buffer,
+            _ => device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Stream Readback Buffer"),
+                size: (layout.padded_bytes_per_row * layout.height) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }),
+        };
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(layout.padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        self.readback.copied(buffer.clone(), layout);
+        self.buffer = Some((buffer, layout));
+    }
+
+    /// Starts mapping the readback buffer once the frame containing the copy was submitted.
+    pub fn after_submit(&mut self) {
+        self.readback.after_submit();
+    }
+
+    /// Hands a frame in flight to the encoder thread, if its buffer has been mapped. This
+    /// never blocks; the frame is dropped if the encoder is still busy with the previous one.
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        let result = self
+            .readback
+            .poll(device, |data, layout| (packed_rgb(data, layout), layout));
+        match result {
+            Some(Ok(frame)) => {
+                let _ = self.frames.try_send(frame);
+            }
+            Some(Err(error)) => log::warn!("Failed to read back a streamed frame: {error}"),
+            None => {}
+        }
+    }
+}
+
+impl Drop for FrameStreamer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Release);
+        self.latest.1.notify_all();
+        // Wake the server thread blocked in `accept`, so it sees the shutdown flag.
+        let _ = TcpStream::connect(self.local_addr);
+    }
+}
+
+/// Converts the pixels in a readback buffer into tightly packed RGB rows, dropping alpha.
+fn packed_rgb(data: &[u8], layout: FrameLayout) -> Vec<u8> {
+    let row_bytes = (layout.width * 4) as usize;
+    let mut pixels = Vec::with_capacity(layout.width as usize * 3 * layout.height as usize);
+    for row in data.chunks(layout.padded_bytes_per_row as usize) {
+        for pixel in row[..row_bytes].chunks_exact(4) {
+            if layout.bgra {
+                pixels.extend_from_slice(&[pixel[2], pixel[1], pixel[0]]);
+            } else {
+                pixels.extend_from_slice(&pixel[..3]);
+            }
+        }
+    }
+    pixels
+}
+
+/// Encodes the frames received from the streamer as JPEGs and publishes them, until the
+/// streamer is dropped.
+fn encode_frames(receiver: Receiver<(Vec<u8>, FrameLayout)>, latest: SharedLatestFrame) {
+    for (pixels, layout) in receiver {
+        let mut jpeg = Vec::new();
+        let encoded = image::codecs::jpeg::JpegEncoder::new_with_quality(
+            &mut jpeg,
+            FrameStreamer::JPEG_QUALITY,
+        )
+        .encode(
+            &pixels,
+            layout.width,
+            layout.height,
+            image::ExtendedColorType::Rgb8,
+        );
+        if let Err(error) = encoded {
+            log::warn!("Failed to encode a streamed frame: {error}");
+            continue;
+        }
+
+        let (frame, new_frame) = &*latest;
+        let mut frame = frame.lock().unwrap_or_else(|error| error.into_inner());
+        frame.jpeg = Some(Arc::new(jpeg));
+        frame.sequence += 1;
+        new_frame.notify_all();
+    }
+}
+
+/// The time a client may take to send its request, or to accept a chunk of a response,
+/// before it is disconnected.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The maximum size of a request's line and headers, beyond which the rest is ignored.
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+
+/// Counts a client as served until it is dropped.
+struct ClientSlot(Arc<AtomicUsize>);
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Accepts connections, serving each client on its own thread, until `shutdown` is set.
+/// Connections beyond [`FrameStreamer::MAX_CLIENTS`] are answered with `503` and closed.
+fn serve(listener: TcpListener, latest: SharedLatestFrame, shutdown: Arc<AtomicBool>) {
+    let clients = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        if shutdown.load(Ordering::Acquire) {
+            break;
+        }
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+        if stream.set_read_timeout(Some(CLIENT_TIMEOUT)).is_err()
+            || stream.set_write_timeout(Some(CLIENT_TIMEOUT)).is_err()
+        {
+            continue;
+        }
+        if clients.fetch_add(1, Ordering::AcqRel) >= FrameStreamer::MAX_CLIENTS {
+            clients.fetch_sub(1, Ordering::AcqRel);
+            let _ = respond(
+                &mut stream,
+                "503 Service Unavailable",
+                "text/plain",
+                b"too many clients",
+            );
+            continue;
+        }
+        let slot = ClientSlot(Arc::clone(&clients));
+        let latest = Arc::clone(&latest);
+        let shutdown = Arc::clone(&shutdown);
+        let spawned = std::thread::Builder::new()
+            .name("Stream Client".into())
+            .spawn(move || {
+                let _slot = slot;
+                if let Err(error) = serve_client(stream, &latest, &shutdown) {
+                    log::debug!("Stream client disconnected: {error}");
+                }
+            });
+        if let Err(error) = spawned {
+            log::warn!("Failed to serve a stream client: {error}");
+        }
+    }
+}
+
+/// The page served at `/`, showing the stream.
+const INDEX_PAGE: &str = "<!DOCTYPE html><html><head><title>wgpu-example stream</title></head>\
+    <body style=\"margin: 0; background: black\">\
+    <img src=\"/stream\" style=\"width: 100vw; height: 100vh; object-fit: contain\">\
+    </body></html>";
+
+/// Answers one HTTP request on `stream`.
+fn serve_client(
+    mut stream: TcpStream,
+    latest: &SharedLatestFrame,
+    shutdown: &AtomicBool,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_BYTES));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers, up to the empty line ending them.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    match path {
+        "/" => respond(&mut stream, "200 OK", "text/html", INDEX_PAGE.as_bytes()),
+        "/frame.jpg" => {
+            let jpeg = latest
+                .0
+                .lock()
+                .unwrap_or_else(|error| error.into_inner())
+                .jpeg
+                .clone();
+            match jpeg {
+                Some(jpeg) => respond(&mut stream, "200 OK", "image/jpeg", &jpeg),
+                None => respond(
+                    &mut stream,
+                    "503 Service Unavailable",
+                    "text/plain",
+                    b"no frame yet",
+                ),
+            }
+        }
+        "/stream" => stream_frames(&mut stream, latest, shutdown),
+        _ => respond(&mut stream, "404 Not Found", "text/plain", b"not found"),
+    }
+}
+
+/// Writes a complete HTTP response with `body`.
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)
+}
+
+/// Writes every new frame as a part of a `multipart/x-mixed-replace` response, until the
+/// client disconnects or the streamer is dropped.
+fn stream_frames(
+    stream: &mut TcpStream,
+    latest: &SharedLatestFrame,
+    shutdown: &AtomicBool,
+) -> std::io::Result<()> {
+    stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: multipart/x-mixed-replace; boundary=frame\r\n\
+          Cache-Control: no-cache\r\nConnection: close\r\n\r\n",
+    )?;
+
+    let (frame, new_frame) = &**latest;
+    let mut sent = 0;
+    loop {
+        let jpeg = {
+            let mut frame = frame.lock().unwrap_or_else(|error| error.into_inner());
+            while frame.sequence == sent && !shutdown.load(Ordering::Acquire) {
+                frame = new_frame
+                    .wait_timeout(frame, Duration::from_secs(1))
+                    .unwrap_or_else(|error| error.into_inner())
+                    .0;
+            }
+            if shutdown.load(Ordering::Acquire) {
+                return Ok(());
+            }
+            sent = frame.sequence;
+            frame.jpeg.clone()
+        };
+        let Some(jpeg) = jpeg else {
+            continue;
+        };
+        write!(
+            stream,
+            "--frame\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+            jpeg.len()
+        )?;
+        stream.write_all(&jpeg)?;
+        stream.write_all(b"\r\n")?;
+    }
+}
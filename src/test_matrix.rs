@@ -0,0 +1,636 @@
+//! # Test Matrix Module
+//!
+//! The `test_matrix` module renders the demo gallery headlessly on every available adapter and
+//! compares the screenshots across backends, catching backend-specific rendering differences
+//! before users do.
+//!
+//! ## Overview
+//!
+//! - [`GalleryScene`] lists the scenes rendered: the triangle, the triangle over the ground
+//!   grid, the end of the dolly zoom and, with the `scene3d` feature, the sky and the crowd
+//!   demo.
+//! - [`run_test_matrix`] renders every scene offscreen on every adapter of every backend, at
+//!   [`SCREENSHOT_SIZE`], and writes the screenshots to `<output>/<adapter>/<scene>.png`.
+//!   Scenes an adapter does not support (such as the crowd on WebGL-class GL drivers) are
+//!   skipped on it.
+//! - The first adapter that renders the gallery is the reference. Every other adapter's
+//!   screenshots are compared with it by [`psnr`] and [`ssim`], and a comparison with an SSIM
+//!   below [`SSIM_THRESHOLD`] fails. The [`TestMatrixReport`] is written to
+//!   `<output>/report.md`.
+//!
+//! The scenes do not animate and the crowd is seeded, so every run renders the same images.
+//! The `test-matrix` developer subcommand runs the matrix and exits with an error if any
+//! comparison failed, so it can run in CI:
+//!
+//! ```sh
+//! $ cargo run --release -- test-matrix [OUTPUT_DIR]
+//! ```
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let report = run_test_matrix("test-matrix")?;
+//! println!("{}", report.to_markdown());
+//! if !report.passed() {
+//!     std::process::exit(1);
+//! }
+//! ```
+
+use std::path::Path;
+
+use crate::camera::{OrbitCamera, Projection};
+#[cfg(feature = "scene3d")]
+use crate::crowd::CrowdDemo;
+#[cfg(feature = "scene3d")]
+use crate::determinism::DeterministicRng;
+use crate::dolly_zoom::{dolly_distance, focus_width, DollyZoom};
+use crate::environment::Environment;
+#[cfg(feature = "scene3d")]
+use crate::environment::SkyRenderer;
+use crate::gpu::UploadBelt;
+use crate::lighting::Light;
+use crate::lines::{grid_lines, LineRenderer};
+use crate::renderer::Renderer;
+use crate::scene::Scene;
+
+/// The width and height of every screenshot, in pixels.
+pub const SCREENSHOT_SIZE: (u32, u32) = (320, 240);
+
+/// The lowest structural similarity at which a screenshot matches the reference. Backends
+/// rasterize and filter slightly differently, so identical images are not expected.
+pub const SSIM_THRESHOLD: f64 = 0.98;
+
+/// The format the screenshots are rendered in.
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Errors produced while running the test matrix.
+#[derive(Debug, thiserror::Error)]
+pub enum TestMatrixError {
+    /// No adapter of any backend is available.
+    #[error("no graphics adapters are available")]
+    NoAdapters,
+
+    /// A device could not be created on an adapter.
+    #[error("failed to create a device: {0}")]
+    Device(#[from] wgpu::RequestDeviceError),
+
+    /// A screenshot could not be read back from the GPU.
+    #[error("failed to read back a screenshot: {0}")]
+    Readback(#[from] wgpu::BufferAsyncError),
+
+    /// A screenshot could not be encoded as a PNG.
+    #[error("failed to encode a screenshot: {0}")]
+    Encode(#[from] png::EncodingError),
+
+    /// A screenshot or the report could not be written.
+    #[error("failed to write '{path}': {source}")]
+    Io {
+        /// The path of the file.
+        path: String,
+
+        /// The underlying I/O error.
+        source: std::io::Error,
+    },
+}
+
+/// A scene of the demo gallery rendered by the test matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GalleryScene {
+    /// The scene's triangle, seen by the default camera.
+    Triangle,
+
+    /// The triangle over the ground grid, seen from above.
+    Grid,
+
+    /// The triangle over the ground grid at the end of the dolly zoom: as large as in
+    /// `Triangle`, but seen from afar through a narrow field of view.
+    DollyZoom,
+
+    /// The triangle in front of the sky.
+    #[cfg(feature = "scene3d")]
+    Sky,
+
+    /// The crowd demo around the triangle.
+    #[cfg(feature = "scene3d")]
+    Crowd,
+}
+
+impl GalleryScene {
+    /// Every scene, in the order they are rendered and reported.
+    pub const ALL: &'static [GalleryScene] = &[
+        GalleryScene::Triangle,
+        GalleryScene::Grid,
+        GalleryScene::DollyZoom,
+        #[cfg(feature = "scene3d")]
+        GalleryScene::Sky,
+        #[cfg(feature = "scene3d")]
+        GalleryScene::Crowd,
+    ];
+
+    /// The number of characters in the crowd scene.
+    #[cfg(feature = "scene3d")]
+    const CROWD_COUNT: usize = 64;
+
+    /// Returns the name of the scene, used for its file name and in the report.
+    pub fn name(self) -> &'static str {
+        match self {
+            GalleryScene::Triangle => "triangle",
+            GalleryScene::Grid => "grid",
+            GalleryScene::DollyZoom => "dolly-zoom",
+            #[cfg(feature = "scene3d")]
+            GalleryScene::Sky => "sky",
+            #[cfg(feature = "scene3d")]
+            GalleryScene::Crowd => "crowd",
+        }
+    }
+
+    /// Returns `true` if the scene can be rendered on `adapter` with `device`.
+    #[cfg_attr(not(feature = "scene3d"), allow(unused_variables))]
+    fn is_supported(self, adapter: &wgpu::Adapter, device: &wgpu::Device) -> bool {
+        match self {
+            #[cfg(feature = "scene3d")]
+            GalleryScene::Crowd => {
+                CrowdDemo::is_supported(&adapter.get_downlevel_capabilities(), &device.limits())
+            }
+            _ => true,
+        }
+    }
+}
+
+/// An RGBA screenshot with 8 bits per channel, in sRGB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screenshot {
+    /// The width in pixels.
+    pub width: u32,
+
+    /// The height in pixels.
+    pub height: u32,
+
+    /// The tightly packed rows of pixels, top to bottom.
+    pub pixels: Vec<u8>,
+}
+
+impl Screenshot {
+    /// Encodes the screenshot as a PNG.
+    pub fn encode_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        crate::frame_capture::encode_png(&self.pixels, self.width, self.height)
+    }
+
+    /// Returns the luma of every pixel (Rec. 601), from `0.0` to `255.0`.
+    fn luma(&self) -> Vec<f64> {
+        self.pixels
+            .chunks_exact(4)
+            .map(|pixel| {
+                0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64
+            })
+            .collect()
+    }
+}
+
+/// Returns the peak signal-to-noise ratio between the colors of `a` and `b`, in decibels.
+///
+/// Identical screenshots have an infinite ratio; screenshots of different sizes have `0.0`.
+pub fn psnr(a: &Screenshot, b: &Screenshot) -> f64 {
+    if (a.width, a.height) != (b.width, b.height) || a.pixels.len() != b.pixels.len() {
+        return 0.0;
+    }
+    let (sum, count) = a
+        .pixels
+        .chunks_exact(4)
+        .zip(b.pixels.chunks_exact(4))
+        .flat_map(|(a, b)| (0..3).map(move |channel| a[channel] as f64 - b[channel] as f64))
+        .fold((0.0, 0usize), |(sum, count), error| {
+            (sum + error * error, count + 1)
+        });
+    if count == 0 || sum == 0.0 {
+        return f64::INFINITY;
+    }
+    10.0 * (255.0 * 255.0 / (sum / count as f64)).log10()
+}
+
+/// Returns the mean structural similarity (SSIM) of the luma of `a` and `b`, from `-1.0` to
+/// `1.0` for identical screenshots.
+///
+/// It is averaged over 8×8 windows, 4 pixels apart. Screenshots of different sizes have `0.0`.
+pub fn ssim(a: &Screenshot, b: &Screenshot) -> f64 {
+    const WINDOW: usize = 8;
+    const STRIDE: usize = 4;
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    if (a.width, a.height) != (b.width, b.height) || a.pixels.len() != b.pixels.len() {
+        return 0.0;
+    }
+    let (width, height) = (a.width as usize, a.height as usize);
+    let (a, b) = (a.luma(), b.luma());
+    let window = (WINDOW.min(width), WINDOW.min(height));
+    if window.0 == 0 || window.1 == 0 {
+        return 1.0;
+    }
+
+    let mut total = 0.0;
+    let mut windows = 0;
+    for top in (0..=height - window.1).step_by(STRIDE) {
+        for left in (0..=width - window.0).step_by(STRIDE) {
+            let pixels = (top..top + window.1)
+                .flat_map(|y| (left..left + window.0).map(move |x| y * width + x));
+            let count = (window.0 * window.1) as f64;
+            let (mut mean_a, mut mean_b) = (0.0, 0.0);
+            for index in pixels.clone() {
+                mean_a += a[index];
+                mean_b += b[index];
+            }
+            (mean_a, mean_b) = (mean_a / count, mean_b / count);
+            let (mut variance_a, mut variance_b, mut covariance) = (0.0, 0.0, 0.0);
+            for index in pixels {
+                let (da, db) = (a[index] - mean_a, b[index] - mean_b);
+                variance_a += da * da;
+                variance_b += db * db;
+                covariance += da * db;
+            }
+            (variance_a, variance_b, covariance) =
+                (variance_a / count, variance_b / count, covariance / count);
+
+            total += ((2.0 * mean_a * mean_b + C1) * (2.0 * covariance + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (variance_a + variance_b + C2));
+            windows += 1;
+        }
+    }
+    total / windows as f64
+}
+
+/// The comparison of one screenshot with the reference adapter's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// The scene compared.
+    pub scene: GalleryScene,
+
+    /// The adapter compared with the reference.
+    pub adapter: String,
+
+    /// The peak signal-to-noise ratio, in decibels.
+    pub psnr: f64,
+
+    /// The structural similarity.
+    pub ssim: f64,
+}
+
+impl Comparison {
+    /// Returns `true` if the screenshot matches the reference closely enough.
+    pub fn passed(&self) -> bool {
+        self.ssim >= SSIM_THRESHOLD
+    }
+}
+
+/// The result of a test matrix run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TestMatrixReport {
+    /// The adapters that rendered the gallery; the first one is the reference.
+    pub adapters: Vec<String>,
+
+    /// The comparison of every other adapter's screenshots with the reference.
+    pub comparisons: Vec<Comparison>,
+
+    /// The adapters that failed to render the gallery, and why.
+    pub failures: Vec<(String, String)>,
+}
+
+impl TestMatrixReport {
+    /// Returns `true` if every adapter rendered the gallery and every comparison passed.
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty() && self.comparisons.iter().all(Comparison::passed)
+    }
+
+    /// Returns the report as a Markdown document.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Screenshot Test Matrix\n\n");
+        match self.adapters.first() {
+            Some(reference) => markdown.push_str(&format!("Reference: {reference}\n\n")),
+            None => markdown.push_str("No adapter rendered the gallery.\n\n"),
+        }
+        if self.adapters.len() == 1 {
+            markdown.push_str("Only one adapter is available, so nothing was compared.\n\n");
+        }
+        if !self.comparisons.is_empty() {
+            markdown.push_str(&format!(
+                "Screenshots with an SSIM below {SSIM_THRESHOLD} fail.\n\n"
+            ));
+            markdown.push_str("| Scene | Adapter | PSNR (dB) | SSIM | Result |\n");
+            markdown.push_str("|---|---|---:|---:|---|\n");
+            for comparison in &self.comparisons {
+                markdown.push_str(&format!(
+                    "| {} | {} | {:.2} | {:.4} | {} |\n",
+                    comparison.scene.name(),
+                    comparison.adapter,
+                    comparison.psnr,
+                    comparison.ssim,
+                    if comparison.passed() { "pass" } else { "FAIL" },
+                ));
+            }
+            markdown.push('\n');
+        }
+        if !self.failures.is_empty() {
+            markdown.push_str("## Failed Adapters\n\n");
+            for (adapter, error) in &self.failures {
+                markdown.push_str(&format!("- {adapter}: {error}\n"));
+            }
+        }
+        markdown
+    }
+}
+
+/// Renders the gallery on every available adapter into `output`, compares the screenshots
+/// with the first adapter's, and writes the report to `output/report.md`.
+///
+/// Adapters that fail to render are recorded in the report rather than stopping the run.
+///
+/// # Errors
+///
+/// Returns `TestMatrixError::NoAdapters` if no adapter is available, and
+/// `TestMatrixError::Io` if a screenshot or the report cannot be written.
+pub fn run_test_matrix(output: impl AsRef<Path>) -> Result<TestMatrixReport, TestMatrixError> {
+    let output = output.as_ref();
+    let io_error = |path: &Path| {
+        let path = path.display().to_string();
+        move |source| TestMatrixError::Io { path, source }
+    };
+
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        ..Default::default()
+    });
+    let adapters = instance.enumerate_adapters(wgpu::Backends::all());
+    if adapters.is_empty() {
+        return Err(TestMatrixError::NoAdapters);
+    }
+
+    let mut report = TestMatrixReport::default();
+    let mut reference: Option<Vec<(GalleryScene, Screenshot)>> = None;
+    for (index, adapter) in adapters.iter().enumerate() {
+        let info = adapter.get_info();
+        let name = format!("{:?} {}", info.backend, info.name);
+        log::info!("Rendering the gallery on {name}");
+
+        let screenshots = match render_gallery(adapter) {
+            Ok(screenshots) => screenshots,
+            Err(error) => {
+                report.failures.push((name, error.to_string()));
+                continue;
+            }
+        };
+
+        let directory = output.join(directory_name(index, &name));
+        std::fs::create_dir_all(&directory).map_err(io_error(&directory))?;
+        for (scene, screenshot) in &screenshots {
+            let path = directory.join(format!("{}.png", scene.name()));
+            std::fs::write(&path, screenshot.encode_png()?).map_err(io_error(&path))?;
+        }
+
+        match &reference {
+            None => reference = Some(screenshots),
+            Some(reference) => {
+                for (scene, screenshot) in &screenshots {
+                    let Some((_, expected)) = reference.iter().find(|(other, _)| other == scene)
+                    else {
+                        continue;
+                    };
+                    report.comparisons.push(Comparison {
+                        scene: *scene,
+                        adapter: name.clone(),
+                        psnr: psnr(expected, screenshot),
+                        ssim: ssim(expected, screenshot),
+                    });
+                }
+            }
+        }
+        report.adapters.push(name);
+    }
+
+    let path = output.join("report.md");
+    std::fs::create_dir_all(output).map_err(io_error(output))?;
+    std::fs::write(&path, report.to_markdown()).map_err(io_error(&path))?;
+    Ok(report)
+}
+
+/// Returns the directory name of the `index`th adapter, named `name`, with only characters
+/// that are safe in file names.
+fn directory_name(index: usize, name: &str) -> String {
+    let words: Vec<String> = name
+        .split(|character: char| !character.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    format!("{index}-{}", words.join("-"))
+}
+
+/// Creates a device on `adapter` and renders every scene it supports.
+fn render_gallery(
+    adapter: &wgpu::Adapter,
+) -> Result<Vec<(GalleryScene, Screenshot)>, TestMatrixError> {
+    let (device, queue) = pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: Some("Test Matrix Device"),
+            required_features: wgpu::Features::empty(),
+            required_limits: adapter.limits(),
+            memory_hints: wgpu::MemoryHints::default(),
+        },
+        None,
+    ))?;
+
+    GalleryScene::ALL
+        .iter()
+        .filter(|scene| scene.is_supported(adapter, &device))
+        .map(|&scene| Ok((scene, render_scene(&device, &queue, scene)?)))
+        .collect()
+}
+
+/// Renders `gallery_scene` offscreen and reads the screenshot back.
+fn render_scene(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    gallery_scene: GalleryScene,
+) -> Result<Screenshot, TestMatrixError> {
+    let (width, height) = SCREENSHOT_SIZE;
+    let mut scene = Scene::new(device, queue, FORMAT, None);
+    let mut lines = LineRenderer::new(device, FORMAT, None);
+    #[cfg(feature = "scene3d")]
+    let sky = (gallery_scene == GalleryScene::Sky)
+        .then(|| SkyRenderer::new(device, FORMAT, &scene, None));
+    #[cfg(feature = "scene3d")]
+    let mut crowd = (gallery_scene == GalleryScene::Crowd).then(|| {
+        let seed = DeterministicRng::DEFAULT_SEED;
+        CrowdDemo::new(device, FORMAT, GalleryScene::CROWD_COUNT, seed, None)
+    });
+
+    // Every scene but the triangle and the dolly zoom is framed from above, so the grid and
+    // crowd can be seen.
+    let mut bounds = scene.bounds();
+    if matches!(gallery_scene, GalleryScene::Grid | GalleryScene::DollyZoom) {
+        lines.extend(grid_lines(
+            Renderer::GRID_CELLS,
+            Renderer::GRID_SPACING,
+            Renderer::GRID_HEIGHT,
+            1.0,
+        ));
+        if let Some(grid) = lines.queued_bounds() {
+            bounds = bounds.union(&grid);
+        }
+    }
+    #[cfg(feature = "scene3d")]
+    if let Some(crowd) = crowd.as_ref().and_then(CrowdDemo::bounds) {
+        bounds = bounds.union(&crowd);
+    }
+    let mut projection = Projection::default();
+    let camera = match gallery_scene {
+        GalleryScene::Triangle => OrbitCamera::default(),
+        GalleryScene::DollyZoom => {
+            // Keep the width the default camera sees at the triangle.
+            let dolly_zoom = DollyZoom::default();
+            let start = OrbitCamera::default();
+            let width = focus_width(start.distance, projection.field_of_view);
+            projection.field_of_view = dolly_zoom.end_field_of_view;
+            OrbitCamera {
+                yaw: 0.6,
+                pitch: 0.2,
+                distance: dolly_distance(width, projection.field_of_view),
+                ..start
+            }
+        }
+        _ => OrbitCamera {
+            target: bounds.center(),
+            yaw: 0.6,
+            pitch: 0.45,
+            distance: bounds.radius() / (projection.field_of_view.to_radians() * 0.5).sin(),
+        },
+    };
+
+    scene.set_camera(camera.view(), camera.eye());
+    scene.projection = projection.fitted(&camera.eye(), Some(&bounds));
+    let mut uploads = UploadBelt::new(device);
+    scene.update(&mut uploads, width as f32 / height as f32, 0.0);
+    scene.upload_environment(queue, &Environment::default());
+    scene.upload_light(queue, &Light::default());
+    #[cfg(feature = "scene3d")]
+    if let Some(crowd) = crowd.as_mut() {
+        crowd.update(queue, &scene.view_projection, &scene.camera_position, 0.5);
+    }
+    lines.prepare(
+        device,
+        &mut uploads,
+        &scene.view_projection,
+        (width, height),
+    );
+
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let target = |label: &str, format, usage| {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | usage,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    };
+    let (color, color_view) = target(
+        "Test Matrix Color Texture",
+        FORMAT,
+        wgpu::TextureUsages::COPY_SRC,
+    );
+    let (_, object_id_view) = target(
+        "Test Matrix Object Id Texture",
+        Scene::OBJECT_ID_FORMAT,
+        wgpu::TextureUsages::empty(),
+    );
+    let (_, depth_view) = target(
+        "Test Matrix Depth Texture",
+        Renderer::DEPTH_FORMAT,
+        wgpu::TextureUsages::empty(),
+    );
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Test Matrix Encoder"),
+    });
+    {
+        let attachment = |view, clear| {
+            Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(clear),
+                    store: wgpu::StoreOp::Store,
+                },
+            })
+        };
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Test Matrix Render Pass"),
+            color_attachments: &[
+                attachment(&color_view, Renderer::CLEAR_COLOR),
+                attachment(&object_id_view, wgpu::Color::TRANSPARENT),
+            ],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        #[cfg(feature = "scene3d")]
+        if let Some(sky) = &sky {
+            sky.render(&mut render_pass, &scene);
+        }
+        scene.render(&mut render_pass);
+        #[cfg(feature = "scene3d")]
+        if let Some(crowd) = &crowd {
+            crowd.render(&mut render_pass);
+        }
+        lines.render(&mut render_pass);
+    }
+
+    // Rows of a texture copy must be aligned to 256 bytes.
+    let row_bytes = width * 4;
+    let padded_row_bytes =
+        row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Test Matrix Readback Buffer"),
+        size: (padded_row_bytes * height) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    encoder.copy_texture_to_buffer(
+        color.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row_bytes),
+                rows_per_image: None,
+            },
+        },
+        size,
+    );
+    queue.submit(uploads.finish().into_iter().chain(Some(encoder.finish())));
+
+    let pixels = crate::readback::read_blocking(device, &buffer, |data| {
+        data.chunks(padded_row_bytes as usize)
+            .flat_map(|row| row[..row_bytes as usize].iter().copied())
+            .collect()
+    })?;
+    Ok(Screenshot {
+        width,
+        height,
+        pixels,
+    })
+}
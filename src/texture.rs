@@ -0,0 +1,238 @@
+//! # Texture Module
+//!
+//! The `texture` module decodes images into GPU textures, which the scene's materials are
+//! sampled from.
+//!
+//! ## Overview
+//!
+//! - [`Texture`] owns a 2D texture, its view, and the sampler it is read with. It is created
+//!   from PNG or JPEG bytes with [`Texture::from_bytes`], which decodes them with the `image`
+//!   crate and uploads the pixels with `Queue::write_texture`, or from raw RGBA pixels with
+//!   [`Texture::from_rgba8`].
+//! - The material module's `MaterialBinding` binds textures to `shader_source.wgsl` at set 2:
+//!   the scene's texture, multiplied with the vertex colors, and the textures of each
+//!   material.
+//!
+//! Textures are stored in an sRGB format, since image files hold sRGB colors, so sampling
+//! returns linear colors for lighting. Textures holding other data, such as the metallic and
+//! roughness of a material, are created with [`Texture::from_bytes_linear`] or
+//! [`Texture::from_rgba8_linear`] instead, and read unchanged. The sampler repeats the texture
+//! and filters it linearly.
+//!
+//! Until a texture is loaded, the scene samples a single white texel, so the vertex colors
+//! show unchanged.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let texture = Texture::from_bytes(&device, &queue, &std::fs::read("checker.png")?, "Checker")?;
+//! renderer.set_scene_texture(texture);
+//! ```
+
+/// Errors that can occur while creating a texture.
+#[derive(Debug, thiserror::Error)]
+pub enum TextureError {
+    /// The image could not be decoded, or its format is not supported.
+    #[error("failed to decode the image: {0}")]
+    Decode(#[from] image::ImageError),
+
+    /// The image is larger than the device supports.
+    #[error("the image is {width}x{height} pixels, but the device supports at most {limit}")]
+    TooLarge {
+        /// The width of the image, in pixels.
+        width: u32,
+
+        /// The height of the image, in pixels.
+        height: u32,
+
+        /// The largest width and height of a 2D texture on the device.
+        limit: u32,
+    },
+
+    /// The pixel data does not match the given size.
+    #[error("expected {expected} bytes of RGBA pixels, got {actual}")]
+    SizeMismatch {
+        /// The number of bytes of a `width` x `height` RGBA image.
+        expected: usize,
+
+        /// The number of bytes given.
+        actual: usize,
+    },
+}
+
+/// A 2D texture on the GPU, with the view and sampler it is read with.
+#[derive(Debug)]
+pub struct Texture {
+    /// The texture.
+    pub texture: wgpu::Texture,
+
+    /// The view of the whole texture.
+    pub view: wgpu::TextureView,
+
+    /// The sampler, repeating the texture and filtering it linearly.
+    pub sampler: wgpu::Sampler,
+}
+
+impl Texture {
+    /// The format of the textures, holding sRGB colors.
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// The format of the textures holding data rather than colors, read unchanged.
+    pub const LINEAR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    /// Decodes a PNG or JPEG image and uploads it into a new texture.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TextureError` if the image cannot be decoded, or is too large for the device.
+    pub fn from_bytes(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bytes: &[u8],
+        label: &str,
+    ) -> Result<Self, TextureError> {
+        let image = image::load_from_memory(bytes)?.to_rgba8();
+        Self::from_rgba8(device, queue, image.width(), image.height(), &image, label)
+    }
+
+    /// Decodes a PNG or JPEG image holding data rather than colors, such as the metallic and
+    /// roughness of a material, and uploads it into a new texture read unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TextureError` if the image cannot be decoded, or is too large for the device.
+    pub fn from_bytes_linear(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        bytes: &[u8],
+        label: &str,
+    ) -> Result<Self, TextureError> {
+        let image = image::load_from_memory(bytes)?.to_rgba8();
+        Self::from_rgba8_linear(device, queue, image.width(), image.height(), &image, label)
+    }
+
+    /// Uploads `width` x `height` RGBA pixels with 8 bits per channel, row by row from the top,
+    /// into a new texture.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TextureError` if the pixels do not match the size, or the size is too large
+    /// for the device.
+    pub fn from_rgba8(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        label: &str,
+    ) -> Result<Self, TextureError> {
+        Self::upload(device, queue, (width, height), pixels, Self::FORMAT, label)
+    }
+
+    /// Uploads `width` x `height` RGBA pixels holding data rather than colors, like
+    /// [`Texture::from_rgba8`], into a new texture read unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TextureError` if the pixels do not match the size, or the size is too large
+    /// for the device.
+    pub fn from_rgba8_linear(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        label: &str,
+    ) -> Result<Self, TextureError> {
+        Self::upload(
+            device,
+            queue,
+            (width, height),
+            pixels,
+            Self::LINEAR_FORMAT,
+            label,
+        )
+    }
+
+    /// Uploads `width` x `height` RGBA pixels into a new texture of `format`.
+    fn upload(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        (width, height): (u32, u32),
+        pixels: &[u8],
+        format: wgpu::TextureFormat,
+        label: &str,
+    ) -> Result<Self, TextureError> {
+        let limit = device.limits().max_texture_dimension_2d;
+        if width.max(height) > limit || width == 0 || height == 0 {
+            return Err(TextureError::TooLarge {
+                width,
+                height,
+                limit,
+            });
+        }
+        let expected = width as usize * height as usize * 4;
+        if pixels.len() != expected {
+            return Err(TextureError::SizeMismatch {
+                expected,
+                actual: pixels.len(),
+            });
+        }
+
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            pixels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some(label),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Ok(Self {
+            texture,
+            view,
+            sampler,
+        })
+    }
+
+    /// Creates a texture of a single white texel, which leaves the colors it is multiplied
+    /// with unchanged.
+    pub fn white(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        Self::from_rgba8(device, queue, 1, 1, &[255; 4], "White Texture")
+            .expect("a single texel fits on every device")
+    }
+}
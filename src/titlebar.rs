@@ -0,0 +1,46 @@
+//! # Titlebar Module
+//!
+//! The `titlebar` module describes the title bar drawn with `egui` in place of the platform's
+//! window decorations.
+//!
+//! ## Overview
+//!
+//! - The title bar is enabled with `App::with_custom_titlebar`, which creates the window
+//!   without decorations.
+//! - `ui::show_titlebar` draws the window title and the minimize, maximize, and close buttons
+//!   along the top of the window, and returns the [`TitlebarAction`] the user chose, if any.
+//! - The buttons are hit-tested before the bar itself, so only the bar's empty space acts as
+//!   the drag region: dragging it moves the window, and double-clicking it toggles maximization.
+//! - The `App` applies the action to the window, since the GUI has no access to it.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! match ui::show_titlebar(&egui_context, &window.title(), window.is_maximized()) {
+//!     Some(TitlebarAction::Drag) => window.drag_window()?,
+//!     Some(TitlebarAction::Minimize) => window.set_minimized(true),
+//!     Some(TitlebarAction::ToggleMaximize) => window.set_maximized(!window.is_maximized()),
+//!     Some(TitlebarAction::Close) => event_loop.exit(),
+//!     None => {}
+//! }
+//! ```
+
+/// The height of the title bar, in points.
+pub const TITLEBAR_HEIGHT: f32 = 32.0;
+
+/// What the user asked the window to do through the title bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TitlebarAction {
+    /// The title bar was pressed and dragged; the window follows the pointer until it is
+    /// released.
+    Drag,
+
+    /// The minimize button was clicked.
+    Minimize,
+
+    /// The maximize button was clicked, or the title bar was double-clicked.
+    ToggleMaximize,
+
+    /// The close button was clicked.
+    Close,
+}
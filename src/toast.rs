@@ -0,0 +1,171 @@
+//! # Toast Notifications Module
+//!
+//! The `toast` module implements short-lived, non-blocking notifications ("toasts") drawn
+//! with `egui` in the bottom-right corner of the window. They are used to surface
+//! recoverable problems (such as a requested configuration the GPU does not support) and
+//! informational messages to the user without interrupting rendering.
+//!
+//! ## Overview
+//!
+//! - [`Toasts`] owns the queue of active notifications and draws them every frame.
+//! - [`Toast`] is a single notification with a [`ToastLevel`], a message, and the time it
+//!   was created.
+//! - Toasts expire automatically after [`Toasts::DURATION`], or can be dismissed early by
+//!   clicking them.
+//!
+//! Without the `gui` feature, toasts are not drawn; every message still reaches the log.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut toasts = Toasts::default();
+//! toasts.warning("4x MSAA is not supported, falling back to 1x");
+//!
+//! // Once per frame, between `begin_pass` and `end_pass`:
+//! toasts.show(&egui_context);
+//! ```
+
+// Importing `Instant` from `web_time` so toast expiry works identically on desktop and in
+// the browser, where `std::time::Instant` is unavailable.
+use web_time::{Duration, Instant};
+
+/// The severity of a toast, which determines its accent color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    /// Neutral information, such as a completed action.
+    Info,
+
+    /// A recoverable problem where a fallback was applied.
+    Warning,
+
+    /// An operation that failed.
+    Error,
+}
+
+#[cfg(feature = "gui")]
+impl ToastLevel {
+    /// Returns the accent color used to draw toasts of this level.
+    pub fn color(self) -> egui::Color32 {
+        match self {
+            ToastLevel::Info => egui::Color32::from_rgb(110, 170, 250),
+            ToastLevel::Warning => egui::Color32::from_rgb(240, 190, 70),
+            ToastLevel::Error => egui::Color32::from_rgb(240, 90, 80),
+        }
+    }
+}
+
+/// A single notification shown by [`Toasts`].
+#[derive(Debug, Clone)]
+pub struct Toast {
+    /// The severity of the notification.
+    pub level: ToastLevel,
+
+    /// The message displayed to the user.
+    pub message: String,
+
+    /// The moment the toast was created, used to expire it.
+    pub created: Instant,
+}
+
+/// A queue of active toast notifications.
+///
+/// Notifications are appended with [`Toasts::push`] (or the `info`, `warning`, and `error`
+/// shorthands) from anywhere in the application and drawn by calling [`Toasts::show`] once
+/// per frame while an `egui` pass is active.
+#[derive(Debug, Default)]
+pub struct Toasts {
+    /// The notifications that have not yet expired or been dismissed, oldest first.
+    entries: Vec<Toast>,
+}
+
+impl Toasts {
+    /// How long a toast remains visible before it is removed automatically.
+    pub const DURATION: Duration = Duration::from_secs(6);
+
+    /// The maximum number of toasts kept at once. Older toasts are dropped first.
+    pub const MAX_VISIBLE: usize = 5;
+
+    /// Adds a notification with the given `level` and `message`.
+    ///
+    /// The message is also written to the log so it is not lost once the toast expires.
+    pub fn push(&mut self, level: ToastLevel, message: impl Into<String>) {
+        let message = message.into();
+        match level {
+            ToastLevel::Info => log::info!("{message}"),
+            ToastLevel::Warning => log::warn!("{message}"),
+            ToastLevel::Error => log::error!("{message}"),
+        }
+        self.entries.push(Toast {
+            level,
+            message,
+            created: Instant::now(),
+        });
+        if self.entries.len() > Self::MAX_VISIBLE {
+            let overflow = self.entries.len() - Self::MAX_VISIBLE;
+            self.entries.drain(..overflow);
+        }
+    }
+
+    /// Adds an informational notification.
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(ToastLevel::Info, message);
+    }
+
+    /// Adds a warning notification.
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.push(ToastLevel::Warning, message);
+    }
+
+    /// Adds an error notification.
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(ToastLevel::Error, message);
+    }
+
+    /// Returns the notifications that are currently active, oldest first.
+    pub fn entries(&self) -> &[Toast] {
+        &self.entries
+    }
+
+    /// Draws all active toasts stacked in the bottom-right corner of the screen.
+    ///
+    /// Expired toasts are removed before drawing, and clicking a toast dismisses it. This
+    /// must be called between `egui::Context::begin_pass` and `egui::Context::end_pass`.
+    #[cfg(feature = "gui")]
+    pub fn show(&mut self, context: &egui::Context) {
+        self.entries
+            .retain(|toast| toast.created.elapsed() < Self::DURATION);
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -12.0))
+            // Stay clear of the panels and the platform's safe-area insets.
+            .constrain_to(context.available_rect())
+            .order(egui::Order::Foreground)
+            .interactable(true)
+            .show(context, |ui| {
+                ui.set_max_width(360.0);
+                for (index, toast) in self.entries.iter().enumerate().rev() {
+                    let response = egui::Frame::popup(ui.style())
+                        .stroke(egui::Stroke::new(1.0, toast.level.color()))
+                        .show(ui, |ui| {
+                            ui.colored_label(toast.level.color(), &toast.message);
+                        })
+                        .response
+                        .interact(egui::Sense::click());
+                    if response.clicked() {
+                        dismissed = Some(index);
+                    }
+                }
+            });
+
+        if let Some(index) = dismissed {
+            self.entries.remove(index);
+        }
+
+        // Keep repainting while toasts are visible so they disappear on time.
+        context.request_repaint_after(Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,2504 @@
+//! # User Interface Module
+//!
+//! The `ui` module builds the application's `egui` interface: the settings window and the
+//! optional top, left, right, and bottom panels around the 3D viewport.
+//!
+//! ## Overview
+//!
+//! - [`UiState`] holds everything the interface edits, such as whether the panels are visible.
+//! - [`show`] builds the interface for one frame from a `UiState`.
+//! - [`show_depth_probe`] draws the depth probe readout next to the cursor.
+//! - [`show_letterbox`] paints the bars around a viewport locked to an aspect ratio.
+//! - [`show_titlebar`] draws the title bar of a window without platform decorations.
+//! - [`show_inspector_window`] builds the GUI of a secondary Inspector window.
+//!
+//! Everything but `UiState` requires the `gui` feature. Without it, the `App` keeps the state
+//! at its defaults and renders no interface.
+//!
+//! Keeping the panel-building code free of any window or GPU state allows it to run
+//! headlessly. The tests at the bottom of this module drive it with simulated pointer input
+//! and compare the resulting accessibility tree against snapshots stored in
+//! `tests/snapshots`. Run the tests with `UPDATE_SNAPSHOTS=1` to accept intentional changes.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! egui_context.begin_pass(input);
+//! ui::show(&egui_context, "Rust/Wgpu", &mut ui_state);
+//! let output = egui_context.end_pass();
+//! ```
+
+/// The state edited through the user interface.
+///
+/// The panels start hidden, so the 3D viewport is unobstructed until the user enables them.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UiState {
+    /// Whether the top, left, right, and bottom panels are shown.
+    pub panels_visible: bool,
+
+    /// The heading of the panel whose button was clicked most recently, if any.
+    pub last_clicked: Option<&'static str>,
+
+    /// Whether the depth probe readout is shown next to the cursor.
+    pub depth_probe_enabled: bool,
+
+    /// Whether the ground grid is drawn below the scene.
+    pub grid_visible: bool,
+
+    /// Whether the infinite ground grid and the world axes are drawn below the scene, toggled
+    /// in the View menu.
+    pub world_grid_visible: bool,
+
+    /// Whether the scene's nodes are labeled with their names, toggled in the View menu.
+    pub node_labels_visible: bool,
+
+    /// Whether the camera's flight path is drawn and can be edited with the mouse.
+    pub camera_path_visible: bool,
+
+    /// Whether the skinned crowd demo is shown behind the scene.
+    pub crowd_demo_enabled: bool,
+
+    /// Whether the GPU particle fountain is shown below the scene.
+    pub particle_demo_enabled: bool,
+
+    /// Whether "Scene in Panel" is checked, showing the scene in the central panel left free
+    /// by the other panels instead of behind the GUI.
+    pub scene_in_panel: bool,
+
+    /// The texture the scene is rendered into while it is shown in a panel, set by the `App`
+    /// every frame.
+    #[cfg(feature = "gui")]
+    pub scene_texture: Option<egui::TextureId>,
+
+    /// The rectangle of the central panel showing the scene, in points, set by `show` while
+    /// `scene_in_panel` is checked. The `App` resizes the scene's texture to match it.
+    #[cfg(feature = "gui")]
+    pub scene_panel_rect: Option<egui::Rect>,
+
+    /// Whether the cursor is over the central panel showing the scene, so that the `App`
+    /// passes pointer input on to the camera although the GUI is under the cursor.
+    pub scene_panel_hovered: bool,
+
+    /// How the instancing demo draws its grid of triangles, if at all.
+    pub grid_demo: crate::instancing::GridDemo,
+
+    /// The layers the main camera draws and the layers of each object, edited in the
+    /// Inspector panel.
+    pub layers: crate::layers::LayerMasks,
+
+    /// How the renderer draws the scene.
+    pub debug_view: crate::debug_view::DebugView,
+
+    /// The render mode and depth test the scene is drawn with, edited in the Inspector panel.
+    pub rasterization: crate::scene::Rasterization,
+
+    /// The render modes the renderer supports, set by the `App` every frame. The others are
+    /// shown disabled.
+    pub render_modes: Vec<crate::scene::RenderMode>,
+
+    /// The present mode frames are shown with, edited in the Inspector panel. The `App` sets
+    /// it back to the mode in effect, which differs if the surface does not support it.
+    pub present_mode: wgpu::PresentMode,
+
+    /// The number of frames that may be queued for presentation, edited in the Inspector panel.
+    /// The `App` sets it back to the latency in effect.
+    pub frame_latency: u32,
+
+    /// Whether frames are delayed until just before the display's next refresh, so they show
+    /// the latest input, edited in the Inspector panel.
+    pub frame_pacing: bool,
+
+    /// When frames are rendered, edited in the Inspector panel.
+    pub redraw_policy: crate::redraw::RedrawPolicy,
+
+    /// The present modes the surface supports, set by the `App` every frame. The others are
+    /// shown disabled.
+    pub present_modes: Vec<wgpu::PresentMode>,
+
+    /// The aspect ratio the 3D viewport is locked to, if any.
+    pub aspect_lock: crate::viewport::AspectLock,
+
+    /// Which camera drives the view.
+    pub camera_mode: crate::camera::CameraMode,
+
+    /// How mouse and keyboard input move the cameras.
+    pub camera_settings: crate::camera::CameraSettings,
+
+    /// The keys and mouse buttons bound to the actions, edited in the "Key Bindings" section.
+    /// The `App` loads them from the settings when the window is created.
+    pub action_map: crate::actions::ActionMap,
+
+    /// The action whose binding button was clicked, if any. The `App` binds it to the next key
+    /// or mouse button pressed outside of the GUI and clears it.
+    pub rebinding: Option<crate::actions::Action>,
+
+    /// The camera's field of view and clip planes.
+    pub projection: crate::camera::Projection,
+
+    /// The settings of the dolly zoom demo, which animates the field of view.
+    pub dolly_zoom: crate::dolly_zoom::DollyZoom,
+
+    /// The flythrough's recording and playback controls, and the requests of its buttons.
+    pub flythrough: crate::camera_path::FlythroughControls,
+
+    /// The quality scaler's target frame rate and the bounds of each setting.
+    pub adaptive_quality: crate::quality::AdaptiveQuality,
+
+    /// Whether the window is fullscreen, how, and in which video mode, edited in the
+    /// "Fullscreen" section. The `App` applies them to the window.
+    pub fullscreen: crate::fullscreen::FullscreenControls,
+
+    /// The current level of each quality setting, set by the `App` every frame.
+    pub quality_levels: crate::quality::QualityLevels,
+
+    /// Whether the draw statistics of the scene pass are shown over the viewport.
+    pub draw_stats_visible: bool,
+
+    /// The draw statistics of the most recent scene pass, set by the `App` every frame.
+    pub draw_stats: crate::render_queue::DrawStats,
+
+    /// Whether the Frame Statistics window is shown, toggled with F1.
+    pub frame_stats_visible: bool,
+
+    /// The frame rate and frame times, set by the `App` every frame.
+    pub frame_stats: crate::frame_stats::FrameStatsSummary,
+
+    /// Whether the Profiler window is shown, charting the GPU time of each pass.
+    pub profiler_visible: bool,
+
+    /// Whether the CPU Profiler window is shown.
+    pub cpu_profiler_visible: bool,
+
+    /// Whether the CPU profiler records, toggled with "Record" in the CPU Profiler window.
+    pub cpu_profiler_enabled: bool,
+
+    /// The scopes of the latest frame the CPU profiler recorded, set by the `App` every frame
+    /// while the CPU Profiler window is shown.
+    pub cpu_frame: Option<crate::cpu_profiler::CpuFrame>,
+
+    /// Whether "Export Chrome Trace" was clicked. The `App` clears it and saves the recorded
+    /// frames as a Chrome trace.
+    pub cpu_trace_export_requested: bool,
+
+    /// Whether the GPU Info window is shown.
+    pub gpu_info_visible: bool,
+
+    /// The adapter the renderer uses and its granted features and limits, set by the `App`
+    /// once the renderer is created, or `None` for backends without a GPU adapter.
+    pub gpu_info: Option<crate::capabilities::GpuInfo>,
+
+    /// Whether the GPU Errors window is shown.
+    pub gpu_errors_visible: bool,
+
+    /// The latest distinct GPU errors, oldest first, set by the `App` every frame.
+    pub gpu_errors: Vec<crate::gpu_errors::GpuErrorRecord>,
+
+    /// Whether "Clear" was clicked in the GPU Errors window. The `App` clears it and forgets
+    /// the renderer's errors.
+    pub gpu_errors_clear_requested: bool,
+
+    /// The Widget Gallery window and the frame times it plots.
+    pub gallery: crate::gallery::WidgetGallery,
+
+    /// The Shader Editor window and the WGSL source it edits.
+    pub shader_editor: crate::shader_editor::ShaderEditor,
+
+    /// Whether "Frame Scene" was clicked. The `App` clears it and moves the camera to show
+    /// all of the scene's content.
+    pub frame_scene_requested: bool,
+
+    /// Whether "Export Camera" was clicked. The `App` clears it and writes the active camera
+    /// to `camera.json`.
+    pub camera_export_requested: bool,
+
+    /// Whether "Import Camera" was clicked. The `App` clears it and moves the camera to the
+    /// one in `camera.json`.
+    pub camera_import_requested: bool,
+
+    /// The thresholds touch gestures are recognized with.
+    pub gesture_settings: crate::gestures::GestureSettings,
+
+    /// The name of the most recently recognized gesture, set by the `App`.
+    pub last_gesture: Option<&'static str>,
+
+    /// The pointer events received and handled during the last frame, set by the `App`.
+    pub pointer_events: crate::input::PointerEventCounts,
+
+    /// Whether the Environment window is shown.
+    pub environment_visible: bool,
+
+    /// The global environment the scene is drawn with.
+    pub environment: crate::environment::Environment,
+
+    /// The scene's own environment, overriding the global one, if set.
+    pub scene_environment: Option<crate::environment::Environment>,
+
+    /// The directional light the scene is shaded with, edited in the Inspector panel.
+    pub light: crate::lighting::Light,
+
+    /// Whether the Post FX window is shown.
+    pub post_effects_visible: bool,
+
+    /// The effects applied to the scene before it is shown, edited in the Post FX window.
+    pub post_effects: crate::post_process::PostEffects,
+
+    /// The platform's safe-area insets, set by the `App` every frame. Panels and windows are
+    /// kept inside them.
+    pub safe_area: crate::safe_area::SafeAreaInsets,
+
+    /// Whether the safe-area insets are highlighted in a debug overlay.
+    pub safe_area_overlay: bool,
+
+    /// Whether the `App` replaces the platform's insets with a simulated notch, to preview
+    /// safe-area layouts on a desktop.
+    pub simulate_notch: bool,
+
+    /// Whether rulers are shown along the viewport's edges.
+    pub rulers_visible: bool,
+
+    /// Whether the guide lines are shown.
+    pub guides_visible: bool,
+
+    /// The guide lines across the viewport, dragged out of the rulers.
+    pub guides: Vec<crate::rulers::Guide>,
+
+    /// The GUI zoom, changed with the "GUI Scale" slider or `Ctrl` with `+`, `-`, and `0`.
+    pub gui_scale: crate::settings::GuiScale,
+
+    /// Whether "Open Inspector Window" was clicked. The `App` clears it and opens a secondary
+    /// window with the Inspector panel.
+    pub inspector_window_requested: bool,
+
+    /// Whether "Detach Windows" is checked, showing the Frame Statistics and GPU Info windows
+    /// in native windows of their own.
+    pub detach_windows: bool,
+
+    /// Whether the rendering backend can draw GUI windows detached from the main window, set
+    /// by the `App` every frame. Detached windows are embedded in the main window otherwise.
+    pub viewports_supported: bool,
+
+    /// Whether "Capture GUI" was clicked. The `App` clears it and captures the next frame, in
+    /// which the View menu is closed again.
+    pub ui_capture_requested: bool,
+
+    /// Whether "Screenshot" was clicked or its action (F12) triggered. The `App` clears it and
+    /// saves the next frame as a PNG.
+    pub screenshot_requested: bool,
+
+    /// Whether the "Capture GPU Frame" action (F9) was triggered. The `App` clears it and has
+    /// the renderer capture the next frame in the attached graphics debugger.
+    pub gpu_capture_requested: bool,
+
+    /// The progress of the background tasks still running, set by the `App` every frame.
+    pub tasks: Vec<crate::background::TaskProgress>,
+
+    /// The background color as linear RGBA with unmultiplied alpha, edited in the Inspector
+    /// panel. The `App` loads it from the settings when the window is created.
+    pub clear_color: [f32; 4],
+
+    /// The scene's materials, the selected one edited in the Inspector panel. The `App`
+    /// replaces them with a loaded model's materials.
+    #[cfg(feature = "gui")]
+    pub materials: crate::material::MaterialLibrary,
+
+    /// The texture of the material preview, set by the `App` while the panels are shown.
+    #[cfg(feature = "gui")]
+    pub material_preview: Option<egui::TextureId>,
+
+    /// The name and texture of each mesh preview, set by the `App` while the panels are shown.
+    #[cfg(feature = "gui")]
+    pub mesh_previews: Vec<(&'static str, egui::TextureId)>,
+
+    /// The mesh preview under the cursor, which turns on a turntable.
+    pub turntable_mesh: Option<&'static str>,
+
+    /// The name of the scene's node selected by clicking it, shown in the Inspector panel.
+    /// Set by the `App` every frame.
+    pub selected_node: Option<String>,
+
+    /// What the handles of the selected node's transform gizmo do.
+    pub gizmo_mode: crate::gizmo::GizmoMode,
+
+    /// The steps the transform gizmo's drags are rounded to.
+    pub gizmo_snapping: crate::gizmo::GizmoSnapping,
+}
+
+/// Builds the user interface for one frame.
+///
+/// The settings window is titled `title` and contains checkboxes toggling the panels, the
+/// depth probe, the ground grid, the camera path, the crowd demo, and the Environment window,
+/// selectors for the debug view and the viewport's aspect ratio, a collapsible "Input" section
+/// with the camera settings, and a collapsible "Key Bindings" section. When
+/// `state.panels_visible` is `true`, the top, left, right, and bottom panels are shown as well,
+/// and while `state.tasks` is not empty, a "Tasks" window shows their progress. When
+/// `state.scene_in_panel` is `true`, a central panel fills the space left between the panels,
+/// showing `state.scene_texture` once the `App` set it.
+///
+/// Everything is laid out inside `state.safe_area`, whose insets are reserved with empty panels
+/// along the window's edges.
+///
+/// This must be called between `egui::Context::begin_pass` and `egui::Context::end_pass`.
+#[cfg(feature = "gui")]
+pub fn show(context: &egui::Context, title: &str, state: &mut UiState) {
+    context.set_embed_viewports(!(state.detach_windows && state.viewports_supported));
+    zoom_with_keyboard(context, state);
+    let safe_rect = reserve_safe_area(context, &state.safe_area);
+
+    if state.panels_visible {
+        egui::TopBottomPanel::top("top").show(context, |ui| {
+            ui.horizontal(|ui| {
+                ui.menu_button("File", |ui| {
+                    if ui.button("Export Camera").clicked() {
+                        state.camera_export_requested = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("Import Camera").clicked() {
+                        state.camera_import_requested = true;
+                        ui.close_menu();
+                    }
+                });
+                ui.label("Edit");
+                ui.menu_button("View", |ui| {
+                    ui.checkbox(&mut state.world_grid_visible, "World Grid and Axes");
+                    ui.checkbox(&mut state.node_labels_visible, "Node Labels");
+                    ui.checkbox(&mut state.rulers_visible, "Rulers");
+                    ui.checkbox(&mut state.guides_visible, "Guides");
+                    let clear = egui::Button::new("Clear Guides");
+                    if ui.add_enabled(!state.guides.is_empty(), clear).clicked() {
+                        state.guides.clear();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    zoom_menu_buttons(ui, state);
+                    ui.separator();
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("Open Inspector Window").clicked() {
+                        state.inspector_window_requested = true;
+                        ui.close_menu();
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    ui.add_enabled(
+                        state.viewports_supported,
+                        egui::Checkbox::new(&mut state.detach_windows, "Detach Windows"),
+                    );
+                    if ui.button("Capture GUI").clicked() {
+                        state.ui_capture_requested = true;
+                        ui.close_menu();
+                    }
+                });
+                if ui.button("Screenshot").clicked() {
+                    state.screenshot_requested = true;
+                }
+            });
+        });
+
+        egui::SidePanel::left("left").show(context, |ui| {
+            panel_contents(ui, "Scene Explorer", state);
+        });
+
+        egui::SidePanel::right("right").show(context, |ui| inspector(ui, state));
+
+        egui::TopBottomPanel::bottom("bottom").show(context, |ui| {
+            panel_contents(ui, "Assets", state);
+            mesh_thumbnails(ui, state);
+        });
+    }
+
+    scene_panel(context, state);
+
+    egui::Window::new(title)
+        .constrain_to(safe_rect)
+        .show(context, |ui| {
+            ui.checkbox(&mut state.panels_visible, "Show Panels");
+            ui.checkbox(&mut state.depth_probe_enabled, "Depth Probe");
+            ui.checkbox(&mut state.grid_visible, "Show Grid");
+            ui.checkbox(&mut state.camera_path_visible, "Camera Path");
+            #[cfg(feature = "scene3d")]
+            ui.checkbox(&mut state.crowd_demo_enabled, "Crowd Demo");
+            #[cfg(feature = "scene3d")]
+            ui.checkbox(&mut state.particle_demo_enabled, "Particle Demo");
+            ui.checkbox(&mut state.scene_in_panel, "Scene in Panel");
+            egui::ComboBox::from_label("Grid Demo")
+                .selected_text(state.grid_demo.label())
+                .show_ui(ui, |ui| {
+                    for demo in crate::instancing::GridDemo::ALL {
+                        ui.selectable_value(&mut state.grid_demo, demo, demo.label());
+                    }
+                });
+            ui.checkbox(&mut state.environment_visible, "Environment");
+            ui.checkbox(&mut state.post_effects_visible, "Post FX");
+            ui.checkbox(&mut state.safe_area_overlay, "Safe Area");
+            ui.checkbox(&mut state.simulate_notch, "Simulate Notch");
+            ui.checkbox(&mut state.draw_stats_visible, "Draw Statistics");
+            ui.checkbox(&mut state.frame_stats_visible, "Frame Statistics");
+            ui.checkbox(&mut state.profiler_visible, "Profiler");
+            ui.checkbox(&mut state.cpu_profiler_visible, "CPU Profiler");
+            ui.checkbox(&mut state.gpu_info_visible, "GPU Info");
+            ui.checkbox(&mut state.gpu_errors_visible, "GPU Errors");
+            ui.checkbox(&mut state.gallery.visible, "Widget Gallery");
+            ui.checkbox(&mut state.shader_editor.visible, "Shader Editor");
+            gui_scale_slider(ui, state);
+            egui::ComboBox::from_label("Debug View")
+                .selected_text(state.debug_view.label())
+                .show_ui(ui, |ui| {
+                    for debug_view in crate::debug_view::DebugView::ALL {
+                        ui.selectable_value(&mut state.debug_view, debug_view, debug_view.label());
+                    }
+                });
+            egui::ComboBox::from_label("Aspect Ratio")
+                .selected_text(state.aspect_lock.label())
+                .show_ui(ui, |ui| {
+                    for aspect_lock in crate::viewport::AspectLock::ALL {
+                        let label = aspect_lock.label();
+                        ui.selectable_value(&mut state.aspect_lock, aspect_lock, label);
+                    }
+                });
+            egui::CollapsingHeader::new("Input").show(ui, |ui| {
+                input_settings(ui, state);
+            });
+            egui::CollapsingHeader::new("Key Bindings").show(ui, |ui| {
+                key_bindings(ui, state);
+            });
+            egui::CollapsingHeader::new("Gestures").show(ui, |ui| {
+                gesture_settings(ui, state);
+            });
+            egui::CollapsingHeader::new("Dolly Zoom").show(ui, |ui| {
+                dolly_zoom_settings(ui, &mut state.dolly_zoom);
+            });
+            egui::CollapsingHeader::new("Flythrough").show(ui, |ui| {
+                flythrough_settings(ui, &mut state.flythrough);
+            });
+            egui::CollapsingHeader::new("Adaptive Quality").show(ui, |ui| {
+                adaptive_quality_settings(ui, &mut state.adaptive_quality, &state.quality_levels);
+            });
+            egui::CollapsingHeader::new("Fullscreen").show(ui, |ui| {
+                fullscreen_settings(ui, &mut state.fullscreen);
+            });
+        });
+
+    let mut environment_visible = state.environment_visible;
+    egui::Window::new("Environment")
+        .open(&mut environment_visible)
+        .constrain_to(safe_rect)
+        .show(context, |ui| environment_settings(ui, state));
+    state.environment_visible = environment_visible;
+
+    let mut post_effects_visible = state.post_effects_visible;
+    egui::Window::new("Post FX")
+        .open(&mut post_effects_visible)
+        .constrain_to(safe_rect)
+        .show(context, |ui| {
+            post_effects_settings(ui, &mut state.post_effects)
+        });
+    state.post_effects_visible = post_effects_visible;
+
+    show_detachable(
+        context,
+        egui::Window::new("Frame Statistics")
+            .constrain_to(safe_rect)
+            .resizable(false),
+        "Frame Statistics",
+        &mut state.frame_stats_visible,
+        state.frame_stats.clone(),
+        frame_stats,
+    );
+
+    let mut profiler_visible = state.profiler_visible;
+    egui::Window::new("Profiler")
+        .open(&mut profiler_visible)
+        .constrain_to(safe_rect)
+        .resizable(false)
+        .show(context, |ui| profiler(ui, &state.frame_stats));
+    state.profiler_visible = profiler_visible;
+
+    let mut cpu_profiler_visible = state.cpu_profiler_visible;
+    egui::Window::new("CPU Profiler")
+        .open(&mut cpu_profiler_visible)
+        .constrain_to(safe_rect)
+        .default_width(360.0)
+        .show(context, |ui| cpu_profiler(ui, state));
+    state.cpu_profiler_visible = cpu_profiler_visible;
+
+    show_detachable(
+        context,
+        egui::Window::new("GPU Info").constrain_to(safe_rect),
+        "GPU Info",
+        &mut state.gpu_info_visible,
+        state.gpu_info.clone(),
+        |ui, info| gpu_info(ui, info.as_ref()),
+    );
+
+    let mut gpu_errors_visible = state.gpu_errors_visible;
+    egui::Window::new("GPU Errors")
+        .open(&mut gpu_errors_visible)
+        .constrain_to(safe_rect)
+        .default_width(420.0)
+        .show(context, |ui| gpu_errors(ui, state));
+    state.gpu_errors_visible = gpu_errors_visible;
+
+    if state.gallery.visible {
+        show_widget_gallery(context, safe_rect, state);
+    }
+
+    if state.shader_editor.visible {
+        show_shader_editor(context, safe_rect, &mut state.shader_editor);
+    }
+
+    if !state.tasks.is_empty() {
+        show_tasks(context, safe_rect, &state.tasks);
+    }
+
+    if state.safe_area_overlay {
+        show_safe_area_overlay(context, safe_rect);
+    }
+}
+
+/// Shows the scene's texture in a central panel while `state.scene_in_panel` is checked, and
+/// records the panel's rectangle and whether the cursor is over it.
+#[cfg(feature = "gui")]
+fn scene_panel(context: &egui::Context, state: &mut UiState) {
+    state.scene_panel_rect = None;
+    state.scene_panel_hovered = false;
+    if !state.scene_in_panel {
+        return;
+    }
+    egui::CentralPanel::default()
+        .frame(egui::Frame::NONE)
+        .show(context, |ui| {
+            let rect = ui.max_rect();
+            if let Some(texture) = state.scene_texture {
+                ui.put(rect, egui::Image::new((texture, rect.size())));
+            }
+            state.scene_panel_rect = Some(rect);
+            state.scene_panel_hovered = ui.rect_contains_pointer(rect);
+        });
+}
+
+/// Shows `window`, titled `title`, with the contents added by `add_contents` from `data`
+/// while `open` is `true`.
+///
+/// While `context` does not embed viewports, the window is shown in a native window of its
+/// own instead, built when that window is redrawn. It then shows the `data` of the latest
+/// pass of the main window, and closing it clears `open` in the next pass.
+#[cfg(feature = "gui")]
+fn show_detachable<T: Send + Sync + 'static>(
+    context: &egui::Context,
+    window: egui::Window,
+    title: &'static str,
+    open: &mut bool,
+    data: T,
+    add_contents: fn(&mut egui::Ui, &T),
+) {
+    if context.embed_viewports() {
+        window
+            .open(open)
+            .show(context, |ui| add_contents(ui, &data));
+        return;
+    }
+
+    // The native window reports its closing through the context's memory, which the main
+    // window shares.
+    let closed_id = egui::Id::new(title).with("closed");
+    if context.data_mut(|memory| memory.remove_temp::<bool>(closed_id)) == Some(true) {
+        *open = false;
+    }
+    if !*open {
+        return;
+    }
+    let builder = egui::ViewportBuilder::default()
+        .with_title(title)
+        .with_inner_size([320.0, 360.0]);
+    context.show_viewport_deferred(
+        egui::ViewportId::from_hash_of(title),
+        builder,
+        move |context, _| {
+            egui::CentralPanel::default().show(context, |ui| add_contents(ui, &data));
+            if context.input(|input| input.viewport().close_requested()) {
+                context.data_mut(|memory| memory.insert_temp(closed_id, true));
+            }
+        },
+    );
+}
+
+/// Builds the GUI of a secondary Inspector window for one frame: the Inspector panel beside
+/// the scene, editing the same `state` as the main window.
+///
+/// The material preview is not shown, as its texture belongs to the main window's renderer.
+///
+/// This must be called between `egui::Context::begin_pass` and `egui::Context::end_pass` of
+/// the window's own context. Secondary windows are only opened on native targets.
+#[cfg(all(feature = "gui", not(target_arch = "wasm32")))]
+pub fn show_inspector_window(context: &egui::Context, state: &mut UiState) {
+    let material_preview = state.material_preview.take();
+    egui::SidePanel::right("inspector_window")
+        .default_width(280.0)
+        .show(context, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| inspector(ui, state));
+        });
+    state.material_preview = material_preview;
+}
+
+/// Fills the Inspector panel with the selected node and the transform gizmo's settings, the
+/// material, the light, the rendering settings, and the render layers.
+#[cfg(feature = "gui")]
+fn inspector(ui: &mut egui::Ui, state: &mut UiState) {
+    panel_contents(ui, "Inspector", state);
+    ui.separator();
+    let selected = state.selected_node.as_deref().unwrap_or("None");
+    ui.label(format!("Selected Node: {selected}"))
+        .on_hover_text("Click an object in the scene to select it.");
+    gizmo_inspector(ui, state);
+    ui.separator();
+    material_inspector(ui, state);
+    ui.separator();
+    light_inspector(ui, &mut state.light);
+    ui.separator();
+    rendering_inspector(ui, state);
+    ui.separator();
+    egui::CollapsingHeader::new("Render Layers").show(ui, |ui| {
+        layer_inspector(ui, &mut state.layers);
+    });
+}
+
+/// Shows the frame rate, the frame time statistics, and the CPU and GPU times in a grid.
+#[cfg(feature = "gui")]
+fn frame_stats(ui: &mut egui::Ui, stats: &crate::frame_stats::FrameStatsSummary) {
+    if stats.frames == 0 {
+        ui.label("No frames rendered yet.");
+        return;
+    }
+    let milliseconds = |time: crate::Duration| format!("{:.2} ms", time.as_secs_f64() * 1000.0);
+    egui::Grid::new("frame_stats")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("FPS");
+            ui.label(format!("{:.1}", stats.fps));
+            ui.end_row();
+            for (label, time) in [
+                ("Mean", stats.mean),
+                ("Min", stats.min),
+                ("Max", stats.max),
+                ("50th Percentile", stats.p50),
+                ("95th Percentile", stats.p95),
+                ("99th Percentile", stats.p99),
+                ("CPU", stats.cpu),
+            ] {
+                ui.label(label);
+                ui.label(milliseconds(time));
+                ui.end_row();
+            }
+            ui.label("GPU");
+            ui.label(
+                stats
+                    .gpu
+                    .map_or_else(|| "Unsupported".to_owned(), milliseconds),
+            );
+            ui.end_row();
+            ui.label("Input Latency");
+            ui.label(
+                stats
+                    .input_latency
+                    .map_or_else(|| "No input yet".to_owned(), milliseconds),
+            );
+            ui.end_row();
+            for &(pass, time) in &stats.gpu_passes {
+                ui.label(format!("GPU {pass} Pass"));
+                ui.label(milliseconds(time));
+                ui.end_row();
+            }
+        });
+    ui.label(format!("Over the last {} frames", stats.frames));
+}
+
+/// Charts the GPU time of each pass of the most recently measured frame as horizontal bars,
+/// scaled to the GPU time of the whole frame.
+#[cfg(feature = "gui")]
+fn profiler(ui: &mut egui::Ui, stats: &crate::frame_stats::FrameStatsSummary) {
+    let Some(gpu) = stats.gpu else {
+        ui.label("GPU timing is unsupported by this adapter.");
+        return;
+    };
+    const BAR_WIDTH: f32 = 200.0;
+    let scale = gpu.as_secs_f32().max(f32::EPSILON);
+    let milliseconds = |time: crate::Duration| format!("{:.3} ms", time.as_secs_f64() * 1000.0);
+    egui::Grid::new("profiler").num_columns(3).show(ui, |ui| {
+        for &(pass, time) in &stats.gpu_passes {
+            ui.label(pass);
+            let height = ui.spacing().interact_size.y * 0.6;
+            let (rect, _) =
+                ui.allocate_exact_size(egui::vec2(BAR_WIDTH, height), egui::Sense::hover());
+            let fraction = (time.as_secs_f32() / scale).clamp(0.0, 1.0);
+            let painter = ui.painter();
+            painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+            let bar = egui::Rect::from_min_size(rect.min, egui::vec2(BAR_WIDTH * fraction, height));
+            painter.rect_filled(bar, 2.0, ui.visuals().selection.bg_fill);
+            ui.label(milliseconds(time));
+            ui.end_row();
+        }
+        ui.label("Frame");
+        ui.label("");
+        ui.label(milliseconds(gpu));
+        ui.end_row();
+    });
+    ui.label("Measured with timestamp queries, a few frames behind.");
+}
+
+/// Shows the scopes of the latest frame recorded by the CPU profiler as bars laid out on the
+/// frame's timeline, nested scopes indented below the scopes they are nested in, along with
+/// the toggle for recording and the trace export.
+#[cfg(feature = "gui")]
+fn cpu_profiler(ui: &mut egui::Ui, state: &mut UiState) {
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut state.cpu_profiler_enabled, "Record");
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui
+            .add_enabled(
+                state.cpu_frame.is_some(),
+                egui::Button::new("Export Chrome Trace"),
+            )
+            .on_hover_text("Saves the last recorded frames for chrome://tracing or Perfetto.")
+            .clicked()
+        {
+            state.cpu_trace_export_requested = true;
+        }
+    });
+    let Some(frame) = &state.cpu_frame else {
+        ui.label("Check \"Record\" to profile the following frames.");
+        return;
+    };
+    let milliseconds = |time: crate::Duration| format!("{:.3} ms", time.as_secs_f64() * 1000.0);
+    ui.label(format!(
+        "Frame {}: {}",
+        frame.index,
+        milliseconds(frame.duration)
+    ));
+
+    let row_height = ui.spacing().interact_size.y;
+    let rows = frame
+        .scopes
+        .iter()
+        .map(|scope| scope.depth + 1)
+        .max()
+        .unwrap_or(0);
+    let width = ui.available_width().max(200.0);
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(width, row_height * rows as f32),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    let scale = rect.width() / frame.duration.as_secs_f32().max(f32::EPSILON);
+    let mut hovered = None;
+    for scope in &frame.scopes {
+        let offset = scope.start.saturating_sub(frame.start).as_secs_f32() * scale;
+        let bar = egui::Rect::from_min_size(
+            rect.min + egui::vec2(offset, row_height * scope.depth as f32),
+            egui::vec2(
+                (scope.duration.as_secs_f32() * scale).max(1.0),
+                row_height - 2.0,
+            ),
+        );
+        painter.rect_filled(bar, 2.0, ui.visuals().selection.bg_fill);
+        painter.text(
+            bar.left_center() + egui::vec2(4.0, 0.0),
+            egui::Align2::LEFT_CENTER,
+            scope.name,
+            egui::TextStyle::Small.resolve(ui.style()),
+            ui.visuals().strong_text_color(),
+        );
+        if response
+            .hover_pos()
+            .is_some_and(|position| bar.contains(position))
+        {
+            hovered = Some(scope);
+        }
+    }
+    if let Some(scope) = hovered {
+        response.on_hover_text(format!("{}: {}", scope.name, milliseconds(scope.duration)));
+    }
+
+    egui::Grid::new("cpu_profiler_scopes")
+        .num_columns(2)
+        .show(ui, |ui| {
+            for scope in &frame.scopes {
+                ui.label(format!("{}{}", "    ".repeat(scope.depth), scope.name));
+                ui.label(milliseconds(scope.duration));
+                ui.end_row();
+            }
+        });
+}
+
+/// Fills the GPU Info window with the name, type, backend, and driver of the adapter, and
+/// collapsible lists of the device's features and key limits.
+#[cfg(feature = "gui")]
+fn gpu_info(ui: &mut egui::Ui, info: Option<&crate::capabilities::GpuInfo>) {
+    let Some(info) = info else {
+        ui.label("No GPU adapter in use");
+        return;
+    };
+    let adapter = &info.adapter;
+    egui::Grid::new("gpu_info").num_columns(2).show(ui, |ui| {
+        let rows = [
+            ("Adapter", adapter.name.clone()),
+            ("Type", format!("{:?}", adapter.device_type)),
+            ("Backend", adapter.backend.to_string()),
+            ("Vendor", format!("{:#06x}", adapter.vendor)),
+            ("Device", format!("{:#06x}", adapter.device)),
+            ("Driver", adapter.driver.clone()),
+            ("Driver Info", adapter.driver_info.clone()),
+        ];
+        for (label, value) in rows {
+            ui.label(label);
+            ui.label(value);
+            ui.end_row();
+        }
+    });
+
+    ui.collapsing("Features", |ui| {
+        if info.features.is_empty() {
+            ui.label("No optional features");
+        }
+        for (name, _) in info.features.iter_names() {
+            ui.monospace(name);
+        }
+    });
+    ui.collapsing("Limits", |ui| {
+        egui::Grid::new("gpu_limits").num_columns(2).show(ui, |ui| {
+            for (label, value) in info.key_limits() {
+                ui.label(label);
+                ui.label(value.to_string());
+                ui.end_row();
+            }
+        });
+    });
+}
+
+/// Fills the GPU Errors window with the recorded errors, newest first, and a button clearing
+/// them.
+#[cfg(feature = "gui")]
+fn gpu_errors(ui: &mut egui::Ui, state: &mut UiState) {
+    if ui.button("Clear").clicked() {
+        state.gpu_errors_clear_requested = true;
+    }
+    if state.gpu_errors.is_empty() {
+        ui.label("No GPU errors");
+        return;
+    }
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for record in state.gpu_errors.iter().rev() {
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.colored_label(ui.visuals().error_fg_color, record.kind.label());
+                ui.label(record.operation);
+                if record.count > 1 {
+                    ui.weak(format!("×{}", record.count));
+                }
+            });
+            ui.label(egui::RichText::new(&record.message).monospace());
+        }
+    });
+}
+
+/// Shows the Shader Editor window: the scene's WGSL source in a code editor, the buttons
+/// applying it, and the error of the last attempt below.
+#[cfg(feature = "gui")]
+fn show_shader_editor(
+    context: &egui::Context,
+    safe_rect: egui::Rect,
+    editor: &mut crate::shader_editor::ShaderEditor,
+) {
+    let mut visible = editor.visible;
+    egui::Window::new("Shader Editor")
+        .open(&mut visible)
+        .constrain_to(safe_rect)
+        .default_width(480.0)
+        .show(context, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Apply").clicked() {
+                    editor.request_apply();
+                }
+                if ui.button("Reset").clicked() {
+                    editor.reset();
+                }
+                if editor.checking {
+                    ui.spinner();
+                    ui.label("Checking the shader…");
+                }
+            });
+            egui::ScrollArea::vertical()
+                .id_salt("shader_source")
+                .max_height(480.0)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut editor.source)
+                            .code_editor()
+                            .desired_rows(24)
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+            if let Some(error) = &editor.error {
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .id_salt("shader_error")
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            egui::RichText::new(error).monospace(),
+                        );
+                    });
+            }
+        });
+    editor.visible = visible;
+}
+
+/// Shows a progress bar for each of the `tasks` in a window at the bottom right.
+#[cfg(feature = "gui")]
+fn show_tasks(
+    context: &egui::Context,
+    safe_rect: egui::Rect,
+    tasks: &[crate::background::TaskProgress],
+) {
+    egui::Window::new("Tasks")
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+        .constrain_to(safe_rect)
+        .resizable(false)
+        .collapsible(false)
+        .show(context, |ui| {
+            for task in tasks {
+                ui.label(&task.label);
+                ui.add(egui::ProgressBar::new(task.fraction()).show_percentage());
+            }
+        });
+}
+
+/// Applies `Ctrl` with `+`, `-`, and `0` to `state.gui_scale`, then zooms the GUI by it.
+///
+/// These replace egui's own zoom shortcuts, so the scale stays within the `GuiScale` bounds
+/// and can be persisted. The zoom takes effect from the next frame.
+#[cfg(feature = "gui")]
+fn zoom_with_keyboard(context: &egui::Context, state: &mut UiState) {
+    use crate::settings::GuiScale;
+    use egui::gui_zoom::kb_shortcuts::{ZOOM_IN, ZOOM_IN_SECONDARY, ZOOM_OUT, ZOOM_RESET};
+
+    context.options_mut(|options| options.zoom_with_keyboard = false);
+    context.input_mut(|input| {
+        if input.consume_shortcut(&ZOOM_RESET) {
+            state.gui_scale = GuiScale::default();
+        }
+        if input.consume_shortcut(&ZOOM_IN) || input.consume_shortcut(&ZOOM_IN_SECONDARY) {
+            state.gui_scale = state.gui_scale.zoomed_in();
+        }
+        if input.consume_shortcut(&ZOOM_OUT) {
+            state.gui_scale = state.gui_scale.zoomed_out();
+        }
+    });
+    context.set_zoom_factor(state.gui_scale.get());
+}
+
+/// Adds the "GUI Scale" slider, which only offers scales within the `GuiScale` bounds.
+#[cfg(feature = "gui")]
+fn gui_scale_slider(ui: &mut egui::Ui, state: &mut UiState) {
+    use crate::settings::GuiScale;
+
+    let mut gui_scale = state.gui_scale.get();
+    let slider = egui::Slider::new(&mut gui_scale, GuiScale::MIN..=GuiScale::MAX)
+        .text("GUI Scale")
+        .step_by(GuiScale::STEP as f64);
+    if ui.add(slider).changed() {
+        state.gui_scale = GuiScale::new(gui_scale);
+    }
+}
+
+/// Fills the zoom items of the View menu, labeled with their keyboard shortcuts.
+#[cfg(feature = "gui")]
+fn zoom_menu_buttons(ui: &mut egui::Ui, state: &mut UiState) {
+    use crate::settings::GuiScale;
+    use egui::gui_zoom::kb_shortcuts::{ZOOM_IN, ZOOM_OUT, ZOOM_RESET};
+
+    let items = [
+        ("Zoom In", ZOOM_IN, state.gui_scale.zoomed_in()),
+        ("Zoom Out", ZOOM_OUT, state.gui_scale.zoomed_out()),
+        ("Reset Zoom", ZOOM_RESET, GuiScale::default()),
+    ];
+    for (label, shortcut, gui_scale) in items {
+        let button = egui::Button::new(label).shortcut_text(ui.ctx().format_shortcut(&shortcut));
+        if ui.add(button).clicked() {
+            state.gui_scale = gui_scale;
+        }
+    }
+}
+
+/// Reserves the `insets` along the window's edges with empty panels, and returns the safe
+/// rectangle inside them.
+#[cfg(feature = "gui")]
+fn reserve_safe_area(
+    context: &egui::Context,
+    insets: &crate::safe_area::SafeAreaInsets,
+) -> egui::Rect {
+    let empty = egui::Frame::NONE;
+    if insets.top > 0.0 {
+        egui::TopBottomPanel::top("safe_area_top")
+            .frame(empty)
+            .show_separator_line(false)
+            .resizable(false)
+            .exact_height(insets.top)
+            .show(context, |_| {});
+    }
+    if insets.bottom > 0.0 {
+        egui::TopBottomPanel::bottom("safe_area_bottom")
+            .frame(empty)
+            .show_separator_line(false)
+            .resizable(false)
+            .exact_height(insets.bottom)
+            .show(context, |_| {});
+    }
+    if insets.left > 0.0 {
+        egui::SidePanel::left("safe_area_left")
+            .frame(empty)
+            .show_separator_line(false)
+            .resizable(false)
+            .exact_width(insets.left)
+            .show(context, |_| {});
+    }
+    if insets.right > 0.0 {
+        egui::SidePanel::right("safe_area_right")
+            .frame(empty)
+            .show_separator_line(false)
+            .resizable(false)
+            .exact_width(insets.right)
+            .show(context, |_| {});
+    }
+
+    let screen = context.screen_rect();
+    egui::Rect::from_min_max(
+        screen.min + egui::vec2(insets.left, insets.top),
+        screen.max - egui::vec2(insets.right, insets.bottom),
+    )
+}
+
+/// Highlights the unsafe margins around `safe_rect` and outlines the safe region.
+#[cfg(feature = "gui")]
+fn show_safe_area_overlay(context: &egui::Context, safe_rect: egui::Rect) {
+    let painter = context.layer_painter(egui::LayerId::debug());
+    let screen = context.screen_rect();
+    let unsafe_color = egui::Color32::from_rgba_unmultiplied(255, 0, 0, 64);
+    for margin in [
+        egui::Rect::from_min_max(screen.min, egui::pos2(screen.max.x, safe_rect.min.y)),
+        egui::Rect::from_min_max(egui::pos2(screen.min.x, safe_rect.max.y), screen.max),
+        egui::Rect::from_x_y_ranges(screen.min.x..=safe_rect.min.x, safe_rect.y_range()),
+        egui::Rect::from_x_y_ranges(safe_rect.max.x..=screen.max.x, safe_rect.y_range()),
+    ] {
+        if margin.is_positive() {
+            painter.rect_filled(margin, 0.0, unsafe_color);
+        }
+    }
+    painter.rect_stroke(
+        safe_rect,
+        0.0,
+        egui::Stroke::new(1.0, egui::Color32::GREEN),
+        egui::StrokeKind::Inside,
+    );
+}
+
+/// Fills the Post FX window with a toggle for each effect applied to the scene: the bloom
+/// with its threshold and intensity, the tonemapping curve, gamma correction, and the
+/// vignette with its strength.
+#[cfg(feature = "gui")]
+fn post_effects_settings(ui: &mut egui::Ui, effects: &mut crate::post_process::PostEffects) {
+    ui.checkbox(&mut effects.bloom, "Bloom");
+    ui.add_enabled_ui(effects.bloom, |ui| {
+        ui.add(egui::Slider::new(&mut effects.bloom_threshold, 0.0..=4.0).text("Threshold"));
+        ui.add(egui::Slider::new(&mut effects.bloom_intensity, 0.0..=2.0).text("Intensity"));
+    });
+    egui::ComboBox::from_label("Tonemapping")
+        .selected_text(effects.tonemapping.label())
+        .show_ui(ui, |ui| {
+            for tonemapping in crate::post_process::Tonemapping::ALL {
+                ui.selectable_value(&mut effects.tonemapping, tonemapping, tonemapping.label());
+            }
+        });
+    ui.checkbox(&mut effects.gamma, "Gamma Correction");
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut effects.vignette, "Vignette");
+        ui.add_enabled(
+            effects.vignette,
+            egui::Slider::new(&mut effects.vignette_strength, 0.0..=1.0).text("Strength"),
+        );
+    });
+}
+
+/// Fills the Environment window: presets, the scene override, and every setting of the
+/// environment being edited.
+#[cfg(feature = "gui")]
+fn environment_settings(ui: &mut egui::Ui, state: &mut UiState) {
+    let mut scene_override = state.scene_environment.is_some();
+    if ui
+        .checkbox(&mut scene_override, "Override for Scene")
+        .changed()
+    {
+        // The override starts out as a copy of the global environment.
+        state.scene_environment = scene_override.then_some(state.environment);
+    }
+
+    let environment = state
+        .scene_environment
+        .as_mut()
+        .unwrap_or(&mut state.environment);
+
+    ui.horizontal(|ui| {
+        ui.label("Preset");
+        for preset in crate::environment::EnvironmentPreset::ALL {
+            if ui.button(preset.label()).clicked() {
+                *environment = preset.environment();
+            }
+        }
+    });
+    ui.separator();
+
+    ui.horizontal(|ui| {
+        ui.label("Ambient");
+        ui.color_edit_button_rgb(&mut environment.ambient_color);
+        ui.add(egui::Slider::new(&mut environment.ambient_intensity, 0.0..=2.0).text("Intensity"));
+    });
+    egui::ComboBox::from_label("Sky")
+        .selected_text(environment.sky.label())
+        .show_ui(ui, |ui| {
+            for sky in crate::environment::Sky::ALL {
+                ui.selectable_value(&mut environment.sky, sky, sky.label());
+            }
+        });
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut environment.fog.enabled, "Fog");
+        ui.color_edit_button_rgb(&mut environment.fog.color);
+        ui.add(
+            egui::Slider::new(&mut environment.fog.density, 0.0..=1.0)
+                .logarithmic(true)
+                .text("Density"),
+        );
+    });
+    ui.add(
+        egui::Slider::new(&mut environment.exposure, -4.0..=4.0)
+            .text("Exposure")
+            .suffix(" EV"),
+    );
+    ui.add(egui::Slider::new(&mut environment.ibl_strength, 0.0..=2.0).text("IBL Strength"));
+    ui.separator();
+
+    if ui.button("Copy as JSON").clicked() {
+        match serde_json::to_string_pretty(environment) {
+            Ok(json) => ui.ctx().copy_text(json),
+            Err(error) => log::warn!("Could not serialize the environment: {error}"),
+        }
+    }
+}
+
+/// Fills the "Input" section with the camera mode, the camera's input settings, and its
+/// projection.
+#[cfg(feature = "gui")]
+fn input_settings(ui: &mut egui::Ui, state: &mut UiState) {
+    egui::ComboBox::from_label("Camera")
+        .selected_text(state.camera_mode.label())
+        .show_ui(ui, |ui| {
+            for camera_mode in crate::camera::CameraMode::ALL {
+                ui.selectable_value(&mut state.camera_mode, camera_mode, camera_mode.label());
+            }
+        });
+
+    let settings = &mut state.camera_settings;
+    ui.add(egui::Slider::new(&mut settings.sensitivity_x, 0.05..=1.0).text("Sensitivity X"));
+    ui.add(egui::Slider::new(&mut settings.sensitivity_y, 0.05..=1.0).text("Sensitivity Y"));
+    ui.checkbox(&mut settings.invert_x, "Invert X");
+    ui.checkbox(&mut settings.invert_y, "Invert Y");
+    ui.add(
+        egui::Slider::new(&mut settings.smoothing, 0.0..=0.5)
+            .text("Smoothing")
+            .suffix(" s"),
+    );
+
+    ui.separator();
+    let projection = &mut state.projection;
+    let (min_fov, max_fov) = crate::camera::Projection::FIELD_OF_VIEW_RANGE;
+    ui.add(
+        egui::Slider::new(&mut projection.field_of_view, min_fov..=max_fov)
+            .text("Field of View")
+            .suffix("°"),
+    );
+    ui.checkbox(&mut projection.auto_clip, "Fit Clip Planes to Scene");
+    ui.add_enabled_ui(!projection.auto_clip, |ui| {
+        // Typed values are not clamped here, so the far plane can be entered before the near
+        // plane; the renderer sanitizes both before use.
+        ui.horizontal(|ui| {
+            ui.label("Near");
+            ui.add(egui::DragValue::new(&mut projection.near).speed(0.01));
+            ui.label("Far");
+            ui.add(egui::DragValue::new(&mut projection.far).speed(1.0));
+        });
+    });
+    if ui.button("Frame Scene").clicked() {
+        state.frame_scene_requested = true;
+    }
+
+    // High-rate mice report many cursor moves per frame, of which only the last is handled.
+    let pointer_events = state.pointer_events;
+    ui.label(format!(
+        "Pointer Events: {} per frame, {} handled",
+        pointer_events.received, pointer_events.handled
+    ));
+}
+
+/// Fills the "Key Bindings" section with a button per action showing its binding. Clicking a
+/// button waits for the next key or mouse button, and clicking it again cancels.
+#[cfg(feature = "gui")]
+fn key_bindings(ui: &mut egui::Ui, state: &mut UiState) {
+    egui::Grid::new("key_bindings")
+        .num_columns(2)
+        .show(ui, |ui| {
+            for action in crate::actions::Action::ALL {
+                ui.label(action.label());
+                let waiting = state.rebinding == Some(action);
+                let text = if waiting {
+                    "Press a key…".to_string()
+                } else {
+                    state.action_map.binding(action).label()
+                };
+                if ui.selectable_label(waiting, text).clicked() {
+                    state.rebinding = (!waiting).then_some(action);
+                }
+                ui.end_row();
+            }
+        });
+    if ui.button("Reset to Defaults").clicked() {
+        state.action_map = crate::actions::ActionMap::default();
+        state.rebinding = None;
+    }
+}
+
+/// Fills the "Gestures" section with the gesture thresholds and the last recognized gesture.
+#[cfg(feature = "gui")]
+fn gesture_settings(ui: &mut egui::Ui, state: &mut UiState) {
+    ui.label(format!(
+        "Last Gesture: {}",
+        state.last_gesture.unwrap_or("None")
+    ));
+
+    let settings = &mut state.gesture_settings;
+    ui.add(
+        egui::Slider::new(&mut settings.tap_slop, 2.0..=40.0)
+            .text("Tap Slop")
+            .suffix(" px"),
+    );
+    ui.add(
+        egui::Slider::new(&mut settings.tap_duration, 0.1..=1.0)
+            .text("Tap Duration")
+            .suffix(" s"),
+    );
+    ui.add(
+        egui::Slider::new(&mut settings.double_tap_interval, 0.1..=1.0)
+            .text("Double Tap Interval")
+            .suffix(" s"),
+    );
+    ui.add(
+        egui::Slider::new(&mut settings.long_press_duration, 0.2..=2.0)
+            .text("Long Press")
+            .suffix(" s"),
+    );
+    ui.add(
+        egui::Slider::new(&mut settings.pan_threshold, 0.0..=50.0)
+            .text("Pan Threshold")
+            .suffix(" px"),
+    );
+    ui.add(egui::Slider::new(&mut settings.pinch_threshold, 0.0..=0.5).text("Pinch Threshold"));
+    ui.add(
+        egui::Slider::new(&mut settings.rotate_threshold, 0.0..=45.0)
+            .text("Rotate Threshold")
+            .suffix("°"),
+    );
+}
+
+/// Fills the "Dolly Zoom" section with the demo's toggle and the range and duration of its
+/// field of view animation.
+#[cfg(feature = "gui")]
+fn dolly_zoom_settings(ui: &mut egui::Ui, settings: &mut crate::dolly_zoom::DollyZoom) {
+    ui.checkbox(&mut settings.enabled, "Animate");
+    let (min_fov, max_fov) = crate::camera::Projection::FIELD_OF_VIEW_RANGE;
+    ui.add(
+        egui::Slider::new(&mut settings.start_field_of_view, min_fov..=max_fov)
+            .text("Start FOV")
+            .suffix("°"),
+    );
+    ui.add(
+        egui::Slider::new(&mut settings.end_field_of_view, min_fov..=max_fov)
+            .text("End FOV")
+            .suffix("°"),
+    );
+    let (min_duration, max_duration) = crate::dolly_zoom::DollyZoom::DURATION_RANGE;
+    ui.add(
+        egui::Slider::new(&mut settings.duration, min_duration..=max_duration)
+            .text("Duration")
+            .suffix(" s"),
+    );
+}
+
+/// Fills the "Flythrough" section with the recording and playback toggles, the time slider
+/// scrubbing the path while paused, and the buttons editing, exporting, and importing it.
+#[cfg(feature = "gui")]
+fn flythrough_settings(ui: &mut egui::Ui, controls: &mut crate::camera_path::FlythroughControls) {
+    ui.label(format!(
+        "{} keyframes, {:.1} s",
+        controls.keyframes, controls.duration
+    ));
+    ui.horizontal(|ui| {
+        ui.toggle_value(&mut controls.recording, "Record");
+        ui.add_enabled_ui(controls.keyframes >= 2, |ui| {
+            ui.toggle_value(&mut controls.playing, "Play");
+        });
+    });
+    ui.checkbox(&mut controls.looping, "Loop");
+    ui.checkbox(&mut controls.fixed_step, "Fixed Step")
+        .on_hover_text("Advances 1/60 s per frame, so every run renders the same frames");
+    ui.add_enabled(
+        !controls.recording,
+        egui::Slider::new(&mut controls.time, 0.0..=controls.duration)
+            .text("Time")
+            .suffix(" s"),
+    );
+    ui.horizontal(|ui| {
+        if ui.button("Add Keyframe").clicked() {
+            controls.add_keyframe_requested = true;
+        }
+        if ui.button("Clear").clicked() {
+            controls.clear_requested = true;
+        }
+    });
+    ui.horizontal(|ui| {
+        if ui.button("Export Path").clicked() {
+            controls.export_requested = true;
+        }
+        if ui.button("Import Path").clicked() {
+            controls.import_requested = true;
+        }
+    });
+}
+
+/// Fills the "Adaptive Quality" section with the scaler's toggle and target frame rate, and a
+/// row per setting with its bounds and current `levels`.
+#[cfg(feature = "gui")]
+fn adaptive_quality_settings(
+    ui: &mut egui::Ui,
+    settings: &mut crate::quality::AdaptiveQuality,
+    levels: &crate::quality::QualityLevels,
+) {
+    use crate::quality::QualitySetting;
+
+    ui.checkbox(&mut settings.enabled, "Scale Quality");
+    let (min_fps, max_fps) = crate::quality::AdaptiveQuality::TARGET_FPS_RANGE;
+    ui.add(
+        egui::Slider::new(&mut settings.target_fps, min_fps..=max_fps)
+            .text("Target")
+            .suffix(" FPS"),
+    );
+
+    egui::Grid::new("quality_bounds").show(ui, |ui| {
+        for label in ["", "Min", "Max", "Level"] {
+            ui.label(label);
+        }
+        ui.end_row();
+
+        for setting in QualitySetting::ALL {
+            let bounds = &mut settings.bounds[setting as usize];
+            ui.label(setting.label());
+            ui.add(egui::DragValue::new(&mut bounds.min).range(0..=bounds.max));
+            ui.add(
+                egui::DragValue::new(&mut bounds.max).range(bounds.min..=QualitySetting::MAX_LEVEL),
+            );
+            ui.label(levels.get(setting).to_string());
+            ui.end_row();
+        }
+    });
+}
+
+/// Fills the "Fullscreen" section with the fullscreen toggle, the mode, and the video mode of
+/// exclusive fullscreen.
+#[cfg(feature = "gui")]
+fn fullscreen_settings(ui: &mut egui::Ui, controls: &mut crate::fullscreen::FullscreenControls) {
+    use crate::fullscreen::FullscreenMode;
+
+    ui.checkbox(&mut controls.enabled, "Fullscreen")
+        .on_hover_text("Also toggled with F11 or Alt+Enter");
+    ui.horizontal(|ui| {
+        for mode in FullscreenMode::ALL {
+            let supported = mode == FullscreenMode::Borderless || controls.exclusive_supported;
+            ui.add_enabled_ui(supported, |ui| {
+                ui.radio_value(&mut controls.mode, mode, mode.label());
+            });
+        }
+    });
+    ui.add_enabled_ui(controls.mode == FullscreenMode::Exclusive, |ui| {
+        let selected = controls
+            .video_mode
+            .map_or_else(|| "Best".to_owned(), |mode| mode.label());
+        egui::ComboBox::from_label("Video Mode")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut controls.video_mode, None, "Best");
+                for mode in &controls.video_modes {
+                    ui.selectable_value(&mut controls.video_mode, Some(*mode), mode.label());
+                }
+            });
+    });
+}
+
+/// Shows the Widget Gallery window, whose widgets are all bound to the live renderer state in
+/// `state`. See the `gallery` module for how each value reaches the GPU.
+#[cfg(feature = "gui")]
+fn show_widget_gallery(context: &egui::Context, safe_rect: egui::Rect, state: &mut UiState) {
+    let mut visible = state.gallery.visible;
+    egui::Window::new("Widget Gallery")
+        .open(&mut visible)
+        .constrain_to(safe_rect)
+        .default_width(320.0)
+        .show(context, |ui| {
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::CollapsingHeader::new("Plot")
+                    .default_open(true)
+                    .show(ui, |ui| frame_time_plot(ui, state));
+                egui::CollapsingHeader::new("Tables").show(ui, |ui| gallery_tables(ui, state));
+                egui::CollapsingHeader::new("Drag Values").show(ui, |ui| {
+                    gallery_drag_values(ui, state);
+                });
+                egui::CollapsingHeader::new("Color Pickers").show(ui, |ui| {
+                    gallery_color_pickers(ui, state);
+                });
+                egui::CollapsingHeader::new("Images").show(ui, |ui| gallery_images(ui, state));
+                egui::CollapsingHeader::new("Selection").show(ui, |ui| {
+                    gallery_selection(ui, state);
+                });
+            });
+        });
+    state.gallery.visible = visible;
+}
+
+/// Paints the recent frame times as a line over a grid, with the adaptive quality target as a
+/// horizontal line, and a checkbox pausing the recording.
+#[cfg(feature = "gui")]
+fn frame_time_plot(ui: &mut egui::Ui, state: &mut UiState) {
+    let gallery = &mut state.gallery;
+    let target = state.adaptive_quality.target_frame_time().as_secs_f32() * 1000.0;
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut gallery.paused, "Pause");
+        if let Some((mean, max)) = gallery.frame_time_summary() {
+            ui.label(format!("Mean {mean:.2} ms, max {max:.2} ms"));
+        }
+    });
+
+    let (response, painter) = ui.allocate_painter(
+        egui::vec2(ui.available_width(), 120.0),
+        egui::Sense::hover(),
+    );
+    let rect = response.rect;
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+
+    let max_sample = gallery.frame_times().fold(0.0, f32::max);
+    let (ceiling, step) = crate::gallery::plot_axis(max_sample, target);
+    let y = |milliseconds: f32| rect.bottom() - milliseconds / ceiling * rect.height();
+    let grid_stroke = egui::Stroke::new(1.0, visuals.weak_text_color().gamma_multiply(0.3));
+    for index in crate::rulers::tick_indices(step, ceiling - step * 0.5, step) {
+        let milliseconds = index as f32 * step;
+        painter.hline(rect.x_range(), y(milliseconds), grid_stroke);
+        painter.text(
+            egui::pos2(rect.left() + 2.0, y(milliseconds)),
+            egui::Align2::LEFT_BOTTOM,
+            format!("{milliseconds} ms"),
+            egui::FontId::proportional(10.0),
+            visuals.weak_text_color(),
+        );
+    }
+    painter.hline(
+        rect.x_range(),
+        y(target),
+        egui::Stroke::new(1.0, egui::Color32::from_rgb(220, 160, 40)),
+    );
+
+    let x_step = rect.width() / (crate::gallery::WidgetGallery::PLOT_LENGTH - 1) as f32;
+    let points: Vec<egui::Pos2> = gallery
+        .frame_times()
+        .enumerate()
+        .map(|(index, milliseconds)| {
+            egui::pos2(rect.left() + index as f32 * x_step, y(milliseconds))
+        })
+        .collect();
+    painter.add(egui::Shape::line(
+        points,
+        egui::Stroke::new(1.5, visuals.selection.bg_fill),
+    ));
+}
+
+/// Lists the draw statistics of the scene pass and the quality levels in striped grids.
+#[cfg(feature = "gui")]
+fn gallery_tables(ui: &mut egui::Ui, state: &mut UiState) {
+    let stats = &state.draw_stats;
+    egui::Grid::new("gallery_draw_stats")
+        .striped(true)
+        .num_columns(3)
+        .show(ui, |ui| {
+            for label in ["", "Sorted", "Queued"] {
+                ui.strong(label);
+            }
+            ui.end_row();
+            let rows = [
+                ("Draws", stats.draws, stats.draws),
+                (
+                    "Pipeline Switches",
+                    stats.pipeline_switches,
+                    stats.unsorted_pipeline_switches,
+                ),
+                (
+                    "Bind Group Switches",
+                    stats.bind_group_switches,
+                    stats.unsorted_bind_group_switches,
+                ),
+            ];
+            for (label, sorted, queued) in rows {
+                ui.label(label);
+                ui.label(sorted.to_string());
+                ui.label(queued.to_string());
+                ui.end_row();
+            }
+        });
+
+    ui.separator();
+    egui::Grid::new("gallery_quality_levels")
+        .striped(true)
+        .num_columns(2)
+        .show(ui, |ui| {
+            for setting in crate::quality::QualitySetting::ALL {
+                let level = state.quality_levels.get(setting);
+                ui.label(setting.label());
+                ui.add(
+                    egui::ProgressBar::new(
+                        level as f32 / crate::quality::QualitySetting::MAX_LEVEL as f32,
+                    )
+                    .text(level.to_string()),
+                );
+                ui.end_row();
+            }
+        });
+}
+
+/// Edits the projection, the exposure, and the fog density with drag values.
+#[cfg(feature = "gui")]
+fn gallery_drag_values(ui: &mut egui::Ui, state: &mut UiState) {
+    let (min_fov, max_fov) = crate::camera::Projection::FIELD_OF_VIEW_RANGE;
+    let projection = &mut state.projection;
+    egui::Grid::new("gallery_drag_values")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Field of View");
+            ui.add(
+                egui::DragValue::new(&mut projection.field_of_view)
+                    .range(min_fov..=max_fov)
+                    .suffix("°"),
+            );
+            ui.end_row();
+
+            ui.label("Clip Planes");
+            ui.add_enabled_ui(!projection.auto_clip, |ui| {
+                ui.add(
+                    egui::DragValue::new(&mut projection.near)
+                        .speed(0.01)
+                        .prefix("near "),
+                );
+                ui.add(egui::DragValue::new(&mut projection.far).prefix("far "));
+            });
+            ui.end_row();
+
+            let environment = &mut state.environment;
+            ui.label("Exposure");
+            ui.add(
+                egui::DragValue::new(&mut environment.exposure)
+                    .speed(0.05)
+                    .range(-4.0..=4.0)
+                    .suffix(" EV"),
+            );
+            ui.end_row();
+
+            ui.label("Fog Density");
+            ui.add(
+                egui::DragValue::new(&mut environment.fog.density)
+                    .speed(0.005)
+                    .range(0.0..=1.0),
+            );
+            ui.end_row();
+        });
+}
+
+/// Edits the ambient and fog colors of the environment and the selected material's base
+/// color.
+#[cfg(feature = "gui")]
+fn gallery_color_pickers(ui: &mut egui::Ui, state: &mut UiState) {
+    egui::Grid::new("gallery_color_pickers")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Ambient");
+            ui.color_edit_button_rgb(&mut state.environment.ambient_color);
+            ui.end_row();
+
+            ui.label("Fog");
+            ui.color_edit_button_rgb(&mut state.environment.fog.color);
+            ui.end_row();
+
+            ui.label("Material");
+            ui.color_edit_button_rgb(&mut state.materials.selected_mut().base_color);
+            ui.end_row();
+        });
+}
+
+/// Shows the material and mesh previews, `wgpu` textures registered with the `egui` renderer.
+#[cfg(feature = "gui")]
+fn gallery_images(ui: &mut egui::Ui, state: &mut UiState) {
+    let Some(material_preview) = state.material_preview else {
+        ui.label("The previews are rendered on the next frame.");
+        return;
+    };
+    let size = egui::vec2(64.0, 64.0);
+    ui.horizontal_wrapped(|ui| {
+        ui.image((material_preview, size)).on_hover_text("Material");
+        for &(name, texture) in &state.mesh_previews {
+            ui.image((texture, size)).on_hover_text(name);
+        }
+    });
+    let material = state.materials.selected_mut();
+    ui.add(egui::Slider::new(&mut material.roughness, 0.0..=1.0).text("Roughness"));
+}
+
+/// Selects the debug view with a combo box, the camera with radio buttons, and toggles the
+/// scene's helpers with checkboxes.
+#[cfg(feature = "gui")]
+fn gallery_selection(ui: &mut egui::Ui, state: &mut UiState) {
+    egui::ComboBox::from_label("Debug View")
+        .selected_text(state.debug_view.label())
+        .show_ui(ui, |ui| {
+            for debug_view in crate::debug_view::DebugView::ALL {
+                ui.selectable_value(&mut state.debug_view, debug_view, debug_view.label());
+            }
+        });
+    ui.horizontal(|ui| {
+        for camera_mode in crate::camera::CameraMode::ALL {
+            ui.radio_value(&mut state.camera_mode, camera_mode, camera_mode.label());
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.toggle_value(&mut state.grid_visible, "Grid");
+        ui.toggle_value(&mut state.draw_stats_visible, "Draw Statistics");
+    });
+}
+
+/// Draws the depth probe readout for `sample` next to `cursor`, in points.
+///
+/// The readout lists the object under the cursor, its depth value, and its world position.
+/// It ignores pointer input, so it never gets in the way of the widgets underneath.
+#[cfg(feature = "gui")]
+pub fn show_depth_probe(
+    context: &egui::Context,
+    cursor: egui::Pos2,
+    sample: &crate::probe::DepthProbeSample,
+) {
+    egui::Area::new(egui::Id::new("depth_probe"))
+        .order(egui::Order::Tooltip)
+        .fixed_pos(cursor + egui::vec2(16.0, 16.0))
+        .interactable(false)
+        .show(context, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(format!("Object: {}", sample.object_name()));
+                ui.label(match sample.depth {
+                    Some(depth) => format!("Depth: {depth:.6}"),
+                    None => "Depth: unavailable".to_string(),
+                });
+                ui.label(match sample.world_position {
+                    Some(position) => format!(
+                        "World: ({:.3}, {:.3}, {:.3})",
+                        position.x, position.y, position.z
+                    ),
+                    None => "World: -".to_string(),
+                });
+            });
+        });
+}
+
+/// Draws a title bar with `title` and the minimize, maximize, and close buttons along the top
+/// of the window, and returns the action the user chose this frame, if any.
+///
+/// The maximize button shows the restore symbol while the window is `maximized`. Pressing and
+/// dragging the bar outside the buttons returns `TitlebarAction::Drag`, and double-clicking it
+/// `TitlebarAction::ToggleMaximize`.
+///
+/// This must be called before `show`, so the bar spans the full width above every panel.
+#[cfg(feature = "gui")]
+pub fn show_titlebar(
+    context: &egui::Context,
+    title: &str,
+    maximized: bool,
+) -> Option<crate::titlebar::TitlebarAction> {
+    use crate::titlebar::{TitlebarAction, TITLEBAR_HEIGHT};
+
+    let mut action = None;
+    egui::TopBottomPanel::top("titlebar")
+        .exact_height(TITLEBAR_HEIGHT)
+        .resizable(false)
+        .show(context, |ui| {
+            // The bar is interacted with first, so the buttons added over it take precedence.
+            let rect = ui.max_rect();
+            let bar = ui.interact(
+                rect,
+                egui::Id::new("titlebar"),
+                egui::Sense::click_and_drag(),
+            );
+            if bar.double_clicked() {
+                action = Some(TitlebarAction::ToggleMaximize);
+            } else if bar.drag_started_by(egui::PointerButton::Primary) {
+                action = Some(TitlebarAction::Drag);
+            }
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                title,
+                egui::FontId::proportional(TITLEBAR_HEIGHT * 0.45),
+                ui.style().visuals.text_color(),
+            );
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let (maximize, maximize_hint) = if maximized {
+                    ("🗗", "Restore")
+                } else {
+                    ("🗖", "Maximize")
+                };
+                let buttons = [
+                    ("🗙", "Close", TitlebarAction::Close),
+                    (maximize, maximize_hint, TitlebarAction::ToggleMaximize),
+                    ("🗕", "Minimize", TitlebarAction::Minimize),
+                ];
+                for (symbol, hint, button_action) in buttons {
+                    let button =
+                        egui::Button::new(egui::RichText::new(symbol).size(14.0)).frame(false);
+                    if ui.add(button).on_hover_text(hint).clicked() {
+                        action = Some(button_action);
+                    }
+                }
+            });
+        });
+    action
+}
+
+/// Shows the draw statistics of the scene pass in the top right corner of `viewport`: the
+/// number of draws, the pipeline and bind group switches with the sorted order next to the
+/// queued order, and the meshes drawn and culled against the view frustum.
+#[cfg(feature = "gui")]
+pub fn show_draw_stats(
+    context: &egui::Context,
+    viewport: &crate::viewport::Viewport,
+    stats: &crate::render_queue::DrawStats,
+) {
+    let pixels_per_point = context.pixels_per_point();
+    let corner =
+        egui::pos2((viewport.x + viewport.width) as f32, viewport.y as f32) / pixels_per_point;
+    egui::Area::new(egui::Id::new("draw_stats"))
+        .order(egui::Order::Foreground)
+        .pivot(egui::Align2::RIGHT_TOP)
+        .fixed_pos(corner + egui::vec2(-8.0, 8.0))
+        .interactable(false)
+        .show(context, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(format!("Draws: {}", stats.draws));
+                ui.label(format!(
+                    "Pipeline Switches: {} (unsorted {})",
+                    stats.pipeline_switches, stats.unsorted_pipeline_switches
+                ));
+                ui.label(format!(
+                    "Bind Group Switches: {} (unsorted {})",
+                    stats.bind_group_switches, stats.unsorted_bind_group_switches
+                ));
+                ui.label(format!(
+                    "Meshes: {} drawn, {} culled",
+                    stats.meshes_drawn, stats.meshes_culled
+                ));
+            });
+        });
+}
+
+/// Paints the bars around `viewport` on a surface of `size` physical pixels.
+///
+/// The bars are painted behind every window and panel, so the GUI stays usable on top of them.
+#[cfg(feature = "gui")]
+pub fn show_letterbox(
+    context: &egui::Context,
+    viewport: &crate::viewport::Viewport,
+    size: (u32, u32),
+) {
+    let painter = context.layer_painter(egui::LayerId::background());
+    let pixels_per_point = context.pixels_per_point();
+    for (x, y, width, height) in viewport.bars(size) {
+        let rect = egui::Rect::from_min_size(
+            egui::pos2(x as f32, y as f32) / pixels_per_point,
+            egui::vec2(width as f32, height as f32) / pixels_per_point,
+        );
+        painter.rect_filled(rect, 0.0, egui::Color32::BLACK);
+    }
+}
+
+/// The thickness of the rulers, in points.
+#[cfg(feature = "gui")]
+const RULER_THICKNESS: f32 = 20.0;
+
+/// The smallest distance between labeled ruler ticks, in points.
+#[cfg(feature = "gui")]
+const RULER_LABEL_SPACING: f32 = 60.0;
+
+/// Paints the rulers along the edges of `viewport` and the guide lines across it.
+///
+/// The top and left rulers count physical pixels from the viewport's corner; the bottom and
+/// right rulers count world units at the focus plane from its center, one pixel spanning
+/// `world_units_per_pixel`. Dragging from a ruler adds a guide, dragging a guide moves it, and
+/// dropping it on a ruler or outside the viewport removes it.
+#[cfg(feature = "gui")]
+pub fn show_rulers(
+    context: &egui::Context,
+    state: &mut UiState,
+    viewport: &crate::viewport::Viewport,
+    world_units_per_pixel: f32,
+) {
+    use crate::rulers::{Guide, GuideAxis};
+
+    let pixels_per_point = context.pixels_per_point();
+    let rect = egui::Rect::from_min_size(
+        egui::pos2(viewport.x as f32, viewport.y as f32) / pixels_per_point,
+        egui::vec2(viewport.width as f32, viewport.height as f32) / pixels_per_point,
+    );
+    // Guides dropped outside this rectangle are removed.
+    let inner = if state.rulers_visible {
+        rect.shrink(RULER_THICKNESS)
+    } else {
+        rect
+    };
+    let position = |axis: GuideAxis, pointer: egui::Pos2| match axis {
+        GuideAxis::Horizontal => (pointer.y - rect.min.y) / rect.height(),
+        GuideAxis::Vertical => (pointer.x - rect.min.x) / rect.width(),
+    };
+    let pointer = context.pointer_interact_pos();
+
+    if state.rulers_visible {
+        let pixel_points = 1.0 / pixels_per_point;
+        let world_points = 1.0 / (world_units_per_pixel * pixels_per_point);
+        let thickness = egui::vec2(RULER_THICKNESS, RULER_THICKNESS);
+        let rulers = [
+            (
+                "ruler_top",
+                egui::Rect::from_min_max(rect.min, egui::pos2(rect.max.x, rect.min.y) + thickness),
+                GuideAxis::Horizontal,
+                rect.min.x,
+                pixel_points,
+            ),
+            (
+                "ruler_left",
+                egui::Rect::from_min_max(rect.min, egui::pos2(rect.min.x, rect.max.y) + thickness),
+                GuideAxis::Vertical,
+                rect.min.y,
+                pixel_points,
+            ),
+            (
+                "ruler_bottom",
+                egui::Rect::from_min_max(egui::pos2(rect.min.x, rect.max.y) - thickness, rect.max),
+                GuideAxis::Horizontal,
+                rect.center().x,
+                world_points,
+            ),
+            (
+                "ruler_right",
+                egui::Rect::from_min_max(egui::pos2(rect.max.x, rect.min.y) - thickness, rect.max),
+                GuideAxis::Vertical,
+                rect.center().y,
+                // World units count upwards.
+                -world_points,
+            ),
+        ];
+        for (id, strip, axis, origin, points_per_unit) in rulers {
+            let response = overlay_strip(context, egui::Id::new(id), strip, |painter, strip| {
+                paint_ruler(painter, strip, axis, origin, points_per_unit);
+            });
+            // A horizontal ruler measures along X, and drags out horizontal guides.
+            if response.drag_started() {
+                state.guides.push(Guide {
+                    axis,
+                    position: 0.0,
+                });
+                state.guides_visible = true;
+            }
+            if let (true, Some(guide), Some(pointer)) =
+                (response.dragged(), state.guides.last_mut(), pointer)
+            {
+                guide.position = position(axis, pointer);
+            }
+            if response.drag_stopped() && !pointer.is_some_and(|pointer| inner.contains(pointer)) {
+                state.guides.pop();
+            }
+        }
+    }
+
+    if !state.guides_visible {
+        return;
+    }
+    let mut removed = None;
+    for (index, guide) in state.guides.iter_mut().enumerate() {
+        let line = match guide.axis {
+            GuideAxis::Horizontal => egui::Rect::from_x_y_ranges(
+                rect.x_range(),
+                egui::Rangef::point(rect.min.y + guide.position * rect.height()).expand(3.0),
+            ),
+            GuideAxis::Vertical => egui::Rect::from_x_y_ranges(
+                egui::Rangef::point(rect.min.x + guide.position * rect.width()).expand(3.0),
+                rect.y_range(),
+            ),
+        };
+        let id = egui::Id::new(("guide", index));
+        let response = overlay_strip(context, id, line, |painter, line| {
+            let (start, end) = match guide.axis {
+                GuideAxis::Horizontal => (line.left_center(), line.right_center()),
+                GuideAxis::Vertical => (line.center_top(), line.center_bottom()),
+            };
+            let stroke = egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE);
+            painter.line_segment([start, end], stroke);
+        });
+        let response = response.on_hover_cursor(match guide.axis {
+            GuideAxis::Horizontal => egui::CursorIcon::ResizeVertical,
+            GuideAxis::Vertical => egui::CursorIcon::ResizeHorizontal,
+        });
+        if let (true, Some(pointer)) = (response.dragged(), pointer) {
+            guide.position = position(guide.axis, pointer);
+        }
+        if response.drag_stopped() && !pointer.is_some_and(|pointer| inner.contains(pointer)) {
+            removed = Some(index);
+        }
+    }
+    if let Some(index) = removed {
+        state.guides.remove(index);
+    }
+}
+
+/// Shows a draggable strip at `rect` on the 2D overlay, behind every window, and paints it.
+#[cfg(feature = "gui")]
+fn overlay_strip(
+    context: &egui::Context,
+    id: egui::Id,
+    rect: egui::Rect,
+    paint: impl FnOnce(&egui::Painter, egui::Rect),
+) -> egui::Response {
+    egui::Area::new(id)
+        .order(egui::Order::Background)
+        .fixed_pos(rect.min)
+        .show(context, |ui| {
+            let (rect, response) = ui.allocate_exact_size(rect.size(), egui::Sense::drag());
+            paint(ui.painter(), rect);
+            response
+        })
+        .inner
+}
+
+/// Paints a ruler into `strip`, with labeled ticks counting units from `origin`. One unit spans
+/// `points_per_unit` points; negative values count against the axis.
+///
+/// Horizontal rulers measure along X, vertical rulers along Y.
+#[cfg(feature = "gui")]
+fn paint_ruler(
+    painter: &egui::Painter,
+    strip: egui::Rect,
+    axis: crate::rulers::GuideAxis,
+    origin: f32,
+    points_per_unit: f32,
+) {
+    use crate::rulers::{tick_decimals, tick_indices, tick_step, GuideAxis};
+
+    painter.rect_filled(strip, 0.0, egui::Color32::from_black_alpha(160));
+    let step = tick_step(1.0 / points_per_unit.abs(), RULER_LABEL_SPACING);
+    let minor_step = step / 5.0;
+    let decimals = tick_decimals(step);
+    let (along, across) = match axis {
+        GuideAxis::Horizontal => (strip.x_range(), strip.y_range()),
+        GuideAxis::Vertical => (strip.y_range(), strip.x_range()),
+    };
+    let to_unit = |point: f32| (point - origin) / points_per_unit;
+    let stroke = egui::Stroke::new(1.0, egui::Color32::LIGHT_GRAY);
+
+    for index in tick_indices(to_unit(along.min), to_unit(along.max), minor_step) {
+        let value = index as f32 * minor_step;
+        let point = origin + value * points_per_unit;
+        let major = index % 5 == 0;
+        let length = across.span() * if major { 1.0 } else { 0.3 };
+        let (start, end) = match axis {
+            GuideAxis::Horizontal => (
+                egui::pos2(point, across.min),
+                egui::pos2(point, across.min + length),
+            ),
+            GuideAxis::Vertical => (
+                egui::pos2(across.min, point),
+                egui::pos2(across.min + length, point),
+            ),
+        };
+        painter.line_segment([start, end], stroke);
+        if major {
+            painter.text(
+                start + egui::vec2(2.0, 1.0),
+                egui::Align2::LEFT_TOP,
+                format!("{value:.decimals$}"),
+                egui::FontId::monospace(8.0),
+                egui::Color32::WHITE,
+            );
+        }
+    }
+}
+
+/// Fills a side or bottom panel with its `heading` and a button recording clicks in `state`.
+#[cfg(feature = "gui")]
+fn panel_contents(ui: &mut egui::Ui, heading: &'static str, state: &mut UiState) {
+    ui.heading(heading);
+    if ui.button("Click me!").clicked() {
+        log::info!("Button clicked in {heading}!");
+        state.last_clicked = Some(heading);
+    }
+}
+
+/// Fills the Inspector panel's material section with a selection of the scene's materials,
+/// and the selected material's preview and settings.
+#[cfg(feature = "gui")]
+fn material_inspector(ui: &mut egui::Ui, state: &mut UiState) {
+    ui.label("Material");
+    if let Some(texture) = state.material_preview {
+        let size = crate::preview::PreviewRenderer::SIZE as f32;
+        ui.image((texture, egui::vec2(size, size)));
+    }
+
+    let materials = &mut state.materials;
+    let count = materials.materials().len();
+    egui::ComboBox::from_label("Edited")
+        .selected_text(format!("Material {}", materials.selected.min(count - 1)))
+        .show_ui(ui, |ui| {
+            for index in 0..count {
+                ui.selectable_value(&mut materials.selected, index, format!("Material {index}"));
+            }
+        });
+
+    let material = materials.selected_mut();
+    ui.horizontal(|ui| {
+        ui.label("Base Color");
+        ui.color_edit_button_rgb(&mut material.base_color);
+    });
+    ui.add(egui::Slider::new(&mut material.metallic, 0.0..=1.0).text("Metallic"));
+    ui.add(egui::Slider::new(&mut material.roughness, 0.0..=1.0).text("Roughness"));
+    ui.horizontal(|ui| {
+        ui.label("Emissive");
+        ui.color_edit_button_rgb(&mut material.emissive);
+    });
+}
+
+/// Fills the Inspector panel's light section with the direction of the scene's light, as an
+/// azimuth and an elevation, its color and intensity, and the strength of the ambient light.
+#[cfg(feature = "gui")]
+fn light_inspector(ui: &mut egui::Ui, light: &mut crate::lighting::Light) {
+    ui.label("Light");
+    ui.add(
+        egui::Slider::new(&mut light.azimuth, -180.0..=180.0)
+            .text("Azimuth")
+            .suffix("°"),
+    );
+    ui.add(
+        egui::Slider::new(&mut light.elevation, -90.0..=90.0)
+            .text("Elevation")
+            .suffix("°"),
+    );
+    ui.horizontal(|ui| {
+        ui.label("Color");
+        ui.color_edit_button_rgb(&mut light.color);
+        ui.add(egui::Slider::new(&mut light.intensity, 0.0..=2.0).text("Intensity"));
+    });
+    ui.add(egui::Slider::new(&mut light.ambient, 0.0..=1.0).text("Ambient"));
+}
+
+/// Fills the Inspector panel's gizmo section with the gizmo's mode and the snapping steps.
+#[cfg(feature = "gui")]
+fn gizmo_inspector(ui: &mut egui::Ui, state: &mut UiState) {
+    use crate::gizmo::GizmoMode;
+
+    ui.horizontal(|ui| {
+        ui.label("Gizmo");
+        for mode in GizmoMode::ALL {
+            ui.selectable_value(&mut state.gizmo_mode, mode, mode.label());
+        }
+    });
+    let snapping = &mut state.gizmo_snapping;
+    ui.checkbox(&mut snapping.enabled, "Snap")
+        .on_hover_text("Round gizmo drags to the steps below");
+    ui.add_enabled_ui(snapping.enabled, |ui| {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut snapping.translation)
+                    .range(0.01..=10.0)
+                    .speed(0.01)
+                    .prefix("Move "),
+            );
+            ui.add(
+                egui::DragValue::new(&mut snapping.rotation_degrees)
+                    .range(1.0..=90.0)
+                    .speed(1.0)
+                    .prefix("Rotate ")
+                    .suffix("°"),
+            );
+            ui.add(
+                egui::DragValue::new(&mut snapping.scale)
+                    .range(0.01..=1.0)
+                    .speed(0.01)
+                    .prefix("Scale "),
+            );
+        });
+    });
+}
+
+/// Fills the Inspector panel's rendering section with the background color, the render mode,
+/// the depth test toggle, and the present mode. Render modes the renderer does not support, such as
+/// wireframes on WebGL, and present modes the surface does not support are shown disabled.
+#[cfg(feature = "gui")]
+fn rendering_inspector(ui: &mut egui::Ui, state: &mut UiState) {
+    use crate::redraw::RedrawPolicy;
+    use crate::scene::RenderMode;
+
+    ui.label("Rendering");
+    ui.horizontal(|ui| {
+        ui.label("Background");
+        ui.color_edit_button_rgba_unmultiplied(&mut state.clear_color)
+            .on_hover_text("The alpha only applies to transparent windows");
+    });
+    egui::ComboBox::from_label("Render Mode")
+        .selected_text(state.rasterization.mode.label())
+        .show_ui(ui, |ui| {
+            for mode in RenderMode::ALL {
+                let supported = state.render_modes.contains(&mode);
+                let selected = state.rasterization.mode == mode;
+                let label = egui::SelectableLabel::new(selected, mode.label());
+                let option = ui
+                    .add_enabled(supported, label)
+                    .on_disabled_hover_text("Not supported by this GPU");
+                if option.clicked() {
+                    state.rasterization.mode = mode;
+                }
+            }
+        });
+    ui.checkbox(&mut state.rasterization.depth_test, "Depth Test");
+
+    egui::ComboBox::from_label("Present Mode")
+        .selected_text(crate::gpu::Gpu::present_mode_label(state.present_mode))
+        .show_ui(ui, |ui| {
+            for present_mode in crate::gpu::Gpu::SELECTABLE_PRESENT_MODES {
+                let supported = state.present_modes.contains(&present_mode);
+                let selected = state.present_mode == present_mode;
+                let label = crate::gpu::Gpu::present_mode_label(present_mode);
+                let option = ui
+                    .add_enabled(supported, egui::SelectableLabel::new(selected, label))
+                    .on_disabled_hover_text("Not supported by this surface");
+                if option.clicked() {
+                    state.present_mode = present_mode;
+                }
+            }
+        });
+
+    let (min_latency, max_latency) = crate::gpu::Gpu::FRAME_LATENCY_RANGE;
+    ui.add(
+        egui::Slider::new(&mut state.frame_latency, min_latency..=max_latency)
+            .text("Frame Latency"),
+    )
+    .on_hover_text("The frames queued for presentation: fewer show input sooner");
+    ui.checkbox(&mut state.frame_pacing, "Frame Pacing")
+        .on_hover_text("Start frames just before the display refreshes, to show newer input");
+
+    let fixed_rate = RedrawPolicy::FixedRate(RedrawPolicy::DEFAULT_RATE);
+    egui::ComboBox::from_label("Redraw")
+        .selected_text(state.redraw_policy.label())
+        .show_ui(ui, |ui| {
+            for policy in [RedrawPolicy::Continuous, RedrawPolicy::OnDemand, fixed_rate] {
+                let selected = state.redraw_policy.label() == policy.label();
+                if ui.selectable_label(selected, policy.label()).clicked() && !selected {
+                    state.redraw_policy = policy;
+                }
+            }
+        })
+        .response
+        .on_hover_text("On Demand and Fixed Rate let the CPU and GPU idle between frames");
+    if let RedrawPolicy::FixedRate(rate) = &mut state.redraw_policy {
+        ui.add(egui::Slider::new(rate, 1..=240).text("Rate (Hz)"));
+    }
+}
+
+/// Fills the Inspector panel's render layer section with a row of layer checkboxes for the
+/// main camera and for each object.
+#[cfg(feature = "gui")]
+fn layer_inspector(ui: &mut egui::Ui, layers: &mut crate::layers::LayerMasks) {
+    use crate::layers::RenderLayers;
+
+    let mut rows = vec![
+        ("Camera", &mut layers.camera),
+        ("Triangle", &mut layers.scene),
+    ];
+    #[cfg(feature = "scene3d")]
+    rows.push(("Crowd", &mut layers.crowd));
+    #[cfg(feature = "scene3d")]
+    rows.push(("Particles", &mut layers.particles));
+    rows.push(("Helpers", &mut layers.helpers));
+
+    egui::Grid::new("render_layers").show(ui, |ui| {
+        ui.label("");
+        for (_, name) in RenderLayers::NAMED {
+            ui.label(name);
+        }
+        ui.end_row();
+
+        for (label, mask) in rows {
+            ui.label(label);
+            for (layer, name) in RenderLayers::NAMED {
+                let mut enabled = mask.contains(layer);
+                let checkbox = ui.checkbox(&mut enabled, "").on_hover_text(name);
+                if checkbox.changed() {
+                    mask.set(layer, enabled);
+                }
+            }
+            ui.end_row();
+        }
+    });
+}
+
+/// Fills the Assets panel with a thumbnail of each mesh. The thumbnail under the cursor turns
+/// on a turntable.
+#[cfg(feature = "gui")]
+fn mesh_thumbnails(ui: &mut egui::Ui, state: &mut UiState) {
+    state.turntable_mesh = None;
+    ui.horizontal(|ui| {
+        for &(name, texture) in &state.mesh_previews {
+            ui.vertical(|ui| {
+                let image =
+                    egui::Image::new((texture, egui::vec2(96.0, 96.0))).sense(egui::Sense::hover());
+                if ui.add(image).hovered() {
+                    state.turntable_mesh = Some(name);
+                }
+                ui.label(name);
+            });
+        }
+    });
+}
+
+#[cfg(all(test, feature = "gui"))]
+mod tests {
+    use super::*;
+
+    /// The title used for the settings window in tests, independent of the target platform.
+    const TITLE: &str = "Rust/Wgpu";
+
+    /// Runs [`show`] headlessly on a fixed-size screen and inspects its accessibility tree.
+    struct Harness {
+        context: egui::Context,
+        state: UiState,
+        events: Vec<egui::Event>,
+        tree: Option<accesskit::TreeUpdate>,
+    }
+
+    impl Harness {
+        fn new(state: UiState) -> Self {
+            let context = egui::Context::default();
+            context.enable_accesskit();
+            let mut harness = Self {
+                context,
+                state,
+                events: Vec::new(),
+                tree: None,
+            };
+            harness.run();
+            harness
+        }
+
+        /// Runs a few frames with the queued input, so layouts that egui settles over several
+        /// passes (such as window sizes) are stable.
+        fn run(&mut self) {
+            for _ in 0..4 {
+                let input = egui::RawInput {
+                    screen_rect: Some(egui::Rect::from_min_size(
+                        egui::Pos2::ZERO,
+                        egui::vec2(1024.0, 768.0),
+                    )),
+                    events: std::mem::take(&mut self.events),
+                    ..Default::default()
+                };
+                let output = self
+                    .context
+                    .run(input, |context| show(context, TITLE, &mut self.state));
+                self.tree = output.platform_output.accesskit_update;
+            }
+        }
+
+        /// Returns the accessibility nodes of the last frame, keyed by id.
+        fn nodes(&self) -> std::collections::HashMap<accesskit::NodeId, &accesskit::Node> {
+            let tree = self.tree.as_ref().expect("accesskit is enabled");
+            tree.nodes.iter().map(|(id, node)| (*id, node)).collect()
+        }
+
+        /// Clicks the `index`-th widget (in tree order) labelled `label`.
+        fn click_nth(&mut self, label: &str, index: usize) {
+            let center = {
+                let nodes = self.nodes();
+                let mut matches: Vec<_> = self
+                    .walk()
+                    .into_iter()
+                    .filter(|(_, id)| nodes[id].label() == Some(label))
+                    .collect();
+                assert!(
+                    index < matches.len(),
+                    "no widget #{index} labelled {label:?}"
+                );
+                let (_, id) = matches.remove(index);
+                let bounds = nodes[&id].bounds().expect("widget has bounds");
+                egui::pos2(
+                    ((bounds.x0 + bounds.x1) / 2.0) as f32,
+                    ((bounds.y0 + bounds.y1) / 2.0) as f32,
+                )
+            };
+            for pressed in [true, false] {
+                self.events.push(egui::Event::PointerMoved(center));
+                self.events.push(egui::Event::PointerButton {
+                    pos: center,
+                    button: egui::PointerButton::Primary,
+                    pressed,
+                    modifiers: egui::Modifiers::NONE,
+                });
+                self.run();
+            }
+        }
+
+        fn click(&mut self, label: &str) {
+            self.click_nth(label, 0);
+        }
+
+        /// Returns `(depth, id)` for every node, depth-first from the root.
+        fn walk(&self) -> Vec<(usize, accesskit::NodeId)> {
+            let tree = self.tree.as_ref().expect("accesskit is enabled");
+            let nodes = self.nodes();
+            let root = tree.tree.as_ref().expect("full tree update").root;
+            let mut order = Vec::new();
+            let mut stack = vec![(0, root)];
+            while let Some((depth, id)) = stack.pop() {
+                order.push((depth, id));
+                if let Some(node) = nodes.get(&id) {
+                    for child in node.children().iter().rev() {
+                        stack.push((depth + 1, *child));
+                    }
+                }
+            }
+            order
+        }
+
+        /// Renders the accessibility tree as indented text, one `role "label"` per line.
+        ///
+        /// Unlabelled nodes without a role (such as resize handles) are left out, so the
+        /// snapshots only change when a meaningful widget does.
+        fn snapshot_text(&self) -> String {
+            let nodes = self.nodes();
+            let mut text = String::new();
+            for (depth, id) in self.walk() {
+                let node = nodes[&id];
+                if node.role() == accesskit::Role::Unknown && node.label().is_none() {
+                    continue;
+                }
+                text.push_str(&"  ".repeat(depth));
+                text.push_str(&format!("{:?}", node.role()));
+                if let Some(label) = node.label() {
+                    text.push_str(&format!(" {label:?}"));
+                }
+                if let Some(value) = node.value() {
+                    text.push_str(&format!(" value={value:?}"));
+                }
+                if let Some(toggled) = node.toggled() {
+                    text.push_str(&format!(" toggled={toggled:?}"));
+                }
+                text.push('\n');
+            }
+            text
+        }
+
+        /// Compares the accessibility tree with `tests/snapshots/{name}.txt`.
+        ///
+        /// With `UPDATE_SNAPSHOTS` set, the snapshot is (re)written instead.
+        fn snapshot(&self, name: &str) {
+            let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/snapshots")
+                .join(format!("{name}.txt"));
+            let actual = self.snapshot_text();
+            if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, actual).unwrap();
+                return;
+            }
+            let expected = std::fs::read_to_string(&path).unwrap_or_else(|error| {
+                panic!(
+                    "missing snapshot {}: {error} (run with UPDATE_SNAPSHOTS=1)",
+                    path.display()
+                )
+            });
+            assert_eq!(
+                expected, actual,
+                "snapshot {name} changed (run with UPDATE_SNAPSHOTS=1 to accept)"
+            );
+        }
+    }
+
+    /// Returns a state with the panels shown.
+    fn panels_shown() -> UiState {
+        UiState {
+            panels_visible: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn panels_hidden_by_default() {
+        let harness = Harness::new(UiState::default());
+        harness.snapshot("ui_panels_hidden");
+    }
+
+    #[test]
+    fn checking_show_panels_shows_panels() {
+        let mut harness = Harness::new(UiState::default());
+        harness.click("Show Panels");
+        assert!(harness.state.panels_visible);
+        harness.snapshot("ui_panels_visible");
+    }
+
+    #[test]
+    fn unchecking_show_panels_hides_panels() {
+        let mut harness = Harness::new(panels_shown());
+        harness.click("Show Panels");
+        assert!(!harness.state.panels_visible);
+        harness.snapshot("ui_panels_hidden");
+    }
+
+    #[test]
+    fn panel_buttons_record_clicks() {
+        let mut harness = Harness::new(panels_shown());
+        harness.click_nth("Click me!", 1);
+        assert_eq!(harness.state.last_clicked, Some("Inspector"));
+        harness.click_nth("Click me!", 2);
+        assert_eq!(harness.state.last_clicked, Some("Assets"));
+    }
+
+    #[test]
+    fn widget_gallery_edits_the_state() {
+        let mut harness = Harness::new(UiState::default());
+        harness.click("Widget Gallery");
+        assert!(harness.state.gallery.visible);
+        harness.click("Pause");
+        assert!(harness.state.gallery.paused);
+    }
+}
@@ -0,0 +1,215 @@
+//! # GUI Capture Module
+//!
+//! The `ui_capture` module captures the `egui` layer on its own, over a transparent background,
+//! into a PNG with alpha. It makes it easy to produce screenshots of the user interface for
+//! documentation, or assets, from the running application.
+//!
+//! ## Overview
+//!
+//! [`UiCapture`] renders the frame's GUI a second time into an offscreen target and reads it
+//! back without blocking the render thread, in the same stages as the depth probe:
+//!
+//! 1. **`request`**: Asks for the GUI of the next rendered frame to be captured.
+//! 2. **`begin`**: Creates the offscreen target, which the renderer clears to transparent and
+//!    draws the GUI into.
+//! 3. **`encode_copy`**: Records a copy of the target into a readback buffer.
+//! 4. **`after_submit`**: Once the frame is submitted, starts mapping the readback buffer.
+//! 5. **`poll`**: In a later frame, converts the pixels and saves the PNG.
+//!
+//! The offscreen target has the surface's format, so the GUI renders with the same pipeline as
+//! on screen. `egui` blends in premultiplied alpha, so the pixels are converted to the straight
+//! alpha PNG expects, and from BGRA to RGBA order where needed. On desktop the PNG is written to
+//! the working directory; on the web it is downloaded by the browser. The `frame_capture`
+//! module saves screenshots of whole frames with the same conversion.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! capture.request();
+//!
+//! // ... in the next frame ...
+//! if let Some(view) = capture.begin(&device, width, height, surface_format) {
+//!     // ... render the GUI into `view` ...
+//!     capture.encode_copy(&device, &mut encoder);
+//! }
+//! queue.submit(std::iter::once(encoder.finish()));
+//! capture.after_submit();
+//!
+//! capture.poll(&device);
+//! if let Some(Ok(location)) = capture.take_finished() {
+//!     log::info!("Saved the GUI capture to {location}");
+//! }
+//! ```
+
+// Importing the readback layout and the PNG helpers shared with the frame capture.
+use crate::frame_capture::{encode_png, save, straight_rgba, CaptureLayout};
+
+// Importing the readback the captured GUI is mapped through.
+use crate::readback::Readback;
+
+/// Errors produced while capturing the GUI.
+#[derive(Debug, thiserror::Error)]
+pub enum UiCaptureError {
+    /// The surface format cannot be converted to an 8-bit RGBA PNG.
+    #[error("capturing the GUI is not supported with the {0:?} surface format")]
+    UnsupportedFormat(wgpu::TextureFormat),
+
+    /// The captured pixels could not be read back from the GPU.
+    #[error("failed to read back the GUI capture: {0}")]
+    Readback(#[from] wgpu::BufferAsyncError),
+
+    /// The captured pixels could not be encoded as a PNG.
+    #[error("failed to encode the GUI capture: {0}")]
+    Encode(#[from] png::EncodingError),
+
+    /// The PNG could not be written, or the browser refused to download it.
+    #[error("failed to save the GUI capture: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The stage of a capture, until it is copied into the readback buffer.
+#[derive(Debug)]
+enum CaptureState {
+    /// No capture is in progress, or it is being read back.
+    Idle,
+
+    /// The next frame's GUI is captured.
+    Requested,
+
+    /// The GUI is being rendered into `texture`.
+    Rendering {
+        texture: wgpu::Texture,
+        layout: CaptureLayout,
+    },
+}
+
+/// Captures the GUI layer over a transparent background into a PNG.
+#[derive(Debug)]
+pub struct UiCapture {
+    /// The stage of the capture in progress.
+    state: CaptureState,
+
+    /// The readback of the captured GUI, in the layout it was copied with.
+    readback: Readback<CaptureLayout>,
+
+    /// Where the last capture was saved, or why it failed, until taken.
+    finished: Option<Result<String, UiCaptureError>>,
+}
+
+impl Default for UiCapture {
+    fn default() -> Self {
+        Self {
+            state: CaptureState::Idle,
+            readback: Readback::default(),
+            finished: None,
+        }
+    }
+}
+
+impl UiCapture {
+    /// Asks for the GUI of the next rendered frame to be captured. Ignored while a capture is
+    /// in progress.
+    pub fn request(&mut self) {
+        if matches!(self.state, CaptureState::Idle) && self.readback.is_idle() {
+            self.state = CaptureState::Requested;
+        }
+    }
+
+    /// Returns where the last capture was saved, or why it failed, once it finished.
+    pub fn take_finished(&mut self) -> Option<Result<String, UiCaptureError>> {
+        self.finished.take()
+    }
+
+    /// Creates the offscreen target for a requested capture, returning the view to render the
+    /// GUI into, cleared to transparent. Returns `None` if no capture was requested.
+    ///
+    /// `format` must be the format the GUI pipeline was created for.
+    pub fn begin(
+        &mut self,
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> Option<wgpu::TextureView> {
+        if !matches!(self.state, CaptureState::Requested) {
+            return None;
+        }
+
+        let Some(layout) = CaptureLayout::new(width, height, format) else {
+            self.state = CaptureState::Idle;
+            self.finished = Some(Err(UiCaptureError::UnsupportedFormat(format)));
+            return None;
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("GUI Capture Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        self.state = CaptureState::Rendering { texture, layout };
+        Some(view)
+    }
+
+    /// Records a copy of the rendered target into a readback buffer.
+    pub fn encode_copy(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder) {
+        let CaptureState::Rendering { texture, layout } =
+            std::mem::replace(&mut self.state, CaptureState::Idle)
+        else {
+            return;
+        };
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GUI Capture Readback Buffer"),
+            size: (layout.padded_bytes_per_row * layout.height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(layout.padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        self.readback.copied(buffer, layout);
+    }
+
+    /// Starts mapping the readback buffer once the frame containing the copy was submitted.
+    pub fn after_submit(&mut self) {
+        self.readback.after_submit();
+    }
+
+    /// Finishes a capture in flight, if its buffer has been mapped, and saves the PNG. This
+    /// never blocks.
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        let Some(result) = self
+            .readback
+            .poll(device, |data, layout| (straight_rgba(data, layout), layout))
+        else {
+            return;
+        };
+
+        self.finished = Some(
+            result
+                .map_err(UiCaptureError::from)
+                .and_then(|(pixels, layout)| {
+                    let png = encode_png(&pixels, layout.width, layout.height)?;
+                    Ok(save(png, "gui-capture")?)
+                }),
+        );
+    }
+}
@@ -38,7 +38,7 @@
 //!
 //! // Update the uniform buffer with a new Model-View-Projection matrix
 //! let mvp_matrix = nalgebra_glm::identity();
-//! let uniform_buffer_data = UniformBuffer { mvp: mvp_matrix };
+//! let uniform_buffer_data = UniformBuffer { mvp: mvp_matrix, model: nalgebra_glm::identity() };
 //! uniform_binding.update_buffer(&queue, 0, uniform_buffer_data);
 //!
 //! // Use `uniform_binding.bind_group` in your render pipeline
@@ -75,11 +75,14 @@
 //! `bytemuck::Pod` and `bytemuck::Zeroable` for the uniform buffer's data type.
 
 // Import the `UniformBuffer` struct, which represents the uniform data structure
-// (e.g., transformation matrices) passed from the CPU to the GPU. 
+// (e.g., transformation matrices) passed from the CPU to the GPU.
 // It is used to define the layout and contents of the uniform buffer
 // managed by the `UniformBinding` struct.
 use crate::uniform_buffer::UniformBuffer;
 
+// Import the bind group of `shader_source.wgsl`, generated from the shader at build time.
+use crate::shader_bindings::shader_source;
+
 /// Represents the binding of a uniform buffer to the GPU pipeline.
 ///
 /// This struct encapsulates all components required to bind a uniform buffer
@@ -106,7 +109,7 @@ use crate::uniform_buffer::UniformBuffer;
 ///
 /// // Update the uniform buffer with a new MVP matrix
 /// let mvp_matrix = nalgebra_glm::identity();
-/// let uniform_buffer = UniformBuffer { mvp: mvp_matrix };
+/// let uniform_buffer = UniformBuffer { mvp: mvp_matrix, model: nalgebra_glm::identity() };
 /// uniform_binding.update_buffer(&queue, 0, uniform_buffer);
 ///
 /// // Use `uniform_binding.bind_group` in your render pipeline
@@ -271,78 +274,16 @@ impl UniformBinding {
             },
         );
 
-        // A layout for the bind group that defines the structure of resource bindings.
-        //
-        // This layout describes how resources like buffers and textures are bound to the GPU pipeline.
-        // It ensures that the GPU knows the type, stage visibility, and binding index for each resource.
-        //
-        // # Key Features
-        //
-        // - **Binding Index**: This layout has a single entry at binding `0`, representing the uniform buffer.
-        // - **Shader Stage Visibility**: The uniform buffer is visible to the vertex shader stage only.
-        // - **Binding Type**: The binding is configured as a `Uniform` buffer, which allows read-only
-        //   access to the data during rendering.
-        //
-        // # Usage
-        //
-        // This layout is a blueprint for creating bind groups that link uniform data to specific
-        // bindings in the shader. It supports efficient resource management and ensures compatibility with
-        // the GPU pipeline.
-        //
-        // # Notes
-        //
-        // - The `has_dynamic_offset` field is set to `false`, which means that the data binding does not
-        //   allow dynamic offsets.
-        // - The `min_binding_size` field is set to `None` because the buffer size is fixed and defined
-        //   by the structure of the `UniformBuffer`.
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
-                },
-                count: None,
-            }],
-            label: Some("uniform_bind_group_layout"),
-        });
-
-        // A bind group that links the uniform buffer to the GPU pipeline.
-        //
-        // This bind group represents a binding between the uniform buffer and its layout
-        // in the GPU's resource bindings. It is used to pass uniform data from the CPU (via the `buffer`)
-        // to the GPU during rendering.
-        //
-        // # Key Features
-        //
-        // - **Resource Binding**: Associates the uniform buffer with the binding index `0` specified
-        //   in the bind group layout.
-        // - **GPU Pipeline Integration**: Ensures that the uniform data becomes available to the GPU shaders
-        //   during the rendering process.
-        // - **Fixed Binding**: This bind group has a single entry for the uniform buffer, making it a simple
-        //   and efficient way to manage the connection to the shader pipeline.
+        // The layout and the bind group of the `@group(0)` bindings of `shader_source.wgsl`,
+        // generated from the shader by the build script (see the `shader_bindings` module).
         //
-        // # Usage
-        //
-        // The bind group is tied directly to the `bind_group_layout` and is required whenever
-        // the shader requires access to the uniform buffer data. It simplifies the process of
-        // providing uniform data to GPU resources.
-        //
-        // # Notes
-        //
-        // - The bind group must match the layout defined by `bind_group_layout` for compatibility.
-        // - The `UniformBuffer` data structure backing the buffer is expected to align with the shader's
-        //   expectations to avoid runtime errors.
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: buffer.as_entire_binding(),
-            }],
-            label: Some("uniform_bind_group"),
-        });
+        // The uniform buffer is bound at binding `0`, visible to the vertex stage that reads
+        // the `mvp` matrix from it, and must hold at least one `UniformBuffer`.
+        let bind_group_layout = shader_source::BindGroup0::create_layout(device);
+        let bind_group = shader_source::BindGroup0 {
+            ubo: buffer.as_entire_buffer_binding(),
+        }
+        .create(device, &bind_group_layout);
 
         Self {
             buffer,
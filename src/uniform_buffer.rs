@@ -21,7 +21,7 @@
 //!
 //! The `UniformBuffer` struct contains a single field:
 //!
-//! - `mvp`: A 4x4 matrix (`nalgebra_glm::Mat4`) used to store the combined MVP transformation.
+//! - `mvp`: A 4x4 matrix (`math::Mat4`) used to store the combined MVP transformation.
 //!
 //! This struct is designed specifically for transferring data to the GPU via a uniform buffer. Its memory layout
 //! is optimized to meet GPU alignment requirements.
@@ -48,6 +48,7 @@
 //! // Initialize an MVP matrix as an identity matrix
 //! let uniform_instance = UniformBuffer {
 //!     mvp: glm::identity(), // No transformations applied initially
+//!     model: glm::identity(),
 //! };
 //!
 //! // Convert the struct into raw bytes for uploading to the GPU
@@ -84,6 +85,9 @@
 //!     /// The MVP matrix is uploaded to GPU memory and used during rendering to position objects
 //!     /// in screen space relative to the camera and scene settings.
 //!     pub mvp: nalgebra_glm::Mat4,
+//!
+//!     /// The model matrix alone, placing positions and normals in world space for lighting.
+//!     pub model: nalgebra_glm::Mat4,
 //! }
 //! ```
 //!
@@ -101,15 +105,17 @@
 
 /// Represents the uniform buffer used to pass data from the CPU to the GPU.
 ///
-/// This struct contains a single field, `mvp`, which is a 4x4 matrix used for
-/// Model-View-Projection (MVP) transformations in the rendering pipeline.
+/// This struct contains the `mvp` field, a 4x4 matrix used for Model-View-Projection (MVP)
+/// transformations in the rendering pipeline, and the `model` matrix used for lighting.
 /// These transformations are typically applied to vertices during rendering
 /// operations to ensure proper positioning, perspective, and scaling of
 /// rendered objects.
 ///
 /// # Fields
 ///
-/// - `mvp`: A 4x4 matrix (`nalgebra_glm::Mat4`) used for MVP transformations.
+/// - `mvp`: A 4x4 matrix (`math::Mat4`) used for MVP transformations.
+/// - `model`: A 4x4 matrix (`math::Mat4`) placing the vertices in world space.
+/// - `object_id`: The id written to the object id target, for the depth probe and picking.
 ///
 /// # Memory Layout
 ///
@@ -129,13 +135,15 @@
 /// ```rust
 /// let uniform_instance = UniformBuffer {
 ///     mvp: nalgebra_glm::identity(),
+///     model: nalgebra_glm::identity(),
+///     ..Default::default()
 /// };
 /// // Pass this buffer to the GPU via `wgpu::Buffer`
 /// ```
 #[repr(C)]
 #[derive(Default, Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct UniformBuffer {
-    /// A 4x4 matrix (`nalgebra_glm::Mat4`) representing the Model-View-Projection (MVP) transformations.
+    /// A 4x4 matrix (`math::Mat4`) representing the Model-View-Projection (MVP) transformations.
     ///
     /// This matrix is used by the GPU rendering pipeline to transform vertex positions
     /// from model space to screen space. The MVP matrix consists of the following components:
@@ -149,5 +157,16 @@ pub struct UniformBuffer {
     ///
     /// This property is designed to be compatible with GPU memory and is efficiently
     /// transferred to the GPU uniform buffer for real-time rendering.
-    pub mvp: nalgebra_glm::Mat4,
+    pub mvp: crate::math::Mat4,
+
+    /// The model (world) matrix alone, which places the vertex positions and normals in world
+    /// space, where the scene's light is shaded.
+    pub model: crate::math::Mat4,
+
+    /// The id the node writes to the object id target, as returned by
+    /// [`Scene::node_object_id`](crate::scene::Scene::node_object_id).
+    pub object_id: u32,
+
+    /// Pads the struct to a multiple of 16 bytes, as WGSL lays it out.
+    pub padding: [u32; 3],
 }
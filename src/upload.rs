@@ -0,0 +1,255 @@
+//! # Upload Module
+//!
+//! The `upload` module streams large buffer and texture uploads to the GPU in chunks spread
+//! over several frames, so that assets of hundreds of megabytes load without frame hitches.
+//!
+//! ## Overview
+//!
+//! - [`UploadScheduler`] queues uploads and writes chunks of them at the start of every frame
+//!   until the frame's time budget is spent. At least one chunk is written per frame, so
+//!   uploads always make progress, even with a tiny budget.
+//! - Buffers are split into chunks of [`UploadScheduler::CHUNK_SIZE`] bytes, and textures into
+//!   bands of whole rows of about that size.
+//! - The progress of each upload is reported as a [`TaskProgress`], and shown in the GUI's
+//!   "Tasks" window alongside the background compute jobs.
+//!
+//! The data is only visible to the GPU once the upload has finished, so the caller should not
+//! draw with a resource while its upload is still in progress.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! renderer.set_upload_budget(Duration::from_millis(2));
+//! renderer.upload_texture("Terrain Albedo", &texture, pixels, 4 * width);
+//!
+//! for task in renderer.task_progress() {
+//!     println!("{}: {:.0}%", task.label, task.fraction() * 100.0);
+//! }
+//! ```
+
+use std::collections::VecDeque;
+
+use web_time::Instant;
+
+use crate::background::TaskProgress;
+use crate::Duration;
+
+/// Where an upload's data is written to.
+enum UploadTarget {
+    /// A buffer, starting at a byte offset.
+    Buffer {
+        buffer: wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+    },
+
+    /// The first layer of a 2D texture's top mip level, with rows `bytes_per_row` apart.
+    Texture {
+        texture: wgpu::Texture,
+        bytes_per_row: u32,
+    },
+}
+
+/// An upload in progress.
+struct Upload {
+    /// The name of the upload, shown in the GUI.
+    label: String,
+
+    /// Where the data is written to.
+    target: UploadTarget,
+
+    /// The data to upload.
+    data: Vec<u8>,
+
+    /// The number of bytes written so far.
+    written: usize,
+}
+
+impl Upload {
+    /// Writes the next chunk of at most `chunk_size` bytes.
+    fn write_chunk(&mut self, queue: &wgpu::Queue, chunk_size: usize) {
+        match &self.target {
+            UploadTarget::Buffer { buffer, offset } => {
+                let end = (self.written + chunk_size).min(self.data.len());
+                let chunk_offset = offset + self.written as wgpu::BufferAddress;
+                queue.write_buffer(buffer, chunk_offset, &self.data[self.written..end]);
+                self.written = end;
+            }
+            UploadTarget::Texture {
+                texture,
+                bytes_per_row,
+            } => {
+                let row_size = *bytes_per_row as usize;
+                let first_row = (self.written / row_size) as u32;
+                let rows = (chunk_size / row_size)
+                    .max(1)
+                    .min(texture.height() as usize - first_row as usize)
+                    as u32;
+                let end = self.written + rows as usize * row_size;
+                queue.write_texture(
+                    wgpu::TexelCopyTextureInfo {
+                        texture,
+                        mip_level: 0,
+                        origin: wgpu::Origin3d {
+                            x: 0,
+                            y: first_row,
+                            z: 0,
+                        },
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    &self.data[self.written..end],
+                    wgpu::TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(*bytes_per_row),
+                        rows_per_image: Some(rows),
+                    },
+                    wgpu::Extent3d {
+                        width: texture.width(),
+                        height: rows,
+                        depth_or_array_layers: 1,
+                    },
+                );
+                self.written = end;
+            }
+        }
+    }
+
+    /// Returns `true` once all the data has been written.
+    fn is_finished(&self) -> bool {
+        self.written >= self.data.len()
+    }
+
+    /// Returns the upload's progress, in bytes.
+    fn progress(&self) -> TaskProgress {
+        TaskProgress {
+            label: self.label.clone(),
+            completed: self.written as u64,
+            total: self.data.len() as u64,
+        }
+    }
+}
+
+/// Queues large uploads and writes them in chunks, within a time budget per frame.
+pub struct UploadScheduler {
+    /// The uploads in progress, oldest first.
+    uploads: VecDeque<Upload>,
+
+    /// How long writing chunks may take per frame.
+    budget: Duration,
+}
+
+impl Default for UploadScheduler {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_BUDGET)
+    }
+}
+
+impl UploadScheduler {
+    /// The size of a chunk, in bytes.
+    pub const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+    /// The default time budget per frame.
+    pub const DEFAULT_BUDGET: Duration = Duration::from_millis(2);
+
+    /// Creates a scheduler writing chunks for up to `budget` per frame.
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            uploads: VecDeque::new(),
+            budget,
+        }
+    }
+
+    /// Returns the time budget per frame.
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// Sets the time budget per frame.
+    pub fn set_budget(&mut self, budget: Duration) {
+        self.budget = budget;
+    }
+
+    /// Queues an upload of `data` into `buffer`, starting at `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` or the length of `data` is not a multiple of
+    /// `wgpu::COPY_BUFFER_ALIGNMENT`.
+    pub fn upload_buffer(
+        &mut self,
+        label: &str,
+        buffer: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+        data: Vec<u8>,
+    ) {
+        assert_eq!(offset % wgpu::COPY_BUFFER_ALIGNMENT, 0);
+        assert_eq!(data.len() as u64 % wgpu::COPY_BUFFER_ALIGNMENT, 0);
+        self.push(
+            label,
+            UploadTarget::Buffer {
+                buffer: buffer.clone(),
+                offset,
+            },
+            data,
+        );
+    }
+
+    /// Queues an upload of `data` into the top mip level of the 2D `texture`, whose rows are
+    /// `bytes_per_row` apart in `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` does not hold exactly the texture's rows.
+    pub fn upload_texture(
+        &mut self,
+        label: &str,
+        texture: &wgpu::Texture,
+        data: Vec<u8>,
+        bytes_per_row: u32,
+    ) {
+        assert_eq!(
+            data.len(),
+            bytes_per_row as usize * texture.height() as usize
+        );
+        self.push(
+            label,
+            UploadTarget::Texture {
+                texture: texture.clone(),
+                bytes_per_row,
+            },
+            data,
+        );
+    }
+
+    /// Returns the progress of every upload in progress, oldest first.
+    pub fn progress(&self) -> Vec<TaskProgress> {
+        self.uploads.iter().map(Upload::progress).collect()
+    }
+
+    /// Writes chunks of the oldest uploads until the budget is spent, and drops the uploads
+    /// that finished. Call this once per frame, before the frame is submitted.
+    pub fn run(&mut self, queue: &wgpu::Queue) {
+        let start = Instant::now();
+        while let Some(upload) = self.uploads.front_mut() {
+            upload.write_chunk(queue, Self::CHUNK_SIZE);
+            if upload.is_finished() {
+                log::info!("Finished uploading {}", upload.label);
+                self.uploads.pop_front();
+            }
+            if start.elapsed() >= self.budget {
+                break;
+            }
+        }
+    }
+
+    /// Queues an upload, finishing empty ones right away.
+    fn push(&mut self, label: &str, target: UploadTarget, data: Vec<u8>) {
+        if !data.is_empty() {
+            self.uploads.push_back(Upload {
+                label: label.to_owned(),
+                target,
+                data,
+                written: 0,
+            });
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//! # Configuration Validation Module
+//!
+//! The `validation` module checks requested rendering configurations against what the
+//! selected adapter and surface actually support, *before* those configurations reach
+//! `wgpu`. Misconfigurations that would otherwise surface as opaque `wgpu` validation
+//! panics deep inside resource creation are instead reported as descriptive
+//! `ValidationError` values that callers can recover from (for example by falling back
+//! to a supported setting) and present to the user.
+//!
+//! ## Checks
+//!
+//! - **MSAA sample counts**: [`validate_sample_count`] and [`resolve_sample_count`] compare a
+//!   requested sample count with the counts supported by a texture format on the adapter.
+//! - **Texture formats**: [`validate_texture_format`] verifies that a format supports every
+//!   requested `wgpu::TextureUsages` flag.
+//! - **Surface configuration**: [`validate_surface_format`] and [`validate_surface_usage`]
+//!   check a surface format or usage against `wgpu::SurfaceCapabilities`.
+//! - **Transparency**: [`resolve_alpha_mode`] selects a composite alpha mode that lets the
+//!   window be composited over the desktop with per-pixel alpha, or an opaque one.
+//! - **Present modes**: [`validate_present_mode`] and [`resolve_present_mode`] check a present
+//!   mode against the surface's capabilities, which can change when the window moves to another
+//!   monitor or the driver changes.
+//! - **Limits**: [`validate_limit`] and [`validate_bind_group_count`] compare a requested
+//!   amount with the corresponding `wgpu::Limits` value.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! // Fall back to the closest supported MSAA level and tell the user about it.
+//! let (sample_count, warning) = resolve_sample_count(&adapter, surface_format, 8);
+//! if let Some(warning) = warning {
+//!     toasts.warning(warning.to_string());
+//! }
+//! ```
+
+/// A requested configuration that the adapter or surface does not support.
+///
+/// Every variant carries enough information to produce an actionable message through its
+/// `Display` implementation, including the values that *would* have been accepted.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ValidationError {
+    /// The requested MSAA sample count is not supported for a texture format.
+    #[error(
+        "{requested}x MSAA is not supported for {format:?} on this adapter (supported: {supported:?})"
+    )]
+    UnsupportedSampleCount {
+        /// The texture format the sample count was requested for.
+        format: wgpu::TextureFormat,
+
+        /// The requested sample count.
+        requested: u32,
+
+        /// The sample counts the adapter supports for `format`.
+        supported: Vec<u32>,
+    },
+
+    /// A texture format does not support all of the requested usages.
+    #[error("{format:?} does not support usages {missing:?} on this adapter")]
+    UnsupportedTextureUsage {
+        /// The texture format that was checked.
+        format: wgpu::TextureFormat,
+
+        /// The requested usages that are not supported.
+        missing: wgpu::TextureUsages,
+    },
+
+    /// The surface cannot be configured with the requested texture format.
+    #[error("surface does not support {requested:?} (supported: {supported:?})")]
+    UnsupportedSurfaceFormat {
+        /// The requested surface format.
+        requested: wgpu::TextureFormat,
+
+        /// The formats the surface supports.
+        supported: Vec<wgpu::TextureFormat>,
+    },
+
+    /// The surface cannot be configured with the requested usages.
+    #[error("surface does not support usages {missing:?} (supported: {supported:?})")]
+    UnsupportedSurfaceUsage {
+        /// The requested usages that are not supported.
+        missing: wgpu::TextureUsages,
+
+        /// The usages the surface supports.
+        supported: wgpu::TextureUsages,
+    },
+
+    /// The surface cannot present with the requested present mode.
+    #[error("surface does not support present mode {requested:?} (supported: {supported:?})")]
+    UnsupportedPresentMode {
+        /// The requested present mode.
+        requested: wgpu::PresentMode,
+
+        /// The present modes the surface supports.
+        supported: Vec<wgpu::PresentMode>,
+    },
+
+    /// The surface cannot be composited with per-pixel alpha.
+    #[error("surface does not support transparency (supported alpha modes: {supported:?})")]
+    UnsupportedTransparency {
+        /// The composite alpha modes the surface supports.
+        supported: Vec<wgpu::CompositeAlphaMode>,
+    },
+
+    /// A requested amount exceeds one of the device limits.
+    #[error("{name} of {requested} exceeds the device limit of {maximum}")]
+    LimitExceeded {
+        /// The name of the `wgpu::Limits` field that was exceeded.
+        name: &'static str,
+
+        /// The requested amount.
+        requested: u32,
+
+        /// The maximum allowed by the limits.
+        maximum: u32,
+    },
+}
+
+/// Checks that `format` supports `requested` samples per pixel on `adapter`.
+///
+/// # Returns
+///
+/// The requested sample count if it is supported.
+///
+/// # Errors
+///
+/// Returns `ValidationError::UnsupportedSampleCount`, listing the supported counts, otherwise.
+pub fn validate_sample_count(
+    adapter: &wgpu::Adapter,
+    format: wgpu::TextureFormat,
+    requested: u32,
+) -> Result<u32, ValidationError> {
+    let flags = adapter.get_texture_format_features(format).flags;
+    if flags.sample_count_supported(requested) {
+        Ok(requested)
+    } else {
+        Err(ValidationError::UnsupportedSampleCount {
+            format,
+            requested,
+            supported: flags.supported_sample_counts(),
+        })
+    }
+}
+
+/// Resolves a requested sample count to one the adapter supports for `format`.
+///
+/// If the request is unsupported, the highest supported sample count that does not exceed
+/// the request is used instead (a single sample is always supported).
+///
+/// # Returns
+///
+/// A tuple of the sample count to use and, if a fallback was necessary, the
+/// `ValidationError` describing why, so it can be reported to the user.
+pub fn resolve_sample_count(
+    adapter: &wgpu::Adapter,
+    format: wgpu::TextureFormat,
+    requested: u32,
+) -> (u32, Option<ValidationError>) {
+    match validate_sample_count(adapter, format, requested) {
+        Ok(count) => (count, None),
+        Err(error) => {
+            let fallback = match &error {
+                ValidationError::UnsupportedSampleCount { supported, .. } => supported
+                    .iter()
+                    .copied()
+                    .filter(|count| *count <= requested)
+                    .max()
+                    .unwrap_or(1),
+                _ => 1,
+            };
+            (fallback, Some(error))
+        }
+    }
+}
+
+/// Checks that `format` supports every usage in `usages` on `adapter`.
+///
+/// # Errors
+///
+/// Returns `ValidationError::UnsupportedTextureUsage` with the missing usages otherwise.
+pub fn validate_texture_format(
+    adapter: &wgpu::Adapter,
+    format: wgpu::TextureFormat,
+    usages: wgpu::TextureUsages,
+) -> Result<(), ValidationError> {
+    let missing = usages - adapter.get_texture_format_features(format).allowed_usages;
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError::UnsupportedTextureUsage { format, missing })
+    }
+}
+
+/// Checks that a surface with `capabilities` can be configured with `format`.
+///
+/// # Errors
+///
+/// Returns `ValidationError::UnsupportedSurfaceFormat` with the supported formats otherwise.
+pub fn validate_surface_format(
+    capabilities: &wgpu::SurfaceCapabilities,
+    format: wgpu::TextureFormat,
+) -> Result<(), ValidationError> {
+    if capabilities.formats.contains(&format) {
+        Ok(())
+    } else {
+        Err(ValidationError::UnsupportedSurfaceFormat {
+            requested: format,
+            supported: capabilities.formats.clone(),
+        })
+    }
+}
+
+/// Checks that a surface with `capabilities` can be configured with `usages`.
+///
+/// # Errors
+///
+/// Returns `ValidationError::UnsupportedSurfaceUsage` with the missing usages otherwise.
+pub fn validate_surface_usage(
+    capabilities: &wgpu::SurfaceCapabilities,
+    usages: wgpu::TextureUsages,
+) -> Result<(), ValidationError> {
+    let missing = usages - capabilities.usages;
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError::UnsupportedSurfaceUsage {
+            missing,
+            supported: capabilities.usages,
+        })
+    }
+}
+
+/// Checks that a surface with `capabilities` can present with `present_mode`.
+///
+/// The automatic modes are always accepted, since `wgpu` resolves them to a supported mode.
+///
+/// # Errors
+///
+/// Returns `ValidationError::UnsupportedPresentMode` with the supported modes otherwise.
+pub fn validate_present_mode(
+    capabilities: &wgpu::SurfaceCapabilities,
+    present_mode: wgpu::PresentMode,
+) -> Result<(), ValidationError> {
+    let automatic = matches!(
+        present_mode,
+        wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync
+    );
+    if automatic || capabilities.present_modes.contains(&present_mode) {
+        Ok(())
+    } else {
+        Err(ValidationError::UnsupportedPresentMode {
+            requested: present_mode,
+            supported: capabilities.present_modes.clone(),
+        })
+    }
+}
+
+/// Resolves a requested present mode to one the surface with `capabilities` supports.
+///
+/// If the request is unsupported, the nearest supported mode is used instead: modes that keep
+/// the request's tearing behavior are preferred, then the ones closest in latency. A surface
+/// without any present modes falls back to `Fifo`, which every surface must support.
+///
+/// # Returns
+///
+/// A tuple of the present mode to use and, if a fallback was necessary, the
+/// `ValidationError` describing why, so it can be reported to the user.
+pub fn resolve_present_mode(
+    capabilities: &wgpu::SurfaceCapabilities,
+    requested: wgpu::PresentMode,
+) -> (wgpu::PresentMode, Option<ValidationError>) {
+    use wgpu::PresentMode::{Fifo, FifoRelaxed, Immediate, Mailbox};
+
+    match validate_present_mode(capabilities, requested) {
+        Ok(()) => (requested, None),
+        Err(error) => {
+            let preferences: &[wgpu::PresentMode] = match requested {
+                Mailbox => &[Fifo, Immediate, FifoRelaxed],
+                Immediate => &[Mailbox, FifoRelaxed, Fifo],
+                FifoRelaxed => &[Fifo, Mailbox, Immediate],
+                _ => &[FifoRelaxed, Mailbox, Immediate],
+            };
+            let fallback = preferences
+                .iter()
+                .copied()
+                .find(|mode| capabilities.present_modes.contains(mode))
+                .or_else(|| capabilities.present_modes.first().copied())
+                .unwrap_or(Fifo);
+            (fallback, Some(error))
+        }
+    }
+}
+
+/// Selects the composite alpha mode for a surface with `capabilities`, transparent or opaque.
+///
+/// A transparent surface prefers `PreMultiplied`, matching the premultiplied colors the scene
+/// and GUI blend with, then `PostMultiplied` and `Inherit`. An opaque surface uses `Opaque`
+/// where supported.
+///
+/// # Returns
+///
+/// A tuple of the alpha mode to use and, if transparency was requested but is unsupported, the
+/// `ValidationError` describing why, so it can be reported to the user. The surface is then
+/// opaque.
+pub fn resolve_alpha_mode(
+    capabilities: &wgpu::SurfaceCapabilities,
+    transparent: bool,
+) -> (wgpu::CompositeAlphaMode, Option<ValidationError>) {
+    use wgpu::CompositeAlphaMode::{Inherit, Opaque, PostMultiplied, PreMultiplied};
+
+    let supported = |mode: &wgpu::CompositeAlphaMode| capabilities.alpha_modes.contains(mode);
+    let opaque = [Opaque]
+        .into_iter()
+        .chain(capabilities.alpha_modes.first().copied())
+        .find(supported)
+        .unwrap_or(wgpu::CompositeAlphaMode::Auto);
+    if !transparent {
+        return (opaque, None);
+    }
+
+    match [PreMultiplied, PostMultiplied, Inherit]
+        .into_iter()
+        .find(supported)
+    {
+        Some(mode) => (mode, None),
+        None => (
+            opaque,
+            Some(ValidationError::UnsupportedTransparency {
+                supported: capabilities.alpha_modes.clone(),
+            }),
+        ),
+    }
+}
+
+/// Checks that `requested` does not exceed the limit `maximum` named `name`.
+///
+/// # Errors
+///
+/// Returns `ValidationError::LimitExceeded` if `requested > maximum`.
+pub fn validate_limit(
+    name: &'static str,
+    requested: u32,
+    maximum: u32,
+) -> Result<u32, ValidationError> {
+    if requested <= maximum {
+        Ok(requested)
+    } else {
+        Err(ValidationError::LimitExceeded {
+            name,
+            requested,
+            maximum,
+        })
+    }
+}
+
+/// Checks that a pipeline layout with `count` bind groups fits within `limits`.
+///
+/// # Errors
+///
+/// Returns `ValidationError::LimitExceeded` if `count` exceeds `limits.max_bind_groups`.
+pub fn validate_bind_group_count(
+    limits: &wgpu::Limits,
+    count: u32,
+) -> Result<u32, ValidationError> {
+    validate_limit("max_bind_groups", count, limits.max_bind_groups)
+}
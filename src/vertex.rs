@@ -4,15 +4,17 @@
 //! vertex data in a 3D graphics pipeline. Vertices are a fundamental building block for rendering
 //! 3D objects, as they define the position and color of points in 3D space.
 //!
-//! The `Vertex` struct includes both positional data and color information, which is formatted
-//! and passed to the GPU's vertex buffer. The GPU processes this data during rendering to produce
-//! visuals on the screen.
+//! The `Vertex` struct includes positional data, color information, the texture coordinates
+//! at which the scene's texture is sampled, and the normal the scene is lit with, which is
+//! formatted and passed to the GPU's vertex buffer. The GPU processes this data during
+//! rendering to produce visuals on the screen.
 //!
 //! # Overview
 //!
 //! ## Structs
 //!
-//! - [`Vertex`]: Represents a single vertex in 3D space, including position and color attributes.
+//! - [`Vertex`]: Represents a single vertex in 3D space, including position, color, UV, and
+//!   normal attributes.
 //!
 //! ## Methods
 //!
@@ -33,14 +35,20 @@
 //!     Vertex {
 //!         position: [0.0, 1.0, 0.0, 1.0],
 //!         color: [1.0, 0.0, 0.0, 1.0],
+//!         uv: [0.5, 0.0],
+//!         normal: [0.0, 0.0, 1.0],
 //!     },
 //!     Vertex {
 //!         position: [-1.0, -1.0, 0.0, 1.0],
 //!         color: [0.0, 1.0, 0.0, 1.0],
+//!         uv: [0.0, 1.0],
+//!         normal: [0.0, 0.0, 1.0],
 //!     },
 //!     Vertex {
 //!         position: [1.0, -1.0, 0.0, 1.0],
 //!         color: [0.0, 0.0, 1.0, 1.0],
+//!         uv: [1.0, 1.0],
+//!         normal: [0.0, 0.0, 1.0],
 //!     },
 //! ];
 //!
@@ -54,7 +62,8 @@
 //!
 //! # Features
 //!
-//! - Easy definition of vertex data with position and color attributes.
+//! - Easy definition of vertex data with position, color, texture coordinate, and normal
+//!   attributes.
 //! - Automatic generation of GPU-compatible buffer layouts through `vertex_attributes` and `description` methods.
 //!
 //! # GPU Compatibility
@@ -72,8 +81,8 @@
 /// Represents a single vertex in a 3D scene, including its position and color attributes.
 ///
 /// This struct is used to define the data structure for vertices passed to the GPU
-/// through vertex buffers. Each `Vertex` object contains a 4D position vector and a 4D
-/// color vector, both stored as arrays of `f32`.
+/// through vertex buffers. Each `Vertex` object contains a 4D position vector, a 4D
+/// color vector, 2D texture coordinates, and a 3D normal, all stored as arrays of `f32`.
 ///
 /// # Fields
 ///
@@ -81,6 +90,10 @@
 ///   The fourth component is typically used for homogenous coordinates in rendering pipelines.
 /// - `color`: A `[f32; 4]` array representing the color of the vertex. The values are
 ///   typically normalized between 0.0 and 1.0, corresponding to RGBA components.
+/// - `uv`: A `[f32; 2]` array of texture coordinates, with `[0.0, 0.0]` at the top left of
+///   the texture.
+/// - `normal`: A `[f32; 3]` array holding the unit normal of the surface at the vertex, which
+///   the scene's light is shaded with.
 ///
 /// # Usage
 ///
@@ -91,6 +104,8 @@
 /// let vertex = Vertex {
 ///     position: [0.0, 1.0, 0.0, 1.0],
 ///     color: [1.0, 0.0, 0.0, 1.0],
+///     uv: [0.5, 0.0],
+///     normal: [0.0, 0.0, 1.0],
 /// };
 /// ```
 ///
@@ -125,6 +140,19 @@ pub struct Vertex {
     /// let vertex_color = [1.0, 0.0, 0.0, 1.0]; // Red color with full opacity
     /// ```
     color: [f32; 4],
+
+    /// The texture coordinates of the vertex.
+    ///
+    /// The scene's texture is sampled at these coordinates and multiplied with the color.
+    /// `[0.0, 0.0]` is the top left corner of the texture and `[1.0, 1.0]` the bottom right;
+    /// coordinates outside this range repeat the texture.
+    uv: [f32; 2],
+
+    /// The unit normal of the surface at the vertex, in the mesh's space.
+    ///
+    /// The scene's shader transforms it with the node's world matrix and shades the surface
+    /// with the scene's light by it. Faces are lit from whichever side the camera sees.
+    normal: [f32; 3],
 }
 
 /// Implementation of methods for the `Vertex` struct which represents a 3D model vertex.
@@ -154,6 +182,47 @@ pub struct Vertex {
 /// The layout is passed during pipeline creation, while the attributes are
 /// used to create shaders and bind proper data from the buffer.
 impl Vertex {
+    /// Creates an opaque vertex from a position and an RGB color, at the top left corner of
+    /// the texture, with its normal along positive Z, towards the default camera.
+    pub fn new(position: [f32; 3], color: [f32; 3]) -> Self {
+        Self {
+            position: [position[0], position[1], position[2], 1.0],
+            color: [color[0], color[1], color[2], 1.0],
+            uv: [0.0, 0.0],
+            normal: [0.0, 0.0, 1.0],
+        }
+    }
+
+    /// Returns the vertex with the given texture coordinates.
+    pub fn with_uv(self, uv: [f32; 2]) -> Self {
+        Self { uv, ..self }
+    }
+
+    /// Returns the vertex with the given unit normal.
+    pub fn with_normal(self, normal: [f32; 3]) -> Self {
+        Self { normal, ..self }
+    }
+
+    /// Returns the vertex's position in 3D space, without the homogeneous coordinate.
+    pub fn position(&self) -> crate::math::Vec3 {
+        crate::math::vec3(self.position[0], self.position[1], self.position[2])
+    }
+
+    /// Returns the vertex's RGB color, without the alpha channel.
+    pub fn color(&self) -> [f32; 3] {
+        [self.color[0], self.color[1], self.color[2]]
+    }
+
+    /// Returns the vertex's texture coordinates.
+    pub fn uv(&self) -> [f32; 2] {
+        self.uv
+    }
+
+    /// Returns the vertex's unit normal.
+    pub fn normal(&self) -> [f32; 3] {
+        self.normal
+    }
+
     /// Generates the vertex attributes layout for the `Vertex` struct.
     ///
     /// This method defines how the vertex data is interpreted by the GPU, specifying
@@ -161,12 +230,16 @@ impl Vertex {
     ///
     /// # Returns
     ///
-    /// A `Vec<wgpu::VertexAttribute>` array consisting of two vertex attributes:
+    /// A `Vec<wgpu::VertexAttribute>` array consisting of four vertex attributes:
     ///
     /// - The first attribute corresponds to the `position` field and is represented
     ///   as a 4-component floating-point vector (`Float32x4`).
     /// - The second attribute corresponds to the `color` field and is represented
     ///   as a 4-component floating-point vector (`Float32x4`).
+    /// - The third attribute corresponds to the `uv` field and is represented
+    ///   as a 2-component floating-point vector (`Float32x2`).
+    /// - The fourth attribute corresponds to the `normal` field and is represented
+    ///   as a 3-component floating-point vector (`Float32x3`).
     ///
     /// These attributes are indexed starting from 0 in the vertex shader.
     ///
@@ -176,6 +249,8 @@ impl Vertex {
     /// let attributes = Vertex::vertex_attributes();
     /// // attributes[0] will represent the layout for `position`
     /// // attributes[1] will represent the layout for `color`
+    /// // attributes[2] will represent the layout for `uv`
+    /// // attributes[3] will represent the layout for `normal`
     /// ```
     ///
     /// # GPU Compatibility
@@ -183,7 +258,8 @@ impl Vertex {
     /// This layout is essential for configuring how the GPU interprets vertex data
     /// passed to it during rendering pipeline setup.
     pub fn vertex_attributes() -> Vec<wgpu::VertexAttribute> {
-        wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4].to_vec()
+        wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4, 2 => Float32x2, 3 => Float32x3]
+            .to_vec()
     }
 
     /// Returns the vertex buffer layout for the `Vertex` struct.
@@ -237,6 +313,8 @@ impl Vertex {
 /// Each vertex includes:
 /// - `position`: The 4D coordinates of the vertex in homogeneous space, defined as `[x, y, z, w]`.
 /// - `color`: The color of the vertex represented as `[r, g, b, a]` with channels specified in the range `[0.0, 1.0]`.
+/// - `uv`: The texture coordinates of the vertex, mapping the texture upright onto the triangle.
+/// - `normal`: The normal of the triangle's plane, facing the default camera.
 ///
 /// This triangle is defined in a right-handed coordinate system:
 /// - The first vertex is located at `[1.0, -1.0, 0.0, 1.0]` with red color `[1.0, 0.0, 0.0, 1.0]`.
@@ -249,13 +327,19 @@ pub const VERTICES: [Vertex; 3] = [
     Vertex {
         position: [1.0, -1.0, 0.0, 1.0],
         color: [1.0, 0.0, 0.0, 1.0],
+        uv: [0.0, 1.0],
+        normal: [0.0, 0.0, 1.0],
     },
     Vertex {
         position: [-1.0, -1.0, 0.0, 1.0],
         color: [0.0, 1.0, 0.0, 1.0],
+        uv: [1.0, 1.0],
+        normal: [0.0, 0.0, 1.0],
     },
     Vertex {
         position: [0.0, 1.0, 0.0, 1.0],
         color: [0.0, 0.0, 1.0, 1.0],
+        uv: [0.5, 0.0],
+        normal: [0.0, 0.0, 1.0],
     },
 ];
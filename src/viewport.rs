@@ -0,0 +1,176 @@
+//! # Viewport Module
+//!
+//! The `viewport` module computes where on the surface the 3D scene is drawn, optionally
+//! locking it to a fixed aspect ratio.
+//!
+//! ## Overview
+//!
+//! - [`AspectLock`] selects a fixed aspect ratio for the 3D viewport, such as 16:9 for
+//!   cinematic capture, or none at all.
+//! - [`Viewport`] is the rectangle of the surface the scene is drawn into. With a locked aspect
+//!   ratio it is the largest centered rectangle of that ratio, leaving letterbox bars (above and
+//!   below) or pillarbox bars (left and right) around it.
+//!
+//! The scene's projection always uses the viewport's aspect ratio, so the framing stays the
+//! same whatever the shape of the window. Cursor positions are converted into the viewport
+//! with [`Viewport::to_local`] before projecting and unprojecting them.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let viewport = Viewport::fit((width, height), AspectLock::Widescreen);
+//! viewport.apply(&mut render_pass);
+//! scene.render(&mut render_pass);
+//! ```
+
+/// A fixed aspect ratio for the 3D viewport.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AspectLock {
+    /// The viewport fills the whole surface.
+    #[default]
+    Free,
+
+    /// 16:9, the common widescreen video format.
+    Widescreen,
+
+    /// 21:9, the ultra-wide cinematic format.
+    Ultrawide,
+
+    /// 4:3, the classic television format.
+    Standard,
+
+    /// 1:1.
+    Square,
+}
+
+impl AspectLock {
+    /// Every aspect lock, in the order they are offered in the user interface.
+    pub const ALL: [AspectLock; 5] = [
+        AspectLock::Free,
+        AspectLock::Widescreen,
+        AspectLock::Ultrawide,
+        AspectLock::Standard,
+        AspectLock::Square,
+    ];
+
+    /// Returns the name shown in the user interface.
+    pub fn label(self) -> &'static str {
+        match self {
+            AspectLock::Free => "Free",
+            AspectLock::Widescreen => "16:9",
+            AspectLock::Ultrawide => "21:9",
+            AspectLock::Standard => "4:3",
+            AspectLock::Square => "1:1",
+        }
+    }
+
+    /// Returns the locked ratio of width to height, or `None` for [`AspectLock::Free`].
+    pub fn ratio(self) -> Option<f32> {
+        match self {
+            AspectLock::Free => None,
+            AspectLock::Widescreen => Some(16.0 / 9.0),
+            AspectLock::Ultrawide => Some(21.0 / 9.0),
+            AspectLock::Standard => Some(4.0 / 3.0),
+            AspectLock::Square => Some(1.0),
+        }
+    }
+}
+
+/// The rectangle of the surface the 3D scene is drawn into, in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Viewport {
+    /// The left edge, from the left of the surface.
+    pub x: u32,
+
+    /// The top edge, from the top of the surface.
+    pub y: u32,
+
+    /// The width, at least one pixel.
+    pub width: u32,
+
+    /// The height, at least one pixel.
+    pub height: u32,
+}
+
+impl Viewport {
+    /// Returns the largest viewport centered on a surface of `size` pixels that has the aspect
+    /// ratio locked by `lock`, or the whole surface if the aspect ratio is free.
+    pub fn fit(size: (u32, u32), lock: AspectLock) -> Self {
+        let (surface_width, surface_height) = (size.0.max(1), size.1.max(1));
+        let (width, height) = match lock.ratio() {
+            None => (surface_width, surface_height),
+            Some(ratio) if surface_width as f32 / surface_height as f32 > ratio => {
+                // Wider than the ratio: pillarbox bars on the left and right.
+                let width = (surface_height as f32 * ratio).round() as u32;
+                (width.clamp(1, surface_width), surface_height)
+            }
+            Some(ratio) => {
+                // Taller than the ratio: letterbox bars above and below.
+                let height = (surface_width as f32 / ratio).round() as u32;
+                (surface_width, height.clamp(1, surface_height))
+            }
+        };
+
+        Self {
+            x: (surface_width - width) / 2,
+            y: (surface_height - height) / 2,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the size as `(width, height)`.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the ratio of width to height, used for the scene's projection.
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+
+    /// Converts a surface pixel into a pixel relative to the viewport's top left corner, or
+    /// returns `None` if it lies on a bar outside the viewport.
+    pub fn to_local(&self, pixel: (u32, u32)) -> Option<(u32, u32)> {
+        let x = pixel.0.checked_sub(self.x).filter(|x| *x < self.width)?;
+        let y = pixel.1.checked_sub(self.y).filter(|y| *y < self.height)?;
+        Some((x, y))
+    }
+
+    /// Converts a surface pixel into a pixel relative to the viewport's top left corner,
+    /// moving pixels on the bars to the nearest edge of the viewport.
+    pub fn to_local_clamped(&self, pixel: (u32, u32)) -> (u32, u32) {
+        (
+            pixel.0.saturating_sub(self.x).min(self.width - 1),
+            pixel.1.saturating_sub(self.y).min(self.height - 1),
+        )
+    }
+
+    /// Returns the bars around the viewport on a surface of `size` pixels, as
+    /// `(x, y, width, height)` rectangles. Empty bars are left out.
+    pub fn bars(&self, size: (u32, u32)) -> Vec<(u32, u32, u32, u32)> {
+        let right = self.x + self.width;
+        let bottom = self.y + self.height;
+        [
+            (0, 0, size.0, self.y),
+            (0, bottom, size.0, size.1.saturating_sub(bottom)),
+            (0, self.y, self.x, self.height),
+            (right, self.y, size.0.saturating_sub(right), self.height),
+        ]
+        .into_iter()
+        .filter(|bar| bar.2 > 0 && bar.3 > 0)
+        .collect()
+    }
+
+    /// Restricts `render_pass` to the viewport.
+    pub fn apply(&self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_viewport(
+            self.x as f32,
+            self.y as f32,
+            self.width as f32,
+            self.height as f32,
+            0.0,
+            1.0,
+        );
+    }
+}
@@ -0,0 +1,350 @@
+//! # Viewports Module
+//!
+//! The `viewports` module shows `egui` viewports in native windows of their own, so windows of
+//! the GUI such as the Frame Statistics can be moved out of the main window, for example onto a
+//! second monitor.
+//!
+//! ## Overview
+//!
+//! `egui` describes every window it wants besides the main one as a deferred viewport in the
+//! output of each pass: its title and size, the commands sent to it, and a callback building
+//! its GUI. Its contents are built in a pass of their own, on the `egui` context shared with
+//! the main window, whenever its window is redrawn.
+//!
+//! - [`ViewportSurface`] is the `wgpu` surface of a viewport's window, created from the
+//!   renderer's device, into which the renderer draws the viewport's GUI without a scene.
+//! - [`ViewportWindows`] opens a native window for every deferred viewport in the output of a
+//!   pass, closes the windows of the viewports that are gone, and routes each window's events
+//!   to its `egui` integration, running the viewport's pass when it is redrawn.
+//!
+//! The `App` only lets `egui` create viewports while "Detach Windows" is checked in the View
+//! menu and the rendering backend can draw into further windows; otherwise `egui` embeds them
+//! in the main window as regular `egui::Window`s. Viewports are only available on native
+//! targets, where the platform can open more than one window.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! // After the main window's pass:
+//! let output = context.end_pass();
+//! viewports.update(event_loop, &context, renderer, &output.viewport_output);
+//!
+//! // In `window_event`:
+//! if viewports.contains(window_id) {
+//!     viewports.handle_event(event_loop, window_id, &event, renderer);
+//! }
+//! ```
+
+// Importing `Arc` to share the windows with their surfaces, and the viewports' GUI callbacks.
+use std::sync::Arc;
+
+use std::collections::HashMap;
+
+use egui::{DeferredViewportUiCallback, ViewportBuilder, ViewportClass, ViewportId};
+use egui::{ViewportIdMap, ViewportInfo, ViewportOutput};
+use winit::event::WindowEvent;
+use winit::window::{Window, WindowId};
+
+use crate::error::RenderError;
+use crate::gpu::Gpu;
+use crate::renderer::Renderer;
+use crate::validation;
+use crate::windows::WindowError;
+
+/// The `wgpu` surface of a viewport's window, rendered to with the renderer's device.
+pub struct ViewportSurface {
+    /// The surface presenting to the window.
+    surface: wgpu::Surface<'static>,
+
+    /// The configuration of the surface, in the format of the main window's surface.
+    config: wgpu::SurfaceConfiguration,
+
+    /// The depth texture the GUI is drawn with, as in the main window.
+    depth_texture_view: wgpu::TextureView,
+}
+
+impl ViewportSurface {
+    /// Creates and configures the surface of `window` for the device of `gpu`.
+    ///
+    /// The surface uses the format of the main window's surface, which the renderer's `egui`
+    /// pipelines were created for, and the configured present mode if the surface supports it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RenderError::CreateSurface` if the surface cannot be created, and
+    /// `RenderError::IncompatibleSurface` if it cannot be presented to by the adapter in the
+    /// main window's format.
+    pub fn new(gpu: &Gpu, window: Arc<Window>) -> Result<Self, RenderError> {
+        let size = window.inner_size();
+        let surface = gpu.instance.create_surface(window)?;
+        let capabilities = surface.get_capabilities(&gpu.adapter);
+        if !capabilities.formats.contains(&gpu.surface_config.format)
+            || capabilities.present_modes.is_empty()
+            || capabilities.alpha_modes.is_empty()
+        {
+            return Err(RenderError::IncompatibleSurface);
+        }
+
+        let (width, height) = (size.width.max(1), size.height.max(1));
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: gpu.surface_config.format,
+            width,
+            height,
+            present_mode: validation::resolve_present_mode(
+                &capabilities,
+                gpu.surface_config.present_mode,
+            )
+            .0,
+            alpha_mode: validation::resolve_alpha_mode(&capabilities, false).0,
+            view_formats: vec![],
+            desired_maximum_frame_latency: gpu.surface_config.desired_maximum_frame_latency,
+        };
+        surface.configure(&gpu.device, &config);
+        Ok(Self {
+            surface,
+            config,
+            depth_texture_view: gpu.create_depth_texture(width, height),
+        })
+    }
+
+    /// Resizes the surface and its depth texture to `width` x `height`. Sizes without area,
+    /// as reported for a minimized window, are ignored.
+    pub fn resize(&mut self, gpu: &Gpu, width: u32, height: u32) {
+        if width == 0 || height == 0 || (self.config.width, self.config.height) == (width, height) {
+            return;
+        }
+        self.config.width = width;
+        self.config.height = height;
+        self.surface.configure(&gpu.device, &self.config);
+        self.depth_texture_view = gpu.create_depth_texture(width, height);
+    }
+
+    /// Returns the next texture of the surface to draw into.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `wgpu::SurfaceError` if the texture cannot be acquired, as for a lost surface.
+    pub fn acquire(&self) -> Result<wgpu::SurfaceTexture, wgpu::SurfaceError> {
+        self.surface.get_current_texture()
+    }
+
+    /// Returns the depth texture the GUI is drawn with.
+    pub fn depth_texture_view(&self) -> &wgpu::TextureView {
+        &self.depth_texture_view
+    }
+}
+
+/// The native window of a deferred viewport.
+struct ViewportWindow {
+    /// The window.
+    window: Arc<Window>,
+
+    /// The `egui` integration of the window, on the context shared with the main window.
+    gui_state: egui_winit::State,
+
+    /// The builder the window was last created or changed with.
+    builder: ViewportBuilder,
+
+    /// The callback building the viewport's GUI, from the latest pass of its parent.
+    callback: Option<Arc<DeferredViewportUiCallback>>,
+
+    /// The state of the window as reported to `egui`, such as whether it was asked to close.
+    info: ViewportInfo,
+}
+
+/// The native windows of the deferred viewports of an `egui` context.
+#[derive(Default)]
+pub struct ViewportWindows {
+    /// The windows, by the viewport they show.
+    windows: HashMap<ViewportId, ViewportWindow>,
+
+    /// The viewport of each window, by the id its events arrive with.
+    ids: HashMap<WindowId, ViewportId>,
+}
+
+impl ViewportWindows {
+    /// Returns `true` if the window with `window_id` shows a viewport.
+    pub fn contains(&self, window_id: WindowId) -> bool {
+        self.ids.contains_key(&window_id)
+    }
+
+    /// Opens, updates, and closes the viewport windows after a pass of `context`, whose
+    /// `viewport_output` lists the viewports `egui` wants.
+    ///
+    /// Windows are opened for new deferred viewports and registered with `renderer`, and
+    /// closed for the viewports no longer in the output. The commands sent to the open ones,
+    /// such as changing their title, are applied to their windows.
+    pub fn update(
+        &mut self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        context: &egui::Context,
+        renderer: &mut Renderer,
+        viewport_output: &ViewportIdMap<ViewportOutput>,
+    ) {
+        for (&id, output) in viewport_output {
+            if id == ViewportId::ROOT || output.class != ViewportClass::Deferred {
+                continue;
+            }
+            let viewport = match self.windows.entry(id) {
+                std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                std::collections::hash_map::Entry::Vacant(entry) => {
+                    match Self::open(event_loop, context, renderer, id, &output.builder) {
+                        Ok(viewport) => {
+                            self.ids.insert(viewport.window.id(), id);
+                            entry.insert(viewport)
+                        }
+                        Err(error) => {
+                            log::warn!("Failed to open a window for a viewport: {error}");
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let (mut commands, _) = viewport.builder.patch(output.builder.clone());
+            commands.extend(output.commands.iter().cloned());
+            egui_winit::process_viewport_commands(
+                context,
+                &mut viewport.info,
+                commands,
+                &viewport.window,
+                &mut egui::ahash::HashSet::default(),
+            );
+            viewport.callback = output.viewport_ui_cb.clone();
+        }
+
+        let ids = &mut self.ids;
+        self.windows.retain(|id, viewport| {
+            let open = viewport_output.contains_key(id);
+            if !open {
+                ids.remove(&viewport.window.id());
+                renderer.remove_viewport(*id);
+            }
+            open
+        });
+    }
+
+    /// Opens the window of viewport `id`, created with `builder`, and registers it with
+    /// `renderer`.
+    fn open(
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        context: &egui::Context,
+        renderer: &mut Renderer,
+        id: ViewportId,
+        builder: &ViewportBuilder,
+    ) -> Result<ViewportWindow, WindowError> {
+        let window = Arc::new(egui_winit::create_window(context, event_loop, builder)?);
+        renderer.add_viewport(id, window.clone())?;
+
+        let gui_state = egui_winit::State::new(
+            context.clone(),
+            id,
+            &window,
+            Some(window.scale_factor() as _),
+            Some(winit::window::Theme::Dark),
+            None,
+        );
+        let mut info = ViewportInfo::default();
+        egui_winit::update_viewport_info(&mut info, context, &window, true);
+        window.request_redraw();
+        Ok(ViewportWindow {
+            window,
+            gui_state,
+            builder: builder.clone(),
+            callback: None,
+            info,
+        })
+    }
+
+    /// Handles an event for the viewport window with `window_id`, running the viewport's pass
+    /// and rendering it with `renderer` when a redraw was requested.
+    ///
+    /// A request to close the window is passed on to the viewport, whose GUI decides whether
+    /// it stays open.
+    pub fn handle_event(
+        &mut self,
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        window_id: WindowId,
+        event: &WindowEvent,
+        renderer: &mut Renderer,
+    ) {
+        let Some(&id) = self.ids.get(&window_id) else {
+            return;
+        };
+        let Some(viewport) = self.windows.get_mut(&id) else {
+            return;
+        };
+        if viewport
+            .gui_state
+            .on_window_event(&viewport.window, event)
+            .repaint
+        {
+            viewport.window.request_redraw();
+        }
+
+        match event {
+            WindowEvent::CloseRequested => {
+                viewport.info.events.push(egui::ViewportEvent::Close);
+                viewport.window.request_redraw();
+            }
+            WindowEvent::Resized(size) => {
+                renderer.resize_viewport(id, size.width, size.height);
+                viewport.window.request_redraw();
+            }
+            WindowEvent::RedrawRequested => {
+                let Some(callback) = viewport.callback.clone() else {
+                    return;
+                };
+                let context = viewport.gui_state.egui_ctx().clone();
+                egui_winit::update_viewport_info(
+                    &mut viewport.info,
+                    &context,
+                    &viewport.window,
+                    false,
+                );
+                let mut input = viewport.gui_state.take_egui_input(&viewport.window);
+                input.viewports.insert(id, viewport.info.clone());
+                viewport.info.events.clear();
+
+                let output = context.run(input, |context| callback(context));
+                viewport
+                    .gui_state
+                    .handle_platform_output(&viewport.window, output.platform_output);
+                let paint_jobs = context.tessellate(output.shapes, output.pixels_per_point);
+                let size = viewport.window.inner_size();
+                let screen_descriptor = egui_wgpu::ScreenDescriptor {
+                    size_in_pixels: [size.width, size.height],
+                    pixels_per_point: output.pixels_per_point,
+                };
+                let result = renderer.render_viewport(
+                    id,
+                    screen_descriptor,
+                    paint_jobs,
+                    output.textures_delta,
+                );
+                if let Err(error) = result {
+                    log::warn!("Failed to render a viewport: {error}");
+                }
+                self.update(event_loop, &context, renderer, &output.viewport_output);
+            }
+            _ => {}
+        }
+    }
+
+    /// Requests a redraw of every viewport window.
+    pub fn request_redraws(&self) {
+        for viewport in self.windows.values() {
+            viewport.window.request_redraw();
+        }
+    }
+
+    /// Closes every viewport window, unregistering it from `renderer`.
+    pub fn close_all(&mut self, renderer: &mut Renderer) {
+        for id in self.windows.keys() {
+            renderer.remove_viewport(*id);
+        }
+        self.windows.clear();
+        self.ids.clear();
+    }
+}
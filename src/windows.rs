@@ -0,0 +1,244 @@
+//! # Windows Module
+//!
+//! The `windows` module manages the secondary windows the `App` opens next to its main window,
+//! such as a detached Inspector for tooling-style layouts on a second monitor.
+//!
+//! ## Overview
+//!
+//! - [`WindowKind`] names what a secondary window shows.
+//! - [`WindowState`] is one open secondary window, with its own surface, renderer, and `egui`
+//!   context. It draws the scene from the main camera and its GUI on top, editing the same
+//!   `UiState` as the main window, so changes in either window show in both.
+//!
+//! The `App` keeps its secondary windows in a map from `WindowId` to `WindowState`, routes
+//! their events by id, and closes them individually. Windows are opened with
+//! `App::open_window` or the "Open Inspector Window" item of the View menu, and are only
+//! available on native targets, where the platform can open more than one window.
+//!
+//! Each renderer owns its GPU device, so the GPU resources of the scene are created once per
+//! window.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let state = WindowState::open(event_loop, WindowKind::Inspector, &backend, config)?;
+//! windows.insert(state.id(), state);
+//!
+//! // In `window_event`:
+//! if let Some(state) = windows.get_mut(&window_id) {
+//!     if !state.handle_event(&event, &mut ui, &camera) {
+//!         windows.remove(&window_id);
+//!     }
+//! }
+//! ```
+
+// Importing `Arc` to share the window with its renderer, which renders to its surface.
+use std::sync::Arc;
+
+use web_time::Instant;
+use winit::event::WindowEvent;
+use winit::window::{Window, WindowId};
+
+use crate::backend::{BackendFactory, RendererBackend};
+use crate::camera::Camera;
+use crate::config::RendererConfig;
+use crate::error::RenderError;
+use crate::ui::UiState;
+
+/// Errors produced while opening a secondary window.
+#[derive(Debug, thiserror::Error)]
+pub enum WindowError {
+    /// The platform could not create the window.
+    #[error("failed to create the window: {0}")]
+    Os(#[from] winit::error::OsError),
+
+    /// The renderer for the window could not be created.
+    #[error("failed to create the window's renderer: {0}")]
+    Render(#[from] RenderError),
+}
+
+/// What a secondary window shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowKind {
+    /// The scene with the Inspector panel beside it.
+    Inspector,
+}
+
+impl WindowKind {
+    /// Returns the window's title.
+    pub fn title(self) -> &'static str {
+        match self {
+            WindowKind::Inspector => "Inspector",
+        }
+    }
+}
+
+/// A secondary window with its own surface, renderer, and GUI.
+pub struct WindowState {
+    /// What the window shows.
+    kind: WindowKind,
+
+    /// The window.
+    window: Arc<Window>,
+
+    /// The renderer drawing into the window's surface.
+    renderer: Box<dyn RendererBackend>,
+
+    /// The `egui` integration of the window, with its own context.
+    #[cfg(feature = "gui")]
+    gui_state: egui_winit::State,
+
+    /// The size of the window's surface, in physical pixels.
+    size: (u32, u32),
+
+    /// The time the last frame was rendered at.
+    last_render_time: Instant,
+}
+
+impl WindowState {
+    /// Opens a window showing `kind`, rendered by a backend created with `backend` and
+    /// `config`.
+    ///
+    /// The renderer is created synchronously, stalling the event loop until it is ready.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `WindowError` if the window or its renderer could not be created.
+    pub fn open(
+        event_loop: &winit::event_loop::ActiveEventLoop,
+        kind: WindowKind,
+        backend: &BackendFactory,
+        config: RendererConfig,
+    ) -> Result<Self, WindowError> {
+        let attributes = Window::default_attributes()
+            .with_title(kind.title())
+            .with_inner_size(winit::dpi::LogicalSize::new(480.0, 640.0));
+        let window = Arc::new(event_loop.create_window(attributes)?);
+        let size = window.inner_size();
+        let renderer = pollster::block_on(backend.create(
+            window.clone(),
+            size.width,
+            size.height,
+            false,
+            config,
+        ))?;
+
+        #[cfg(feature = "gui")]
+        let gui_state = {
+            let context = egui::Context::default();
+            let viewport_id = context.viewport_id();
+            egui_winit::State::new(
+                context,
+                viewport_id,
+                &window,
+                Some(window.scale_factor() as _),
+                Some(winit::window::Theme::Dark),
+                None,
+            )
+        };
+
+        log::info!("Opened the {} window", kind.title());
+        Ok(Self {
+            kind,
+            window,
+            renderer,
+            #[cfg(feature = "gui")]
+            gui_state,
+            size: (size.width, size.height),
+            last_render_time: Instant::now(),
+        })
+    }
+
+    /// Returns the id events for this window arrive with.
+    pub fn id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    /// Returns what the window shows.
+    pub fn kind(&self) -> WindowKind {
+        self.kind
+    }
+
+    /// Requests a redraw of the window.
+    pub fn request_redraw(&self) {
+        self.window.request_redraw();
+    }
+
+    /// Handles an event for this window, rendering a frame with `ui` and `camera` when a redraw
+    /// was requested. Returns `false` once the window was asked to close.
+    pub fn handle_event(&mut self, event: &WindowEvent, ui: &mut UiState, camera: &Camera) -> bool {
+        #[cfg(feature = "gui")]
+        if self.gui_state.on_window_event(&self.window, event).consumed {
+            return true;
+        }
+
+        match event {
+            WindowEvent::CloseRequested => {
+                log::info!("Closed the {} window", self.kind.title());
+                return false;
+            }
+            WindowEvent::Resized(size) => {
+                self.size = (size.width, size.height);
+                self.renderer.resize(size.width, size.height);
+            }
+            WindowEvent::RedrawRequested if self.size.0 > 0 && self.size.1 > 0 => {
+                if let Err(error) = self.render(ui, camera) {
+                    log::warn!("Failed to render the {} window: {error}", self.kind.title());
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Renders the scene from `camera` and the window's GUI editing `ui`.
+    fn render(&mut self, ui: &mut UiState, camera: &Camera) -> Result<(), RenderError> {
+        let now = Instant::now();
+        let delta_time = now - self.last_render_time;
+        self.last_render_time = now;
+
+        let [r, g, b, a] = ui.clear_color.map(f64::from);
+        self.renderer.set_clear_color(wgpu::Color { r, g, b, a });
+        self.renderer.set_camera(camera.view(), camera.eye());
+        self.renderer.set_projection(ui.projection);
+        if let Some(renderer) = self.renderer.as_renderer_mut() {
+            renderer.set_light(&ui.light);
+            renderer.set_post_effects(&ui.post_effects);
+            #[cfg(feature = "gui")]
+            renderer.set_scene_materials(ui.materials.materials());
+        }
+
+        #[cfg(feature = "gui")]
+        {
+            let input = self.gui_state.take_egui_input(&self.window);
+            let context = self.gui_state.egui_ctx().clone();
+            context.begin_pass(input);
+            match self.kind {
+                WindowKind::Inspector => crate::ui::show_inspector_window(&context, ui),
+            }
+            let egui::FullOutput {
+                textures_delta,
+                shapes,
+                pixels_per_point,
+                platform_output,
+                ..
+            } = context.end_pass();
+            self.gui_state
+                .handle_platform_output(&self.window, platform_output);
+            let paint_jobs = context.tessellate(shapes, pixels_per_point);
+            let screen_descriptor = egui_wgpu::ScreenDescriptor {
+                size_in_pixels: [self.size.0, self.size.1],
+                pixels_per_point,
+            };
+            self.renderer
+                .render(screen_descriptor, paint_jobs, textures_delta, delta_time)
+        }
+        #[cfg(not(feature = "gui"))]
+        self.renderer.render(delta_time)
+    }
+
+    /// Shuts down the window's renderer before the window is closed.
+    pub fn shutdown(&mut self) {
+        self.renderer.shutdown();
+    }
+}